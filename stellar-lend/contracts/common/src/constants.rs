@@ -0,0 +1,13 @@
+//! Units shared by the StellarLend contracts.
+
+/// 100% in basis points; ratios, factors and rates are expressed against it
+pub const BASIS_POINTS: i128 = 10_000;
+
+/// One unit of the base currency in protocol prices (7 decimals)
+pub const PRICE_SCALE: i128 = 10_000_000;
+
+/// Seconds in a 365-day year, used to pro-rate annual rates
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Minimum collateral ratio for borrowing (150%)
+pub const MIN_COLLATERAL_RATIO_BPS: i128 = 15_000;
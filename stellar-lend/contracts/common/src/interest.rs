@@ -0,0 +1,21 @@
+//! # Borrow Indexes
+//!
+//! Both contracts grow cross-asset debt through a per-asset borrow index that
+//! compounds at each accrual with the asset's borrow rate. Debt settled when
+//! the index was `a` is worth `debt * index / a` now.
+
+use soroban_sdk::contracttype;
+
+/// Borrow index of an asset whose debt has not grown (9 decimals)
+pub const BORROW_INDEX_ONE: i128 = 1_000_000_000;
+
+/// Cumulative interest growth of an asset's debt
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowIndex {
+    /// Current index, scaled by [`BORROW_INDEX_ONE`]; that value when first
+    /// touched
+    pub index: i128,
+    /// Ledger timestamp the index was last advanced to
+    pub last_update: u64,
+}
@@ -0,0 +1,27 @@
+//! # StellarLend Common
+//!
+//! Storage schema and constants shared by the StellarLend contracts.
+//!
+//! The core protocol contract (`hello-world`) and the simplified lending
+//! contract (`stellarlend-lending`) are deployed separately, but model the same
+//! concepts. Definitions used by both live here so the two contracts agree on
+//! value formats and units instead of each carrying its own copy:
+//!
+//! - [`constants`]: basis-point, price and time scales
+//! - [`interest`]: the per-asset borrow index cross-asset debt grows by
+//! - [`pause`]: the pause switch map and its operation names
+//!
+//! Asset configuration and position types are not shared yet; each contract
+//! keeps its own, as their models differ. Consolidating them is tracked in
+//! `docs/contract-consolidation.md`.
+//!
+//! This crate is a plain library (no contract), linked into each contract.
+
+#![no_std]
+
+pub mod constants;
+pub mod interest;
+pub mod pause;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,81 @@
+//! # Pause Switches
+//!
+//! Both contracts store pause state as one `Map<Symbol, bool>` keyed by
+//! operation name, where a missing entry means "not paused". The operation
+//! names below are the keys of that map.
+//!
+//! Contracts without an existing layout keep the map in instance storage
+//! under [`PauseDataKey::PauseSwitches`] and use [`is_paused`] and
+//! [`set_paused`]. The core contract predates this crate and keeps its map at
+//! its original location, with the same names and format.
+//!
+//! A contract that kept its own flags before adopting the map reads
+//! [`get_paused`] and falls back to its old flag while the switch is unset,
+//! so a pause set before an upgrade survives it.
+
+use soroban_sdk::{contractevent, contracttype, Env, Map, Symbol};
+
+/// Deposits of collateral
+pub const PAUSE_DEPOSIT: &str = "pause_deposit";
+/// Withdrawals of collateral
+pub const PAUSE_WITHDRAW: &str = "pause_withdraw";
+/// New borrows
+pub const PAUSE_BORROW: &str = "pause_borrow";
+/// Debt repayments
+pub const PAUSE_REPAY: &str = "pause_repay";
+/// Liquidations
+pub const PAUSE_LIQUIDATE: &str = "pause_liquidate";
+
+/// Storage key of the pause switch map
+#[contracttype]
+#[derive(Clone)]
+pub enum PauseDataKey {
+    /// Pause flag per operation name: Map<Symbol, bool>
+    PauseSwitches,
+}
+
+/// Emitted by [`set_paused`] whenever a switch is written
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PauseToggledEvent {
+    /// Operation name, e.g. `pause_borrow`
+    pub operation: Symbol,
+    /// Whether the operation is now paused
+    pub paused: bool,
+}
+
+/// Whether `operation` is switched off in `switches`
+pub fn is_switch_set(env: &Env, switches: &Map<Symbol, bool>, operation: &str) -> bool {
+    switches.get(Symbol::new(env, operation)).unwrap_or(false)
+}
+
+/// Whether `operation` is paused in the contract's pause switch map
+pub fn is_paused(env: &Env, operation: &str) -> bool {
+    get_paused(env, operation).unwrap_or(false)
+}
+
+/// Pause state of `operation` in the contract's pause switch map, if its
+/// switch has been set
+pub fn get_paused(env: &Env, operation: &str) -> Option<bool> {
+    env.storage()
+        .instance()
+        .get::<_, Map<Symbol, bool>>(&PauseDataKey::PauseSwitches)
+        .and_then(|switches| switches.get(Symbol::new(env, operation)))
+}
+
+/// Pause or resume `operation` in the contract's pause switch map, leaving
+/// other switches unchanged
+pub fn set_paused(env: &Env, operation: &str, paused: bool) {
+    let mut switches: Map<Symbol, bool> = env
+        .storage()
+        .instance()
+        .get(&PauseDataKey::PauseSwitches)
+        .unwrap_or(Map::new(env));
+    let operation = Symbol::new(env, operation);
+    switches.set(operation.clone(), paused);
+    env.storage()
+        .instance()
+        .set(&PauseDataKey::PauseSwitches, &switches);
+
+    PauseToggledEvent { operation, paused }.publish(env);
+}
@@ -0,0 +1,34 @@
+use crate::pause::{get_paused, is_paused, is_switch_set, set_paused, PAUSE_BORROW, PAUSE_DEPOSIT};
+use soroban_sdk::{contract, Env, Map, Symbol};
+
+#[contract]
+struct Host;
+
+#[test]
+fn test_switches_are_independent() {
+    let env = Env::default();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        assert!(!is_paused(&env, PAUSE_DEPOSIT));
+        assert_eq!(get_paused(&env, PAUSE_DEPOSIT), None);
+
+        set_paused(&env, PAUSE_DEPOSIT, true);
+        assert!(is_paused(&env, PAUSE_DEPOSIT));
+        assert!(!is_paused(&env, PAUSE_BORROW));
+        assert_eq!(get_paused(&env, PAUSE_BORROW), None);
+
+        set_paused(&env, PAUSE_DEPOSIT, false);
+        assert!(!is_paused(&env, PAUSE_DEPOSIT));
+        assert_eq!(get_paused(&env, PAUSE_DEPOSIT), Some(false));
+    });
+}
+
+#[test]
+fn test_switch_map_format() {
+    let env = Env::default();
+    let mut switches = Map::new(&env);
+    switches.set(Symbol::new(&env, "pause_borrow"), true);
+
+    assert!(is_switch_set(&env, &switches, PAUSE_BORROW));
+    assert!(!is_switch_set(&env, &switches, PAUSE_DEPOSIT));
+}
@@ -0,0 +1,197 @@
+//! # Account Sweep
+//!
+//! Prunes the storage of abandoned accounts so their rent can be reclaimed.
+//!
+//! An account is dust when it holds no collateral, debt, accrued interest
+//! or pool shares in any market, and none of its stored positions or
+//! analytics have changed for the sweep idle period (set by a risk admin,
+//! [`DEFAULT_SWEEP_IDLE_DAYS`] until then). Keepers find candidates off-chain,
+//! for example from `deposit`, `withdrawal` and `repay` events, and submit them
+//! in batches of at most [`MAX_SWEEP_BATCH`] to [`sweep_dust_accounts`]. Each
+//! candidate is checked on-chain; candidates that are not dust are skipped.
+//!
+//! ## Pruned Entries
+//! - Core position, collateral balance, user analytics, last borrow time and
+//!   locked stable rate
+//! - Cross-asset positions, and membership of the user and borrower registries
+//!
+//! Credit records are kept: they are the user's repayment history, which the
+//! user may still share with lenders.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Vec};
+
+use crate::constants::SECONDS_PER_DAY;
+use crate::cross_asset::{empty_positions_updated_at, get_asset_list, remove_user_positions};
+use crate::deposit::{DepositDataKey, Position, UserAnalytics};
+use crate::events::{emit_account_pruned, AccountPrunedEvent};
+use crate::lending_pool::get_supply_shares;
+use crate::rate_mode::RateModeDataKey;
+use crate::roles::{self, Role};
+use crate::storage;
+
+/// Maximum number of candidate accounts per [`sweep_dust_accounts`] call
+pub const MAX_SWEEP_BATCH: u32 = 25;
+
+/// Idle period used until a risk admin sets one
+pub const DEFAULT_SWEEP_IDLE_DAYS: u32 = 180;
+
+/// Shortest idle period a risk admin may set
+pub const MIN_SWEEP_IDLE_DAYS: u32 = 30;
+
+/// Errors that can occur during account sweeps
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AccountSweepError {
+    /// Caller lacks the required role
+    Unauthorized = 1,
+    /// More than [`MAX_SWEEP_BATCH`] candidates were submitted
+    BatchTooLarge = 2,
+    /// Idle period is below [`MIN_SWEEP_IDLE_DAYS`]
+    InvalidIdlePeriod = 3,
+}
+
+/// Storage keys for account sweep data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum AccountSweepDataKey {
+    /// Days without activity before an empty account may be pruned: u32
+    IdleDays,
+}
+
+/// Set the idle period after which empty accounts may be pruned (risk admin only)
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not a risk admin
+/// * `InvalidIdlePeriod` - `days` is below [`MIN_SWEEP_IDLE_DAYS`]
+pub fn set_sweep_idle_days(env: &Env, caller: Address, days: u32) -> Result<(), AccountSweepError> {
+    roles::require_role(env, &caller, Role::RiskAdmin)
+        .map_err(|_| AccountSweepError::Unauthorized)?;
+    if days < MIN_SWEEP_IDLE_DAYS {
+        return Err(AccountSweepError::InvalidIdlePeriod);
+    }
+    storage::set(env, &AccountSweepDataKey::IdleDays, &days);
+    Ok(())
+}
+
+/// Get the idle period after which empty accounts may be pruned, in days
+pub fn get_sweep_idle_days(env: &Env) -> u32 {
+    storage::get(env, &AccountSweepDataKey::IdleDays).unwrap_or(DEFAULT_SWEEP_IDLE_DAYS)
+}
+
+/// Last activity of an empty account, or `None` if the account holds
+/// anything or has no stored state.
+fn dust_last_activity(env: &Env, user: &Address) -> Option<u64> {
+    let mut last_activity = empty_positions_updated_at(env, user)?;
+    let mut found = last_activity > 0;
+
+    if let Some(position) =
+        storage::get::<_, Position>(env, &DepositDataKey::Position(user.clone()))
+    {
+        if position.collateral != 0 || position.debt != 0 || position.borrow_interest != 0 {
+            return None;
+        }
+        last_activity = last_activity.max(position.last_accrual_time);
+        found = true;
+    }
+    if let Some(balance) =
+        storage::get::<_, i128>(env, &DepositDataKey::CollateralBalance(user.clone()))
+    {
+        if balance != 0 {
+            return None;
+        }
+        found = true;
+    }
+    if let Some(analytics) =
+        storage::get::<_, UserAnalytics>(env, &DepositDataKey::UserAnalytics(user.clone()))
+    {
+        last_activity = last_activity.max(analytics.last_activity);
+        found = true;
+    }
+    if let Some(borrowed_at) =
+        storage::get::<_, u64>(env, &DepositDataKey::LastBorrowTime(user.clone()))
+    {
+        last_activity = last_activity.max(borrowed_at);
+        found = true;
+    }
+
+    for asset_key in get_asset_list(env).iter() {
+        if get_supply_shares(env, user, asset_key.to_option()) != 0 {
+            return None;
+        }
+    }
+
+    found.then_some(last_activity)
+}
+
+/// Last activity of an account that can be pruned now
+fn prunable_since(env: &Env, user: &Address) -> Option<u64> {
+    let idle_seconds = get_sweep_idle_days(env) as u64 * SECONDS_PER_DAY.get();
+    dust_last_activity(env, user).filter(|last_activity| {
+        env.ledger().timestamp().saturating_sub(*last_activity) >= idle_seconds
+    })
+}
+
+/// Whether an account can be pruned by [`sweep_dust_accounts`] now
+pub fn is_dust_account(env: &Env, user: &Address) -> bool {
+    prunable_since(env, user).is_some()
+}
+
+/// Prune an account that holds nothing, however recently it was active
+///
+/// Used when the owner closes their own position, so no idle period applies.
+/// Returns whether the account was pruned.
+pub(crate) fn prune_if_empty(env: &Env, user: &Address) -> bool {
+    if dust_last_activity(env, user).is_none() {
+        return false;
+    }
+    prune_account(env, user);
+    true
+}
+
+/// Delete the prunable entries of an account
+fn prune_account(env: &Env, user: &Address) {
+    storage::remove(env, &DepositDataKey::Position(user.clone()));
+    storage::remove(env, &DepositDataKey::CollateralBalance(user.clone()));
+    storage::remove(env, &DepositDataKey::UserAnalytics(user.clone()));
+    storage::remove(env, &DepositDataKey::LastBorrowTime(user.clone()));
+    storage::remove(env, &RateModeDataKey::Stable(user.clone()));
+    remove_user_positions(env, user);
+}
+
+/// Prune the storage of abandoned empty accounts (anyone can call)
+///
+/// Candidates that are not dust are skipped, so a batch never fails because
+/// one account became active again.
+///
+/// # Returns
+/// The number of accounts pruned.
+///
+/// # Errors
+/// * `BatchTooLarge` - More than [`MAX_SWEEP_BATCH`] candidates
+pub fn sweep_dust_accounts(env: &Env, users: Vec<Address>) -> Result<u32, AccountSweepError> {
+    if users.len() > MAX_SWEEP_BATCH {
+        return Err(AccountSweepError::BatchTooLarge);
+    }
+
+    let mut pruned = 0;
+    for user in users.iter() {
+        let Some(last_activity) = prunable_since(env, &user) else {
+            continue;
+        };
+
+        prune_account(env, &user);
+        pruned += 1;
+
+        emit_account_pruned(
+            env,
+            AccountPrunedEvent {
+                user,
+                last_activity,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+    Ok(pruned)
+}
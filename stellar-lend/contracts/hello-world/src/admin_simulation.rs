@@ -0,0 +1,195 @@
+//! # Admin Action Dry Runs
+//!
+//! [`simulate_admin_action`] checks a proposed admin change against the same
+//! bounds and current state as the real entrypoint, then reports what it
+//! would do to open positions. Nothing is written; admins and governance
+//! voters can preview a change before submitting it.
+//!
+//! ## Supported Actions
+//! - Risk parameter changes (`set_risk_params`)
+//! - Asset listings (`initialize_asset`)
+//! - Asset configuration updates (`update_asset_config`)
+//! - Supply tenure rebate schedules (`set_tenure_rebates`)
+//!
+//! ## Projected Effects
+//! Actions that change cross-asset market configuration are projected by
+//! recomputing every registered cross-asset position under the new
+//! configuration. Risk parameters and rebate schedules do not enter the
+//! cross-asset health factor, so those actions are only validated.
+//!
+//! The scan covers every registered user, so the call is meant for
+//! transaction simulation rather than on-chain use.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Map, Vec};
+
+use crate::cross_asset::{
+    check_token_interface, get_asset_configs, get_asset_list, get_user_list, require_valid_config,
+    summarize_position, updated_asset_config, validate_tenure_rebates, AssetConfig,
+    AssetConfigUpdate, AssetKey, CrossAssetError, TenureRebateTier,
+};
+use crate::risk_management::{project_risk_params, RiskManagementError, RiskParamsChange};
+
+/// Reasons a simulated admin action would be rejected
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AdminSimulationError {
+    /// A value is outside its allowed range or the configuration is inconsistent
+    OutOfBounds = 1,
+    /// A risk parameter moves by more than the per-change limit
+    ChangeTooLarge = 2,
+    /// Risk parameters cannot change during an emergency pause
+    EmergencyPaused = 3,
+    /// The asset is not a registered cross-asset market
+    AssetNotConfigured = 4,
+    /// A scanned position references a stale price
+    PriceStale = 5,
+    /// The asset to list lacks a required token interface function
+    UnsupportedToken = 6,
+}
+
+impl From<RiskManagementError> for AdminSimulationError {
+    fn from(err: RiskManagementError) -> Self {
+        match err {
+            RiskManagementError::ParameterChangeTooLarge => AdminSimulationError::ChangeTooLarge,
+            RiskManagementError::EmergencyPaused => AdminSimulationError::EmergencyPaused,
+            _ => AdminSimulationError::OutOfBounds,
+        }
+    }
+}
+
+/// A new cross-asset market listing
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetListing {
+    /// Asset to list (`None` for native XLM)
+    pub asset: Option<Address>,
+    /// Full asset configuration
+    pub config: AssetConfig,
+}
+
+/// An admin change to simulate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Change risk parameters
+    RiskParams(RiskParamsChange),
+    /// List an asset, or replace a listed asset's configuration
+    ListAsset(AssetListing),
+    /// Update selected fields of a listed asset's configuration
+    UpdateAssetConfig(AssetConfigUpdate),
+    /// Replace the supply tenure rebate schedule
+    TenureRebates(Vec<TenureRebateTier>),
+}
+
+/// Projected effects of an admin action
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AdminActionEffects {
+    /// Cross-asset positions evaluated (0 if the action was only validated)
+    pub positions_scanned: u32,
+    /// Positions liquidatable now
+    pub liquidatable_before: u32,
+    /// Positions liquidatable after the action
+    pub liquidatable_after: u32,
+    /// Positions the action would make liquidatable
+    pub newly_liquidatable: u32,
+    /// Total debt value of those positions (7 decimals)
+    pub newly_liquidatable_debt: i128,
+}
+
+/// Validate an admin action and project its effects without applying it.
+///
+/// Authorization is not checked; anyone may preview a change.
+///
+/// # Errors
+/// * `OutOfBounds` - A value is out of range
+/// * `ChangeTooLarge` - A risk parameter change exceeds the per-change limit
+/// * `EmergencyPaused` - Risk parameters are frozen by the emergency pause
+/// * `AssetNotConfigured` - The asset to update is not listed
+/// * `PriceStale` - A position cannot be valued
+/// * `UnsupportedToken` - The asset to list is not a conforming token
+pub fn simulate_admin_action(
+    env: &Env,
+    action: AdminAction,
+) -> Result<AdminActionEffects, AdminSimulationError> {
+    match action {
+        AdminAction::RiskParams(change) => {
+            project_risk_params(env, &change)?;
+            Ok(AdminActionEffects::default())
+        }
+        AdminAction::ListAsset(listing) => {
+            require_valid_config(&listing.config).map_err(|_| AdminSimulationError::OutOfBounds)?;
+            check_token_interface(env, &listing.asset)
+                .map_err(|_| AdminSimulationError::UnsupportedToken)?;
+            project_market_change(env, AssetKey::from_option(listing.asset), listing.config)
+        }
+        AdminAction::UpdateAssetConfig(update) => {
+            let asset_key = AssetKey::from_option(update.asset.clone());
+            if !get_asset_configs(env).contains_key(asset_key.clone()) {
+                return Err(AdminSimulationError::AssetNotConfigured);
+            }
+            let config = updated_asset_config(env, &update)
+                .map_err(|_| AdminSimulationError::OutOfBounds)?;
+            project_market_change(env, asset_key, config)
+        }
+        AdminAction::TenureRebates(tiers) => {
+            validate_tenure_rebates(&tiers).map_err(|_| AdminSimulationError::OutOfBounds)?;
+            Ok(AdminActionEffects::default())
+        }
+    }
+}
+
+/// Compare every position under the current configs and with `config`
+/// applied to `asset_key`
+fn project_market_change(
+    env: &Env,
+    asset_key: AssetKey,
+    config: AssetConfig,
+) -> Result<AdminActionEffects, AdminSimulationError> {
+    let asset_list = get_asset_list(env);
+    let configs = get_asset_configs(env);
+
+    let mut projected_list = asset_list.clone();
+    if !projected_list.contains(&asset_key) {
+        projected_list.push_back(asset_key.clone());
+    }
+    let mut projected_configs = configs.clone();
+    projected_configs.set(asset_key, config);
+
+    let no_shocks = Map::new(env);
+    let mut effects = AdminActionEffects::default();
+    for user in get_user_list(env).iter() {
+        let before = summarize_position(env, &user, &asset_list, &configs, &no_shocks, 0)
+            .map_err(scan_error)?;
+        let after = summarize_position(
+            env,
+            &user,
+            &projected_list,
+            &projected_configs,
+            &no_shocks,
+            0,
+        )
+        .map_err(scan_error)?;
+
+        effects.positions_scanned += 1;
+        if before.is_liquidatable {
+            effects.liquidatable_before += 1;
+        }
+        if after.is_liquidatable {
+            effects.liquidatable_after += 1;
+            if !before.is_liquidatable {
+                effects.newly_liquidatable += 1;
+                effects.newly_liquidatable_debt += after.total_debt_value;
+            }
+        }
+    }
+    Ok(effects)
+}
+
+fn scan_error(err: CrossAssetError) -> AdminSimulationError {
+    match err {
+        CrossAssetError::PriceStale => AdminSimulationError::PriceStale,
+        _ => AdminSimulationError::OutOfBounds,
+    }
+}
@@ -7,6 +7,19 @@
 //! - **User metrics**: collateral, debt, health factor, risk level, activity score
 //! - **Activity feed**: bounded log of recent protocol operations (max 10,000 entries)
 //!
+//! ## Activity Storage
+//! Each entry is written twice, in constant time regardless of protocol usage:
+//! - to the user's own log (`UserActivity`, the last 200 entries), which
+//!   [`get_user_activity_feed`] reads without scanning other users' activity;
+//! - to one slot of a global ring buffer (`ActivitySlot`, 10,000 slots), which
+//!   backs [`get_recent_activity`] and [`get_activity_by_type`].
+//!
+//! Entries recorded before the ring buffer existed stay in the legacy
+//! `ActivityLog` vector, which is read after the ring and never written again.
+//! It is deleted once the ring buffer has filled, by which point the old log
+//! would have trimmed those entries. Its entries name their activity with a
+//! Symbol, converted by [`ActivityType::from_symbol`] when read.
+//!
 //! ## Health Factor
 //! `health_factor = (collateral * 10000) / debt`
 //!
@@ -20,14 +33,75 @@
 //! | ≥ 1.10        | 3          |
 //! | ≥ 1.05        | 4          |
 //! | < 1.05        | 5 (Critical) |
+//!
+//! ## TVL History
+//! Every change to TVL updates a [`TvlHistory`]: the all-time high, the
+//! largest drawdown from a high (in basis points of that high), and the TVL
+//! at the start of the current day. [`ProtocolMetrics`] reports these along
+//! with the change since the start of the day, so dashboards need no indexed
+//! history. Days are UTC days of the ledger timestamp.
+//!
+//! ## Category Exposure
+//! [`ProtocolMetrics`] also reports supply and borrow value per asset
+//! category of the cross-asset markets (see
+//! [`crate::cross_asset::get_category_exposure`]).
+//!
+//! ## Activity Score
+//! `activity_score = transaction_count * 100 + total_deposits / 1000`, decayed
+//! by the time since the user's last activity: after a grace period the score
+//! halves every `half_life` seconds (linearly interpolated within a half-life).
+//! The decay is applied when the score is read, so idle users lose standing
+//! without any writes.
+//!
+//! ## Risk Badges
+//! [`UserReport`] carries [`RiskBadge`]s derived from the user's positions, so
+//! every front-end shows the same risk messaging. Each badge is shown from a
+//! threshold in the [`RiskBadgeConfig`] the risk admin sets:
+//! - `HighUtilization`: core debt is at least `high_utilization_bps` of core
+//!   collateral.
+//! - `CollateralConcentration`: with cross-asset debt outstanding, one asset
+//!   makes up at least `concentration_bps` of cross-asset collateral value.
+//! - `NoLiquidationBuffer`: the core collateral ratio is less than
+//!   `liquidation_buffer_bps` above the position's liquidation threshold, or
+//!   the cross-asset health factor is less than that above 1.0.
+//! - `StableRateHeavy`: at least `stable_rate_share_bps` of core debt accrues
+//!   at a stable rate. A core position has one rate mode for all of its debt,
+//!   so the share is either none or all of it.
+//!
+//! ## Maintenance Freeze
+//! During storage migrations the pause guardian can freeze analytics writes
+//! with [`set_analytics_frozen`]. While frozen, activity log entries, the
+//! transaction counter and tag attribution are held in a [`QueuedAnalytics`]
+//! queue, and metric snapshots are computed but not cached. Core lending
+//! logic, including the protocol totals the rate model reads, is unaffected.
+//! Lifting the freeze applies the queue.
 
 #![allow(unused)]
-use soroban_sdk::{contracterror, contracttype, Address, Env, Map, Symbol, Vec};
+use soroban_sdk::{
+    contracterror, contracttype, xdr::ToXdr, Address, BytesN, Env, Map, Symbol, Vec,
+};
 
+use crate::constants::{
+    BASIS_POINTS_SCALE, DEFAULT_ACTIVITY_HALF_LIFE, MAX_ACTIVITY_LOG_SIZE,
+    MAX_USER_ACTIVITY_LOG_SIZE, SECONDS_PER_DAY,
+};
+use crate::cross_asset::{
+    get_asset_configs, get_category_exposure, get_user_asset_positions, AssetCategory, AssetKey,
+    AssetPosition, CategoryExposure,
+};
 use crate::deposit::{
     DepositDataKey, Position, ProtocolAnalytics as DepositProtocolAnalytics,
     UserAnalytics as DepositUserAnalytics,
 };
+use crate::events::{
+    emit_activity_decay_updated, emit_analytics_frozen, emit_risk_badge_config_updated,
+    ActivityDecayUpdatedEvent, AnalyticsFrozenEvent, RiskBadgeConfigUpdatedEvent,
+};
+use crate::math::{Amount, MathError};
+use crate::rate_mode::{get_rate_mode, RateMode};
+use crate::risk_management::get_position_liquidation_threshold;
+use crate::roles::{require_role, Role};
+use crate::storage;
 
 /// Errors that can occur during analytics operations.
 #[contracterror]
@@ -42,6 +116,14 @@ pub enum AnalyticsError {
     Overflow = 3,
     /// Requested data (user position, activity, etc.) was not found
     DataNotFound = 4,
+    /// Caller lacks the required role
+    Unauthorized = 5,
+}
+
+impl From<MathError> for AnalyticsError {
+    fn from(_: MathError) -> Self {
+        AnalyticsError::Overflow
+    }
 }
 
 /// Storage keys for analytics data.
@@ -53,12 +135,40 @@ pub enum AnalyticsDataKey {
     ProtocolMetrics,
     /// Per-user computed metrics
     UserMetrics(Address),
-    /// Bounded activity log (max 10,000 entries)
+    /// Legacy activity log, read-only: Vec<LegacyActivityEntry>
     ActivityLog,
+    /// A user's recent activity, oldest first: Vec<ActivityEntry>
+    UserActivity(Address),
+    /// One slot of the global activity ring buffer: ActivityEntry
+    ActivitySlot(u32),
+    /// Entries ever written to the activity ring buffer: u64
+    ActivityCount,
     /// Count of unique users that have interacted with the protocol
     TotalUsers,
     /// Total number of transactions across all users
     TotalTransactions,
+    /// Activity score decay parameters: ActivityDecayConfig
+    ActivityDecay,
+    /// Flows attributed to an integrator tag: TagAttribution
+    TagAttribution(Symbol),
+    /// Whether analytics writes are frozen: bool
+    WritesFrozen,
+    /// Analytics writes held back by a freeze: QueuedAnalytics
+    Queued,
+    /// TVL high-water mark and drawdown: TvlHistory
+    TvlHistory,
+    /// Thresholds for risk badges: RiskBadgeConfig
+    RiskBadgeConfig,
+}
+
+/// How activity scores decay while a user is idle.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActivityDecayConfig {
+    /// Seconds for an idle user's score to halve (0 = no decay)
+    pub half_life: u64,
+    /// Seconds after the last activity before decay starts
+    pub grace_period: u64,
 }
 
 /// Snapshot of protocol-wide metrics.
@@ -79,10 +189,68 @@ pub struct ProtocolMetrics {
     pub total_users: u64,
     /// Total transaction count
     pub total_transactions: u64,
+    /// Number of emergency oracle price overrides ever set
+    pub emergency_price_overrides: u32,
+    /// Protocol reserves accrued across all markets, including withdrawn reserves
+    pub total_reserves_accrued: i128,
+    /// Protocol reserves withdrawn to the treasury across all markets
+    pub total_revenue_claimed: i128,
+    /// Highest TVL ever recorded
+    pub tvl_all_time_high: i128,
+    /// Current fall from the all-time high in basis points
+    pub tvl_drawdown: i128,
+    /// Largest fall from a high-water mark ever recorded, in basis points
+    pub tvl_max_drawdown: i128,
+    /// TVL change since the start of the current day
+    pub tvl_daily_change: i128,
+    /// Supply and borrow value per asset category, at oracle prices
+    pub category_exposure: Map<AssetCategory, CategoryExposure>,
     /// Timestamp of last metrics update
     pub last_update: u64,
 }
 
+/// TVL high-water mark and drawdown, updated on every TVL change.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TvlHistory {
+    /// Highest TVL ever recorded
+    pub all_time_high: i128,
+    /// Largest fall from a high-water mark, in basis points of that mark
+    pub max_drawdown: i128,
+    /// Most recently recorded TVL
+    pub last_tvl: i128,
+    /// TVL at the start of `day`, i.e. the previous day's close
+    pub day_open: i128,
+    /// Day (timestamp / 86400) of the most recent TVL change
+    pub day: u64,
+}
+
+/// Flows attributed to one integrator tag.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TagAttribution {
+    /// Cumulative tagged deposit volume
+    pub total_deposits: i128,
+    /// Cumulative tagged borrow volume
+    pub total_borrows: i128,
+    /// Loans opened by a tagged borrow
+    pub loans_opened: u32,
+    /// Timestamp of the last tagged activity
+    pub last_activity: u64,
+}
+
+/// Analytics writes held back while analytics writes are frozen.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedAnalytics {
+    /// Activity log entries, oldest first (at most `MAX_ACTIVITY_LOG_SIZE`)
+    pub activities: Vec<ActivityEntry>,
+    /// Transactions not yet added to the global counter
+    pub transactions: u64,
+    /// Flows not yet added to each tag's attribution
+    pub tag_flows: Map<Symbol, TagAttribution>,
+}
+
 /// Per-user computed metrics.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -101,7 +269,8 @@ pub struct UserMetrics {
     pub total_withdrawals: i128,
     /// Cumulative repayment amount
     pub total_repayments: i128,
-    /// Computed activity score (transaction count * 100 + deposits / 1000)
+    /// Activity score (transaction count * 100 + deposits / 1000), decayed
+    /// by the time since the user's last activity
     pub activity_score: i128,
     /// Risk level from 1 (low) to 5 (critical), based on health factor
     pub risk_level: i128,
@@ -109,22 +278,100 @@ pub struct UserMetrics {
     pub transaction_count: u64,
 }
 
+/// Kind of operation recorded in the activity log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ActivityType {
+    /// Collateral deposited
+    Deposit,
+    /// Collateral withdrawn
+    Withdraw,
+    /// Funds borrowed
+    Borrow,
+    /// Debt repaid
+    Repay,
+    /// Position liquidated
+    Liquidation,
+    /// Coverage or rewards claimed
+    Claim,
+    /// Administrative action, named by the entrypoint that performed it
+    AdminAction(Symbol),
+}
+
+impl ActivityType {
+    /// The Symbol this activity was logged under before activity types were
+    /// typed: `deposit`, `withdraw`, `borrow`, `repay`, `liquidate`, `claim`,
+    /// or the admin action's name.
+    pub fn to_symbol(&self, env: &Env) -> Symbol {
+        match self {
+            ActivityType::Deposit => Symbol::new(env, "deposit"),
+            ActivityType::Withdraw => Symbol::new(env, "withdraw"),
+            ActivityType::Borrow => Symbol::new(env, "borrow"),
+            ActivityType::Repay => Symbol::new(env, "repay"),
+            ActivityType::Liquidation => Symbol::new(env, "liquidate"),
+            ActivityType::Claim => Symbol::new(env, "claim"),
+            ActivityType::AdminAction(action) => action.clone(),
+        }
+    }
+
+    /// Parse a Symbol written by [`ActivityType::to_symbol`]. Any other
+    /// Symbol is taken to be an admin action.
+    pub fn from_symbol(env: &Env, symbol: &Symbol) -> Self {
+        [
+            ActivityType::Deposit,
+            ActivityType::Withdraw,
+            ActivityType::Borrow,
+            ActivityType::Repay,
+            ActivityType::Liquidation,
+            ActivityType::Claim,
+        ]
+        .into_iter()
+        .find(|activity_type| activity_type.to_symbol(env) == *symbol)
+        .unwrap_or_else(|| ActivityType::AdminAction(symbol.clone()))
+    }
+}
+
 /// A single activity log entry.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct ActivityEntry {
     /// User who performed the activity
     pub user: Address,
-    /// Type of activity (e.g., "deposit", "borrow", "repay", "withdraw")
-    pub activity_type: Symbol,
+    /// Type of activity
+    pub activity_type: ActivityType,
     /// Amount involved in the activity
     pub amount: i128,
     /// Asset address (None for native XLM)
     pub asset: Option<Address>,
     /// Ledger timestamp when activity occurred
     pub timestamp: u64,
-    /// Additional metadata key-value pairs
-    pub metadata: Map<Symbol, i128>,
+    /// Additional metadata key-value pairs (the integrator tag is under `tag`)
+    pub metadata: Map<Symbol, Symbol>,
+}
+
+/// An entry of the legacy activity log, which typed activities as Symbols.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyActivityEntry {
+    pub user: Address,
+    pub activity_type: Symbol,
+    pub amount: i128,
+    pub asset: Option<Address>,
+    pub timestamp: u64,
+    pub metadata: Map<Symbol, Symbol>,
+}
+
+impl LegacyActivityEntry {
+    fn upgrade(self, env: &Env) -> ActivityEntry {
+        ActivityEntry {
+            activity_type: ActivityType::from_symbol(env, &self.activity_type),
+            user: self.user,
+            amount: self.amount,
+            asset: self.asset,
+            timestamp: self.timestamp,
+            metadata: self.metadata,
+        }
+    }
 }
 
 /// Protocol-level analytics report.
@@ -137,6 +384,34 @@ pub struct ProtocolReport {
     pub timestamp: u64,
 }
 
+/// A risk condition flagged on a user's positions.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RiskBadge {
+    /// Core debt is a large share of core collateral
+    HighUtilization,
+    /// One asset backs most of the cross-asset collateral
+    CollateralConcentration,
+    /// A position is close to its liquidation threshold
+    NoLiquidationBuffer,
+    /// Most core debt accrues at a stable rate
+    StableRateHeavy,
+}
+
+/// Thresholds at which risk badges are shown, all in basis points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskBadgeConfig {
+    /// Core debt as a share of core collateral
+    pub high_utilization_bps: i128,
+    /// Share of cross-asset collateral value held in a single asset
+    pub concentration_bps: i128,
+    /// Margin above the liquidation threshold (0 = only when liquidatable)
+    pub liquidation_buffer_bps: i128,
+    /// Share of core debt accruing at a stable rate
+    pub stable_rate_share_bps: i128,
+}
+
 /// User-level analytics report.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -149,12 +424,32 @@ pub struct UserReport {
     pub position: Position,
     /// Most recent 10 activities for this user
     pub recent_activities: Vec<ActivityEntry>,
+    /// Risk conditions flagged on the user's positions
+    pub badges: Vec<RiskBadge>,
     /// Report generation timestamp
     pub timestamp: u64,
 }
 
-const BASIS_POINTS: i128 = 10_000;
-const MAX_ACTIVITY_LOG_SIZE: u32 = 10_000;
+/// Canonical snapshot of a user's full position, for indexers.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionSnapshot {
+    /// User address this snapshot is for
+    pub user: Address,
+    /// Core position (collateral, debt, interest, last accrual time)
+    pub position: Position,
+    /// Core health factor in basis points (`i128::MAX` when debt-free)
+    pub health_factor: i128,
+    /// Non-empty cross-asset positions keyed by asset
+    pub asset_positions: Map<AssetKey, AssetPosition>,
+    /// Cross-asset health factor, `None` if any relevant price is stale
+    pub cross_asset_health_factor: Option<i128>,
+    /// Snapshot timestamp
+    pub timestamp: u64,
+}
+
+/// Maximum number of users per `get_positions_batch` call
+pub const MAX_POSITIONS_BATCH_SIZE: u32 = 50;
 
 /// Get the total value locked (TVL) in the protocol.
 ///
@@ -163,15 +458,15 @@ const MAX_ACTIVITY_LOG_SIZE: u32 = 10_000;
 /// # Returns
 /// The total value locked as an `i128`.
 pub fn get_total_value_locked(env: &Env) -> Result<i128, AnalyticsError> {
-    let protocol_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, DepositProtocolAnalytics>(&DepositDataKey::ProtocolAnalytics)
-        .unwrap_or(DepositProtocolAnalytics {
-            total_deposits: 0,
-            total_borrows: 0,
-            total_value_locked: 0,
-        });
+    let protocol_analytics = storage::get::<DepositDataKey, DepositProtocolAnalytics>(
+        env,
+        &DepositDataKey::ProtocolAnalytics,
+    )
+    .unwrap_or(DepositProtocolAnalytics {
+        total_deposits: 0,
+        total_borrows: 0,
+        total_value_locked: 0,
+    });
 
     Ok(protocol_analytics.total_value_locked)
 }
@@ -184,25 +479,24 @@ pub fn get_total_value_locked(env: &Env) -> Result<i128, AnalyticsError> {
 /// # Returns
 /// Utilization rate in basis points (0–10000).
 pub fn get_protocol_utilization(env: &Env) -> Result<i128, AnalyticsError> {
-    let protocol_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, DepositProtocolAnalytics>(&DepositDataKey::ProtocolAnalytics)
-        .unwrap_or(DepositProtocolAnalytics {
-            total_deposits: 0,
-            total_borrows: 0,
-            total_value_locked: 0,
-        });
+    let protocol_analytics = storage::get::<DepositDataKey, DepositProtocolAnalytics>(
+        env,
+        &DepositDataKey::ProtocolAnalytics,
+    )
+    .unwrap_or(DepositProtocolAnalytics {
+        total_deposits: 0,
+        total_borrows: 0,
+        total_value_locked: 0,
+    });
 
     if protocol_analytics.total_deposits == 0 {
         return Ok(0);
     }
 
-    let utilization = (protocol_analytics.total_borrows * BASIS_POINTS)
-        .checked_div(protocol_analytics.total_deposits)
-        .ok_or(AnalyticsError::Overflow)?;
+    let utilization = Amount::new(protocol_analytics.total_borrows)
+        .ratio_bps(Amount::new(protocol_analytics.total_deposits))?;
 
-    Ok(utilization)
+    Ok(utilization.get())
 }
 
 /// Calculate the weighted average borrow interest rate.
@@ -213,15 +507,15 @@ pub fn get_protocol_utilization(env: &Env) -> Result<i128, AnalyticsError> {
 /// # Returns
 /// Weighted average interest rate in basis points.
 pub fn calculate_weighted_avg_interest_rate(env: &Env) -> Result<i128, AnalyticsError> {
-    let protocol_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, DepositProtocolAnalytics>(&DepositDataKey::ProtocolAnalytics)
-        .unwrap_or(DepositProtocolAnalytics {
-            total_deposits: 0,
-            total_borrows: 0,
-            total_value_locked: 0,
-        });
+    let protocol_analytics = storage::get::<DepositDataKey, DepositProtocolAnalytics>(
+        env,
+        &DepositDataKey::ProtocolAnalytics,
+    )
+    .unwrap_or(DepositProtocolAnalytics {
+        total_deposits: 0,
+        total_borrows: 0,
+        total_value_locked: 0,
+    });
 
     if protocol_analytics.total_borrows == 0 {
         return Ok(0);
@@ -229,7 +523,7 @@ pub fn calculate_weighted_avg_interest_rate(env: &Env) -> Result<i128, Analytics
 
     let utilization = get_protocol_utilization(env)?;
     let base_rate = 200;
-    let rate = base_rate + (utilization * 10) / BASIS_POINTS;
+    let rate = base_rate + (utilization * 10) / BASIS_POINTS_SCALE.get();
 
     Ok(rate)
 }
@@ -237,7 +531,8 @@ pub fn calculate_weighted_avg_interest_rate(env: &Env) -> Result<i128, Analytics
 /// Recompute and persist protocol-wide metrics.
 ///
 /// Aggregates TVL, utilization, average rate, and user/transaction counts
-/// into a fresh `ProtocolMetrics` snapshot and stores it.
+/// into a fresh `ProtocolMetrics` snapshot and stores it (unless analytics
+/// writes are frozen).
 ///
 /// # Returns
 /// The newly computed `ProtocolMetrics`.
@@ -246,27 +541,30 @@ pub fn update_protocol_metrics(env: &Env) -> Result<ProtocolMetrics, AnalyticsEr
     let utilization = get_protocol_utilization(env)?;
     let avg_rate = calculate_weighted_avg_interest_rate(env)?;
 
-    let protocol_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, DepositProtocolAnalytics>(&DepositDataKey::ProtocolAnalytics)
-        .unwrap_or(DepositProtocolAnalytics {
-            total_deposits: 0,
-            total_borrows: 0,
-            total_value_locked: 0,
-        });
+    let protocol_analytics = storage::get::<DepositDataKey, DepositProtocolAnalytics>(
+        env,
+        &DepositDataKey::ProtocolAnalytics,
+    )
+    .unwrap_or(DepositProtocolAnalytics {
+        total_deposits: 0,
+        total_borrows: 0,
+        total_value_locked: 0,
+    });
 
-    let total_users = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, u64>(&AnalyticsDataKey::TotalUsers)
-        .unwrap_or(0);
+    let total_users =
+        storage::get::<AnalyticsDataKey, u64>(env, &AnalyticsDataKey::TotalUsers).unwrap_or(0);
 
-    let total_transactions = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, u64>(&AnalyticsDataKey::TotalTransactions)
-        .unwrap_or(0);
+    let total_transactions =
+        storage::get::<AnalyticsDataKey, u64>(env, &AnalyticsDataKey::TotalTransactions)
+            .unwrap_or(0);
+
+    let history = get_tvl_history(env);
+    let today = env.ledger().timestamp() / SECONDS_PER_DAY.get();
+    let day_open = if history.day == today {
+        history.day_open
+    } else {
+        history.last_tvl
+    };
 
     let metrics = ProtocolMetrics {
         total_value_locked: tvl,
@@ -276,16 +574,58 @@ pub fn update_protocol_metrics(env: &Env) -> Result<ProtocolMetrics, AnalyticsEr
         average_borrow_rate: avg_rate,
         total_users,
         total_transactions,
+        emergency_price_overrides: crate::oracle::get_emergency_price_count(env),
+        total_reserves_accrued: crate::lending_pool::get_reserves_accrued(env),
+        total_revenue_claimed: crate::lending_pool::get_revenue_claimed(env),
+        tvl_all_time_high: history.all_time_high,
+        tvl_drawdown: drawdown_bps(history.all_time_high, tvl),
+        tvl_max_drawdown: history.max_drawdown,
+        tvl_daily_change: tvl.saturating_sub(day_open),
+        category_exposure: get_category_exposure(env).map_err(|_| AnalyticsError::Overflow)?,
         last_update: env.ledger().timestamp(),
     };
 
-    env.storage()
-        .persistent()
-        .set(&AnalyticsDataKey::ProtocolMetrics, &metrics);
+    if !is_analytics_frozen(env) {
+        storage::set(env, &AnalyticsDataKey::ProtocolMetrics, &metrics);
+    }
 
     Ok(metrics)
 }
 
+/// Record a new TVL, updating the high-water mark, drawdown and day open.
+///
+/// Called by every operation that changes TVL, right after storing it.
+pub(crate) fn record_tvl(env: &Env, tvl: i128) {
+    let mut history = get_tvl_history(env);
+    let today = env.ledger().timestamp() / SECONDS_PER_DAY.get();
+    if history.day != today {
+        history.day_open = history.last_tvl;
+        history.day = today;
+    }
+    history.last_tvl = tvl;
+    history.all_time_high = history.all_time_high.max(tvl);
+    history.max_drawdown = history
+        .max_drawdown
+        .max(drawdown_bps(history.all_time_high, tvl));
+    storage::set(env, &AnalyticsDataKey::TvlHistory, &history);
+}
+
+/// Get the TVL high-water mark and drawdown history
+pub fn get_tvl_history(env: &Env) -> TvlHistory {
+    storage::get(env, &AnalyticsDataKey::TvlHistory).unwrap_or_default()
+}
+
+/// Fall from `high` to `tvl` in basis points of `high`
+fn drawdown_bps(high: i128, tvl: i128) -> i128 {
+    if high <= 0 || tvl >= high {
+        return 0;
+    }
+    Amount::new(high - tvl)
+        .ratio_bps(Amount::new(high))
+        .map(|bps| bps.get())
+        .unwrap_or(BASIS_POINTS_SCALE.get())
+}
+
 /// Get cached protocol metrics, recomputing if none exist.
 ///
 /// Returns the stored `ProtocolMetrics` if available, otherwise calls
@@ -294,10 +634,8 @@ pub fn update_protocol_metrics(env: &Env) -> Result<ProtocolMetrics, AnalyticsEr
 /// # Returns
 /// Current `ProtocolMetrics`.
 pub fn get_protocol_stats(env: &Env) -> Result<ProtocolMetrics, AnalyticsError> {
-    let cached_metrics = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, ProtocolMetrics>(&AnalyticsDataKey::ProtocolMetrics);
+    let cached_metrics =
+        storage::get::<AnalyticsDataKey, ProtocolMetrics>(env, &AnalyticsDataKey::ProtocolMetrics);
 
     if let Some(metrics) = cached_metrics {
         Ok(metrics)
@@ -317,11 +655,9 @@ pub fn get_protocol_stats(env: &Env) -> Result<ProtocolMetrics, AnalyticsError>
 /// # Errors
 /// Returns `AnalyticsError::DataNotFound` if the user has no position.
 pub fn get_user_position_summary(env: &Env, user: &Address) -> Result<Position, AnalyticsError> {
-    let position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&DepositDataKey::Position(user.clone()))
-        .ok_or(AnalyticsError::DataNotFound)?;
+    let position =
+        storage::get::<DepositDataKey, Position>(env, &DepositDataKey::Position(user.clone()))
+            .ok_or(AnalyticsError::DataNotFound)?;
 
     Ok(position)
 }
@@ -343,11 +679,9 @@ pub fn calculate_health_factor(env: &Env, user: &Address) -> Result<i128, Analyt
         return Ok(i128::MAX);
     }
 
-    let health_factor = (position.collateral * BASIS_POINTS)
-        .checked_div(position.debt)
-        .ok_or(AnalyticsError::Overflow)?;
+    let health_factor = Amount::new(position.collateral).ratio_bps(Amount::new(position.debt))?;
 
-    Ok(health_factor)
+    Ok(health_factor.get())
 }
 
 /// Map a health factor to a risk level (1–5).
@@ -387,11 +721,11 @@ pub fn calculate_user_risk_level(health_factor: i128) -> i128 {
 /// # Errors
 /// Returns `AnalyticsError::DataNotFound` if the user has no analytics data.
 pub fn get_user_activity_summary(env: &Env, user: &Address) -> Result<UserMetrics, AnalyticsError> {
-    let user_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, DepositUserAnalytics>(&DepositDataKey::UserAnalytics(user.clone()))
-        .ok_or(AnalyticsError::DataNotFound)?;
+    let user_analytics = storage::get::<DepositDataKey, DepositUserAnalytics>(
+        env,
+        &DepositDataKey::UserAnalytics(user.clone()),
+    )
+    .ok_or(AnalyticsError::DataNotFound)?;
 
     let position = get_user_position_summary(env, user).unwrap_or(Position {
         collateral: 0,
@@ -403,9 +737,13 @@ pub fn get_user_activity_summary(env: &Env, user: &Address) -> Result<UserMetric
     let health_factor = calculate_health_factor(env, user).unwrap_or(i128::MAX);
     let risk_level = calculate_user_risk_level(health_factor);
 
-    let activity_score = (user_analytics.transaction_count as i128)
-        .saturating_mul(100)
-        .saturating_add(user_analytics.total_deposits / 1000);
+    let activity_score = decay_activity_score(
+        env,
+        (user_analytics.transaction_count as i128)
+            .saturating_mul(100)
+            .saturating_add(user_analytics.total_deposits / 1000),
+        user_analytics.last_activity,
+    );
 
     let metrics = UserMetrics {
         collateral: position.collateral,
@@ -423,9 +761,86 @@ pub fn get_user_activity_summary(env: &Env, user: &Address) -> Result<UserMetric
     Ok(metrics)
 }
 
+/// Set how activity scores decay (risk admin only).
+///
+/// Applies to every score from its next read, including time already idle.
+///
+/// # Arguments
+/// * `half_life` - Seconds for an idle user's score to halve (0 = no decay)
+/// * `grace_period` - Seconds after the last activity before decay starts
+///
+/// # Errors
+/// Returns `AnalyticsError::Unauthorized` if the caller lacks the risk admin role.
+pub fn set_activity_decay(
+    env: &Env,
+    caller: Address,
+    half_life: u64,
+    grace_period: u64,
+) -> Result<(), AnalyticsError> {
+    require_role(env, &caller, Role::RiskAdmin).map_err(|_| AnalyticsError::Unauthorized)?;
+
+    storage::set(
+        env,
+        &AnalyticsDataKey::ActivityDecay,
+        &ActivityDecayConfig {
+            half_life,
+            grace_period,
+        },
+    );
+
+    emit_activity_decay_updated(
+        env,
+        ActivityDecayUpdatedEvent {
+            admin: caller,
+            half_life,
+            grace_period,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the activity score decay parameters.
+///
+/// Defaults to a 90-day half-life with no grace period.
+pub fn get_activity_decay(env: &Env) -> ActivityDecayConfig {
+    storage::get(env, &AnalyticsDataKey::ActivityDecay).unwrap_or(ActivityDecayConfig {
+        half_life: DEFAULT_ACTIVITY_HALF_LIFE.get(),
+        grace_period: 0,
+    })
+}
+
+/// Decay `score` by the time since `last_activity`.
+///
+/// Halves the score for each full half-life past the grace period and
+/// interpolates linearly within the remaining partial half-life.
+fn decay_activity_score(env: &Env, score: i128, last_activity: u64) -> i128 {
+    let config = get_activity_decay(env);
+    let idle = env
+        .ledger()
+        .timestamp()
+        .saturating_sub(last_activity)
+        .saturating_sub(config.grace_period);
+    if config.half_life == 0 || idle == 0 || score <= 0 {
+        return score;
+    }
+
+    let halvings = idle / config.half_life;
+    if halvings >= 127 {
+        return 0;
+    }
+    let halved = score >> halvings;
+    // Between 1x and 0.5x across the partial half-life
+    let remainder = (idle % config.half_life) as i128;
+    let span = 2 * config.half_life as i128;
+    halved - halved * remainder / span
+}
+
 /// Recompute and persist a user's metrics.
 ///
-/// Calls [`get_user_activity_summary`] and stores the result.
+/// Calls [`get_user_activity_summary`] and stores the result (unless
+/// analytics writes are frozen).
 ///
 /// # Arguments
 /// * `user` - The user's address
@@ -435,36 +850,31 @@ pub fn get_user_activity_summary(env: &Env, user: &Address) -> Result<UserMetric
 pub fn update_user_metrics(env: &Env, user: &Address) -> Result<UserMetrics, AnalyticsError> {
     let metrics = get_user_activity_summary(env, user)?;
 
-    env.storage()
-        .persistent()
-        .set(&AnalyticsDataKey::UserMetrics(user.clone()), &metrics);
+    if !is_analytics_frozen(env) {
+        storage::set(env, &AnalyticsDataKey::UserMetrics(user.clone()), &metrics);
+    }
 
     Ok(metrics)
 }
 
 /// Record a new activity entry in the protocol activity log.
 ///
-/// Appends the entry and trims the log to `MAX_ACTIVITY_LOG_SIZE` (10,000).
-/// Also increments the global transaction counter.
+/// Appends the entry to the user's log and the global ring buffer, and
+/// increments the global transaction counter. Both are queued while analytics
+/// writes are frozen.
 ///
 /// # Arguments
 /// * `user` - The user who performed the activity
-/// * `activity_type` - Type symbol (e.g., "deposit", "borrow")
+/// * `activity_type` - Kind of operation
 /// * `amount` - Amount involved
 /// * `asset` - Asset address (None for native XLM)
 pub fn record_activity(
     env: &Env,
     user: &Address,
-    activity_type: Symbol,
+    activity_type: ActivityType,
     amount: i128,
     asset: Option<Address>,
 ) -> Result<(), AnalyticsError> {
-    let mut activity_log = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, Vec<ActivityEntry>>(&AnalyticsDataKey::ActivityLog)
-        .unwrap_or_else(|| Vec::new(env));
-
     let entry = ActivityEntry {
         user: user.clone(),
         activity_type,
@@ -474,30 +884,238 @@ pub fn record_activity(
         metadata: Map::new(env),
     };
 
-    activity_log.push_back(entry);
+    if is_analytics_frozen(env) {
+        let mut queued = get_queued_analytics(env);
+        push_activity(&mut queued.activities, entry, MAX_ACTIVITY_LOG_SIZE);
+        queued.transactions = queued.transactions.saturating_add(1);
+        storage::set(env, &AnalyticsDataKey::Queued, &queued);
+        return Ok(());
+    }
+
+    write_activity(env, entry);
+    add_transactions(env, 1);
+
+    Ok(())
+}
+
+/// Append `entry` to a log, dropping the oldest entry past `max_len`
+fn push_activity(log: &mut Vec<ActivityEntry>, entry: ActivityEntry, max_len: u32) {
+    log.push_back(entry);
+    if log.len() > max_len {
+        log.pop_front();
+    }
+}
 
-    if activity_log.len() > MAX_ACTIVITY_LOG_SIZE {
-        activity_log.pop_front();
+/// Record an activity entry, or queue it while analytics writes are frozen
+pub(crate) fn append_activity(env: &Env, entry: ActivityEntry) {
+    if is_analytics_frozen(env) {
+        queue_activity(env, entry);
+    } else {
+        write_activity(env, entry);
     }
+}
 
-    env.storage()
-        .persistent()
-        .set(&AnalyticsDataKey::ActivityLog, &activity_log);
+/// Write an entry to its user's log and the next ring buffer slot
+fn write_activity(env: &Env, entry: ActivityEntry) {
+    let user_key = AnalyticsDataKey::UserActivity(entry.user.clone());
+    let mut user_log: Vec<ActivityEntry> =
+        storage::get(env, &user_key).unwrap_or_else(|| Vec::new(env));
+    push_activity(&mut user_log, entry.clone(), MAX_USER_ACTIVITY_LOG_SIZE);
+    storage::set(env, &user_key, &user_log);
+
+    let count = activity_count(env);
+    let slot = (count % MAX_ACTIVITY_LOG_SIZE as u64) as u32;
+    storage::set(env, &AnalyticsDataKey::ActivitySlot(slot), &entry);
+    let count = count.saturating_add(1);
+    storage::set(env, &AnalyticsDataKey::ActivityCount, &count);
+
+    // Everything in the legacy log is now older than the whole ring
+    if count == MAX_ACTIVITY_LOG_SIZE as u64 {
+        storage::remove(env, &AnalyticsDataKey::ActivityLog);
+    }
+}
 
-    let total_transactions = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, u64>(&AnalyticsDataKey::TotalTransactions)
-        .unwrap_or(0);
+fn activity_count(env: &Env) -> u64 {
+    storage::get(env, &AnalyticsDataKey::ActivityCount).unwrap_or(0)
+}
 
-    env.storage().persistent().set(
+/// Protocol activity, newest first: the ring buffer, then the legacy log
+struct ActivityHistory {
+    count: u64,
+    ring_len: u32,
+    legacy: Option<Vec<LegacyActivityEntry>>,
+}
+
+impl ActivityHistory {
+    fn load(env: &Env) -> Self {
+        let count = activity_count(env);
+        Self {
+            count,
+            ring_len: count.min(MAX_ACTIVITY_LOG_SIZE as u64) as u32,
+            legacy: None,
+        }
+    }
+
+    /// The legacy log, read on first use
+    fn legacy(&mut self, env: &Env) -> &Vec<LegacyActivityEntry> {
+        self.legacy.get_or_insert_with(|| {
+            storage::get(env, &AnalyticsDataKey::ActivityLog).unwrap_or_else(|| Vec::new(env))
+        })
+    }
+
+    fn len(&mut self, env: &Env) -> u32 {
+        self.ring_len + self.legacy(env).len()
+    }
+
+    /// The `index`-th most recent entry
+    fn get(&mut self, env: &Env, index: u32) -> Option<ActivityEntry> {
+        if index < self.ring_len {
+            let slot = (self.count - 1 - index as u64) % MAX_ACTIVITY_LOG_SIZE as u64;
+            return storage::get(env, &AnalyticsDataKey::ActivitySlot(slot as u32));
+        }
+        let from_end = index - self.ring_len;
+        let legacy = self.legacy(env);
+        if from_end >= legacy.len() {
+            return None;
+        }
+        legacy
+            .get(legacy.len() - 1 - from_end)
+            .map(|entry| entry.upgrade(env))
+    }
+}
+
+fn add_transactions(env: &Env, count: u64) {
+    let total_transactions =
+        storage::get::<AnalyticsDataKey, u64>(env, &AnalyticsDataKey::TotalTransactions)
+            .unwrap_or(0);
+    storage::set(
+        env,
         &AnalyticsDataKey::TotalTransactions,
-        &(total_transactions + 1),
+        &total_transactions.saturating_add(count),
+    );
+}
+
+/// Attribute a tagged deposit or borrow to its integrator tag.
+///
+/// `opened_loan` is true when the borrow opened a new loan. Queued while
+/// analytics writes are frozen.
+pub(crate) fn record_tagged_flow(
+    env: &Env,
+    tag: Symbol,
+    deposit: i128,
+    borrow: i128,
+    opened_loan: bool,
+) {
+    let flow = TagAttribution {
+        total_deposits: deposit,
+        total_borrows: borrow,
+        loans_opened: opened_loan as u32,
+        last_activity: env.ledger().timestamp(),
+    };
+    if is_analytics_frozen(env) {
+        let mut queued = get_queued_analytics(env);
+        let pending = queued.tag_flows.get(tag.clone()).unwrap_or_default();
+        queued.tag_flows.set(tag, add_tag_flow(pending, &flow));
+        storage::set(env, &AnalyticsDataKey::Queued, &queued);
+        return;
+    }
+
+    let key = AnalyticsDataKey::TagAttribution(tag);
+    let attribution: TagAttribution = storage::get(env, &key).unwrap_or_default();
+    storage::set(env, &key, &add_tag_flow(attribution, &flow));
+}
+
+fn add_tag_flow(mut attribution: TagAttribution, flow: &TagAttribution) -> TagAttribution {
+    attribution.total_deposits = attribution
+        .total_deposits
+        .saturating_add(flow.total_deposits);
+    attribution.total_borrows = attribution.total_borrows.saturating_add(flow.total_borrows);
+    attribution.loans_opened = attribution.loans_opened.saturating_add(flow.loans_opened);
+    attribution.last_activity = attribution.last_activity.max(flow.last_activity);
+    attribution
+}
+
+/// Get the flows attributed to an integrator tag.
+pub fn get_tag_attribution(env: &Env, tag: Symbol) -> TagAttribution {
+    storage::get(env, &AnalyticsDataKey::TagAttribution(tag)).unwrap_or_default()
+}
+
+/// Freeze or unfreeze analytics writes (pause guardian only).
+///
+/// Lifting a freeze applies the queued activity entries, transaction count
+/// and tag flows, in the order they were recorded.
+///
+/// # Errors
+/// Returns `AnalyticsError::Unauthorized` if the caller lacks the pause
+/// guardian role.
+pub fn set_analytics_frozen(
+    env: &Env,
+    caller: Address,
+    frozen: bool,
+) -> Result<(), AnalyticsError> {
+    require_role(env, &caller, Role::PauseGuardian).map_err(|_| AnalyticsError::Unauthorized)?;
+
+    if frozen {
+        storage::set(env, &AnalyticsDataKey::WritesFrozen, &true);
+    } else {
+        storage::remove(env, &AnalyticsDataKey::WritesFrozen);
+        apply_queued_analytics(env);
+    }
+
+    emit_analytics_frozen(
+        env,
+        AnalyticsFrozenEvent {
+            admin: caller,
+            frozen,
+            timestamp: env.ledger().timestamp(),
+        },
     );
 
     Ok(())
 }
 
+/// Whether analytics writes are frozen
+pub fn is_analytics_frozen(env: &Env) -> bool {
+    storage::get(env, &AnalyticsDataKey::WritesFrozen).unwrap_or(false)
+}
+
+/// Get the analytics writes held back by the current freeze
+pub fn get_queued_analytics(env: &Env) -> QueuedAnalytics {
+    storage::get(env, &AnalyticsDataKey::Queued).unwrap_or(QueuedAnalytics {
+        activities: Vec::new(env),
+        transactions: 0,
+        tag_flows: Map::new(env),
+    })
+}
+
+/// Queue an activity log entry written while analytics writes are frozen
+pub(crate) fn queue_activity(env: &Env, entry: ActivityEntry) {
+    let mut queued = get_queued_analytics(env);
+    push_activity(&mut queued.activities, entry, MAX_ACTIVITY_LOG_SIZE);
+    storage::set(env, &AnalyticsDataKey::Queued, &queued);
+}
+
+fn apply_queued_analytics(env: &Env) {
+    let Some(queued) =
+        storage::get::<AnalyticsDataKey, QueuedAnalytics>(env, &AnalyticsDataKey::Queued)
+    else {
+        return;
+    };
+    storage::remove(env, &AnalyticsDataKey::Queued);
+
+    for entry in queued.activities.iter() {
+        write_activity(env, entry);
+    }
+    if queued.transactions > 0 {
+        add_transactions(env, queued.transactions);
+    }
+    for (tag, flow) in queued.tag_flows.iter() {
+        let key = AnalyticsDataKey::TagAttribution(tag);
+        let attribution: TagAttribution = storage::get(env, &key).unwrap_or_default();
+        storage::set(env, &key, &add_tag_flow(attribution, &flow));
+    }
+}
+
 /// Get recent protocol-wide activity entries with pagination.
 ///
 /// Returns entries in reverse chronological order (most recent first).
@@ -513,24 +1131,13 @@ pub fn get_recent_activity(
     limit: u32,
     offset: u32,
 ) -> Result<Vec<ActivityEntry>, AnalyticsError> {
-    let activity_log = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, Vec<ActivityEntry>>(&AnalyticsDataKey::ActivityLog)
-        .unwrap_or_else(|| Vec::new(env));
-
-    let total_len = activity_log.len();
-    if offset >= total_len {
-        return Ok(Vec::new(env));
-    }
-
+    let mut history = ActivityHistory::load(env);
     let mut result = Vec::new(env);
-    let start = total_len.saturating_sub(offset + limit);
-    let end = total_len.saturating_sub(offset);
 
-    for i in (start..end).rev() {
-        if let Some(entry) = activity_log.get(i) {
-            result.push_back(entry);
+    for i in offset..offset.saturating_add(limit) {
+        match history.get(env, i) {
+            Some(entry) => result.push_back(entry),
+            None => break,
         }
     }
 
@@ -539,8 +1146,8 @@ pub fn get_recent_activity(
 
 /// Get activity entries for a specific user with pagination.
 ///
-/// Filters the global activity log for entries matching the user, then
-/// applies pagination. Returns entries in reverse chronological order.
+/// Reads the user's own log, then any of the user's entries in the legacy
+/// activity log. Returns entries in reverse chronological order.
 ///
 /// # Arguments
 /// * `user` - The user's address to filter by
@@ -555,34 +1162,41 @@ pub fn get_user_activity_feed(
     limit: u32,
     offset: u32,
 ) -> Result<Vec<ActivityEntry>, AnalyticsError> {
-    let activity_log = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, Vec<ActivityEntry>>(&AnalyticsDataKey::ActivityLog)
-        .unwrap_or_else(|| Vec::new(env));
-
-    let mut user_activities = Vec::new(env);
-
-    for i in (0..activity_log.len()).rev() {
-        if let Some(entry) = activity_log.get(i) {
-            if entry.user == *user {
-                user_activities.push_back(entry);
-            }
+    let user_log: Vec<ActivityEntry> =
+        storage::get(env, &AnalyticsDataKey::UserActivity(user.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+
+    let mut result = Vec::new(env);
+    let mut skipped = 0u32;
+    for entry in user_log.iter().rev() {
+        if result.len() >= limit {
+            return Ok(result);
+        }
+        if skipped < offset {
+            skipped += 1;
+        } else {
+            result.push_back(entry);
         }
     }
 
-    let total_len = user_activities.len();
-    if offset >= total_len {
-        return Ok(Vec::new(env));
+    // A full log has trimmed entries newer than anything in the legacy log
+    if user_log.len() >= MAX_USER_ACTIVITY_LOG_SIZE {
+        return Ok(result);
     }
-
-    let mut result = Vec::new(env);
-    let end = total_len.saturating_sub(offset);
-    let start = end.saturating_sub(limit);
-
-    for i in start..end {
-        if let Some(entry) = user_activities.get(i) {
-            result.push_back(entry);
+    // Only users with activity from before the ring buffer reach this scan
+    let legacy: Vec<LegacyActivityEntry> =
+        storage::get(env, &AnalyticsDataKey::ActivityLog).unwrap_or_else(|| Vec::new(env));
+    for entry in legacy.iter().rev() {
+        if result.len() >= limit {
+            break;
+        }
+        if entry.user != *user {
+            continue;
+        }
+        if skipped < offset {
+            skipped += 1;
+        } else {
+            result.push_back(entry.upgrade(env));
         }
     }
 
@@ -591,35 +1205,30 @@ pub fn get_user_activity_feed(
 
 /// Get activity entries filtered by activity type.
 ///
-/// Scans the activity log in reverse order and returns up to `limit` entries
-/// matching the given `activity_type`.
+/// Scans the activity history in reverse order and returns up to `limit`
+/// entries matching the given `activity_type`.
 ///
 /// # Arguments
-/// * `activity_type` - The activity type symbol to filter by (e.g., "deposit")
+/// * `activity_type` - The kind of activity to filter by
 /// * `limit` - Maximum number of entries to return
 ///
 /// # Returns
 /// A vector of matching `ActivityEntry` records.
 pub fn get_activity_by_type(
     env: &Env,
-    activity_type: Symbol,
+    activity_type: ActivityType,
     limit: u32,
 ) -> Result<Vec<ActivityEntry>, AnalyticsError> {
-    let activity_log = env
-        .storage()
-        .persistent()
-        .get::<AnalyticsDataKey, Vec<ActivityEntry>>(&AnalyticsDataKey::ActivityLog)
-        .unwrap_or_else(|| Vec::new(env));
-
+    let mut history = ActivityHistory::load(env);
     let mut filtered = Vec::new(env);
     let mut count = 0u32;
 
-    for i in (0..activity_log.len()).rev() {
+    for i in 0..history.len(env) {
         if count >= limit {
             break;
         }
 
-        if let Some(entry) = activity_log.get(i) {
+        if let Some(entry) = history.get(env, i) {
             if entry.activity_type == activity_type {
                 filtered.push_back(entry);
                 count += 1;
@@ -649,8 +1258,8 @@ pub fn generate_protocol_report(env: &Env) -> Result<ProtocolReport, AnalyticsEr
 
 /// Generate a comprehensive user analytics report.
 ///
-/// Includes the user's computed metrics, current position, and the 10 most
-/// recent activities.
+/// Includes the user's computed metrics, current position, the 10 most
+/// recent activities, and the risk badges their positions show.
 ///
 /// # Arguments
 /// * `user` - The user's address
@@ -664,14 +1273,224 @@ pub fn generate_user_report(env: &Env, user: &Address) -> Result<UserReport, Ana
     let metrics = get_user_activity_summary(env, user)?;
     let position = get_user_position_summary(env, user)?;
     let recent_activities = get_user_activity_feed(env, user, 10, 0)?;
+    let badges = get_risk_badges(env, user, &position);
 
     let report = UserReport {
         user: user.clone(),
         metrics,
         position,
         recent_activities,
+        badges,
         timestamp: env.ledger().timestamp(),
     };
 
     Ok(report)
 }
+
+/// Risk badges shown by a user's positions, in [`RiskBadge`] order.
+///
+/// Cross-asset badges are skipped while a price the user's cross-asset
+/// position depends on is stale.
+fn get_risk_badges(env: &Env, user: &Address, position: &Position) -> Vec<RiskBadge> {
+    let config = get_risk_badge_config(env);
+    let scale = BASIS_POINTS_SCALE.get();
+    let mut badges = Vec::new(env);
+
+    if position.debt > 0
+        && position.debt.saturating_mul(scale)
+            >= position
+                .collateral
+                .saturating_mul(config.high_utilization_bps)
+    {
+        badges.push_back(RiskBadge::HighUtilization);
+    }
+
+    let cross_asset = crate::cross_asset::get_user_position_summary(env, user).ok();
+    if let Some(summary) = &cross_asset {
+        if summary.total_debt_value > 0
+            && largest_collateral_share(env, user, summary.total_collateral_value)
+                >= config.concentration_bps
+        {
+            badges.push_back(RiskBadge::CollateralConcentration);
+        }
+    }
+
+    let core_without_buffer = position.debt > 0
+        && get_position_liquidation_threshold(env, user).is_ok_and(|threshold| {
+            calculate_health_factor(env, user)
+                .is_ok_and(|ratio| ratio < threshold.saturating_add(config.liquidation_buffer_bps))
+        });
+    let cross_asset_without_buffer = cross_asset.is_some_and(|summary| {
+        summary.weighted_debt_value > 0
+            && summary.health_factor < scale.saturating_add(config.liquidation_buffer_bps)
+    });
+    if core_without_buffer || cross_asset_without_buffer {
+        badges.push_back(RiskBadge::NoLiquidationBuffer);
+    }
+
+    let stable_share = match get_rate_mode(env, user) {
+        RateMode::Stable if position.debt > 0 => scale,
+        _ => 0,
+    };
+    if position.debt > 0 && stable_share >= config.stable_rate_share_bps {
+        badges.push_back(RiskBadge::StableRateHeavy);
+    }
+
+    badges
+}
+
+/// Share of `total_value`, in basis points, held as collateral in the user's
+/// largest cross-asset collateral asset.
+fn largest_collateral_share(env: &Env, user: &Address, total_value: i128) -> i128 {
+    if total_value <= 0 {
+        return 0;
+    }
+    let configs = get_asset_configs(env);
+    let largest = get_user_asset_positions(env, user)
+        .iter()
+        .filter_map(|(asset_key, position)| {
+            let config = configs.get(asset_key)?;
+            Some((position.collateral * config.price) / 10_000_000)
+        })
+        .max()
+        .unwrap_or(0);
+    largest.saturating_mul(BASIS_POINTS_SCALE.get()) / total_value
+}
+
+/// Set the thresholds at which risk badges are shown (risk admin only).
+///
+/// # Errors
+/// * `AnalyticsError::Unauthorized` - The caller lacks the risk admin role
+/// * `AnalyticsError::InvalidParameter` - A share is outside 1–10,000 basis
+///   points, or the buffer outside 0–10,000
+pub fn set_risk_badge_config(
+    env: &Env,
+    caller: Address,
+    config: RiskBadgeConfig,
+) -> Result<(), AnalyticsError> {
+    require_role(env, &caller, Role::RiskAdmin).map_err(|_| AnalyticsError::Unauthorized)?;
+
+    let scale = BASIS_POINTS_SCALE.get();
+    let shares = [
+        config.high_utilization_bps,
+        config.concentration_bps,
+        config.stable_rate_share_bps,
+    ];
+    if shares.iter().any(|share| *share <= 0 || *share > scale)
+        || !(0..=scale).contains(&config.liquidation_buffer_bps)
+    {
+        return Err(AnalyticsError::InvalidParameter);
+    }
+
+    storage::set(env, &AnalyticsDataKey::RiskBadgeConfig, &config);
+
+    emit_risk_badge_config_updated(
+        env,
+        RiskBadgeConfigUpdatedEvent {
+            admin: caller,
+            high_utilization_bps: config.high_utilization_bps,
+            concentration_bps: config.concentration_bps,
+            liquidation_buffer_bps: config.liquidation_buffer_bps,
+            stable_rate_share_bps: config.stable_rate_share_bps,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the thresholds at which risk badges are shown.
+///
+/// Defaults to 80% utilization, 90% concentration, a 10% buffer and a 50%
+/// stable-rate share until the risk admin sets them.
+pub fn get_risk_badge_config(env: &Env) -> RiskBadgeConfig {
+    storage::get(env, &AnalyticsDataKey::RiskBadgeConfig).unwrap_or(RiskBadgeConfig {
+        high_utilization_bps: 8_000,
+        concentration_bps: 9_000,
+        liquidation_buffer_bps: 1_000,
+        stable_rate_share_bps: 5_000,
+    })
+}
+
+/// Hash a user's report for off-chain signing.
+///
+/// The hash is SHA-256 over the XDR encoding of the `UserReport` that
+/// [`generate_user_report`] returns in the same ledger, so anyone holding the
+/// report can recompute it and check a signature over it. The report carries
+/// the ledger timestamp, which ties the hash to that snapshot.
+///
+/// # Arguments
+/// * `user` - The user's address
+///
+/// # Returns
+/// The 32-byte SHA-256 digest of the encoded report.
+///
+/// # Errors
+/// Returns `AnalyticsError::DataNotFound` if the user has no recorded data.
+pub fn hash_user_report(env: &Env, user: &Address) -> Result<BytesN<32>, AnalyticsError> {
+    let report = generate_user_report(env, user)?;
+    Ok(env.crypto().sha256(&report.to_xdr(env)).into())
+}
+
+/// Export full positions for a batch of users in a single call.
+///
+/// Intended for indexers bootstrapping state. Users without a core position
+/// are returned with zeroed balances rather than skipped, so the output is
+/// index-aligned with `users`.
+///
+/// # Arguments
+/// * `users` - Up to [`MAX_POSITIONS_BATCH_SIZE`] user addresses
+///
+/// # Returns
+/// One `PositionSnapshot` per requested user, in input order.
+///
+/// # Errors
+/// Returns `AnalyticsError::InvalidParameter` if the batch is too large.
+pub fn get_positions_batch(
+    env: &Env,
+    users: &Vec<Address>,
+) -> Result<Vec<PositionSnapshot>, AnalyticsError> {
+    if users.len() > MAX_POSITIONS_BATCH_SIZE {
+        return Err(AnalyticsError::InvalidParameter);
+    }
+
+    let timestamp = env.ledger().timestamp();
+    let mut snapshots = Vec::new(env);
+
+    for user in users.iter() {
+        let position = get_user_position_summary(env, &user).unwrap_or(Position {
+            collateral: 0,
+            debt: 0,
+            borrow_interest: 0,
+            last_accrual_time: 0,
+        });
+
+        let health_factor = if position.debt == 0 {
+            i128::MAX
+        } else {
+            Amount::new(position.collateral)
+                .ratio_bps(Amount::new(position.debt))?
+                .get()
+        };
+
+        let asset_positions = get_user_asset_positions(env, &user);
+        let cross_asset_health_factor = if asset_positions.is_empty() {
+            None
+        } else {
+            crate::cross_asset::get_user_position_summary(env, &user)
+                .ok()
+                .map(|summary| summary.health_factor)
+        };
+
+        snapshots.push_back(PositionSnapshot {
+            user,
+            position,
+            health_factor,
+            asset_positions,
+            cross_asset_health_factor,
+            timestamp,
+        });
+    }
+
+    Ok(snapshots)
+}
@@ -0,0 +1,106 @@
+//! # Batch Operations
+//!
+//! Runs a sequence of cross-asset deposits, borrows, repayments and
+//! withdrawals for one user in a single transaction, for one-transaction
+//! leverage, deleverage and rebalancing.
+//!
+//! Each operation runs as its standalone entrypoint would, with every check
+//! except the health factor: a borrow or withdrawal may take the position
+//! below a health factor of 1.0 part-way through, e.g. withdrawing collateral
+//! before repaying the debt it backs. The health factor is checked once, after
+//! the last operation. While the batch runs, a scratch flag in temporary
+//! storage (see `storage`) tells `cross_asset` to skip the per-operation
+//! check for this user only.
+//!
+//! A batch is atomic: if any operation or the final check fails, the host
+//! rolls back every operation in it, flag included.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::cross_asset::{self, CrossAssetError, UserPositionSummary};
+use crate::events::{emit_batch_executed, BatchExecutedEvent};
+use crate::storage;
+
+/// Maximum number of operations in one batch
+pub const MAX_BATCH_OPERATIONS: u32 = 10;
+
+/// Scratch keys for batch state
+#[contracttype]
+#[derive(Clone)]
+pub enum BatchDataKey {
+    /// Set while a batch for the user is executing
+    HealthCheckDeferred(Address),
+}
+
+/// One step of a batch: the asset (`None` for XLM) and amount
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Operation {
+    /// Deposit collateral, as `ca_deposit_collateral`
+    Deposit(Option<Address>, i128),
+    /// Borrow, as `ca_borrow_asset`
+    Borrow(Option<Address>, i128),
+    /// Repay debt, as `ca_repay_debt`
+    Repay(Option<Address>, i128),
+    /// Withdraw collateral, as `ca_withdraw_collateral`
+    Withdraw(Option<Address>, i128),
+}
+
+/// Execute `operations` in order for `user` (must authorize)
+///
+/// # Returns
+/// The user's position summary after the last operation.
+///
+/// # Errors
+/// * `InvalidBatch` - The batch is empty or has more than
+///   [`MAX_BATCH_OPERATIONS`]
+/// * `UnhealthyPosition` - The health factor ends below 1.0
+/// * Any error of the failing operation, except the health factor check
+pub fn batch(
+    env: &Env,
+    user: Address,
+    operations: Vec<Operation>,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    user.require_auth();
+    if operations.is_empty() || operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(CrossAssetError::InvalidBatch);
+    }
+
+    let key = BatchDataKey::HealthCheckDeferred(user.clone());
+    storage::set_scratch(env, &key, &true);
+    for operation in operations.iter() {
+        match operation {
+            Operation::Deposit(asset, amount) => {
+                cross_asset::deposit_authorized(env, user.clone(), asset, amount)?
+            }
+            Operation::Borrow(asset, amount) => {
+                cross_asset::borrow_authorized(env, user.clone(), asset, amount)?
+            }
+            Operation::Repay(asset, amount) => {
+                cross_asset::repay_authorized(env, user.clone(), asset, amount)?
+            }
+            Operation::Withdraw(asset, amount) => {
+                cross_asset::withdraw_authorized(env, user.clone(), asset, amount)?
+            }
+        };
+    }
+    storage::remove_scratch(env, &key);
+
+    let summary = cross_asset::require_healthy(env, &user)?;
+    emit_batch_executed(
+        env,
+        BatchExecutedEvent {
+            user,
+            operations: operations.len(),
+            health_factor: summary.health_factor,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(summary)
+}
+
+/// Whether a batch for `user` is executing, so its borrows and withdrawals
+/// leave the health factor check to the end of the batch
+pub(crate) fn is_health_check_deferred(env: &Env, user: &Address) -> bool {
+    storage::has_scratch(env, &BatchDataKey::HealthCheckDeferred(user.clone()))
+}
@@ -10,22 +10,37 @@
 //! ## Interest Accrual
 //! Interest is accrued on existing debt before any new borrow using the dynamic
 //! rate from the `interest_rate` module. The rate is based on protocol utilization
-//! following a kink-based piecewise linear model.
+//! following a kink-based piecewise linear model. Stable-mode positions accrue at
+//! their locked rate instead (see `rate_mode`).
 //!
 //! ## Invariants
 //! - A user must have collateral deposited before borrowing.
 //! - The collateral ratio must remain at or above the minimum after the borrow.
 //! - Borrow amount must not exceed the maximum borrowable given current collateral.
+//! - Total debt must reach the borrowed asset's minimum debt, if it has one.
 
 #![allow(unused)]
 use soroban_sdk::{contracterror, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
+use crate::analytics::{record_tagged_flow, ActivityType};
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::{Bps, BASIS_POINTS_SCALE, DEFAULT_COLLATERAL_FACTOR, MIN_COLLATERAL_RATIO};
+use crate::credit::record_loan_opened;
+use crate::cross_asset::{is_asset_delisted, is_dust_debt, AssetKey};
 use crate::deposit::{
-    add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
-    emit_user_activity_tracked_event, update_protocol_analytics, update_user_analytics, Activity,
-    AssetParams, DepositDataKey, Position, ProtocolAnalytics, UserAnalytics,
+    add_activity_log, adjust_asset_totals, emit_analytics_updated_event,
+    emit_position_updated_event, emit_user_activity_tracked_event, get_asset_totals,
+    get_core_asset_config, update_protocol_analytics, update_user_analytics, DepositDataKey,
+    Position, ProtocolAnalytics, UserAnalytics,
 };
 use crate::events::{emit_borrow, BorrowEvent};
+use crate::loyalty::refresh_tier;
+use crate::math::{Amount, MathError};
+use crate::rate_mode::{record_borrow, resolve_borrow_mode, RateMode};
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::risk_management::{apply_recovery_haircut, is_emergency_paused};
+use crate::storage;
+use stellarlend_common::pause::{is_switch_set, PAUSE_BORROW};
 
 /// Errors that can occur during borrow operations
 #[contracterror]
@@ -48,13 +63,27 @@ pub enum BorrowError {
     Reentrancy = 7,
     /// Maximum borrow limit exceeded
     MaxBorrowExceeded = 8,
-    /// Asset is not enabled for borrowing
+    /// Asset is not enabled for borrowing, or is delisted
     AssetNotEnabled = 9,
+    /// Borrow would push the asset's total borrowed above its cap
+    BorrowCapExceeded = 10,
+    /// Requested rate mode differs from the mode of the existing debt
+    RateModeMismatch = 11,
+    /// Debt would stay below the asset's minimum debt
+    DebtBelowMinimum = 12,
 }
 
-/// Minimum collateral ratio (in basis points, e.g., 15000 = 150%)
-/// This is the minimum ratio required: collateral_value / debt_value >= 1.5
-const MIN_COLLATERAL_RATIO_BPS: i128 = 15000; // 150%
+impl From<Reentered> for BorrowError {
+    fn from(_: Reentered) -> Self {
+        BorrowError::Reentrancy
+    }
+}
+
+impl From<MathError> for BorrowError {
+    fn from(_: MathError) -> Self {
+        BorrowError::Overflow
+    }
+}
 
 /// Annual interest rate in basis points (e.g., 500 = 5% per year)
 /// This is a simple constant rate model - in production, this would be more sophisticated
@@ -63,9 +92,11 @@ const MIN_COLLATERAL_RATIO_BPS: i128 = 15000; // 150%
 /// Calculate interest accrued since last accrual time
 /// Uses simple interest: interest = principal * rate * time
 /// Calculate accrued interest using dynamic interest rate
-/// Uses the current borrow rate based on protocol utilization
+/// Uses the current borrow rate based on protocol utilization, less any
+/// staker discount the user qualifies for
 fn calculate_accrued_interest(
     env: &Env,
+    user: &Address,
     principal: i128,
     last_accrual_time: u64,
     current_time: u64,
@@ -79,8 +110,7 @@ fn calculate_accrued_interest(
     }
 
     // Get current borrow rate (in basis points)
-    let rate_bps =
-        crate::interest_rate::calculate_borrow_rate(env).map_err(|_| BorrowError::Overflow)?;
+    let rate_bps = crate::rate_mode::accrual_rate(env, user).map_err(|_| BorrowError::Overflow)?;
 
     // Calculate interest using the dynamic rate
     crate::interest_rate::calculate_accrued_interest(
@@ -94,7 +124,11 @@ fn calculate_accrued_interest(
 
 /// Accrue interest on a position
 /// Updates the position's borrow_interest and last_accrual_time
-fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), BorrowError> {
+pub(crate) fn accrue_interest(
+    env: &Env,
+    user: &Address,
+    position: &mut Position,
+) -> Result<(), BorrowError> {
     let current_time = env.ledger().timestamp();
 
     if position.debt == 0 {
@@ -104,14 +138,18 @@ fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), BorrowError
     }
 
     // Calculate new interest accrued using dynamic rate
-    let new_interest =
-        calculate_accrued_interest(env, position.debt, position.last_accrual_time, current_time)?;
+    let new_interest = calculate_accrued_interest(
+        env,
+        user,
+        position.debt,
+        position.last_accrual_time,
+        current_time,
+    )?;
 
     // Add to existing interest
-    position.borrow_interest = position
-        .borrow_interest
-        .checked_add(new_interest)
-        .ok_or(BorrowError::Overflow)?;
+    position.borrow_interest = Amount::new(position.borrow_interest)
+        .checked_add(Amount::new(new_interest))?
+        .get();
 
     // Update last accrual time
     position.last_accrual_time = current_time;
@@ -128,18 +166,15 @@ fn calculate_collateral_ratio(
     interest: i128,
     collateral_factor: i128,
 ) -> Option<i128> {
-    let total_debt = debt.checked_add(interest)?;
-    if total_debt == 0 {
+    let total_debt = Amount::new(debt).checked_add(Amount::new(interest)).ok()?;
+    if total_debt == Amount::ZERO {
         return None; // No debt means infinite ratio
     }
 
-    // collateral_value = collateral * collateral_factor / 10000 (basis points)
-    let collateral_value = collateral
-        .checked_mul(collateral_factor)?
-        .checked_div(10000)?;
-
-    // ratio = (collateral_value * 10000) / total_debt (in basis points)
-    collateral_value.checked_mul(10000)?.checked_div(total_debt)
+    let collateral_value = Bps::new(collateral_factor)
+        .apply(Amount::new(collateral))
+        .ok()?;
+    collateral_value.ratio_bps(total_debt).ok().map(Bps::get)
 }
 
 /// Calculate maximum borrowable amount based on collateral
@@ -151,30 +186,19 @@ fn calculate_max_borrowable(
     collateral_factor: i128,
 ) -> Result<i128, BorrowError> {
     // Calculate collateral value
-    let collateral_value = collateral
-        .checked_mul(collateral_factor)
-        .ok_or(BorrowError::Overflow)?
-        .checked_div(10000)
-        .ok_or(BorrowError::Overflow)?;
+    let collateral_value = Bps::new(collateral_factor).apply(Amount::new(collateral))?;
 
     // Calculate current total debt
-    let current_total_debt = current_debt
-        .checked_add(current_interest)
-        .ok_or(BorrowError::Overflow)?;
-
-    // Maximum debt allowed = collateral_value / (MIN_COLLATERAL_RATIO_BPS / 10000)
-    // = collateral_value * 10000 / MIN_COLLATERAL_RATIO_BPS
-    let max_debt = collateral_value
-        .checked_mul(10000)
-        .ok_or(BorrowError::Overflow)?
-        .checked_div(MIN_COLLATERAL_RATIO_BPS)
-        .ok_or(BorrowError::Overflow)?;
+    let current_total_debt =
+        Amount::new(current_debt).checked_add(Amount::new(current_interest))?;
+
+    // Maximum debt allowed = collateral_value * 10000 / MIN_COLLATERAL_RATIO
+    let max_debt =
+        collateral_value.mul_div(BASIS_POINTS_SCALE.get(), MIN_COLLATERAL_RATIO.get())?;
 
     // Maximum borrowable = max_debt - current_total_debt
     if max_debt > current_total_debt {
-        max_debt
-            .checked_sub(current_total_debt)
-            .ok_or(BorrowError::Overflow)
+        Ok(max_debt.checked_sub(current_total_debt)?.get())
     } else {
         Ok(0) // Already at or above max debt
     }
@@ -189,29 +213,22 @@ fn validate_collateral_ratio_after_borrow(
 ) -> Result<(), BorrowError> {
     // Get user position
     let position_key = DepositDataKey::Position(user.clone());
-    let position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
+    let position = storage::get::<DepositDataKey, Position>(env, &position_key)
         .ok_or(BorrowError::InsufficientCollateral)?;
 
     // Get current collateral balance
     let collateral_key = DepositDataKey::CollateralBalance(user.clone());
-    let current_collateral = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    let current_collateral =
+        storage::get::<DepositDataKey, i128>(env, &collateral_key).unwrap_or(0);
 
     if current_collateral == 0 {
         return Err(BorrowError::InsufficientCollateral);
     }
 
     // Calculate new debt after borrow
-    let new_debt = position
-        .debt
-        .checked_add(borrow_amount)
-        .ok_or(BorrowError::Overflow)?;
+    let new_debt = Amount::new(position.debt)
+        .checked_add(Amount::new(borrow_amount))?
+        .get();
 
     // Calculate new collateral ratio
     if let Some(new_ratio) = calculate_collateral_ratio(
@@ -220,7 +237,7 @@ fn validate_collateral_ratio_after_borrow(
         position.borrow_interest,
         collateral_factor,
     ) {
-        if new_ratio < MIN_COLLATERAL_RATIO_BPS {
+        if new_ratio < MIN_COLLATERAL_RATIO.get() {
             return Err(BorrowError::InsufficientCollateralRatio);
         }
     } else {
@@ -245,6 +262,9 @@ fn validate_collateral_ratio_after_borrow(
 /// * `user` - The address of the user borrowing assets
 /// * `asset` - The address of the asset contract to borrow (None for native XLM)
 /// * `amount` - The amount to borrow
+/// * `tag` - Optional integrator tag recorded with the activity
+/// * `rate_mode` - Rate mode for the debt; `None` keeps the mode of existing
+///   debt (variable for a new loan)
 ///
 /// # Returns
 /// Returns the updated total debt (principal + interest) for the user
@@ -253,11 +273,14 @@ fn validate_collateral_ratio_after_borrow(
 /// * `BorrowError::InvalidAmount` - If amount is zero or negative
 /// * `BorrowError::InvalidAsset` - If asset address is invalid
 /// * `BorrowError::InsufficientCollateral` - If user doesn't have enough collateral
-/// * `BorrowError::BorrowPaused` - If borrows are paused
+/// * `BorrowError::BorrowPaused` - If borrows or the protocol are paused
 /// * `BorrowError::InsufficientCollateralRatio` - If borrow would violate minimum ratio
 /// * `BorrowError::MaxBorrowExceeded` - If borrow exceeds maximum allowed
 /// * `BorrowError::Overflow` - If calculation overflow occurs
 /// * `BorrowError::AssetNotEnabled` - If asset is not enabled for borrowing
+/// * `BorrowError::BorrowCapExceeded` - If borrow would exceed the asset's borrow cap
+/// * `BorrowError::RateModeMismatch` - If `rate_mode` differs from the existing debt's mode
+/// * `BorrowError::DebtBelowMinimum` - If total debt would stay below the asset's minimum debt
 ///
 /// # Security
 /// * Validates borrow amount > 0
@@ -275,7 +298,11 @@ pub fn borrow_asset(
     user: Address,
     asset: Option<Address>,
     amount: i128,
+    tag: Option<Symbol>,
+    rate_mode: Option<RateMode>,
 ) -> Result<i128, BorrowError> {
+    let _guard = OperationGuard::enter(env)?;
+
     // Validate amount
     if amount <= 0 {
         return Err(BorrowError::InvalidAmount);
@@ -283,18 +310,19 @@ pub fn borrow_asset(
 
     // Check if borrows are paused
     let pause_switches_key = DepositDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Map<Symbol, bool>>(&pause_switches_key)
+    if let Some(pause_map) =
+        storage::get::<DepositDataKey, Map<Symbol, bool>>(env, &pause_switches_key)
     {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_borrow")) {
-            if paused {
-                return Err(BorrowError::BorrowPaused);
-            }
+        if is_switch_set(env, &pause_map, PAUSE_BORROW) {
+            return Err(BorrowError::BorrowPaused);
         }
     }
 
+    // Check emergency pause
+    if is_emergency_paused(env) {
+        return Err(BorrowError::BorrowPaused);
+    }
+
     // Get current timestamp
     let timestamp = env.ledger().timestamp();
 
@@ -305,29 +333,26 @@ pub fn borrow_asset(
             return Err(BorrowError::InvalidAsset);
         }
 
-        // Check asset parameters
-        let asset_params_key = DepositDataKey::AssetParams(asset_addr.clone());
-        if let Some(params) = env
-            .storage()
-            .persistent()
-            .get::<DepositDataKey, AssetParams>(&asset_params_key)
-        {
-            if !params.deposit_enabled {
-                // For simplicity, we use deposit_enabled as a proxy for borrow enabled
-                // In production, you might have a separate borrow_enabled flag
+        // Check asset configuration
+        if let Some(config) = get_core_asset_config(env, asset_addr) {
+            let delisted = is_asset_delisted(env, &AssetKey::Token(asset_addr.clone()));
+            if !config.can_borrow || delisted {
                 return Err(BorrowError::AssetNotEnabled);
             }
+
+            // Check borrow cap
+            let borrowed = get_asset_totals(env, asset_addr).borrowed;
+            if config.max_borrow > 0 && borrowed.saturating_add(amount) > config.max_borrow {
+                return Err(BorrowError::BorrowCapExceeded);
+            }
         }
     }
 
     // Get user position
     let position_key = DepositDataKey::Position(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
-        .unwrap_or_else(|| Position {
+    let mut position =
+        storage::get::<DepositDataKey, Position>(env, &position_key).unwrap_or_else(|| Position {
             collateral: 0,
             debt: 0,
             borrow_interest: 0,
@@ -335,15 +360,15 @@ pub fn borrow_asset(
         });
 
     // Accrue interest on existing debt before borrowing
-    accrue_interest(env, &mut position)?;
+    accrue_interest(env, &user, &mut position)?;
+    let opens_loan = position.debt == 0 && position.borrow_interest == 0;
+    let rate_mode = resolve_borrow_mode(env, &user, position.debt, rate_mode)
+        .map_err(|_| BorrowError::RateModeMismatch)?;
 
     // Get current collateral balance
     let collateral_key = DepositDataKey::CollateralBalance(user.clone());
-    let current_collateral = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    let current_collateral =
+        storage::get::<DepositDataKey, i128>(env, &collateral_key).unwrap_or(0);
 
     // Check if user has collateral
     if current_collateral == 0 {
@@ -352,20 +377,13 @@ pub fn borrow_asset(
 
     // Get asset parameters for collateral factor
     // Default collateral factor if asset params not found
-    let collateral_factor = if let Some(asset_addr) = asset.as_ref() {
-        let asset_params_key = DepositDataKey::AssetParams(asset_addr.clone());
-        if let Some(params) = env
-            .storage()
-            .persistent()
-            .get::<DepositDataKey, AssetParams>(&asset_params_key)
-        {
-            params.collateral_factor
-        } else {
-            10000 // Default 100% if not configured
-        }
-    } else {
-        10000 // Default 100% for native XLM
-    };
+    let collateral_factor = asset
+        .as_ref()
+        .and_then(|asset_addr| get_core_asset_config(env, asset_addr))
+        .map_or(DEFAULT_COLLATERAL_FACTOR.get(), |config| {
+            config.collateral_factor
+        });
+    let collateral_factor = apply_recovery_haircut(env, collateral_factor);
 
     // Calculate maximum borrowable amount
     let max_borrowable = calculate_max_borrowable(
@@ -384,15 +402,32 @@ pub fn borrow_asset(
     validate_collateral_ratio_after_borrow(env, &user, amount, collateral_factor)?;
 
     // Calculate new debt
-    let new_debt = position
-        .debt
-        .checked_add(amount)
-        .ok_or(BorrowError::Overflow)?;
+    let new_debt = Amount::new(position.debt)
+        .checked_add(Amount::new(amount))?
+        .get();
+    let new_total_debt = Amount::new(new_debt)
+        .checked_add(Amount::new(position.borrow_interest))?
+        .get();
+    if is_dust_debt(env, &AssetKey::from_option(asset.clone()), new_total_debt) {
+        return Err(BorrowError::DebtBelowMinimum);
+    }
+
+    // Lock or re-average the stable rate before the debt grows
+    record_borrow(env, &user, position.debt, amount, rate_mode)
+        .map_err(|_| BorrowError::Overflow)?;
 
     // Update position
     position.debt = new_debt;
     position.last_accrual_time = timestamp;
-    env.storage().persistent().set(&position_key, &position);
+    storage::set(env, &position_key, &position);
+    adjust_asset_totals(env, &asset, 0, amount);
+
+    // Record borrow time for the withdrawal cooldown
+    storage::set(
+        env,
+        &DepositDataKey::LastBorrowTime(user.clone()),
+        &timestamp,
+    );
 
     // Handle asset transfer - contract sends tokens to user
     if let Some(ref asset_addr) = asset {
@@ -410,6 +445,7 @@ pub fn borrow_asset(
             &user,                           // to (user)
             &amount,
         );
+        cash_ledger::record_outflow(env, asset_addr, CashReason::Borrow, amount);
     } else {
         // Native XLM borrow - in Soroban, native assets are handled differently
         // For now, we'll track it but actual XLM handling depends on Soroban's native asset support
@@ -421,20 +457,27 @@ pub fn borrow_asset(
 
     // Update protocol analytics
     update_protocol_analytics_borrow(env, amount)?;
+    if opens_loan {
+        record_loan_opened(env, &user);
+    }
 
     // Add to activity log
     add_activity_log(
         env,
         &user,
-        Symbol::new(env, "borrow"),
+        ActivityType::Borrow,
         amount,
         asset.clone(),
         timestamp,
+        tag.clone(),
     )
     .map_err(|e| match e {
         crate::deposit::DepositError::Overflow => BorrowError::Overflow,
         _ => BorrowError::Overflow,
     })?;
+    if let Some(tag) = tag {
+        record_tagged_flow(env, tag, 0, amount, opens_loan);
+    }
 
     // Emit borrow event
     emit_borrow(
@@ -457,11 +500,9 @@ pub fn borrow_asset(
     emit_user_activity_tracked_event(env, &user, Symbol::new(env, "borrow"), amount, timestamp);
 
     // Return total debt (principal + interest)
-    let total_debt = position
-        .debt
-        .checked_add(position.borrow_interest)
-        .ok_or(BorrowError::Overflow)?;
-    Ok(total_debt)
+    let total_debt =
+        Amount::new(position.debt).checked_add(Amount::new(position.borrow_interest))?;
+    Ok(total_debt.get())
 }
 
 /// Update user analytics after borrow
@@ -473,10 +514,7 @@ fn update_user_analytics_borrow(
 ) -> Result<(), BorrowError> {
     let analytics_key = DepositDataKey::UserAnalytics(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, UserAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, UserAnalytics>(env, &analytics_key)
         .unwrap_or_else(|| UserAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -493,23 +531,20 @@ fn update_user_analytics_borrow(
             loyalty_tier: 0,
         });
 
-    analytics.total_borrows = analytics
-        .total_borrows
-        .checked_add(amount)
-        .ok_or(BorrowError::Overflow)?;
+    analytics.total_borrows = Amount::new(analytics.total_borrows)
+        .checked_add(Amount::new(amount))?
+        .get();
 
     // Update debt value (add borrow)
-    analytics.debt_value = analytics
-        .debt_value
-        .checked_add(amount)
-        .ok_or(BorrowError::Overflow)?;
+    analytics.debt_value = Amount::new(analytics.debt_value)
+        .checked_add(Amount::new(amount))?
+        .get();
 
     // Recalculate collateralization ratio
     if analytics.debt_value > 0 && analytics.collateral_value > 0 {
-        analytics.collateralization_ratio = analytics
-            .collateral_value
-            .checked_mul(10000)
-            .and_then(|v| v.checked_div(analytics.debt_value))
+        analytics.collateralization_ratio = Amount::new(analytics.collateral_value)
+            .ratio_bps(Amount::new(analytics.debt_value))
+            .map(Bps::get)
             .unwrap_or(0);
     } else {
         analytics.collateralization_ratio = 0; // No debt means no ratio
@@ -517,29 +552,26 @@ fn update_user_analytics_borrow(
 
     analytics.transaction_count = analytics.transaction_count.saturating_add(1);
     analytics.last_activity = timestamp;
+    refresh_tier(env, user, &mut analytics);
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
     Ok(())
 }
 
 /// Update protocol analytics after borrow
 fn update_protocol_analytics_borrow(env: &Env, amount: i128) -> Result<(), BorrowError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, ProtocolAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, ProtocolAnalytics>(env, &analytics_key)
         .unwrap_or(ProtocolAnalytics {
             total_deposits: 0,
             total_borrows: 0,
             total_value_locked: 0,
         });
 
-    analytics.total_borrows = analytics
-        .total_borrows
-        .checked_add(amount)
-        .ok_or(BorrowError::Overflow)?;
+    analytics.total_borrows = Amount::new(analytics.total_borrows)
+        .checked_add(Amount::new(amount))?
+        .get();
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
     Ok(())
 }
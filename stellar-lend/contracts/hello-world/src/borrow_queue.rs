@@ -0,0 +1,316 @@
+//! # Borrow Queue
+//!
+//! Queued cross-asset borrows for markets with a utilization cap.
+//!
+//! The admin can cap a market's utilization with [`set_utilization_cap`]; a
+//! borrow that would push utilization above the cap is rejected with
+//! `UtilizationCapExceeded`. Instead of retrying, a user can queue the borrow
+//! with [`request_borrow`]. Queued requests are filled in order when liquidity
+//! frees up: after deposits, repayments and liquidations in the market, or
+//! when anyone calls [`process_borrow_queue`].
+//!
+//! ## Filling
+//! Each pass looks at up to [`MAX_FILLS_PER_PASS`] requests from the front of
+//! the queue:
+//! - Expired requests are dropped (`expired`).
+//! - A request that still exceeds the cap stops the pass, so later requests
+//!   cannot jump ahead of it.
+//! - A request that fails for any other reason, such as an unhealthy position,
+//!   is dropped (`rejected`).
+//!
+//! Requests are authorized by the user when queued and filled on their behalf
+//! later; health and borrow caps are checked at fill time. Nothing is filled
+//! while the emergency pause is active or borrowing of the asset is paused.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Symbol, Vec};
+
+use crate::constants::{BASIS_POINTS_SCALE, SECONDS_PER_DAY};
+use crate::cross_asset::{
+    borrow_for, get_asset_config_by_address, is_asset_paused, require_admin, AssetKey,
+    CrossAssetError,
+};
+use crate::events::{
+    emit_admin_action, emit_borrow_request_closed, emit_borrow_request_filled,
+    emit_borrow_requested, AdminActionEvent, BorrowRequestClosedEvent, BorrowRequestFilledEvent,
+    BorrowRequestedEvent,
+};
+use crate::risk_management::is_emergency_paused;
+use crate::storage;
+use stellarlend_common::pause::PAUSE_BORROW;
+
+/// Longest time a request may wait in the queue (7 days)
+pub const MAX_BORROW_REQUEST_TTL: u64 = 7 * SECONDS_PER_DAY.get();
+
+/// Maximum number of pending requests per market
+pub const MAX_QUEUE_LENGTH: u32 = 50;
+
+/// Maximum number of requests examined per fill pass
+pub const MAX_FILLS_PER_PASS: u32 = 5;
+
+/// Errors that can occur during borrow queue operations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BorrowQueueError {
+    /// Amount must be greater than zero
+    InvalidAmount = 1,
+    /// The asset is not a registered cross-asset market
+    AssetNotConfigured = 2,
+    /// The asset is not enabled for borrowing
+    AssetDisabled = 3,
+    /// Time to live is zero or longer than [`MAX_BORROW_REQUEST_TTL`]
+    InvalidExpiry = 4,
+    /// The market's queue is full
+    QueueFull = 5,
+    /// No pending request with this id for the user
+    RequestNotFound = 6,
+    /// Caller is not authorized (not admin)
+    NotAuthorized = 7,
+    /// Cap is not in (0%, 100%]
+    InvalidCap = 8,
+}
+
+/// Storage keys for borrow queue data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum BorrowQueueDataKey {
+    /// Utilization cap per market, in basis points: i128
+    UtilizationCap(AssetKey),
+    /// Pending requests per market, oldest first: Vec<BorrowRequest>
+    Queue(AssetKey),
+    /// Id assigned to the next request: u64
+    NextRequestId,
+}
+
+/// A queued borrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowRequest {
+    /// Request id, unique across markets
+    pub id: u64,
+    /// Borrower
+    pub user: Address,
+    /// Amount to borrow
+    pub amount: i128,
+    /// Timestamp after which the request is dropped
+    pub expires_at: u64,
+}
+
+/// Set the utilization cap of a market (admin only)
+///
+/// # Arguments
+/// * `asset` - The market (`None` for native XLM)
+/// * `cap_bps` - Highest utilization a borrow may reach, in basis points
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is not the cross-asset admin
+/// * `InvalidCap` - Cap is not in (0, 10000]
+pub fn set_utilization_cap(
+    env: &Env,
+    asset: Option<Address>,
+    cap_bps: i128,
+) -> Result<(), BorrowQueueError> {
+    let admin = require_admin(env).map_err(|_| BorrowQueueError::NotAuthorized)?;
+    if cap_bps <= 0 || cap_bps > BASIS_POINTS_SCALE.get() {
+        return Err(BorrowQueueError::InvalidCap);
+    }
+    storage::set(
+        env,
+        &BorrowQueueDataKey::UtilizationCap(AssetKey::from_option(asset)),
+        &cap_bps,
+    );
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: admin,
+            action: Symbol::new(env, "set_utilization_cap"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get the utilization cap of a market, if set
+pub fn get_utilization_cap(env: &Env, asset_key: &AssetKey) -> Option<i128> {
+    storage::get(env, &BorrowQueueDataKey::UtilizationCap(asset_key.clone()))
+}
+
+/// Queue a borrow to be filled when the market has room under its cap.
+///
+/// # Arguments
+/// * `user` - The borrower (must authorize)
+/// * `asset` - The market (`None` for native XLM)
+/// * `amount` - Amount to borrow
+/// * `ttl_seconds` - How long the request stays queued
+///
+/// # Returns
+/// The request id.
+///
+/// # Errors
+/// * `InvalidAmount` - Amount is not positive
+/// * `AssetNotConfigured` / `AssetDisabled` - The market cannot be borrowed from
+/// * `InvalidExpiry` - `ttl_seconds` is zero or above [`MAX_BORROW_REQUEST_TTL`]
+/// * `QueueFull` - The market already has [`MAX_QUEUE_LENGTH`] requests
+pub fn request_borrow(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+    ttl_seconds: u64,
+) -> Result<u64, BorrowQueueError> {
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(BorrowQueueError::InvalidAmount);
+    }
+    if ttl_seconds == 0 || ttl_seconds > MAX_BORROW_REQUEST_TTL {
+        return Err(BorrowQueueError::InvalidExpiry);
+    }
+    let config = get_asset_config_by_address(env, asset.clone())
+        .map_err(|_| BorrowQueueError::AssetNotConfigured)?;
+    if !config.can_borrow {
+        return Err(BorrowQueueError::AssetDisabled);
+    }
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut queue = get_queue(env, &asset_key);
+    if queue.len() >= MAX_QUEUE_LENGTH {
+        return Err(BorrowQueueError::QueueFull);
+    }
+
+    let id: u64 = storage::get(env, &BorrowQueueDataKey::NextRequestId).unwrap_or(0);
+    storage::set(env, &BorrowQueueDataKey::NextRequestId, &(id + 1));
+
+    let expires_at = env.ledger().timestamp() + ttl_seconds;
+    queue.push_back(BorrowRequest {
+        id,
+        user: user.clone(),
+        amount,
+        expires_at,
+    });
+    storage::set(env, &BorrowQueueDataKey::Queue(asset_key), &queue);
+
+    emit_borrow_requested(
+        env,
+        BorrowRequestedEvent {
+            id,
+            user,
+            asset,
+            amount,
+            expires_at,
+        },
+    );
+
+    Ok(id)
+}
+
+/// Cancel a pending request.
+///
+/// # Errors
+/// * `RequestNotFound` - The user has no pending request with this id in the market
+pub fn cancel_borrow_request(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    id: u64,
+) -> Result<(), BorrowQueueError> {
+    user.require_auth();
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut queue = get_queue(env, &asset_key);
+    let index = queue
+        .iter()
+        .position(|request| request.id == id && request.user == user)
+        .ok_or(BorrowQueueError::RequestNotFound)? as u32;
+    let request = queue.get(index).unwrap();
+    queue.remove(index);
+    storage::set(env, &BorrowQueueDataKey::Queue(asset_key), &queue);
+
+    close_request(env, asset, request, "cancelled");
+    Ok(())
+}
+
+/// Fill pending requests of a market that now fit under its cap.
+///
+/// Called after operations that lower the market's utilization and available
+/// to anyone as an entrypoint. Never fails: requests that cannot be filled
+/// are left queued or dropped as described in the module docs.
+///
+/// # Returns
+/// The number of requests filled.
+pub fn process_borrow_queue(env: &Env, asset: Option<Address>) -> u32 {
+    let asset_key = AssetKey::from_option(asset.clone());
+    if is_emergency_paused(env) || is_asset_paused(env, &asset_key, PAUSE_BORROW) {
+        return 0;
+    }
+    let mut queue = get_queue(env, &asset_key);
+    if queue.is_empty() {
+        return 0;
+    }
+
+    let now = env.ledger().timestamp();
+    let mut filled = 0;
+    let mut examined = 0;
+    while examined < MAX_FILLS_PER_PASS {
+        let Some(request) = queue.first() else {
+            break;
+        };
+        examined += 1;
+
+        if now > request.expires_at {
+            queue.pop_front();
+            close_request(env, asset.clone(), request, "expired");
+            continue;
+        }
+
+        match borrow_for(env, request.user.clone(), asset.clone(), request.amount) {
+            Ok(_) => {
+                queue.pop_front();
+                filled += 1;
+                emit_borrow_request_filled(
+                    env,
+                    BorrowRequestFilledEvent {
+                        id: request.id,
+                        user: request.user,
+                        asset: asset.clone(),
+                        amount: request.amount,
+                        timestamp: now,
+                    },
+                );
+            }
+            Err(CrossAssetError::UtilizationCapExceeded) => break,
+            Err(_) => {
+                queue.pop_front();
+                close_request(env, asset.clone(), request, "rejected");
+            }
+        }
+    }
+
+    storage::set(env, &BorrowQueueDataKey::Queue(asset_key), &queue);
+    filled
+}
+
+/// Get the pending requests of a market, oldest first
+pub fn get_borrow_queue(env: &Env, asset: Option<Address>) -> Vec<BorrowRequest> {
+    get_queue(env, &AssetKey::from_option(asset))
+}
+
+fn get_queue(env: &Env, asset_key: &AssetKey) -> Vec<BorrowRequest> {
+    storage::get(env, &BorrowQueueDataKey::Queue(asset_key.clone())).unwrap_or(Vec::new(env))
+}
+
+fn close_request(env: &Env, asset: Option<Address>, request: BorrowRequest, reason: &str) {
+    emit_borrow_request_closed(
+        env,
+        BorrowRequestClosedEvent {
+            id: request.id,
+            user: request.user,
+            asset,
+            amount: request.amount,
+            reason: Symbol::new(env, reason),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+}
@@ -0,0 +1,163 @@
+//! # Cash Ledger
+//!
+//! Internal double-entry record of every token movement into and out of the
+//! contract, so accounting discrepancies can be detected on-chain.
+//!
+//! Each transfer is posted twice: to the asset's cash account and, with the
+//! opposite sign, to the account of its [`CashReason`]. The cash balance is
+//! therefore always the sum of the reason accounts' net inflows, and
+//! [`reconcile_cash`] compares it with the contract's actual token balance.
+//!
+//! ## Discrepancies
+//! `discrepancy = token_balance - ledger_balance`:
+//! - Positive: tokens arrived without passing through an entrypoint, such as
+//!   direct transfers, or balances held before the ledger was introduced.
+//! - Negative: tokens left that the protocol did not account for, which
+//!   should never happen and warrants pausing the market.
+//!
+//! Native XLM movements are not token transfers in the core flows and are not
+//! recorded.
+
+use soroban_sdk::{contracttype, token, Address, Env, Map};
+
+use crate::events::{emit_cash_reconciled, CashReconciledEvent};
+use crate::storage;
+
+/// Why tokens moved into or out of the contract
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum CashReason {
+    /// Collateral deposited
+    Deposit,
+    /// Collateral withdrawn
+    Withdraw,
+    /// Borrowed funds disbursed
+    Borrow,
+    /// Debt repaid
+    Repay,
+    /// Debt repaid by a liquidator and collateral released
+    Liquidation,
+    /// Lending pool supply and redemption
+    PoolLiquidity,
+    /// Protocol reserves paid out
+    ReserveWithdrawal,
+    /// Insurance funding and coverage claims
+    Insurance,
+    /// Flash loan principal out, principal and fee back
+    FlashLoan,
+    /// Reward funding and claims
+    Rewards,
+    /// Incentives paid to price reporters from reserves
+    OracleIncentive,
+}
+
+/// Totals moved for one reason
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CashFlow {
+    /// Tokens received by the contract
+    pub inflow: i128,
+    /// Tokens sent by the contract
+    pub outflow: i128,
+}
+
+/// Result of comparing the ledger with the actual token balance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CashReconciliation {
+    /// Balance implied by the recorded movements
+    pub ledger_balance: i128,
+    /// Tokens the contract actually holds
+    pub token_balance: i128,
+    /// `token_balance - ledger_balance`
+    pub discrepancy: i128,
+}
+
+/// Storage keys for cash ledger data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum CashLedgerDataKey {
+    /// Movements per reason for an asset: Map<CashReason, CashFlow>
+    Accounts(Address),
+}
+
+/// Record tokens received by the contract.
+/// Call this after the transfer in has succeeded.
+pub(crate) fn record_inflow(env: &Env, asset: &Address, reason: CashReason, amount: i128) {
+    post(env, asset, reason, amount, 0);
+}
+
+/// Record tokens sent by the contract.
+/// Call this after the transfer out has succeeded.
+pub(crate) fn record_outflow(env: &Env, asset: &Address, reason: CashReason, amount: i128) {
+    post(env, asset, reason, 0, amount);
+}
+
+/// Record tokens sent and received for one reason in a single write.
+/// Call this after both transfers have succeeded.
+pub(crate) fn record_flow(
+    env: &Env,
+    asset: &Address,
+    reason: CashReason,
+    inflow: i128,
+    outflow: i128,
+) {
+    post(env, asset, reason, inflow, outflow);
+}
+
+fn post(env: &Env, asset: &Address, reason: CashReason, inflow: i128, outflow: i128) {
+    if inflow == 0 && outflow == 0 {
+        return;
+    }
+    let key = CashLedgerDataKey::Accounts(asset.clone());
+    let mut accounts: Map<CashReason, CashFlow> = storage::get(env, &key).unwrap_or(Map::new(env));
+    let mut flow = accounts.get(reason).unwrap_or_default();
+    flow.inflow = flow.inflow.saturating_add(inflow);
+    flow.outflow = flow.outflow.saturating_add(outflow);
+    accounts.set(reason, flow);
+    storage::set(env, &key, &accounts);
+}
+
+/// Get the recorded movements of an asset by reason
+pub fn get_cash_ledger(env: &Env, asset: &Address) -> Map<CashReason, CashFlow> {
+    storage::get(env, &CashLedgerDataKey::Accounts(asset.clone())).unwrap_or(Map::new(env))
+}
+
+/// Balance of an asset implied by its recorded movements
+pub fn get_ledger_balance(env: &Env, asset: &Address) -> i128 {
+    get_cash_ledger(env, asset)
+        .values()
+        .iter()
+        .fold(0i128, |balance, flow| {
+            balance
+                .saturating_add(flow.inflow)
+                .saturating_sub(flow.outflow)
+        })
+}
+
+/// Compare an asset's ledger balance with the contract's token balance
+///
+/// Anyone can call this. Emits `cash_reconciled` with the result, so
+/// discrepancies are visible to monitors on-chain.
+pub fn reconcile_cash(env: &Env, asset: Address) -> CashReconciliation {
+    let ledger_balance = get_ledger_balance(env, &asset);
+    let token_balance = token::Client::new(env, &asset).balance(&env.current_contract_address());
+    let reconciliation = CashReconciliation {
+        ledger_balance,
+        token_balance,
+        discrepancy: token_balance.saturating_sub(ledger_balance),
+    };
+
+    emit_cash_reconciled(
+        env,
+        CashReconciledEvent {
+            asset,
+            ledger_balance,
+            token_balance,
+            discrepancy: reconciliation.discrepancy,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    reconciliation
+}
@@ -0,0 +1,132 @@
+//! # Protocol Constants
+//!
+//! Shared units and protocol-wide constants used across all modules.
+//!
+//! Ratios are expressed as [`Bps`] (basis points, 10,000 = 100%) and durations
+//! as [`Seconds`], so a percentage cannot be passed where basis points are
+//! expected without an explicit conversion. Each constant's raw value is
+//! obtained with `.get()`, which is `const` and usable in other constants.
+//!
+//! ## Compile-Time Checks
+//! The relationships the protocol relies on (e.g. the scale of 100%, the
+//! length of a year, the minimum collateral ratio exceeding 100%) are asserted
+//! at compile time at the bottom of this file.
+//!
+//! Values shared with the lending contract (the basis point scale, the length
+//! of a year and the minimum collateral ratio) come from
+//! `stellarlend_common::constants`.
+
+use stellarlend_common::constants as common;
+
+/// A ratio expressed in basis points (1 bp = 0.01%, 10,000 bps = 100%)
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Bps(i128);
+
+impl Bps {
+    /// Wrap a raw basis point value
+    pub const fn new(value: i128) -> Self {
+        Bps(value)
+    }
+
+    /// Raw basis point value
+    pub const fn get(self) -> i128 {
+        self.0
+    }
+
+    /// Whether this value lies within [0%, 100%]
+    pub const fn is_ratio(self) -> bool {
+        self.0 >= 0 && self.0 <= BASIS_POINTS_SCALE.0
+    }
+}
+
+/// A duration in seconds
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Seconds(u64);
+
+impl Seconds {
+    /// Raw number of seconds
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+// ============================================================================
+// Units
+// ============================================================================
+
+/// 100% in basis points
+pub const BASIS_POINTS_SCALE: Bps = Bps(common::BASIS_POINTS);
+
+/// Health factor of exactly 1.0; positions below this can be liquidated
+pub const HEALTH_FACTOR_ONE: Bps = BASIS_POINTS_SCALE;
+
+/// One hour
+pub const SECONDS_PER_HOUR: Seconds = Seconds(60 * 60);
+
+/// One day
+pub const SECONDS_PER_DAY: Seconds = Seconds(24 * SECONDS_PER_HOUR.0);
+
+/// One (non-leap) year, used to annualize interest rates
+pub const SECONDS_PER_YEAR: Seconds = Seconds(365 * SECONDS_PER_DAY.0);
+
+// ============================================================================
+// Protocol defaults
+// ============================================================================
+
+/// Minimum collateral ratio enforced on borrow and withdraw (150%)
+pub const MIN_COLLATERAL_RATIO: Bps = Bps(common::MIN_COLLATERAL_RATIO_BPS);
+
+/// Collateral factor used for assets without an explicit configuration (100%)
+pub const DEFAULT_COLLATERAL_FACTOR: Bps = BASIS_POINTS_SCALE;
+
+/// Maximum age of a cross-asset price before it is considered stale
+pub const MAX_PRICE_AGE: Seconds = SECONDS_PER_HOUR;
+
+/// Health factor below which positions are warned of liquidation risk (1.1)
+pub const LIQUIDATION_WARNING_HEALTH_FACTOR: Bps = Bps(11_000);
+
+/// Longest stretch of unaccrued interest a health check estimates per position
+pub const MAX_INTEREST_STALENESS: Seconds = Seconds(30 * SECONDS_PER_DAY.0);
+
+/// Maximum number of entries retained in the analytics activity log
+pub const MAX_ACTIVITY_LOG_SIZE: u32 = 10_000;
+
+/// Maximum number of entries retained in each user's activity log
+pub const MAX_USER_ACTIVITY_LOG_SIZE: u32 = 200;
+
+/// Default time for an idle user's activity score to halve (90 days)
+pub const DEFAULT_ACTIVITY_HALF_LIFE: Seconds = Seconds(90 * SECONDS_PER_DAY.0);
+
+// ============================================================================
+// Storage TTLs (in ledgers)
+// ============================================================================
+
+/// Approximate number of ledgers closed per day (5-second ledgers)
+pub const LEDGERS_PER_DAY: u32 = 17_280;
+
+/// Instance storage (global configuration) is extended to 30 days of life...
+pub const INSTANCE_BUMP_AMOUNT: u32 = 30 * LEDGERS_PER_DAY;
+
+/// ...whenever fewer than 29 days remain
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - LEDGERS_PER_DAY;
+
+/// Persistent entries are extended to 90 days of life on access, unless the
+/// admin sets another TTL policy...
+pub const USER_DATA_BUMP_AMOUNT: u32 = 90 * LEDGERS_PER_DAY;
+
+/// ...whenever fewer than 83 days remain
+pub const USER_DATA_LIFETIME_THRESHOLD: u32 = USER_DATA_BUMP_AMOUNT - 7 * LEDGERS_PER_DAY;
+
+// ============================================================================
+// Compile-time checks
+// ============================================================================
+
+const _: () = assert!(BASIS_POINTS_SCALE.get() == 10_000);
+const _: () = assert!(SECONDS_PER_YEAR.get() == 31_536_000);
+const _: () = assert!(SECONDS_PER_YEAR.get() == common::SECONDS_PER_YEAR);
+const _: () = assert!(MIN_COLLATERAL_RATIO.get() > BASIS_POINTS_SCALE.get());
+const _: () = assert!(DEFAULT_COLLATERAL_FACTOR.is_ratio());
+const _: () = assert!(MAX_PRICE_AGE.get() > 0);
+const _: () = assert!(LIQUIDATION_WARNING_HEALTH_FACTOR.get() > HEALTH_FACTOR_ONE.get());
+const _: () = assert!(INSTANCE_LIFETIME_THRESHOLD < INSTANCE_BUMP_AMOUNT);
+const _: () = assert!(USER_DATA_LIFETIME_THRESHOLD < USER_DATA_BUMP_AMOUNT);
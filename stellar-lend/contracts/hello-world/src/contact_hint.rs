@@ -0,0 +1,149 @@
+//! # Liquidation Contact Hints
+//!
+//! Borrowers may store an opaque contact hint, such as the hash of an
+//! encrypted contact blob held by a notification service, together with a
+//! preference for when it is surfaced. Liquidation warning events carry the
+//! hint, so a service watching events can reach an at-risk borrower while
+//! personal data stays off-chain. The contract never interprets the hint.
+//!
+//! ## Warnings
+//! A `liquidation_warning` event is emitted when a cross-asset borrow or
+//! withdrawal leaves a position with a health factor below
+//! [`LIQUIDATION_WARNING_HEALTH_FACTOR`], and when anyone calls
+//! [`warn_liquidation_risk`] for such a position, e.g. a keeper after a price
+//! drop. The event includes the hint only if the borrower's
+//! [`NotificationPreference`] covers the position's state.
+
+use soroban_sdk::{contracterror, contracttype, Address, Bytes, Env};
+
+use crate::constants::{HEALTH_FACTOR_ONE, LIQUIDATION_WARNING_HEALTH_FACTOR};
+use crate::cross_asset::{get_user_position_summary, CrossAssetError};
+use crate::events::{emit_liquidation_warning, LiquidationWarningEvent};
+use crate::storage;
+
+/// Maximum length of a contact hint, in bytes
+pub const MAX_CONTACT_HINT_LEN: u32 = 128;
+
+/// Storage keys for contact hints
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum ContactHintDataKey {
+    /// A borrower's contact hint: ContactHint
+    Hint(Address),
+}
+
+/// Errors that can occur while storing a contact hint
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ContactHintError {
+    /// The hint is empty or longer than [`MAX_CONTACT_HINT_LEN`]
+    InvalidHint = 1,
+}
+
+/// When a borrower's contact hint is included in warnings
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NotificationPreference {
+    /// Never; the hint is stored but not surfaced
+    Off,
+    /// Whenever the position is warned
+    AtRisk,
+    /// Only once the position can be liquidated
+    Liquidatable,
+}
+
+/// A borrower's contact hint
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContactHint {
+    /// Opaque bytes, e.g. the hash of an encrypted contact blob
+    pub hint: Bytes,
+    pub preference: NotificationPreference,
+    /// Ledger timestamp of the last update
+    pub updated_at: u64,
+}
+
+/// Store or replace the caller's contact hint
+///
+/// # Errors
+/// * `InvalidHint` - The hint is empty or longer than [`MAX_CONTACT_HINT_LEN`]
+pub fn set_contact_hint(
+    env: &Env,
+    user: Address,
+    hint: Bytes,
+    preference: NotificationPreference,
+) -> Result<(), ContactHintError> {
+    user.require_auth();
+    if hint.is_empty() || hint.len() > MAX_CONTACT_HINT_LEN {
+        return Err(ContactHintError::InvalidHint);
+    }
+
+    storage::set(
+        env,
+        &ContactHintDataKey::Hint(user),
+        &ContactHint {
+            hint,
+            preference,
+            updated_at: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Remove the caller's contact hint
+pub fn clear_contact_hint(env: &Env, user: Address) {
+    user.require_auth();
+    storage::remove(env, &ContactHintDataKey::Hint(user));
+}
+
+/// Get a borrower's contact hint, if any
+pub fn get_contact_hint(env: &Env, user: &Address) -> Option<ContactHint> {
+    storage::get(env, &ContactHintDataKey::Hint(user.clone()))
+}
+
+/// Emit a warning if `health_factor` is below
+/// [`LIQUIDATION_WARNING_HEALTH_FACTOR`]
+///
+/// # Returns
+/// Whether a warning was emitted.
+pub(crate) fn warn_if_at_risk(env: &Env, user: &Address, health_factor: i128) -> bool {
+    if health_factor >= LIQUIDATION_WARNING_HEALTH_FACTOR.get() {
+        return false;
+    }
+
+    let liquidatable = health_factor < HEALTH_FACTOR_ONE.get();
+    let contact_hint = match get_contact_hint(env, user) {
+        Some(contact) => match contact.preference {
+            NotificationPreference::AtRisk => contact.hint,
+            NotificationPreference::Liquidatable if liquidatable => contact.hint,
+            _ => Bytes::new(env),
+        },
+        None => Bytes::new(env),
+    };
+    emit_liquidation_warning(
+        env,
+        LiquidationWarningEvent {
+            user: user.clone(),
+            health_factor,
+            liquidatable,
+            contact_hint,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    true
+}
+
+/// Warn a cross-asset position of liquidation risk, if its health factor is
+/// below [`LIQUIDATION_WARNING_HEALTH_FACTOR`]
+///
+/// # Returns
+/// Whether a warning was emitted.
+///
+/// # Errors
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn warn_liquidation_risk(env: &Env, user: Address) -> Result<bool, CrossAssetError> {
+    let summary = get_user_position_summary(env, &user)?;
+    Ok(warn_if_at_risk(env, &user, summary.health_factor))
+}
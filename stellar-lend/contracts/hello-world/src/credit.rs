@@ -0,0 +1,228 @@
+//! # Credit History
+//!
+//! Per-user repayment record, exported as a compact [`CreditSummary`] for
+//! third parties the user approves (for example, undercollateralized lending
+//! experiments built on top of the protocol).
+//!
+//! ## Loans
+//! A loan opens when a user borrows with no outstanding debt in that book (the
+//! core position, or one cross-asset market) and closes when that debt
+//! returns to zero by repayment or liquidation.
+//!
+//! - A loan is **repaid on time** when it is repaid in full without the user
+//!   being liquidated while it was open.
+//! - The **streak** counts loans repaid on time in a row; a liquidation
+//!   resets it.
+//!
+//! ## Access
+//! A user can read their own summary and grant read access to up to
+//! [`MAX_CREDIT_GRANTS`] addresses with [`grant_credit_access`]. The grant
+//! list gates the contract API only; the underlying ledger data is public.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Vec};
+
+use crate::events::{
+    emit_credit_access_granted, emit_credit_access_revoked, CreditAccessGrantedEvent,
+    CreditAccessRevokedEvent,
+};
+use crate::storage;
+
+/// Maximum number of addresses a user can grant access to
+pub const MAX_CREDIT_GRANTS: u32 = 20;
+
+/// Errors that can occur during credit history operations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CreditError {
+    /// Caller is neither the user nor an approved grantee
+    NotAuthorized = 1,
+    /// The user already has [`MAX_CREDIT_GRANTS`] grantees
+    TooManyGrants = 2,
+    /// The grantee already has access
+    AlreadyGranted = 3,
+    /// The grantee does not have access
+    GrantNotFound = 4,
+    /// A user cannot grant access to themselves
+    InvalidGrantee = 5,
+}
+
+/// Storage keys for credit history data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum CreditDataKey {
+    /// Repayment record per user: CreditRecord
+    Record(Address),
+    /// Addresses approved to read a user's summary: Vec<Address>
+    Grants(Address),
+}
+
+/// A user's repayment record, exported to approved third parties
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CreditSummary {
+    /// Loans opened
+    pub loans_opened: u32,
+    /// Loans closed by full repayment
+    pub loans_repaid: u32,
+    /// Loans repaid in full without a liquidation while open
+    pub loans_repaid_on_time: u32,
+    /// Liquidations of the user's positions
+    pub liquidations: u32,
+    /// Loans repaid on time since the last liquidation
+    pub current_streak: u32,
+    /// Longest run of loans repaid on time
+    pub longest_streak: u32,
+    /// Timestamp of the first loan (0 if none)
+    pub first_loan_at: u64,
+    /// Timestamp of the last recorded event (0 if none)
+    pub last_activity_at: u64,
+}
+
+/// Stored record: the summary plus loans still open
+#[contracttype]
+#[derive(Clone, Debug, Default)]
+pub struct CreditRecord {
+    pub summary: CreditSummary,
+    /// Loans currently open
+    pub open_loans: u32,
+    /// Open loans that were open during a liquidation
+    pub liquidated_open_loans: u32,
+}
+
+/// Record a user opening a loan
+pub(crate) fn record_loan_opened(env: &Env, user: &Address) {
+    let now = env.ledger().timestamp();
+    let mut record = get_record(env, user);
+    record.summary.loans_opened = record.summary.loans_opened.saturating_add(1);
+    if record.summary.first_loan_at == 0 {
+        record.summary.first_loan_at = now;
+    }
+    record.open_loans = record.open_loans.saturating_add(1);
+    save_record(env, user, record, now);
+}
+
+/// Record a user repaying a loan in full
+pub(crate) fn record_loan_repaid(env: &Env, user: &Address) {
+    let mut record = get_record(env, user);
+    let summary = &mut record.summary;
+    summary.loans_repaid = summary.loans_repaid.saturating_add(1);
+    record.open_loans = record.open_loans.saturating_sub(1);
+    if record.liquidated_open_loans > 0 {
+        record.liquidated_open_loans -= 1;
+    } else {
+        summary.loans_repaid_on_time = summary.loans_repaid_on_time.saturating_add(1);
+        summary.current_streak = summary.current_streak.saturating_add(1);
+        summary.longest_streak = summary.longest_streak.max(summary.current_streak);
+    }
+    save_record(env, user, record, env.ledger().timestamp());
+}
+
+/// Record a liquidation of a user's position
+///
+/// Every loan open at the time loses its on-time status. `loan_closed` is
+/// true when the liquidation repaid the liquidated debt in full.
+pub(crate) fn record_liquidation(env: &Env, user: &Address, loan_closed: bool) {
+    let mut record = get_record(env, user);
+    record.summary.liquidations = record.summary.liquidations.saturating_add(1);
+    record.summary.current_streak = 0;
+    record.liquidated_open_loans = record.open_loans;
+    if loan_closed {
+        record.open_loans = record.open_loans.saturating_sub(1);
+        record.liquidated_open_loans = record.liquidated_open_loans.saturating_sub(1);
+    }
+    save_record(env, user, record, env.ledger().timestamp());
+}
+
+/// Get a user's credit summary.
+///
+/// # Arguments
+/// * `caller` - The user or an approved grantee (must authorize)
+/// * `user` - Whose summary to read
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is not the user and has no grant
+pub fn get_credit_summary(
+    env: &Env,
+    caller: Address,
+    user: Address,
+) -> Result<CreditSummary, CreditError> {
+    caller.require_auth();
+    if caller != user && !get_credit_grants(env, &user).contains(&caller) {
+        return Err(CreditError::NotAuthorized);
+    }
+    Ok(get_record(env, &user).summary)
+}
+
+/// Allow `grantee` to read the user's credit summary.
+///
+/// # Errors
+/// * `InvalidGrantee` - Grantee is the user
+/// * `AlreadyGranted` - Grantee already has access
+/// * `TooManyGrants` - The user already has [`MAX_CREDIT_GRANTS`] grantees
+pub fn grant_credit_access(env: &Env, user: Address, grantee: Address) -> Result<(), CreditError> {
+    user.require_auth();
+
+    if grantee == user {
+        return Err(CreditError::InvalidGrantee);
+    }
+    let mut grants = get_credit_grants(env, &user);
+    if grants.contains(&grantee) {
+        return Err(CreditError::AlreadyGranted);
+    }
+    if grants.len() >= MAX_CREDIT_GRANTS {
+        return Err(CreditError::TooManyGrants);
+    }
+    grants.push_back(grantee.clone());
+    storage::set(env, &CreditDataKey::Grants(user.clone()), &grants);
+
+    emit_credit_access_granted(
+        env,
+        CreditAccessGrantedEvent {
+            user,
+            grantee,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Withdraw a grantee's access to the user's credit summary.
+///
+/// # Errors
+/// * `GrantNotFound` - Grantee does not have access
+pub fn revoke_credit_access(env: &Env, user: Address, grantee: Address) -> Result<(), CreditError> {
+    user.require_auth();
+
+    let mut grants = get_credit_grants(env, &user);
+    let index = grants
+        .first_index_of(&grantee)
+        .ok_or(CreditError::GrantNotFound)?;
+    grants.remove(index);
+    storage::set(env, &CreditDataKey::Grants(user.clone()), &grants);
+
+    emit_credit_access_revoked(
+        env,
+        CreditAccessRevokedEvent {
+            user,
+            grantee,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get the addresses approved to read a user's credit summary
+pub fn get_credit_grants(env: &Env, user: &Address) -> Vec<Address> {
+    storage::get(env, &CreditDataKey::Grants(user.clone())).unwrap_or(Vec::new(env))
+}
+
+fn get_record(env: &Env, user: &Address) -> CreditRecord {
+    storage::get(env, &CreditDataKey::Record(user.clone())).unwrap_or_default()
+}
+
+fn save_record(env: &Env, user: &Address, mut record: CreditRecord, now: u64) {
+    record.summary.last_activity_at = now;
+    storage::set(env, &CreditDataKey::Record(user.clone()), &record);
+}
@@ -8,17 +8,151 @@
 //! - Oracle-based price feeds for cross-asset value calculation
 //! - Unified position summary with health factor across all assets
 //! - Supply and borrow cap enforcement per asset
+//! - Savings mode: per-position opt-out from collateral usage
+//! - Liquidation with liquidator-chosen, optionally split, collateral seizure
+//! - Per-asset two-slope borrow rates (see `rate_strategy`)
+//! - Per-asset utilization caps with a queue for blocked borrows (see `borrow_queue`)
+//!
+//! ## Collateral Factor Ramps
+//! [`ramp_collateral_factor`] phases a collateral factor change in linearly
+//! over a number of days instead of applying it at once. The effective factor
+//! is interpolated whenever a configuration is read, so positions drift toward
+//! the new factor rather than becoming liquidatable in a single step.
+//!
+//! ## Per-Asset Pauses
+//! [`set_asset_pause`] switches off one operation for one asset, using the
+//! operation names of the global pause switches (`pause_deposit`,
+//! `pause_withdraw`, `pause_borrow`, `pause_repay`, `pause_liquidate`). This
+//! can, for example, stop borrowing of a volatile asset while its deposits
+//! stay open. Liquidations are paused by their debt asset. These switches
+//! are independent of the risk module's global pause switches, which govern
+//! core positions only. [`is_asset_operation_paused`] answers whether an
+//! operation is currently blocked for an asset, for any reason: the
+//! emergency pause, delisting or the asset's own switch.
+//!
+//! ## Isolation Mode
+//! [`set_isolation_mode`] marks a risky asset as isolated with a debt
+//! ceiling. A position using an isolated asset as collateral may not use any
+//! other asset as collateral, and its total debt value may not exceed the
+//! asset's ceiling. Isolating an asset does not affect positions already
+//! mixing it with other collateral until they deposit or borrow again.
+//!
+//! ## Asset Categories
+//! [`set_asset_metadata`] tags a listed asset with its issuer and an
+//! [`AssetCategory`] such as stablecoin or liquid staking token.
+//! [`get_category_exposure`] sums supply and borrow value per category at
+//! oracle prices, counting untagged assets as `Other`, and is reported in
+//! the analytics `ProtocolMetrics` for category-level concentration limits.
+//!
+//! ## Category Debt Ceilings
+//! [`set_category_debt_ceiling`] caps the debt backed by collateral of a
+//! category as a share of the value supplied across all markets. A
+//! position's debt value is attributed to its collateral categories pro rata
+//! to collateral value. Attributions are refreshed whenever a position
+//! changes, and the category totals adjusted by the difference, so totals
+//! are maintained incrementally rather than by scanning positions. A borrow
+//! that raises a category's attributed debt above its ceiling is rejected;
+//! debt growing through interest or price moves is not. Attributions are
+//! only maintained while some ceiling is set, and a position counts from its
+//! first change after that.
+//!
+//! ## Soft Liquidation
+//! [`set_soft_liquidation`] spreads a collateral asset over bands of health
+//! factor above 1.0. As a position's health factor, computed as if nothing
+//! were converted, falls through the bands, keepers calling
+//! [`soft_liquidate`] sell an equal share of its collateral per band entered
+//! to repay its debt, at the oracle price less a fee. When the health factor
+//! recovers, keepers sell collateral back for new debt at the oracle price
+//! plus the fee. Positions therefore deleverage gradually instead of being
+//! liquidated in one step; hard liquidation still applies below 1.0.
+//!
+//! ## Minimum Debt
+//! [`set_min_borrow_amount`] sets the smallest debt a position may carry in
+//! an asset, since smaller positions cost more to liquidate than they pay.
+//! Borrows must leave at least that much debt, and repayments must clear the
+//! debt or leave at least that much. Liquidations may leave less. The core
+//! borrow and repay paths apply the same threshold to the core position.
+//!
+//! ## Collateral Swap Repayment
+//! [`repay_with_collateral_asset`] repays debt with collateral of another
+//! asset, swapped into the debt asset through the AMM set by
+//! [`set_swap_adapter`], so users need not source the debt token. The swap
+//! must return at least the oracle value of the collateral less the
+//! adapter's slippage bound. Any surplus over the debt is supplied to the
+//! position in the debt asset.
 //!
 //! ## Health Factor
 //! Computed as `weighted_collateral_value / weighted_debt_value * 10000`.
 //! A health factor below 10,000 (1.0x) makes the position liquidatable.
 //!
+//! ## Lazy Accrual
+//! An operation accrues interest only on the markets it touches. Health checks
+//! read every market's last stored debt and add an estimate of the interest
+//! accrued since, at the market's current rate, over at most
+//! [`MAX_INTEREST_STALENESS`] of elapsed time. The estimate is never written.
+//!
+//! Each market also keeps a borrow index ([`get_borrow_index`]), the growth
+//! of one unit of debt borrowed when the market was first touched, advanced
+//! at the market's rate whenever an operation accrues interest on it. Every
+//! accrual emits an `InterestAccruedEvent` with the interest and new index.
+//!
+//! ## Prices
+//! Each market's price is kept in its `AssetConfig`, in [`PRICE_DECIMALS`],
+//! and set with [`update_asset_price`] or, once the oracle aggregates the
+//! asset's sources, from their published median. An active emergency
+//! override from the
+//! oracle (`oracle::set_emergency_price`) takes precedence over it wherever
+//! positions are valued, as it does over every oracle feed.
+//!
 //! ## Invariants
+//! - User operations are rejected with `ProtocolPaused` while the emergency pause is active.
 //! - Withdrawals and borrows are rejected if they would lower health factor below 1.0.
 //! - Prices must not be stale (> 1 hour old) for position calculations.
 
 #![allow(dead_code)]
-use soroban_sdk::{contracterror, contracttype, symbol_short, Address, Env, Map, Symbol, Vec};
+use soroban_sdk::{contracterror, contracttype, vec, Address, Env, IntoVal, Map, Symbol, Vec};
+
+use crate::batch::is_health_check_deferred;
+use crate::borrow_queue::{get_utilization_cap, process_borrow_queue};
+use crate::constants::{
+    Bps, BASIS_POINTS_SCALE, HEALTH_FACTOR_ONE, MAX_INTEREST_STALENESS, MAX_PRICE_AGE,
+    SECONDS_PER_DAY,
+};
+use crate::contact_hint::warn_if_at_risk;
+use crate::credit::{record_liquidation, record_loan_opened, record_loan_repaid};
+use crate::events::{
+    emit_admin_action, emit_asset_config_updated, emit_asset_delisted, emit_asset_metadata_updated,
+    emit_asset_pause_updated, emit_bad_debt_recorded, emit_borrow, emit_category_ceiling_reached,
+    emit_collateral_swap_repay, emit_collateral_toggled, emit_deposit, emit_interest_accrued,
+    emit_interest_mode_updated, emit_isolation_mode_updated, emit_liquidation,
+    emit_liquidation_bands_updated, emit_min_borrow_amount_updated, emit_repay,
+    emit_soft_liquidation, emit_withdrawal, AdminActionEvent, AssetConfigUpdatedEvent,
+    AssetDelistedEvent, AssetMetadataUpdatedEvent, AssetPauseUpdatedEvent, BadDebtRecordedEvent,
+    BorrowEvent, CategoryCeilingReachedEvent, CollateralSwapRepayEvent, CollateralToggledEvent,
+    DepositEvent, InterestAccruedEvent, InterestModeUpdatedEvent, IsolationModeUpdatedEvent,
+    LiquidationBandsUpdatedEvent, LiquidationEvent, MinBorrowAmountUpdatedEvent, RepayEvent,
+    SoftLiquidationEvent, WithdrawalEvent,
+};
+use crate::interest_history::{record_interest, InterestCheckpoint};
+use crate::interest_rate::{calculate_accrued_interest, InterestRateError};
+use crate::lending_pool::{get_liquidity_index, get_reserves, record_bad_debt};
+use crate::liquidation_history::{log_liquidation, LiquidationRecord};
+use crate::lp_collateral::get_lp_pool;
+use crate::math::{Amount, MathError};
+use crate::oracle;
+use crate::rate_strategy::{get_asset_borrow_rate, get_asset_supply_rate};
+use crate::rewards;
+use crate::risk_management::{
+    get_close_factor, get_health_factor_incentive, get_liquidation_target, get_recovery_haircut,
+    is_emergency_paused,
+};
+use crate::roles::{has_role, Role};
+use crate::storage;
+use crate::token_interface::{has_required_capabilities, probe_token};
+pub use stellarlend_common::interest::{BorrowIndex, BORROW_INDEX_ONE};
+use stellarlend_common::pause::{
+    is_switch_set, PAUSE_BORROW, PAUSE_DEPOSIT, PAUSE_LIQUIDATE, PAUSE_REPAY, PAUSE_WITHDRAW,
+};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -45,6 +179,27 @@ pub struct AssetConfig {
     pub price_updated_at: u64,
 }
 
+/// Selected fields of an asset configuration to change; `None` keeps the
+/// current value
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetConfigUpdate {
+    /// Asset to update (`None` for native XLM)
+    pub asset: Option<Address>,
+    /// New collateral factor in basis points
+    pub collateral_factor: Option<i128>,
+    /// New borrow factor in basis points
+    pub borrow_factor: Option<i128>,
+    /// New supply cap
+    pub max_supply: Option<i128>,
+    /// New borrow cap
+    pub max_borrow: Option<i128>,
+    /// Enable or disable as collateral
+    pub can_collateralize: Option<bool>,
+    /// Enable or disable borrowing
+    pub can_borrow: Option<bool>,
+}
+
 /// User position across a single asset
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -57,6 +212,320 @@ pub struct AssetPosition {
     pub accrued_interest: i128,
     /// Last update timestamp
     pub last_updated: u64,
+    /// Whether this deposit counts toward borrowing power. When `false` the
+    /// balance is held in savings mode and excluded from the health factor.
+    pub use_as_collateral: bool,
+    /// Start of the current uninterrupted supply period (0 when nothing is supplied)
+    pub supplied_since: u64,
+}
+
+/// Where a market's borrower interest accrues
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InterestMode {
+    /// Into the position's `accrued_interest`, never earning interest itself
+    Simple,
+    /// Into the position's `debt_principal`, compounding whenever the
+    /// position accrues
+    Compounding,
+}
+
+/// Reserve-factor rebate earned after a supply tenure milestone
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TenureRebateTier {
+    /// Continuous supply time required, in seconds
+    pub min_tenure_seconds: u64,
+    /// Reduction of the asset's reserve factor, in basis points
+    pub rebate_bps: i128,
+}
+
+/// A user's supply position in one asset, including tenure and rebate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SupplyStatement {
+    /// Supplied balance in the asset's native units
+    pub supplied: i128,
+    /// Start of the current uninterrupted supply period (0 if none)
+    pub supplied_since: u64,
+    /// Seconds of continuous supply so far
+    pub tenure_seconds: u64,
+    /// Asset reserve factor before rebate (basis points)
+    pub reserve_factor: i128,
+    /// Rebate from the highest tenure tier reached (basis points)
+    pub rebate_bps: i128,
+    /// Reserve factor applied to this supplier's yield (basis points)
+    pub effective_reserve_factor: i128,
+}
+
+/// Outcome of a cross-asset liquidation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CrossAssetLiquidation {
+    /// Debt repaid on the borrower's behalf, in the debt asset's native units
+    pub debt_repaid: i128,
+    /// `(collateral_asset, amount_seized)` per seizure, in request order
+    pub seized: Vec<(Option<Address>, i128)>,
+    /// Borrower's health factor after the liquidation
+    pub health_factor: i128,
+}
+
+/// Soft liquidation bands of a collateral asset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SoftLiquidationConfig {
+    /// Number of bands the collateral is spread across
+    pub band_count: u32,
+    /// Health factor range of each band, in basis points; the bands span
+    /// from 1.0 up to `1.0 + band_count * band_width_bps`
+    pub band_width_bps: i128,
+    /// Discount on collateral sold, and premium on collateral bought back, in
+    /// basis points
+    pub fee_bps: i128,
+}
+
+/// Collateral of one position converted by soft liquidation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SoftLiquidationState {
+    /// Debt asset the collateral is converted toward
+    pub debt_asset: AssetKey,
+    /// Collateral sold, in the collateral asset's native units
+    pub converted: i128,
+    /// Debt repaid by the sales still outstanding, in the debt asset's native
+    /// units
+    pub proceeds: i128,
+}
+
+/// Outcome of a soft liquidation step
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SoftLiquidation {
+    /// Collateral sold to the keeper; negative when bought back
+    pub collateral_converted: i128,
+    /// Debt repaid by the sale; negative when debt was added to buy back
+    pub debt_repaid: i128,
+    /// Bands converted afterwards
+    pub bands_converted: u32,
+    /// Position's health factor afterwards
+    pub health_factor: i128,
+}
+
+/// Counterparty class of an asset
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssetCategory {
+    Stablecoin,
+    /// Liquid staking tokens
+    LiquidStaking,
+    /// Tokenized real-world assets
+    RealWorldAsset,
+    /// Any other asset, including untagged ones
+    Other,
+}
+
+/// Issuer and category tags of a listed asset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetMetadata {
+    pub category: AssetCategory,
+    /// Issuing account, if known
+    pub issuer: Option<Address>,
+}
+
+/// Protocol exposure to one asset category, at oracle prices
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryExposure {
+    /// Listed assets in the category
+    pub assets: u32,
+    /// Value supplied across the category's markets
+    pub supplied_value: i128,
+    /// Value borrowed across the category's markets
+    pub borrowed_value: i128,
+    /// Debt value attributed to collateral of the category; tracked only
+    /// while a category debt ceiling is set
+    pub backed_debt_value: i128,
+}
+
+/// AMM through which debt is repaid with other collateral
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapAdapter {
+    /// AMM contract executing the swaps
+    pub amm: Address,
+    /// Protocol the AMM routes swaps through
+    pub protocol: Address,
+    /// Largest shortfall of a swap below the oracle price, in basis points
+    pub max_slippage_bps: i128,
+}
+
+/// Swap request in the AMM contract's `SwapParams` layout
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AmmSwapParams {
+    pub protocol: Address,
+    pub token_in: Option<Address>,
+    pub token_out: Option<Address>,
+    pub amount_in: i128,
+    pub min_amount_out: i128,
+    pub slippage_tolerance: i128,
+    pub deadline: u64,
+}
+
+/// Outcome of a repayment through a collateral swap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapRepayment {
+    /// Collateral swapped, in the pay asset's native units
+    pub amount_paid: i128,
+    /// Debt asset received from the swap
+    pub amount_received: i128,
+    /// Debt repaid; the rest of the amount received is supplied as collateral
+    pub debt_repaid: i128,
+    /// Position's health factor afterwards
+    pub health_factor: i128,
+}
+
+/// Last interaction timestamps for one market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketActivity {
+    /// Last deposit, withdrawal, borrow, repayment or liquidation
+    pub last_interaction: u64,
+    /// Last borrow
+    pub last_borrow: u64,
+}
+
+/// Borrows from one market within a single ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LedgerBorrows {
+    /// Ledger sequence the totals apply to
+    pub ledger: u32,
+    /// Available liquidity at the ledger's first borrow
+    pub available: i128,
+    /// Amount borrowed so far in the ledger
+    pub borrowed: i128,
+}
+
+/// A collateral factor change phased in linearly
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralFactorRamp {
+    /// Effective collateral factor when the ramp started, in basis points
+    pub start_factor: i128,
+    /// Collateral factor once the ramp ends, in basis points
+    pub target_factor: i128,
+    /// Timestamp the ramp started
+    pub start_time: u64,
+    /// Timestamp the target factor takes full effect
+    pub end_time: u64,
+}
+
+impl CollateralFactorRamp {
+    /// Collateral factor in effect at `now`
+    fn factor_at(&self, now: u64) -> i128 {
+        if now >= self.end_time {
+            return self.target_factor;
+        }
+        let elapsed = now.saturating_sub(self.start_time) as i128;
+        let duration = (self.end_time - self.start_time) as i128;
+        self.start_factor + (self.target_factor - self.start_factor) * elapsed / duration
+    }
+}
+
+/// Activity summary for one market, used to identify markets to freeze or delist
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketSummary {
+    /// The market's asset
+    pub asset: AssetKey,
+    /// Total supplied in the asset's native units
+    pub total_supply: i128,
+    /// Total borrowed in the asset's native units
+    pub total_borrow: i128,
+    /// Last interaction of any kind (registration if none since)
+    pub last_interaction: u64,
+    /// Last borrow (registration if none since)
+    pub last_borrow: u64,
+    /// Seconds since the last interaction
+    pub idle_seconds: u64,
+    /// Seconds since the last borrow
+    pub borrow_idle_seconds: u64,
+}
+
+/// Everything a frontend shows for one market, from [`get_reserve_data`]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveData {
+    /// The market's asset
+    pub asset: AssetKey,
+    /// Total supplied in the asset's native units
+    pub total_supplied: i128,
+    /// Total borrowed in the asset's native units
+    pub total_borrowed: i128,
+    /// Borrowed share of the supply, in basis points
+    pub utilization: i128,
+    /// Annual rate suppliers earn, in basis points
+    pub supply_apy: i128,
+    /// Annual rate borrowers pay, in basis points
+    pub borrow_apr: i128,
+    /// Supply cap (0 = unlimited)
+    pub supply_cap: i128,
+    /// Borrow cap (0 = unlimited)
+    pub borrow_cap: i128,
+    /// Collateral factor in basis points, with any ramp applied
+    pub collateral_factor: i128,
+    /// Weight of the collateral in the liquidation health factor, in basis
+    /// points. Positions are liquidated at the collateral factor.
+    pub liquidation_threshold: i128,
+    /// Deposits are paused for the asset or protocol-wide
+    pub deposit_paused: bool,
+    /// Withdrawals are paused for the asset or protocol-wide
+    pub withdraw_paused: bool,
+    /// Borrows are paused for the asset or protocol-wide
+    pub borrow_paused: bool,
+    /// Repayments are paused for the asset or protocol-wide
+    pub repay_paused: bool,
+    /// Liquidations repaying the asset are paused for the asset or protocol-wide
+    pub liquidate_paused: bool,
+    /// The asset is delisted: deposits and borrows are frozen for good
+    pub delisted: bool,
+    /// Protocol reserves held in the market's lending pool
+    pub reserves: i128,
+}
+
+/// Protocol-wide result of a uniform collateral price shock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolStressResult {
+    /// Collateral price shock in basis points (e.g., -3000 = 30% drop)
+    pub shock_bps: i128,
+    /// Number of users evaluated so far
+    pub users_scanned: u32,
+    /// Total debt value of positions that would become liquidatable
+    pub liquidatable_debt: i128,
+    /// Debt value not covered by collateral value after the shock
+    pub projected_bad_debt: i128,
+    /// Registry index to resume from, `None` once the scan is complete
+    pub next_cursor: Option<u32>,
+    /// Timestamp of the last page processed
+    pub computed_at: u64,
+}
+
+/// A borrower whose health factor is below a scan threshold
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionHealth {
+    /// Borrower address
+    pub user: Address,
+    /// Current health factor (scaled by 10000)
+    pub health_factor: i128,
+    /// Total collateral value in USD (7 decimals)
+    pub total_collateral_value: i128,
+    /// Total debt value in USD (7 decimals)
+    pub total_debt_value: i128,
 }
 
 /// Unified user position summary across all assets
@@ -88,7 +557,8 @@ pub enum AssetKey {
 
 /// Errors that can occur during cross-asset lending operations.
 #[contracterror]
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum CrossAssetError {
     /// The specified asset has no configuration registered
     AssetNotConfigured = 1,
@@ -110,15 +580,191 @@ pub enum CrossAssetError {
     PriceStale = 9,
     /// Caller is not authorized (not admin)
     NotAuthorized = 10,
+    /// Amount must be greater than zero
+    InvalidAmount = 11,
+    /// Position has a health factor of at least 1.0
+    NotLiquidatable = 12,
+    /// Repayment exceeds the close factor share of the borrower's debt
+    ExceedsCloseFactor = 13,
+    /// Arithmetic overflow while accruing interest
+    Overflow = 14,
+    /// Borrow would push the market's utilization above its cap
+    UtilizationCapExceeded = 15,
+    /// The asset lacks a required token interface function
+    UnsupportedToken = 16,
+    /// Borrow exceeds the market's per-ledger share of available liquidity
+    BorrowSizeExceeded = 17,
+    /// The protocol-wide emergency pause is active
+    ProtocolPaused = 18,
+    /// The operation is paused for this asset
+    AssetPaused = 19,
+    /// The asset is delisted and takes no new deposits or borrows
+    AssetDelisted = 20,
+    /// Isolated collateral cannot be combined with other collateral
+    IsolationConflict = 21,
+    /// Debt would exceed the debt ceiling of the position's isolated collateral
+    DebtCeilingExceeded = 22,
+    /// Debt would be left nonzero but below the asset's minimum
+    DebtBelowMinimum = 23,
+    /// Soft liquidation is not enabled for the collateral asset
+    SoftLiquidationDisabled = 24,
+    /// The position's conversion already matches its bands
+    NothingToConvert = 25,
+    /// The debt asset is the collateral asset, or differs from the one the
+    /// collateral is already converted toward
+    DebtAssetMismatch = 26,
+    /// The operation is not a pause switch name
+    UnknownOperation = 27,
+    /// No swap adapter is configured
+    SwapAdapterNotSet = 28,
+    /// The AMM rejected the swap
+    SwapFailed = 29,
+    /// The swap returned less than the oracle price allows for
+    SlippageExceeded = 30,
+    /// Borrow would push debt backed by a collateral category above its ceiling
+    CategoryCeilingExceeded = 31,
+    /// The owner already has the maximum number of sub-accounts
+    TooManySubAccounts = 32,
+    /// A batch is empty or has more than `batch::MAX_BATCH_OPERATIONS`
+    InvalidBatch = 33,
+    /// The asset is not a listed liquidity pool share token
+    NotLpToken = 34,
+    /// The pool behind a share token did not report its reserves and shares
+    PoolQueryFailed = 35,
+    /// The asset's price is computed by the LP pricing adapter
+    PricedByAdapter = 36,
+}
+
+impl From<MathError> for CrossAssetError {
+    fn from(_: MathError) -> Self {
+        CrossAssetError::Overflow
+    }
+}
+
+impl From<InterestRateError> for CrossAssetError {
+    fn from(_: InterestRateError) -> Self {
+        CrossAssetError::Overflow
+    }
+}
+
+/// Storage keys for cross-asset data
+#[contracttype]
+#[derive(Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum CrossAssetDataKey {
+    /// Asset configurations: Map<AssetKey, AssetConfig>
+    AssetConfigs,
+    /// User positions: Map<UserAssetKey, AssetPosition>
+    UserPositions,
+    /// Total supplied per asset: Map<AssetKey, i128>
+    TotalSupplies,
+    /// Total borrowed per asset: Map<AssetKey, i128>
+    TotalBorrows,
+    /// Registered assets: Vec<AssetKey>
+    AssetList,
+    /// Cross-asset admin address
+    Admin,
+    /// Users that have deposited, supplied or borrowed: Vec<Address>
+    UserList,
+    /// Completed protocol stress results: Map<i128, ProtocolStressResult>
+    StressCache,
+    /// In-progress protocol stress scan
+    StressProgress,
+    /// Supply tenure rebate schedule: Vec<TenureRebateTier>
+    TenureTiers,
+    /// Last activity per market: Map<AssetKey, MarketActivity>
+    MarketActivity,
+    /// Token interface capabilities found at listing: Map<AssetKey, u32>
+    TokenCapabilities,
+    /// Users that have ever borrowed, in order of first borrow: Vec<Address>
+    BorrowerList,
+    /// Largest share of available liquidity borrowable per ledger, in basis
+    /// points: Map<AssetKey, i128>
+    MaxBorrowSize,
+    /// Borrows in the current ledger per market: Map<AssetKey, LedgerBorrows>
+    LedgerBorrows,
+    /// Collateral factor changes being phased in: Map<AssetKey, CollateralFactorRamp>
+    CollateralFactorRamps,
+    /// Per-asset pause switches: Map<AssetKey, Map<Symbol, bool>>
+    AssetPauses,
+    /// When each delisted asset was delisted: Map<AssetKey, u64>
+    DelistedAssets,
+    /// Debt ceilings of isolated assets: Map<AssetKey, i128>
+    IsolationCeilings,
+    /// Markets whose interest is not simple: Map<AssetKey, InterestMode>
+    InterestModes,
+    /// Smallest nonzero debt per asset: Map<AssetKey, i128>
+    MinBorrowAmounts,
+    /// Soft liquidation bands per collateral asset: Map<AssetKey, SoftLiquidationConfig>
+    SoftLiquidationConfigs,
+    /// Collateral converted by soft liquidation: Map<UserAssetKey, SoftLiquidationState>
+    SoftLiquidations,
+    /// AMM used to repay debt with other collateral: SwapAdapter
+    SwapAdapter,
+    /// Issuer and category tags: Map<AssetKey, AssetMetadata>
+    AssetMetadata,
+    /// Debt ceilings per collateral category, in basis points of supplied
+    /// value: Map<AssetCategory, i128>
+    CategoryCeilings,
+    /// Debt value attributed to each collateral category: Map<AssetCategory, i128>
+    CategoryDebts,
+    /// Debt value of each position attributed to its collateral categories:
+    /// Map<Address, Map<AssetCategory, i128>>
+    CategoryDebtShares,
+    /// Borrow index per market: Map<AssetKey, BorrowIndex>
+    BorrowIndexes,
 }
 
-// Storage keys - using Symbol for type-safe storage keys
-const ASSET_CONFIGS: Symbol = symbol_short!("configs");
-const USER_POSITIONS: Symbol = symbol_short!("positions");
-const TOTAL_SUPPLIES: Symbol = symbol_short!("supplies");
-const TOTAL_BORROWS: Symbol = symbol_short!("borrows");
-const ASSET_LIST: Symbol = symbol_short!("assets");
-const ADMIN: Symbol = symbol_short!("admin");
+const ASSET_CONFIGS: CrossAssetDataKey = CrossAssetDataKey::AssetConfigs;
+const USER_POSITIONS: CrossAssetDataKey = CrossAssetDataKey::UserPositions;
+const TOTAL_SUPPLIES: CrossAssetDataKey = CrossAssetDataKey::TotalSupplies;
+const TOTAL_BORROWS: CrossAssetDataKey = CrossAssetDataKey::TotalBorrows;
+const ASSET_LIST: CrossAssetDataKey = CrossAssetDataKey::AssetList;
+const ADMIN: CrossAssetDataKey = CrossAssetDataKey::Admin;
+const USER_LIST: CrossAssetDataKey = CrossAssetDataKey::UserList;
+const STRESS_CACHE: CrossAssetDataKey = CrossAssetDataKey::StressCache;
+const STRESS_PROGRESS: CrossAssetDataKey = CrossAssetDataKey::StressProgress;
+const TENURE_TIERS: CrossAssetDataKey = CrossAssetDataKey::TenureTiers;
+const MARKET_ACTIVITY: CrossAssetDataKey = CrossAssetDataKey::MarketActivity;
+const TOKEN_CAPABILITIES: CrossAssetDataKey = CrossAssetDataKey::TokenCapabilities;
+const BORROWER_LIST: CrossAssetDataKey = CrossAssetDataKey::BorrowerList;
+const MAX_BORROW_SIZE: CrossAssetDataKey = CrossAssetDataKey::MaxBorrowSize;
+const LEDGER_BORROWS: CrossAssetDataKey = CrossAssetDataKey::LedgerBorrows;
+const COLLATERAL_FACTOR_RAMPS: CrossAssetDataKey = CrossAssetDataKey::CollateralFactorRamps;
+const ASSET_PAUSES: CrossAssetDataKey = CrossAssetDataKey::AssetPauses;
+const DELISTED_ASSETS: CrossAssetDataKey = CrossAssetDataKey::DelistedAssets;
+const ISOLATION_CEILINGS: CrossAssetDataKey = CrossAssetDataKey::IsolationCeilings;
+const INTEREST_MODES: CrossAssetDataKey = CrossAssetDataKey::InterestModes;
+const MIN_BORROW_AMOUNTS: CrossAssetDataKey = CrossAssetDataKey::MinBorrowAmounts;
+const SOFT_LIQUIDATION_CONFIGS: CrossAssetDataKey = CrossAssetDataKey::SoftLiquidationConfigs;
+const SOFT_LIQUIDATIONS: CrossAssetDataKey = CrossAssetDataKey::SoftLiquidations;
+const SWAP_ADAPTER: CrossAssetDataKey = CrossAssetDataKey::SwapAdapter;
+const ASSET_METADATA: CrossAssetDataKey = CrossAssetDataKey::AssetMetadata;
+const CATEGORY_CEILINGS: CrossAssetDataKey = CrossAssetDataKey::CategoryCeilings;
+const CATEGORY_DEBTS: CrossAssetDataKey = CrossAssetDataKey::CategoryDebts;
+const CATEGORY_DEBT_SHARES: CrossAssetDataKey = CrossAssetDataKey::CategoryDebtShares;
+const BORROW_INDEXES: CrossAssetDataKey = CrossAssetDataKey::BorrowIndexes;
+
+/// Decimals of cross-asset prices
+pub const PRICE_DECIMALS: u32 = 7;
+
+/// Maximum number of supply tenure rebate tiers
+pub const MAX_TENURE_TIERS: u32 = 10;
+
+/// Maximum number of users scanned per `stress_test_protocol` page
+pub const MAX_STRESS_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of borrowers scanned per `get_positions_below_health` page
+pub const MAX_HEALTH_SCAN_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of assets returned per `get_supported_assets` page
+pub const MAX_ASSET_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of soft liquidation bands per asset
+pub const MAX_SOFT_LIQUIDATION_BANDS: u32 = 50;
+
+/// Maximum slippage a swap adapter may allow, in basis points
+pub const MAX_SWAP_SLIPPAGE_BPS: i128 = 1_000;
 
 /// Initialize the cross-asset lending module.
 ///
@@ -128,76 +774,159 @@ const ADMIN: Symbol = symbol_short!("admin");
 /// # Arguments
 /// * `admin` - The admin address (must authorize the transaction)
 pub fn initialize(env: &Env, admin: Address) -> Result<(), CrossAssetError> {
-    if env.storage().persistent().has(&ADMIN) {
+    if storage::has(env, &ADMIN) {
         return Err(CrossAssetError::NotAuthorized);
     }
 
     admin.require_auth();
 
-    env.storage().persistent().set(&ADMIN, &admin);
+    storage::set(env, &ADMIN, &admin);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: admin,
+            action: Symbol::new(env, "initialize_ca"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     Ok(())
 }
 
-fn require_admin(env: &Env) -> Result<(), CrossAssetError> {
-    let admin: Address = env
-        .storage()
-        .persistent()
-        .get(&ADMIN)
-        .ok_or(CrossAssetError::NotAuthorized)?;
+pub(crate) fn require_admin(env: &Env) -> Result<Address, CrossAssetError> {
+    let admin: Address = storage::get(env, &ADMIN).ok_or(CrossAssetError::NotAuthorized)?;
 
     admin.require_auth();
 
+    Ok(admin)
+}
+
+/// Require `caller` to be the cross-asset admin or to hold `role`, and to
+/// have authorized the call
+pub(crate) fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), CrossAssetError> {
+    let admin: Option<Address> = storage::get(env, &ADMIN);
+    if admin.as_ref() != Some(caller) && !has_role(env, role, caller) {
+        return Err(CrossAssetError::NotAuthorized);
+    }
+    caller.require_auth();
+    Ok(())
+}
+
+/// Reject user operations while the protocol-wide emergency pause is active.
+fn require_not_paused(env: &Env) -> Result<(), CrossAssetError> {
+    if is_emergency_paused(env) {
+        return Err(CrossAssetError::ProtocolPaused);
+    }
     Ok(())
 }
 
 /// Register a new asset with the cross-asset lending module.
 ///
-/// Validates the configuration (factors in basis-point range, positive price)
-/// and appends the asset to the global asset list if not already present.
+/// Validates the configuration (factors in basis-point range, positive price),
+/// checks that a token asset implements the token interface (see
+/// [`crate::token_interface`]) and appends the asset to the global asset list
+/// if not already present. A delisted asset is relisted.
 ///
 /// # Arguments
 /// * `env` - The contract environment
+/// * `caller` - The cross-asset admin or an `AssetListingAdmin`
 /// * `asset` - Asset to configure (`None` for native XLM)
 /// * `config` - Full asset configuration (factors, caps, price)
 ///
 /// # Errors
-/// * `NotAuthorized` - Caller is not the admin
+/// * `NotAuthorized` - Caller is neither the admin nor an `AssetListingAdmin`
 /// * `AssetNotConfigured` - A basis-point field is out of [0, 10000]
 /// * `InvalidPrice` - Price is zero or negative
+/// * `UnsupportedToken` - The token lacks a required interface function
 pub fn initialize_asset(
     env: &Env,
+    caller: Address,
     asset: Option<Address>,
     config: AssetConfig,
 ) -> Result<(), CrossAssetError> {
-    require_admin(env)?;
+    require_role(env, &caller, Role::AssetListingAdmin)?;
+    register_asset(env, caller, asset, config)
+}
 
+/// [`initialize_asset`] for callers that already checked `caller`'s role
+pub(crate) fn register_asset(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    config: AssetConfig,
+) -> Result<(), CrossAssetError> {
     require_valid_config(&config)?;
+    let capabilities = check_token_interface(env, &asset)?;
 
     let asset_key = AssetKey::from_option(asset.clone());
-    let mut configs: Map<AssetKey, AssetConfig> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_CONFIGS)
-        .unwrap_or(Map::new(env));
+    if let Some(capabilities) = capabilities {
+        let mut all: Map<AssetKey, u32> =
+            storage::get(env, &TOKEN_CAPABILITIES).unwrap_or(Map::new(env));
+        all.set(asset_key.clone(), capabilities);
+        storage::set(env, &TOKEN_CAPABILITIES, &all);
+    }
 
-    configs.set(asset_key.clone(), config);
-    env.storage().persistent().set(&ASSET_CONFIGS, &configs);
+    let mut configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
 
-    let mut asset_list: Vec<AssetKey> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_LIST)
-        .unwrap_or(Vec::new(env));
+    configs.set(asset_key.clone(), config.clone());
+    storage::set(env, &ASSET_CONFIGS, &configs);
+
+    let mut asset_list: Vec<AssetKey> = storage::get(env, &ASSET_LIST).unwrap_or(Vec::new(env));
 
     if !asset_list.contains(&asset_key) {
-        asset_list.push_back(asset_key);
-        env.storage().persistent().set(&ASSET_LIST, &asset_list);
+        asset_list.push_back(asset_key.clone());
+        storage::set(env, &ASSET_LIST, &asset_list);
+        // Inactivity is measured from registration until the first interaction
+        record_market_activity(env, &asset_key, true);
     }
+    relist_asset(env, &asset_key);
 
+    emit_config_updated(env, caller, asset, config);
     Ok(())
 }
 
+/// Clear a delisting, if any.
+fn relist_asset(env: &Env, asset_key: &AssetKey) {
+    let Some(mut delisted) =
+        storage::get::<CrossAssetDataKey, Map<AssetKey, u64>>(env, &DELISTED_ASSETS)
+    else {
+        return;
+    };
+    if delisted.contains_key(asset_key.clone()) {
+        delisted.remove(asset_key.clone());
+        storage::set(env, &DELISTED_ASSETS, &delisted);
+    }
+}
+
+/// Probe a token asset's interface. Returns its capability bitmap, or `None`
+/// for native XLM.
+///
+/// # Errors
+/// * `UnsupportedToken` - A required capability is missing
+pub(crate) fn check_token_interface(
+    env: &Env,
+    asset: &Option<Address>,
+) -> Result<Option<u32>, CrossAssetError> {
+    let Some(asset_addr) = asset else {
+        return Ok(None);
+    };
+    let capabilities = probe_token(env, asset_addr);
+    if !has_required_capabilities(capabilities) {
+        return Err(CrossAssetError::UnsupportedToken);
+    }
+    Ok(Some(capabilities))
+}
+
+/// Get the token interface capabilities recorded when an asset was listed.
+///
+/// `None` for native XLM and unlisted assets.
+pub fn get_token_capabilities(env: &Env, asset: Option<Address>) -> Option<u32> {
+    let all: Map<AssetKey, u32> = storage::get(env, &TOKEN_CAPABILITIES)?;
+    all.get(AssetKey::from_option(asset))
+}
+
 /// Selectively update an existing asset's configuration.
 ///
 /// Only the provided `Some` fields are updated; `None` fields keep their
@@ -205,484 +934,3458 @@ pub fn initialize_asset(
 ///
 /// # Arguments
 /// * `env` - The contract environment
-/// * `asset` - Asset to update (`None` for XLM)
-/// * `collateral_factor` - Optional new collateral factor (basis points)
-/// * `borrow_factor` - Optional new borrow factor (basis points)
-/// * `max_supply` - Optional new supply cap
-/// * `max_borrow` - Optional new borrow cap
-/// * `can_collateralize` - Optional flag to enable/disable as collateral
-/// * `can_borrow` - Optional flag to enable/disable borrowing
+/// * `caller` - The cross-asset admin or a `RiskAdmin`
+/// * `update` - Asset to update (`None` for XLM) and the fields to change
 ///
 /// # Errors
-/// * `NotAuthorized` - Caller is not the admin
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
 /// * `AssetNotConfigured` - Asset has not been initialized or factor out of range
-#[allow(clippy::too_many_arguments)]
 pub fn update_asset_config(
     env: &Env,
-    asset: Option<Address>,
-    collateral_factor: Option<i128>,
-    borrow_factor: Option<i128>,
-    max_supply: Option<i128>,
-    max_borrow: Option<i128>,
-    can_collateralize: Option<bool>,
-    can_borrow: Option<bool>,
+    caller: Address,
+    update: AssetConfigUpdate,
 ) -> Result<(), CrossAssetError> {
-    require_admin(env)?;
+    require_role(env, &caller, Role::RiskAdmin)?;
 
-    let asset_key = AssetKey::from_option(asset);
-    let mut config = get_asset_config(env, &asset_key)?;
+    let asset_key = AssetKey::from_option(update.asset.clone());
+    let config = updated_asset_config(env, &update)?;
 
-    if let Some(cf) = collateral_factor {
-        require_valid_basis_points(cf)?;
-        config.collateral_factor = cf;
-    }
+    // Update storage
+    let mut configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
 
-    if let Some(bf) = borrow_factor {
-        require_valid_basis_points(bf)?;
-        config.borrow_factor = bf;
-    }
+    configs.set(asset_key.clone(), config.clone());
+    storage::set(env, &ASSET_CONFIGS, &configs);
 
-    if let Some(ms) = max_supply {
-        config.max_supply = ms;
+    // A new collateral factor replaces any ramp in progress
+    if update.collateral_factor.is_some() {
+        remove_collateral_factor_ramp(env, &asset_key);
     }
 
-    if let Some(mb) = max_borrow {
-        config.max_borrow = mb;
-    }
+    emit_config_updated(env, caller, update.asset, config);
+    Ok(())
+}
 
-    if let Some(cc) = can_collateralize {
-        config.can_collateralize = cc;
-    }
+/// Announce a stored asset configuration change.
+fn emit_config_updated(env: &Env, actor: Address, asset: Option<Address>, config: AssetConfig) {
+    emit_asset_config_updated(
+        env,
+        AssetConfigUpdatedEvent {
+            actor,
+            asset,
+            config,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+}
 
-    if let Some(cb) = can_borrow {
-        config.can_borrow = cb;
-    }
+/// Move an asset's collateral factor to `target_factor` linearly over
+/// `days` days (admin or `RiskAdmin` only).
+///
+/// The ramp starts from the factor in effect now, so a ramp already in
+/// progress is replaced without a jump. A duration of 0 applies the change
+/// at once, as [`update_asset_config`] does.
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `RiskAdmin`
+/// * `asset` - Asset to update (`None` for XLM)
+/// * `target_factor` - Collateral factor at the end of the ramp, in basis points
+/// * `days` - Length of the ramp in days
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - Asset has not been initialized or factor out of range
+pub fn ramp_collateral_factor(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    target_factor: i128,
+    days: u32,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+    require_valid_basis_points(target_factor)?;
 
-    // Update storage
-    let mut configs: Map<AssetKey, AssetConfig> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_CONFIGS)
-        .unwrap_or(Map::new(env));
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut config = get_stored_asset_config(env, &asset_key)?;
+    let start_factor = config.collateral_factor;
+    config.collateral_factor = target_factor;
 
-    configs.set(asset_key, config);
-    env.storage().persistent().set(&ASSET_CONFIGS, &configs);
+    let mut configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
+    configs.set(asset_key.clone(), config.clone());
+    storage::set(env, &ASSET_CONFIGS, &configs);
+    emit_config_updated(env, caller, asset, config);
 
+    if days == 0 {
+        remove_collateral_factor_ramp(env, &asset_key);
+        return Ok(());
+    }
+    let now = env.ledger().timestamp();
+    let mut ramps: Map<AssetKey, CollateralFactorRamp> =
+        storage::get(env, &COLLATERAL_FACTOR_RAMPS).unwrap_or(Map::new(env));
+    ramps.set(
+        asset_key,
+        CollateralFactorRamp {
+            start_factor,
+            target_factor,
+            start_time: now,
+            end_time: now.saturating_add(days as u64 * SECONDS_PER_DAY.get()),
+        },
+    );
+    storage::set(env, &COLLATERAL_FACTOR_RAMPS, &ramps);
     Ok(())
 }
 
-/// Update the oracle price for an asset.
-///
-/// Records the new price and the current ledger timestamp for staleness checks.
+/// Return an asset's collateral factor ramp, if one is in progress.
+pub fn get_collateral_factor_ramp(
+    env: &Env,
+    asset: Option<Address>,
+) -> Option<CollateralFactorRamp> {
+    let ramps: Map<AssetKey, CollateralFactorRamp> = storage::get(env, &COLLATERAL_FACTOR_RAMPS)?;
+    ramps
+        .get(AssetKey::from_option(asset))
+        .filter(|ramp| env.ledger().timestamp() < ramp.end_time)
+}
+
+fn remove_collateral_factor_ramp(env: &Env, asset_key: &AssetKey) {
+    let Some(mut ramps) = storage::get::<CrossAssetDataKey, Map<AssetKey, CollateralFactorRamp>>(
+        env,
+        &COLLATERAL_FACTOR_RAMPS,
+    ) else {
+        return;
+    };
+    if ramps.contains_key(asset_key.clone()) {
+        ramps.remove(asset_key.clone());
+        storage::set(env, &COLLATERAL_FACTOR_RAMPS, &ramps);
+    }
+}
+
+/// Pause or resume one operation for one asset (admin or `PauseGuardian` only).
 ///
 /// # Arguments
-/// * `env` - The contract environment
-/// * `asset` - Asset to update price for (`None` for XLM)
-/// * `price` - New price in base units (7 decimals, must be > 0)
+/// * `caller` - The cross-asset admin or a `PauseGuardian`
+/// * `asset` - Asset to update (`None` for XLM)
+/// * `operation` - A pause switch name such as `pause_borrow`
+/// * `paused` - Whether to pause (true) or resume (false) the operation
 ///
 /// # Errors
-/// * `NotAuthorized` - Caller is not the admin
-/// * `InvalidPrice` - Price is zero or negative
-/// * `AssetNotConfigured` - Asset has not been initialized
-pub fn update_asset_price(
+/// * `NotAuthorized` - Caller is neither the admin nor a `PauseGuardian`
+/// * `UnknownOperation` - `operation` is not a pause switch name
+pub fn set_asset_pause(
     env: &Env,
+    caller: Address,
     asset: Option<Address>,
-    price: i128,
+    operation: Symbol,
+    paused: bool,
 ) -> Result<(), CrossAssetError> {
-    require_admin(env)?;
+    require_role(env, &caller, Role::PauseGuardian)?;
+    pause_operation(env, &operation).ok_or(CrossAssetError::UnknownOperation)?;
 
-    if price <= 0 {
-        return Err(CrossAssetError::InvalidPrice);
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut pauses: Map<AssetKey, Map<Symbol, bool>> =
+        storage::get(env, &ASSET_PAUSES).unwrap_or(Map::new(env));
+    let mut switches = pauses.get(asset_key.clone()).unwrap_or(Map::new(env));
+    if paused {
+        switches.set(operation.clone(), true);
+    } else {
+        switches.remove(operation.clone());
     }
+    if switches.is_empty() {
+        pauses.remove(asset_key);
+    } else {
+        pauses.set(asset_key, switches);
+    }
+    storage::set(env, &ASSET_PAUSES, &pauses);
 
-    let asset_key = AssetKey::from_option(asset);
-    let mut config = get_asset_config(env, &asset_key)?;
-    config.price = price;
-    config.price_updated_at = env.ledger().timestamp();
+    emit_asset_pause_updated(
+        env,
+        AssetPauseUpdatedEvent {
+            admin: caller,
+            asset,
+            operation,
+            paused,
+        },
+    );
+    Ok(())
+}
 
-    let mut configs: Map<AssetKey, AssetConfig> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_CONFIGS)
-        .unwrap_or(Map::new(env));
+/// Return the operations paused for an asset.
+pub fn get_asset_pauses(env: &Env, asset: Option<Address>) -> Map<Symbol, bool> {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, Map<Symbol, bool>>>(env, &ASSET_PAUSES)
+        .and_then(|pauses| pauses.get(AssetKey::from_option(asset)))
+        .unwrap_or(Map::new(env))
+}
 
-    configs.set(asset_key, config);
-    env.storage().persistent().set(&ASSET_CONFIGS, &configs);
+/// Whether `operation` is blocked for the asset by the emergency pause,
+/// delisting or the asset's own switch. Unknown operations are never paused.
+pub fn is_asset_operation_paused(env: &Env, asset: Option<Address>, operation: Symbol) -> bool {
+    pause_operation(env, &operation).is_some_and(|operation| {
+        is_emergency_paused(env) || is_asset_paused(env, &AssetKey::from_option(asset), operation)
+    })
+}
 
-    Ok(())
+/// The pause switch name matching `operation`, if any.
+fn pause_operation(env: &Env, operation: &Symbol) -> Option<&'static str> {
+    [
+        PAUSE_DEPOSIT,
+        PAUSE_WITHDRAW,
+        PAUSE_BORROW,
+        PAUSE_REPAY,
+        PAUSE_LIQUIDATE,
+    ]
+    .into_iter()
+    .find(|name| Symbol::new(env, name) == *operation)
 }
 
-/// Get user's position for a specific asset
-///
-/// # Arguments
-/// * `env` - The contract environment
-/// * `user` - User address
-/// * `asset` - Asset address (None for XLM)
-///
-/// # Returns
-/// Asset position or default empty position
-pub fn get_user_asset_position(env: &Env, user: &Address, asset: Option<Address>) -> AssetPosition {
-    let key = UserAssetKey::new(user.clone(), asset);
-    let positions: Map<UserAssetKey, AssetPosition> = env
-        .storage()
-        .persistent()
-        .get(&USER_POSITIONS)
-        .unwrap_or(Map::new(env));
+/// Whether `operation` is paused for the asset. Deposits and borrows of a
+/// delisted asset are always paused.
+pub(crate) fn is_asset_paused(env: &Env, asset_key: &AssetKey, operation: &str) -> bool {
+    if is_frozen_by_delisting(operation) && is_asset_delisted(env, asset_key) {
+        return true;
+    }
+    storage::get::<CrossAssetDataKey, Map<AssetKey, Map<Symbol, bool>>>(env, &ASSET_PAUSES)
+        .and_then(|pauses| pauses.get(asset_key.clone()))
+        .is_some_and(|switches| is_switch_set(env, &switches, operation))
+}
 
-    positions.get(key).unwrap_or(AssetPosition {
-        collateral: 0,
-        debt_principal: 0,
-        accrued_interest: 0,
-        last_updated: env.ledger().timestamp(),
-    })
+/// Reject `operation` if it is paused for the asset.
+fn require_asset_not_paused(
+    env: &Env,
+    asset_key: &AssetKey,
+    operation: &str,
+) -> Result<(), CrossAssetError> {
+    if is_frozen_by_delisting(operation) && is_asset_delisted(env, asset_key) {
+        return Err(CrossAssetError::AssetDelisted);
+    }
+    if is_asset_paused(env, asset_key, operation) {
+        return Err(CrossAssetError::AssetPaused);
+    }
+    Ok(())
 }
 
-/// Update user's position for a specific asset
+/// Whether delisting an asset freezes `operation`.
+fn is_frozen_by_delisting(operation: &str) -> bool {
+    operation == PAUSE_DEPOSIT || operation == PAUSE_BORROW
+}
+
+/// Isolate an asset with a debt ceiling, or lift its isolation (admin or
+/// `RiskAdmin` only).
 ///
 /// # Arguments
-/// * `env` - The contract environment
-/// * `user` - User address
+/// * `caller` - The cross-asset admin or a `RiskAdmin`
+/// * `asset` - Asset to update (`None` for XLM)
+/// * `debt_ceiling` - Most debt value, at current prices, a position using
+///   the asset as collateral may carry; `None` lifts the isolation
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - The asset is not listed
+/// * `InvalidAmount` - The ceiling is negative
+pub fn set_isolation_mode(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    debt_ceiling: Option<i128>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    get_asset_config(env, &asset_key)?;
+    let mut ceilings: Map<AssetKey, i128> =
+        storage::get(env, &ISOLATION_CEILINGS).unwrap_or(Map::new(env));
+    match debt_ceiling {
+        Some(ceiling) if ceiling < 0 => return Err(CrossAssetError::InvalidAmount),
+        Some(ceiling) => ceilings.set(asset_key, ceiling),
+        None => {
+            ceilings.remove(asset_key);
+        }
+    }
+    storage::set(env, &ISOLATION_CEILINGS, &ceilings);
+
+    emit_isolation_mode_updated(
+        env,
+        IsolationModeUpdatedEvent {
+            admin: caller,
+            asset,
+            debt_ceiling,
+        },
+    );
+    Ok(())
+}
+
+/// Get an asset's debt ceiling, if it is isolated.
+pub fn get_isolation_debt_ceiling(env: &Env, asset: Option<Address>) -> Option<i128> {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, i128>>(env, &ISOLATION_CEILINGS)
+        .and_then(|ceilings| ceilings.get(AssetKey::from_option(asset)))
+}
+
+/// Tag a listed asset with its issuer and category, or clear its tags with
+/// `None` (admin or `AssetListingAdmin` only).
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor an `AssetListingAdmin`
+/// * `AssetNotConfigured` - The asset is not listed
+pub fn set_asset_metadata(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    metadata: Option<AssetMetadata>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::AssetListingAdmin)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    get_asset_config(env, &asset_key)?;
+    let mut all: Map<AssetKey, AssetMetadata> =
+        storage::get(env, &ASSET_METADATA).unwrap_or(Map::new(env));
+    match &metadata {
+        Some(metadata) => all.set(asset_key, metadata.clone()),
+        None => {
+            all.remove(asset_key);
+        }
+    }
+    storage::set(env, &ASSET_METADATA, &all);
+
+    emit_asset_metadata_updated(
+        env,
+        AssetMetadataUpdatedEvent {
+            admin: caller,
+            asset,
+            category: metadata.as_ref().map(|metadata| metadata.category),
+            issuer: metadata.and_then(|metadata| metadata.issuer),
+        },
+    );
+    Ok(())
+}
+
+/// Get an asset's issuer and category tags, if tagged.
+pub fn get_asset_metadata(env: &Env, asset: Option<Address>) -> Option<AssetMetadata> {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, AssetMetadata>>(env, &ASSET_METADATA)
+        .and_then(|all| all.get(AssetKey::from_option(asset)))
+}
+
+/// Sum supply and borrow value per asset category, at oracle prices.
+///
+/// Untagged assets count as [`AssetCategory::Other`]; categories without
+/// listed assets are omitted.
+///
+/// # Errors
+/// * `Overflow` - A category's value overflowed
+pub fn get_category_exposure(
+    env: &Env,
+) -> Result<Map<AssetCategory, CategoryExposure>, CrossAssetError> {
+    let metadata: Map<AssetKey, AssetMetadata> =
+        storage::get(env, &ASSET_METADATA).unwrap_or(Map::new(env));
+    let debts: Map<AssetCategory, i128> =
+        storage::get(env, &CATEGORY_DEBTS).unwrap_or(Map::new(env));
+    let mut exposures: Map<AssetCategory, CategoryExposure> = Map::new(env);
+    for (asset_key, config) in get_asset_configs(env).iter() {
+        let category = metadata
+            .get(asset_key.clone())
+            .map_or(AssetCategory::Other, |metadata| metadata.category);
+        let mut exposure = exposures.get(category).unwrap_or(CategoryExposure {
+            assets: 0,
+            supplied_value: 0,
+            borrowed_value: 0,
+            backed_debt_value: debts.get(category).unwrap_or(0),
+        });
+        exposure.assets += 1;
+        exposure.supplied_value = Amount::new(exposure.supplied_value)
+            .checked_add(value_at(get_total_supply(env, &asset_key), config.price)?)?
+            .get();
+        exposure.borrowed_value = Amount::new(exposure.borrowed_value)
+            .checked_add(value_at(get_total_borrow(env, &asset_key), config.price)?)?
+            .get();
+        exposures.set(category, exposure);
+    }
+    Ok(exposures)
+}
+
+/// Cap the debt backed by collateral of a category, or lift the cap with
+/// `None` (admin or `RiskAdmin` only).
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `RiskAdmin`
+/// * `category` - Collateral category to cap
+/// * `ceiling_bps` - Most attributed debt value, in basis points of the
+///   value supplied across all markets
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `InvalidAmount` - The ceiling is outside [0, 10000]
+pub fn set_category_debt_ceiling(
+    env: &Env,
+    caller: Address,
+    category: AssetCategory,
+    ceiling_bps: Option<i128>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    let mut ceilings: Map<AssetCategory, i128> =
+        storage::get(env, &CATEGORY_CEILINGS).unwrap_or(Map::new(env));
+    match ceiling_bps {
+        Some(ceiling) if !(0..=BASIS_POINTS_SCALE.get()).contains(&ceiling) => {
+            return Err(CrossAssetError::InvalidAmount)
+        }
+        Some(ceiling) => ceilings.set(category, ceiling),
+        None => {
+            ceilings.remove(category);
+        }
+    }
+    storage::set(env, &CATEGORY_CEILINGS, &ceilings);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_category_debt_ceiling"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get a collateral category's debt ceiling in basis points of supplied
+/// value, if capped.
+pub fn get_category_debt_ceiling(env: &Env, category: AssetCategory) -> Option<i128> {
+    storage::get::<CrossAssetDataKey, Map<AssetCategory, i128>>(env, &CATEGORY_CEILINGS)
+        .and_then(|ceilings| ceilings.get(category))
+}
+
+/// Debt value of a position attributed to each of its collateral
+/// categories, pro rata to collateral value
+fn attribute_category_debt(
+    env: &Env,
+    user: &Address,
+) -> Result<Map<AssetCategory, i128>, CrossAssetError> {
+    let configs = get_asset_configs(env);
+    let metadata: Map<AssetKey, AssetMetadata> =
+        storage::get(env, &ASSET_METADATA).unwrap_or(Map::new(env));
+    let mut collateral: Map<AssetCategory, i128> = Map::new(env);
+    let mut total_collateral = Amount::ZERO;
+    let mut debt = Amount::ZERO;
+    for (asset_key, position) in get_user_asset_positions(env, user).iter() {
+        let Some(config) = configs.get(asset_key.clone()) else {
+            continue;
+        };
+        let owed = Amount::new(position.debt_principal)
+            .checked_add(Amount::new(position.accrued_interest))?;
+        debt = debt.checked_add(value_at(owed.get(), config.price)?)?;
+        if position.collateral > 0 && position.use_as_collateral && config.can_collateralize {
+            let value = value_at(position.collateral, config.price)?;
+            let category = metadata
+                .get(asset_key)
+                .map_or(AssetCategory::Other, |metadata| metadata.category);
+            let category_value =
+                Amount::new(collateral.get(category).unwrap_or(0)).checked_add(value)?;
+            collateral.set(category, category_value.get());
+            total_collateral = total_collateral.checked_add(value)?;
+        }
+    }
+
+    let mut shares = Map::new(env);
+    if debt.get() > 0 && total_collateral.get() > 0 {
+        for (category, value) in collateral.iter() {
+            let share = debt.mul_div(value, total_collateral.get())?.get();
+            if share > 0 {
+                shares.set(category, share);
+            }
+        }
+    }
+    Ok(shares)
+}
+
+fn get_category_debt_shares(env: &Env, user: &Address) -> Map<AssetCategory, i128> {
+    storage::get::<CrossAssetDataKey, Map<Address, Map<AssetCategory, i128>>>(
+        env,
+        &CATEGORY_DEBT_SHARES,
+    )
+    .and_then(|all| all.get(user.clone()))
+    .unwrap_or(Map::new(env))
+}
+
+/// Ceiling of a category in debt value, from its basis points of the value
+/// supplied across all markets
+fn category_ceiling_value(env: &Env, ceiling_bps: i128) -> Result<i128, CrossAssetError> {
+    let mut supplied = Amount::ZERO;
+    for (asset_key, config) in get_asset_configs(env).iter() {
+        supplied =
+            supplied.checked_add(value_at(get_total_supply(env, &asset_key), config.price)?)?;
+    }
+    Ok(supplied
+        .mul_div(ceiling_bps, BASIS_POINTS_SCALE.get())?
+        .get())
+}
+
+/// Refresh a position's category debt attribution and adjust the category
+/// totals by the difference. Does nothing while no category is capped.
+///
+/// Emits `category_ceiling_reached` for each capped category whose
+/// attributed debt grew while at or above its ceiling.
+///
+/// # Errors
+/// * `Overflow` - A debt or collateral value overflowed
+fn sync_category_debt(env: &Env, user: &Address) -> Result<(), CrossAssetError> {
+    let ceilings: Map<AssetCategory, i128> =
+        storage::get(env, &CATEGORY_CEILINGS).unwrap_or(Map::new(env));
+    if ceilings.is_empty() {
+        return Ok(());
+    }
+    let old = get_category_debt_shares(env, user);
+    let new = attribute_category_debt(env, user)?;
+    if old == new {
+        return Ok(());
+    }
+
+    let mut debts: Map<AssetCategory, i128> =
+        storage::get(env, &CATEGORY_DEBTS).unwrap_or(Map::new(env));
+    for (category, share) in old.iter() {
+        let debt = Amount::new(debts.get(category).unwrap_or(0)).checked_sub(Amount::new(share))?;
+        debts.set(category, debt.get());
+    }
+    for (category, share) in new.iter() {
+        let debt = Amount::new(debts.get(category).unwrap_or(0)).checked_add(Amount::new(share))?;
+        debts.set(category, debt.get());
+    }
+    storage::set(env, &CATEGORY_DEBTS, &debts);
+    let mut all: Map<Address, Map<AssetCategory, i128>> =
+        storage::get(env, &CATEGORY_DEBT_SHARES).unwrap_or(Map::new(env));
+    if new.is_empty() {
+        all.remove(user.clone());
+    } else {
+        all.set(user.clone(), new.clone());
+    }
+    storage::set(env, &CATEGORY_DEBT_SHARES, &all);
+
+    for (category, share) in new.iter() {
+        let Some(ceiling_bps) = ceilings.get(category) else {
+            continue;
+        };
+        if share <= old.get(category).unwrap_or(0) {
+            continue;
+        }
+        let debt_value = debts.get(category).unwrap_or(0);
+        let ceiling_value = category_ceiling_value(env, ceiling_bps)?;
+        if debt_value >= ceiling_value {
+            emit_category_ceiling_reached(
+                env,
+                CategoryCeilingReachedEvent {
+                    user: user.clone(),
+                    category,
+                    debt_value,
+                    ceiling_value,
+                    timestamp: env.ledger().timestamp(),
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reject a change that raised a position's attributed debt in a capped
+/// category above the category's ceiling.
+///
+/// # Errors
+/// * `CategoryCeilingExceeded` - A category's attributed debt rose above its
+///   ceiling
+/// * `Overflow` - The ceiling's value overflowed
+fn require_category_headroom(
+    env: &Env,
+    user: &Address,
+    before: &Map<AssetCategory, i128>,
+) -> Result<(), CrossAssetError> {
+    let ceilings: Map<AssetCategory, i128> =
+        storage::get(env, &CATEGORY_CEILINGS).unwrap_or(Map::new(env));
+    if ceilings.is_empty() {
+        return Ok(());
+    }
+    let debts: Map<AssetCategory, i128> =
+        storage::get(env, &CATEGORY_DEBTS).unwrap_or(Map::new(env));
+    for (category, share) in get_category_debt_shares(env, user).iter() {
+        let Some(ceiling_bps) = ceilings.get(category) else {
+            continue;
+        };
+        if share > before.get(category).unwrap_or(0)
+            && debts.get(category).unwrap_or(0) > category_ceiling_value(env, ceiling_bps)?
+        {
+            return Err(CrossAssetError::CategoryCeilingExceeded);
+        }
+    }
+    Ok(())
+}
+
+/// Choose where a market's borrower interest accrues (admin or `RiskAdmin`
+/// only).
+///
+/// Applies to interest accrued from then on; interest already in a
+/// position's `accrued_interest` stays there and is still repaid first.
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - The asset is not listed
+pub fn set_interest_mode(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    mode: InterestMode,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    get_asset_config(env, &asset_key)?;
+    let mut modes: Map<AssetKey, InterestMode> =
+        storage::get(env, &INTEREST_MODES).unwrap_or(Map::new(env));
+    match mode {
+        InterestMode::Simple => {
+            modes.remove(asset_key);
+        }
+        InterestMode::Compounding => modes.set(asset_key, mode),
+    }
+    storage::set(env, &INTEREST_MODES, &modes);
+
+    emit_interest_mode_updated(
+        env,
+        InterestModeUpdatedEvent {
+            admin: caller,
+            asset,
+            mode,
+        },
+    );
+    Ok(())
+}
+
+/// Get where a market's borrower interest accrues.
+pub fn get_interest_mode(env: &Env, asset_key: &AssetKey) -> InterestMode {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, InterestMode>>(env, &INTEREST_MODES)
+        .and_then(|modes| modes.get(asset_key.clone()))
+        .unwrap_or(InterestMode::Simple)
+}
+
+/// Set the smallest nonzero debt a position may carry in an asset (admin or
+/// `RiskAdmin` only).
+///
+/// Existing positions below the new minimum are unaffected until they borrow
+/// or repay again.
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `RiskAdmin`
+/// * `asset` - Asset to update (`None` for XLM)
+/// * `amount` - Minimum debt; `0` removes it
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - The asset is not listed
+/// * `InvalidAmount` - The amount is negative
+pub fn set_min_borrow_amount(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    get_asset_config(env, &asset_key)?;
+    if amount < 0 {
+        return Err(CrossAssetError::InvalidAmount);
+    }
+    let mut minimums: Map<AssetKey, i128> =
+        storage::get(env, &MIN_BORROW_AMOUNTS).unwrap_or(Map::new(env));
+    if amount == 0 {
+        minimums.remove(asset_key);
+    } else {
+        minimums.set(asset_key, amount);
+    }
+    storage::set(env, &MIN_BORROW_AMOUNTS, &minimums);
+
+    emit_min_borrow_amount_updated(
+        env,
+        MinBorrowAmountUpdatedEvent {
+            admin: caller,
+            asset,
+            amount,
+        },
+    );
+    Ok(())
+}
+
+/// Get the smallest nonzero debt a position may carry in an asset; `0` if
+/// there is no minimum.
+pub fn get_min_borrow_amount(env: &Env, asset: Option<Address>) -> i128 {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, i128>>(env, &MIN_BORROW_AMOUNTS)
+        .and_then(|minimums| minimums.get(AssetKey::from_option(asset)))
+        .unwrap_or(0)
+}
+
+/// Whether `debt` is nonzero but below the asset's minimum debt.
+pub(crate) fn is_dust_debt(env: &Env, asset_key: &AssetKey, debt: i128) -> bool {
+    debt > 0 && debt < get_min_borrow_amount(env, asset_key.to_option())
+}
+
+/// Whether the asset is isolated.
+pub(crate) fn is_asset_isolated(env: &Env, asset_key: &AssetKey) -> bool {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, i128>>(env, &ISOLATION_CEILINGS)
+        .is_some_and(|ceilings| ceilings.contains_key(asset_key.clone()))
+}
+
+/// Assets the user holds collateral in that count toward borrowing power.
+fn collateral_assets(env: &Env, user: &Address) -> Vec<AssetKey> {
+    let mut assets = Vec::new(env);
+    for (asset_key, position) in get_user_asset_positions(env, user).iter() {
+        if position.collateral > 0 && position.use_as_collateral {
+            assets.push_back(asset_key);
+        }
+    }
+    assets
+}
+
+/// Reject using `asset_key` as collateral alongside the user's other
+/// collateral when either side is isolated.
+fn require_collateral_compatible(
+    env: &Env,
+    user: &Address,
+    asset_key: &AssetKey,
+) -> Result<(), CrossAssetError> {
+    let ceilings: Map<AssetKey, i128> =
+        storage::get(env, &ISOLATION_CEILINGS).unwrap_or(Map::new(env));
+    let isolated = ceilings.contains_key(asset_key.clone());
+    for other in collateral_assets(env, user).iter() {
+        if &other != asset_key && (isolated || ceilings.contains_key(other)) {
+            return Err(CrossAssetError::IsolationConflict);
+        }
+    }
+    Ok(())
+}
+
+/// The lowest debt ceiling among the user's isolated collateral, if any.
+fn get_isolation_ceiling(env: &Env, user: &Address) -> Option<i128> {
+    let ceilings: Map<AssetKey, i128> = storage::get(env, &ISOLATION_CEILINGS)?;
+    collateral_assets(env, user)
+        .iter()
+        .filter_map(|asset_key| ceilings.get(asset_key))
+        .min()
+}
+
+/// Delist an asset (admin or `AssetListingAdmin` only).
+///
+/// New deposits and borrows of the asset are rejected from then on, while
+/// withdrawals, repayments and liquidations continue so positions can be
+/// unwound. The asset stays in [`get_asset_list`] but leaves
+/// [`get_supported_assets`]. Configuring it again with [`initialize_asset`]
+/// relists it.
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor an `AssetListingAdmin`
+/// * `AssetNotConfigured` - The asset is not listed
+/// * `AssetDelisted` - The asset is already delisted
+pub fn delist_asset(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::AssetListingAdmin)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    get_asset_config(env, &asset_key)?;
+    let mut delisted: Map<AssetKey, u64> =
+        storage::get(env, &DELISTED_ASSETS).unwrap_or(Map::new(env));
+    if delisted.contains_key(asset_key.clone()) {
+        return Err(CrossAssetError::AssetDelisted);
+    }
+    let timestamp = env.ledger().timestamp();
+    delisted.set(asset_key, timestamp);
+    storage::set(env, &DELISTED_ASSETS, &delisted);
+
+    emit_asset_delisted(
+        env,
+        AssetDelistedEvent {
+            admin: caller,
+            asset,
+            timestamp,
+        },
+    );
+    Ok(())
+}
+
+/// Whether the asset is delisted.
+pub fn is_asset_delisted(env: &Env, asset_key: &AssetKey) -> bool {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, u64>>(env, &DELISTED_ASSETS)
+        .is_some_and(|delisted| delisted.contains_key(asset_key.clone()))
+}
+
+/// Page through the listed assets that are not delisted, in listing order.
+///
+/// # Arguments
+/// * `limit` - Assets per page (at most [`MAX_ASSET_PAGE_SIZE`])
+/// * `offset` - Number of supported assets to skip
+///
+/// # Errors
+/// * `InvalidAmount` - `limit` is zero or above the page size
+pub fn get_supported_assets(
+    env: &Env,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<AssetKey>, CrossAssetError> {
+    if limit == 0 || limit > MAX_ASSET_PAGE_SIZE {
+        return Err(CrossAssetError::InvalidAmount);
+    }
+
+    let delisted: Map<AssetKey, u64> = storage::get(env, &DELISTED_ASSETS).unwrap_or(Map::new(env));
+    let mut page = Vec::new(env);
+    let mut skipped = 0;
+    for asset_key in get_asset_list(env).iter() {
+        if delisted.contains_key(asset_key.clone()) {
+            continue;
+        }
+        if skipped < offset {
+            skipped += 1;
+            continue;
+        }
+        page.push_back(asset_key);
+        if page.len() == limit {
+            break;
+        }
+    }
+    Ok(page)
+}
+
+/// Validate an asset configuration update and return the resulting config
+/// without storing it.
+pub(crate) fn updated_asset_config(
+    env: &Env,
+    update: &AssetConfigUpdate,
+) -> Result<AssetConfig, CrossAssetError> {
+    let asset_key = AssetKey::from_option(update.asset.clone());
+    let mut config = get_stored_asset_config(env, &asset_key)?;
+
+    if let Some(cf) = update.collateral_factor {
+        require_valid_basis_points(cf)?;
+        config.collateral_factor = cf;
+    }
+
+    if let Some(bf) = update.borrow_factor {
+        require_valid_basis_points(bf)?;
+        config.borrow_factor = bf;
+    }
+
+    if let Some(ms) = update.max_supply {
+        config.max_supply = ms;
+    }
+
+    if let Some(mb) = update.max_borrow {
+        config.max_borrow = mb;
+    }
+
+    if let Some(cc) = update.can_collateralize {
+        config.can_collateralize = cc;
+    }
+
+    if let Some(cb) = update.can_borrow {
+        config.can_borrow = cb;
+    }
+
+    Ok(config)
+}
+
+/// Update the oracle price for an asset.
+///
+/// Records the new price and the current ledger timestamp for staleness checks.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `caller` - The cross-asset admin or an `AssetListingAdmin`
+/// * `asset` - Asset to update price for (`None` for XLM)
+/// * `price` - New price in base units (7 decimals, must be > 0)
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor an `AssetListingAdmin`
+/// * `InvalidPrice` - Price is zero or negative
+/// * `AssetNotConfigured` - Asset has not been initialized
+/// * `PricedByAdapter` - Asset is an LP share token (see `lp_collateral`)
+pub fn update_asset_price(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    price: i128,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::AssetListingAdmin)?;
+
+    if price <= 0 {
+        return Err(CrossAssetError::InvalidPrice);
+    }
+    if asset
+        .as_ref()
+        .is_some_and(|asset| get_lp_pool(env, asset).is_some())
+    {
+        return Err(CrossAssetError::PricedByAdapter);
+    }
+
+    let config = set_asset_price(env, asset.clone(), price, env.ledger().timestamp())?;
+    emit_config_updated(env, caller, asset, config);
+    Ok(())
+}
+
+/// Price a listed asset's market at an aggregated oracle price
+///
+/// Called when the oracle publishes an asset's price from its sources, so
+/// positions are valued at the same price the oracle reports. Does nothing
+/// for unlisted assets, LP share tokens (priced by `lp_collateral`) and
+/// prices that cannot be converted to [`PRICE_DECIMALS`].
+pub(crate) fn sync_oracle_price(
+    env: &Env,
+    actor: Address,
+    asset: &Address,
+    price: i128,
+    decimals: u32,
+) {
+    if get_lp_pool(env, asset).is_some() {
+        return;
+    }
+    let Ok(price) = normalize_price(price, decimals) else {
+        return;
+    };
+    let asset = Some(asset.clone());
+    if let Ok(config) = set_asset_price(env, asset.clone(), price, env.ledger().timestamp()) {
+        emit_config_updated(env, actor, asset, config);
+    }
+}
+
+/// Record a price for a listed asset, as of `updated_at`
+pub(crate) fn set_asset_price(
+    env: &Env,
+    asset: Option<Address>,
+    price: i128,
+    updated_at: u64,
+) -> Result<AssetConfig, CrossAssetError> {
+    let asset_key = AssetKey::from_option(asset);
+    let mut config = get_stored_asset_config(env, &asset_key)?;
+    config.price = price;
+    config.price_updated_at = updated_at;
+
+    let mut configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
+
+    configs.set(asset_key, config.clone());
+    storage::set(env, &ASSET_CONFIGS, &configs);
+    Ok(config)
+}
+
+/// Get user's position for a specific asset
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User address
 /// * `asset` - Asset address (None for XLM)
-/// * `position` - Updated position data
+///
+/// # Returns
+/// Asset position or default empty position
+pub fn get_user_asset_position(env: &Env, user: &Address, asset: Option<Address>) -> AssetPosition {
+    let key = UserAssetKey::new(user.clone(), asset);
+    let positions: Map<UserAssetKey, AssetPosition> =
+        storage::get(env, &USER_POSITIONS).unwrap_or(Map::new(env));
+
+    positions.get(key).unwrap_or(AssetPosition {
+        collateral: 0,
+        debt_principal: 0,
+        accrued_interest: 0,
+        last_updated: env.ledger().timestamp(),
+        use_as_collateral: true,
+        supplied_since: 0,
+    })
+}
+
+/// Update user's position for a specific asset
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User address
+/// * `asset` - Asset address (None for XLM)
+/// * `position` - Updated position data
 fn set_user_asset_position(
     env: &Env,
     user: &Address,
     asset: Option<Address>,
-    position: AssetPosition,
+    position: AssetPosition,
+) -> Result<(), CrossAssetError> {
+    let key = UserAssetKey::new(user.clone(), asset);
+    let mut positions: Map<UserAssetKey, AssetPosition> =
+        storage::get(env, &USER_POSITIONS).unwrap_or(Map::new(env));
+
+    positions.set(key, position);
+    storage::set(env, &USER_POSITIONS, &positions);
+    sync_category_debt(env, user)
+}
+
+/// Accrue interest on a user's debt in one asset up to the current time.
+///
+/// Adds simple interest at the market's current borrow rate (see
+/// `rate_strategy`) to `accrued_interest` and to the market's total borrow,
+/// checkpoints it in the `interest_history`, and stores the position. Must
+/// be called before any operation that refreshes `last_updated`, or the
+/// elapsed period is never charged.
+///
+/// Cross-asset debt is not backed by tokens, so its interest is not credited
+/// to the market's lending pool (see `lending_pool`).
+///
+/// Advances the market's borrow index first, and emits an
+/// `InterestAccruedEvent` when interest is charged.
+///
+/// # Returns
+/// The position with interest accrued.
+pub(crate) fn accrue_interest(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+) -> Result<AssetPosition, CrossAssetError> {
+    let borrow_index = accrue_borrow_index(env, asset.clone())?;
+    let mut position = get_user_asset_position(env, user, asset.clone());
+    let now = env.ledger().timestamp();
+    if position.debt_principal == 0 || now <= position.last_updated {
+        return Ok(position);
+    }
+
+    let rate_bps = get_asset_borrow_rate(env, asset.clone())?;
+    let interest = calculate_accrued_interest(
+        position.debt_principal,
+        position.last_updated,
+        now,
+        rate_bps,
+    )?;
+    if interest == 0 {
+        return Ok(position);
+    }
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    record_interest(
+        env,
+        user,
+        &asset_key,
+        InterestCheckpoint {
+            start: position.last_updated,
+            end: now,
+            principal: position.debt_principal,
+            rate_bps,
+            interest,
+        },
+    );
+    let bucket = match get_interest_mode(env, &asset_key) {
+        InterestMode::Simple => &mut position.accrued_interest,
+        InterestMode::Compounding => &mut position.debt_principal,
+    };
+    *bucket = bucket
+        .checked_add(interest)
+        .ok_or(CrossAssetError::Overflow)?;
+    position.last_updated = now;
+    set_user_asset_position(env, user, asset.clone(), position.clone())?;
+
+    update_total_borrow(env, &asset_key, interest);
+
+    emit_interest_accrued(
+        env,
+        InterestAccruedEvent {
+            asset: asset.clone(),
+            borrow_interest: interest,
+            reserves: 0,
+            borrow_index,
+            liquidity_index: get_liquidity_index(env, asset)
+                .map_err(|_| CrossAssetError::Overflow)?,
+            timestamp: now,
+        },
+    );
+
+    Ok(position)
+}
+
+/// Advance a market's borrow index to the current ledger time.
+///
+/// Grows the index by simple interest at the market's current borrow rate
+/// over the time since it was last advanced, so the index compounds across
+/// accruals. A market's first call starts its index at [`BORROW_INDEX_ONE`].
+///
+/// # Returns
+/// The new borrow index.
+pub(crate) fn accrue_borrow_index(
+    env: &Env,
+    asset: Option<Address>,
+) -> Result<i128, CrossAssetError> {
+    let asset_key = AssetKey::from_option(asset.clone());
+    let now = env.ledger().timestamp();
+    let mut indexes: Map<AssetKey, BorrowIndex> =
+        storage::get(env, &BORROW_INDEXES).unwrap_or(Map::new(env));
+    let mut borrow_index = indexes.get(asset_key.clone()).unwrap_or(BorrowIndex {
+        index: BORROW_INDEX_ONE,
+        last_update: now,
+    });
+    if now > borrow_index.last_update {
+        let growth = calculate_accrued_interest(
+            borrow_index.index,
+            borrow_index.last_update,
+            now,
+            get_asset_borrow_rate(env, asset)?,
+        )?;
+        borrow_index.index = Amount::new(borrow_index.index)
+            .checked_add(Amount::new(growth))?
+            .get();
+    }
+    borrow_index.last_update = now;
+    indexes.set(asset_key, borrow_index.clone());
+    storage::set(env, &BORROW_INDEXES, &indexes);
+    Ok(borrow_index.index)
+}
+
+/// Get a market's borrow index as of its last accrual (9 decimals).
+///
+/// [`BORROW_INDEX_ONE`] for a market that has never accrued.
+pub fn get_borrow_index(env: &Env, asset: Option<Address>) -> i128 {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, BorrowIndex>>(env, &BORROW_INDEXES)
+        .and_then(|indexes| indexes.get(AssetKey::from_option(asset)))
+        .map(|borrow_index| borrow_index.index)
+        .unwrap_or(BORROW_INDEX_ONE)
+}
+
+/// Estimate the interest a position has accrued since it was last touched.
+///
+/// Uses the market's current borrow rate over the elapsed time, capped at
+/// [`MAX_INTEREST_STALENESS`]. Nothing is written to storage.
+fn pending_interest(
+    env: &Env,
+    asset: Option<Address>,
+    position: &AssetPosition,
+) -> Result<i128, CrossAssetError> {
+    if position.debt_principal == 0 {
+        return Ok(0);
+    }
+    let now = env.ledger().timestamp().min(
+        position
+            .last_updated
+            .saturating_add(MAX_INTEREST_STALENESS.get()),
+    );
+    let rate_bps = get_asset_borrow_rate(env, asset)?;
+    Ok(calculate_accrued_interest(
+        position.debt_principal,
+        position.last_updated,
+        now,
+        rate_bps,
+    )?)
+}
+
+/// Calculate a unified position summary across all registered assets.
+///
+/// Iterates over all configured assets, aggregates collateral and debt values
+/// weighted by their respective factors, and computes the health factor.
+/// Debt includes an estimate of interest not yet accrued (see
+/// `pending_interest`). Prices older than 1 hour are rejected.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User address
+///
+/// # Returns
+/// [`UserPositionSummary`] with health factor, liquidation status, and borrow capacity.
+///
+/// # Errors
+/// * `PriceStale` - Any asset with a non-zero position has a price older than 1 hour
+pub fn get_user_position_summary(
+    env: &Env,
+    user: &Address,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    compute_position_summary(env, user, &Map::new(env), 0)
+}
+
+/// Position summary with collateral reduced by the post-pause recovery
+/// haircut. Used to gate new borrows and withdrawals; liquidation eligibility
+/// is judged on unadjusted values.
+fn recovery_position_summary(
+    env: &Env,
+    user: &Address,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    compute_position_summary(env, user, &Map::new(env), -get_recovery_haircut(env))
+}
+
+/// Require a user's position, less the recovery haircut, to have a health
+/// factor of at least 1.0 if it has debt
+///
+/// Used where the per-operation check was deferred (see `batch`).
+///
+/// # Errors
+/// * `UnhealthyPosition` - Health factor is below 1.0
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub(crate) fn require_healthy(
+    env: &Env,
+    user: &Address,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    let summary = recovery_position_summary(env, user)?;
+    if summary.total_debt_value > 0 && summary.health_factor < HEALTH_FACTOR_ONE.get() {
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+    warn_if_at_risk(env, user, summary.health_factor);
+    Ok(summary)
+}
+
+/// Compute a user's position summary under hypothetical price shocks.
+///
+/// Each shock is `(asset, shock_bps)` where `shock_bps` is a signed change in
+/// basis points applied to the asset's current price (e.g. `-2300` models a
+/// 23% drop). Assets without a shock keep their current price. Nothing is
+/// written to storage.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User address
+/// * `shocks` - Price shocks to apply
+///
+/// # Returns
+/// [`UserPositionSummary`] as it would be at the shocked prices.
+///
+/// # Errors
+/// * `InvalidPrice` - A shock is below -100%
+/// * `PriceStale` - Any asset with a non-zero position has a price older than 1 hour
+pub fn stress_test_position(
+    env: &Env,
+    user: &Address,
+    shocks: Vec<(Option<Address>, i128)>,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    let mut shock_map: Map<AssetKey, i128> = Map::new(env);
+    for (asset, shock_bps) in shocks.iter() {
+        if shock_bps < -BASIS_POINTS_SCALE.get() {
+            return Err(CrossAssetError::InvalidPrice);
+        }
+        shock_map.set(AssetKey::from_option(asset), shock_bps);
+    }
+
+    compute_position_summary(env, user, &shock_map, 0)
+}
+
+/// Shared summary computation. `shocks` adjusts individual asset prices;
+/// `collateral_shock_bps` is applied on top to collateral values only.
+fn compute_position_summary(
+    env: &Env,
+    user: &Address,
+    shocks: &Map<AssetKey, i128>,
+    collateral_shock_bps: i128,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    summarize_position(
+        env,
+        user,
+        &get_asset_list(env),
+        &get_asset_configs(env),
+        shocks,
+        collateral_shock_bps,
+    )
+}
+
+/// [`compute_position_summary`] over the given markets and configurations
+/// instead of the stored ones. Used to project the effect of config changes.
+pub(crate) fn summarize_position(
+    env: &Env,
+    user: &Address,
+    asset_list: &Vec<AssetKey>,
+    configs: &Map<AssetKey, AssetConfig>,
+    shocks: &Map<AssetKey, i128>,
+    collateral_shock_bps: i128,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    let mut total_collateral_value = Amount::ZERO;
+    let mut weighted_collateral_value = Amount::ZERO;
+    let mut total_debt_value = Amount::ZERO;
+    let mut weighted_debt_value = Amount::ZERO;
+
+    for i in 0..asset_list.len() {
+        let asset_key = asset_list.get(i).unwrap();
+
+        if let Some(config) = configs.get(asset_key.clone()) {
+            let asset_option = asset_key.to_option();
+            let position = get_user_asset_position(env, user, asset_option.clone());
+
+            if position.collateral == 0 && position.debt_principal == 0 {
+                continue;
+            }
+
+            let current_time = env.ledger().timestamp();
+            if current_time > config.price_updated_at
+                && current_time - config.price_updated_at > MAX_PRICE_AGE.get()
+            {
+                return Err(CrossAssetError::PriceStale);
+            }
+
+            let price = match shocks.get(asset_key.clone()) {
+                Some(shock_bps) => Amount::new(config.price)
+                    .mul_div(
+                        BASIS_POINTS_SCALE.get() + shock_bps,
+                        BASIS_POINTS_SCALE.get(),
+                    )?
+                    .get(),
+                None => config.price,
+            };
+
+            let collateral_price = Amount::new(price)
+                .mul_div(
+                    BASIS_POINTS_SCALE.get() + collateral_shock_bps,
+                    BASIS_POINTS_SCALE.get(),
+                )?
+                .get();
+            let collateral_value = value_at(position.collateral, collateral_price)?;
+            total_collateral_value = total_collateral_value.checked_add(collateral_value)?;
+
+            if config.can_collateralize && position.use_as_collateral {
+                weighted_collateral_value = weighted_collateral_value.checked_add(
+                    collateral_value.mul_div(config.collateral_factor, BASIS_POINTS_SCALE.get())?,
+                )?;
+            }
+
+            let total_debt = Amount::new(position.debt_principal)
+                .checked_add(Amount::new(position.accrued_interest))?
+                .checked_add(Amount::new(pending_interest(env, asset_option, &position)?))?;
+            let debt_value = value_at(total_debt.get(), price)?;
+            total_debt_value = total_debt_value.checked_add(debt_value)?;
+
+            if config.can_borrow {
+                weighted_debt_value = weighted_debt_value.checked_add(
+                    debt_value.mul_div(config.borrow_factor, BASIS_POINTS_SCALE.get())?,
+                )?;
+            }
+        }
+    }
+
+    // Calculate health factor (weighted_collateral / weighted_debt * 10000)
+    // Health factor of 1.0 = 10000, below 1.0 can be liquidated
+    let total_collateral_value = total_collateral_value.get();
+    let weighted_collateral_value = weighted_collateral_value.get();
+    let total_debt_value = total_debt_value.get();
+    let weighted_debt_value = weighted_debt_value.get();
+    let health_factor = if weighted_debt_value > 0 {
+        Amount::new(weighted_collateral_value)
+            .mul_div(HEALTH_FACTOR_ONE.get(), weighted_debt_value)?
+            .get()
+    } else {
+        i128::MAX // No debt = infinite health
+    };
+
+    // Position is liquidatable if health factor < 1.0 (10000)
+    let is_liquidatable = health_factor < HEALTH_FACTOR_ONE.get() && weighted_debt_value > 0;
+
+    // Calculate remaining borrow capacity
+    let borrow_capacity = if weighted_collateral_value > weighted_debt_value {
+        weighted_collateral_value - weighted_debt_value
+    } else {
+        0
+    };
+
+    Ok(UserPositionSummary {
+        total_collateral_value,
+        weighted_collateral_value,
+        total_debt_value,
+        weighted_debt_value,
+        health_factor,
+        is_liquidatable,
+        borrow_capacity,
+    })
+}
+
+/// Deposit collateral for a specific asset.
+///
+/// Requires user authorization. Validates the asset is enabled for collateral
+/// and that the deposit does not exceed the supply cap.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User depositing collateral (must authorize)
+/// * `asset` - Asset to deposit (`None` for XLM)
+/// * `amount` - Amount to deposit
+///
+/// # Returns
+/// Updated [`AssetPosition`] after the deposit.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `AssetNotConfigured` - Asset is not registered
+/// * `AssetDisabled` - Asset is not enabled for collateral
+/// * `SupplyCapExceeded` - Deposit would exceed the asset's supply cap
+/// * `IsolationConflict` - Deposit would mix isolated collateral with other collateral
+pub fn cross_asset_deposit(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    user.require_auth();
+    deposit_authorized(env, user, asset, amount)
+}
+
+/// [`cross_asset_deposit`] for callers that already required `user`'s auth
+pub(crate) fn deposit_authorized(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    require_not_paused(env)?;
+    require_asset_not_paused(env, &AssetKey::from_option(asset.clone()), PAUSE_DEPOSIT)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let config = get_asset_config(env, &asset_key)?;
+
+    if !config.can_collateralize {
+        return Err(CrossAssetError::AssetDisabled);
+    }
+
+    if config.max_supply > 0 {
+        let total_supply = get_total_supply(env, &asset_key);
+        if total_supply + amount > config.max_supply {
+            return Err(CrossAssetError::SupplyCapExceeded);
+        }
+    }
+
+    let mut position = accrue_interest(env, &user, asset.clone())?;
+    if position.use_as_collateral {
+        require_collateral_compatible(env, &user, &asset_key)?;
+    }
+
+    position.collateral += amount;
+    position.last_updated = env.ledger().timestamp();
+    refresh_supply_tenure(env, &mut position);
+
+    set_user_asset_position(env, &user, asset.clone(), position.clone())?;
+    update_total_supply(env, &asset_key, amount);
+    record_market_activity(env, &asset_key, false);
+    rewards::settle(env, &user, &asset_key);
+    register_user(env, &user);
+    emit_deposit(
+        env,
+        DepositEvent {
+            user,
+            asset: asset.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    process_borrow_queue(env, asset);
+
+    Ok(position)
+}
+
+/// Withdraw collateral for a specific asset.
+///
+/// Requires user authorization. Checks that the user has sufficient collateral
+/// and that the withdrawal does not bring the health factor below 1.0. If the
+/// health check fails, the withdrawal is rolled back.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User withdrawing collateral (must authorize)
+/// * `asset` - Asset to withdraw (`None` for XLM)
+/// * `amount` - Amount to withdraw
+///
+/// # Returns
+/// Updated [`AssetPosition`] after the withdrawal.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `InsufficientCollateral` - User's collateral balance is below `amount`
+/// * `UnhealthyPosition` - Withdrawal would drop health factor below 1.0
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn cross_asset_withdraw(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    user.require_auth();
+    withdraw_authorized(env, user, asset, amount)
+}
+
+/// [`cross_asset_withdraw`] for callers that already required `user`'s auth
+pub(crate) fn withdraw_authorized(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    require_not_paused(env)?;
+    require_asset_not_paused(env, &AssetKey::from_option(asset.clone()), PAUSE_WITHDRAW)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+
+    let original = accrue_interest(env, &user, asset.clone())?;
+
+    if original.collateral < amount {
+        return Err(CrossAssetError::InsufficientCollateral);
+    }
+
+    let mut position = original.clone();
+    position.collateral -= amount;
+    position.last_updated = env.ledger().timestamp();
+    refresh_supply_tenure(env, &mut position);
+
+    set_user_asset_position(env, &user, asset.clone(), position.clone())?;
+
+    let summary = recovery_position_summary(env, &user)?;
+
+    if summary.total_debt_value > 0
+        && summary.health_factor < HEALTH_FACTOR_ONE.get()
+        && !is_health_check_deferred(env, &user)
+    {
+        set_user_asset_position(env, &user, asset, original)?;
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+
+    update_total_supply(env, &asset_key, -amount);
+    record_market_activity(env, &asset_key, false);
+    rewards::settle(env, &user, &asset_key);
+    emit_withdrawal(
+        env,
+        WithdrawalEvent {
+            user: user.clone(),
+            asset,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    warn_if_at_risk(env, &user, summary.health_factor);
+
+    Ok(position)
+}
+
+/// Borrow a specific asset against cross-asset collateral.
+///
+/// Requires user authorization. Validates the asset is enabled for borrowing,
+/// checks the borrow and utilization caps, and verifies the post-borrow health factor stays
+/// above 1.0. If the health check fails, the borrow is rolled back.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User borrowing (must authorize)
+/// * `asset` - Asset to borrow (`None` for XLM)
+/// * `amount` - Amount to borrow
+///
+/// # Returns
+/// Updated [`AssetPosition`] after the borrow.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `AssetNotConfigured` - Asset is not registered
+/// * `AssetDisabled` - Asset is not enabled for borrowing
+/// * `BorrowCapExceeded` - Borrow would exceed the asset's borrow cap
+/// * `UtilizationCapExceeded` - Borrow would push utilization above the market's
+///   cap (see `borrow_queue` to queue it instead)
+/// * `BorrowSizeExceeded` - Borrow exceeds the market's per-ledger share of
+///   available liquidity (split it across ledgers)
+/// * `ExceedsBorrowCapacity` - Health factor would drop below 1.0
+/// * `DebtBelowMinimum` - Debt in the asset would stay below its minimum
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn cross_asset_borrow(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    user.require_auth();
+    borrow_authorized(env, user, asset, amount)
+}
+
+/// [`cross_asset_borrow`] for callers that already required `user`'s auth
+pub(crate) fn borrow_authorized(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    require_not_paused(env)?;
+    borrow_for(env, user, asset, amount)
+}
+
+/// Borrow on behalf of a user who has already authorized it.
+///
+/// Shared by [`cross_asset_borrow`] and the borrow queue, which fills requests
+/// authorized when they were queued. Leaves the position unchanged (apart from
+/// interest accrual) when it fails.
+pub(crate) fn borrow_for(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    let asset_key = AssetKey::from_option(asset.clone());
+    let config = get_asset_config(env, &asset_key)?;
+
+    if !config.can_borrow {
+        return Err(CrossAssetError::AssetDisabled);
+    }
+    require_asset_not_paused(env, &asset_key, PAUSE_BORROW)?;
+
+    let total_borrow = get_total_borrow(env, &asset_key);
+    if config.max_borrow > 0 && total_borrow + amount > config.max_borrow {
+        return Err(CrossAssetError::BorrowCapExceeded);
+    }
+
+    if let Some(cap_bps) = get_utilization_cap(env, &asset_key) {
+        let total_supply = get_total_supply(env, &asset_key);
+        let borrowed = total_borrow
+            .checked_add(amount)
+            .and_then(|borrowed| borrowed.checked_mul(BASIS_POINTS_SCALE.get()))
+            .ok_or(CrossAssetError::Overflow)?;
+        if total_supply <= 0 || borrowed / total_supply > cap_bps {
+            return Err(CrossAssetError::UtilizationCapExceeded);
+        }
+    }
+    let ledger_borrows = check_borrow_size(env, &asset_key, total_borrow, amount)?;
+
+    let original = accrue_interest(env, &user, asset.clone())?;
+    let opens_loan = original.debt_principal == 0 && original.accrued_interest == 0;
+
+    let mut position = original.clone();
+    position.debt_principal += amount;
+    position.last_updated = env.ledger().timestamp();
+    if is_dust_debt(
+        env,
+        &asset_key,
+        position.debt_principal + position.accrued_interest,
+    ) {
+        return Err(CrossAssetError::DebtBelowMinimum);
+    }
+
+    let category_debt = get_category_debt_shares(env, &user);
+    set_user_asset_position(env, &user, asset.clone(), position.clone())?;
+
+    let allowed = recovery_position_summary(env, &user).and_then(|summary| {
+        if summary.health_factor < HEALTH_FACTOR_ONE.get() && !is_health_check_deferred(env, &user)
+        {
+            return Err(CrossAssetError::ExceedsBorrowCapacity);
+        }
+        if let Some(ceiling) = get_isolation_ceiling(env, &user) {
+            if summary.total_debt_value > ceiling {
+                return Err(CrossAssetError::DebtCeilingExceeded);
+            }
+        }
+        require_category_headroom(env, &user, &category_debt)?;
+        Ok(summary.health_factor)
+    });
+    let health_factor = match allowed {
+        Ok(health_factor) => health_factor,
+        Err(err) => {
+            set_user_asset_position(env, &user, asset, original)?;
+            return Err(err);
+        }
+    };
+
+    update_total_borrow(env, &asset_key, amount);
+    if let Some(ledger_borrows) = ledger_borrows {
+        let mut all: Map<AssetKey, LedgerBorrows> =
+            storage::get(env, &LEDGER_BORROWS).unwrap_or(Map::new(env));
+        all.set(asset_key.clone(), ledger_borrows);
+        storage::set(env, &LEDGER_BORROWS, &all);
+    }
+    record_market_activity(env, &asset_key, true);
+    register_user(env, &user);
+    register_borrower(env, &user);
+    if opens_loan {
+        record_loan_opened(env, &user);
+    }
+    emit_borrow(
+        env,
+        BorrowEvent {
+            user: user.clone(),
+            asset,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    warn_if_at_risk(env, &user, health_factor);
+
+    Ok(position)
+}
+
+/// Repay debt for a specific asset.
+///
+/// Requires user authorization. Repayment is capped at the total outstanding
+/// debt (principal + accrued interest). Interest is paid first, then principal.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User repaying debt (must authorize)
+/// * `asset` - Asset to repay (`None` for XLM)
+/// * `amount` - Amount to repay (capped at total debt)
+///
+/// # Returns
+/// Updated [`AssetPosition`] after the repayment.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `AssetPaused` - Repayments of the asset are paused
+/// * `DebtBelowMinimum` - Remaining debt would be nonzero but below the
+///   asset's minimum
+pub fn cross_asset_repay(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    user.require_auth();
+    repay_authorized(env, user, asset, amount)
+}
+
+/// [`cross_asset_repay`] for callers that already required `user`'s auth
+pub(crate) fn repay_authorized(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    require_not_paused(env)?;
+    require_asset_not_paused(env, &AssetKey::from_option(asset.clone()), PAUSE_REPAY)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+
+    // Get current position, with interest accrued up to now
+    let mut position = accrue_interest(env, &user, asset.clone())?;
+
+    let total_debt = position.debt_principal + position.accrued_interest;
+    let repay_amount = amount.min(total_debt);
+    if is_dust_debt(env, &asset_key, total_debt - repay_amount) {
+        return Err(CrossAssetError::DebtBelowMinimum);
+    }
+
+    // Pay interest first, then principal
+    if repay_amount <= position.accrued_interest {
+        position.accrued_interest -= repay_amount;
+    } else {
+        let remaining = repay_amount - position.accrued_interest;
+        position.accrued_interest = 0;
+        position.debt_principal -= remaining;
+    }
+
+    position.last_updated = env.ledger().timestamp();
+
+    // Update storage
+    set_user_asset_position(env, &user, asset.clone(), position.clone())?;
+    update_total_borrow(env, &asset_key, -repay_amount);
+    record_market_activity(env, &asset_key, false);
+    if total_debt > 0 && repay_amount == total_debt {
+        record_loan_repaid(env, &user);
+    }
+    emit_repay(
+        env,
+        RepayEvent {
+            user,
+            asset: asset.clone(),
+            amount: repay_amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    process_borrow_queue(env, asset);
+
+    Ok(position)
+}
+
+/// Repay debt using the user's own supplied balance of the same asset.
+///
+/// Burns collateral and debt against each other internally, with no token
+/// transfers or allowances. The amount is capped at the smaller of the
+/// supplied balance and the outstanding debt; interest is paid first. If
+/// debt remains afterwards, the position must still have a health factor of
+/// at least 1.0.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User repaying (must authorize)
+/// * `asset` - Asset to net off (`None` for XLM)
+/// * `amount` - Maximum amount to repay
+///
+/// # Returns
+/// Updated [`AssetPosition`] after the repayment.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `InvalidAmount` - Amount is not positive
+/// * `InsufficientCollateral` - User has no supplied balance or no debt in this asset
+/// * `UnhealthyPosition` - Remaining debt would leave health factor below 1.0
+/// * `DebtBelowMinimum` - Remaining debt would be nonzero but below the
+///   asset's minimum
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn repay_from_supply(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    user.require_auth();
+    require_not_paused(env)?;
+    require_asset_not_paused(env, &AssetKey::from_option(asset.clone()), PAUSE_REPAY)?;
+
+    if amount <= 0 {
+        return Err(CrossAssetError::InvalidAmount);
+    }
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let original = accrue_interest(env, &user, asset.clone())?;
+
+    let total_debt = original.debt_principal + original.accrued_interest;
+    let repay_amount = amount.min(total_debt).min(original.collateral);
+    if repay_amount == 0 {
+        return Err(CrossAssetError::InsufficientCollateral);
+    }
+    if is_dust_debt(env, &asset_key, total_debt - repay_amount) {
+        return Err(CrossAssetError::DebtBelowMinimum);
+    }
+
+    let mut position = original.clone();
+    position.collateral -= repay_amount;
+    if repay_amount <= position.accrued_interest {
+        position.accrued_interest -= repay_amount;
+    } else {
+        position.debt_principal -= repay_amount - position.accrued_interest;
+        position.accrued_interest = 0;
+    }
+    position.last_updated = env.ledger().timestamp();
+    refresh_supply_tenure(env, &mut position);
+
+    set_user_asset_position(env, &user, asset.clone(), position.clone())?;
+
+    let summary = get_user_position_summary(env, &user)?;
+    if summary.weighted_debt_value > 0 && summary.health_factor < HEALTH_FACTOR_ONE.get() {
+        set_user_asset_position(env, &user, asset, original)?;
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+
+    update_total_supply(env, &asset_key, -repay_amount);
+    update_total_borrow(env, &asset_key, -repay_amount);
+    record_market_activity(env, &asset_key, false);
+    if repay_amount == total_debt {
+        record_loan_repaid(env, &user);
+    }
+    emit_repay(
+        env,
+        RepayEvent {
+            user,
+            asset: asset.clone(),
+            amount: repay_amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    process_borrow_queue(env, asset);
+
+    Ok(position)
+}
+
+/// Set the AMM through which debt is repaid with other collateral, or
+/// remove it with `None`.
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is not the admin or a risk admin
+/// * `InvalidAmount` - The slippage bound is negative or above
+///   [`MAX_SWAP_SLIPPAGE_BPS`]
+pub fn set_swap_adapter(
+    env: &Env,
+    caller: Address,
+    adapter: Option<SwapAdapter>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    match adapter {
+        Some(adapter) => {
+            if adapter.max_slippage_bps < 0 || adapter.max_slippage_bps > MAX_SWAP_SLIPPAGE_BPS {
+                return Err(CrossAssetError::InvalidAmount);
+            }
+            storage::set(env, &SWAP_ADAPTER, &adapter);
+        }
+        None => storage::remove(env, &SWAP_ADAPTER),
+    }
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_swap_adapter"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get the AMM through which debt is repaid with other collateral, if set.
+pub fn get_swap_adapter(env: &Env) -> Option<SwapAdapter> {
+    storage::get(env, &SWAP_ADAPTER)
+}
+
+/// Repay debt by swapping collateral of another asset into the debt asset.
+///
+/// Swaps at most `max_pay_amount` of the user's `pay_asset` collateral, and
+/// no more than clears the debt at the worst accepted rate, through the
+/// swap adapter's AMM. The swap must return at least the oracle value of the
+/// collateral swapped less the adapter's slippage bound. The proceeds repay
+/// interest first, then principal; any surplus is supplied to the user's
+/// position in the debt asset. If debt remains afterwards, the position must
+/// still have a health factor of at least 1.0.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - User repaying (must authorize)
+/// * `debt_asset` - Asset to repay (`None` for XLM)
+/// * `pay_asset` - Collateral asset to swap (`None` for XLM)
+/// * `max_pay_amount` - Maximum collateral to swap
+///
+/// # Returns
+/// [`SwapRepayment`] with the amounts swapped and repaid.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `AssetPaused` - Repayments of the debt asset or withdrawals of the pay
+///   asset are paused
+/// * `InvalidAmount` - Amount is not positive
+/// * `DebtAssetMismatch` - The pay asset is the debt asset
+/// * `SwapAdapterNotSet` - No swap adapter is configured
+/// * `AssetNotConfigured` - An asset is not registered
+/// * `InsufficientCollateral` - User has no debt in the debt asset or no
+///   collateral in the pay asset
+/// * `SwapFailed` - The AMM rejected the swap
+/// * `SlippageExceeded` - The swap returned less than the slippage bound
+/// * `DebtBelowMinimum` - Remaining debt would be nonzero but below the
+///   asset's minimum
+/// * `UnhealthyPosition` - Remaining debt would leave health factor below 1.0
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn repay_with_collateral_asset(
+    env: &Env,
+    user: Address,
+    debt_asset: Option<Address>,
+    pay_asset: Option<Address>,
+    max_pay_amount: i128,
+) -> Result<SwapRepayment, CrossAssetError> {
+    user.require_auth();
+    require_not_paused(env)?;
+    let debt_key = AssetKey::from_option(debt_asset.clone());
+    let pay_key = AssetKey::from_option(pay_asset.clone());
+    require_asset_not_paused(env, &debt_key, PAUSE_REPAY)?;
+    require_asset_not_paused(env, &pay_key, PAUSE_WITHDRAW)?;
+
+    if max_pay_amount <= 0 {
+        return Err(CrossAssetError::InvalidAmount);
+    }
+    if debt_key == pay_key {
+        return Err(CrossAssetError::DebtAssetMismatch);
+    }
+    let adapter = get_swap_adapter(env).ok_or(CrossAssetError::SwapAdapterNotSet)?;
+
+    let debt_config = get_asset_config(env, &debt_key)?;
+    let pay_config = get_asset_config(env, &pay_key)?;
+    let mut debt_position = accrue_interest(env, &user, debt_asset.clone())?;
+    let mut pay_position = accrue_interest(env, &user, pay_asset.clone())?;
+    let total_debt = Amount::new(debt_position.debt_principal)
+        .checked_add(Amount::new(debt_position.accrued_interest))?
+        .get();
+    if total_debt == 0 || pay_position.collateral == 0 {
+        return Err(CrossAssetError::InsufficientCollateral);
+    }
+
+    // Swap no more than clears the debt at the worst accepted rate
+    let scale = BASIS_POINTS_SCALE.get();
+    let worst_rate = scale - adapter.max_slippage_bps;
+    let pay_rate = pay_config
+        .price
+        .checked_mul(worst_rate)
+        .ok_or(CrossAssetError::Overflow)?;
+    let debt_rate = debt_config
+        .price
+        .checked_mul(scale)
+        .ok_or(CrossAssetError::Overflow)?;
+    let needed = Amount::new(total_debt)
+        .mul_div_ceil(debt_rate, pay_rate)?
+        .get();
+    let amount_paid = max_pay_amount.min(pay_position.collateral).min(needed);
+    let min_amount_out = Amount::new(amount_paid).mul_div(pay_rate, debt_rate)?.get();
+
+    let timestamp = env.ledger().timestamp();
+    let params = AmmSwapParams {
+        protocol: adapter.protocol,
+        token_in: pay_asset.clone(),
+        token_out: debt_asset.clone(),
+        amount_in: amount_paid,
+        min_amount_out,
+        slippage_tolerance: adapter.max_slippage_bps,
+        deadline: timestamp,
+    };
+    let amount_received = match env.try_invoke_contract::<i128, soroban_sdk::Error>(
+        &adapter.amm,
+        &Symbol::new(env, "execute_swap"),
+        vec![
+            env,
+            env.current_contract_address().into_val(env),
+            params.into_val(env),
+        ],
+    ) {
+        Ok(Ok(amount)) => amount,
+        _ => return Err(CrossAssetError::SwapFailed),
+    };
+    if amount_received < min_amount_out {
+        return Err(CrossAssetError::SlippageExceeded);
+    }
+
+    let debt_repaid = amount_received.min(total_debt);
+    if is_dust_debt(env, &debt_key, total_debt - debt_repaid) {
+        return Err(CrossAssetError::DebtBelowMinimum);
+    }
+    let surplus = amount_received - debt_repaid;
+
+    // Pay interest first, then principal
+    if debt_repaid <= debt_position.accrued_interest {
+        debt_position.accrued_interest -= debt_repaid;
+    } else {
+        debt_position.debt_principal -= debt_repaid - debt_position.accrued_interest;
+        debt_position.accrued_interest = 0;
+    }
+    debt_position.collateral += surplus;
+    debt_position.last_updated = timestamp;
+    refresh_supply_tenure(env, &mut debt_position);
+    set_user_asset_position(env, &user, debt_asset.clone(), debt_position)?;
+    pay_position.collateral -= amount_paid;
+    pay_position.last_updated = timestamp;
+    refresh_supply_tenure(env, &mut pay_position);
+    set_user_asset_position(env, &user, pay_asset.clone(), pay_position)?;
+
+    let summary = get_user_position_summary(env, &user)?;
+    if summary.weighted_debt_value > 0 && summary.health_factor < HEALTH_FACTOR_ONE.get() {
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+
+    update_total_supply(env, &pay_key, -amount_paid);
+    update_total_supply(env, &debt_key, surplus);
+    update_total_borrow(env, &debt_key, -debt_repaid);
+    record_market_activity(env, &pay_key, false);
+    record_market_activity(env, &debt_key, false);
+    if debt_repaid == total_debt {
+        record_loan_repaid(env, &user);
+    }
+    emit_repay(
+        env,
+        RepayEvent {
+            user: user.clone(),
+            asset: debt_asset.clone(),
+            amount: debt_repaid,
+            timestamp,
+        },
+    );
+    emit_collateral_swap_repay(
+        env,
+        CollateralSwapRepayEvent {
+            user,
+            debt_asset: debt_asset.clone(),
+            pay_asset,
+            amount_paid,
+            amount_received,
+            debt_repaid,
+            timestamp,
+        },
+    );
+    process_borrow_queue(env, debt_asset);
+
+    Ok(SwapRepayment {
+        amount_paid,
+        amount_received,
+        debt_repaid,
+        health_factor: summary.health_factor,
+    })
+}
+
+/// Liquidate an unhealthy position, seizing collateral from assets the
+/// liquidator chooses.
+///
+/// Each seizure is `(collateral_asset, repay_amount)`: the liquidator repays
+/// `repay_amount` of the borrower's `debt_asset` debt and receives collateral
+/// of that value, plus the liquidation incentive, from `collateral_asset`. A
+/// single entry seizes from one asset; several entries split the seizure
+/// across assets, so an illiquid collateral market can be avoided. Seized
+/// collateral is credited to the liquidator's own position in that asset, and
+/// the repayment is paid with the liquidator's own collateral in
+/// `debt_asset`, as in soft liquidations.
+///
+/// Collateral held in savings mode cannot be seized. The total repayment is
+/// capped by the close factor of the borrower's debt in `debt_asset`. With a
+/// liquidation target health factor set, each seizure's repayment is also
+/// reduced to what brings the position to the target, and seizures left
+/// with nothing to repay are skipped. If no
+/// collateral remains afterwards, the borrower's remaining debt in every
+/// asset is moved into that market's bad debt.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `liquidator` - Address repaying the debt (must authorize)
+/// * `borrower` - Owner of the liquidatable position
+/// * `debt_asset` - Asset of the debt being repaid (`None` for XLM)
+/// * `seizures` - `(collateral_asset, repay_amount)` pairs
+///
+/// # Returns
+/// [`CrossAssetLiquidation`] with the debt repaid and collateral seized.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `InvalidAmount` - No seizures, or a repay amount is not positive
+/// * `NotLiquidatable` - Borrower's health factor is at least 1.0
+/// * `ExceedsCloseFactor` - Total repayment exceeds the close factor limit
+/// * `AssetNotConfigured` - An asset is not registered, or risk parameters are not set
+/// * `AssetDisabled` - A seizure targets collateral that cannot be seized
+/// * `InsufficientCollateral` - Borrower lacks the collateral for a seizure,
+///   or the liquidator lacks the `debt_asset` collateral to repay with
+/// * `UnhealthyPosition` - The liquidator would have a health factor below
+///   1.0 afterwards
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn cross_asset_liquidate(
+    env: &Env,
+    liquidator: Address,
+    borrower: Address,
+    debt_asset: Option<Address>,
+    seizures: Vec<(Option<Address>, i128)>,
+) -> Result<CrossAssetLiquidation, CrossAssetError> {
+    liquidator.require_auth();
+    require_not_paused(env)?;
+    require_asset_not_paused(
+        env,
+        &AssetKey::from_option(debt_asset.clone()),
+        PAUSE_LIQUIDATE,
+    )?;
+
+    if seizures.is_empty() {
+        return Err(CrossAssetError::InvalidAmount);
+    }
+    let mut requested_repay: i128 = 0;
+    for (_, repay_amount) in seizures.iter() {
+        if repay_amount <= 0 {
+            return Err(CrossAssetError::InvalidAmount);
+        }
+        requested_repay += repay_amount;
+    }
+
+    accrue_interest(env, &borrower, debt_asset.clone())?;
+    let summary = get_user_position_summary(env, &borrower)?;
+    if !summary.is_liquidatable {
+        return Err(CrossAssetError::NotLiquidatable);
+    }
+
+    let close_factor = get_close_factor(env).map_err(|_| CrossAssetError::AssetNotConfigured)?;
+    let incentive_bps = get_health_factor_incentive(env, summary.health_factor)
+        .map_err(|_| CrossAssetError::AssetNotConfigured)?;
+
+    let debt_key = AssetKey::from_option(debt_asset.clone());
+    let debt_config = get_asset_config(env, &debt_key)?;
+    let mut debt_position = get_user_asset_position(env, &borrower, debt_asset.clone());
+    let total_debt = Amount::new(debt_position.debt_principal)
+        .checked_add(Amount::new(debt_position.accrued_interest))?;
+    if requested_repay
+        > total_debt
+            .mul_div(close_factor, BASIS_POINTS_SCALE.get())?
+            .get()
+    {
+        return Err(CrossAssetError::ExceedsCloseFactor);
+    }
+    let total_debt = total_debt.get();
+
+    let timestamp = env.ledger().timestamp();
+    let mut seized = Vec::new(env);
+    let mut total_repay = Amount::ZERO;
+    let mut weighted_collateral = summary.weighted_collateral_value;
+    let mut weighted_debt = summary.weighted_debt_value;
+
+    for (collateral_asset, repay_amount) in seizures.iter() {
+        let collateral_key = AssetKey::from_option(collateral_asset.clone());
+        let collateral_config = get_asset_config(env, &collateral_key)?;
+        let mut position = accrue_interest(env, &borrower, collateral_asset.clone())?;
+        if !collateral_config.can_collateralize || !position.use_as_collateral {
+            return Err(CrossAssetError::AssetDisabled);
+        }
+
+        let repay_amount = match target_repay_limit(
+            env,
+            weighted_collateral,
+            weighted_debt,
+            &debt_config,
+            &collateral_config,
+            incentive_bps,
+        )? {
+            Some(limit) => repay_amount.min(limit),
+            None => repay_amount,
+        };
+        if repay_amount == 0 {
+            continue;
+        }
+
+        let seize_amount = Amount::new(repay_amount)
+            .mul_div(debt_config.price, collateral_config.price)?
+            .mul_div(
+                BASIS_POINTS_SCALE.get() + incentive_bps,
+                BASIS_POINTS_SCALE.get(),
+            )?
+            .get();
+        if seize_amount > position.collateral {
+            return Err(CrossAssetError::InsufficientCollateral);
+        }
+        total_repay = total_repay.checked_add(Amount::new(repay_amount))?;
+        // Track the weighted totals for the next seizure's limit, erring
+        // toward a lower health factor so the target is not undershot
+        let weight_scale = 10_000_000 * BASIS_POINTS_SCALE.get();
+        let collateral_weight = collateral_config
+            .price
+            .checked_mul(collateral_config.collateral_factor)
+            .ok_or(CrossAssetError::Overflow)?;
+        let debt_weight = debt_config
+            .price
+            .checked_mul(debt_config.borrow_factor)
+            .ok_or(CrossAssetError::Overflow)?;
+        weighted_collateral = Amount::new(weighted_collateral)
+            .checked_sub(Amount::new(seize_amount).mul_div_ceil(collateral_weight, weight_scale)?)?
+            .get();
+        weighted_debt = Amount::new(weighted_debt)
+            .checked_sub(Amount::new(repay_amount).mul_div(debt_weight, weight_scale)?)?
+            .get();
+
+        position.collateral -= seize_amount;
+        position.last_updated = timestamp;
+        refresh_supply_tenure(env, &mut position);
+        set_user_asset_position(env, &borrower, collateral_asset.clone(), position)?;
+
+        let mut received = accrue_interest(env, &liquidator, collateral_asset.clone())?;
+        received.collateral = Amount::new(received.collateral)
+            .checked_add(Amount::new(seize_amount))?
+            .get();
+        received.last_updated = timestamp;
+        refresh_supply_tenure(env, &mut received);
+        set_user_asset_position(env, &liquidator, collateral_asset.clone(), received)?;
+
+        record_market_activity(env, &collateral_key, false);
+        rewards::settle(env, &borrower, &collateral_key);
+        rewards::settle(env, &liquidator, &collateral_key);
+        seized.push_back((collateral_asset.clone(), seize_amount));
+        log_liquidation(
+            env,
+            LiquidationRecord {
+                liquidator: liquidator.clone(),
+                borrower: borrower.clone(),
+                debt_asset: debt_asset.clone(),
+                collateral_asset: collateral_asset.clone(),
+                debt_repaid: repay_amount,
+                collateral_seized: seize_amount,
+                timestamp,
+            },
+        );
+
+        emit_liquidation(
+            env,
+            LiquidationEvent {
+                liquidator: liquidator.clone(),
+                borrower: borrower.clone(),
+                debt_asset: debt_asset.clone(),
+                collateral_asset,
+                debt_liquidated: repay_amount,
+                collateral_seized: seize_amount,
+                incentive_amount: Amount::new(repay_amount)
+                    .mul_div(incentive_bps, BASIS_POINTS_SCALE.get())?
+                    .get(),
+                // Unseized collateral stays in the borrower's position
+                surplus_returned: 0,
+                timestamp,
+            },
+        );
+    }
+
+    // Pay interest first, then principal
+    let total_repay = total_repay.get();
+    if total_repay <= debt_position.accrued_interest {
+        debt_position.accrued_interest -= total_repay;
+    } else {
+        debt_position.debt_principal -= total_repay - debt_position.accrued_interest;
+        debt_position.accrued_interest = 0;
+    }
+    debt_position.last_updated = timestamp;
+    set_user_asset_position(env, &borrower, debt_asset.clone(), debt_position)?;
+
+    let mut payment = accrue_interest(env, &liquidator, debt_asset.clone())?;
+    if payment.collateral < total_repay {
+        return Err(CrossAssetError::InsufficientCollateral);
+    }
+    payment.collateral -= total_repay;
+    payment.last_updated = timestamp;
+    refresh_supply_tenure(env, &mut payment);
+    set_user_asset_position(env, &liquidator, debt_asset.clone(), payment)?;
+
+    update_total_supply(env, &debt_key, -total_repay);
+    update_total_borrow(env, &debt_key, -total_repay);
+    record_market_activity(env, &debt_key, false);
+    let debt_cleared = record_residual_bad_debt(env, &borrower)?;
+    record_liquidation(env, &borrower, total_repay == total_debt || debt_cleared);
+    register_user(env, &liquidator);
+    let liquidator_summary = get_user_position_summary(env, &liquidator)?;
+    if liquidator_summary.weighted_debt_value > 0
+        && liquidator_summary.health_factor < HEALTH_FACTOR_ONE.get()
+    {
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+    process_borrow_queue(env, debt_asset);
+
+    Ok(CrossAssetLiquidation {
+        debt_repaid: total_repay,
+        seized,
+        health_factor: get_user_position_summary(env, &borrower)?.health_factor,
+    })
+}
+
+/// Most of the debt asset that can be repaid by seizing from one collateral
+/// asset before the position reaches the liquidation target health factor,
+/// or `None` if no target applies.
+///
+/// Repaying a debt value `v` removes `v * borrow_factor` of weighted debt and
+/// `v * (1 + incentive) * collateral_factor` of weighted collateral, so the
+/// health factor reaches the target `T` at
+/// `v = (T * debt - collateral) / (T * borrow_factor - (1 + incentive) * collateral_factor)`.
+/// A repayment that lowers the health factor is never limited.
+///
+/// # Errors
+/// * `Overflow` - A weighted value overflowed
+fn target_repay_limit(
+    env: &Env,
+    weighted_collateral: i128,
+    weighted_debt: i128,
+    debt_config: &AssetConfig,
+    collateral_config: &AssetConfig,
+    incentive_bps: i128,
+) -> Result<Option<i128>, CrossAssetError> {
+    let target = get_liquidation_target(env);
+    if target == 0 {
+        return Ok(None);
+    }
+    let scale = BASIS_POINTS_SCALE.get();
+    let release = (scale + incentive_bps)
+        .checked_mul(collateral_config.collateral_factor)
+        .ok_or(CrossAssetError::Overflow)?;
+    let kept = weighted_collateral
+        .checked_mul(debt_config.borrow_factor * scale)
+        .ok_or(CrossAssetError::Overflow)?;
+    let released = release
+        .checked_mul(weighted_debt)
+        .ok_or(CrossAssetError::Overflow)?;
+    if kept <= released {
+        return Ok(None);
+    }
+
+    let shortfall = target
+        .checked_mul(weighted_debt)
+        .zip(HEALTH_FACTOR_ONE.get().checked_mul(weighted_collateral))
+        .and_then(|(debt, collateral)| debt.checked_sub(collateral))
+        .map(Amount::new)
+        .ok_or(CrossAssetError::Overflow)?;
+    if shortfall.get() <= 0 {
+        return Ok(Some(0));
+    }
+    // Round the repayment up so the target is reached
+    let denominator = target
+        .checked_mul(debt_config.borrow_factor)
+        .and_then(|weight| weight.checked_sub(release))
+        .and_then(|weight| weight.checked_mul(debt_config.price))
+        .ok_or(CrossAssetError::Overflow)?;
+    Ok(Some(
+        shortfall
+            .mul_div_ceil(scale * 10_000_000, denominator)?
+            .get(),
+    ))
+}
+
+/// Move the debt of a borrower left with no collateral into protocol bad
+/// debt.
+///
+/// # Returns
+/// Whether any debt was moved.
+fn record_residual_bad_debt(env: &Env, borrower: &Address) -> Result<bool, CrossAssetError> {
+    let positions = get_user_asset_positions(env, borrower);
+    if positions
+        .values()
+        .iter()
+        .any(|position| position.collateral > 0)
+    {
+        return Ok(false);
+    }
+
+    let timestamp = env.ledger().timestamp();
+    let mut recorded = false;
+    for asset_key in positions.keys().iter() {
+        let asset = asset_key.to_option();
+        let mut position = accrue_interest(env, borrower, asset.clone())?;
+        let residual = position.debt_principal + position.accrued_interest;
+        if residual == 0 {
+            continue;
+        }
+
+        position.debt_principal = 0;
+        position.accrued_interest = 0;
+        position.last_updated = timestamp;
+        set_user_asset_position(env, borrower, asset.clone(), position)?;
+        update_total_borrow(env, &asset_key, -residual);
+        record_bad_debt(env, asset.clone(), residual);
+        recorded = true;
+
+        emit_bad_debt_recorded(
+            env,
+            BadDebtRecordedEvent {
+                borrower: borrower.clone(),
+                asset,
+                amount: residual,
+                timestamp,
+            },
+        );
+    }
+    Ok(recorded)
+}
+
+/// Enable soft liquidation for a collateral asset, or disable it (admin or
+/// `RiskAdmin` only).
+///
+/// Disabling stops new conversions; collateral already converted can still
+/// be bought back by keepers.
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `RiskAdmin`
+/// * `asset` - Collateral asset to update (`None` for XLM)
+/// * `config` - Band layout; `None` disables soft liquidation
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - The asset is not listed
+/// * `InvalidAmount` - Band count is zero or above
+///   [`MAX_SOFT_LIQUIDATION_BANDS`], band width is not positive, the bands
+///   span more than 10,000 basis points, or the fee is not below 10,000
+pub fn set_soft_liquidation(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    config: Option<SoftLiquidationConfig>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    get_asset_config(env, &asset_key)?;
+    let mut configs: Map<AssetKey, SoftLiquidationConfig> =
+        storage::get(env, &SOFT_LIQUIDATION_CONFIGS).unwrap_or(Map::new(env));
+    match &config {
+        Some(config) => {
+            let span = config.band_width_bps * i128::from(config.band_count);
+            if config.band_count == 0
+                || config.band_count > MAX_SOFT_LIQUIDATION_BANDS
+                || config.band_width_bps <= 0
+                || span > BASIS_POINTS_SCALE.get()
+                || config.fee_bps < 0
+                || config.fee_bps >= BASIS_POINTS_SCALE.get()
+            {
+                return Err(CrossAssetError::InvalidAmount);
+            }
+            configs.set(asset_key, config.clone());
+        }
+        None => {
+            configs.remove(asset_key);
+        }
+    }
+    storage::set(env, &SOFT_LIQUIDATION_CONFIGS, &configs);
+
+    emit_liquidation_bands_updated(
+        env,
+        LiquidationBandsUpdatedEvent {
+            admin: caller,
+            asset,
+            band_count: config.as_ref().map_or(0, |config| config.band_count),
+            band_width_bps: config.as_ref().map_or(0, |config| config.band_width_bps),
+            fee_bps: config.map_or(0, |config| config.fee_bps),
+        },
+    );
+    Ok(())
+}
+
+/// Get a collateral asset's soft liquidation bands, if enabled.
+pub fn get_soft_liquidation_config(
+    env: &Env,
+    asset: Option<Address>,
+) -> Option<SoftLiquidationConfig> {
+    storage::get::<CrossAssetDataKey, Map<AssetKey, SoftLiquidationConfig>>(
+        env,
+        &SOFT_LIQUIDATION_CONFIGS,
+    )
+    .and_then(|configs| configs.get(AssetKey::from_option(asset)))
+}
+
+/// Get how much of a user's collateral in an asset soft liquidation has
+/// converted, if any.
+pub fn get_soft_liquidation_state(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+) -> Option<SoftLiquidationState> {
+    storage::get::<CrossAssetDataKey, Map<UserAssetKey, SoftLiquidationState>>(
+        env,
+        &SOFT_LIQUIDATIONS,
+    )
+    .and_then(|states| states.get(UserAssetKey::new(user.clone(), asset)))
+}
+
+fn set_soft_liquidation_state(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+    state: Option<SoftLiquidationState>,
 ) {
     let key = UserAssetKey::new(user.clone(), asset);
-    let mut positions: Map<UserAssetKey, AssetPosition> = env
-        .storage()
-        .persistent()
-        .get(&USER_POSITIONS)
-        .unwrap_or(Map::new(env));
+    let mut states: Map<UserAssetKey, SoftLiquidationState> =
+        storage::get(env, &SOFT_LIQUIDATIONS).unwrap_or(Map::new(env));
+    match state {
+        Some(state) => states.set(key, state),
+        None => {
+            states.remove(key);
+        }
+    }
+    storage::set(env, &SOFT_LIQUIDATIONS, &states);
+}
 
-    positions.set(key, position);
-    env.storage().persistent().set(&USER_POSITIONS, &positions);
+/// Number of bands a position with the given weighted values has entered.
+///
+/// # Errors
+/// * `Overflow` - The health factor overflowed
+fn bands_entered(
+    config: &SoftLiquidationConfig,
+    weighted_collateral_value: i128,
+    weighted_debt_value: i128,
+) -> Result<u32, CrossAssetError> {
+    if weighted_debt_value <= 0 {
+        return Ok(0);
+    }
+    let health_factor = Amount::new(weighted_collateral_value)
+        .mul_div(HEALTH_FACTOR_ONE.get(), weighted_debt_value)?
+        .get();
+    let top = HEALTH_FACTOR_ONE.get() + config.band_width_bps * i128::from(config.band_count);
+    if health_factor >= top {
+        return Ok(0);
+    }
+    let entered = (top - health_factor + config.band_width_bps - 1) / config.band_width_bps;
+    Ok(entered.min(i128::from(config.band_count)) as u32)
 }
 
-/// Calculate a unified position summary across all registered assets.
+/// Move a soft-liquidating position's collateral conversion to match its
+/// bands at current prices.
 ///
-/// Iterates over all configured assets, aggregates collateral and debt values
-/// weighted by their respective factors, and computes the health factor.
-/// Prices older than 1 hour are rejected.
+/// The position's health factor is computed as if nothing were converted.
+/// For each band it has entered, an equal share of its collateral (current
+/// plus converted) should be sold. If less is converted, the keeper buys the
+/// difference at the oracle price less the fee, paying with its own
+/// collateral in the debt asset, which repays the user's debt. If more is
+/// converted, the keeper sells the difference back at the oracle price plus
+/// the fee, receiving collateral in the debt asset funded by new debt of the
+/// user. Both sides settle in cross-asset positions, as in liquidations.
 ///
 /// # Arguments
 /// * `env` - The contract environment
-/// * `user` - User address
+/// * `keeper` - Address taking the other side (must authorize)
+/// * `user` - Owner of the position
+/// * `collateral_asset` - Soft-liquidating collateral asset (`None` for XLM)
+/// * `debt_asset` - Asset of the debt repaid (`None` for XLM)
 ///
 /// # Returns
-/// [`UserPositionSummary`] with health factor, liquidation status, and borrow capacity.
+/// [`SoftLiquidation`] with the amounts converted and the user's health
+/// factor afterwards.
 ///
 /// # Errors
-/// * `PriceStale` - Any asset with a non-zero position has a price older than 1 hour
-pub fn get_user_position_summary(
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `AssetPaused` - Liquidations of the debt asset are paused
+/// * `NotAuthorized` - The keeper is the user
+/// * `SoftLiquidationDisabled` - The collateral asset has no bands and
+///   nothing converted
+/// * `DebtAssetMismatch` - The debt asset is the collateral asset, or not the
+///   one the collateral is converted toward
+/// * `AssetDisabled` - The user's collateral does not back borrowing
+/// * `NothingToConvert` - The conversion already matches the bands
+/// * `InsufficientCollateral` - The keeper lacks the collateral to pay with
+/// * `UnhealthyPosition` - The user or keeper would have a health factor
+///   below 1.0 afterwards
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn soft_liquidate(
     env: &Env,
-    user: &Address,
-) -> Result<UserPositionSummary, CrossAssetError> {
-    let asset_list: Vec<AssetKey> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_LIST)
-        .unwrap_or(Vec::new(env));
-
-    let configs: Map<AssetKey, AssetConfig> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_CONFIGS)
-        .unwrap_or(Map::new(env));
-
-    let mut total_collateral_value: i128 = 0;
-    let mut weighted_collateral_value: i128 = 0;
-    let mut total_debt_value: i128 = 0;
-    let mut weighted_debt_value: i128 = 0;
+    keeper: Address,
+    user: Address,
+    collateral_asset: Option<Address>,
+    debt_asset: Option<Address>,
+) -> Result<SoftLiquidation, CrossAssetError> {
+    keeper.require_auth();
+    require_not_paused(env)?;
+    let collateral_key = AssetKey::from_option(collateral_asset.clone());
+    let debt_key = AssetKey::from_option(debt_asset.clone());
+    require_asset_not_paused(env, &debt_key, PAUSE_LIQUIDATE)?;
+    if keeper == user {
+        return Err(CrossAssetError::NotAuthorized);
+    }
+    if collateral_key == debt_key {
+        return Err(CrossAssetError::DebtAssetMismatch);
+    }
 
-    for i in 0..asset_list.len() {
-        let asset_key = asset_list.get(i).unwrap();
+    let config = get_soft_liquidation_config(env, collateral_asset.clone());
+    let (converted, proceeds) =
+        match get_soft_liquidation_state(env, &user, collateral_asset.clone()) {
+            Some(state) if state.debt_asset != debt_key => {
+                return Err(CrossAssetError::DebtAssetMismatch)
+            }
+            Some(state) => (state.converted, state.proceeds),
+            None if config.is_none() => return Err(CrossAssetError::SoftLiquidationDisabled),
+            None => (0, 0),
+        };
 
-        if let Some(config) = configs.get(asset_key.clone()) {
-            let asset_option = asset_key.to_option();
-            let position = get_user_asset_position(env, user, asset_option);
+    let collateral_config = get_asset_config(env, &collateral_key)?;
+    let debt_config = get_asset_config(env, &debt_key)?;
+    let mut collateral_position = accrue_interest(env, &user, collateral_asset.clone())?;
+    let mut debt_position = accrue_interest(env, &user, debt_asset.clone())?;
+    if !collateral_config.can_collateralize || !collateral_position.use_as_collateral {
+        return Err(CrossAssetError::AssetDisabled);
+    }
 
-            if position.collateral == 0 && position.debt_principal == 0 {
-                continue;
-            }
+    // Undo the conversion to place the position in its bands
+    let summary = get_user_position_summary(env, &user)?;
+    let converted_value = value_at(converted, collateral_config.price)?;
+    let weighted_collateral = Amount::new(summary.weighted_collateral_value)
+        .checked_add(converted_value.mul_div(
+            collateral_config.collateral_factor,
+            BASIS_POINTS_SCALE.get(),
+        )?)?
+        .get();
+    let mut weighted_debt = summary.weighted_debt_value;
+    if debt_config.can_borrow {
+        let proceeds_value = value_at(proceeds, debt_config.price)?;
+        weighted_debt = Amount::new(weighted_debt)
+            .checked_add(
+                proceeds_value.mul_div(debt_config.borrow_factor, BASIS_POINTS_SCALE.get())?,
+            )?
+            .get();
+    }
+    let (bands, target, fee_bps) = match &config {
+        Some(config) => {
+            let bands = bands_entered(config, weighted_collateral, weighted_debt)?;
+            let target = Amount::new(collateral_position.collateral)
+                .checked_add(Amount::new(converted))?
+                .mul_div(i128::from(bands), i128::from(config.band_count))?
+                .get();
+            (bands, target, config.fee_bps)
+        }
+        None => (0, 0, 0),
+    };
 
-            let current_time = env.ledger().timestamp();
-            if current_time > config.price_updated_at
-                && current_time - config.price_updated_at > 3600
-            {
-                return Err(CrossAssetError::PriceStale);
-            }
+    let timestamp = env.ledger().timestamp();
+    let mut keeper_collateral = accrue_interest(env, &keeper, collateral_asset.clone())?;
+    let mut keeper_debt_asset = accrue_interest(env, &keeper, debt_asset.clone())?;
+    let debt_rate = debt_config
+        .price
+        .checked_mul(BASIS_POINTS_SCALE.get())
+        .ok_or(CrossAssetError::Overflow)?;
+    let (sold, repaid) = if target > converted {
+        let total_debt = Amount::new(debt_position.debt_principal)
+            .checked_add(Amount::new(debt_position.accrued_interest))?
+            .get();
+        let sell_rate = collateral_config
+            .price
+            .checked_mul(BASIS_POINTS_SCALE.get() - fee_bps)
+            .ok_or(CrossAssetError::Overflow)?;
+        let max_by_debt = Amount::new(total_debt).mul_div(debt_rate, sell_rate)?.get();
+        let sold = (target - converted)
+            .min(collateral_position.collateral)
+            .min(max_by_debt);
+        let repaid = Amount::new(sold)
+            .mul_div(sell_rate, debt_rate)?
+            .get()
+            .min(total_debt);
+        if sold <= 0 || repaid <= 0 {
+            return Err(CrossAssetError::NothingToConvert);
+        }
+        if keeper_debt_asset.collateral < repaid {
+            return Err(CrossAssetError::InsufficientCollateral);
+        }
 
-            let collateral_value = (position.collateral * config.price) / 10_000_000;
-            total_collateral_value += collateral_value;
+        // Pay interest first, then principal
+        if repaid <= debt_position.accrued_interest {
+            debt_position.accrued_interest -= repaid;
+        } else {
+            debt_position.debt_principal -= repaid - debt_position.accrued_interest;
+            debt_position.accrued_interest = 0;
+        }
+        (sold, repaid)
+    } else if target < converted {
+        let bought = converted - target;
+        let buy_rate = collateral_config
+            .price
+            .checked_mul(BASIS_POINTS_SCALE.get() + fee_bps)
+            .ok_or(CrossAssetError::Overflow)?;
+        let cost = Amount::new(bought).mul_div(buy_rate, debt_rate)?.get();
+        if keeper_collateral.collateral < bought {
+            return Err(CrossAssetError::InsufficientCollateral);
+        }
+        debt_position.debt_principal = Amount::new(debt_position.debt_principal)
+            .checked_add(Amount::new(cost))?
+            .get();
+        (-bought, -cost)
+    } else {
+        return Err(CrossAssetError::NothingToConvert);
+    };
 
-            if config.can_collateralize {
-                weighted_collateral_value += (collateral_value * config.collateral_factor) / 10_000;
-            }
+    collateral_position.collateral = Amount::new(collateral_position.collateral)
+        .checked_sub(Amount::new(sold))?
+        .get();
+    collateral_position.last_updated = timestamp;
+    refresh_supply_tenure(env, &mut collateral_position);
+    set_user_asset_position(env, &user, collateral_asset.clone(), collateral_position)?;
+    debt_position.last_updated = timestamp;
+    set_user_asset_position(env, &user, debt_asset.clone(), debt_position)?;
 
-            let total_debt = position.debt_principal + position.accrued_interest;
-            let debt_value = (total_debt * config.price) / 10_000_000;
-            total_debt_value += debt_value;
+    keeper_collateral.collateral = Amount::new(keeper_collateral.collateral)
+        .checked_add(Amount::new(sold))?
+        .get();
+    keeper_collateral.last_updated = timestamp;
+    refresh_supply_tenure(env, &mut keeper_collateral);
+    set_user_asset_position(env, &keeper, collateral_asset.clone(), keeper_collateral)?;
+    keeper_debt_asset.collateral = Amount::new(keeper_debt_asset.collateral)
+        .checked_sub(Amount::new(repaid))?
+        .get();
+    keeper_debt_asset.last_updated = timestamp;
+    refresh_supply_tenure(env, &mut keeper_debt_asset);
+    set_user_asset_position(env, &keeper, debt_asset.clone(), keeper_debt_asset)?;
 
-            if config.can_borrow {
-                weighted_debt_value += (debt_value * config.borrow_factor) / 10_000;
-            }
+    update_total_supply(env, &debt_key, -repaid);
+    update_total_borrow(env, &debt_key, -repaid);
+    record_market_activity(env, &collateral_key, false);
+    record_market_activity(env, &debt_key, false);
+    register_user(env, &keeper);
+
+    // Buying back keeps the share of the proceeds attributable to the
+    // remaining conversion
+    let converted_after = Amount::new(converted).checked_add(Amount::new(sold))?.get();
+    let proceeds_after = if sold >= 0 {
+        Amount::new(proceeds).checked_add(Amount::new(repaid))?
+    } else {
+        Amount::new(proceeds).checked_sub(Amount::new(proceeds).mul_div(-sold, converted)?)?
+    }
+    .get();
+    set_soft_liquidation_state(
+        env,
+        &user,
+        collateral_asset.clone(),
+        (converted_after > 0).then(|| SoftLiquidationState {
+            debt_asset: debt_key,
+            converted: converted_after,
+            proceeds: proceeds_after,
+        }),
+    );
+
+    let summary = get_user_position_summary(env, &user)?;
+    if sold < 0 && summary.health_factor < HEALTH_FACTOR_ONE.get() {
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+    let keeper_summary = get_user_position_summary(env, &keeper)?;
+    if keeper_summary.weighted_debt_value > 0
+        && keeper_summary.health_factor < HEALTH_FACTOR_ONE.get()
+    {
+        return Err(CrossAssetError::UnhealthyPosition);
+    }
+
+    emit_soft_liquidation(
+        env,
+        SoftLiquidationEvent {
+            keeper,
+            user,
+            collateral_asset,
+            debt_asset,
+            collateral_converted: sold,
+            debt_repaid: repaid,
+            bands_converted: bands,
+            timestamp,
+        },
+    );
+
+    Ok(SoftLiquidation {
+        collateral_converted: sold,
+        debt_repaid: repaid,
+        bands_converted: bands,
+        health_factor: summary.health_factor,
+    })
+}
+
+/// Toggle whether a deposit counts as collateral (savings mode).
+///
+/// A deposit with `use_as_collateral = false` is held as a pure supply
+/// position: it contributes nothing to the weighted collateral value, so it
+/// provides no borrowing power and can never be seized to cover debt.
+/// Disabling is rejected if the remaining collateral would leave an
+/// outstanding debt with a health factor below 1.0.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - Owner of the position (must authorize)
+/// * `asset` - Asset to toggle (`None` for XLM)
+/// * `enabled` - `true` to use as collateral, `false` for savings mode
+///
+/// # Returns
+/// Updated [`AssetPosition`] after the toggle.
+///
+/// # Errors
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `AssetNotConfigured` - Asset is not registered
+/// * `AssetDisabled` - Enabling on an asset that cannot be collateral
+/// * `IsolationConflict` - Enabling would mix isolated collateral with other collateral
+/// * `UnhealthyPosition` - Disabling would drop health factor below 1.0
+/// * `PriceStale` - Stale price prevents health factor calculation
+pub fn set_use_as_collateral(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    enabled: bool,
+) -> Result<AssetPosition, CrossAssetError> {
+    user.require_auth();
+    require_not_paused(env)?;
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let config = get_asset_config(env, &asset_key)?;
+
+    if enabled && !config.can_collateralize {
+        return Err(CrossAssetError::AssetDisabled);
+    }
+
+    let mut position = accrue_interest(env, &user, asset.clone())?;
+    if position.use_as_collateral == enabled {
+        return Ok(position);
+    }
+    if enabled {
+        require_collateral_compatible(env, &user, &asset_key)?;
+    }
+
+    let previous = position.use_as_collateral;
+    position.use_as_collateral = enabled;
+    position.last_updated = env.ledger().timestamp();
+
+    set_user_asset_position(env, &user, asset.clone(), position.clone())?;
+
+    if !enabled {
+        let summary = get_user_position_summary(env, &user)?;
+        if summary.weighted_debt_value > 0 && summary.health_factor < HEALTH_FACTOR_ONE.get() {
+            position.use_as_collateral = previous;
+            set_user_asset_position(env, &user, asset, position)?;
+            return Err(CrossAssetError::UnhealthyPosition);
         }
     }
 
-    // Calculate health factor (weighted_collateral / weighted_debt * 10000)
-    // Health factor of 1.0 = 10000, below 1.0 can be liquidated
-    let health_factor = if weighted_debt_value > 0 {
-        (weighted_collateral_value * 10_000) / weighted_debt_value
-    } else {
-        i128::MAX // No debt = infinite health
+    emit_collateral_toggled(
+        env,
+        CollateralToggledEvent {
+            user,
+            asset: asset_key.to_option(),
+            use_as_collateral: enabled,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(position)
+}
+
+/// Run one page of a protocol-wide stress test.
+///
+/// Applies a uniform `shock_bps` to every collateral price (debt keeps its
+/// current price) and, for the users in `[cursor, cursor + limit)` of the
+/// registry, accumulates the debt that would become liquidatable and the debt
+/// left uncovered by collateral. Starting at `cursor = 0` resets the run.
+/// When the last page is processed the result is cached per shock level and
+/// can be read back with [`get_protocol_stress_result`].
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `shock_bps` - Collateral price shock in basis points (-10000..=0 typical)
+/// * `cursor` - Registry index to start from (use `next_cursor` of the previous page)
+/// * `limit` - Users per page (at most [`MAX_STRESS_PAGE_SIZE`])
+///
+/// # Returns
+/// The accumulated [`ProtocolStressResult`] after this page.
+///
+/// # Errors
+/// * `InvalidPrice` - Shock is below -100% or the page parameters are invalid
+/// * `PriceStale` - A scanned position references a stale price
+pub fn stress_test_protocol(
+    env: &Env,
+    shock_bps: i128,
+    cursor: u32,
+    limit: u32,
+) -> Result<ProtocolStressResult, CrossAssetError> {
+    if shock_bps < -BASIS_POINTS_SCALE.get() || limit == 0 || limit > MAX_STRESS_PAGE_SIZE {
+        return Err(CrossAssetError::InvalidPrice);
+    }
+
+    let mut progress: Map<i128, ProtocolStressResult> =
+        storage::get(env, &STRESS_PROGRESS).unwrap_or(Map::new(env));
+
+    let mut result = match progress.get(shock_bps) {
+        Some(partial) if cursor > 0 && partial.next_cursor == Some(cursor) => partial,
+        _ if cursor == 0 => ProtocolStressResult {
+            shock_bps,
+            users_scanned: 0,
+            liquidatable_debt: 0,
+            projected_bad_debt: 0,
+            next_cursor: Some(0),
+            computed_at: 0,
+        },
+        _ => return Err(CrossAssetError::InvalidPrice),
     };
 
-    // Position is liquidatable if health factor < 1.0 (10000)
-    let is_liquidatable = health_factor < 10_000 && weighted_debt_value > 0;
+    let users = get_user_list(env);
+    let end = cursor.saturating_add(limit).min(users.len());
 
-    // Calculate remaining borrow capacity
-    let borrow_capacity = if weighted_collateral_value > weighted_debt_value {
-        weighted_collateral_value - weighted_debt_value
+    for i in cursor..end {
+        let user = users.get(i).unwrap();
+        let summary = compute_position_summary(env, &user, &Map::new(env), shock_bps)?;
+
+        if summary.is_liquidatable {
+            result.liquidatable_debt += summary.total_debt_value;
+        }
+        if summary.total_debt_value > summary.total_collateral_value {
+            result.projected_bad_debt += summary.total_debt_value - summary.total_collateral_value;
+        }
+        result.users_scanned += 1;
+    }
+
+    result.computed_at = env.ledger().timestamp();
+    result.next_cursor = if end < users.len() { Some(end) } else { None };
+
+    if result.next_cursor.is_none() {
+        progress.remove(shock_bps);
+        let mut cache: Map<i128, ProtocolStressResult> =
+            storage::get(env, &STRESS_CACHE).unwrap_or(Map::new(env));
+        cache.set(shock_bps, result.clone());
+        storage::set(env, &STRESS_CACHE, &cache);
     } else {
-        0
-    };
+        progress.set(shock_bps, result.clone());
+    }
+    storage::set(env, &STRESS_PROGRESS, &progress);
 
-    Ok(UserPositionSummary {
-        total_collateral_value,
-        weighted_collateral_value,
-        total_debt_value,
-        weighted_debt_value,
-        health_factor,
-        is_liquidatable,
-        borrow_capacity,
-    })
+    Ok(result)
+}
+
+/// Get the last completed protocol stress result for a shock level.
+///
+/// `computed_at` tells callers how fresh the cached figures are.
+pub fn get_protocol_stress_result(env: &Env, shock_bps: i128) -> Option<ProtocolStressResult> {
+    let cache: Map<i128, ProtocolStressResult> =
+        storage::get(env, &STRESS_CACHE).unwrap_or(Map::new(env));
+    cache.get(shock_bps)
+}
+
+/// Return every user that has ever deposited, supplied or borrowed, in
+/// order of first activity.
+pub fn get_user_list(env: &Env) -> Vec<Address> {
+    storage::get(env, &USER_LIST).unwrap_or(Vec::new(env))
+}
+
+pub(crate) fn register_user(env: &Env, user: &Address) {
+    let mut users = get_user_list(env);
+    if !users.contains(user) {
+        users.push_back(user.clone());
+        storage::set(env, &USER_LIST, &users);
+    }
 }
 
-/// Deposit collateral for a specific asset.
+/// Latest update among a user's stored cross-asset positions, for dust
+/// sweeping.
 ///
-/// Requires user authorization. Validates the asset is enabled for collateral
-/// and that the deposit does not exceed the supply cap.
+/// # Returns
+/// `None` if any stored position holds collateral, debt or interest;
+/// otherwise the latest `last_updated` (0 when nothing is stored).
+pub(crate) fn empty_positions_updated_at(env: &Env, user: &Address) -> Option<u64> {
+    let positions: Map<UserAssetKey, AssetPosition> =
+        storage::get(env, &USER_POSITIONS).unwrap_or(Map::new(env));
+    let mut updated_at = 0;
+    for asset_key in get_asset_list(env).iter() {
+        let Some(position) = positions.get(UserAssetKey {
+            user: user.clone(),
+            asset: asset_key,
+        }) else {
+            continue;
+        };
+        if position.collateral != 0
+            || position.debt_principal != 0
+            || position.accrued_interest != 0
+        {
+            return None;
+        }
+        updated_at = updated_at.max(position.last_updated);
+    }
+    Some(updated_at)
+}
+
+/// Move every cross-asset position of `from` to `to`, which must hold none
 ///
-/// # Arguments
-/// * `env` - The contract environment
-/// * `user` - User depositing collateral (must authorize)
-/// * `asset` - Asset to deposit (`None` for XLM)
-/// * `amount` - Amount to deposit
+/// Interest is accrued up to now first, so `to` takes over the debt as it
+/// stands. Soft liquidation progress moves with its position; accrued
+/// rewards and interest statements stay with `from`.
 ///
 /// # Returns
-/// Updated [`AssetPosition`] after the deposit.
+/// The positions moved, by asset.
 ///
 /// # Errors
-/// * `AssetNotConfigured` - Asset is not registered
-/// * `AssetDisabled` - Asset is not enabled for collateral
-/// * `SupplyCapExceeded` - Deposit would exceed the asset's supply cap
-pub fn cross_asset_deposit(
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `Overflow` - Interest accrual overflowed
+pub(crate) fn move_user_positions(
     env: &Env,
-    user: Address,
-    asset: Option<Address>,
-    amount: i128,
-) -> Result<AssetPosition, CrossAssetError> {
-    user.require_auth();
-
-    let asset_key = AssetKey::from_option(asset.clone());
-    let config = get_asset_config(env, &asset_key)?;
+    from: &Address,
+    to: &Address,
+) -> Result<Map<AssetKey, AssetPosition>, CrossAssetError> {
+    require_not_paused(env)?;
 
-    if !config.can_collateralize {
-        return Err(CrossAssetError::AssetDisabled);
-    }
+    let now = env.ledger().timestamp();
+    let mut moved = Map::new(env);
+    for asset_key in get_user_asset_positions(env, from).keys().iter() {
+        let asset = asset_key.to_option();
+        let position = accrue_interest(env, from, asset.clone())?;
+        let soft_liquidation = get_soft_liquidation_state(env, from, asset.clone());
 
-    if config.max_supply > 0 {
-        let total_supply = get_total_supply(env, &asset_key);
-        if total_supply + amount > config.max_supply {
-            return Err(CrossAssetError::SupplyCapExceeded);
+        set_user_asset_position(env, to, asset.clone(), position.clone())?;
+        set_user_asset_position(
+            env,
+            from,
+            asset.clone(),
+            AssetPosition {
+                collateral: 0,
+                debt_principal: 0,
+                accrued_interest: 0,
+                last_updated: now,
+                use_as_collateral: position.use_as_collateral,
+                supplied_since: 0,
+            },
+        )?;
+        if soft_liquidation.is_some() {
+            set_soft_liquidation_state(env, from, asset.clone(), None);
+            set_soft_liquidation_state(env, to, asset, soft_liquidation);
+        }
+        if position.debt_principal > 0 || position.accrued_interest > 0 {
+            register_borrower(env, to);
         }
+        moved.set(asset_key, position);
     }
+    if !moved.is_empty() {
+        register_user(env, to);
+    }
+    Ok(moved)
+}
 
-    let mut position = get_user_asset_position(env, &user, asset.clone());
+/// Delete a user's stored cross-asset positions and drop the user from the
+/// user and borrower registries. Only for accounts without balances.
+pub(crate) fn remove_user_positions(env: &Env, user: &Address) {
+    let mut positions: Map<UserAssetKey, AssetPosition> =
+        storage::get(env, &USER_POSITIONS).unwrap_or(Map::new(env));
+    let count = positions.len();
+    for asset_key in get_asset_list(env).iter() {
+        positions.remove(UserAssetKey {
+            user: user.clone(),
+            asset: asset_key,
+        });
+    }
+    if positions.len() != count {
+        storage::set(env, &USER_POSITIONS, &positions);
+    }
 
-    position.collateral += amount;
-    position.last_updated = env.ledger().timestamp();
+    for key in [USER_LIST, BORROWER_LIST] {
+        let mut users: Vec<Address> = storage::get(env, &key).unwrap_or(Vec::new(env));
+        if let Some(index) = users.first_index_of(user) {
+            users.remove(index);
+            storage::set(env, &key, &users);
+        }
+    }
+}
 
-    set_user_asset_position(env, &user, asset, position.clone());
-    update_total_supply(env, &asset_key, amount);
+/// Return every user that has ever borrowed, in order of first borrow.
+///
+/// Users stay registered after repaying; keepers filter them out by health.
+pub fn get_borrower_list(env: &Env) -> Vec<Address> {
+    storage::get(env, &BORROWER_LIST).unwrap_or(Vec::new(env))
+}
 
-    Ok(position)
+fn register_borrower(env: &Env, user: &Address) {
+    let mut borrowers = get_borrower_list(env);
+    if !borrowers.contains(user) {
+        borrowers.push_back(user.clone());
+        storage::set(env, &BORROWER_LIST, &borrowers);
+    }
 }
 
-/// Withdraw collateral for a specific asset.
+/// Find borrowers whose health factor is below `threshold`.
 ///
-/// Requires user authorization. Checks that the user has sufficient collateral
-/// and that the withdrawal does not bring the health factor below 1.0. If the
-/// health check fails, the withdrawal is rolled back.
+/// Scans the borrowers in `[offset, offset + limit)` of the borrower registry,
+/// so a page may return fewer than `limit` positions; keepers page through
+/// the registry until `offset` reaches [`get_borrower_list`]'s length.
+/// Positions that cannot be valued because a price is stale are skipped, as
+/// they cannot be liquidated either.
 ///
 /// # Arguments
 /// * `env` - The contract environment
-/// * `user` - User withdrawing collateral (must authorize)
-/// * `asset` - Asset to withdraw (`None` for XLM)
-/// * `amount` - Amount to withdraw
-///
-/// # Returns
-/// Updated [`AssetPosition`] after the withdrawal.
+/// * `threshold` - Health factor bound (scaled by 10000; 10000 finds liquidatable positions)
+/// * `limit` - Borrowers per page (at most [`MAX_HEALTH_SCAN_PAGE_SIZE`])
+/// * `offset` - Registry index to start from
 ///
 /// # Errors
-/// * `InsufficientCollateral` - User's collateral balance is below `amount`
-/// * `UnhealthyPosition` - Withdrawal would drop health factor below 1.0
-/// * `PriceStale` - Stale price prevents health factor calculation
-pub fn cross_asset_withdraw(
+/// * `InvalidAmount` - `limit` is zero or above the page size
+pub fn get_positions_below_health(
     env: &Env,
-    user: Address,
-    asset: Option<Address>,
-    amount: i128,
-) -> Result<AssetPosition, CrossAssetError> {
-    user.require_auth();
-
-    let asset_key = AssetKey::from_option(asset.clone());
-
-    let mut position = get_user_asset_position(env, &user, asset.clone());
+    threshold: i128,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<PositionHealth>, CrossAssetError> {
+    if limit == 0 || limit > MAX_HEALTH_SCAN_PAGE_SIZE {
+        return Err(CrossAssetError::InvalidAmount);
+    }
 
-    if position.collateral < amount {
-        return Err(CrossAssetError::InsufficientCollateral);
+    let borrowers = get_borrower_list(env);
+    let end = offset.saturating_add(limit).min(borrowers.len());
+    let mut positions = Vec::new(env);
+    for i in offset..end {
+        let user = borrowers.get(i).unwrap();
+        let summary = match get_user_position_summary(env, &user) {
+            Ok(summary) => summary,
+            Err(CrossAssetError::PriceStale) => continue,
+            Err(err) => return Err(err),
+        };
+        if summary.health_factor < threshold {
+            positions.push_back(PositionHealth {
+                user,
+                health_factor: summary.health_factor,
+                total_collateral_value: summary.total_collateral_value,
+                total_debt_value: summary.total_debt_value,
+            });
+        }
     }
+    Ok(positions)
+}
 
-    position.collateral -= amount;
-    position.last_updated = env.ledger().timestamp();
+/// Set the supply tenure rebate schedule (admin or `RiskAdmin` only).
+///
+/// Tiers must be sorted by strictly increasing `min_tenure_seconds` with
+/// non-decreasing rebates in [0, 10000] bps. An empty list disables rebates.
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - A tier is out of range or out of order
+pub fn set_tenure_rebates(
+    env: &Env,
+    caller: Address,
+    tiers: Vec<TenureRebateTier>,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+    validate_tenure_rebates(&tiers)?;
 
-    set_user_asset_position(env, &user, asset.clone(), position.clone());
+    storage::set(env, &TENURE_TIERS, &tiers);
 
-    let summary = get_user_position_summary(env, &user)?;
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_tenure_rebates"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
 
-    if summary.total_debt_value > 0 && summary.health_factor < 10_000 {
-        position.collateral += amount;
-        set_user_asset_position(env, &user, asset, position);
-        return Err(CrossAssetError::UnhealthyPosition);
+/// Check a rebate schedule against the rules of [`set_tenure_rebates`]
+pub(crate) fn validate_tenure_rebates(
+    tiers: &Vec<TenureRebateTier>,
+) -> Result<(), CrossAssetError> {
+    if tiers.len() > MAX_TENURE_TIERS {
+        return Err(CrossAssetError::AssetNotConfigured);
     }
 
-    update_total_supply(env, &asset_key, -amount);
-
-    Ok(position)
+    let mut previous: Option<TenureRebateTier> = None;
+    for tier in tiers.iter() {
+        require_valid_basis_points(tier.rebate_bps)?;
+        if let Some(prev) = previous {
+            if tier.min_tenure_seconds <= prev.min_tenure_seconds
+                || tier.rebate_bps < prev.rebate_bps
+            {
+                return Err(CrossAssetError::AssetNotConfigured);
+            }
+        }
+        previous = Some(tier);
+    }
+    Ok(())
 }
 
-/// Borrow a specific asset against cross-asset collateral.
+/// Cap the share of a market's available liquidity borrowable per ledger
+/// (admin or `RiskAdmin` only).
 ///
-/// Requires user authorization. Validates the asset is enabled for borrowing,
-/// checks the borrow cap, and verifies the post-borrow health factor stays
-/// above 1.0. If the health check fails, the borrow is rolled back.
+/// All borrows from the market within one ledger share the allowance, which
+/// is `cap_bps` of the liquidity available at the ledger's first borrow; a
+/// larger amount must be split across ledgers. This keeps a single
+/// transaction from draining the market or swinging its rate at once.
 ///
 /// # Arguments
-/// * `env` - The contract environment
-/// * `user` - User borrowing (must authorize)
-/// * `asset` - Asset to borrow (`None` for XLM)
-/// * `amount` - Amount to borrow
-///
-/// # Returns
-/// Updated [`AssetPosition`] after the borrow.
+/// * `asset` - The market (`None` for native XLM)
+/// * `cap_bps` - Share of available liquidity in basis points (0 removes the cap)
 ///
 /// # Errors
-/// * `AssetNotConfigured` - Asset is not registered
-/// * `AssetDisabled` - Asset is not enabled for borrowing
-/// * `BorrowCapExceeded` - Borrow would exceed the asset's borrow cap
-/// * `ExceedsBorrowCapacity` - Health factor would drop below 1.0
-/// * `PriceStale` - Stale price prevents health factor calculation
-pub fn cross_asset_borrow(
+/// * `NotAuthorized` - Caller is neither the admin nor a `RiskAdmin`
+/// * `AssetNotConfigured` - Asset is not registered or cap is out of range
+pub fn set_max_borrow_size(
     env: &Env,
-    user: Address,
+    caller: Address,
     asset: Option<Address>,
-    amount: i128,
-) -> Result<AssetPosition, CrossAssetError> {
-    user.require_auth();
-
-    let asset_key = AssetKey::from_option(asset.clone());
-    let config = get_asset_config(env, &asset_key)?;
-
-    if !config.can_borrow {
-        return Err(CrossAssetError::AssetDisabled);
-    }
+    cap_bps: i128,
+) -> Result<(), CrossAssetError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+    let asset_key = AssetKey::from_option(asset);
+    get_asset_config(env, &asset_key)?;
+    require_valid_basis_points(cap_bps)?;
 
-    if config.max_borrow > 0 {
-        let total_borrow = get_total_borrow(env, &asset_key);
-        if total_borrow + amount > config.max_borrow {
-            return Err(CrossAssetError::BorrowCapExceeded);
-        }
+    let mut caps: Map<AssetKey, i128> =
+        storage::get(env, &MAX_BORROW_SIZE).unwrap_or(Map::new(env));
+    if cap_bps == 0 {
+        caps.remove(asset_key);
+    } else {
+        caps.set(asset_key, cap_bps);
     }
+    storage::set(env, &MAX_BORROW_SIZE, &caps);
 
-    let mut position = get_user_asset_position(env, &user, asset.clone());
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_max_borrow_size"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
 
-    position.debt_principal += amount;
-    position.last_updated = env.ledger().timestamp();
+/// Return a market's per-ledger borrow cap in basis points, if set.
+pub fn get_max_borrow_size(env: &Env, asset: Option<Address>) -> Option<i128> {
+    let caps: Map<AssetKey, i128> = storage::get(env, &MAX_BORROW_SIZE).unwrap_or(Map::new(env));
+    caps.get(AssetKey::from_option(asset))
+}
 
-    set_user_asset_position(env, &user, asset.clone(), position.clone());
+/// Check a borrow against the market's per-ledger cap.
+///
+/// # Returns
+/// The market's ledger totals including the borrow, to store once it
+/// succeeds, or `None` if the market has no cap.
+fn check_borrow_size(
+    env: &Env,
+    asset_key: &AssetKey,
+    total_borrow: i128,
+    amount: i128,
+) -> Result<Option<LedgerBorrows>, CrossAssetError> {
+    let caps: Map<AssetKey, i128> = storage::get(env, &MAX_BORROW_SIZE).unwrap_or(Map::new(env));
+    let Some(cap_bps) = caps.get(asset_key.clone()) else {
+        return Ok(None);
+    };
 
-    let summary = get_user_position_summary(env, &user)?;
+    let ledger = env.ledger().sequence();
+    let all: Map<AssetKey, LedgerBorrows> =
+        storage::get(env, &LEDGER_BORROWS).unwrap_or(Map::new(env));
+    let mut borrows = match all.get(asset_key.clone()) {
+        Some(borrows) if borrows.ledger == ledger => borrows,
+        _ => LedgerBorrows {
+            ledger,
+            available: (get_total_supply(env, asset_key) - total_borrow).max(0),
+            borrowed: 0,
+        },
+    };
 
-    if summary.health_factor < 10_000 {
-        position.debt_principal -= amount;
-        set_user_asset_position(env, &user, asset, position);
-        return Err(CrossAssetError::ExceedsBorrowCapacity);
+    borrows.borrowed = borrows
+        .borrowed
+        .checked_add(amount)
+        .ok_or(CrossAssetError::Overflow)?;
+    let allowance = Bps::new(cap_bps).apply(Amount::new(borrows.available))?;
+    if borrows.borrowed > allowance.get() {
+        return Err(CrossAssetError::BorrowSizeExceeded);
     }
+    Ok(Some(borrows))
+}
 
-    update_total_borrow(env, &asset_key, amount);
-
-    Ok(position)
+/// Return the supply tenure rebate schedule.
+pub fn get_tenure_rebates(env: &Env) -> Vec<TenureRebateTier> {
+    storage::get(env, &TENURE_TIERS).unwrap_or(Vec::new(env))
 }
 
-/// Repay debt for a specific asset.
+/// Build a user's supply statement for one asset.
 ///
-/// Requires user authorization. Repayment is capped at the total outstanding
-/// debt (principal + accrued interest). Interest is paid first, then principal.
+/// The rebate comes from the highest tier whose tenure the user has reached
+/// and is subtracted from the asset's reserve factor, floored at zero.
 ///
-/// # Arguments
-/// * `env` - The contract environment
-/// * `user` - User repaying debt (must authorize)
-/// * `asset` - Asset to repay (`None` for XLM)
-/// * `amount` - Amount to repay (capped at total debt)
+/// # Errors
+/// * `AssetNotConfigured` - Asset is not registered
+pub fn get_supply_statement(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+) -> Result<SupplyStatement, CrossAssetError> {
+    let config = get_asset_config(env, &AssetKey::from_option(asset.clone()))?;
+    let position = get_user_asset_position(env, user, asset);
+
+    let tenure_seconds = if position.supplied_since == 0 {
+        0
+    } else {
+        env.ledger()
+            .timestamp()
+            .saturating_sub(position.supplied_since)
+    };
+
+    let mut rebate_bps = 0;
+    if position.collateral > 0 {
+        for tier in get_tenure_rebates(env).iter() {
+            if tenure_seconds >= tier.min_tenure_seconds {
+                rebate_bps = tier.rebate_bps;
+            }
+        }
+    }
+
+    Ok(SupplyStatement {
+        supplied: position.collateral,
+        supplied_since: position.supplied_since,
+        tenure_seconds,
+        reserve_factor: config.reserve_factor,
+        rebate_bps,
+        effective_reserve_factor: (config.reserve_factor - rebate_bps).max(0),
+    })
+}
+
+/// Summarize a market's balances and activity.
 ///
-/// # Returns
-/// Updated [`AssetPosition`] after the repayment.
-pub fn cross_asset_repay(
+/// # Errors
+/// * `AssetNotConfigured` - Asset is not registered
+pub fn get_market_summary(
     env: &Env,
-    user: Address,
     asset: Option<Address>,
-    amount: i128,
-) -> Result<AssetPosition, CrossAssetError> {
-    user.require_auth();
+) -> Result<MarketSummary, CrossAssetError> {
+    let asset_key = AssetKey::from_option(asset);
+    get_asset_config(env, &asset_key)?;
+    Ok(build_market_summary(env, asset_key))
+}
 
+/// Get a market's balances, rates, caps, risk parameters and pause switches
+/// in one call.
+///
+/// # Errors
+/// * `AssetNotConfigured` - The asset is not listed
+/// * `Overflow` - The market's rates could not be calculated
+pub fn get_reserve_data(env: &Env, asset: Option<Address>) -> Result<ReserveData, CrossAssetError> {
     let asset_key = AssetKey::from_option(asset.clone());
+    let config = get_asset_config(env, &asset_key)?;
+    let emergency = is_emergency_paused(env);
+    let paused = |operation| emergency || is_asset_paused(env, &asset_key, operation);
 
-    // Get current position
-    let mut position = get_user_asset_position(env, &user, asset.clone());
+    Ok(ReserveData {
+        total_supplied: get_total_supply(env, &asset_key),
+        total_borrowed: get_total_borrow(env, &asset_key),
+        utilization: get_market_utilization(env, &asset_key),
+        supply_apy: get_asset_supply_rate(env, asset.clone(), config.reserve_factor)?,
+        borrow_apr: get_asset_borrow_rate(env, asset.clone())?,
+        supply_cap: config.max_supply,
+        borrow_cap: config.max_borrow,
+        collateral_factor: config.collateral_factor,
+        liquidation_threshold: config.collateral_factor,
+        deposit_paused: paused(PAUSE_DEPOSIT),
+        withdraw_paused: paused(PAUSE_WITHDRAW),
+        borrow_paused: paused(PAUSE_BORROW),
+        repay_paused: paused(PAUSE_REPAY),
+        liquidate_paused: paused(PAUSE_LIQUIDATE),
+        delisted: is_asset_delisted(env, &asset_key),
+        reserves: get_reserves(env, asset),
+        asset: asset_key,
+    })
+}
 
-    let total_debt = position.debt_principal + position.accrued_interest;
-    let repay_amount = amount.min(total_debt);
+/// List markets with no interaction for at least `threshold_seconds`.
+///
+/// Intended for governance pruning decisions. Each entry also reports how
+/// long the market has gone without a borrow.
+pub fn get_inactive_markets(env: &Env, threshold_seconds: u64) -> Vec<MarketSummary> {
+    let mut result = Vec::new(env);
+    for asset_key in get_asset_list(env).iter() {
+        let summary = build_market_summary(env, asset_key);
+        if summary.idle_seconds >= threshold_seconds {
+            result.push_back(summary);
+        }
+    }
+    result
+}
 
-    // Pay interest first, then principal
-    if repay_amount <= position.accrued_interest {
-        position.accrued_interest -= repay_amount;
-    } else {
-        let remaining = repay_amount - position.accrued_interest;
-        position.accrued_interest = 0;
-        position.debt_principal -= remaining;
+fn build_market_summary(env: &Env, asset_key: AssetKey) -> MarketSummary {
+    let activities: Map<AssetKey, MarketActivity> =
+        storage::get(env, &MARKET_ACTIVITY).unwrap_or(Map::new(env));
+    let now = env.ledger().timestamp();
+    let activity = activities.get(asset_key.clone()).unwrap_or(MarketActivity {
+        last_interaction: 0,
+        last_borrow: 0,
+    });
+
+    MarketSummary {
+        total_supply: get_total_supply(env, &asset_key),
+        total_borrow: get_total_borrow(env, &asset_key),
+        last_interaction: activity.last_interaction,
+        last_borrow: activity.last_borrow,
+        idle_seconds: now.saturating_sub(activity.last_interaction),
+        borrow_idle_seconds: now.saturating_sub(activity.last_borrow),
+        asset: asset_key,
     }
+}
 
-    position.last_updated = env.ledger().timestamp();
+/// Stamp a market's last interaction, and its last borrow if `borrowed`.
+fn record_market_activity(env: &Env, asset_key: &AssetKey, borrowed: bool) {
+    let mut activities: Map<AssetKey, MarketActivity> =
+        storage::get(env, &MARKET_ACTIVITY).unwrap_or(Map::new(env));
+    let now = env.ledger().timestamp();
+    let mut activity = activities.get(asset_key.clone()).unwrap_or(MarketActivity {
+        last_interaction: now,
+        last_borrow: now,
+    });
 
-    // Update storage
-    set_user_asset_position(env, &user, asset, position.clone());
-    update_total_borrow(env, &asset_key, -repay_amount);
+    activity.last_interaction = now;
+    if borrowed {
+        activity.last_borrow = now;
+    }
+    activities.set(asset_key.clone(), activity);
+    storage::set(env, &MARKET_ACTIVITY, &activities);
+}
 
-    Ok(position)
+/// Collect a user's non-empty positions across all registered assets.
+///
+/// Assets where the user has neither collateral nor debt are omitted.
+pub fn get_user_asset_positions(env: &Env, user: &Address) -> Map<AssetKey, AssetPosition> {
+    let asset_list = get_asset_list(env);
+    let mut result = Map::new(env);
+
+    for asset_key in asset_list.iter() {
+        let position = get_user_asset_position(env, user, asset_key.to_option());
+        if position.collateral != 0
+            || position.debt_principal != 0
+            || position.accrued_interest != 0
+        {
+            result.set(asset_key, position);
+        }
+    }
+
+    result
+}
+
+/// Return every registered asset configuration, keyed by asset, with ramped
+/// collateral factors at their current values.
+pub(crate) fn get_asset_configs(env: &Env) -> Map<AssetKey, AssetConfig> {
+    let mut configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
+    let ramps: Map<AssetKey, CollateralFactorRamp> =
+        storage::get(env, &COLLATERAL_FACTOR_RAMPS).unwrap_or(Map::new(env));
+    let now = env.ledger().timestamp();
+    for (asset_key, ramp) in ramps.iter() {
+        if let Some(mut config) = configs.get(asset_key.clone()) {
+            config.collateral_factor = ramp.factor_at(now);
+            configs.set(asset_key, config);
+        }
+    }
+    for (asset_key, mut config) in configs.iter() {
+        if apply_emergency_price(env, &asset_key, &mut config) {
+            configs.set(asset_key, config);
+        }
+    }
+    configs
 }
 
 /// Return the list of all registered asset keys.
 ///
 /// Returns an empty vector if no assets have been configured.
 pub fn get_asset_list(env: &Env) -> Vec<AssetKey> {
-    env.storage()
-        .persistent()
-        .get(&ASSET_LIST)
-        .unwrap_or(Vec::new(env))
+    storage::get(env, &ASSET_LIST).unwrap_or(Vec::new(env))
 }
 
 /// Look up the configuration for a specific asset by address.
@@ -704,21 +4407,119 @@ pub fn get_asset_config_by_address(
     get_asset_config(env, &asset_key)
 }
 
+/// Set a listed asset's supply and borrow caps.
+///
+/// Returns `false`, storing nothing, if the asset is not listed.
+pub(crate) fn set_listed_asset_caps(
+    env: &Env,
+    asset: &Address,
+    max_supply: i128,
+    max_borrow: i128,
+) -> bool {
+    let mut configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
+    let asset_key = AssetKey::from_option(Some(asset.clone()));
+    let Some(mut config) = configs.get(asset_key.clone()) else {
+        return false;
+    };
+    config.max_supply = max_supply;
+    config.max_borrow = max_borrow;
+    configs.set(asset_key, config);
+    storage::set(env, &ASSET_CONFIGS, &configs);
+    true
+}
+
 // Helper functions
 
-fn get_asset_config(env: &Env, asset_key: &AssetKey) -> Result<AssetConfig, CrossAssetError> {
-    let configs: Map<AssetKey, AssetConfig> = env
-        .storage()
-        .persistent()
-        .get(&ASSET_CONFIGS)
-        .unwrap_or(Map::new(env));
+pub(crate) fn get_asset_config(
+    env: &Env,
+    asset_key: &AssetKey,
+) -> Result<AssetConfig, CrossAssetError> {
+    let mut config = get_stored_asset_config(env, asset_key)?;
+    apply_emergency_price(env, asset_key, &mut config);
+    Ok(config)
+}
+
+/// An asset's configuration with any collateral factor ramp applied, but
+/// not an emergency price, so it can be changed and stored back.
+fn get_stored_asset_config(
+    env: &Env,
+    asset_key: &AssetKey,
+) -> Result<AssetConfig, CrossAssetError> {
+    let configs: Map<AssetKey, AssetConfig> =
+        storage::get(env, &ASSET_CONFIGS).unwrap_or(Map::new(env));
 
-    configs
+    let mut config = configs
         .get(asset_key.clone())
-        .ok_or(CrossAssetError::AssetNotConfigured)
+        .ok_or(CrossAssetError::AssetNotConfigured)?;
+    let ramps: Option<Map<AssetKey, CollateralFactorRamp>> =
+        storage::get(env, &COLLATERAL_FACTOR_RAMPS);
+    if let Some(ramp) = ramps.and_then(|ramps| ramps.get(asset_key.clone())) {
+        config.collateral_factor = ramp.factor_at(env.ledger().timestamp());
+    }
+    Ok(config)
+}
+
+/// Price a config at its asset's active emergency override, if any, as of
+/// when the override was set.
+///
+/// # Returns
+/// Whether an override was applied.
+fn apply_emergency_price(env: &Env, asset_key: &AssetKey, config: &mut AssetConfig) -> bool {
+    let AssetKey::Token(asset) = asset_key else {
+        return false;
+    };
+    let Some(emergency) = oracle::get_emergency_price(env, asset) else {
+        return false;
+    };
+    let Ok(price) = normalize_price(emergency.price, oracle::DEFAULT_PRICE_DECIMALS) else {
+        return false;
+    };
+    config.price = price;
+    config.price_updated_at = emergency.set_at;
+    true
+}
+
+/// Convert an oracle price with `decimals` decimals to [`PRICE_DECIMALS`].
+///
+/// # Errors
+/// * `InvalidPrice` - The converted price is not positive
+/// * `Overflow` - The conversion overflowed
+pub(crate) fn normalize_price(price: i128, decimals: u32) -> Result<i128, CrossAssetError> {
+    let normalized = if decimals >= PRICE_DECIMALS {
+        10i128
+            .checked_pow(decimals - PRICE_DECIMALS)
+            .map(|scale| price / scale)
+    } else {
+        10i128
+            .checked_pow(PRICE_DECIMALS - decimals)
+            .and_then(|scale| price.checked_mul(scale))
+    }
+    .ok_or(CrossAssetError::Overflow)?;
+    if normalized <= 0 {
+        return Err(CrossAssetError::InvalidPrice);
+    }
+    Ok(normalized)
+}
+
+/// Value of `amount` at a [`PRICE_DECIMALS`] price, rounding toward zero.
+///
+/// # Errors
+/// * `Overflow` - The value overflowed
+fn value_at(amount: i128, price: i128) -> Result<Amount, CrossAssetError> {
+    Ok(Amount::new(amount).mul_div(price, 10_000_000)?)
+}
+
+/// Start the tenure clock on a first supply and reset it once fully withdrawn.
+fn refresh_supply_tenure(env: &Env, position: &mut AssetPosition) {
+    if position.collateral == 0 {
+        position.supplied_since = 0;
+    } else if position.supplied_since == 0 {
+        position.supplied_since = env.ledger().timestamp();
+    }
 }
 
-fn require_valid_config(config: &AssetConfig) -> Result<(), CrossAssetError> {
+pub(crate) fn require_valid_config(config: &AssetConfig) -> Result<(), CrossAssetError> {
     require_valid_basis_points(config.collateral_factor)?;
     require_valid_basis_points(config.borrow_factor)?;
     require_valid_basis_points(config.reserve_factor)?;
@@ -731,54 +4532,52 @@ fn require_valid_config(config: &AssetConfig) -> Result<(), CrossAssetError> {
 }
 
 fn require_valid_basis_points(value: i128) -> Result<(), CrossAssetError> {
-    if !(0..=10_000).contains(&value) {
+    if !Bps::new(value).is_ratio() {
         return Err(CrossAssetError::AssetNotConfigured);
     }
     Ok(())
 }
 
-fn get_total_supply(env: &Env, asset_key: &AssetKey) -> i128 {
-    let supplies: Map<AssetKey, i128> = env
-        .storage()
-        .persistent()
-        .get(&TOTAL_SUPPLIES)
-        .unwrap_or(Map::new(env));
+pub(crate) fn get_total_supply(env: &Env, asset_key: &AssetKey) -> i128 {
+    let supplies: Map<AssetKey, i128> = storage::get(env, &TOTAL_SUPPLIES).unwrap_or(Map::new(env));
 
     supplies.get(asset_key.clone()).unwrap_or(0)
 }
 
 fn update_total_supply(env: &Env, asset_key: &AssetKey, delta: i128) {
-    let mut supplies: Map<AssetKey, i128> = env
-        .storage()
-        .persistent()
-        .get(&TOTAL_SUPPLIES)
-        .unwrap_or(Map::new(env));
+    let mut supplies: Map<AssetKey, i128> =
+        storage::get(env, &TOTAL_SUPPLIES).unwrap_or(Map::new(env));
 
     let current = supplies.get(asset_key.clone()).unwrap_or(0);
     supplies.set(asset_key.clone(), current + delta);
-    env.storage().persistent().set(&TOTAL_SUPPLIES, &supplies);
+    storage::set(env, &TOTAL_SUPPLIES, &supplies);
 }
 
-fn get_total_borrow(env: &Env, asset_key: &AssetKey) -> i128 {
-    let borrows: Map<AssetKey, i128> = env
-        .storage()
-        .persistent()
-        .get(&TOTAL_BORROWS)
-        .unwrap_or(Map::new(env));
+pub(crate) fn get_total_borrow(env: &Env, asset_key: &AssetKey) -> i128 {
+    let borrows: Map<AssetKey, i128> = storage::get(env, &TOTAL_BORROWS).unwrap_or(Map::new(env));
 
     borrows.get(asset_key.clone()).unwrap_or(0)
 }
 
 fn update_total_borrow(env: &Env, asset_key: &AssetKey, delta: i128) {
-    let mut borrows: Map<AssetKey, i128> = env
-        .storage()
-        .persistent()
-        .get(&TOTAL_BORROWS)
-        .unwrap_or(Map::new(env));
+    let mut borrows: Map<AssetKey, i128> =
+        storage::get(env, &TOTAL_BORROWS).unwrap_or(Map::new(env));
 
     let current = borrows.get(asset_key.clone()).unwrap_or(0);
     borrows.set(asset_key.clone(), current + delta);
-    env.storage().persistent().set(&TOTAL_BORROWS, &borrows);
+    storage::set(env, &TOTAL_BORROWS, &borrows);
+}
+
+/// Utilization of a market (`total_borrow / total_supply`) in basis points,
+/// capped at 100%. A market with no supply has zero utilization.
+pub(crate) fn get_market_utilization(env: &Env, asset_key: &AssetKey) -> i128 {
+    let total_supply = get_total_supply(env, asset_key);
+    if total_supply <= 0 {
+        return 0;
+    }
+    let total_borrow = get_total_borrow(env, asset_key).max(0);
+    (total_borrow.saturating_mul(BASIS_POINTS_SCALE.get()) / total_supply)
+        .min(BASIS_POINTS_SCALE.get())
 }
 
 /// Combined key for user-asset position lookups
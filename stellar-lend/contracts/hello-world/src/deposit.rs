@@ -11,24 +11,68 @@
 //! ## Storage Layout
 //! - `CollateralBalance(user)` — per-user collateral amount
 //! - `Position(user)` — per-user position (collateral, debt, interest)
-//! - `AssetParams(asset)` — per-asset deposit parameters
+//! - `AssetParams(asset)` — legacy per-asset parameters (see Asset Configuration)
+//! - `AssetTotals(asset)` — per-asset supplied and borrowed totals
 //! - `PauseSwitches` — operation pause flags
 //! - `ProtocolAnalytics` — aggregate protocol metrics
 //! - `UserAnalytics(user)` — per-user activity metrics
-//! - `ActivityLog` — bounded activity history (max 1000 entries)
+//! - `ActivityLog` — legacy activity history (see the analytics module)
 //!
 //! ## Invariants
 //! - Deposit amount must be strictly positive.
 //! - Deposits are rejected when the protocol or deposit operation is paused.
 //! - Token transfers use `transfer_from`, requiring prior user approval.
+//!
+//! ## Asset Configuration
+//! An asset is configured once, by the cross-asset module's [`AssetConfig`]:
+//! deposits, borrows and withdrawals here read its collateral factor, caps and
+//! `can_collateralize`/`can_borrow` flags through [`get_core_asset_config`].
+//! A delisted asset takes no new deposits or borrows, and an isolated asset
+//! is only accepted as cross-asset collateral.
+//! [`AssetParams`] predate listing and only configure assets that were never
+//! listed; they are converted to an `AssetConfig` on read, with
+//! `deposit_enabled` standing for both flags. Their `max_deposit`, a
+//! per-deposit limit with no `AssetConfig` counterpart, applies either way.
+//!
+//! ## Caps
+//! An asset's supply and borrow caps bound the totals supplied and borrowed
+//! across all users; 0 means unlimited. Totals follow the asset named in each
+//! deposit, withdrawal, borrow, repayment and liquidation and never go below
+//! zero.
+//!
+//! ## Integrator Tags
+//! Deposits and borrows may carry an integrator-supplied `tag` (for example
+//! an aggregator or vault's product name). The tag is stored under the `tag`
+//! key of the activity entry's metadata and counted toward the tag's
+//! attribution totals; it never affects position logic.
 
 #![allow(unused)]
-use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
+use soroban_sdk::{
+    contracterror, contracttype, symbol_short, Address, Env, IntoVal, Map, Symbol, Val, Vec,
+};
 
+use crate::analytics::{
+    append_activity, is_analytics_frozen, record_tagged_flow, record_tvl, ActivityEntry,
+    ActivityType,
+};
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::{BASIS_POINTS_SCALE, DEFAULT_COLLATERAL_FACTOR};
+use crate::cross_asset::{
+    get_asset_config_by_address, is_asset_delisted, is_asset_isolated, register_user,
+    set_listed_asset_caps, AssetConfig, AssetKey,
+};
 use crate::events::{
-    emit_analytics_updated, emit_deposit, emit_position_updated, emit_user_activity_tracked,
-    AnalyticsUpdatedEvent, DepositEvent, PositionUpdatedEvent, UserActivityTrackedEvent,
+    emit_analytics_updated, emit_asset_caps_updated, emit_deposit, emit_position_updated,
+    emit_user_activity_tracked, AnalyticsUpdatedEvent, AssetCapsUpdatedEvent, DepositEvent,
+    PositionUpdatedEvent, UserActivityTrackedEvent,
 };
+use crate::loyalty::refresh_tier;
+use crate::math::{Amount, MathError};
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::risk_management::require_role;
+use crate::roles::Role;
+use crate::storage;
+use stellarlend_common::pause::{is_switch_set, PAUSE_DEPOSIT};
 
 /// Errors that can occur during deposit operations
 #[contracterror]
@@ -43,12 +87,30 @@ pub enum DepositError {
     InsufficientBalance = 3,
     /// Deposit operations are currently paused
     DepositPaused = 4,
-    /// Asset is not enabled for deposits
+    /// Asset is not enabled for deposits, or is delisted
     AssetNotEnabled = 5,
     /// Overflow occurred during calculation
     Overflow = 6,
     /// Reentrancy detected
     Reentrancy = 7,
+    /// Deposit would push the asset's total supply above its cap
+    SupplyCapExceeded = 8,
+    /// Caller is not the admin
+    Unauthorized = 9,
+    /// Asset is isolated and only accepted as cross-asset collateral
+    AssetIsolated = 10,
+}
+
+impl From<Reentered> for DepositError {
+    fn from(_: Reentered) -> Self {
+        DepositError::Reentrancy
+    }
+}
+
+impl From<MathError> for DepositError {
+    fn from(_: MathError) -> Self {
+        DepositError::Overflow
+    }
 }
 
 /// Storage keys for deposit-related data
@@ -70,8 +132,12 @@ pub enum DepositDataKey {
     ProtocolAnalytics,
     /// User analytics: Map<Address, UserAnalytics>
     UserAnalytics(Address),
-    /// Activity log: Vec<Activity>
+    /// Legacy activity log, now read by the analytics module: Vec<LegacyActivityEntry>
     ActivityLog,
+    /// Timestamp of the user's most recent borrow: u64
+    LastBorrowTime(Address),
+    /// Total supplied and borrowed per asset: AssetTotals
+    AssetTotals(Address),
 }
 
 /// Asset parameters for collateral
@@ -84,6 +150,20 @@ pub struct AssetParams {
     pub collateral_factor: i128,
     /// Maximum deposit amount
     pub max_deposit: i128,
+    /// Cap on the total supplied across all users (0 = unlimited)
+    pub supply_cap: i128,
+    /// Cap on the total borrowed across all users (0 = unlimited)
+    pub borrow_cap: i128,
+}
+
+/// Amounts supplied and borrowed in one asset, checked against its caps
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetTotals {
+    /// Collateral supplied
+    pub supplied: i128,
+    /// Principal borrowed
+    pub borrowed: i128,
 }
 
 /// User position tracking
@@ -100,24 +180,6 @@ pub struct Position {
     pub last_accrual_time: u64,
 }
 
-/// Activity log entry
-#[contracttype]
-#[derive(Clone, Debug, PartialEq)]
-pub struct Activity {
-    /// User address
-    pub user: Address,
-    /// Activity type (e.g., "deposit", "withdraw", "borrow")
-    pub activity_type: Symbol,
-    /// Amount involved
-    pub amount: i128,
-    /// Asset address (if applicable)
-    pub asset: Option<Address>,
-    /// Timestamp
-    pub timestamp: u64,
-    /// Additional metadata
-    pub metadata: Map<Symbol, Symbol>,
-}
-
 /// User analytics
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -162,6 +224,118 @@ pub struct ProtocolAnalytics {
     pub total_value_locked: i128,
 }
 
+/// Set an asset's supply and borrow caps (risk admin only)
+///
+/// Updates the asset's [`AssetConfig`] if it is listed. Otherwise updates its
+/// legacy parameters, creating defaults (deposits enabled, default collateral
+/// factor, no per-deposit limit) if the asset has none yet.
+///
+/// # Arguments
+/// * `caller` - The caller address (must hold the RiskAdmin role)
+/// * `asset` - The asset contract address
+/// * `supply_cap` - Cap on the total supplied (0 = unlimited)
+/// * `borrow_cap` - Cap on the total borrowed (0 = unlimited)
+///
+/// # Errors
+/// * `DepositError::Unauthorized` - If caller lacks the RiskAdmin role
+/// * `DepositError::InvalidAmount` - If a cap is negative
+pub fn set_asset_caps(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    supply_cap: i128,
+    borrow_cap: i128,
+) -> Result<(), DepositError> {
+    require_role(env, &caller, Role::RiskAdmin).map_err(|_| DepositError::Unauthorized)?;
+
+    if supply_cap < 0 || borrow_cap < 0 {
+        return Err(DepositError::InvalidAmount);
+    }
+
+    if !set_listed_asset_caps(env, &asset, supply_cap, borrow_cap) {
+        let key = DepositDataKey::AssetParams(asset.clone());
+        let mut params =
+            storage::get::<DepositDataKey, AssetParams>(env, &key).unwrap_or(AssetParams {
+                deposit_enabled: true,
+                collateral_factor: DEFAULT_COLLATERAL_FACTOR.get(),
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            });
+        params.supply_cap = supply_cap;
+        params.borrow_cap = borrow_cap;
+        storage::set(env, &key, &params);
+    }
+
+    emit_asset_caps_updated(
+        env,
+        AssetCapsUpdatedEvent {
+            admin: caller,
+            asset,
+            supply_cap,
+            borrow_cap,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get an asset's legacy parameters, if set
+pub fn get_asset_params(env: &Env, asset: &Address) -> Option<AssetParams> {
+    storage::get(env, &DepositDataKey::AssetParams(asset.clone()))
+}
+
+/// Get the configuration deposits, borrows and withdrawals apply to an asset
+///
+/// The asset's [`AssetConfig`] if it is listed, otherwise its legacy
+/// parameters converted to one.
+pub(crate) fn get_core_asset_config(env: &Env, asset: &Address) -> Option<AssetConfig> {
+    if let Ok(config) = get_asset_config_by_address(env, Some(asset.clone())) {
+        return Some(config);
+    }
+    get_asset_params(env, asset).map(|params| params.to_asset_config(asset))
+}
+
+impl AssetParams {
+    /// The equivalent configuration of an asset that was never listed
+    fn to_asset_config(&self, asset: &Address) -> AssetConfig {
+        AssetConfig {
+            asset: Some(asset.clone()),
+            collateral_factor: self.collateral_factor,
+            borrow_factor: BASIS_POINTS_SCALE.get(),
+            reserve_factor: 0,
+            max_supply: self.supply_cap,
+            max_borrow: self.borrow_cap,
+            can_collateralize: self.deposit_enabled,
+            can_borrow: self.deposit_enabled,
+            price: 0,
+            price_updated_at: 0,
+        }
+    }
+}
+
+/// Get the amounts supplied and borrowed in an asset
+pub fn get_asset_totals(env: &Env, asset: &Address) -> AssetTotals {
+    storage::get(env, &DepositDataKey::AssetTotals(asset.clone())).unwrap_or_default()
+}
+
+/// Add signed deltas to an asset's totals, flooring each at zero. Native XLM
+/// has no asset parameters and is not tracked.
+pub(crate) fn adjust_asset_totals(
+    env: &Env,
+    asset: &Option<Address>,
+    supplied_delta: i128,
+    borrowed_delta: i128,
+) {
+    let Some(asset) = asset else {
+        return;
+    };
+    let mut totals = get_asset_totals(env, asset);
+    totals.supplied = totals.supplied.saturating_add(supplied_delta).max(0);
+    totals.borrowed = totals.borrowed.saturating_add(borrowed_delta).max(0);
+    storage::set(env, &DepositDataKey::AssetTotals(asset.clone()), &totals);
+}
+
 /// Deposit collateral function
 ///
 /// Allows users to deposit assets as collateral in the protocol.
@@ -171,6 +345,7 @@ pub struct ProtocolAnalytics {
 /// * `user` - The address of the user depositing collateral
 /// * `asset` - The address of the asset contract to deposit (None for native XLM)
 /// * `amount` - The amount to deposit
+/// * `tag` - Optional integrator tag recorded with the activity
 ///
 /// # Returns
 /// Returns the updated collateral balance for the user
@@ -196,7 +371,10 @@ pub fn deposit_collateral(
     user: Address,
     asset: Option<Address>,
     amount: i128,
+    tag: Option<Symbol>,
 ) -> Result<i128, DepositError> {
+    let _guard = OperationGuard::enter(env)?;
+
     // Validate amount
     if amount <= 0 {
         return Err(DepositError::InvalidAmount);
@@ -207,15 +385,11 @@ pub fn deposit_collateral(
     // This check maintains backward compatibility with the old pause switch system.
     // The risk management pause switches are checked at the contract level in lib.rs.
     let pause_switches_key = DepositDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Map<Symbol, bool>>(&pause_switches_key)
+    if let Some(pause_map) =
+        storage::get::<DepositDataKey, Map<Symbol, bool>>(env, &pause_switches_key)
     {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_deposit")) {
-            if paused {
-                return Err(DepositError::DepositPaused);
-            }
+        if is_switch_set(env, &pause_map, PAUSE_DEPOSIT) {
+            return Err(DepositError::DepositPaused);
         }
     }
 
@@ -233,18 +407,26 @@ pub fn deposit_collateral(
             return Err(DepositError::InvalidAsset);
         }
 
-        // Check asset parameters
-        let asset_params_key = DepositDataKey::AssetParams(asset_addr.clone());
-        if let Some(params) = env
-            .storage()
-            .persistent()
-            .get::<DepositDataKey, AssetParams>(&asset_params_key)
-        {
-            if !params.deposit_enabled {
+        // Check asset configuration
+        if let Some(config) = get_core_asset_config(env, asset_addr) {
+            let asset_key = AssetKey::Token(asset_addr.clone());
+            if !config.can_collateralize || is_asset_delisted(env, &asset_key) {
                 return Err(DepositError::AssetNotEnabled);
             }
+            // Core collateral is pooled across assets, so mixing cannot be prevented
+            if is_asset_isolated(env, &asset_key) {
+                return Err(DepositError::AssetIsolated);
+            }
 
-            // Check max deposit limit
+            // Check supply cap
+            let supplied = get_asset_totals(env, asset_addr).supplied;
+            if config.max_supply > 0 && supplied.saturating_add(amount) > config.max_supply {
+                return Err(DepositError::SupplyCapExceeded);
+            }
+        }
+
+        // Check max deposit limit
+        if let Some(params) = get_asset_params(env, asset_addr) {
             if params.max_deposit > 0 && amount > params.max_deposit {
                 return Err(DepositError::InvalidAmount);
             }
@@ -269,6 +451,7 @@ pub fn deposit_collateral(
             &env.current_contract_address(), // to (this contract)
             &amount,
         );
+        cash_ledger::record_inflow(env, asset_addr, CashReason::Deposit, amount);
     } else {
         // Native XLM deposit - in Soroban, native assets are handled differently
         // For now, we'll track it but actual XLM handling depends on Soroban's native asset support
@@ -278,11 +461,8 @@ pub fn deposit_collateral(
     // Get or create user position
     let position_key = DepositDataKey::Position(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
-        .unwrap_or_else(|| Position {
+    let mut position =
+        storage::get::<DepositDataKey, Position>(env, &position_key).unwrap_or_else(|| Position {
             collateral: 0,
             debt: 0,
             borrow_interest: 0,
@@ -291,26 +471,23 @@ pub fn deposit_collateral(
 
     // Update collateral balance
     let collateral_key = DepositDataKey::CollateralBalance(user.clone());
-    let current_collateral = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    let current_collateral =
+        storage::get::<DepositDataKey, i128>(env, &collateral_key).unwrap_or(0);
 
     // Check for overflow
-    let new_collateral = current_collateral
-        .checked_add(amount)
-        .ok_or(DepositError::Overflow)?;
+    let new_collateral = Amount::new(current_collateral)
+        .checked_add(Amount::new(amount))?
+        .get();
 
     // Update storage
-    env.storage()
-        .persistent()
-        .set(&collateral_key, &new_collateral);
+    storage::set(env, &collateral_key, &new_collateral);
 
     // Update position
     position.collateral = new_collateral;
     position.last_accrual_time = timestamp;
-    env.storage().persistent().set(&position_key, &position);
+    storage::set(env, &position_key, &position);
+    adjust_asset_totals(env, &asset, amount, 0);
+    register_user(env, &user);
 
     // Update user analytics
     update_user_analytics(env, &user, amount, timestamp, true)?;
@@ -322,11 +499,15 @@ pub fn deposit_collateral(
     add_activity_log(
         env,
         &user,
-        Symbol::new(env, "deposit"),
+        ActivityType::Deposit,
         amount,
         asset.clone(),
         timestamp,
+        tag.clone(),
     )?;
+    if let Some(tag) = tag {
+        record_tagged_flow(env, tag, amount, 0, false);
+    }
 
     // Emit deposit event
     emit_deposit(
@@ -361,10 +542,7 @@ pub fn update_user_analytics(
 ) -> Result<(), DepositError> {
     let analytics_key = DepositDataKey::UserAnalytics(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, UserAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, UserAnalytics>(env, &analytics_key)
         .unwrap_or_else(|| UserAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -382,20 +560,19 @@ pub fn update_user_analytics(
         });
 
     if is_deposit {
-        analytics.total_deposits = analytics
-            .total_deposits
-            .checked_add(amount)
-            .ok_or(DepositError::Overflow)?;
-        analytics.collateral_value = analytics
-            .collateral_value
-            .checked_add(amount)
-            .ok_or(DepositError::Overflow)?;
+        analytics.total_deposits = Amount::new(analytics.total_deposits)
+            .checked_add(Amount::new(amount))?
+            .get();
+        analytics.collateral_value = Amount::new(analytics.collateral_value)
+            .checked_add(Amount::new(amount))?
+            .get();
     }
 
     analytics.transaction_count = analytics.transaction_count.saturating_add(1);
     analytics.last_activity = timestamp;
+    refresh_tier(env, user, &mut analytics);
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
     Ok(())
 }
 
@@ -406,10 +583,7 @@ pub fn update_protocol_analytics(
     is_deposit: bool,
 ) -> Result<(), DepositError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, ProtocolAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, ProtocolAnalytics>(env, &analytics_key)
         .unwrap_or(ProtocolAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -417,53 +591,50 @@ pub fn update_protocol_analytics(
         });
 
     if is_deposit {
-        analytics.total_deposits = analytics
-            .total_deposits
-            .checked_add(amount)
-            .ok_or(DepositError::Overflow)?;
-        analytics.total_value_locked = analytics
-            .total_value_locked
-            .checked_add(amount)
-            .ok_or(DepositError::Overflow)?;
+        analytics.total_deposits = Amount::new(analytics.total_deposits)
+            .checked_add(Amount::new(amount))?
+            .get();
+        analytics.total_value_locked = Amount::new(analytics.total_value_locked)
+            .checked_add(Amount::new(amount))?
+            .get();
     }
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
+    record_tvl(env, analytics.total_value_locked);
     Ok(())
 }
 
+/// Metadata key of an activity's integrator tag
+pub const TAG_METADATA_KEY: Symbol = symbol_short!("tag");
+
 /// Add entry to activity log
+///
+/// A `tag` is stored in the entry's metadata under [`TAG_METADATA_KEY`]. The
+/// entry is queued while analytics writes are frozen.
 pub fn add_activity_log(
     env: &Env,
     user: &Address,
-    activity_type: Symbol,
+    activity_type: ActivityType,
     amount: i128,
     asset: Option<Address>,
     timestamp: u64,
+    tag: Option<Symbol>,
 ) -> Result<(), DepositError> {
-    let log_key = DepositDataKey::ActivityLog;
-    let mut log = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Vec<Activity>>(&log_key)
-        .unwrap_or_else(|| Vec::new(env));
-
-    let activity = Activity {
-        user: user.clone(),
-        activity_type,
-        amount,
-        asset,
-        timestamp,
-        metadata: Map::new(env),
-    };
-
-    log.push_back(activity);
-
-    // Keep only last 1000 activities (prevent unbounded growth)
-    if log.len() > 1000 {
-        log.pop_front();
+    let mut metadata = Map::new(env);
+    if let Some(tag) = tag {
+        metadata.set(TAG_METADATA_KEY, tag);
     }
-
-    env.storage().persistent().set(&log_key, &log);
+    append_activity(
+        env,
+        ActivityEntry {
+            user: user.clone(),
+            activity_type,
+            amount,
+            asset,
+            timestamp,
+            metadata,
+        },
+    );
     Ok(())
 }
 
@@ -532,10 +703,7 @@ fn check_risk_management_pause(env: &Env) -> Result<(), DepositError> {
 
     // Check emergency pause first
     let emergency_key = RiskDataKey::EmergencyPause;
-    if let Some(emergency_paused) = env
-        .storage()
-        .persistent()
-        .get::<RiskDataKey, bool>(&emergency_key)
+    if let Some(emergency_paused) = storage::get_instance::<RiskDataKey, bool>(env, &emergency_key)
     {
         if emergency_paused {
             return Err(DepositError::DepositPaused);
@@ -18,7 +18,12 @@
 /// Events are indexed by contract address + the auto-generated topic (the
 /// snake_case struct name). Consumers retrieve them via Stellar Horizon or a
 /// Soroban event streaming service.
-use soroban_sdk::{contractevent, Address, Env, Symbol};
+use soroban_sdk::{contractevent, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+use crate::cross_asset::{AssetCategory, AssetConfig, AssetKey, InterestMode};
+use crate::lending_pool::BadDebtWriteOff;
+use crate::rate_mode::RateMode;
+use crate::roles::Role;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Protocol action event structs
@@ -101,6 +106,8 @@ pub struct RepayEvent {
 /// * `debt_liquidated` – The debt amount repaid by the liquidator.
 /// * `collateral_seized` – The collateral transferred to the liquidator.
 /// * `incentive_amount` – The liquidation bonus (in collateral terms).
+/// * `surplus_returned` – Collateral returned to the borrower when the
+///   liquidation closed the whole debt.
 /// * `timestamp` – Ledger timestamp at liquidation time.
 ///
 /// # Security
@@ -116,6 +123,76 @@ pub struct LiquidationEvent {
     pub debt_liquidated: i128,
     pub collateral_seized: i128,
     pub incentive_amount: i128,
+    pub surplus_returned: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a keeper converts collateral of a soft-liquidating position
+/// toward its debt asset, or back.
+///
+/// # Fields
+/// * `keeper` – The keeper taking the other side of the conversion.
+/// * `user` – Owner of the position.
+/// * `collateral_asset` – The converted collateral asset.
+/// * `debt_asset` – The debt asset converted toward.
+/// * `collateral_converted` – Collateral sold to the keeper; negative when
+///   bought back.
+/// * `debt_repaid` – Debt repaid by the sale; negative when debt was added to
+///   buy collateral back.
+/// * `bands_converted` – Bands converted afterwards.
+/// * `timestamp` – Ledger timestamp of the conversion.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SoftLiquidationEvent {
+    pub keeper: Address,
+    pub user: Address,
+    pub collateral_asset: Option<Address>,
+    pub debt_asset: Option<Address>,
+    pub collateral_converted: i128,
+    pub debt_repaid: i128,
+    pub bands_converted: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when a user repays cross-asset debt by swapping collateral of
+/// another asset.
+///
+/// # Fields
+/// * `user` – Owner of the position.
+/// * `debt_asset` – The repaid asset; `None` for native XLM.
+/// * `pay_asset` – The collateral asset swapped; `None` for native XLM.
+/// * `amount_paid` – Collateral swapped, in the pay asset's native units.
+/// * `amount_received` – Debt asset received from the swap.
+/// * `debt_repaid` – Debt repaid; any surplus is supplied to the position.
+/// * `timestamp` – Ledger timestamp of the repayment.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CollateralSwapRepayEvent {
+    pub user: Address,
+    pub debt_asset: Option<Address>,
+    pub pay_asset: Option<Address>,
+    pub amount_paid: i128,
+    pub amount_received: i128,
+    pub debt_repaid: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a position's health factor is close to or below liquidation.
+///
+/// # Fields
+/// * `user` – Owner of the position.
+/// * `health_factor` – The position's health factor.
+/// * `liquidatable` – Whether the position can be liquidated.
+/// * `contact_hint` – The user's contact hint if their notification
+///   preference covers this warning; empty otherwise.
+/// * `timestamp` – Ledger timestamp of the warning.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LiquidationWarningEvent {
+    pub user: Address,
+    pub health_factor: i128,
+    pub liquidatable: bool,
+    pub contact_hint: Bytes,
     pub timestamp: u64,
 }
 
@@ -194,6 +271,98 @@ pub struct PriceUpdatedEvent {
     pub timestamp: u64,
 }
 
+/// Emitted when a reporter's signed price is accepted.
+///
+/// # Fields
+/// * `reporter` – The reporter that signed the price.
+/// * `asset` – The asset reported on.
+/// * `price` – The reported price.
+/// * `median` – The median published to the feed, if enough reports were fresh.
+/// * `incentive` – Incentive paid to the reporter from reserves.
+/// * `timestamp` – Ledger timestamp of the submission.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PriceSubmittedEvent {
+    pub reporter: Address,
+    pub asset: Address,
+    pub price: i128,
+    pub median: Option<i128>,
+    pub incentive: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when an admin points an asset at a new primary or fallback oracle.
+///
+/// # Fields
+/// * `actor` – The admin's address.
+/// * `asset` – The priced asset.
+/// * `oracle` – The new oracle contract address.
+/// * `is_fallback` – `true` for the fallback oracle, `false` for the primary.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OracleSourceUpdatedEvent {
+    pub actor: Address,
+    pub asset: Address,
+    pub oracle: Address,
+    pub is_fallback: bool,
+    pub timestamp: u64,
+}
+
+/// Emitted when an admin sets the oracle sources aggregated for an asset.
+///
+/// # Fields
+/// * `actor` – The admin's address.
+/// * `asset` – The priced asset.
+/// * `sources` – The new sources; empty when they were removed.
+/// * `min_fresh` – Fresh source prices needed before their median applies.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OracleSourcesSetEvent {
+    pub actor: Address,
+    pub asset: Address,
+    pub sources: Vec<Address>,
+    pub min_fresh: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when an admin sets an emergency price override.
+///
+/// # Fields
+/// * `actor` – The admin's address.
+/// * `asset` – The asset whose price is overridden.
+/// * `price` – The override price.
+/// * `expires_at` – Ledger timestamp at which the override stops applying.
+/// * `timestamp` – Ledger timestamp of the override.
+///
+/// # Security
+/// Overrides bypass the oracle deviation guard; indexers should alert on
+/// every occurrence.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EmergencyPriceSetEvent {
+    pub actor: Address,
+    pub asset: Address,
+    pub price: i128,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when an admin clears an emergency price override early.
+///
+/// # Fields
+/// * `actor` – The admin's address.
+/// * `asset` – The asset whose override was removed.
+/// * `timestamp` – Ledger timestamp of the removal.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EmergencyPriceClearedEvent {
+    pub actor: Address,
+    pub asset: Address,
+    pub timestamp: u64,
+}
+
 /// Emitted when risk parameters are updated by an admin.
 ///
 /// # Fields
@@ -208,6 +377,22 @@ pub struct RiskParamsUpdatedEvent {
     pub timestamp: u64,
 }
 
+/// Emitted when a liquidation threshold increase starts phasing in.
+///
+/// # Fields
+/// * `previous_threshold` – Threshold kept by existing positions meanwhile.
+/// * `new_threshold` – Threshold applied to new borrows immediately.
+/// * `effective_at` – Timestamp from which every position uses the new threshold.
+/// * `timestamp` – Ledger timestamp of the increase.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ThresholdMigrationEvent {
+    pub previous_threshold: i128,
+    pub new_threshold: i128,
+    pub effective_at: u64,
+    pub timestamp: u64,
+}
+
 /// Emitted when the pause state of any protocol operation changes.
 ///
 /// # Fields
@@ -225,73 +410,1385 @@ pub struct PauseStateChangedEvent {
     pub timestamp: u64,
 }
 
-// ─────────────────────────────────────────────────────────────────────────────
-// Emitter helpers
-// ─────────────────────────────────────────────────────────────────────────────
+/// Emitted when a user toggles whether a cross-asset deposit counts as collateral.
+///
+/// # Fields
+/// * `user` – The position owner's address.
+/// * `asset` – The toggled asset; `None` for native XLM.
+/// * `use_as_collateral` – `true` if the deposit backs borrowing,
+///   `false` if it is held in savings mode.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CollateralToggledEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub use_as_collateral: bool,
+    pub timestamp: u64,
+}
 
-/// Emit a deposit event.
-/// Call this after successfully updating collateral storage.
-pub fn emit_deposit(e: &Env, event: DepositEvent) {
-    event.publish(e);
+/// Emitted when a user supplies liquidity to a lending pool.
+///
+/// # Fields
+/// * `user` – The supplier's address.
+/// * `asset` – The pool's asset; `None` for native XLM.
+/// * `amount` – The amount supplied in the asset's smallest unit.
+/// * `shares` – Pool shares minted to the supplier.
+/// * `timestamp` – Ledger timestamp at supply time.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PoolSupplyEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub shares: i128,
+    pub timestamp: u64,
 }
 
-/// Emit a withdrawal event.
-/// Call this after successfully updating collateral storage.
-pub fn emit_withdrawal(e: &Env, event: WithdrawalEvent) {
-    event.publish(e);
+/// Emitted when a user redeems lending pool shares for the underlying asset.
+///
+/// # Fields
+/// * `user` – The redeemer's address.
+/// * `asset` – The pool's asset; `None` for native XLM.
+/// * `amount` – The amount paid out, including earned interest.
+/// * `shares` – Pool shares burned.
+/// * `timestamp` – Ledger timestamp at redemption time.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PoolRedeemEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub shares: i128,
+    pub timestamp: u64,
 }
 
-/// Emit a borrow event.
-/// Call this after successfully updating debt storage.
-pub fn emit_borrow(e: &Env, event: BorrowEvent) {
-    event.publish(e);
+/// Emitted when the admin withdraws protocol reserves from a market.
+///
+/// # Fields
+/// * `admin` – The admin or treasury admin that authorized the withdrawal.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `to` – Recipient of the reserves.
+/// * `amount` – Amount withdrawn.
+/// * `timestamp` – Ledger timestamp of the withdrawal.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReservesWithdrawnEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub to: Address,
+    pub amount: i128,
+    pub timestamp: u64,
 }
 
-/// Emit a repay event.
-/// Call this after successfully reducing debt storage.
-pub fn emit_repay(e: &Env, event: RepayEvent) {
-    event.publish(e);
+/// Emitted when interest accrues on a market's cross-asset debt, or when
+/// borrower interest is credited to its pool.
+///
+/// # Fields
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `borrow_interest` – Interest accrued on borrower debt.
+/// * `reserves` – The part of `borrow_interest` set aside as reserves; zero
+///   for cross-asset accruals, which credit nothing to the pool.
+/// * `borrow_index` – The market's borrow index after the accrual.
+/// * `liquidity_index` – The pool's liquidity index after the interest.
+/// * `timestamp` – Ledger timestamp of the accrual.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InterestAccruedEvent {
+    pub asset: Option<Address>,
+    pub borrow_interest: i128,
+    pub reserves: i128,
+    pub borrow_index: i128,
+    pub liquidity_index: i128,
+    pub timestamp: u64,
 }
 
-/// Emit a liquidation event.
-/// Call this after the debt repayment and collateral seizure are committed.
-pub fn emit_liquidation(e: &Env, event: LiquidationEvent) {
-    event.publish(e);
+/// Emitted when a liquidation leaves debt with no collateral to recover it.
+///
+/// # Fields
+/// * `borrower` – The liquidated borrower.
+/// * `asset` – The debt's market; `None` for native XLM.
+/// * `amount` – Debt moved into the market's bad debt.
+/// * `timestamp` – Ledger timestamp of the liquidation.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BadDebtRecordedEvent {
+    pub borrower: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
 }
 
-/// Emit a flash-loan-initiated event.
-/// Call this after the flash loan record is stored and tokens transferred.
-pub fn emit_flash_loan_initiated(e: &Env, event: FlashLoanInitiatedEvent) {
-    event.publish(e);
+/// Emitted when the admin writes off a market's bad debt.
+///
+/// # Fields
+/// * `admin` – The admin or treasury admin that authorized the write-off.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `amount` – Bad debt written off.
+/// * `method` – Whether reserves or suppliers absorbed the loss.
+/// * `timestamp` – Ledger timestamp of the write-off.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BadDebtWrittenOffEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub method: BadDebtWriteOff,
+    pub timestamp: u64,
 }
 
-/// Emit a flash-loan-repaid event.
-/// Call this after the record is cleared and repayment received.
-pub fn emit_flash_loan_repaid(e: &Env, event: FlashLoanRepaidEvent) {
-    event.publish(e);
+/// Emitted when a borrow is queued behind a market's utilization cap.
+///
+/// # Fields
+/// * `id` – The request id.
+/// * `user` – The borrower's address.
+/// * `asset` – The market; `None` for native XLM.
+/// * `amount` – The amount to borrow.
+/// * `expires_at` – Timestamp after which the request is dropped.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BorrowRequestedEvent {
+    pub id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub expires_at: u64,
 }
 
-/// Emit an admin-action event.
-/// Use for initialization or admin operations without a dedicated event type.
-pub fn emit_admin_action(e: &Env, event: AdminActionEvent) {
-    event.publish(e);
+/// Emitted when a queued borrow is executed.
+///
+/// # Fields
+/// * `id` – The request id.
+/// * `user` – The borrower's address.
+/// * `asset` – The market; `None` for native XLM.
+/// * `amount` – The amount borrowed.
+/// * `timestamp` – Ledger timestamp at execution time.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BorrowRequestFilledEvent {
+    pub id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
 }
 
-/// Emit a price-updated event.
-/// Call this after committing a new oracle price to storage.
-pub fn emit_price_updated(e: &Env, event: PriceUpdatedEvent) {
-    event.publish(e);
+/// Emitted when a queued borrow leaves the queue without being executed.
+///
+/// # Fields
+/// * `id` – The request id.
+/// * `user` – The borrower's address.
+/// * `asset` – The market; `None` for native XLM.
+/// * `amount` – The amount that was requested.
+/// * `reason` – `cancelled`, `expired` or `rejected`.
+/// * `timestamp` – Ledger timestamp at removal time.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BorrowRequestClosedEvent {
+    pub id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub reason: Symbol,
+    pub timestamp: u64,
 }
 
-/// Emit a risk-params-updated event.
-/// Call this after risk configuration has been written to storage.
-pub fn emit_risk_params_updated(e: &Env, event: RiskParamsUpdatedEvent) {
-    event.publish(e);
+/// Emitted when a user lets an address read their credit summary.
+///
+/// # Fields
+/// * `user` – The user whose summary is shared.
+/// * `grantee` – The address granted access.
+/// * `timestamp` – Ledger timestamp of the grant.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CreditAccessGrantedEvent {
+    pub user: Address,
+    pub grantee: Address,
+    pub timestamp: u64,
 }
 
-/// Emit a pause-state-changed event.
-/// Call this after any pause switch (including emergency) is toggled.
-pub fn emit_pause_state_changed(e: &Env, event: PauseStateChangedEvent) {
+/// Emitted when a user withdraws an address's access to their credit summary.
+///
+/// # Fields
+/// * `user` – The user whose summary was shared.
+/// * `grantee` – The address that lost access.
+/// * `timestamp` – Ledger timestamp of the revocation.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CreditAccessRevokedEvent {
+    pub user: Address,
+    pub grantee: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin changes an asset's supply and borrow caps.
+///
+/// # Fields
+/// * `admin` – The admin that made the change.
+/// * `asset` – The capped asset.
+/// * `supply_cap` – New cap on the total supplied (0 = unlimited).
+/// * `borrow_cap` – New cap on the total borrowed (0 = unlimited).
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetCapsUpdatedEvent {
+    pub admin: Address,
+    pub asset: Address,
+    pub supply_cap: i128,
+    pub borrow_cap: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin offers coverage in a market or changes its terms.
+///
+/// # Fields
+/// * `admin` – The admin that made the change.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `premium_bps` – Premium charged on covered yield, in basis points.
+/// * `coverage_cap` – Highest value of covered shares (0 = unlimited).
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InsuranceConfigUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub premium_bps: i128,
+    pub coverage_cap: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a market's insurance fund is topped up.
+///
+/// # Fields
+/// * `from` – The funder, typically the safety module.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `amount` – Amount added to the fund.
+/// * `timestamp` – Ledger timestamp of the deposit.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InsuranceFundedEvent {
+    pub from: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a supplier opts in to or out of coverage.
+///
+/// # Fields
+/// * `user` – The supplier's address.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `covered` – `true` on opt-in, `false` on opt-out.
+/// * `shares` – Pool shares covered, or no longer covered.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CoverageUpdatedEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub covered: bool,
+    pub shares: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin records a loss to a market's pool.
+///
+/// # Fields
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `loss` – Amount taken from pool liquidity.
+/// * `payout` – Part of the loss the insurance fund pays covered suppliers.
+/// * `timestamp` – Ledger timestamp of the report.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ShortfallReportedEvent {
+    pub asset: Option<Address>,
+    pub loss: i128,
+    pub payout: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a covered supplier collects shortfall payouts.
+///
+/// # Fields
+/// * `user` – The supplier's address.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `amount` – Amount paid out.
+/// * `timestamp` – Ledger timestamp of the claim.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CoverageClaimedEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when the protocol admin grants or revokes a role.
+///
+/// # Fields
+/// * `admin` – The protocol admin that made the change.
+/// * `role` – The role granted or revoked.
+/// * `account` – The account whose role changed.
+/// * `granted` – `true` on grant, `false` on revocation.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RoleUpdatedEvent {
+    pub admin: Address,
+    pub role: Role,
+    pub account: Address,
+    pub granted: bool,
+    pub timestamp: u64,
+}
+
+/// Emitted when the risk admin changes how activity scores decay.
+///
+/// # Fields
+/// * `admin` – The account that made the change.
+/// * `half_life` – Seconds for an idle user's score to halve (0 = no decay).
+/// * `grace_period` – Seconds after the last activity before decay starts.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ActivityDecayUpdatedEvent {
+    pub admin: Address,
+    pub half_life: u64,
+    pub grace_period: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when the risk admin changes the thresholds for risk badges.
+///
+/// # Fields
+/// * `admin` – The account that made the change.
+/// * `high_utilization_bps` – Core debt share of collateral for `HighUtilization`.
+/// * `concentration_bps` – Single-asset collateral share for `CollateralConcentration`.
+/// * `liquidation_buffer_bps` – Margin above liquidation for `NoLiquidationBuffer`.
+/// * `stable_rate_share_bps` – Stable-rate debt share for `StableRateHeavy`.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RiskBadgeConfigUpdatedEvent {
+    pub admin: Address,
+    pub high_utilization_bps: i128,
+    pub concentration_bps: i128,
+    pub liquidation_buffer_bps: i128,
+    pub stable_rate_share_bps: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when the pause guardian freezes or unfreezes analytics writes.
+///
+/// # Fields
+/// * `admin` – The account that made the change.
+/// * `frozen` – Whether analytics writes are now frozen.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AnalyticsFrozenEvent {
+    pub admin: Address,
+    pub frozen: bool,
+    pub timestamp: u64,
+}
+
+/// Emitted when a cross-asset market is listed or its configuration changes.
+///
+/// # Fields
+/// * `actor` – The admin or role holder that made the change.
+/// * `asset` – The market asset; `None` for native XLM.
+/// * `config` – The configuration after the change.
+/// * `timestamp` – Ledger timestamp of the change.
+///
+/// Covers listing, config updates, collateral factor ramps (with the target
+/// factor) and manual price updates.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetConfigUpdatedEvent {
+    pub actor: Address,
+    pub asset: Option<Address>,
+    pub config: AssetConfig,
+    pub timestamp: u64,
+}
+
+/// Emitted when the pause guardian pauses or resumes an operation for one asset.
+///
+/// # Fields
+/// * `admin` – The admin or pause guardian that made the change.
+/// * `asset` – The asset; `None` for native XLM.
+/// * `operation` – The pause switch name, e.g. `pause_borrow`.
+/// * `paused` – Whether the operation is now paused for the asset.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetPauseUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub operation: Symbol,
+    pub paused: bool,
+}
+
+/// Emitted when a risk admin isolates an asset or lifts its isolation.
+///
+/// # Fields
+/// * `admin` – The admin or risk admin that made the change.
+/// * `asset` – The asset; `None` for native XLM.
+/// * `debt_ceiling` – Debt ceiling of positions using the asset as
+///   collateral; `None` when the asset is no longer isolated.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct IsolationModeUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub debt_ceiling: Option<i128>,
+}
+
+/// Emitted when a listing admin tags an asset with its issuer and category,
+/// or clears its tags.
+///
+/// # Fields
+/// * `admin` – The admin or listing admin that made the change.
+/// * `asset` – The asset; `None` for native XLM.
+/// * `category` – The asset's category; `None` when its tags are cleared.
+/// * `issuer` – The asset's issuer, if known.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetMetadataUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub category: Option<AssetCategory>,
+    pub issuer: Option<Address>,
+}
+
+/// Emitted when a position's debt attributed to a collateral category grows
+/// while the category is at or above its debt ceiling.
+///
+/// # Fields
+/// * `user` – Owner of the position.
+/// * `category` – The capped collateral category.
+/// * `debt_value` – Debt value attributed to the category.
+/// * `ceiling_value` – The category's ceiling, in debt value.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CategoryCeilingReachedEvent {
+    pub user: Address,
+    pub category: AssetCategory,
+    pub debt_value: i128,
+    pub ceiling_value: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a treasury admin changes a market's reward emissions.
+///
+/// # Fields
+/// * `admin` – The admin or treasury admin that made the change.
+/// * `asset` – The market's asset; `None` for native XLM.
+/// * `supply_rate` – Reward tokens emitted per second to suppliers.
+/// * `program_id` – The market's running reward program; `None` once its
+///   emissions stop.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EmissionRatesUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub supply_rate: i128,
+    pub program_id: Option<u32>,
+}
+
+/// Emitted when reward tokens are added to the rewards balance.
+///
+/// # Fields
+/// * `from` – The funder's address.
+/// * `amount` – Reward tokens added.
+/// * `timestamp` – Ledger timestamp of the funding.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RewardsFundedEvent {
+    pub from: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a user's accrued rewards are claimed.
+///
+/// # Fields
+/// * `caller` – The user or their claim delegate.
+/// * `user` – The user's address, which the rewards are paid to; a
+///   sub-account's rewards are paid to its owner.
+/// * `token` – The reward token.
+/// * `amount` – Reward tokens paid.
+/// * `timestamp` – Ledger timestamp of the claim.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RewardsClaimedEvent {
+    pub caller: Address,
+    pub user: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a user names or removes their reward claim delegate.
+///
+/// # Fields
+/// * `user` – The user's address.
+/// * `delegate` – The new delegate; `None` when removed.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RewardDelegateUpdatedEvent {
+    pub user: Address,
+    pub delegate: Option<Address>,
+}
+
+/// Emitted when a user's activity moves them to another loyalty tier.
+///
+/// # Fields
+/// * `user` – The user's address.
+/// * `previous_tier` – The tier before the activity; 0 for none.
+/// * `tier` – The new tier; 0 for none.
+/// * `timestamp` – Ledger timestamp of the activity.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LoyaltyTierChangedEvent {
+    pub user: Address,
+    pub previous_tier: u32,
+    pub tier: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when an owner first deposits into one of their sub-accounts.
+///
+/// # Fields
+/// * `owner` – The address that controls the sub-account.
+/// * `sub_id` – The owner's number for the sub-account.
+/// * `account` – The sub-account's derived address.
+/// * `timestamp` – Ledger timestamp of the first deposit.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SubAccountOpenedEvent {
+    pub owner: Address,
+    pub sub_id: u32,
+    pub account: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a risk admin changes how borrower interest accrues in a market.
+///
+/// # Fields
+/// * `admin` – The admin or risk admin that made the change.
+/// * `asset` – The asset; `None` for native XLM.
+/// * `mode` – The new interest mode.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InterestModeUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub mode: InterestMode,
+}
+
+/// Emitted when a risk admin changes an asset's minimum debt.
+///
+/// # Fields
+/// * `admin` – The admin or risk admin that made the change.
+/// * `asset` – The asset; `None` for native XLM.
+/// * `amount` – Smallest nonzero debt a position may carry; `0` for none.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MinBorrowAmountUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+}
+
+/// Emitted when a risk admin changes an asset's soft liquidation bands.
+///
+/// # Fields
+/// * `admin` – The admin or risk admin that made the change.
+/// * `asset` – The collateral asset; `None` for native XLM.
+/// * `band_count` – Number of bands; `0` when soft liquidation is disabled.
+/// * `band_width_bps` – Health factor range of each band.
+/// * `fee_bps` – Conversion fee paid by the position.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LiquidationBandsUpdatedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub band_count: u32,
+    pub band_width_bps: i128,
+    pub fee_bps: i128,
+}
+
+/// Emitted when an asset is delisted, freezing new deposits and borrows.
+///
+/// # Fields
+/// * `admin` – The admin or asset listing admin that delisted the asset.
+/// * `asset` – The asset; `None` for native XLM.
+/// * `timestamp` – Ledger timestamp of the delisting.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetDelistedEvent {
+    pub admin: Address,
+    pub asset: Option<Address>,
+    pub timestamp: u64,
+}
+
+/// Emitted when the protocol admin sets the wasm used for new isolated markets.
+///
+/// # Fields
+/// * `admin` – The protocol admin.
+/// * `wasm_hash` – Hash of the uploaded market wasm.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MarketWasmUpdatedEvent {
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+}
+
+/// Emitted when the factory deploys and registers an isolated market.
+///
+/// # Fields
+/// * `id` – The market's registry id.
+/// * `market` – Address of the deployed market contract.
+/// * `admin` – The market's admin.
+/// * `wasm_hash` – Hash of the wasm the market was deployed from.
+/// * `timestamp` – Ledger timestamp of the deployment.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MarketCreatedEvent {
+    pub id: u32,
+    pub market: Address,
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+/// Emitted when an asset's cash ledger is reconciled against its token balance.
+///
+/// # Fields
+/// * `asset` – The reconciled token.
+/// * `ledger_balance` – Balance implied by the recorded movements.
+/// * `token_balance` – Tokens the contract actually holds.
+/// * `discrepancy` – `token_balance - ledger_balance`.
+/// * `timestamp` – Ledger timestamp of the reconciliation.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CashReconciledEvent {
+    pub asset: Address,
+    pub ledger_balance: i128,
+    pub token_balance: i128,
+    pub discrepancy: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when the storage of an abandoned empty account is pruned.
+///
+/// # Fields
+/// * `user` – The pruned account.
+/// * `last_activity` – Timestamp of the account's last recorded activity.
+/// * `timestamp` – Ledger timestamp of the sweep.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AccountPrunedEvent {
+    pub user: Address,
+    pub last_activity: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when a user closes their position with `close_position`.
+///
+/// # Fields
+/// * `user` – The account closed.
+/// * `debt_repaid` – Core debt repaid, interest included.
+/// * `collateral_withdrawn` – Core collateral withdrawn.
+/// * `pruned` – Whether the account's storage was pruned.
+/// * `timestamp` – Ledger timestamp of the closure.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PositionClosedEvent {
+    pub user: Address,
+    pub debt_repaid: i128,
+    pub collateral_withdrawn: i128,
+    pub pruned: bool,
+    pub timestamp: u64,
+}
+
+/// Emitted when an account's positions move to another address with
+/// `transfer_position`.
+///
+/// # Fields
+/// * `from` – The previous holder.
+/// * `to` – The new holder.
+/// * `collateral` – Core collateral moved.
+/// * `debt` – Core debt moved, interest included.
+/// * `assets` – Assets whose cross-asset positions moved.
+/// * `timestamp` – Ledger timestamp of the transfer.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PositionTransferredEvent {
+    pub from: Address,
+    pub to: Address,
+    pub collateral: i128,
+    pub debt: i128,
+    pub assets: Vec<AssetKey>,
+    pub timestamp: u64,
+}
+
+/// Emitted when a batch of cross-asset operations completes.
+///
+/// # Fields
+/// * `user` – The user the operations ran for.
+/// * `operations` – Number of operations executed.
+/// * `health_factor` – Health factor after the last operation.
+/// * `timestamp` – Ledger timestamp of the batch.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BatchExecutedEvent {
+    pub user: Address,
+    pub operations: u32,
+    pub health_factor: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when an LP share token is repriced from its pool.
+///
+/// # Fields
+/// * `asset` – The share token.
+/// * `pool_value` – The pool's fair value at the underlying oracle prices.
+/// * `price` – The new price per share.
+/// * `timestamp` – Ledger timestamp of the refresh.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LpPriceRefreshedEvent {
+    pub asset: Address,
+    pub pool_value: i128,
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a borrower switches their debt between rate modes.
+///
+/// # Fields
+/// * `user` – The borrower.
+/// * `mode` – The new rate mode.
+/// * `rate_bps` – The rate the debt now accrues at.
+/// * `timestamp` – Ledger timestamp of the swap.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RateModeSwappedEvent {
+    pub user: Address,
+    pub mode: RateMode,
+    pub rate_bps: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a stable-mode position is re-locked at the current stable rate.
+///
+/// # Fields
+/// * `user` – The borrower.
+/// * `old_rate_bps` – The previously locked rate.
+/// * `new_rate_bps` – The newly locked rate.
+/// * `timestamp` – Ledger timestamp of the rebalance.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct StableRateRebalancedEvent {
+    pub user: Address,
+    pub old_rate_bps: i128,
+    pub new_rate_bps: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a supplier fixes the yield on pool shares.
+///
+/// # Fields
+/// * `user` – The supplier.
+/// * `asset` – The market's asset (`None` for native XLM).
+/// * `shares` – Shares locked.
+/// * `value` – Value of the shares when locked.
+/// * `rate_bps` – Fixed annual rate.
+/// * `maturity` – When the lock can be settled.
+/// * `timestamp` – Ledger timestamp of the lock.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RateLockedEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub shares: i128,
+    pub value: i128,
+    pub rate_bps: i128,
+    pub maturity: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when a matured rate lock is settled against reserves.
+///
+/// # Fields
+/// * `user` – The supplier.
+/// * `asset` – The market's asset (`None` for native XLM).
+/// * `guaranteed` – Locked value grown at the fixed rate.
+/// * `actual` – Value of the locked shares at settlement.
+/// * `settlement` – Value paid from reserves (positive) or into them (negative).
+/// * `timestamp` – Ledger timestamp of the settlement.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RateLockSettledEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub guaranteed: i128,
+    pub actual: i128,
+    pub settlement: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when the protocol admin replaces the contract's code.
+///
+/// # Fields
+/// * `admin` – The protocol admin.
+/// * `wasm_hash` – Hash of the new wasm.
+/// * `version` – Storage schema version at the time of the upgrade.
+/// * `timestamp` – Ledger timestamp of the upgrade.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ContractUpgradedEvent {
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+    pub version: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when storage is migrated to a newer schema version.
+///
+/// # Fields
+/// * `admin` – The protocol admin.
+/// * `from_version` – Schema version before the migration.
+/// * `to_version` – Schema version after the migration.
+/// * `timestamp` – Ledger timestamp of the migration.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ContractMigratedEvent {
+    pub admin: Address,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when the protocol admin sets the recovery guardian set.
+///
+/// # Fields
+/// * `admin` – The protocol admin.
+/// * `guardians` – The guardian addresses.
+/// * `threshold` – Approvals needed to start the recovery delay.
+/// * `delay` – Seconds between quorum and execution.
+/// * `timestamp` – Ledger timestamp of the change.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct GuardiansUpdatedEvent {
+    pub admin: Address,
+    pub guardians: Vec<Address>,
+    pub threshold: u32,
+    pub delay: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when a guardian proposes a new protocol admin.
+///
+/// # Fields
+/// * `guardian` – The proposing guardian.
+/// * `new_admin` – The proposed admin.
+/// * `timestamp` – Ledger timestamp of the proposal.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RecoveryProposedEvent {
+    pub guardian: Address,
+    pub new_admin: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a guardian approves the pending recovery.
+///
+/// # Fields
+/// * `guardian` – The approving guardian.
+/// * `approvals` – Approvals so far, the proposer's included.
+/// * `timestamp` – Ledger timestamp of the approval.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RecoveryApprovedEvent {
+    pub guardian: Address,
+    pub approvals: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when the protocol admin cancels the pending recovery.
+///
+/// # Fields
+/// * `admin` – The protocol admin.
+/// * `new_admin` – The admin the recovery proposed.
+/// * `timestamp` – Ledger timestamp of the cancellation.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RecoveryCancelledEvent {
+    pub admin: Address,
+    pub new_admin: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a guardian recovery replaces the protocol admin.
+///
+/// # Fields
+/// * `old_admin` – The replaced admin.
+/// * `new_admin` – The new admin.
+/// * `timestamp` – Ledger timestamp of the rotation.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminRecoveredEvent {
+    pub old_admin: Option<Address>,
+    pub new_admin: Address,
+    pub timestamp: u64,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Emitter helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Emit a deposit event.
+/// Call this after successfully updating collateral storage.
+pub fn emit_deposit(e: &Env, event: DepositEvent) {
+    event.publish(e);
+}
+
+/// Emit a withdrawal event.
+/// Call this after successfully updating collateral storage.
+pub fn emit_withdrawal(e: &Env, event: WithdrawalEvent) {
+    event.publish(e);
+}
+
+/// Emit a borrow event.
+/// Call this after successfully updating debt storage.
+pub fn emit_borrow(e: &Env, event: BorrowEvent) {
+    event.publish(e);
+}
+
+/// Emit a repay event.
+/// Call this after successfully reducing debt storage.
+pub fn emit_repay(e: &Env, event: RepayEvent) {
+    event.publish(e);
+}
+
+/// Emit a liquidation event.
+/// Call this after the debt repayment and collateral seizure are committed.
+pub fn emit_liquidation(e: &Env, event: LiquidationEvent) {
+    event.publish(e);
+}
+
+/// Emit a flash-loan-initiated event.
+/// Call this after the flash loan record is stored and tokens transferred.
+pub fn emit_flash_loan_initiated(e: &Env, event: FlashLoanInitiatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a flash-loan-repaid event.
+/// Call this after the record is cleared and repayment received.
+pub fn emit_flash_loan_repaid(e: &Env, event: FlashLoanRepaidEvent) {
+    event.publish(e);
+}
+
+/// Emit an admin-action event.
+/// Use for initialization or admin operations without a dedicated event type.
+pub fn emit_admin_action(e: &Env, event: AdminActionEvent) {
+    event.publish(e);
+}
+
+/// Emit a price-updated event.
+/// Call this after committing a new oracle price to storage.
+pub fn emit_price_updated(e: &Env, event: PriceUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a price-submitted event.
+/// Call this after the report is stored and the incentive paid.
+pub fn emit_price_submitted(e: &Env, event: PriceSubmittedEvent) {
+    event.publish(e);
+}
+
+/// Emit an oracle-source-updated event.
+/// Call this after the new oracle address has been written to storage.
+pub fn emit_oracle_source_updated(e: &Env, event: OracleSourceUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an oracle-sources-set event.
+/// Call this after the new sources have been written to storage.
+pub fn emit_oracle_sources_set(e: &Env, event: OracleSourcesSetEvent) {
+    event.publish(e);
+}
+
+/// Emit an emergency-price-set event.
+/// Call this after the override has been written to storage.
+pub fn emit_emergency_price_set(e: &Env, event: EmergencyPriceSetEvent) {
+    event.publish(e);
+}
+
+/// Emit an emergency-price-cleared event.
+/// Call this after the override has been removed from storage.
+pub fn emit_emergency_price_cleared(e: &Env, event: EmergencyPriceClearedEvent) {
+    event.publish(e);
+}
+
+/// Emit a risk-params-updated event.
+/// Call this after risk configuration has been written to storage.
+pub fn emit_risk_params_updated(e: &Env, event: RiskParamsUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a threshold-migration event.
+/// Call this after the migration has been written to storage.
+pub fn emit_threshold_migration(e: &Env, event: ThresholdMigrationEvent) {
+    event.publish(e);
+}
+
+/// Emit a pause-state-changed event.
+/// Call this after any pause switch (including emergency) is toggled.
+pub fn emit_pause_state_changed(e: &Env, event: PauseStateChangedEvent) {
+    event.publish(e);
+}
+
+/// Emit a collateral-toggled event.
+/// Call this after the position's collateral flag has been persisted.
+pub fn emit_collateral_toggled(e: &Env, event: CollateralToggledEvent) {
+    event.publish(e);
+}
+
+/// Emit a pool supply event.
+/// Call this after minting the supplier's shares.
+pub fn emit_pool_supply(e: &Env, event: PoolSupplyEvent) {
+    event.publish(e);
+}
+
+/// Emit a pool redemption event.
+/// Call this after burning the redeemer's shares.
+pub fn emit_pool_redeem(e: &Env, event: PoolRedeemEvent) {
+    event.publish(e);
+}
+
+/// Emit a reserves withdrawal event.
+/// Call this after the reserves have been transferred out.
+pub fn emit_reserves_withdrawn(e: &Env, event: ReservesWithdrawnEvent) {
+    event.publish(e);
+}
+
+/// Emit an interest accrued event.
+/// Call this after the interest has been accrued or credited to the pool.
+pub fn emit_interest_accrued(e: &Env, event: InterestAccruedEvent) {
+    event.publish(e);
+}
+
+/// Emit a bad debt event.
+/// Call this after moving the debt into the market's bad debt.
+pub fn emit_bad_debt_recorded(e: &Env, event: BadDebtRecordedEvent) {
+    event.publish(e);
+}
+
+/// Emit a bad debt write-off event.
+/// Call this after the loss has been taken and the bad debt reduced.
+pub fn emit_bad_debt_written_off(e: &Env, event: BadDebtWrittenOffEvent) {
+    event.publish(e);
+}
+
+/// Emit a borrow request event.
+/// Call this after appending the request to the queue.
+pub fn emit_borrow_requested(e: &Env, event: BorrowRequestedEvent) {
+    event.publish(e);
+}
+
+/// Emit a filled borrow request event.
+/// Call this after the queued borrow has been executed.
+pub fn emit_borrow_request_filled(e: &Env, event: BorrowRequestFilledEvent) {
+    event.publish(e);
+}
+
+/// Emit a closed borrow request event.
+/// Call this after removing the request from the queue.
+pub fn emit_borrow_request_closed(e: &Env, event: BorrowRequestClosedEvent) {
+    event.publish(e);
+}
+
+/// Emit a credit access grant event.
+/// Call this after storing the updated grant list.
+pub fn emit_credit_access_granted(e: &Env, event: CreditAccessGrantedEvent) {
+    event.publish(e);
+}
+
+/// Emit a credit access revocation event.
+/// Call this after storing the updated grant list.
+pub fn emit_credit_access_revoked(e: &Env, event: CreditAccessRevokedEvent) {
+    event.publish(e);
+}
+
+/// Emit an asset caps update event.
+/// Call this after storing the updated asset parameters.
+pub fn emit_asset_caps_updated(e: &Env, event: AssetCapsUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an insurance terms update event.
+/// Call this after storing the market's coverage terms.
+pub fn emit_insurance_config_updated(e: &Env, event: InsuranceConfigUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an insurance funding event.
+/// Call this after crediting the market's insurance fund.
+pub fn emit_insurance_funded(e: &Env, event: InsuranceFundedEvent) {
+    event.publish(e);
+}
+
+/// Emit a coverage opt-in or opt-out event.
+/// Call this after storing the updated fund totals.
+pub fn emit_coverage_updated(e: &Env, event: CoverageUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a shortfall event.
+/// Call this after taking the loss from pool liquidity.
+pub fn emit_shortfall_reported(e: &Env, event: ShortfallReportedEvent) {
+    event.publish(e);
+}
+
+/// Emit a coverage claim event.
+/// Call this after transferring the payout.
+pub fn emit_coverage_claimed(e: &Env, event: CoverageClaimedEvent) {
+    event.publish(e);
+}
+
+/// Emit a role grant or revocation event.
+/// Call this after storing the role change.
+pub fn emit_role_updated(e: &Env, event: RoleUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an activity decay update event.
+/// Call this after storing the new decay parameters.
+pub fn emit_activity_decay_updated(e: &Env, event: ActivityDecayUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a risk badge threshold update event.
+/// Call this after storing the new thresholds.
+pub fn emit_risk_badge_config_updated(e: &Env, event: RiskBadgeConfigUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an analytics freeze event.
+/// Call this after storing the flag and applying any queued writes.
+pub fn emit_analytics_frozen(e: &Env, event: AnalyticsFrozenEvent) {
+    event.publish(e);
+}
+
+/// Emit an asset config update event.
+/// Call this after storing the new configuration.
+pub fn emit_asset_config_updated(e: &Env, event: AssetConfigUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an asset pause event.
+/// Call this after storing the asset's pause switches.
+pub fn emit_asset_pause_updated(e: &Env, event: AssetPauseUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an isolation mode event.
+/// Call this after storing the asset's debt ceiling.
+pub fn emit_isolation_mode_updated(e: &Env, event: IsolationModeUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a category debt ceiling event.
+pub fn emit_category_ceiling_reached(e: &Env, event: CategoryCeilingReachedEvent) {
+    event.publish(e);
+}
+
+/// Emit an emission rates update event.
+/// Call this after storing the market's new rates.
+pub fn emit_emission_rates_updated(e: &Env, event: EmissionRatesUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a rewards funding event.
+pub fn emit_rewards_funded(e: &Env, event: RewardsFundedEvent) {
+    event.publish(e);
+}
+
+/// Emit a loyalty tier change event.
+/// Call this before storing the user's analytics with the new tier.
+pub fn emit_loyalty_tier_changed(e: &Env, event: LoyaltyTierChangedEvent) {
+    event.publish(e);
+}
+
+/// Emit a sub-account opening event.
+/// Call this after registering the sub-account.
+pub fn emit_sub_account_opened(e: &Env, event: SubAccountOpenedEvent) {
+    event.publish(e);
+}
+
+/// Emit a reward delegate update event.
+pub fn emit_reward_delegate_updated(e: &Env, event: RewardDelegateUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a rewards claim event.
+pub fn emit_rewards_claimed(e: &Env, event: RewardsClaimedEvent) {
+    event.publish(e);
+}
+
+/// Emit an asset metadata update event.
+pub fn emit_asset_metadata_updated(e: &Env, event: AssetMetadataUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit an interest mode event.
+pub fn emit_interest_mode_updated(e: &Env, event: InterestModeUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a minimum debt event.
+pub fn emit_min_borrow_amount_updated(e: &Env, event: MinBorrowAmountUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a soft liquidation band configuration event.
+pub fn emit_liquidation_bands_updated(e: &Env, event: LiquidationBandsUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a liquidation warning event.
+pub fn emit_liquidation_warning(e: &Env, event: LiquidationWarningEvent) {
+    event.publish(e);
+}
+
+/// Emit a soft liquidation conversion event.
+pub fn emit_soft_liquidation(e: &Env, event: SoftLiquidationEvent) {
+    event.publish(e);
+}
+
+/// Emit a collateral swap repayment event.
+pub fn emit_collateral_swap_repay(e: &Env, event: CollateralSwapRepayEvent) {
+    event.publish(e);
+}
+
+/// Emit an asset delisting event.
+pub fn emit_asset_delisted(e: &Env, event: AssetDelistedEvent) {
+    event.publish(e);
+}
+
+/// Emit a market wasm update event.
+/// Call this after storing the new wasm hash.
+pub fn emit_market_wasm_updated(e: &Env, event: MarketWasmUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a market created event.
+/// Call this after the market is deployed, initialized and registered.
+pub fn emit_market_created(e: &Env, event: MarketCreatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a cash reconciliation event.
+/// Call this after comparing the ledger with the token balance.
+pub fn emit_cash_reconciled(e: &Env, event: CashReconciledEvent) {
+    event.publish(e);
+}
+
+/// Emit an account pruned event.
+/// Call this after removing the account's storage entries.
+pub fn emit_account_pruned(e: &Env, event: AccountPrunedEvent) {
+    event.publish(e);
+}
+
+/// Emit a rate mode swapped event.
+/// Call this after the new mode has been stored.
+pub fn emit_rate_mode_swapped(e: &Env, event: RateModeSwappedEvent) {
+    event.publish(e);
+}
+
+/// Emit a stable rate rebalanced event.
+/// Call this after the new rate has been stored.
+pub fn emit_stable_rate_rebalanced(e: &Env, event: StableRateRebalancedEvent) {
+    event.publish(e);
+}
+
+/// Emit a rate locked event.
+/// Call this after the lock has been stored.
+pub fn emit_rate_locked(e: &Env, event: RateLockedEvent) {
+    event.publish(e);
+}
+
+/// Emit a rate lock settled event.
+/// Call this after the settlement has been applied and the lock removed.
+pub fn emit_rate_lock_settled(e: &Env, event: RateLockSettledEvent) {
+    event.publish(e);
+}
+
+/// Emit a position closed event.
+/// Call this after every step of the closure has succeeded.
+pub fn emit_position_closed(e: &Env, event: PositionClosedEvent) {
+    event.publish(e);
+}
+
+/// Emit a position transferred event.
+/// Call this after the positions have moved and passed the solvency check.
+pub fn emit_position_transferred(e: &Env, event: PositionTransferredEvent) {
+    event.publish(e);
+}
+
+/// Emit a batch executed event.
+/// Call this after the final health factor check has passed.
+pub fn emit_batch_executed(e: &Env, event: BatchExecutedEvent) {
+    event.publish(e);
+}
+
+/// Emit an LP price refreshed event.
+/// Call this after the share token's new price has been recorded.
+pub fn emit_lp_price_refreshed(e: &Env, event: LpPriceRefreshedEvent) {
+    event.publish(e);
+}
+
+/// Emit a contract upgraded event.
+/// Call this after the contract's wasm has been replaced.
+pub fn emit_contract_upgraded(e: &Env, event: ContractUpgradedEvent) {
+    event.publish(e);
+}
+
+/// Emit a contract migrated event.
+/// Call this after every migration step has run.
+pub fn emit_contract_migrated(e: &Env, event: ContractMigratedEvent) {
+    event.publish(e);
+}
+
+/// Emit a guardians updated event.
+/// Call this after storing the guardian set.
+pub fn emit_guardians_updated(e: &Env, event: GuardiansUpdatedEvent) {
+    event.publish(e);
+}
+
+/// Emit a recovery proposed event.
+/// Call this after storing the recovery request.
+pub fn emit_recovery_proposed(e: &Env, event: RecoveryProposedEvent) {
+    event.publish(e);
+}
+
+/// Emit a recovery approved event.
+/// Call this after storing the approval.
+pub fn emit_recovery_approved(e: &Env, event: RecoveryApprovedEvent) {
+    event.publish(e);
+}
+
+/// Emit a recovery cancelled event.
+/// Call this after removing the recovery request.
+pub fn emit_recovery_cancelled(e: &Env, event: RecoveryCancelledEvent) {
+    event.publish(e);
+}
+
+/// Emit an admin recovered event.
+/// Call this after the new admin has been stored.
+pub fn emit_admin_recovered(e: &Env, event: AdminRecoveredEvent) {
     event.publish(e);
 }
 
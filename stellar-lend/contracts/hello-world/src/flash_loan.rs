@@ -3,15 +3,24 @@
 //! Provides uncollateralized flash loan functionality for the lending protocol.
 //!
 //! Flash loans allow users to borrow assets without collateral, provided the loan
-//! (principal + fee) is repaid within the same transaction via a callback contract.
+//! (principal + fee) is repaid within the same call via a callback contract.
 //!
 //! ## Fee Structure
 //! - Default fee: 9 basis points (0.09%) of the borrowed amount.
 //! - Fee is configurable by the admin.
 //!
+//! ## Single-Call Flash Loans
+//! [`flash_loan`] is the only way to borrow, and performs the whole loan in one call: it transfers the asset to a
+//! receiver contract, invokes the receiver's `on_flash_loan(asset, amount, fee,
+//! params)` callback, and then checks that the contract's balance has grown by
+//! at least the fee. The receiver repays by transferring principal + fee back to
+//! this contract before its callback returns. Collected fees are added to the
+//! asset's protocol reserves (see `lending_pool`).
+//!
 //! ## Reentrancy Protection
-//! An active flash loan is recorded per (user, asset) pair. A second flash loan
-//! for the same pair is rejected until the first is repaid, preventing reentrancy.
+//! [`flash_loan`] holds the contract-wide operation guard (see `reentrancy`)
+//! for the whole loan, so the receiver's callback cannot reenter the protocol
+//! or take a second flash loan before the first is repaid.
 //!
 //! ## Invariants
 //! - The borrowed amount must be within configured min/max limits.
@@ -19,13 +28,20 @@
 //! - Repayment must cover principal + fee in full.
 
 #![allow(unused)]
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::{Bps, BASIS_POINTS_SCALE};
 use crate::events::{
-    emit_flash_loan_initiated, emit_flash_loan_repaid, FlashLoanInitiatedEvent,
-    FlashLoanRepaidEvent,
+    emit_admin_action, emit_flash_loan_initiated, emit_flash_loan_repaid, AdminActionEvent,
+    FlashLoanInitiatedEvent, FlashLoanRepaidEvent,
+};
+use crate::storage;
+use soroban_sdk::{
+    contracterror, contracttype, vec, Address, Bytes, Env, IntoVal, Map, Symbol, Val, Vec,
 };
-use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
 use crate::deposit::DepositDataKey;
+use crate::lending_pool::{add_reserves, RevenueSource};
+use crate::reentrancy::{OperationGuard, Reentered};
 use crate::risk_management::get_admin;
 
 /// Errors that can occur during flash loan operations
@@ -55,6 +71,12 @@ pub enum FlashLoanError {
     CallbackFailed = 10,
 }
 
+impl From<Reentered> for FlashLoanError {
+    fn from(_: Reentered) -> Self {
+        FlashLoanError::Reentrancy
+    }
+}
+
 /// Storage keys for flash loan-related data
 #[contracttype]
 #[derive(Clone)]
@@ -62,28 +84,12 @@ pub enum FlashLoanError {
 pub enum FlashLoanDataKey {
     /// Flash loan fee in basis points (e.g., 9 = 0.09%)
     FlashLoanFeeBps,
-    /// Active flash loans: Map<(Address, Address), FlashLoanRecord>
-    ActiveFlashLoan(Address, Address),
     /// Flash loan configuration
     FlashLoanConfig,
     /// Pause switches for flash loan operations
     PauseSwitches,
 }
 
-/// Flash loan record
-#[contracttype]
-#[derive(Clone, Debug, PartialEq)]
-pub struct FlashLoanRecord {
-    /// Amount borrowed
-    pub amount: i128,
-    /// Fee amount
-    pub fee: i128,
-    /// Timestamp when loan was initiated
-    pub timestamp: u64,
-    /// Callback contract address
-    pub callback: Address,
-}
-
 /// Flash loan configuration
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -117,9 +123,7 @@ fn get_default_config() -> FlashLoanConfig {
 /// Get flash loan configuration
 fn get_flash_loan_config(env: &Env) -> FlashLoanConfig {
     let config_key = FlashLoanDataKey::FlashLoanConfig;
-    env.storage()
-        .persistent()
-        .get::<FlashLoanDataKey, FlashLoanConfig>(&config_key)
+    storage::get_instance::<FlashLoanDataKey, FlashLoanConfig>(env, &config_key)
         .unwrap_or_else(get_default_config)
 }
 
@@ -131,226 +135,144 @@ fn calculate_flash_loan_fee(env: &Env, amount: i128) -> Result<i128, FlashLoanEr
     amount
         .checked_mul(config.fee_bps)
         .ok_or(FlashLoanError::Overflow)?
-        .checked_div(10000)
+        .checked_div(BASIS_POINTS_SCALE.get())
         .ok_or(FlashLoanError::Overflow)
 }
 
-/// Check if flash loan is active
-fn is_flash_loan_active(env: &Env, user: &Address, asset: &Address) -> bool {
-    let loan_key = FlashLoanDataKey::ActiveFlashLoan(user.clone(), asset.clone());
-    env.storage()
-        .persistent()
-        .get::<FlashLoanDataKey, FlashLoanRecord>(&loan_key)
-        .is_some()
-}
-
-/// Record active flash loan
-fn record_flash_loan(
-    env: &Env,
-    user: &Address,
-    asset: &Address,
-    amount: i128,
-    fee: i128,
-    callback: &Address,
-) {
-    let loan_key = FlashLoanDataKey::ActiveFlashLoan(user.clone(), asset.clone());
-    let record = FlashLoanRecord {
-        amount,
-        fee,
-        timestamp: env.ledger().timestamp(),
-        callback: callback.clone(),
-    };
-    env.storage().persistent().set(&loan_key, &record);
-}
-
-/// Clear flash loan record
-fn clear_flash_loan(env: &Env, user: &Address, asset: &Address) {
-    let loan_key = FlashLoanDataKey::ActiveFlashLoan(user.clone(), asset.clone());
-    env.storage().persistent().remove(&loan_key);
-}
-
-/// Execute flash loan
+/// Execute a flash loan through a receiver callback
 ///
-/// Allows users to borrow assets without collateral for a single transaction.
-/// The loan must be repaid (with fee) within the same transaction via callback.
+/// Transfers `amount` of `asset` to `receiver`, calls
+/// `receiver.on_flash_loan(asset, amount, fee, params)` and verifies that the
+/// receiver returned principal + fee before the callback finished. The whole
+/// loan is reverted otherwise. The fee is added to the asset's protocol reserves.
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
-/// * `user` - The address borrowing the flash loan
+/// * `receiver` - The contract receiving the loan (must authorize)
 /// * `asset` - The address of the asset contract to borrow
 /// * `amount` - The amount to borrow
-/// * `callback` - The callback contract address that will handle repayment
+/// * `params` - Opaque data passed through to the callback
 ///
 /// # Returns
-/// Returns the total amount to repay (principal + fee)
+/// Returns the fee charged
 ///
 /// # Errors
 /// * `FlashLoanError::InvalidAmount` - If amount is zero, negative, or outside limits
 /// * `FlashLoanError::InvalidAsset` - If asset address is invalid
 /// * `FlashLoanError::InsufficientLiquidity` - If contract doesn't have enough liquidity
 /// * `FlashLoanError::FlashLoanPaused` - If flash loans are paused
-/// * `FlashLoanError::Reentrancy` - If flash loan is already active for this user/asset
-/// * `FlashLoanError::InvalidCallback` - If callback address is invalid
-/// * `FlashLoanError::Overflow` - If calculation overflow occurs
-pub fn execute_flash_loan(
+/// * `FlashLoanError::InvalidCallback` - If the receiver is this contract
+/// * `FlashLoanError::CallbackFailed` - If the receiver's callback fails
+/// * `FlashLoanError::NotRepaid` - If principal + fee was not returned
+pub fn flash_loan(
     env: &Env,
-    user: Address,
+    receiver: Address,
     asset: Address,
     amount: i128,
-    callback: Address,
+    params: Bytes,
 ) -> Result<i128, FlashLoanError> {
-    // Validate amount
+    let _guard = OperationGuard::enter(env)?;
+    receiver.require_auth();
+
     if amount <= 0 {
         return Err(FlashLoanError::InvalidAmount);
     }
 
-    // Check if flash loans are paused
     let pause_key = FlashLoanDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<FlashLoanDataKey, Map<Symbol, bool>>(&pause_key)
+    if let Some(pause_map) =
+        storage::get_instance::<FlashLoanDataKey, Map<Symbol, bool>>(env, &pause_key)
     {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_flash_loan")) {
-            if paused {
-                return Err(FlashLoanError::FlashLoanPaused);
-            }
+        if pause_map
+            .get(Symbol::new(env, "pause_flash_loan"))
+            .unwrap_or(false)
+        {
+            return Err(FlashLoanError::FlashLoanPaused);
         }
     }
 
-    // Validate asset address
     if asset == env.current_contract_address() {
         return Err(FlashLoanError::InvalidAsset);
     }
-
-    // Validate callback address
-    if callback == env.current_contract_address() {
+    if receiver == env.current_contract_address() {
         return Err(FlashLoanError::InvalidCallback);
     }
 
-    // Check configuration limits
     let config = get_flash_loan_config(env);
     if amount < config.min_amount || amount > config.max_amount {
         return Err(FlashLoanError::InvalidAmount);
     }
 
-    // Check for reentrancy (active flash loan)
-    if is_flash_loan_active(env, &user, &asset) {
-        return Err(FlashLoanError::Reentrancy);
-    }
-
-    // Calculate fee
     let fee = calculate_flash_loan_fee(env, amount)?;
-    let total_repayment = amount.checked_add(fee).ok_or(FlashLoanError::Overflow)?;
 
-    // Check contract balance
     let token_client = soroban_sdk::token::Client::new(env, &asset);
-    let contract_balance = token_client.balance(&env.current_contract_address());
-    if contract_balance < amount {
+    let balance_before = token_client.balance(&env.current_contract_address());
+    if balance_before < amount {
         return Err(FlashLoanError::InsufficientLiquidity);
     }
 
-    // Record flash loan before transfer
-    record_flash_loan(env, &user, &asset, amount, fee, &callback);
+    token_client.transfer(&env.current_contract_address(), &receiver, &amount);
 
-    // Transfer tokens to user
-    token_client.transfer(
-        &env.current_contract_address(), // from (this contract)
-        &user,                           // to (user)
-        &amount,
-    );
-
-    // Emit flash loan initiated event
     emit_flash_loan_initiated(
         env,
         FlashLoanInitiatedEvent {
-            user: user.clone(),
+            user: receiver.clone(),
             asset: asset.clone(),
             amount,
             fee,
-            callback: callback.clone(),
+            callback: receiver.clone(),
             timestamp: env.ledger().timestamp(),
         },
     );
 
-    // Note: In a real implementation, we would call the callback here
-    // For Soroban, the callback would need to be invoked by the user
-    // The repayment check happens when the user calls repay_flash_loan
-
-    Ok(total_repayment)
-}
-
-/// Repay flash loan
-///
-/// Must be called within the same transaction as the flash loan.
-/// Validates that the full amount (principal + fee) is repaid.
-///
-/// # Arguments
-/// * `env` - The Soroban environment
-/// * `user` - The address repaying the flash loan
-/// * `asset` - The address of the asset contract
-/// * `amount` - The amount being repaid (should equal principal + fee)
-///
-/// # Returns
-/// Returns success if repayment is valid
-pub fn repay_flash_loan(
-    env: &Env,
-    user: Address,
-    asset: Address,
-    amount: i128,
-) -> Result<(), FlashLoanError> {
-    // Get active flash loan record
-    let loan_key = FlashLoanDataKey::ActiveFlashLoan(user.clone(), asset.clone());
-    let record = env
-        .storage()
-        .persistent()
-        .get::<FlashLoanDataKey, FlashLoanRecord>(&loan_key)
-        .ok_or(FlashLoanError::NotRepaid)?;
-
-    // Calculate required repayment
-    let required_repayment = record
-        .amount
-        .checked_add(record.fee)
-        .ok_or(FlashLoanError::Overflow)?;
-
-    // Validate repayment amount
-    if amount < required_repayment {
-        return Err(FlashLoanError::InsufficientRepayment);
+    let args: Vec<Val> = vec![
+        env,
+        asset.into_val(env),
+        amount.into_val(env),
+        fee.into_val(env),
+        params.into_val(env),
+    ];
+    match env.try_invoke_contract::<(), soroban_sdk::Error>(
+        &receiver,
+        &Symbol::new(env, "on_flash_loan"),
+        args,
+    ) {
+        Ok(Ok(())) => {}
+        _ => return Err(FlashLoanError::CallbackFailed),
     }
 
-    // Transfer tokens from user to contract
-    let token_client = soroban_sdk::token::Client::new(env, &asset);
-
-    // Check user balance
-    let user_balance = token_client.balance(&user);
-    if user_balance < required_repayment {
-        return Err(FlashLoanError::InsufficientRepayment);
+    // Principal + fee must be back before the loan completes
+    let required_balance = balance_before
+        .checked_add(fee)
+        .ok_or(FlashLoanError::Overflow)?;
+    let balance_after = token_client.balance(&env.current_contract_address());
+    if balance_after < required_balance {
+        return Err(FlashLoanError::NotRepaid);
     }
-
-    // Transfer repayment (user must have approved the contract)
-    token_client.transfer_from(
-        &env.current_contract_address(), // spender (this contract)
-        &user,                           // from (user)
-        &env.current_contract_address(), // to (this contract)
-        &required_repayment,
+    // The receiver may return more than principal + fee; record what arrived,
+    // with the principal sent, in one ledger write
+    cash_ledger::record_flow(
+        env,
+        &asset,
+        CashReason::FlashLoan,
+        balance_after - balance_before + amount,
+        amount,
     );
 
-    // Clear flash loan record
-    clear_flash_loan(env, &user, &asset);
+    add_reserves(env, Some(asset.clone()), fee, RevenueSource::FlashLoanFee)
+        .map_err(|_| FlashLoanError::Overflow)?;
 
-    // Emit flash loan repaid event
     emit_flash_loan_repaid(
         env,
         FlashLoanRepaidEvent {
-            user: user.clone(),
-            asset: asset.clone(),
-            amount: record.amount,
-            fee: record.fee,
+            user: receiver,
+            asset,
+            amount,
+            fee,
             timestamp: env.ledger().timestamp(),
         },
     );
 
-    Ok(())
+    Ok(fee)
 }
 
 /// Set flash loan fee
@@ -368,7 +290,7 @@ pub fn set_flash_loan_fee(env: &Env, caller: Address, fee_bps: i128) -> Result<(
     }
 
     // Validate fee (must be between 0 and 10000 basis points)
-    if !(0..=10000).contains(&fee_bps) {
+    if !Bps::new(fee_bps).is_ratio() {
         return Err(FlashLoanError::InvalidAmount);
     }
 
@@ -376,7 +298,16 @@ pub fn set_flash_loan_fee(env: &Env, caller: Address, fee_bps: i128) -> Result<(
     let mut config = get_flash_loan_config(env);
     config.fee_bps = fee_bps;
     let config_key = FlashLoanDataKey::FlashLoanConfig;
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_flash_loan_fee"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     Ok(())
 }
@@ -400,7 +331,7 @@ pub fn configure_flash_loan(
     }
 
     // Validate configuration
-    if !(0..=10000).contains(&config.fee_bps) {
+    if !Bps::new(config.fee_bps).is_ratio() {
         return Err(FlashLoanError::InvalidAmount);
     }
 
@@ -410,7 +341,16 @@ pub fn configure_flash_loan(
 
     // Update configuration
     let config_key = FlashLoanDataKey::FlashLoanConfig;
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "configure_flash_loan"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     Ok(())
 }
@@ -1,21 +1,23 @@
 //! # Flash Loan Test Suite
 //!
 //! Comprehensive tests for flash loan functionality including:
-//! - Successful flash loan execution and repayment
 //! - Fee calculation and validation
-//! - Unpaid loan revert scenarios
-//! - Callback validation
 //! - Admin fee configuration (set_fee_bps)
-//! - Security assumptions (reentrancy, pause, limits)
+//! - Security assumptions (pause, limits)
+//! - Single-call flash loans through a receiver callback, including unpaid
+//!   loan and callback failure reverts
 
-use soroban_sdk::{testutils::Address as _, token, Address, Env, Map, Symbol};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, testutils::Address as _, token, Address, Bytes, Env, Map,
+    Symbol,
+};
 
+use crate::cash_ledger::{CashFlow, CashReason};
 use crate::flash_loan::{
-    configure_flash_loan, execute_flash_loan, repay_flash_loan, set_flash_loan_fee,
-    FlashLoanConfig, FlashLoanDataKey, FlashLoanError,
+    configure_flash_loan, set_flash_loan_fee, FlashLoanConfig, FlashLoanDataKey, FlashLoanError,
 };
 use crate::risk_management::RiskDataKey;
-use crate::HelloContract;
+use crate::{HelloContract, HelloContractClient};
 
 /// Setup test environment with contract context
 fn setup_env() -> (Env, Address, Address, Address, Address) {
@@ -31,7 +33,7 @@ fn setup_env() -> (Env, Address, Address, Address, Address) {
 
     // Set admin in contract context
     env.as_contract(&contract_id, || {
-        env.storage().persistent().set(&RiskDataKey::Admin, &admin);
+        env.storage().instance().set(&RiskDataKey::Admin, &admin);
     });
 
     (env, contract_id, admin, user, token_address)
@@ -45,59 +47,6 @@ fn setup_with_balance(balance: i128) -> (Env, Address, Address, Address, Address
     (env, contract_id, admin, user, token_address)
 }
 
-// ============================================================================
-// SUCCESS CASES
-// ============================================================================
-
-/// Test successful flash loan execution
-#[test]
-fn test_flash_loan_success() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-    });
-
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 1_000_900); // 1M + 900 fee
-}
-
-/// Test successful repayment
-#[test]
-fn test_flash_loan_repayment_success() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-    let token_client = token::StellarAssetClient::new(&env, &token_address);
-    let token_std_client = token::TokenClient::new(&env, &token_address);
-
-    let total = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-        .unwrap()
-    });
-
-    token_client.mint(&user, &(total * 2));
-    token_std_client.approve(&user, &contract_id, &total, &99999);
-
-    let result = env.as_contract(&contract_id, || {
-        repay_flash_loan(&env, user.clone(), token_address.clone(), total)
-    });
-
-    assert!(result.is_ok());
-}
-
 // ============================================================================
 // FEE CALCULATION TESTS
 // ============================================================================
@@ -105,201 +54,53 @@ fn test_flash_loan_repayment_success() {
 /// Test default fee (9 bps)
 #[test]
 fn test_default_fee_calculation() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(100_000_000);
-    let callback = Address::generate(&env);
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(100_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 100_000);
+    let params = Bytes::from_array(&env, &[0]);
 
     let cases = [(1_000_000_i128, 900_i128), (10_000_000_i128, 9_000_i128)];
 
     for (amount, expected_fee) in cases {
-        let total = env.as_contract(&contract_id, || {
-            execute_flash_loan(
-                &env,
-                user.clone(),
-                token_address.clone(),
-                amount,
-                callback.clone(),
-            )
-            .unwrap()
-        });
-
-        assert_eq!(total, amount + expected_fee);
-
-        // Clear for next test
-        env.as_contract(&contract_id, || {
-            let key = FlashLoanDataKey::ActiveFlashLoan(user.clone(), token_address.clone());
-            env.storage().persistent().remove(&key);
-        });
+        let fee = client.flash_loan(&receiver, &token_address, &amount, &params);
+        assert_eq!(fee, expected_fee);
     }
 }
 
 /// Test custom fee
 #[test]
 fn test_custom_fee_calculation() {
-    let (env, contract_id, admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    env.as_contract(&contract_id, || {
-        set_flash_loan_fee(&env, admin, 50).unwrap(); // 0.5%
-    });
-
-    let total = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-        .unwrap()
-    });
-
-    assert_eq!(total, 1_005_000); // 1M + 5K fee
+    let (env, contract_id, admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 100_000);
+
+    client.set_flash_loan_fee(&admin, &50); // 0.5%
+    let fee = client.flash_loan(
+        &receiver,
+        &token_address,
+        &1_000_000,
+        &Bytes::from_array(&env, &[0]),
+    );
+
+    assert_eq!(fee, 5_000);
 }
 
 /// Test zero fee
 #[test]
 fn test_zero_fee() {
-    let (env, contract_id, admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    env.as_contract(&contract_id, || {
-        set_flash_loan_fee(&env, admin, 0).unwrap();
-    });
-
-    let total = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-        .unwrap()
-    });
-
-    assert_eq!(total, 1_000_000);
-}
-
-// ============================================================================
-// UNPAID LOAN REVERT TESTS
-// ============================================================================
-
-/// Test unpaid loan error
-#[test]
-fn test_unpaid_loan_revert() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-
-    let result = env.as_contract(&contract_id, || {
-        repay_flash_loan(&env, user.clone(), token_address.clone(), 1_000_000)
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::NotRepaid);
-}
-
-/// Test insufficient repayment
-#[test]
-fn test_insufficient_repayment() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-    let token_client = token::StellarAssetClient::new(&env, &token_address);
-    let token_std_client = token::TokenClient::new(&env, &token_address);
-
-    let total = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-        .unwrap()
-    });
-
-    let insufficient = total - 100;
-    token_client.mint(&user, &(insufficient * 2));
-    token_std_client.approve(&user, &contract_id, &insufficient, &99999);
-
-    let result = env.as_contract(&contract_id, || {
-        repay_flash_loan(&env, user.clone(), token_address.clone(), insufficient)
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InsufficientRepayment);
-}
-
-/// Test user insufficient balance
-#[test]
-fn test_insufficient_user_balance() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    let total = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-        .unwrap()
-    });
-
-    let result = env.as_contract(&contract_id, || {
-        repay_flash_loan(&env, user.clone(), token_address.clone(), total)
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InsufficientRepayment);
-}
-
-// ============================================================================
-// CALLBACK VALIDATION TESTS
-// ============================================================================
-
-/// Test invalid callback (contract itself)
-#[test]
-fn test_invalid_callback_self() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            contract_id.clone(),
-        )
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InvalidCallback);
-}
-
-/// Test valid callback
-#[test]
-fn test_valid_callback() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback.clone(),
-        )
-    });
-
-    assert!(result.is_ok());
-
-    // Verify callback stored
-    env.as_contract(&contract_id, || {
-        let key = FlashLoanDataKey::ActiveFlashLoan(user.clone(), token_address.clone());
-        let record = env
-            .storage()
-            .persistent()
-            .get::<FlashLoanDataKey, crate::flash_loan::FlashLoanRecord>(&key)
-            .unwrap();
-        assert_eq!(record.callback, callback);
-    });
+    let (env, contract_id, admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 0);
+
+    client.set_flash_loan_fee(&admin, &0);
+    let fee = client.flash_loan(
+        &receiver,
+        &token_address,
+        &1_000_000,
+        &Bytes::from_array(&env, &[0]),
+    );
+
+    assert_eq!(fee, 0);
 }
 
 // ============================================================================
@@ -319,7 +120,7 @@ fn test_set_fee_bps_admin() {
         let key = FlashLoanDataKey::FlashLoanConfig;
         let config = env
             .storage()
-            .persistent()
+            .instance()
             .get::<FlashLoanDataKey, FlashLoanConfig>(&key)
             .unwrap();
         assert_eq!(config.fee_bps, 25);
@@ -366,175 +167,91 @@ fn test_set_fee_bps_maximum() {
 // SECURITY TESTS
 // ============================================================================
 
-/// Test reentrancy protection
-#[test]
-fn test_reentrancy_protection() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(20_000_000);
-    let callback = Address::generate(&env);
-
-    env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback.clone(),
-        )
-        .unwrap();
-    });
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::Reentrancy);
-}
-
 /// Test pause functionality
 #[test]
 fn test_pause_flash_loan() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
 
     env.as_contract(&contract_id, || {
         let key = FlashLoanDataKey::PauseSwitches;
         let mut pause_map = Map::new(&env);
         pause_map.set(Symbol::new(&env, "pause_flash_loan"), true);
-        env.storage().persistent().set(&key, &pause_map);
-    });
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
+        env.storage().instance().set(&key, &pause_map);
     });
 
-    assert_eq!(result.unwrap_err(), FlashLoanError::FlashLoanPaused);
-}
-
-/// Test insufficient liquidity
-#[test]
-fn test_insufficient_liquidity() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(100_000);
-    let callback = Address::generate(&env);
+    let result = client.try_flash_loan(
+        &receiver,
+        &token_address,
+        &1_000_000,
+        &Bytes::from_array(&env, &[0]),
+    );
 
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            1_000_000,
-            callback,
-        )
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InsufficientLiquidity);
-}
-
-/// Test invalid amount (zero)
-#[test]
-fn test_invalid_amount_zero() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(&env, user.clone(), token_address.clone(), 0, callback)
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InvalidAmount);
+    assert_eq!(result, Err(Ok(FlashLoanError::FlashLoanPaused)));
 }
 
 /// Test invalid amount (negative)
 #[test]
 fn test_invalid_amount_negative() {
-    let (env, contract_id, _admin, user, token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            -1_000_000,
-            callback,
-        )
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InvalidAmount);
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+
+    let result = client.try_flash_loan(
+        &receiver,
+        &token_address,
+        &-1_000_000,
+        &Bytes::from_array(&env, &[0]),
+    );
+
+    assert_eq!(result, Err(Ok(FlashLoanError::InvalidAmount)));
 }
 
 /// Test invalid asset (contract itself)
 #[test]
 fn test_invalid_asset() {
-    let (env, contract_id, _admin, user, _token_address) = setup_with_balance(10_000_000);
-    let callback = Address::generate(&env);
-
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(&env, user.clone(), contract_id.clone(), 1_000_000, callback)
-    });
-
-    assert_eq!(result.unwrap_err(), FlashLoanError::InvalidAsset);
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+
+    let result = client.try_flash_loan(
+        &receiver,
+        &contract_id,
+        &1_000_000,
+        &Bytes::from_array(&env, &[0]),
+    );
+
+    assert_eq!(result, Err(Ok(FlashLoanError::InvalidAsset)));
 }
 
 /// Test configuration limits
 #[test]
 fn test_configuration_limits() {
-    let (env, contract_id, admin, user, token_address) = setup_with_balance(100_000_000);
-    let callback = Address::generate(&env);
-
-    env.as_contract(&contract_id, || {
-        let config = FlashLoanConfig {
+    let (env, contract_id, admin, _user, token_address) = setup_with_balance(100_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+    let params = Bytes::from_array(&env, &[0]);
+
+    client.configure_flash_loan(
+        &admin,
+        &FlashLoanConfig {
             fee_bps: 9,
             max_amount: 10_000_000,
             min_amount: 1_000,
-        };
-        configure_flash_loan(&env, admin, config).unwrap();
-    });
+        },
+    );
 
     // Below minimum
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            500,
-            callback.clone(),
-        )
-    });
-    assert_eq!(result.unwrap_err(), FlashLoanError::InvalidAmount);
+    let result = client.try_flash_loan(&receiver, &token_address, &500, &params);
+    assert_eq!(result, Err(Ok(FlashLoanError::InvalidAmount)));
 
     // Above maximum
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            20_000_000,
-            callback.clone(),
-        )
-    });
-    assert_eq!(result.unwrap_err(), FlashLoanError::InvalidAmount);
+    let result = client.try_flash_loan(&receiver, &token_address, &20_000_000, &params);
+    assert_eq!(result, Err(Ok(FlashLoanError::InvalidAmount)));
 
     // Within limits
-    let result = env.as_contract(&contract_id, || {
-        execute_flash_loan(
-            &env,
-            user.clone(),
-            token_address.clone(),
-            5_000_000,
-            callback,
-        )
-    });
+    let result = client.try_flash_loan(&receiver, &token_address, &5_000_000, &params);
     assert!(result.is_ok());
 }
 
@@ -576,3 +293,179 @@ fn test_invalid_configuration() {
     });
     assert!(result.is_err());
 }
+
+// ============================================================================
+// SINGLE-CALL FLASH LOANS
+// ============================================================================
+
+#[contracttype]
+enum ReceiverKey {
+    Lender,
+    Params,
+}
+
+/// Receiver that repays according to the first byte of `params`:
+/// 0 = principal + fee, 1 = principal only, anything else = fail
+#[contract]
+pub struct FlashReceiver;
+
+#[contractimpl]
+impl FlashReceiver {
+    pub fn init(env: Env, lender: Address) {
+        env.storage().instance().set(&ReceiverKey::Lender, &lender);
+    }
+
+    pub fn params(env: Env) -> Option<Bytes> {
+        env.storage().instance().get(&ReceiverKey::Params)
+    }
+
+    pub fn on_flash_loan(env: Env, asset: Address, amount: i128, fee: i128, params: Bytes) {
+        env.storage().instance().set(&ReceiverKey::Params, &params);
+        let lender: Address = env.storage().instance().get(&ReceiverKey::Lender).unwrap();
+        let repayment = match params.get(0) {
+            Some(0) => amount + fee,
+            Some(1) => amount,
+            _ => panic!("receiver failed"),
+        };
+        token::Client::new(&env, &asset).transfer(
+            &env.current_contract_address(),
+            &lender,
+            &repayment,
+        );
+    }
+}
+
+/// Registers a receiver holding `float` tokens to pay fees with
+fn setup_receiver(
+    env: &Env,
+    contract_id: &Address,
+    token_address: &Address,
+    float: i128,
+) -> Address {
+    let receiver = env.register(FlashReceiver, ());
+    FlashReceiverClient::new(env, &receiver).init(contract_id);
+    token::StellarAssetClient::new(env, token_address).mint(&receiver, &float);
+    receiver
+}
+
+/// Test a flash loan repaid with its fee inside the callback
+#[test]
+fn test_single_call_flash_loan() {
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+    let params = Bytes::from_array(&env, &[0, 7, 7]);
+
+    let fee = client.flash_loan(&receiver, &token_address, &1_000_000, &params);
+
+    // Default fee: 9 bps
+    assert_eq!(fee, 900);
+    let token_client = token::Client::new(&env, &token_address);
+    assert_eq!(token_client.balance(&contract_id), 10_000_900);
+    assert_eq!(token_client.balance(&receiver), 9_100);
+    assert_eq!(
+        FlashReceiverClient::new(&env, &receiver).params(),
+        Some(params)
+    );
+}
+
+/// Test the principal out and repayment in are posted to the cash ledger
+#[test]
+fn test_single_call_posts_cash_ledger_once() {
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+
+    client.flash_loan(
+        &receiver,
+        &token_address,
+        &1_000_000,
+        &Bytes::from_array(&env, &[0]),
+    );
+
+    assert_eq!(
+        client
+            .get_cash_ledger(&token_address)
+            .get(CashReason::FlashLoan),
+        Some(CashFlow {
+            inflow: 1_000_900,
+            outflow: 1_000_000,
+        })
+    );
+}
+
+/// Test flash loan fees accrue to protocol reserves
+#[test]
+fn test_single_call_fee_accrues_to_reserves() {
+    let (env, contract_id, admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+    let params = Bytes::from_array(&env, &[0]);
+
+    client.set_flash_loan_fee(&admin, &30);
+    client.flash_loan(&receiver, &token_address, &1_000_000, &params);
+    client.flash_loan(&receiver, &token_address, &500_000, &params);
+
+    let pool = client.get_pool_state(&Some(token_address));
+    assert_eq!(pool.reserves, 4_500);
+    assert_eq!(pool.total_liquidity, 0);
+}
+
+/// Test a receiver that returns only the principal
+#[test]
+fn test_single_call_unpaid_fee_reverts() {
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+
+    let result = client.try_flash_loan(
+        &receiver,
+        &token_address,
+        &1_000_000,
+        &Bytes::from_array(&env, &[1]),
+    );
+
+    assert_eq!(result, Err(Ok(FlashLoanError::NotRepaid)));
+    let token_client = token::Client::new(&env, &token_address);
+    assert_eq!(token_client.balance(&contract_id), 10_000_000);
+    assert_eq!(token_client.balance(&receiver), 10_000);
+}
+
+/// Test a failing callback
+#[test]
+fn test_single_call_callback_failure() {
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(10_000_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+
+    let result = client.try_flash_loan(
+        &receiver,
+        &token_address,
+        &1_000_000,
+        &Bytes::from_array(&env, &[2]),
+    );
+
+    assert_eq!(result, Err(Ok(FlashLoanError::CallbackFailed)));
+    assert_eq!(
+        token::Client::new(&env, &token_address).balance(&contract_id),
+        10_000_000
+    );
+}
+
+/// Test single-call loan validation
+#[test]
+fn test_single_call_validation() {
+    let (env, contract_id, _admin, _user, token_address) = setup_with_balance(100_000);
+    let client = HelloContractClient::new(&env, &contract_id);
+    let receiver = setup_receiver(&env, &contract_id, &token_address, 10_000);
+    let params = Bytes::from_array(&env, &[0]);
+
+    let result = client.try_flash_loan(&receiver, &token_address, &0, &params);
+    assert_eq!(result, Err(Ok(FlashLoanError::InvalidAmount)));
+
+    let result = client.try_flash_loan(&receiver, &token_address, &1_000_000, &params);
+    assert_eq!(result, Err(Ok(FlashLoanError::InsufficientLiquidity)));
+
+    let result = client.try_flash_loan(&contract_id, &token_address, &1_000, &params);
+    assert_eq!(result, Err(Ok(FlashLoanError::InvalidCallback)));
+}
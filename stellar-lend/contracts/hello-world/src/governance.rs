@@ -18,6 +18,7 @@
 //! - Voting threshold: 50% of total voting power
 
 #![allow(unused)]
+use crate::constants::{Bps, BASIS_POINTS_SCALE, SECONDS_PER_DAY};
 use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Vec};
 
 /// Errors that can occur during governance operations
@@ -151,10 +152,9 @@ pub struct Proposal {
 }
 
 /// Constants
-const DEFAULT_VOTING_PERIOD: u64 = 7 * 24 * 60 * 60; // 7 days in seconds
-const DEFAULT_EXECUTION_TIMELOCK: u64 = 2 * 24 * 60 * 60; // 2 days in seconds
+const DEFAULT_VOTING_PERIOD: u64 = 7 * SECONDS_PER_DAY.get(); // 7 days in seconds
+const DEFAULT_EXECUTION_TIMELOCK: u64 = 2 * SECONDS_PER_DAY.get(); // 2 days in seconds
 const DEFAULT_VOTING_THRESHOLD: i128 = 5_000; // 50% in basis points
-const BASIS_POINTS_SCALE: i128 = 10_000; // 100% = 10,000 basis points
 
 /// Initialize the governance system.
 ///
@@ -232,7 +232,7 @@ pub fn create_proposal(
     let voting_threshold = voting_threshold.unwrap_or(DEFAULT_VOTING_THRESHOLD);
 
     // Validate voting threshold
-    if voting_threshold < 0 || voting_threshold > BASIS_POINTS_SCALE {
+    if !Bps::new(voting_threshold).is_ratio() {
         return Err(GovernanceError::InvalidProposal);
     }
 
@@ -347,7 +347,7 @@ pub fn vote(
 
     // Check if threshold is met
     let threshold_votes =
-        (proposal.total_voting_power * proposal.voting_threshold) / BASIS_POINTS_SCALE;
+        (proposal.total_voting_power * proposal.voting_threshold) / BASIS_POINTS_SCALE.get();
     if proposal.votes_for >= threshold_votes && proposal.status == ProposalStatus::Active {
         proposal.status = ProposalStatus::Passed;
     }
@@ -398,7 +398,7 @@ pub fn execute_proposal(
         ProposalStatus::Active => {
             // Check if threshold is met
             let threshold_votes =
-                (proposal.total_voting_power * proposal.voting_threshold) / BASIS_POINTS_SCALE;
+                (proposal.total_voting_power * proposal.voting_threshold) / BASIS_POINTS_SCALE.get();
             if proposal.votes_for < threshold_votes {
                 proposal.status = ProposalStatus::Failed;
                 env.storage().persistent().set(&proposal_key, &proposal);
@@ -454,7 +454,7 @@ pub fn mark_proposal_failed(env: &Env, proposal_id: u64) -> Result<(), Governanc
 
     // Check if threshold was met
     let threshold_votes =
-        (proposal.total_voting_power * proposal.voting_threshold) / BASIS_POINTS_SCALE;
+        (proposal.total_voting_power * proposal.voting_threshold) / BASIS_POINTS_SCALE.get();
     if proposal.votes_for < threshold_votes {
         proposal.status = ProposalStatus::Failed;
         env.storage().persistent().set(&proposal_key, &proposal);
@@ -0,0 +1,595 @@
+//! # Supplier Insurance
+//!
+//! Optional coverage for lending pool suppliers, backed by a per-market
+//! insurance fund that the safety module (or anyone) tops up with
+//! [`fund_insurance`].
+//!
+//! A supplier opts in with [`opt_in_coverage`], which covers every pool share
+//! they hold in the market, including shares supplied later.
+//!
+//! ## Premiums
+//! Covered suppliers pay `premium_bps` of the yield their shares earn. The
+//! premium is charged whenever coverage is checkpointed (on supply, redeem,
+//! opt-out and claims): the yield since the last checkpoint is measured from
+//! the pool's liquidity index, and the premium is burned from the supplier's
+//! shares at the current index and moved from pool liquidity into the fund.
+//! Yield is only charged above the highest index already charged, so value
+//! recovered after a shortfall is not charged twice.
+//!
+//! ## Shortfalls
+//! The admin reports a pool loss with [`report_shortfall`], or socializes bad
+//! debt with [`crate::lending_pool::write_off_bad_debt`]; the loss is taken
+//! from pool liquidity, lowering the value of every share. The fund then makes
+//! covered suppliers whole first by paying the covered shares' part of the
+//! loss, bounded by the market's coverage cap and the fund balance. Payouts
+//! accrue per covered share and are collected with [`claim_coverage`].
+//!
+//! ## Coverage Cap
+//! `coverage_cap` bounds the value of covered shares (0 = unlimited): opting
+//! in fails once covered value would exceed it, and a shortfall payout covers
+//! losses on at most that much value.
+
+use soroban_sdk::{contracterror, contracttype, token, Address, Env};
+
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::Bps;
+use crate::cross_asset::{get_asset_config_by_address, require_admin, AssetKey};
+use crate::events::{
+    emit_coverage_claimed, emit_coverage_updated, emit_insurance_config_updated,
+    emit_insurance_funded, emit_shortfall_reported, CoverageClaimedEvent, CoverageUpdatedEvent,
+    InsuranceConfigUpdatedEvent, InsuranceFundedEvent, ShortfallReportedEvent,
+};
+use crate::lending_pool::{
+    get_pool_state, get_supply_shares, liquidity_index, set_supply_shares, PoolDataKey,
+    LIQUIDITY_INDEX_ONE,
+};
+use crate::math::{Amount, MathError, Shares};
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::storage;
+
+/// Errors that can occur during insurance operations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum InsuranceError {
+    /// Amount must be greater than zero and within the pool's liquidity
+    InvalidAmount = 1,
+    /// The asset is not a registered cross-asset market
+    AssetNotConfigured = 2,
+    /// Caller is not the admin
+    Unauthorized = 3,
+    /// Premium is outside [0, 10000] or the cap is negative
+    InvalidParameter = 4,
+    /// The market does not offer coverage
+    CoverageNotOffered = 5,
+    /// The user is already covered in the market
+    AlreadyCovered = 6,
+    /// The user is not covered in the market
+    NotCovered = 7,
+    /// The user holds no pool shares in the market
+    NoSupply = 8,
+    /// Covering the user would exceed the market's coverage cap
+    CoverageCapReached = 9,
+    /// Overflow occurred during calculation
+    Overflow = 10,
+    /// Reentrancy detected
+    Reentrancy = 11,
+}
+
+impl From<MathError> for InsuranceError {
+    fn from(_: MathError) -> Self {
+        InsuranceError::Overflow
+    }
+}
+
+impl From<Reentered> for InsuranceError {
+    fn from(_: Reentered) -> Self {
+        InsuranceError::Reentrancy
+    }
+}
+
+/// Storage keys for insurance data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum InsuranceDataKey {
+    /// Coverage terms per market: InsuranceConfig
+    Config(AssetKey),
+    /// Insurance fund per market: InsuranceFund
+    Fund(AssetKey),
+    /// A supplier's coverage in a market: Coverage
+    Coverage(Address, AssetKey),
+}
+
+/// Coverage terms of one market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceConfig {
+    /// Premium charged on covered suppliers' yield (in basis points)
+    pub premium_bps: i128,
+    /// Highest value of covered shares (0 = unlimited)
+    pub coverage_cap: i128,
+}
+
+/// Insurance fund of one market
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InsuranceFund {
+    /// Asset available to pay shortfalls
+    pub balance: i128,
+    /// Pool shares currently covered
+    pub covered_shares: i128,
+    /// Cumulative payout per covered share, scaled by [`LIQUIDITY_INDEX_ONE`]
+    pub payout_index: i128,
+    /// Premiums collected since the market first offered coverage
+    pub premiums_collected: i128,
+    /// Shortfall payouts owed or paid to covered suppliers
+    pub payouts: i128,
+}
+
+/// A supplier's coverage in one market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Coverage {
+    /// Pool shares covered, kept equal to the supplier's shares
+    pub shares: i128,
+    /// Highest liquidity index premiums have been charged up to
+    pub charged_index: i128,
+    /// Fund payout index at the last checkpoint
+    pub payout_index: i128,
+    /// Shortfall payouts not yet claimed
+    pub claimable: i128,
+}
+
+/// Offer coverage in a market, or change its terms (cross-asset admin only).
+///
+/// New terms apply to yield and shortfalls from the next checkpoint on.
+///
+/// # Errors
+/// * `Unauthorized` - The admin did not authorize the call
+/// * `AssetNotConfigured` - The asset is not a registered market
+/// * `InvalidParameter` - Premium outside [0, 10000] or negative cap
+pub fn set_insurance_config(
+    env: &Env,
+    asset: Option<Address>,
+    premium_bps: i128,
+    coverage_cap: i128,
+) -> Result<(), InsuranceError> {
+    let admin = require_admin(env).map_err(|_| InsuranceError::Unauthorized)?;
+    get_asset_config_by_address(env, asset.clone())
+        .map_err(|_| InsuranceError::AssetNotConfigured)?;
+    if !Bps::new(premium_bps).is_ratio() || coverage_cap < 0 {
+        return Err(InsuranceError::InvalidParameter);
+    }
+
+    storage::set(
+        env,
+        &InsuranceDataKey::Config(AssetKey::from_option(asset.clone())),
+        &InsuranceConfig {
+            premium_bps,
+            coverage_cap,
+        },
+    );
+
+    emit_insurance_config_updated(
+        env,
+        InsuranceConfigUpdatedEvent {
+            admin,
+            asset,
+            premium_bps,
+            coverage_cap,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Add to a market's insurance fund.
+///
+/// Transfers `amount` of the asset from `from` (who must have approved the
+/// contract), typically the safety module.
+///
+/// # Errors
+/// * `InvalidAmount` - Amount is not positive
+/// * `CoverageNotOffered` - The market does not offer coverage
+pub fn fund_insurance(
+    env: &Env,
+    from: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<(), InsuranceError> {
+    let _guard = OperationGuard::enter(env)?;
+    from.require_auth();
+
+    if amount <= 0 {
+        return Err(InsuranceError::InvalidAmount);
+    }
+    if get_insurance_config(env, asset.clone()).is_none() {
+        return Err(InsuranceError::CoverageNotOffered);
+    }
+
+    if let Some(ref asset_addr) = asset {
+        token::Client::new(env, asset_addr).transfer_from(
+            &env.current_contract_address(),
+            &from,
+            &env.current_contract_address(),
+            &amount,
+        );
+        cash_ledger::record_inflow(env, asset_addr, CashReason::Insurance, amount);
+    }
+
+    let mut fund = get_insurance_fund(env, asset.clone());
+    fund.balance = Amount::new(fund.balance)
+        .checked_add(Amount::new(amount))?
+        .get();
+    set_fund(env, &asset, &fund);
+
+    emit_insurance_funded(
+        env,
+        InsuranceFundedEvent {
+            from,
+            asset,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Cover all of a supplier's pool shares in a market.
+///
+/// # Errors
+/// * `CoverageNotOffered` - The market does not offer coverage
+/// * `AlreadyCovered` - The user is already covered
+/// * `NoSupply` - The user holds no pool shares
+/// * `CoverageCapReached` - Covered value would exceed the coverage cap
+pub fn opt_in_coverage(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+) -> Result<Coverage, InsuranceError> {
+    let _guard = OperationGuard::enter(env)?;
+    user.require_auth();
+
+    let config =
+        get_insurance_config(env, asset.clone()).ok_or(InsuranceError::CoverageNotOffered)?;
+    let key = InsuranceDataKey::Coverage(user.clone(), AssetKey::from_option(asset.clone()));
+    if storage::has(env, &key) {
+        return Err(InsuranceError::AlreadyCovered);
+    }
+    let shares = get_supply_shares(env, &user, asset.clone());
+    if shares <= 0 {
+        return Err(InsuranceError::NoSupply);
+    }
+
+    let mut fund = get_insurance_fund(env, asset.clone());
+    let covered_shares = Shares::new(fund.covered_shares).checked_add(Shares::new(shares))?;
+    let pool = get_pool_state(env, asset.clone());
+    if config.coverage_cap > 0 {
+        let covered_value = covered_shares.to_amount(
+            Amount::new(pool.total_liquidity),
+            Shares::new(pool.total_shares),
+        )?;
+        if covered_value.get() > config.coverage_cap {
+            return Err(InsuranceError::CoverageCapReached);
+        }
+    }
+
+    let coverage = Coverage {
+        shares,
+        charged_index: liquidity_index(&pool)?,
+        payout_index: fund.payout_index,
+        claimable: 0,
+    };
+    fund.covered_shares = covered_shares.get();
+    set_fund(env, &asset, &fund);
+    storage::set(env, &key, &coverage);
+
+    emit_coverage_updated(
+        env,
+        CoverageUpdatedEvent {
+            user,
+            asset,
+            covered: true,
+            shares,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(coverage)
+}
+
+/// End a supplier's coverage in a market.
+///
+/// Charges the premium owed up to now and pays out any unclaimed shortfall
+/// payouts.
+///
+/// # Returns
+/// The payout transferred to the user.
+///
+/// # Errors
+/// * `NotCovered` - The user is not covered
+pub fn opt_out_coverage(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+) -> Result<i128, InsuranceError> {
+    let _guard = OperationGuard::enter(env)?;
+    user.require_auth();
+
+    checkpoint(env, &user, asset.clone())?;
+    let key = InsuranceDataKey::Coverage(user.clone(), AssetKey::from_option(asset.clone()));
+    let coverage: Coverage = storage::get(env, &key).ok_or(InsuranceError::NotCovered)?;
+
+    let mut fund = get_insurance_fund(env, asset.clone());
+    fund.covered_shares = Shares::new(fund.covered_shares)
+        .checked_sub(Shares::new(coverage.shares))?
+        .get();
+    set_fund(env, &asset, &fund);
+    storage::remove(env, &key);
+    pay_out(env, &user, &asset, coverage.claimable);
+
+    emit_coverage_updated(
+        env,
+        CoverageUpdatedEvent {
+            user,
+            asset,
+            covered: false,
+            shares: coverage.shares,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(coverage.claimable)
+}
+
+/// Record a loss to a market's pool (cross-asset admin only).
+///
+/// Takes `loss` from pool liquidity and pays covered suppliers their part of
+/// it from the insurance fund, bounded by the coverage cap and fund balance.
+///
+/// # Returns
+/// The payout owed to covered suppliers.
+///
+/// # Errors
+/// * `Unauthorized` - The admin did not authorize the call
+/// * `InvalidAmount` - Loss is not positive or exceeds the pool's liquidity
+pub fn report_shortfall(
+    env: &Env,
+    asset: Option<Address>,
+    loss: i128,
+) -> Result<i128, InsuranceError> {
+    let _guard = OperationGuard::enter(env)?;
+    require_admin(env).map_err(|_| InsuranceError::Unauthorized)?;
+    apply_shortfall(env, asset, loss)
+}
+
+/// Take `loss` from a market's pool liquidity and pay covered suppliers
+/// their part of it, as [`report_shortfall`] does without the admin check
+pub(crate) fn apply_shortfall(
+    env: &Env,
+    asset: Option<Address>,
+    loss: i128,
+) -> Result<i128, InsuranceError> {
+    let mut pool = get_pool_state(env, asset.clone());
+    if loss <= 0 || loss > pool.total_liquidity {
+        return Err(InsuranceError::InvalidAmount);
+    }
+
+    let mut fund = get_insurance_fund(env, asset.clone());
+    let mut payout = 0;
+    if fund.covered_shares > 0 {
+        let mut covered_value = Shares::new(fund.covered_shares).to_amount(
+            Amount::new(pool.total_liquidity),
+            Shares::new(pool.total_shares),
+        )?;
+        match get_insurance_config(env, asset.clone()) {
+            Some(config) if config.coverage_cap > 0 => {
+                covered_value = covered_value.min(Amount::new(config.coverage_cap));
+            }
+            _ => {}
+        }
+        payout = Amount::new(loss)
+            .mul_div(covered_value.get(), pool.total_liquidity)?
+            .get()
+            .min(fund.balance);
+
+        fund.balance -= payout;
+        fund.payouts = Amount::new(fund.payouts)
+            .checked_add(Amount::new(payout))?
+            .get();
+        fund.payout_index = Amount::new(fund.payout_index)
+            .checked_add(Amount::new(payout).mul_div(LIQUIDITY_INDEX_ONE, fund.covered_shares)?)?
+            .get();
+        set_fund(env, &asset, &fund);
+    }
+
+    pool.total_liquidity -= loss;
+    storage::set(
+        env,
+        &PoolDataKey::Pool(AssetKey::from_option(asset.clone())),
+        &pool,
+    );
+
+    emit_shortfall_reported(
+        env,
+        ShortfallReportedEvent {
+            asset,
+            loss,
+            payout,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(payout)
+}
+
+/// Collect a supplier's shortfall payouts in a market.
+///
+/// # Returns
+/// The amount transferred to the user.
+///
+/// # Errors
+/// * `NotCovered` - The user is not covered
+pub fn claim_coverage(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+) -> Result<i128, InsuranceError> {
+    let _guard = OperationGuard::enter(env)?;
+    user.require_auth();
+
+    checkpoint(env, &user, asset.clone())?;
+    let key = InsuranceDataKey::Coverage(user.clone(), AssetKey::from_option(asset.clone()));
+    let mut coverage: Coverage = storage::get(env, &key).ok_or(InsuranceError::NotCovered)?;
+    let amount = coverage.claimable;
+    if amount > 0 {
+        coverage.claimable = 0;
+        storage::set(env, &key, &coverage);
+        pay_out(env, &user, &asset, amount);
+
+        emit_coverage_claimed(
+            env,
+            CoverageClaimedEvent {
+                user,
+                asset,
+                amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    Ok(amount)
+}
+
+/// Settle a covered supplier's premium and payouts up to now.
+///
+/// Call before the supplier's pool shares change; does nothing for suppliers
+/// without coverage.
+pub(crate) fn checkpoint(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+) -> Result<(), MathError> {
+    let asset_key = AssetKey::from_option(asset.clone());
+    let key = InsuranceDataKey::Coverage(user.clone(), asset_key.clone());
+    let Some(mut coverage) = storage::get::<_, Coverage>(env, &key) else {
+        return Ok(());
+    };
+    let mut fund = get_insurance_fund(env, asset.clone());
+
+    let earned = Shares::new(coverage.shares).mul_div(
+        fund.payout_index - coverage.payout_index,
+        LIQUIDITY_INDEX_ONE,
+    )?;
+    coverage.claimable = Amount::new(coverage.claimable)
+        .checked_add(Amount::new(earned.get()))?
+        .get();
+    coverage.payout_index = fund.payout_index;
+
+    let mut pool = get_pool_state(env, asset.clone());
+    let index = liquidity_index(&pool)?;
+    let premium_bps = get_insurance_config(env, asset.clone()).map_or(0, |c| c.premium_bps);
+    if index > coverage.charged_index && premium_bps > 0 && pool.total_liquidity > 0 {
+        let earnings = Amount::new(coverage.shares)
+            .mul_div(index - coverage.charged_index, LIQUIDITY_INDEX_ONE)?;
+        let premium = Bps::new(premium_bps).apply(earnings)?;
+        // Burn whole shares and charge exactly their value so the index holds
+        let burned = premium.to_shares(
+            Amount::new(pool.total_liquidity),
+            Shares::new(pool.total_shares),
+        )?;
+        if burned.get() > 0 {
+            let charged = burned.to_amount(
+                Amount::new(pool.total_liquidity),
+                Shares::new(pool.total_shares),
+            )?;
+            pool.total_liquidity = Amount::new(pool.total_liquidity)
+                .checked_sub(charged)?
+                .get();
+            pool.total_shares = Shares::new(pool.total_shares).checked_sub(burned)?.get();
+            storage::set(env, &PoolDataKey::Pool(asset_key.clone()), &pool);
+
+            let held =
+                Shares::new(get_supply_shares(env, user, asset.clone())).checked_sub(burned)?;
+            set_supply_shares(env, user, asset_key, held.get());
+            coverage.shares = held.get();
+
+            fund.covered_shares = Shares::new(fund.covered_shares).checked_sub(burned)?.get();
+            fund.balance = Amount::new(fund.balance).checked_add(charged)?.get();
+            fund.premiums_collected = Amount::new(fund.premiums_collected)
+                .checked_add(charged)?
+                .get();
+            set_fund(env, &asset, &fund);
+        }
+    }
+    coverage.charged_index = coverage.charged_index.max(index);
+    storage::set(env, &key, &coverage);
+    Ok(())
+}
+
+/// Match a covered supplier's coverage to their current pool shares.
+///
+/// Call after the supplier's pool shares change, following [`checkpoint`].
+pub(crate) fn sync_coverage(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+) -> Result<(), MathError> {
+    let key = InsuranceDataKey::Coverage(user.clone(), AssetKey::from_option(asset.clone()));
+    let Some(mut coverage) = storage::get::<_, Coverage>(env, &key) else {
+        return Ok(());
+    };
+    let held = get_supply_shares(env, user, asset.clone());
+    let mut fund = get_insurance_fund(env, asset.clone());
+    fund.covered_shares = Shares::new(fund.covered_shares)
+        .checked_sub(Shares::new(coverage.shares))?
+        .checked_add(Shares::new(held))?
+        .get();
+    set_fund(env, &asset, &fund);
+    coverage.shares = held;
+    storage::set(env, &key, &coverage);
+    Ok(())
+}
+
+fn pay_out(env: &Env, user: &Address, asset: &Option<Address>, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    if let Some(asset_addr) = asset {
+        token::Client::new(env, asset_addr).transfer(
+            &env.current_contract_address(),
+            user,
+            &amount,
+        );
+        cash_ledger::record_outflow(env, asset_addr, CashReason::Insurance, amount);
+    }
+}
+
+fn set_fund(env: &Env, asset: &Option<Address>, fund: &InsuranceFund) {
+    storage::set(
+        env,
+        &InsuranceDataKey::Fund(AssetKey::from_option(asset.clone())),
+        fund,
+    );
+}
+
+/// Get a market's coverage terms, if it offers coverage
+pub fn get_insurance_config(env: &Env, asset: Option<Address>) -> Option<InsuranceConfig> {
+    storage::get(env, &InsuranceDataKey::Config(AssetKey::from_option(asset)))
+}
+
+/// Get a market's insurance fund
+pub fn get_insurance_fund(env: &Env, asset: Option<Address>) -> InsuranceFund {
+    storage::get(env, &InsuranceDataKey::Fund(AssetKey::from_option(asset))).unwrap_or_default()
+}
+
+/// Get a supplier's coverage in a market, as of its last checkpoint
+pub fn get_coverage(env: &Env, user: &Address, asset: Option<Address>) -> Option<Coverage> {
+    storage::get(
+        env,
+        &InsuranceDataKey::Coverage(user.clone(), AssetKey::from_option(asset)),
+    )
+}
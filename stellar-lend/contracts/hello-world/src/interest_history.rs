@@ -0,0 +1,162 @@
+//! # Interest History
+//!
+//! Checkpoints of the interest charged on every cross-asset debt, so
+//! borrowers can get the interest for an accounting period from the contract
+//! instead of an indexer.
+//!
+//! Each time interest accrues on a position, a checkpoint records the period
+//! it covers, the principal it was charged on and the borrow rate applied.
+//! Checkpoints are their own persistent entries, indexed per position in
+//! order of accrual, so a period is found by binary search however long the
+//! history grows.
+//!
+//! ## Periods
+//! [`get_interest_paid`] sums the interest of the checkpoints that overlap a
+//! period. Interest is simple over each checkpoint, so one that only partly
+//! overlaps counts in proportion to the overlap. Interest not yet accrued on
+//! the open position is estimated at the market's current borrow rate.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env};
+
+use crate::cross_asset::{get_user_asset_position, AssetKey};
+use crate::interest_rate::calculate_accrued_interest;
+use crate::rate_strategy::get_asset_borrow_rate;
+use crate::storage;
+
+/// Storage keys for interest history
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum InterestHistoryDataKey {
+    /// Number of checkpoints of a position: u32
+    Count(Address, AssetKey),
+    /// One checkpoint of a position, by index from 0: InterestCheckpoint
+    Checkpoint(Address, AssetKey, u32),
+}
+
+/// Errors that can occur while reading interest history
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum InterestHistoryError {
+    /// `from` is not before `to`
+    InvalidPeriod = 1,
+    /// The market's borrow rate could not be calculated
+    RateUnavailable = 2,
+}
+
+/// Interest charged on a position over one accrual period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestCheckpoint {
+    /// Start of the period
+    pub start: u64,
+    /// End of the period, when the interest accrued
+    pub end: u64,
+    /// Principal the interest was charged on
+    pub principal: i128,
+    /// Annual borrow rate applied, in basis points
+    pub rate_bps: i128,
+    /// Interest charged, in the asset's native units
+    pub interest: i128,
+}
+
+/// Append an accrual to a position's interest history
+pub(crate) fn record_interest(
+    env: &Env,
+    user: &Address,
+    asset_key: &AssetKey,
+    checkpoint: InterestCheckpoint,
+) {
+    let count = get_interest_checkpoint_count(env, user, asset_key);
+    storage::set(
+        env,
+        &InterestHistoryDataKey::Checkpoint(user.clone(), asset_key.clone(), count),
+        &checkpoint,
+    );
+    storage::set(
+        env,
+        &InterestHistoryDataKey::Count(user.clone(), asset_key.clone()),
+        &(count + 1),
+    );
+}
+
+fn get_interest_checkpoint_count(env: &Env, user: &Address, asset_key: &AssetKey) -> u32 {
+    storage::get(
+        env,
+        &InterestHistoryDataKey::Count(user.clone(), asset_key.clone()),
+    )
+    .unwrap_or(0)
+}
+
+fn get_interest_checkpoint(
+    env: &Env,
+    user: &Address,
+    asset_key: &AssetKey,
+    index: u32,
+) -> Option<InterestCheckpoint> {
+    storage::get(
+        env,
+        &InterestHistoryDataKey::Checkpoint(user.clone(), asset_key.clone(), index),
+    )
+}
+
+/// Interest charged on a user's debt in one asset between `from` and `to`
+///
+/// Includes interest not yet accrued on the open position, up to `to` or
+/// now, whichever is earlier.
+///
+/// # Errors
+/// * `InvalidPeriod` - `from` is not before `to`
+/// * `RateUnavailable` - The market's borrow rate could not be calculated
+pub fn get_interest_paid(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+    from: u64,
+    to: u64,
+) -> Result<i128, InterestHistoryError> {
+    if from >= to {
+        return Err(InterestHistoryError::InvalidPeriod);
+    }
+    let asset_key = AssetKey::from_option(asset.clone());
+    let count = get_interest_checkpoint_count(env, user, &asset_key);
+
+    // First checkpoint ending after `from`
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match get_interest_checkpoint(env, user, &asset_key, mid) {
+            Some(checkpoint) if checkpoint.end <= from => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+
+    let mut total: i128 = 0;
+    for index in low..count {
+        let Some(checkpoint) = get_interest_checkpoint(env, user, &asset_key, index) else {
+            continue;
+        };
+        if checkpoint.start >= to {
+            break;
+        }
+        let overlap = checkpoint.end.min(to) - checkpoint.start.max(from);
+        let length = checkpoint.end - checkpoint.start;
+        total += checkpoint.interest * overlap as i128 / length as i128;
+    }
+
+    let position = get_user_asset_position(env, user, asset.clone());
+    let end = env.ledger().timestamp().min(to);
+    if position.debt_principal > 0 && end > position.last_updated.max(from) {
+        let rate_bps =
+            get_asset_borrow_rate(env, asset).map_err(|_| InterestHistoryError::RateUnavailable)?;
+        total += calculate_accrued_interest(
+            position.debt_principal,
+            position.last_updated.max(from),
+            end,
+            rate_bps,
+        )
+        .map_err(|_| InterestHistoryError::RateUnavailable)?;
+    }
+    Ok(total)
+}
@@ -9,6 +9,9 @@
 //!
 //! The supply rate is derived as: `supply_rate = borrow_rate - spread`
 //!
+//! This pool-wide model prices the core lending positions. Cross-asset markets
+//! use per-asset strategies instead (see `rate_strategy`).
+//!
 //! ## Configuration (defaults)
 //! - Base rate: 1% APY
 //! - Kink utilization: 80%
@@ -20,12 +23,25 @@
 //! ## Emergency Adjustment
 //! Admin can apply a positive or negative emergency adjustment to the calculated rate,
 //! bounded to ±100%.
+//!
+//! ## Staker Discount
+//! Borrowers whose balance in the configured safety-module staking contract is at
+//! least `min_stake` get `discount_bps` taken off their borrow rate at accrual time,
+//! never below the rate floor. A failing staking lookup simply yields no discount.
+//!
+//! ## Loyalty Discount
+//! The discount of the borrower's loyalty tier (see `loyalty`) is taken off
+//! as well, on top of any staker discount and subject to the same floor.
 
 #![allow(unused)]
-use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal};
+use soroban_sdk::{contracterror, contracttype, vec, Address, Env, IntoVal, Symbol};
 
+use crate::constants::{Bps, BASIS_POINTS_SCALE, SECONDS_PER_YEAR};
 use crate::deposit::{DepositDataKey, ProtocolAnalytics};
+use crate::events::{emit_admin_action, AdminActionEvent};
+use crate::loyalty::get_loyalty_discount;
 use crate::risk_management::get_admin;
+use crate::storage;
 
 /// Errors that can occur during interest rate operations
 #[contracterror]
@@ -57,6 +73,20 @@ pub enum InterestRateDataKey {
     Admin,
     /// Emergency rate adjustment flag
     EmergencyRateAdjustment,
+    /// Borrow-rate discount for safety-module stakers
+    StakerDiscount,
+}
+
+/// Borrow-rate discount granted to safety-module stakers
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StakerDiscountConfig {
+    /// Staking contract queried via `balance(user) -> i128`
+    pub staking_contract: Address,
+    /// Minimum staked balance required for the discount
+    pub min_stake: i128,
+    /// Discount subtracted from the borrow rate (in basis points)
+    pub discount_bps: i128,
 }
 
 /// Interest rate configuration parameters
@@ -89,10 +119,6 @@ pub struct InterestRateConfig {
     pub last_update: u64,
 }
 
-/// Constants for validation
-const BASIS_POINTS_SCALE: i128 = 10_000; // 100% = 10,000 basis points
-const SECONDS_PER_YEAR: u64 = 365 * 86400; // 31,536,000 seconds
-
 /// Default interest rate configuration
 fn get_default_config() -> InterestRateConfig {
     InterestRateConfig {
@@ -111,9 +137,7 @@ fn get_default_config() -> InterestRateConfig {
 /// Get interest rate configuration
 pub fn get_interest_rate_config(env: &Env) -> Option<InterestRateConfig> {
     let config_key = InterestRateDataKey::InterestRateConfig;
-    env.storage()
-        .persistent()
-        .get::<InterestRateDataKey, InterestRateConfig>(&config_key)
+    storage::get_instance::<InterestRateDataKey, InterestRateConfig>(env, &config_key)
 }
 
 /// Initialize interest rate configuration
@@ -121,20 +145,16 @@ pub fn initialize_interest_rate_config(env: &Env, admin: Address) -> Result<(),
     let config_key = InterestRateDataKey::InterestRateConfig;
 
     // Guard against double initialization
-    if env
-        .storage()
-        .persistent()
-        .has::<InterestRateDataKey>(&config_key)
-    {
+    if storage::has_instance::<InterestRateDataKey>(env, &config_key) {
         return Err(InterestRateError::AlreadyInitialized);
     }
 
     let config = get_default_config();
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
 
     // Store admin
     let admin_key = InterestRateDataKey::Admin;
-    env.storage().persistent().set(&admin_key, &admin);
+    storage::set_instance(env, &admin_key, &admin);
 
     Ok(())
 }
@@ -144,10 +164,7 @@ pub fn initialize_interest_rate_config(env: &Env, admin: Address) -> Result<(),
 /// Returns utilization in basis points (0-10000)
 pub fn calculate_utilization(env: &Env) -> Result<i128, InterestRateError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
-    let analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, ProtocolAnalytics>(&analytics_key)
+    let analytics = storage::get::<DepositDataKey, ProtocolAnalytics>(env, &analytics_key)
         .unwrap_or(ProtocolAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -161,13 +178,13 @@ pub fn calculate_utilization(env: &Env) -> Result<i128, InterestRateError> {
     // Calculate utilization: (borrows * 10000) / deposits
     let utilization = analytics
         .total_borrows
-        .checked_mul(BASIS_POINTS_SCALE)
+        .checked_mul(BASIS_POINTS_SCALE.get())
         .ok_or(InterestRateError::Overflow)?
         .checked_div(analytics.total_deposits)
         .ok_or(InterestRateError::DivisionByZero)?;
 
     // Cap at 100%
-    Ok(utilization.min(BASIS_POINTS_SCALE))
+    Ok(utilization.min(BASIS_POINTS_SCALE.get()))
 }
 
 /// Calculate borrow interest rate based on utilization
@@ -205,6 +222,7 @@ pub fn calculate_borrow_rate(env: &Env) -> Result<i128, InterestRateError> {
             .ok_or(InterestRateError::Overflow)?;
 
         let max_utilization_above_kink = BASIS_POINTS_SCALE
+            .get()
             .checked_sub(config.kink_utilization_bps)
             .ok_or(InterestRateError::Overflow)?;
 
@@ -281,7 +299,8 @@ pub fn calculate_accrued_interest(
     // Calculate interest: principal * (rate / 10000) * (time_elapsed / seconds_per_year)
     // To avoid precision loss: principal * rate * time_elapsed / (10000 * seconds_per_year)
     let denominator = BASIS_POINTS_SCALE
-        .checked_mul(SECONDS_PER_YEAR as i128)
+        .get()
+        .checked_mul(SECONDS_PER_YEAR.get() as i128)
         .ok_or(InterestRateError::Overflow)?;
 
     let numerator = principal
@@ -323,10 +342,7 @@ pub fn update_interest_rate_config(
 ) -> Result<(), InterestRateError> {
     // Check authorization
     let admin_key = InterestRateDataKey::Admin;
-    let admin = env
-        .storage()
-        .persistent()
-        .get::<InterestRateDataKey, Address>(&admin_key)
+    let admin = storage::get_instance::<InterestRateDataKey, Address>(env, &admin_key)
         .ok_or(InterestRateError::Unauthorized)?;
 
     if caller != admin {
@@ -338,14 +354,14 @@ pub fn update_interest_rate_config(
 
     // Update parameters with validation
     if let Some(rate) = base_rate_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&rate) {
+        if !Bps::new(rate).is_ratio() {
             return Err(InterestRateError::InvalidParameter);
         }
         config.base_rate_bps = rate;
     }
 
     if let Some(kink) = kink_utilization_bps {
-        if kink <= 0 || kink >= BASIS_POINTS_SCALE {
+        if kink <= 0 || kink >= BASIS_POINTS_SCALE.get() {
             return Err(InterestRateError::InvalidParameter);
         }
         config.kink_utilization_bps = kink;
@@ -366,7 +382,7 @@ pub fn update_interest_rate_config(
     }
 
     if let Some(floor) = rate_floor_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&floor) {
+        if !Bps::new(floor).is_ratio() {
             return Err(InterestRateError::InvalidParameter);
         }
         if floor > config.rate_ceiling_bps {
@@ -376,7 +392,7 @@ pub fn update_interest_rate_config(
     }
 
     if let Some(ceiling) = rate_ceiling_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&ceiling) {
+        if !Bps::new(ceiling).is_ratio() {
             return Err(InterestRateError::InvalidParameter);
         }
         if ceiling < config.rate_floor_bps {
@@ -386,14 +402,23 @@ pub fn update_interest_rate_config(
     }
 
     if let Some(spread) = spread_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&spread) {
+        if !Bps::new(spread).is_ratio() {
             return Err(InterestRateError::InvalidParameter);
         }
         config.spread_bps = spread;
     }
 
     config.last_update = env.ledger().timestamp();
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "update_interest_rate_config"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     Ok(())
 }
@@ -411,10 +436,7 @@ pub fn set_emergency_rate_adjustment(
 ) -> Result<(), InterestRateError> {
     // Check authorization
     let admin_key = InterestRateDataKey::Admin;
-    let admin = env
-        .storage()
-        .persistent()
-        .get::<InterestRateDataKey, Address>(&admin_key)
+    let admin = storage::get_instance::<InterestRateDataKey, Address>(env, &admin_key)
         .ok_or(InterestRateError::Unauthorized)?;
 
     if caller != admin {
@@ -422,7 +444,7 @@ pub fn set_emergency_rate_adjustment(
     }
 
     // Validate adjustment is within reasonable bounds
-    if adjustment_bps.abs() > BASIS_POINTS_SCALE {
+    if adjustment_bps.abs() > BASIS_POINTS_SCALE.get() {
         return Err(InterestRateError::InvalidParameter);
     }
 
@@ -432,11 +454,119 @@ pub fn set_emergency_rate_adjustment(
     config.emergency_adjustment_bps = adjustment_bps;
     config.last_update = env.ledger().timestamp();
 
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_emergency_rate_adjustment"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Configure the staker borrow-rate discount (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `staking_contract` - Contract exposing `balance(user) -> i128`
+/// * `min_stake` - Minimum staked balance to qualify
+/// * `discount_bps` - Discount in basis points; 0 disables the discount
+pub fn set_staker_discount(
+    env: &Env,
+    caller: Address,
+    staking_contract: Address,
+    min_stake: i128,
+    discount_bps: i128,
+) -> Result<(), InterestRateError> {
+    let admin =
+        storage::get_instance::<InterestRateDataKey, Address>(env, &InterestRateDataKey::Admin)
+            .ok_or(InterestRateError::Unauthorized)?;
+
+    if caller != admin {
+        return Err(InterestRateError::Unauthorized);
+    }
+    caller.require_auth();
+
+    if min_stake < 0 || !Bps::new(discount_bps).is_ratio() {
+        return Err(InterestRateError::InvalidParameter);
+    }
+
+    let config = StakerDiscountConfig {
+        staking_contract,
+        min_stake,
+        discount_bps,
+    };
+    storage::set_instance(env, &InterestRateDataKey::StakerDiscount, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_staker_discount"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     Ok(())
 }
 
+/// Get the staker discount configuration, if set
+pub fn get_staker_discount(env: &Env) -> Option<StakerDiscountConfig> {
+    storage::get_instance::<InterestRateDataKey, StakerDiscountConfig>(
+        env,
+        &InterestRateDataKey::StakerDiscount,
+    )
+}
+
+/// Look up a user's staked balance in the safety module
+///
+/// Returns `None` if the staking contract call fails or returns an unexpected type.
+fn get_staked_balance(env: &Env, staking_contract: &Address, user: &Address) -> Option<i128> {
+    match env.try_invoke_contract::<i128, soroban_sdk::Error>(
+        staking_contract,
+        &Symbol::new(env, "balance"),
+        vec![env, user.into_val(env)],
+    ) {
+        Ok(Ok(balance)) => Some(balance),
+        _ => None,
+    }
+}
+
+/// Calculate the borrow rate for a specific user (in basis points)
+///
+/// Applies the user's loyalty tier discount, and the staker discount when the
+/// user's staked balance meets the threshold, to the pool borrow rate. The
+/// result never drops below the configured rate floor.
+pub fn calculate_user_borrow_rate(env: &Env, user: &Address) -> Result<i128, InterestRateError> {
+    let rate = calculate_borrow_rate(env)?;
+
+    let mut discount_bps = get_loyalty_discount(env, user);
+    if let Some(staker) = get_staker_discount(env) {
+        if staker.discount_bps > 0 {
+            match get_staked_balance(env, &staker.staking_contract, user) {
+                Some(balance) if balance >= staker.min_stake => {
+                    discount_bps += staker.discount_bps;
+                }
+                _ => {}
+            }
+        }
+    }
+    if discount_bps == 0 {
+        return Ok(rate);
+    }
+
+    let config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
+    Ok(rate
+        .checked_sub(discount_bps)
+        .ok_or(InterestRateError::Overflow)?
+        .max(config.rate_floor_bps))
+}
+
 /// Get current borrow rate (in basis points)
 pub fn get_current_borrow_rate(env: &Env) -> Result<i128, InterestRateError> {
     calculate_borrow_rate(env)
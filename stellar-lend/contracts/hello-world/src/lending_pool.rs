@@ -0,0 +1,786 @@
+//! # Lending Pool
+//!
+//! Supply-side liquidity that earns the interest paid by borrowers.
+//!
+//! Each cross-asset market has a pool. Suppliers deposit the market's asset
+//! with [`supply`] and receive pool shares; [`redeem`] burns shares for their
+//! current value. A share is worth `total_liquidity / total_shares` of the
+//! asset, exposed as the pool's liquidity index (see [`get_liquidity_index`]).
+//!
+//! ## Interest
+//! When a core borrower repays interest in a market's token (see `repay`),
+//! the market's reserve factor is set aside as protocol reserves and the rest
+//! is added to the pool's liquidity, raising the value of every share. Only
+//! interest received in tokens is credited: cross-asset positions move no
+//! tokens, so interest on cross-asset debt never reaches the pool, which
+//! would otherwise pay suppliers out of other users' deposits. While a pool
+//! has no suppliers, all interest goes to reserves. Each credit emits an
+//! `InterestAccruedEvent` with the interest, reserves, the market's borrow
+//! index and the new liquidity index.
+//!
+//! Flash loan fees are also added to the reserves of the borrowed asset.
+//! The admin withdraws reserves to a treasury with [`withdraw_reserves`], or
+//! all of a market's reserves with [`claim_revenue`]; the cumulative amounts
+//! accrued and claimed are reported in protocol metrics.
+//!
+//! ## Revenue
+//! Each market's income is tracked by source, reserve factor interest and
+//! flash loan fees, together with the amount claimed, in a
+//! [`ProtocolRevenue`] ([`get_protocol_revenue`]). Reserves also move with
+//! rate lock settlements, bad debt write-offs and price reporter incentives
+//! (see [`crate::price_reporter`]), so what remains claimable is the market's
+//! current reserves, not income less claims.
+//!
+//! ## Bad Debt
+//! A liquidation that leaves a borrower with debt but no collateral moves
+//! the remaining debt into the market's bad debt ([`get_bad_debt`]). The
+//! admin writes it off with [`write_off_bad_debt`], either against reserves
+//! or by socializing it across suppliers.
+//!
+//! Pool deposits are separate from collateral: they earn yield but do not
+//! count toward borrowing power. Suppliers can insure their shares against
+//! pool shortfalls (see [`crate::insurance`]); supply and redeem settle that
+//! coverage before changing a covered supplier's shares.
+//!
+//! Suppliers can also fix the yield of some of their shares for a term (see
+//! [`crate::rate_lock`]); locked shares cannot be redeemed until settled.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env};
+
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::Bps;
+use crate::cross_asset::{
+    get_asset_config_by_address, get_borrow_index, is_asset_delisted, register_user, require_role,
+    AssetKey,
+};
+use crate::events::{
+    emit_bad_debt_written_off, emit_interest_accrued, emit_pool_redeem, emit_pool_supply,
+    emit_reserves_withdrawn, BadDebtWrittenOffEvent, InterestAccruedEvent, PoolRedeemEvent,
+    PoolSupplyEvent, ReservesWithdrawnEvent,
+};
+use crate::insurance;
+use crate::math::{Amount, MathError, Shares};
+use crate::rate_lock;
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::rewards;
+use crate::roles::Role;
+use crate::storage;
+
+/// Liquidity index of a pool whose shares are worth exactly one unit (9 decimals)
+pub const LIQUIDITY_INDEX_ONE: i128 = 1_000_000_000;
+
+/// Errors that can occur during lending pool operations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PoolError {
+    /// Amount or share count must be greater than zero
+    InvalidAmount = 1,
+    /// The asset is not a registered cross-asset market
+    AssetNotConfigured = 2,
+    /// User does not hold enough pool shares
+    InsufficientShares = 3,
+    /// The contract does not hold enough of the asset to pay out
+    InsufficientLiquidity = 4,
+    /// Overflow occurred during calculation
+    Overflow = 5,
+    /// Reentrancy detected
+    Reentrancy = 6,
+    /// Caller is not the admin
+    Unauthorized = 7,
+    /// Withdrawal exceeds the market's reserves
+    InsufficientReserves = 8,
+    /// Write-off exceeds the market's bad debt
+    ExceedsBadDebt = 9,
+    /// The market is delisted and takes no new supply
+    AssetDelisted = 10,
+}
+
+/// Who absorbs written-off bad debt
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BadDebtWriteOff {
+    /// Paid from the market's protocol reserves
+    Reserves,
+    /// Taken from pool liquidity, lowering the liquidity index; covered
+    /// suppliers are paid from the insurance fund as for a shortfall
+    Suppliers,
+}
+
+impl From<MathError> for PoolError {
+    fn from(_: MathError) -> Self {
+        PoolError::Overflow
+    }
+}
+
+impl From<Reentered> for PoolError {
+    fn from(_: Reentered) -> Self {
+        PoolError::Reentrancy
+    }
+}
+
+/// Storage keys for lending pool data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum PoolDataKey {
+    /// Pool totals per market: PoolState
+    Pool(AssetKey),
+    /// Pool shares held by a user in a market: i128
+    Shares(Address, AssetKey),
+    /// Reserves accrued across all markets since deployment: i128
+    ReservesAccrued,
+    /// Debt left by liquidations with no collateral to seize: i128
+    BadDebt(AssetKey),
+    /// Income and claims per market: ProtocolRevenue
+    Revenue(AssetKey),
+    /// Reserves withdrawn across all markets since deployment: i128
+    RevenueClaimed,
+}
+
+/// Source of protocol income
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RevenueSource {
+    /// Reserve factor share of borrower interest
+    ReserveInterest,
+    /// Fees paid on flash loans
+    FlashLoanFee,
+}
+
+/// Protocol income and claims of one market since deployment
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolRevenue {
+    /// Reserve factor share of borrower interest
+    pub reserve_interest: i128,
+    /// Fees paid on flash loans
+    pub flash_loan_fees: i128,
+    /// Reserves withdrawn to the treasury
+    pub claimed: i128,
+}
+
+/// Totals of one market's pool
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolState {
+    /// Asset owed to suppliers, including interest earned
+    pub total_liquidity: i128,
+    /// Shares outstanding
+    pub total_shares: i128,
+    /// Interest set aside for the protocol
+    pub reserves: i128,
+}
+
+/// Supply liquidity to a market's pool.
+///
+/// Transfers `amount` of the asset from the user (who must have approved the
+/// contract) and mints shares at the current liquidity index.
+///
+/// # Arguments
+/// * `user` - The supplier (must authorize)
+/// * `asset` - The market's asset (`None` for native XLM)
+/// * `amount` - Amount to supply
+///
+/// # Returns
+/// The number of shares minted.
+///
+/// # Errors
+/// * `InvalidAmount` - Amount is not positive or buys no shares
+/// * `AssetNotConfigured` - The asset is not a registered market
+/// * `AssetDelisted` - The market is delisted
+pub fn supply(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<i128, PoolError> {
+    let _guard = OperationGuard::enter(env)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(PoolError::InvalidAmount);
+    }
+    get_asset_config_by_address(env, asset.clone()).map_err(|_| PoolError::AssetNotConfigured)?;
+    let asset_key = AssetKey::from_option(asset.clone());
+    if is_asset_delisted(env, &asset_key) {
+        return Err(PoolError::AssetDelisted);
+    }
+    insurance::checkpoint(env, &user, asset.clone())?;
+
+    let mut pool = get_pool_state(env, asset.clone());
+    let shares = if pool.total_shares == 0 {
+        Shares::new(amount)
+    } else {
+        Amount::new(amount).to_shares(
+            Amount::new(pool.total_liquidity),
+            Shares::new(pool.total_shares),
+        )?
+    };
+    if shares.get() <= 0 {
+        return Err(PoolError::InvalidAmount);
+    }
+
+    if let Some(ref asset_addr) = asset {
+        soroban_sdk::token::Client::new(env, asset_addr).transfer_from(
+            &env.current_contract_address(),
+            &user,
+            &env.current_contract_address(),
+            &amount,
+        );
+        cash_ledger::record_inflow(env, asset_addr, CashReason::PoolLiquidity, amount);
+    }
+
+    pool.total_liquidity = Amount::new(pool.total_liquidity)
+        .checked_add(Amount::new(amount))?
+        .get();
+    pool.total_shares = Shares::new(pool.total_shares).checked_add(shares)?.get();
+    storage::set(env, &PoolDataKey::Pool(asset_key.clone()), &pool);
+
+    let held = Shares::new(get_supply_shares(env, &user, asset.clone())).checked_add(shares)?;
+    set_supply_shares(env, &user, asset_key, held.get());
+    insurance::sync_coverage(env, &user, asset.clone())?;
+    register_user(env, &user);
+
+    emit_pool_supply(
+        env,
+        PoolSupplyEvent {
+            user,
+            asset,
+            amount,
+            shares: shares.get(),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(shares.get())
+}
+
+/// Redeem pool shares for the underlying asset.
+///
+/// # Arguments
+/// * `user` - The share holder (must authorize)
+/// * `asset` - The market's asset (`None` for native XLM)
+/// * `shares` - Number of shares to burn
+///
+/// # Returns
+/// The amount paid out, including the interest earned by the shares.
+///
+/// # Errors
+/// * `InvalidAmount` - Share count is not positive
+/// * `InsufficientShares` - User holds fewer shares outside a rate lock
+/// * `InsufficientLiquidity` - The contract cannot pay out the amount
+pub fn redeem(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    shares: i128,
+) -> Result<i128, PoolError> {
+    let _guard = OperationGuard::enter(env)?;
+    user.require_auth();
+
+    if shares <= 0 {
+        return Err(PoolError::InvalidAmount);
+    }
+    insurance::checkpoint(env, &user, asset.clone())?;
+    let held = get_supply_shares(env, &user, asset.clone());
+    if shares > held - rate_lock::locked_shares(env, &user, asset.clone()) {
+        return Err(PoolError::InsufficientShares);
+    }
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut pool = get_pool_state(env, asset.clone());
+    let amount = Shares::new(shares).to_amount(
+        Amount::new(pool.total_liquidity),
+        Shares::new(pool.total_shares),
+    )?;
+
+    let token = asset
+        .as_ref()
+        .map(|asset_addr| soroban_sdk::token::Client::new(env, asset_addr));
+    if let Some(ref token) = token {
+        if token.balance(&env.current_contract_address()) < amount.get() {
+            return Err(PoolError::InsufficientLiquidity);
+        }
+    }
+
+    pool.total_liquidity = Amount::new(pool.total_liquidity).checked_sub(amount)?.get();
+    pool.total_shares = Shares::new(pool.total_shares)
+        .checked_sub(Shares::new(shares))?
+        .get();
+    storage::set(env, &PoolDataKey::Pool(asset_key.clone()), &pool);
+    set_supply_shares(env, &user, asset_key, held - shares);
+    insurance::sync_coverage(env, &user, asset.clone())?;
+
+    if let Some(token) = token {
+        token.transfer(&env.current_contract_address(), &user, &amount.get());
+        cash_ledger::record_outflow(env, &token.address, CashReason::PoolLiquidity, amount.get());
+    }
+
+    emit_pool_redeem(
+        env,
+        PoolRedeemEvent {
+            user,
+            asset,
+            amount: amount.get(),
+            shares,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(amount.get())
+}
+
+/// Credit borrower interest to a market's pool.
+///
+/// Splits `interest` into reserves (the market's reserve factor) and supplier
+/// earnings, which are added to the pool's liquidity. `interest` must have
+/// been received in the market's token.
+pub(crate) fn distribute_interest(
+    env: &Env,
+    asset: Option<Address>,
+    interest: i128,
+    reserve_factor: i128,
+) -> Result<(), MathError> {
+    let mut pool = get_pool_state(env, asset.clone());
+    let interest = Amount::new(interest);
+    let reserve = if pool.total_shares == 0 {
+        interest
+    } else {
+        Bps::new(reserve_factor).apply(interest)?
+    };
+
+    pool.reserves = Amount::new(pool.reserves).checked_add(reserve)?.get();
+    pool.total_liquidity = Amount::new(pool.total_liquidity)
+        .checked_add(interest.checked_sub(reserve)?)?
+        .get();
+    storage::set(
+        env,
+        &PoolDataKey::Pool(AssetKey::from_option(asset.clone())),
+        &pool,
+    );
+    record_reserves_accrued(env, reserve)?;
+    record_revenue(
+        env,
+        asset.clone(),
+        RevenueSource::ReserveInterest,
+        reserve.get(),
+    );
+
+    let borrow_index = get_borrow_index(env, asset.clone());
+    emit_interest_accrued(
+        env,
+        InterestAccruedEvent {
+            asset,
+            borrow_interest: interest.get(),
+            reserves: reserve.get(),
+            borrow_index,
+            liquidity_index: liquidity_index(&pool)?,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Move value between a market's reserves and a supplier's shares at the
+/// current liquidity index, as an internal swap with no token transfer.
+///
+/// A positive `amount` is paid from reserves to the supplier as new shares
+/// (reserves permitting); a negative one is taken from the supplier's shares
+/// into reserves.
+///
+/// # Returns
+/// The change in the supplier's shares.
+pub(crate) fn swap_with_reserves(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<i128, PoolError> {
+    insurance::checkpoint(env, user, asset.clone())?;
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut pool = get_pool_state(env, asset.clone());
+    let value = Amount::new(amount.abs());
+    let shares = value.to_shares(
+        Amount::new(pool.total_liquidity),
+        Shares::new(pool.total_shares),
+    )?;
+    let held = Shares::new(get_supply_shares(env, user, asset.clone()));
+
+    let (held, delta) = if amount >= 0 {
+        if value.get() > pool.reserves {
+            return Err(PoolError::InsufficientReserves);
+        }
+        pool.reserves -= value.get();
+        pool.total_liquidity = Amount::new(pool.total_liquidity).checked_add(value)?.get();
+        pool.total_shares = Shares::new(pool.total_shares).checked_add(shares)?.get();
+        (held.checked_add(shares)?, shares.get())
+    } else {
+        if shares > held {
+            return Err(PoolError::InsufficientShares);
+        }
+        pool.reserves = Amount::new(pool.reserves).checked_add(value)?.get();
+        pool.total_liquidity = Amount::new(pool.total_liquidity).checked_sub(value)?.get();
+        pool.total_shares = Shares::new(pool.total_shares).checked_sub(shares)?.get();
+        record_reserves_accrued(env, value)?;
+        (held.checked_sub(shares)?, -shares.get())
+    };
+    storage::set(env, &PoolDataKey::Pool(asset_key.clone()), &pool);
+    set_supply_shares(env, user, asset_key, held.get());
+    insurance::sync_coverage(env, user, asset)?;
+    Ok(delta)
+}
+
+/// Add protocol fees (such as flash loan fees) to a market's reserves
+pub(crate) fn add_reserves(
+    env: &Env,
+    asset: Option<Address>,
+    amount: i128,
+    source: RevenueSource,
+) -> Result<(), MathError> {
+    let mut pool = get_pool_state(env, asset.clone());
+    pool.reserves = Amount::new(pool.reserves)
+        .checked_add(Amount::new(amount))?
+        .get();
+    storage::set(
+        env,
+        &PoolDataKey::Pool(AssetKey::from_option(asset.clone())),
+        &pool,
+    );
+    record_reserves_accrued(env, Amount::new(amount))?;
+    record_revenue(env, asset, source, amount);
+    Ok(())
+}
+
+/// Add income to a market's revenue
+fn record_revenue(env: &Env, asset: Option<Address>, source: RevenueSource, amount: i128) {
+    let key = PoolDataKey::Revenue(AssetKey::from_option(asset));
+    let mut revenue: ProtocolRevenue = storage::get(env, &key).unwrap_or_default();
+    match source {
+        RevenueSource::ReserveInterest => {
+            revenue.reserve_interest = revenue.reserve_interest.saturating_add(amount)
+        }
+        RevenueSource::FlashLoanFee => {
+            revenue.flash_loan_fees = revenue.flash_loan_fees.saturating_add(amount)
+        }
+    }
+    storage::set(env, &key, &revenue);
+}
+
+/// Record reserves withdrawn from a market
+fn record_revenue_claimed(env: &Env, asset: Option<Address>, amount: i128) {
+    let key = PoolDataKey::Revenue(AssetKey::from_option(asset));
+    let mut revenue: ProtocolRevenue = storage::get(env, &key).unwrap_or_default();
+    revenue.claimed = revenue.claimed.saturating_add(amount);
+    storage::set(env, &key, &revenue);
+    storage::set(
+        env,
+        &PoolDataKey::RevenueClaimed,
+        &get_revenue_claimed(env).saturating_add(amount),
+    );
+}
+
+/// Get a market's protocol income by source and the amount claimed
+pub fn get_protocol_revenue(env: &Env, asset: Option<Address>) -> ProtocolRevenue {
+    storage::get(env, &PoolDataKey::Revenue(AssetKey::from_option(asset))).unwrap_or_default()
+}
+
+/// Get the reserves withdrawn across all markets since deployment
+pub fn get_revenue_claimed(env: &Env) -> i128 {
+    storage::get(env, &PoolDataKey::RevenueClaimed).unwrap_or(0)
+}
+
+fn record_reserves_accrued(env: &Env, amount: Amount) -> Result<(), MathError> {
+    let total = Amount::new(get_reserves_accrued(env)).checked_add(amount)?;
+    storage::set(env, &PoolDataKey::ReservesAccrued, &total.get());
+    Ok(())
+}
+
+/// Get the reserves accrued across all markets since deployment, including
+/// reserves already withdrawn
+pub fn get_reserves_accrued(env: &Env) -> i128 {
+    storage::get(env, &PoolDataKey::ReservesAccrued).unwrap_or(0)
+}
+
+/// Get a market's protocol reserves available for withdrawal
+pub fn get_reserves(env: &Env, asset: Option<Address>) -> i128 {
+    get_pool_state(env, asset).reserves
+}
+
+/// Withdraw protocol reserves from a market (admin or `TreasuryAdmin` only).
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `TreasuryAdmin`
+/// * `asset` - The market's asset (`None` for native XLM)
+/// * `to` - Recipient of the reserves, typically the treasury
+/// * `amount` - Amount to withdraw
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor a `TreasuryAdmin`
+/// * `InvalidAmount` - Amount is not positive
+/// * `InsufficientReserves` - Amount exceeds the market's reserves
+/// * `InsufficientLiquidity` - The contract cannot pay out the amount
+pub fn withdraw_reserves(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    to: Address,
+    amount: i128,
+) -> Result<(), PoolError> {
+    let _guard = OperationGuard::enter(env)?;
+    require_role(env, &caller, Role::TreasuryAdmin).map_err(|_| PoolError::Unauthorized)?;
+
+    if amount <= 0 {
+        return Err(PoolError::InvalidAmount);
+    }
+    pay_reserves(env, caller, asset, to, amount)
+}
+
+/// Pay `amount` of a market's reserves to `to`, once the caller is authorized
+fn pay_reserves(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    to: Address,
+    amount: i128,
+) -> Result<(), PoolError> {
+    let mut pool = get_pool_state(env, asset.clone());
+    if amount > pool.reserves {
+        return Err(PoolError::InsufficientReserves);
+    }
+
+    let token = asset
+        .as_ref()
+        .map(|asset_addr| soroban_sdk::token::Client::new(env, asset_addr));
+    if let Some(ref token) = token {
+        if token.balance(&env.current_contract_address()) < amount {
+            return Err(PoolError::InsufficientLiquidity);
+        }
+    }
+
+    pool.reserves -= amount;
+    storage::set(
+        env,
+        &PoolDataKey::Pool(AssetKey::from_option(asset.clone())),
+        &pool,
+    );
+
+    record_revenue_claimed(env, asset.clone(), amount);
+
+    if let Some(token) = token {
+        token.transfer(&env.current_contract_address(), &to, &amount);
+        cash_ledger::record_outflow(env, &token.address, CashReason::ReserveWithdrawal, amount);
+    }
+
+    emit_reserves_withdrawn(
+        env,
+        ReservesWithdrawnEvent {
+            admin: caller,
+            asset,
+            to,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Pay up to `amount` of a market's reserves to `to` as a keeper incentive
+///
+/// Pays nothing, rather than failing, when the reserves or the contract's
+/// balance fall short.
+///
+/// # Returns
+/// The amount paid.
+pub(crate) fn pay_incentive(env: &Env, asset: &Address, to: &Address, amount: i128) -> i128 {
+    let asset_key = AssetKey::from_option(Some(asset.clone()));
+    let mut pool = get_pool_state(env, Some(asset.clone()));
+    let token = soroban_sdk::token::Client::new(env, asset);
+    if amount <= 0
+        || amount > pool.reserves
+        || token.balance(&env.current_contract_address()) < amount
+    {
+        return 0;
+    }
+
+    pool.reserves -= amount;
+    storage::set(env, &PoolDataKey::Pool(asset_key), &pool);
+    token.transfer(&env.current_contract_address(), to, &amount);
+    cash_ledger::record_outflow(env, asset, CashReason::OracleIncentive, amount);
+    amount
+}
+
+/// Claim all of a market's protocol reserves (admin or `TreasuryAdmin` only).
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `TreasuryAdmin`
+/// * `asset` - The market's asset (`None` for native XLM)
+/// * `to` - Recipient of the reserves, typically the treasury
+///
+/// # Returns
+/// The amount claimed.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor a `TreasuryAdmin`
+/// * `InsufficientReserves` - The market has no reserves
+/// * `InsufficientLiquidity` - The contract cannot pay out the reserves
+pub fn claim_revenue(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    to: Address,
+) -> Result<i128, PoolError> {
+    let _guard = OperationGuard::enter(env)?;
+    require_role(env, &caller, Role::TreasuryAdmin).map_err(|_| PoolError::Unauthorized)?;
+
+    let amount = get_reserves(env, asset.clone());
+    if amount <= 0 {
+        return Err(PoolError::InsufficientReserves);
+    }
+    pay_reserves(env, caller, asset, to, amount)?;
+    Ok(amount)
+}
+
+/// Record debt a liquidation could not recover in a market
+pub(crate) fn record_bad_debt(env: &Env, asset: Option<Address>, amount: i128) {
+    let key = PoolDataKey::BadDebt(AssetKey::from_option(asset));
+    let bad_debt: i128 = storage::get(env, &key).unwrap_or(0);
+    storage::set(env, &key, &bad_debt.saturating_add(amount));
+}
+
+/// Get a market's bad debt not yet written off
+pub fn get_bad_debt(env: &Env, asset: Option<Address>) -> i128 {
+    storage::get(env, &PoolDataKey::BadDebt(AssetKey::from_option(asset))).unwrap_or(0)
+}
+
+/// Write off a market's bad debt (admin or `TreasuryAdmin` only).
+///
+/// The loss is taken from the market's reserves or, with
+/// [`BadDebtWriteOff::Suppliers`], socialized across its suppliers.
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or a `TreasuryAdmin`
+/// * `asset` - The market's asset (`None` for native XLM)
+/// * `amount` - Bad debt to write off
+/// * `method` - Who absorbs the loss
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor a `TreasuryAdmin`
+/// * `InvalidAmount` - Amount is not positive, or exceeds pool liquidity
+/// * `ExceedsBadDebt` - Amount exceeds the market's bad debt
+/// * `InsufficientReserves` - Amount exceeds the market's reserves
+pub fn write_off_bad_debt(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    amount: i128,
+    method: BadDebtWriteOff,
+) -> Result<(), PoolError> {
+    let _guard = OperationGuard::enter(env)?;
+    require_role(env, &caller, Role::TreasuryAdmin).map_err(|_| PoolError::Unauthorized)?;
+
+    if amount <= 0 {
+        return Err(PoolError::InvalidAmount);
+    }
+    let bad_debt = get_bad_debt(env, asset.clone());
+    if amount > bad_debt {
+        return Err(PoolError::ExceedsBadDebt);
+    }
+
+    let mut pool = get_pool_state(env, asset.clone());
+    match method {
+        BadDebtWriteOff::Reserves => {
+            if amount > pool.reserves {
+                return Err(PoolError::InsufficientReserves);
+            }
+            pool.reserves -= amount;
+            storage::set(
+                env,
+                &PoolDataKey::Pool(AssetKey::from_option(asset.clone())),
+                &pool,
+            );
+        }
+        BadDebtWriteOff::Suppliers => {
+            if amount > pool.total_liquidity {
+                return Err(PoolError::InvalidAmount);
+            }
+            insurance::apply_shortfall(env, asset.clone(), amount)
+                .map_err(|_| PoolError::Overflow)?;
+        }
+    }
+    storage::set(
+        env,
+        &PoolDataKey::BadDebt(AssetKey::from_option(asset.clone())),
+        &(bad_debt - amount),
+    );
+
+    emit_bad_debt_written_off(
+        env,
+        BadDebtWrittenOffEvent {
+            admin: caller,
+            asset,
+            amount,
+            method,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get a market's pool totals
+pub fn get_pool_state(env: &Env, asset: Option<Address>) -> PoolState {
+    storage::get(env, &PoolDataKey::Pool(AssetKey::from_option(asset))).unwrap_or_default()
+}
+
+/// Get the pool shares a user holds in a market
+pub fn get_supply_shares(env: &Env, user: &Address, asset: Option<Address>) -> i128 {
+    storage::get(
+        env,
+        &PoolDataKey::Shares(user.clone(), AssetKey::from_option(asset)),
+    )
+    .unwrap_or(0)
+}
+
+/// Record the pool shares a user holds in a market, checkpointing the
+/// rewards earned on the previous holding (see [`crate::rewards`])
+pub(crate) fn set_supply_shares(env: &Env, user: &Address, asset_key: AssetKey, shares: i128) {
+    rewards::checkpoint(env, user, &asset_key, shares);
+    storage::set(env, &PoolDataKey::Shares(user.clone(), asset_key), &shares);
+}
+
+/// Get the current value of a user's pool shares in a market
+pub fn get_supply_balance(
+    env: &Env,
+    user: &Address,
+    asset: Option<Address>,
+) -> Result<i128, PoolError> {
+    let shares = get_supply_shares(env, user, asset.clone());
+    if shares == 0 {
+        return Ok(0);
+    }
+    let pool = get_pool_state(env, asset);
+    Ok(Shares::new(shares)
+        .to_amount(
+            Amount::new(pool.total_liquidity),
+            Shares::new(pool.total_shares),
+        )?
+        .get())
+}
+
+/// Get a market's liquidity index: the value of one share, scaled by
+/// [`LIQUIDITY_INDEX_ONE`]. An empty pool has an index of one.
+pub fn get_liquidity_index(env: &Env, asset: Option<Address>) -> Result<i128, PoolError> {
+    Ok(liquidity_index(&get_pool_state(env, asset))?)
+}
+
+/// Liquidity index of the given pool totals
+pub(crate) fn liquidity_index(pool: &PoolState) -> Result<i128, MathError> {
+    if pool.total_shares == 0 {
+        return Ok(LIQUIDITY_INDEX_ONE);
+    }
+    Ok(Amount::new(pool.total_liquidity)
+        .mul_div(LIQUIDITY_INDEX_ONE, pool.total_shares)?
+        .get())
+}
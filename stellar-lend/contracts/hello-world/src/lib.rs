@@ -18,66 +18,258 @@
 //! - Interest accrues continuously based on protocol utilization.
 //! - Only the admin can modify risk parameters, oracle config, and pause switches.
 //! - Emergency pause halts all operations immediately.
+//!
+//! ## Cargo Features
+//! Flash loans, rate locks, supplier insurance, isolated markets and admin
+//! recovery are optional features, all enabled by default. Each feature adds
+//! its own `#[contractimpl]` block at the end of this file; the modules stay
+//! compiled so the core's hooks into them and their storage are unchanged.
 
 #![allow(clippy::too_many_arguments)]
 #![allow(deprecated)]
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, Env, Map, String, Symbol};
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec};
 
 mod borrow;
+mod constants;
 mod deposit;
 mod events;
 mod repay;
 mod risk_management;
+mod storage;
 mod withdraw;
 
-use borrow::borrow_asset;
-use deposit::deposit_collateral;
+use borrow::{borrow_asset, BorrowError};
+use deposit::{deposit_collateral, AssetParams, AssetTotals, DepositError};
 use repay::repay_debt;
 use risk_management::{
-    can_be_liquidated, get_close_factor, get_liquidation_incentive,
-    get_liquidation_incentive_amount, get_liquidation_threshold, get_max_liquidatable_amount,
-    get_min_collateral_ratio, initialize_risk_management, is_emergency_paused, is_operation_paused,
-    require_min_collateral_ratio, set_emergency_pause, set_pause_switch, set_pause_switches,
-    set_risk_params, RiskConfig, RiskManagementError,
+    can_be_liquidated, get_close_factor, get_health_factor_incentive, get_liquidation_incentive,
+    get_liquidation_incentive_amount, get_liquidation_incentive_curve, get_liquidation_target,
+    get_liquidation_threshold, get_max_liquidatable_amount, get_min_collateral_ratio,
+    get_position_liquidation_threshold, get_protocol_health, get_threshold_migration,
+    get_threshold_migration_delay, get_withdraw_cooldown, initialize_risk_management,
+    is_emergency_paused, is_operation_paused, require_min_collateral_ratio, set_emergency_pause,
+    set_liquidation_incentive_curve, set_liquidation_target, set_pause_switch, set_pause_switches,
+    set_recovery_haircut, set_risk_params, set_threshold_migration_delay, set_withdraw_cooldown,
+    LiquidationIncentiveCurve, ProtocolHealth, RiskConfig, RiskManagementError, ThresholdMigration,
 };
+use storage::extend_instance_ttl;
 use withdraw::withdraw_collateral;
 
 mod analytics;
 use analytics::{
-    generate_protocol_report, generate_user_report, get_recent_activity, get_user_activity_feed,
-    AnalyticsError, ProtocolReport, UserReport,
+    generate_protocol_report, generate_user_report, get_activity_decay, get_positions_batch,
+    get_queued_analytics, get_recent_activity, get_risk_badge_config, get_tag_attribution,
+    get_user_activity_feed, hash_user_report, is_analytics_frozen, set_activity_decay,
+    set_analytics_frozen, set_risk_badge_config, ActivityDecayConfig, AnalyticsError,
+    PositionSnapshot, ProtocolReport, QueuedAnalytics, RiskBadgeConfig, TagAttribution, UserReport,
 };
 mod cross_asset;
-#[allow(unused_imports)]
 use cross_asset::{
-    cross_asset_borrow, cross_asset_deposit, cross_asset_repay, cross_asset_withdraw,
-    get_asset_config_by_address, get_asset_list, get_user_asset_position,
-    get_user_position_summary, initialize, initialize_asset, update_asset_config,
-    update_asset_price, AssetConfig, AssetKey, AssetPosition, CrossAssetError, UserPositionSummary,
+    cross_asset_borrow, cross_asset_deposit, cross_asset_liquidate, cross_asset_repay,
+    cross_asset_withdraw, delist_asset, get_asset_config_by_address, get_asset_list,
+    get_asset_metadata, get_asset_pauses, get_borrower_list, get_category_debt_ceiling,
+    get_category_exposure, get_collateral_factor_ramp, get_inactive_markets, get_interest_mode,
+    get_isolation_debt_ceiling, get_market_summary, get_max_borrow_size, get_min_borrow_amount,
+    get_positions_below_health, get_protocol_stress_result, get_reserve_data,
+    get_soft_liquidation_config, get_soft_liquidation_state, get_supply_statement,
+    get_supported_assets, get_swap_adapter, get_tenure_rebates, get_token_capabilities,
+    get_user_asset_position, get_user_position_summary, initialize_asset, is_asset_delisted,
+    is_asset_operation_paused, ramp_collateral_factor, repay_from_supply,
+    repay_with_collateral_asset, set_asset_metadata, set_asset_pause, set_category_debt_ceiling,
+    set_interest_mode, set_isolation_mode, set_max_borrow_size, set_min_borrow_amount,
+    set_soft_liquidation, set_swap_adapter, set_tenure_rebates, set_use_as_collateral,
+    soft_liquidate, stress_test_position, stress_test_protocol, update_asset_config,
+    update_asset_price, AssetCategory, AssetConfig, AssetConfigUpdate, AssetKey, AssetMetadata,
+    AssetPosition, CategoryExposure, CollateralFactorRamp, CrossAssetError, CrossAssetLiquidation,
+    InterestMode, MarketSummary, PositionHealth, ProtocolStressResult, ReserveData,
+    SoftLiquidation, SoftLiquidationConfig, SoftLiquidationState, SupplyStatement, SwapAdapter,
+    SwapRepayment, TenureRebateTier, UserPositionSummary,
 };
 
 mod oracle;
 use oracle::{
-    configure_oracle, get_price, set_fallback_oracle, set_primary_oracle, update_price_feed,
-    OracleConfig,
+    clear_emergency_price, configure_oracle, get_emergency_price, get_oracle_sources, get_price,
+    get_price_detail, set_emergency_price, set_fallback_oracle, set_oracle_sources,
+    set_primary_oracle, update_price_feed, EmergencyPrice, OracleConfig, OracleSources,
+    PriceDetail,
+};
+
+mod price_reporter;
+use price_reporter::{
+    get_price_reporters, get_price_reports, get_reporter_nonce, get_reporting_config,
+    set_price_reporter, set_reporting_config, submit_price, PriceReport, PriceSubmission,
+    ReporterError, ReportingConfig,
 };
 
+#[cfg_attr(not(feature = "flash-loans"), allow(dead_code))]
 mod flash_loan;
+#[cfg(feature = "flash-loans")]
 use flash_loan::{
-    configure_flash_loan, execute_flash_loan, repay_flash_loan, set_flash_loan_fee, FlashLoanConfig,
+    configure_flash_loan, flash_loan, set_flash_loan_fee, FlashLoanConfig, FlashLoanError,
 };
 
 mod liquidate;
 use liquidate::liquidate;
 
+mod borrow_queue;
+use borrow_queue::{
+    cancel_borrow_request, get_borrow_queue, get_utilization_cap, process_borrow_queue,
+    request_borrow, set_utilization_cap, BorrowQueueError, BorrowRequest,
+};
+
+mod admin_simulation;
+use admin_simulation::{
+    simulate_admin_action, AdminAction, AdminActionEffects, AdminSimulationError,
+};
+
+mod credit;
+use credit::{
+    get_credit_grants, get_credit_summary, grant_credit_access, revoke_credit_access, CreditError,
+    CreditSummary,
+};
+
+mod lending_pool;
+use lending_pool::{
+    claim_revenue, get_bad_debt, get_liquidity_index, get_pool_state, get_protocol_revenue,
+    get_reserves, get_supply_balance, get_supply_shares, redeem, supply, withdraw_reserves,
+    write_off_bad_debt, BadDebtWriteOff, PoolError, PoolState, ProtocolRevenue,
+};
+
+#[cfg_attr(not(feature = "insurance"), allow(dead_code))]
+mod insurance;
+#[cfg(feature = "insurance")]
+use insurance::{
+    claim_coverage, fund_insurance, get_coverage, get_insurance_config, get_insurance_fund,
+    opt_in_coverage, opt_out_coverage, report_shortfall, set_insurance_config, Coverage,
+    InsuranceConfig, InsuranceError, InsuranceFund,
+};
+
+#[cfg_attr(not(feature = "isolated-markets"), allow(dead_code))]
+mod market_factory;
+#[cfg(feature = "isolated-markets")]
+use market_factory::{
+    create_isolated_market, get_isolated_market, get_isolated_market_by_name,
+    get_isolated_market_count, get_isolated_markets, get_market_wasm, set_market_wasm,
+    IsolatedMarket, IsolatedMarketParams, MarketFactoryError,
+};
+
+mod ops_metrics;
+use ops_metrics::{get_ops_metrics, record_call, EntrypointMetrics};
+
+mod liquidation_history;
+use liquidation_history::{
+    get_liquidation_count, get_liquidation_history, get_liquidator_stats, LiquidationHistoryError,
+    LiquidationRecord, LiquidatorStats,
+};
+
+mod loyalty;
+use loyalty::{get_loyalty_tiers, get_user_tier, set_loyalty_tiers, LoyaltyError, LoyaltyTier};
+
+mod interest_history;
+use interest_history::{get_interest_paid, InterestHistoryError};
+
+mod sub_account;
+use sub_account::{
+    get_sub_account, get_sub_account_owner, get_sub_account_summary, get_sub_accounts,
+    sub_account_borrow, sub_account_deposit, sub_account_repay, sub_account_withdraw, SubAccount,
+};
+
+mod contact_hint;
+use contact_hint::{
+    clear_contact_hint, get_contact_hint, set_contact_hint, warn_liquidation_risk, ContactHint,
+    ContactHintError, NotificationPreference,
+};
+
+mod rate_mode;
+use rate_mode::{
+    get_rate_mode, get_stable_borrow, get_stable_rate, get_stable_rate_config,
+    rebalance_stable_rate, set_stable_rate_config, swap_rate_mode, RateMode, RateModeError,
+    StableBorrow, StableRateConfig,
+};
+
+#[cfg_attr(not(feature = "rate-locks"), allow(dead_code))]
+mod rate_lock;
+#[cfg(feature = "rate-locks")]
+use rate_lock::{
+    get_locked_value, get_pool_supply_rate, get_rate_lock, get_rate_lock_config,
+    get_rate_lock_quote, lock_supply_rate, set_rate_lock_config, settle_rate_lock, RateLock,
+    RateLockConfig, RateLockError,
+};
+
+mod position_closure;
+use position_closure::{close_position, ClosureError, PositionClosure};
+
+mod position_transfer;
+use position_transfer::{
+    transfer_position, transfer_sub_account_position, PositionTransfer, TransferError,
+};
+
+mod lp_collateral;
+use lp_collateral::{get_lp_pool, list_lp_token, refresh_lp_price, LpPool};
+
+mod batch;
+use batch::{batch, Operation};
+
+mod account_sweep;
+use account_sweep::{
+    get_sweep_idle_days, is_dust_account, set_sweep_idle_days, sweep_dust_accounts,
+    AccountSweepError,
+};
+
+#[cfg_attr(not(feature = "admin-recovery"), allow(dead_code))]
+mod recovery;
+#[cfg(feature = "admin-recovery")]
+use recovery::{
+    approve_recovery, cancel_recovery, execute_recovery, get_guardians, get_recovery,
+    propose_recovery, set_guardians, GuardianConfig, RecoveryError, RecoveryRequest,
+};
+
+#[cfg_attr(not(feature = "rewards"), allow(dead_code))]
+mod rewards;
+#[cfg(feature = "rewards")]
+use rewards::{
+    claim_rewards, fund_rewards, get_emission_rates, get_pending_rewards, get_program_report,
+    get_reward_delegate, get_reward_index, get_reward_token, get_rewards_balance,
+    set_emission_rates, set_reward_delegate, set_reward_token, EmissionRates, ProgramReport,
+    RewardIndex, RewardsError,
+};
+
+mod upgrade;
+use upgrade::UpgradeError;
+
+mod ttl;
+use ttl::{bump_position, get_ttl_config, set_ttl_config, TtlConfig, TtlError};
+
+mod cash_ledger;
+use cash_ledger::{
+    get_cash_ledger, get_ledger_balance, reconcile_cash, CashFlow, CashReason, CashReconciliation,
+};
+
 mod interest_rate;
+mod math;
+mod roles;
+use roles::{grant_role, has_role, revoke_role, Role, RoleError};
+mod query;
+mod rate_comparison;
+mod rate_strategy;
+mod reentrancy;
+mod state_export;
+mod token_interface;
 #[allow(unused_imports)]
 use interest_rate::{
-    get_current_borrow_rate, get_current_supply_rate, get_current_utilization,
-    initialize_interest_rate_config, set_emergency_rate_adjustment, update_interest_rate_config,
-    InterestRateError,
+    calculate_user_borrow_rate, get_current_borrow_rate, get_current_supply_rate,
+    get_current_utilization, get_staker_discount, initialize_interest_rate_config,
+    set_emergency_rate_adjustment, set_staker_discount, update_interest_rate_config,
+    InterestRateError, StakerDiscountConfig,
 };
+use query::{query_batch, Query, QueryError};
+use rate_comparison::{
+    add_peer_pool, compare_rates, get_peer_pools, remove_peer_pool, RateComparison,
+    RateComparisonError,
+};
+use rate_strategy::{get_asset_borrow_rate, get_rate_strategy, set_rate_strategy, RateStrategy};
+use state_export::{export_state, ExportPage, ExportSection, StateExportError};
 
 /// The StellarLend core contract.
 ///
@@ -118,6 +310,8 @@ impl HelloContract {
             }
         })?;
         // initialize_governance(&env, admin).map_err(|_| RiskManagementError::Unauthorized)?;
+        upgrade::initialize_version(&env);
+        extend_instance_ttl(&env);
         Ok(())
     }
 
@@ -146,16 +340,71 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> i128 {
-        deposit_collateral(&env, user, asset, amount)
+        record_call(&env, "deposit_collateral");
+        extend_instance_ttl(&env);
+        deposit_collateral(&env, user, asset, amount, None)
+            .unwrap_or_else(|e| panic!("Deposit error: {:?}", e))
+    }
+
+    /// Deposit collateral on behalf of an integrator
+    ///
+    /// Same as `deposit_collateral`, with `tag` recorded in the activity
+    /// entry and the tag's attribution totals.
+    ///
+    /// # Arguments
+    /// * `user` - The address of the user depositing collateral
+    /// * `asset` - The address of the asset contract to deposit (None for native XLM)
+    /// * `amount` - The amount to deposit
+    /// * `tag` - Integrator tag, e.g. the aggregator or vault's product name
+    pub fn deposit_collateral_tagged(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        tag: Symbol,
+    ) -> i128 {
+        record_call(&env, "deposit_collateral_tagged");
+        extend_instance_ttl(&env);
+        deposit_collateral(&env, user, asset, amount, Some(tag))
             .unwrap_or_else(|e| panic!("Deposit error: {:?}", e))
     }
 
-    /// Set risk parameters (admin only)
+    /// Set an asset's supply and borrow caps (risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
+    /// * `asset` - The asset contract address
+    /// * `supply_cap` - Cap on the total supplied (0 = unlimited)
+    /// * `borrow_cap` - Cap on the total borrowed (0 = unlimited)
+    ///
+    /// # Events
+    /// Emits `asset_caps_updated`
+    pub fn set_asset_caps(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        supply_cap: i128,
+        borrow_cap: i128,
+    ) -> Result<(), DepositError> {
+        deposit::set_asset_caps(&env, caller, asset, supply_cap, borrow_cap)
+    }
+
+    /// Get an asset's legacy deposit parameters, if set (listed assets use `get_asset_config`)
+    pub fn get_asset_params(env: Env, asset: Address) -> Option<AssetParams> {
+        deposit::get_asset_params(&env, &asset)
+    }
+
+    /// Get the amounts supplied and borrowed in an asset
+    pub fn get_asset_totals(env: Env, asset: Address) -> AssetTotals {
+        deposit::get_asset_totals(&env, &asset)
+    }
+
+    /// Set risk parameters (risk admin only)
     ///
     /// Updates risk parameters with validation and change limits.
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin)
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
     /// * `min_collateral_ratio` - Optional new minimum collateral ratio (in basis points)
     /// * `liquidation_threshold` - Optional new liquidation threshold (in basis points)
     /// * `close_factor` - Optional new close factor (in basis points)
@@ -181,10 +430,10 @@ impl HelloContract {
         )
     }
 
-    /// Set pause switch for an operation (admin only)
+    /// Set pause switch for an operation (pause guardian only)
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin)
+    /// * `caller` - The caller address (must hold the PauseGuardian role)
     /// * `operation` - The operation symbol (e.g., "pause_deposit", "pause_borrow")
     /// * `paused` - Whether to pause (true) or unpause (false)
     ///
@@ -199,10 +448,10 @@ impl HelloContract {
         set_pause_switch(&env, caller, operation, paused)
     }
 
-    /// Set multiple pause switches at once (admin only)
+    /// Set multiple pause switches at once (pause guardian only)
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin)
+    /// * `caller` - The caller address (must hold the PauseGuardian role)
     /// * `switches` - Map of operation symbols to pause states
     ///
     /// # Returns
@@ -215,12 +464,12 @@ impl HelloContract {
         set_pause_switches(&env, caller, switches)
     }
 
-    /// Set emergency pause (admin only)
+    /// Set emergency pause (pause guardian only)
     ///
     /// Emergency pause stops all operations immediately.
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin)
+    /// * `caller` - The caller address (must hold the PauseGuardian role)
     /// * `paused` - Whether to enable (true) or disable (false) emergency pause
     ///
     /// # Returns
@@ -233,6 +482,132 @@ impl HelloContract {
         set_emergency_pause(&env, caller, paused)
     }
 
+    /// Set the withdrawal cooldown after a borrow (risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
+    /// * `seconds` - Cooldown in seconds; 0 disables it
+    ///
+    /// # Returns
+    /// Returns Ok(()) on success
+    pub fn set_withdraw_cooldown(
+        env: Env,
+        caller: Address,
+        seconds: u64,
+    ) -> Result<(), RiskManagementError> {
+        set_withdraw_cooldown(&env, caller, seconds)
+    }
+
+    /// Get the withdrawal cooldown in seconds
+    pub fn get_withdraw_cooldown(env: Env) -> u64 {
+        get_withdraw_cooldown(&env)
+    }
+
+    /// Set the delay before a raised liquidation threshold reaches existing positions (risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
+    /// * `seconds` - Delay in seconds; 0 applies increases immediately
+    ///
+    /// # Returns
+    /// Returns Ok(()) on success
+    pub fn set_threshold_migration_delay(
+        env: Env,
+        caller: Address,
+        seconds: u64,
+    ) -> Result<(), RiskManagementError> {
+        set_threshold_migration_delay(&env, caller, seconds)
+    }
+
+    /// Get the liquidation threshold migration delay in seconds
+    pub fn get_threshold_migration_delay(env: Env) -> u64 {
+        get_threshold_migration_delay(&env)
+    }
+
+    /// Get the liquidation threshold increase still being phased in, if any
+    pub fn get_threshold_migration(env: Env) -> Option<ThresholdMigration> {
+        get_threshold_migration(&env)
+    }
+
+    /// Get the liquidation threshold that applies to a user's position
+    pub fn get_position_threshold(env: Env, user: Address) -> Result<i128, RiskManagementError> {
+        get_position_liquidation_threshold(&env, &user)
+    }
+
+    /// Configure the post-pause collateral haircut (risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
+    /// * `haircut_bps` - Haircut applied when the emergency pause is lifted; 0 disables it
+    /// * `window_seconds` - Seconds over which the haircut decays to zero
+    ///
+    /// # Returns
+    /// Returns Ok(()) on success
+    pub fn set_recovery_haircut(
+        env: Env,
+        caller: Address,
+        haircut_bps: i128,
+        window_seconds: u64,
+    ) -> Result<(), RiskManagementError> {
+        set_recovery_haircut(&env, caller, haircut_bps, window_seconds)
+    }
+
+    /// Configure the health-factor dependent liquidation incentive, or
+    /// return to the flat incentive with `None` (risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
+    /// * `curve` - Minimum incentive at a health factor of 1.0, rising
+    ///   linearly to the maximum at `max_bonus_health_factor`
+    pub fn set_liquidation_incentive_curve(
+        env: Env,
+        caller: Address,
+        curve: Option<LiquidationIncentiveCurve>,
+    ) -> Result<(), RiskManagementError> {
+        set_liquidation_incentive_curve(&env, caller, curve)
+    }
+
+    /// Set the health factor liquidations restore positions to; 0 lets
+    /// liquidators always repay up to the close factor (risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must hold the RiskAdmin role)
+    /// * `target` - Target health factor in basis points, above 10000 and at most 20000
+    pub fn set_liquidation_target(
+        env: Env,
+        caller: Address,
+        target: i128,
+    ) -> Result<(), RiskManagementError> {
+        set_liquidation_target(&env, caller, target)
+    }
+
+    /// Get the health factor liquidations restore positions to (0 when disabled)
+    pub fn get_liquidation_target(env: Env) -> i128 {
+        get_liquidation_target(&env)
+    }
+
+    /// Get the liquidation incentive curve, if configured
+    pub fn get_liquidation_incentive_curve(env: Env) -> Option<LiquidationIncentiveCurve> {
+        get_liquidation_incentive_curve(&env)
+    }
+
+    /// Get the liquidation incentive, in basis points, for a position at a
+    /// health factor (in basis points, 10000 = 1.0)
+    pub fn get_health_factor_incentive(
+        env: Env,
+        health_factor: i128,
+    ) -> Result<i128, RiskManagementError> {
+        get_health_factor_incentive(&env, health_factor)
+    }
+
+    /// Get the protocol's pause and recovery state
+    ///
+    /// # Returns
+    /// Emergency pause flag and the collateral haircut currently applied
+    pub fn get_protocol_health(env: Env) -> ProtocolHealth {
+        get_protocol_health(&env)
+    }
+
     /// Get current risk configuration
     ///
     /// # Returns
@@ -342,14 +717,17 @@ impl HelloContract {
     ///
     /// # Arguments
     /// * `liquidated_amount` - Amount being liquidated (in base units)
+    /// * `health_factor` - Health factor of the position (in basis points,
+    ///   10000 = 1.0); only affects the result with an incentive curve
     ///
     /// # Returns
     /// Liquidation incentive amount
     pub fn get_liquidation_incentive_amount(
         env: Env,
         liquidated_amount: i128,
+        health_factor: i128,
     ) -> Result<i128, RiskManagementError> {
-        get_liquidation_incentive_amount(&env, liquidated_amount)
+        get_liquidation_incentive_amount(&env, liquidated_amount, health_factor)
     }
 
     /// Withdraw collateral from the protocol
@@ -379,6 +757,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> i128 {
+        record_call(&env, "withdraw_collateral");
+        extend_instance_ttl(&env);
         withdraw_collateral(&env, user, asset, amount)
             .unwrap_or_else(|e| panic!("Withdraw error: {:?}", e))
     }
@@ -408,6 +788,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> (i128, i128, i128) {
+        record_call(&env, "repay_debt");
+        extend_instance_ttl(&env);
         repay_debt(&env, user, asset, amount).unwrap_or_else(|e| panic!("Repay error: {:?}", e))
     }
 
@@ -433,7 +815,176 @@ impl HelloContract {
     /// - `analytics_updated`: Analytics update event
     /// - `user_activity_tracked`: User activity tracking event
     pub fn borrow_asset(env: Env, user: Address, asset: Option<Address>, amount: i128) -> i128 {
-        borrow_asset(&env, user, asset, amount).unwrap_or_else(|e| panic!("Borrow error: {:?}", e))
+        record_call(&env, "borrow_asset");
+        extend_instance_ttl(&env);
+        borrow_asset(&env, user, asset, amount, None, None)
+            .unwrap_or_else(|e| panic!("Borrow error: {:?}", e))
+    }
+
+    /// Borrow on behalf of an integrator
+    ///
+    /// Same as `borrow_asset`, with `tag` recorded in the activity entry and
+    /// the tag's attribution totals, including whether the borrow opened a loan.
+    ///
+    /// # Arguments
+    /// * `user` - The address of the user borrowing assets
+    /// * `asset` - The address of the asset contract to borrow (None for native XLM)
+    /// * `amount` - The amount to borrow
+    /// * `tag` - Integrator tag, e.g. the aggregator or vault's product name
+    pub fn borrow_asset_tagged(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        tag: Symbol,
+    ) -> i128 {
+        record_call(&env, "borrow_asset_tagged");
+        extend_instance_ttl(&env);
+        borrow_asset(&env, user, asset, amount, Some(tag), None)
+            .unwrap_or_else(|e| panic!("Borrow error: {:?}", e))
+    }
+
+    /// Borrow in a chosen rate mode
+    ///
+    /// Same as `borrow_asset`, with the debt accruing at the variable pool rate
+    /// or a stable locked rate. A position has one mode for all of its debt, so
+    /// while debt is outstanding `rate_mode` must match it (see `swap_rate_mode`).
+    ///
+    /// # Arguments
+    /// * `user` - The address of the user borrowing assets
+    /// * `asset` - The address of the asset contract to borrow (None for native XLM)
+    /// * `amount` - The amount to borrow
+    /// * `rate_mode` - Variable or stable
+    pub fn borrow_asset_with_rate_mode(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        rate_mode: RateMode,
+    ) -> Result<i128, BorrowError> {
+        record_call(&env, "borrow_asset_with_rate_mode");
+        extend_instance_ttl(&env);
+        borrow_asset(&env, user, asset, amount, None, Some(rate_mode))
+    }
+
+    /// Switch the caller's debt between variable and stable rate mode
+    ///
+    /// Interest up to now accrues at the old rate. Returns the new mode.
+    ///
+    /// # Events
+    /// Emits `rate_mode_swapped_event`
+    pub fn swap_rate_mode(env: Env, user: Address) -> Result<RateMode, RateModeError> {
+        record_call(&env, "swap_rate_mode");
+        swap_rate_mode(&env, user)
+    }
+
+    /// Re-lock a stable position that drifted from the market (anyone can call)
+    ///
+    /// Returns the newly locked rate in basis points.
+    ///
+    /// # Events
+    /// Emits `stable_rate_rebalanced_event`
+    pub fn rebalance_stable_rate(env: Env, user: Address) -> Result<i128, RateModeError> {
+        rebalance_stable_rate(&env, user)
+    }
+
+    /// Get the rate mode of a user's debt
+    pub fn get_rate_mode(env: Env, user: Address) -> RateMode {
+        get_rate_mode(&env, &user)
+    }
+
+    /// Get the locked rate of a user's stable-mode debt, if any
+    pub fn get_stable_borrow(env: Env, user: Address) -> Option<StableBorrow> {
+        get_stable_borrow(&env, &user)
+    }
+
+    /// Get the rate a stable borrow would lock now (in basis points)
+    pub fn get_stable_rate(env: Env) -> Result<i128, RateModeError> {
+        get_stable_rate(&env)
+    }
+
+    /// Set the stable premium and rebalance threshold (risk admin only)
+    pub fn set_stable_rate_config(
+        env: Env,
+        caller: Address,
+        config: StableRateConfig,
+    ) -> Result<(), RateModeError> {
+        set_stable_rate_config(&env, caller, config)
+    }
+
+    /// Get the stable premium and rebalance threshold
+    pub fn get_stable_rate_config(env: Env) -> StableRateConfig {
+        get_stable_rate_config(&env)
+    }
+
+    /// Get the deposits, borrows and loans attributed to an integrator tag
+    pub fn get_tag_attribution(env: Env, tag: Symbol) -> TagAttribution {
+        get_tag_attribution(&env, tag)
+    }
+
+    /// Get successful call counts of the user entrypoints, by entrypoint name
+    ///
+    /// Failed calls roll back and are not counted; see `ops_metrics`.
+    pub fn get_ops_metrics(env: Env) -> Map<Symbol, EntrypointMetrics> {
+        get_ops_metrics(&env)
+    }
+
+    /// Store an opaque contact hint, such as the hash of an encrypted contact
+    /// blob, for notification services to find in liquidation warnings
+    ///
+    /// # Arguments
+    /// * `user` - The borrower (must authorize)
+    /// * `hint` - Opaque bytes, at most 128; never store contact details in
+    ///   the clear
+    /// * `preference` - When warnings include the hint
+    pub fn set_contact_hint(
+        env: Env,
+        user: Address,
+        hint: Bytes,
+        preference: NotificationPreference,
+    ) -> Result<(), ContactHintError> {
+        set_contact_hint(&env, user, hint, preference)
+    }
+
+    /// Remove the caller's contact hint
+    pub fn clear_contact_hint(env: Env, user: Address) {
+        clear_contact_hint(&env, user)
+    }
+
+    /// Get a borrower's contact hint, if any
+    pub fn get_contact_hint(env: Env, user: Address) -> Option<ContactHint> {
+        get_contact_hint(&env, &user)
+    }
+
+    /// Emit a liquidation warning for a cross-asset position whose health
+    /// factor is below 1.1; callable by anyone, e.g. keepers after a price
+    /// drop
+    ///
+    /// # Returns
+    /// Whether a warning was emitted
+    ///
+    /// # Events
+    /// Emits `liquidation_warning`
+    pub fn warn_liquidation_risk(env: Env, user: Address) -> Result<bool, CrossAssetError> {
+        warn_liquidation_risk(&env, user)
+    }
+
+    /// Export one page of a section of protocol state, for off-chain archives
+    ///
+    /// # Arguments
+    /// * `section` - Positions, asset parameters, indices or reserves
+    /// * `cursor` - Where the page starts: 0, then the previous page's `next_cursor`
+    /// * `limit` - Entries per page (at most 25)
+    ///
+    /// # Returns
+    /// The entries, in the order documented in `state_export`, and the next cursor
+    pub fn export_state(
+        env: Env,
+        section: ExportSection,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<ExportPage, StateExportError> {
+        export_state(&env, section, cursor, limit)
     }
 
     /// Generate a comprehensive protocol report.
@@ -467,6 +1018,21 @@ impl HelloContract {
         generate_user_report(&env, &user)
     }
 
+    /// Hash a user's report for off-chain attestation.
+    ///
+    /// Returns SHA-256 over the XDR encoding of the report `get_user_report`
+    /// returns in the same ledger. Off-chain services can sign this hash to
+    /// attest to a snapshot of the user's state.
+    ///
+    /// # Arguments
+    /// * `user` - The address of the user to report on
+    ///
+    /// # Errors
+    /// Returns `AnalyticsError::DataNotFound` if the user has no recorded activity.
+    pub fn hash_user_report(env: Env, user: Address) -> Result<BytesN<32>, AnalyticsError> {
+        hash_user_report(&env, &user)
+    }
+
     /// Retrieve recent protocol activity entries.
     ///
     /// Returns a paginated list of the most recent protocol activities in
@@ -506,47 +1072,174 @@ impl HelloContract {
     ) -> Result<soroban_sdk::Vec<analytics::ActivityEntry>, AnalyticsError> {
         get_user_activity_feed(&env, &user, limit, offset)
     }
-    /// Update price feed from oracle
+
+    /// Export full positions for a batch of users.
     ///
-    /// Updates the price for an asset from an oracle source with validation.
+    /// Returns core and cross-asset positions plus health factors for up to
+    /// 50 users per call, so indexers can bootstrap state in few round-trips.
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin or oracle)
-    /// * `asset` - The asset address
-    /// * `price` - The new price
-    /// * `decimals` - Price decimals
-    /// * `oracle` - The oracle address providing this price
+    /// * `users` - The addresses to export
     ///
     /// # Returns
-    /// Returns the updated price
+    /// One `PositionSnapshot` per user, in input order.
     ///
-    /// # Events
-    /// Emits `price_updated` event
-    pub fn update_price_feed(
+    /// # Errors
+    /// Returns `AnalyticsError::InvalidParameter` if more than 50 users are requested.
+    pub fn get_positions_batch(
         env: Env,
-        caller: Address,
-        asset: Address,
-        price: i128,
-        decimals: u32,
-        oracle: Address,
-    ) -> i128 {
-        update_price_feed(&env, caller, asset, price, decimals, oracle)
-            .unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
+        users: soroban_sdk::Vec<Address>,
+    ) -> Result<soroban_sdk::Vec<PositionSnapshot>, AnalyticsError> {
+        get_positions_batch(&env, &users)
     }
 
-    /// Get price for an asset
-    ///
-    /// Retrieves the current price for an asset, using cache or fallback if needed.
+    /// Set how activity scores decay while users are idle (risk admin only).
     ///
     /// # Arguments
-    /// * `asset` - The asset address
+    /// * `caller` - Holder of the risk admin role
+    /// * `half_life` - Seconds for an idle user's score to halve (0 = no decay)
+    /// * `grace_period` - Seconds after the last activity before decay starts
     ///
-    /// # Returns
-    /// Returns the current price
-    pub fn get_price(env: Env, asset: Address) -> i128 {
-        get_price(&env, &asset).unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
-    }
-
+    /// # Errors
+    /// Returns `AnalyticsError::Unauthorized` if the caller lacks the role.
+    ///
+    /// # Events
+    /// Emits `activity_decay_updated`
+    pub fn set_activity_decay(
+        env: Env,
+        caller: Address,
+        half_life: u64,
+        grace_period: u64,
+    ) -> Result<(), AnalyticsError> {
+        set_activity_decay(&env, caller, half_life, grace_period)
+    }
+
+    /// Get the activity score decay parameters (90-day half-life by default)
+    pub fn get_activity_decay(env: Env) -> ActivityDecayConfig {
+        get_activity_decay(&env)
+    }
+
+    /// Set the thresholds at which `UserReport` risk badges are shown (risk
+    /// admin only).
+    ///
+    /// # Arguments
+    /// * `caller` - Holder of the risk admin role
+    /// * `config` - Thresholds in basis points
+    ///
+    /// # Errors
+    /// * `AnalyticsError::Unauthorized` - The caller lacks the role
+    /// * `AnalyticsError::InvalidParameter` - A threshold is out of range
+    ///
+    /// # Events
+    /// Emits `risk_badge_config_updated`
+    pub fn set_risk_badge_config(
+        env: Env,
+        caller: Address,
+        config: RiskBadgeConfig,
+    ) -> Result<(), AnalyticsError> {
+        set_risk_badge_config(&env, caller, config)
+    }
+
+    /// Get the thresholds at which `UserReport` risk badges are shown
+    pub fn get_risk_badge_config(env: Env) -> RiskBadgeConfig {
+        get_risk_badge_config(&env)
+    }
+
+    /// Freeze or unfreeze analytics writes during storage migrations (pause
+    /// guardian only).
+    ///
+    /// While frozen, activity log entries, the transaction counter and tag
+    /// attribution are queued; lending operations are unaffected. Unfreezing
+    /// applies the queue.
+    ///
+    /// # Arguments
+    /// * `caller` - Holder of the pause guardian role
+    /// * `frozen` - Whether to freeze analytics writes
+    ///
+    /// # Errors
+    /// Returns `AnalyticsError::Unauthorized` if the caller lacks the role.
+    ///
+    /// # Events
+    /// Emits `analytics_frozen`
+    pub fn set_analytics_frozen(
+        env: Env,
+        caller: Address,
+        frozen: bool,
+    ) -> Result<(), AnalyticsError> {
+        set_analytics_frozen(&env, caller, frozen)
+    }
+
+    /// Whether analytics writes are frozen
+    pub fn is_analytics_frozen(env: Env) -> bool {
+        is_analytics_frozen(&env)
+    }
+
+    /// Get the analytics writes queued by the current freeze
+    pub fn get_queued_analytics(env: Env) -> QueuedAnalytics {
+        get_queued_analytics(&env)
+    }
+
+    /// Evaluate a batch of read-only views in one call.
+    ///
+    /// Lets front-ends fetch markets, rates and account data for a full
+    /// dashboard with a single simulated invocation.
+    ///
+    /// # Arguments
+    /// * `queries` - Up to 25 views to evaluate
+    ///
+    /// # Returns
+    /// One encoded result per query, in input order. A failing query holds its
+    /// contract error in its slot rather than aborting the batch.
+    ///
+    /// # Errors
+    /// Returns `QueryError::BatchTooLarge` if more than 25 queries are requested.
+    pub fn query_batch(
+        env: Env,
+        queries: soroban_sdk::Vec<Query>,
+    ) -> Result<soroban_sdk::Vec<soroban_sdk::Val>, QueryError> {
+        query_batch(&env, &queries)
+    }
+    /// Update price feed from oracle
+    ///
+    /// Updates the price for an asset from an oracle source with validation.
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must be admin or oracle)
+    /// * `asset` - The asset address
+    /// * `price` - The new price
+    /// * `decimals` - Price decimals
+    /// * `oracle` - The oracle address providing this price
+    ///
+    /// # Returns
+    /// Returns the updated price
+    ///
+    /// # Events
+    /// Emits `price_updated` event
+    pub fn update_price_feed(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        price: i128,
+        decimals: u32,
+        oracle: Address,
+    ) -> i128 {
+        update_price_feed(&env, caller, asset, price, decimals, oracle)
+            .unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
+    }
+
+    /// Get price for an asset
+    ///
+    /// Retrieves the current price for an asset, using cache or fallback if needed.
+    ///
+    /// # Arguments
+    /// * `asset` - The asset address
+    ///
+    /// # Returns
+    /// Returns the current price
+    pub fn get_price(env: Env, asset: Address) -> i128 {
+        get_price(&env, &asset).unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
+    }
+
     /// Set primary oracle for an asset (admin only)
     ///
     /// # Arguments
@@ -583,68 +1276,161 @@ impl HelloContract {
         configure_oracle(&env, caller, config).unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
     }
 
-    /// Execute flash loan
+    /// Set the oracle sources whose fresh prices are aggregated by median
+    /// for an asset
     ///
-    /// Allows users to borrow assets without collateral for a single transaction.
-    /// The loan must be repaid (with fee) within the same transaction.
+    /// Each source pushes its price with `update_price_feed`. An empty list
+    /// removes the sources.
     ///
     /// # Arguments
-    /// * `user` - The address borrowing the flash loan
-    /// * `asset` - The address of the asset contract to borrow
-    /// * `amount` - The amount to borrow
-    /// * `callback` - The callback contract address that will handle repayment
-    ///
-    /// # Returns
-    /// Returns the total amount to repay (principal + fee)
+    /// * `caller` - The admin or an `AssetListingAdmin`
+    /// * `asset` - The asset address
+    /// * `sources` - Up to 5 distinct source addresses
+    /// * `min_fresh` - Fresh source prices needed before the median applies
     ///
     /// # Events
-    /// Emits `flash_loan_initiated` event
-    pub fn execute_flash_loan(
+    /// Emits `oracle_sources_set`
+    pub fn set_oracle_sources(
         env: Env,
-        user: Address,
+        caller: Address,
         asset: Address,
-        amount: i128,
-        callback: Address,
-    ) -> i128 {
-        execute_flash_loan(&env, user, asset, amount, callback)
-            .unwrap_or_else(|e| panic!("Flash loan error: {:?}", e))
+        sources: Vec<Address>,
+        min_fresh: u32,
+    ) {
+        set_oracle_sources(&env, caller, asset, sources, min_fresh)
+            .unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
+    }
+
+    /// Get the oracle sources aggregated by median for an asset
+    pub fn get_oracle_sources(env: Env, asset: Address) -> OracleSources {
+        get_oracle_sources(&env, &asset)
     }
 
-    /// Repay flash loan
+    /// Get an asset's effective price with each oracle source's price and
+    /// freshness
+    pub fn get_price_detail(env: Env, asset: Address) -> PriceDetail {
+        get_price_detail(&env, &asset)
+    }
+
+    /// Set an emergency price override (admin only)
     ///
-    /// Must be called within the same transaction as the flash loan.
-    /// Validates that the full amount (principal + fee) is repaid.
+    /// Bypasses the deviation guard and expires automatically after one hour.
     ///
     /// # Arguments
-    /// * `user` - The address repaying the flash loan
-    /// * `asset` - The address of the asset contract
-    /// * `amount` - The amount being repaid (should equal principal + fee)
+    /// * `caller` - The caller address (must be admin)
+    /// * `asset` - The asset address
+    /// * `price` - The override price
+    ///
+    /// # Returns
+    /// Returns the stored override, including its expiry
     ///
     /// # Events
-    /// Emits `flash_loan_repaid` event
-    pub fn repay_flash_loan(env: Env, user: Address, asset: Address, amount: i128) {
-        repay_flash_loan(&env, user, asset, amount)
-            .unwrap_or_else(|e| panic!("Flash loan error: {:?}", e))
+    /// Emits `emergency_price_set`
+    pub fn set_emergency_price(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        price: i128,
+    ) -> EmergencyPrice {
+        set_emergency_price(&env, caller, asset, price)
+            .unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
     }
 
-    /// Set flash loan fee (admin only)
+    /// Clear an emergency price override before it expires (admin only)
+    ///
+    /// # Events
+    /// Emits `emergency_price_cleared`
+    pub fn clear_emergency_price(env: Env, caller: Address, asset: Address) {
+        clear_emergency_price(&env, caller, asset)
+            .unwrap_or_else(|e| panic!("Oracle error: {:?}", e))
+    }
+
+    /// Get the active emergency price override for an asset, if any
+    pub fn get_emergency_price(env: Env, asset: Address) -> Option<EmergencyPrice> {
+        get_emergency_price(&env, &asset)
+    }
+
+    /// Register a price reporter with its ed25519 public key, or remove it
+    /// with `None`
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin)
-    /// * `fee_bps` - The new fee in basis points
-    pub fn set_flash_loan_fee(env: Env, caller: Address, fee_bps: i128) {
-        set_flash_loan_fee(&env, caller, fee_bps)
-            .unwrap_or_else(|e| panic!("Flash loan error: {:?}", e))
+    /// * `caller` - The admin or an `AssetListingAdmin`
+    /// * `reporter` - The reporter, which receives its incentives
+    /// * `public_key` - The key its submissions are signed with
+    pub fn set_price_reporter(
+        env: Env,
+        caller: Address,
+        reporter: Address,
+        public_key: Option<BytesN<32>>,
+    ) -> Result<(), ReporterError> {
+        set_price_reporter(&env, caller, reporter, public_key)
     }
 
-    /// Configure flash loan parameters (admin only)
+    /// Get the registered price reporters and their public keys
+    pub fn get_price_reporters(env: Env) -> Map<Address, BytesN<32>> {
+        get_price_reporters(&env)
+    }
+
+    /// Let an asset take reporter submissions, or stop it with `None`
     ///
     /// # Arguments
-    /// * `caller` - The caller address (must be admin)
-    /// * `config` - The new flash loan configuration
-    pub fn configure_flash_loan(env: Env, caller: Address, config: FlashLoanConfig) {
-        configure_flash_loan(&env, caller, config)
-            .unwrap_or_else(|e| panic!("Flash loan error: {:?}", e))
+    /// * `caller` - The admin or an `AssetListingAdmin`
+    /// * `asset` - The asset reported on
+    /// * `config` - Rate limit, quorum, price decimals and incentive
+    pub fn set_reporting_config(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        config: Option<ReportingConfig>,
+    ) -> Result<(), ReporterError> {
+        set_reporting_config(&env, caller, asset, config)
+    }
+
+    /// Get how an asset takes reporter submissions, if it does
+    pub fn get_reporting_config(env: Env, asset: Address) -> Option<ReportingConfig> {
+        get_reporting_config(&env, &asset)
+    }
+
+    /// Submit a reporter's signed price (anyone may relay it)
+    ///
+    /// The reporter signs the SHA-256 hash of `(contract, asset, price,
+    /// timestamp, nonce)` in XDR, where `nonce` is
+    /// [`Self::get_reporter_nonce`]. The median of the fresh reports becomes
+    /// the asset's primary feed, and the reporter is paid the asset's
+    /// incentive from reserves.
+    ///
+    /// # Arguments
+    /// * `reporter` - The registered reporter that signed the price
+    /// * `asset` - The asset reported on
+    /// * `price` - The price, in the asset's configured decimals
+    /// * `timestamp` - When the reporter observed the price; rejected if in
+    ///   the future or older than the oracle's staleness threshold
+    /// * `signature` - The reporter's ed25519 signature
+    ///
+    /// # Events
+    /// Emits `price_submitted_event`, and `price_updated_event` when the
+    /// median is published
+    pub fn submit_price(
+        env: Env,
+        reporter: Address,
+        asset: Address,
+        price: i128,
+        timestamp: u64,
+        signature: BytesN<64>,
+    ) -> Result<PriceSubmission, ReporterError> {
+        record_call(&env, "submit_price");
+        extend_instance_ttl(&env);
+        submit_price(&env, reporter, asset, price, timestamp, signature)
+    }
+
+    /// Get each reporter's latest accepted price for an asset
+    pub fn get_price_reports(env: Env, asset: Address) -> Map<Address, PriceReport> {
+        get_price_reports(&env, &asset)
+    }
+
+    /// Get the nonce a reporter's next signed submission must carry
+    pub fn get_reporter_nonce(env: Env, reporter: Address) -> u64 {
+        get_reporter_nonce(&env, &reporter)
     }
 
     /// Liquidate an undercollateralized position
@@ -677,6 +1463,8 @@ impl HelloContract {
         collateral_asset: Option<Address>,
         debt_amount: i128,
     ) -> (i128, i128, i128) {
+        record_call(&env, "liquidate");
+        extend_instance_ttl(&env);
         liquidate(
             &env,
             liquidator,
@@ -688,6 +1476,27 @@ impl HelloContract {
         .unwrap_or_else(|e| panic!("Liquidation error: {:?}", e))
     }
 
+    /// Get up to `limit` (at most 50) liquidation records, core and
+    /// cross-asset, oldest first, skipping the first `offset`
+    pub fn get_liquidation_history(
+        env: Env,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<LiquidationRecord>, LiquidationHistoryError> {
+        get_liquidation_history(&env, limit, offset)
+    }
+
+    /// Get the number of liquidation records
+    pub fn get_liquidation_count(env: Env) -> u32 {
+        get_liquidation_count(&env)
+    }
+
+    /// Get a liquidator's liquidation count and totals repaid and seized, by
+    /// asset
+    pub fn get_liquidator_stats(env: Env, liquidator: Address) -> LiquidatorStats {
+        get_liquidator_stats(&env, &liquidator)
+    }
+
     /// Get current utilization rate
     ///
     /// Returns the current protocol utilization (borrows / deposits) in basis points.
@@ -777,11 +1586,1978 @@ impl HelloContract {
         set_emergency_rate_adjustment(&env, caller, adjustment_bps)
     }
 
+    /// Configure the borrow-rate discount for safety-module stakers (admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must be admin)
+    /// * `staking_contract` - Contract exposing `balance(user) -> i128`
+    /// * `min_stake` - Minimum staked balance to qualify
+    /// * `discount_bps` - Discount in basis points (0 disables)
+    ///
+    /// # Returns
+    /// Returns Ok(()) on success
+    pub fn set_staker_discount(
+        env: Env,
+        caller: Address,
+        staking_contract: Address,
+        min_stake: i128,
+        discount_bps: i128,
+    ) -> Result<(), InterestRateError> {
+        set_staker_discount(&env, caller, staking_contract, min_stake, discount_bps)
+    }
+
+    /// Get the staker discount configuration, if set
+    pub fn get_staker_discount(env: Env) -> Option<StakerDiscountConfig> {
+        get_staker_discount(&env)
+    }
+
+    /// Set the loyalty tier schedule (admin or risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The protocol admin or a risk admin
+    /// * `tiers` - Transaction count and volume thresholds with their borrow
+    ///   rate discounts, lowest first; empty disables discounts
+    pub fn set_loyalty_tiers(
+        env: Env,
+        caller: Address,
+        tiers: Vec<LoyaltyTier>,
+    ) -> Result<(), LoyaltyError> {
+        set_loyalty_tiers(&env, caller, tiers)
+    }
+
+    /// Get the loyalty tier schedule, lowest first
+    pub fn get_loyalty_tiers(env: Env) -> Vec<LoyaltyTier> {
+        get_loyalty_tiers(&env)
+    }
+
+    /// Get a user's loyalty tier, numbered from 1; 0 if none was reached
+    pub fn get_user_tier(env: Env, user: Address) -> u32 {
+        get_user_tier(&env, &user)
+    }
+
+    /// Get the borrow rate applied to a specific user
+    ///
+    /// # Returns
+    /// The pool borrow rate less any staker discount (in basis points)
+    pub fn get_user_borrow_rate(env: Env, user: Address) -> i128 {
+        calculate_user_borrow_rate(&env, &user)
+            .unwrap_or_else(|e| panic!("Interest rate error: {:?}", e))
+    }
+
+    /// Set the two-slope borrow rate strategy of a cross-asset market (admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must be admin)
+    /// * `asset` - The market (`None` for native XLM)
+    /// * `strategy` - Base rate, slope1, slope2 and optimal utilization (basis points)
+    ///
+    /// # Returns
+    /// Returns Ok(()) on success
+    pub fn set_rate_strategy(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        strategy: RateStrategy,
+    ) -> Result<(), InterestRateError> {
+        set_rate_strategy(&env, caller, asset, strategy)
+    }
+
+    /// Get the borrow rate strategy of a cross-asset market, if set
+    pub fn get_rate_strategy(env: Env, asset: Option<Address>) -> Option<RateStrategy> {
+        get_rate_strategy(&env, asset)
+    }
+
+    /// Get the current borrow rate of a cross-asset market
+    ///
+    /// # Returns
+    /// The rate at the market's current utilization (in basis points); zero
+    /// if the market has no strategy
+    pub fn get_asset_borrow_rate(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<i128, InterestRateError> {
+        get_asset_borrow_rate(&env, asset)
+    }
+
+    /// Register a peer lending pool for rate comparison (cross-asset admin only)
+    ///
+    /// The pool must expose `get_asset_borrow_rate`, directly or through an
+    /// adapter contract.
+    pub fn add_peer_pool(env: Env, pool: Address) -> Result<(), RateComparisonError> {
+        add_peer_pool(&env, pool)
+    }
+
+    /// Unregister a peer lending pool (cross-asset admin only)
+    pub fn remove_peer_pool(env: Env, pool: Address) -> Result<(), RateComparisonError> {
+        remove_peer_pool(&env, pool)
+    }
+
+    /// Get the registered peer lending pools
+    pub fn get_peer_pools(env: Env) -> Vec<Address> {
+        get_peer_pools(&env)
+    }
+
+    /// Compare an asset's borrow rate here and at every peer pool
+    ///
+    /// # Returns
+    /// The local rate, each peer's quote, peers that could not be queried and
+    /// the pool with the lowest rate
+    pub fn compare_rates(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<RateComparison, RateComparisonError> {
+        compare_rates(&env, asset)
+    }
+
+    // ============================================================================
+    // Roles
     // ============================================================================
-}
 
-#[cfg(test)]
-mod tests;
+    /// Grant an admin role to an account (protocol admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The protocol admin
+    /// * `role` - The role to grant
+    /// * `account` - The account receiving the role
+    ///
+    /// # Events
+    /// Emits `role_updated`
+    pub fn grant_role(
+        env: Env,
+        caller: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), RoleError> {
+        grant_role(&env, caller, role, account)
+    }
 
-#[cfg(test)]
+    /// Revoke an admin role from an account (protocol admin only)
+    ///
+    /// # Events
+    /// Emits `role_updated`
+    pub fn revoke_role(
+        env: Env,
+        caller: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), RoleError> {
+        revoke_role(&env, caller, role, account)
+    }
+
+    /// Whether an account holds a role; the protocol admin holds every role
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        has_role(&env, role, &account)
+    }
+
+    // ============================================================================
+    // Cross-Asset Lending
+    // ============================================================================
+
+    /// Initialize the cross-asset lending module
+    ///
+    /// # Arguments
+    /// * `admin` - The cross-asset admin address (must authorize)
+    pub fn initialize_ca(env: Env, admin: Address) -> Result<(), CrossAssetError> {
+        cross_asset::initialize(&env, admin)
+    }
+
+    /// Register an asset for cross-asset lending (admin or asset listing admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or an asset listing admin
+    /// * `asset` - The asset to register (`None` for native XLM)
+    /// * `config` - Full asset configuration
+    pub fn initialize_asset(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        config: AssetConfig,
+    ) -> Result<(), CrossAssetError> {
+        initialize_asset(&env, caller, asset, config)
+    }
+
+    /// Update selected fields of an asset configuration (admin or risk admin only)
+    ///
+    /// `None` arguments keep their current values.
+    pub fn update_asset_config(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        collateral_factor: Option<i128>,
+        borrow_factor: Option<i128>,
+        max_supply: Option<i128>,
+        max_borrow: Option<i128>,
+        can_collateralize: Option<bool>,
+        can_borrow: Option<bool>,
+    ) -> Result<(), CrossAssetError> {
+        update_asset_config(
+            &env,
+            caller,
+            AssetConfigUpdate {
+                asset,
+                collateral_factor,
+                borrow_factor,
+                max_supply,
+                max_borrow,
+                can_collateralize,
+                can_borrow,
+            },
+        )
+    }
+
+    /// Move an asset's collateral factor to `target_factor` linearly over
+    /// `days` days (admin or risk admin only)
+    ///
+    /// The effective factor is interpolated whenever it is read; 0 days
+    /// applies the change at once.
+    pub fn ramp_collateral_factor(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        target_factor: i128,
+        days: u32,
+    ) -> Result<(), CrossAssetError> {
+        ramp_collateral_factor(&env, caller, asset, target_factor, days)
+    }
+
+    /// Get an asset's collateral factor ramp, if one is in progress
+    pub fn get_collateral_factor_ramp(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Option<CollateralFactorRamp> {
+        get_collateral_factor_ramp(&env, asset)
+    }
+
+    /// Pause or resume one operation for one asset (admin or pause guardian only)
+    ///
+    /// # Arguments
+    /// * `asset` - Asset to update (`None` for XLM)
+    /// * `operation` - Pause switch name, e.g. `pause_borrow`
+    /// * `paused` - Whether to pause (true) or resume (false)
+    ///
+    /// # Events
+    /// Emits `asset_pause_updated`
+    pub fn set_asset_pause(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        operation: Symbol,
+        paused: bool,
+    ) -> Result<(), CrossAssetError> {
+        set_asset_pause(&env, caller, asset, operation, paused)
+    }
+
+    /// Isolate an asset with a debt ceiling, or lift its isolation with
+    /// `None` (admin or risk admin only)
+    ///
+    /// A position using an isolated asset as collateral cannot use other
+    /// collateral, and its debt value cannot exceed the ceiling.
+    ///
+    /// # Events
+    /// Emits `isolation_mode_updated`
+    pub fn set_isolation_mode(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        debt_ceiling: Option<i128>,
+    ) -> Result<(), CrossAssetError> {
+        set_isolation_mode(&env, caller, asset, debt_ceiling)
+    }
+
+    /// Choose whether a market's borrower interest is simple (accrued
+    /// separately) or compounding (capitalized into principal), for interest
+    /// accrued from now on (admin or risk admin only)
+    ///
+    /// # Events
+    /// Emits `interest_mode_updated`
+    pub fn set_interest_mode(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        mode: InterestMode,
+    ) -> Result<(), CrossAssetError> {
+        set_interest_mode(&env, caller, asset, mode)
+    }
+
+    /// Get where a market's borrower interest accrues
+    pub fn get_interest_mode(env: Env, asset: Option<Address>) -> InterestMode {
+        get_interest_mode(&env, &AssetKey::from_option(asset))
+    }
+
+    /// Set the smallest nonzero debt a position may carry in an asset, in
+    /// both cross-asset and core positions; `0` removes it (admin or risk
+    /// admin only)
+    ///
+    /// # Events
+    /// Emits `min_borrow_amount_updated`
+    pub fn set_min_borrow_amount(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<(), CrossAssetError> {
+        set_min_borrow_amount(&env, caller, asset, amount)
+    }
+
+    /// Get the smallest nonzero debt a position may carry in an asset; `0`
+    /// if there is no minimum
+    pub fn get_min_borrow_amount(env: Env, asset: Option<Address>) -> i128 {
+        get_min_borrow_amount(&env, asset)
+    }
+
+    /// Enable banded soft liquidation for a collateral asset, or disable it
+    /// with `None` (admin or risk admin only)
+    ///
+    /// # Events
+    /// Emits `liquidation_bands_updated`
+    pub fn set_soft_liquidation(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        config: Option<SoftLiquidationConfig>,
+    ) -> Result<(), CrossAssetError> {
+        set_soft_liquidation(&env, caller, asset, config)
+    }
+
+    /// Set the AMM through which debt is repaid with other collateral, or
+    /// remove it with `None` (admin or risk admin only)
+    pub fn set_swap_adapter(
+        env: Env,
+        caller: Address,
+        adapter: Option<SwapAdapter>,
+    ) -> Result<(), CrossAssetError> {
+        set_swap_adapter(&env, caller, adapter)
+    }
+
+    /// Get the AMM through which debt is repaid with other collateral, if set
+    pub fn get_swap_adapter(env: Env) -> Option<SwapAdapter> {
+        get_swap_adapter(&env)
+    }
+
+    /// Get a collateral asset's soft liquidation bands, if enabled
+    pub fn get_soft_liquidation_config(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Option<SoftLiquidationConfig> {
+        get_soft_liquidation_config(&env, asset)
+    }
+
+    /// Tag a listed asset with its issuer and category, or clear its tags
+    /// with `None` (admin or asset listing admin only)
+    ///
+    /// # Events
+    /// Emits `asset_metadata_updated`
+    pub fn set_asset_metadata(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        metadata: Option<AssetMetadata>,
+    ) -> Result<(), CrossAssetError> {
+        set_asset_metadata(&env, caller, asset, metadata)
+    }
+
+    /// Get an asset's issuer and category tags, if tagged
+    pub fn get_asset_metadata(env: Env, asset: Option<Address>) -> Option<AssetMetadata> {
+        get_asset_metadata(&env, asset)
+    }
+
+    /// Cap the debt backed by collateral of a category, in basis points of
+    /// the value supplied across all markets, or lift the cap with `None`
+    /// (admin or risk admin only)
+    pub fn set_category_debt_ceiling(
+        env: Env,
+        caller: Address,
+        category: AssetCategory,
+        ceiling_bps: Option<i128>,
+    ) -> Result<(), CrossAssetError> {
+        set_category_debt_ceiling(&env, caller, category, ceiling_bps)
+    }
+
+    /// Get a collateral category's debt ceiling, if capped
+    pub fn get_category_debt_ceiling(env: Env, category: AssetCategory) -> Option<i128> {
+        get_category_debt_ceiling(&env, category)
+    }
+
+    /// Get supply and borrow value per asset category, at oracle prices
+    pub fn get_category_exposure(
+        env: Env,
+    ) -> Result<Map<AssetCategory, CategoryExposure>, CrossAssetError> {
+        get_category_exposure(&env)
+    }
+
+    /// Get an asset's debt ceiling, if it is isolated
+    pub fn get_isolation_debt_ceiling(env: Env, asset: Option<Address>) -> Option<i128> {
+        get_isolation_debt_ceiling(&env, asset)
+    }
+
+    /// Get the operations paused for an asset
+    pub fn get_asset_pauses(env: Env, asset: Option<Address>) -> Map<Symbol, bool> {
+        get_asset_pauses(&env, asset)
+    }
+
+    /// Whether an operation is blocked for an asset by the emergency pause,
+    /// delisting or the asset's own pause switch
+    pub fn is_asset_operation_paused(env: Env, asset: Option<Address>, operation: Symbol) -> bool {
+        is_asset_operation_paused(&env, asset, operation)
+    }
+
+    /// Update the cross-asset price for an asset (admin or asset listing admin only)
+    pub fn update_asset_price(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        price: i128,
+    ) -> Result<(), CrossAssetError> {
+        update_asset_price(&env, caller, asset, price)
+    }
+
+    /// Get the configuration of a registered asset
+    pub fn get_asset_config(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<AssetConfig, CrossAssetError> {
+        get_asset_config_by_address(&env, asset)
+    }
+
+    /// Get all registered cross-asset keys
+    pub fn get_asset_list(env: Env) -> soroban_sdk::Vec<AssetKey> {
+        get_asset_list(&env)
+    }
+
+    /// Page through the assets open to new deposits and borrows
+    ///
+    /// # Arguments
+    /// * `limit` - Assets per page (at most 50)
+    /// * `offset` - Number of supported assets to skip
+    pub fn get_supported_assets(
+        env: Env,
+        limit: u32,
+        offset: u32,
+    ) -> Result<soroban_sdk::Vec<AssetKey>, CrossAssetError> {
+        get_supported_assets(&env, limit, offset)
+    }
+
+    /// Delist an asset (admin or asset listing admin only)
+    ///
+    /// Freezes new deposits and borrows of the asset; withdrawals,
+    /// repayments and liquidations continue.
+    ///
+    /// # Events
+    /// Emits `asset_delisted`
+    pub fn delist_asset(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+    ) -> Result<(), CrossAssetError> {
+        delist_asset(&env, caller, asset)
+    }
+
+    /// Whether an asset is delisted
+    pub fn is_asset_delisted(env: Env, asset: Option<Address>) -> bool {
+        is_asset_delisted(&env, &AssetKey::from_option(asset))
+    }
+
+    /// Get a user's position in a single asset
+    pub fn get_user_asset_position(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> AssetPosition {
+        get_user_asset_position(&env, &user, asset)
+    }
+
+    /// Get the interest charged on a user's debt in one asset over a period
+    ///
+    /// Computed from the position's interest checkpoints, plus interest not
+    /// yet accrued at the current rate, for accounting statements.
+    ///
+    /// # Arguments
+    /// * `user` - The borrower
+    /// * `asset` - The debt's asset (`None` for native XLM)
+    /// * `from` - Start of the period (ledger timestamp, inclusive)
+    /// * `to` - End of the period (ledger timestamp, exclusive)
+    pub fn get_interest_paid(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        from: u64,
+        to: u64,
+    ) -> Result<i128, InterestHistoryError> {
+        get_interest_paid(&env, &user, asset, from, to)
+    }
+
+    /// Get a user's unified position summary across all assets
+    ///
+    /// # Returns
+    /// Collateral and debt values, health factor and remaining borrow capacity
+    pub fn get_user_position_summary(
+        env: Env,
+        user: Address,
+    ) -> Result<UserPositionSummary, CrossAssetError> {
+        get_user_position_summary(&env, &user)
+    }
+
+    /// Get a user's position summary under hypothetical price shocks
+    ///
+    /// # Arguments
+    /// * `user` - The user address
+    /// * `shocks` - `(asset, shock_bps)` pairs; `-2300` models a 23% price drop
+    ///
+    /// # Returns
+    /// The position summary, including health factor, at the shocked prices
+    pub fn stress_test_position(
+        env: Env,
+        user: Address,
+        shocks: soroban_sdk::Vec<(Option<Address>, i128)>,
+    ) -> Result<UserPositionSummary, CrossAssetError> {
+        stress_test_position(&env, &user, shocks)
+    }
+
+    /// Run one page of a protocol-wide collateral price stress test
+    ///
+    /// Call with `cursor = 0` to start, then with each returned `next_cursor`
+    /// until it is `None`; the completed result is cached per shock level.
+    ///
+    /// # Arguments
+    /// * `shock_bps` - Uniform collateral price shock (e.g. -3000 = 30% drop)
+    /// * `cursor` - Registry index to resume from
+    /// * `limit` - Users to scan in this call (max 50)
+    ///
+    /// # Returns
+    /// Liquidatable debt and projected bad debt accumulated so far
+    pub fn stress_test_protocol(
+        env: Env,
+        shock_bps: i128,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<ProtocolStressResult, CrossAssetError> {
+        stress_test_protocol(&env, shock_bps, cursor, limit)
+    }
+
+    /// Get the cached result of the last completed protocol stress test
+    pub fn get_protocol_stress_result(env: Env, shock_bps: i128) -> Option<ProtocolStressResult> {
+        get_protocol_stress_result(&env, shock_bps)
+    }
+
+    /// Get the number of users in the borrower registry
+    pub fn get_borrower_count(env: Env) -> u32 {
+        get_borrower_list(&env).len()
+    }
+
+    /// Find borrowers below a health factor, for liquidation keepers
+    ///
+    /// Scans one page of the borrower registry; page with `offset += limit`
+    /// until `offset` reaches `get_borrower_count`.
+    ///
+    /// # Arguments
+    /// * `threshold` - Health factor bound (10000 finds liquidatable positions)
+    /// * `limit` - Borrowers scanned per page (at most 50)
+    /// * `offset` - Registry index to start from
+    ///
+    /// # Returns
+    /// The scanned borrowers whose health factor is below `threshold`
+    pub fn get_positions_below_health(
+        env: Env,
+        threshold: i128,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<PositionHealth>, CrossAssetError> {
+        get_positions_below_health(&env, threshold, limit, offset)
+    }
+
+    /// Get the token interface capability bitmap recorded when an asset was listed
+    pub fn get_token_capabilities(env: Env, asset: Option<Address>) -> Option<u32> {
+        get_token_capabilities(&env, asset)
+    }
+
+    /// Get a market's balances and last activity
+    pub fn get_market_summary(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<MarketSummary, CrossAssetError> {
+        get_market_summary(&env, asset)
+    }
+
+    /// Get a market's balances, rates, caps, risk parameters, pause switches
+    /// and reserves in one call
+    pub fn get_reserve_data(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<ReserveData, CrossAssetError> {
+        get_reserve_data(&env, asset)
+    }
+
+    /// List markets idle for at least `threshold_seconds`
+    ///
+    /// # Returns
+    /// A summary per inactive market, including time since its last borrow
+    pub fn get_inactive_markets(
+        env: Env,
+        threshold_seconds: u64,
+    ) -> soroban_sdk::Vec<MarketSummary> {
+        get_inactive_markets(&env, threshold_seconds)
+    }
+
+    /// Set the supply tenure rebate schedule (admin or risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a risk admin
+    /// * `tiers` - Milestones sorted by increasing tenure, each with a reserve-factor rebate
+    pub fn set_tenure_rebates(
+        env: Env,
+        caller: Address,
+        tiers: soroban_sdk::Vec<TenureRebateTier>,
+    ) -> Result<(), CrossAssetError> {
+        set_tenure_rebates(&env, caller, tiers)
+    }
+
+    /// Get the supply tenure rebate schedule
+    pub fn get_tenure_rebates(env: Env) -> soroban_sdk::Vec<TenureRebateTier> {
+        get_tenure_rebates(&env)
+    }
+
+    /// Cap the share of a market's available liquidity borrowable per ledger
+    /// (admin or risk admin only)
+    ///
+    /// Borrows in the same ledger share the allowance; larger amounts must be
+    /// split across ledgers.
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a risk admin
+    /// * `asset` - The market (`None` for native XLM)
+    /// * `cap_bps` - Share of available liquidity in basis points (0 removes the cap)
+    pub fn set_max_borrow_size(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        cap_bps: i128,
+    ) -> Result<(), CrossAssetError> {
+        set_max_borrow_size(&env, caller, asset, cap_bps)
+    }
+
+    /// Get a market's per-ledger borrow cap in basis points, if set
+    pub fn get_max_borrow_size(env: Env, asset: Option<Address>) -> Option<i128> {
+        get_max_borrow_size(&env, asset)
+    }
+
+    /// Get a user's supply statement for one asset
+    ///
+    /// # Returns
+    /// Supplied balance, continuous tenure, and the active reserve-factor rebate
+    pub fn get_supply_statement(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<SupplyStatement, CrossAssetError> {
+        get_supply_statement(&env, &user, asset)
+    }
+
+    /// Deposit collateral into a cross-asset position
+    pub fn ca_deposit_collateral(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_deposit_collateral");
+        cross_asset_deposit(&env, user, asset, amount)
+    }
+
+    /// Withdraw collateral from a cross-asset position
+    pub fn ca_withdraw_collateral(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_withdraw_collateral");
+        cross_asset_withdraw(&env, user, asset, amount)
+    }
+
+    /// Borrow an asset against cross-asset collateral
+    pub fn ca_borrow_asset(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_borrow_asset");
+        cross_asset_borrow(&env, user, asset, amount)
+    }
+
+    /// Repay cross-asset debt
+    pub fn ca_repay_debt(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_repay_debt");
+        cross_asset_repay(&env, user, asset, amount)
+    }
+
+    /// Deposit collateral into one of the user's sub-accounts, opening it if needed
+    ///
+    /// # Arguments
+    /// * `user` - The sub-account's owner (must authorize)
+    /// * `sub_id` - The owner's number for the sub-account
+    /// * `asset` - Asset to deposit (`None` for native XLM)
+    /// * `amount` - Amount to deposit
+    pub fn ca_sub_deposit_collateral(
+        env: Env,
+        user: Address,
+        sub_id: u32,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_sub_deposit_collateral");
+        sub_account_deposit(&env, user, sub_id, asset, amount)
+    }
+
+    /// Withdraw collateral from one of the user's sub-accounts
+    pub fn ca_sub_withdraw_collateral(
+        env: Env,
+        user: Address,
+        sub_id: u32,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_sub_withdraw_collateral");
+        sub_account_withdraw(&env, user, sub_id, asset, amount)
+    }
+
+    /// Borrow against the collateral of one of the user's sub-accounts
+    pub fn ca_sub_borrow_asset(
+        env: Env,
+        user: Address,
+        sub_id: u32,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_sub_borrow_asset");
+        sub_account_borrow(&env, user, sub_id, asset, amount)
+    }
+
+    /// Repay the debt of one of the user's sub-accounts
+    pub fn ca_sub_repay_debt(
+        env: Env,
+        user: Address,
+        sub_id: u32,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "ca_sub_repay_debt");
+        sub_account_repay(&env, user, sub_id, asset, amount)
+    }
+
+    /// Get the address holding the positions of a user's sub-account
+    ///
+    /// Pass it wherever a cross-asset user is expected, e.g. to liquidate or
+    /// query the sub-account.
+    pub fn get_sub_account(env: Env, user: Address, sub_id: u32) -> Address {
+        get_sub_account(&env, &user, sub_id)
+    }
+
+    /// Get the sub-accounts a user has opened, by sub_id
+    pub fn get_sub_accounts(env: Env, user: Address) -> Map<u32, Address> {
+        get_sub_accounts(&env, &user)
+    }
+
+    /// Get the owner and sub_id of a sub-account address, if it is one
+    pub fn get_sub_account_owner(env: Env, account: Address) -> Option<SubAccount> {
+        get_sub_account_owner(&env, &account)
+    }
+
+    /// Get the position summary of one of a user's sub-accounts
+    pub fn get_sub_account_summary(
+        env: Env,
+        user: Address,
+        sub_id: u32,
+    ) -> Result<UserPositionSummary, CrossAssetError> {
+        get_sub_account_summary(&env, &user, sub_id)
+    }
+
+    /// Repay cross-asset debt from the user's supplied balance of the same asset
+    ///
+    /// Nets collateral against debt internally; no tokens move.
+    ///
+    /// # Arguments
+    /// * `user` - The borrower (must authorize)
+    /// * `asset` - The asset to net off (`None` for native XLM)
+    /// * `amount` - Maximum amount to repay
+    pub fn repay_from_supply(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "repay_from_supply");
+        repay_from_supply(&env, user, asset, amount)
+    }
+
+    /// Repay cross-asset debt by swapping collateral of another asset into
+    /// the debt asset through the swap adapter's AMM
+    ///
+    /// # Arguments
+    /// * `user` - The borrower (must authorize)
+    /// * `debt_asset` - The asset to repay (`None` for native XLM)
+    /// * `pay_asset` - The collateral asset to swap (`None` for native XLM)
+    /// * `max_pay_amount` - Maximum collateral to swap
+    ///
+    /// # Events
+    /// Emits `repay` and `collateral_swap_repay`
+    pub fn repay_with_collateral_asset(
+        env: Env,
+        user: Address,
+        debt_asset: Option<Address>,
+        pay_asset: Option<Address>,
+        max_pay_amount: i128,
+    ) -> Result<SwapRepayment, CrossAssetError> {
+        record_call(&env, "repay_with_collateral_asset");
+        repay_with_collateral_asset(&env, user, debt_asset, pay_asset, max_pay_amount)
+    }
+
+    /// Liquidate an unhealthy cross-asset position
+    ///
+    /// The liquidator picks which collateral to seize, or splits the seizure
+    /// across several collateral assets.
+    ///
+    /// # Arguments
+    /// * `liquidator` - The liquidator (must authorize)
+    /// * `borrower` - The borrower being liquidated
+    /// * `debt_asset` - The debt asset to repay (`None` for native XLM)
+    /// * `seizures` - `(collateral_asset, repay_amount)` pairs
+    ///
+    /// # Returns
+    /// Debt repaid, collateral seized per asset, and the resulting health factor
+    pub fn ca_liquidate(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        debt_asset: Option<Address>,
+        seizures: soroban_sdk::Vec<(Option<Address>, i128)>,
+    ) -> Result<CrossAssetLiquidation, CrossAssetError> {
+        record_call(&env, "ca_liquidate");
+        cross_asset_liquidate(&env, liquidator, borrower, debt_asset, seizures)
+    }
+
+    /// Convert a soft-liquidating position's collateral toward its debt
+    /// asset, or back, to match its bands at current prices
+    ///
+    /// The keeper takes the other side at the oracle price, less the fee when
+    /// buying collateral and plus the fee when selling it back, settling with
+    /// its own collateral in the debt asset.
+    ///
+    /// # Arguments
+    /// * `keeper` - The keeper (must authorize)
+    /// * `user` - Owner of the position
+    /// * `collateral_asset` - The soft-liquidating collateral (`None` for native XLM)
+    /// * `debt_asset` - The debt asset to repay (`None` for native XLM)
+    ///
+    /// # Returns
+    /// Collateral converted, debt repaid, bands converted and the resulting
+    /// health factor
+    ///
+    /// # Events
+    /// Emits `soft_liquidation`
+    pub fn soft_liquidate(
+        env: Env,
+        keeper: Address,
+        user: Address,
+        collateral_asset: Option<Address>,
+        debt_asset: Option<Address>,
+    ) -> Result<SoftLiquidation, CrossAssetError> {
+        record_call(&env, "soft_liquidate");
+        soft_liquidate(&env, keeper, user, collateral_asset, debt_asset)
+    }
+
+    /// Get how much of a user's collateral in an asset soft liquidation has
+    /// converted, if any
+    pub fn get_soft_liquidation_state(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Option<SoftLiquidationState> {
+        get_soft_liquidation_state(&env, &user, asset)
+    }
+
+    /// Enable or disable a deposit as collateral (savings mode)
+    ///
+    /// Deposits with collateral disabled earn supply yield but provide no
+    /// borrowing power and cannot be seized to cover debt.
+    ///
+    /// # Arguments
+    /// * `user` - The position owner (must authorize)
+    /// * `asset` - The asset to toggle (`None` for native XLM)
+    /// * `enabled` - `false` to move the deposit into savings mode
+    ///
+    /// # Events
+    /// Emits `collateral_toggled`
+    pub fn set_use_as_collateral(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        enabled: bool,
+    ) -> Result<AssetPosition, CrossAssetError> {
+        record_call(&env, "set_use_as_collateral");
+        set_use_as_collateral(&env, user, asset, enabled)
+    }
+
+    // ============================================================================
+    // Lending Pool
+    // ============================================================================
+
+    /// Supply liquidity to a market's lending pool
+    ///
+    /// The user must have approved the contract to transfer `amount`.
+    ///
+    /// # Arguments
+    /// * `user` - The supplier (must authorize)
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `amount` - Amount to supply
+    ///
+    /// # Returns
+    /// The number of pool shares minted
+    ///
+    /// # Events
+    /// Emits `pool_supply`
+    pub fn supply(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<i128, PoolError> {
+        record_call(&env, "supply");
+        supply(&env, user, asset, amount)
+    }
+
+    /// Redeem lending pool shares for the underlying asset plus earned interest
+    ///
+    /// # Arguments
+    /// * `user` - The share holder (must authorize)
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `shares` - Number of shares to burn
+    ///
+    /// # Returns
+    /// The amount paid out
+    ///
+    /// # Events
+    /// Emits `pool_redeem`
+    pub fn redeem(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        shares: i128,
+    ) -> Result<i128, PoolError> {
+        record_call(&env, "redeem");
+        redeem(&env, user, asset, shares)
+    }
+
+    /// Get a market's lending pool totals (liquidity, shares, reserves)
+    pub fn get_pool_state(env: Env, asset: Option<Address>) -> PoolState {
+        get_pool_state(&env, asset)
+    }
+
+    /// Get a market's protocol reserves available for withdrawal
+    pub fn get_reserves(env: Env, asset: Option<Address>) -> i128 {
+        get_reserves(&env, asset)
+    }
+
+    /// Withdraw protocol reserves from a market (admin or treasury admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a treasury admin
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `to` - Recipient of the reserves
+    /// * `amount` - Amount to withdraw
+    ///
+    /// # Events
+    /// Emits `reserves_withdrawn`
+    pub fn withdraw_reserves(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), PoolError> {
+        withdraw_reserves(&env, caller, asset, to, amount)
+    }
+
+    /// Claim all of a market's protocol reserves (admin or treasury admin
+    /// only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a treasury admin
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `to` - Recipient of the reserves
+    ///
+    /// # Returns
+    /// The amount claimed
+    ///
+    /// # Events
+    /// Emits `reserves_withdrawn`
+    pub fn claim_revenue(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        to: Address,
+    ) -> Result<i128, PoolError> {
+        claim_revenue(&env, caller, asset, to)
+    }
+
+    /// Get a market's protocol income by source and the amount claimed
+    pub fn get_protocol_revenue(env: Env, asset: Option<Address>) -> ProtocolRevenue {
+        get_protocol_revenue(&env, asset)
+    }
+
+    /// Get a market's bad debt not yet written off
+    pub fn get_bad_debt(env: Env, asset: Option<Address>) -> i128 {
+        get_bad_debt(&env, asset)
+    }
+
+    /// Write off a market's bad debt against its reserves or across its
+    /// suppliers (admin or treasury admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a treasury admin
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `amount` - Bad debt to write off
+    /// * `method` - Who absorbs the loss
+    ///
+    /// # Events
+    /// Emits `bad_debt_written_off`, and `shortfall_reported` when suppliers
+    /// absorb the loss
+    pub fn write_off_bad_debt(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        amount: i128,
+        method: BadDebtWriteOff,
+    ) -> Result<(), PoolError> {
+        write_off_bad_debt(&env, caller, asset, amount, method)
+    }
+
+    /// Get the pool shares a user holds in a market
+    pub fn get_supply_shares(env: Env, user: Address, asset: Option<Address>) -> i128 {
+        get_supply_shares(&env, &user, asset)
+    }
+
+    /// Get the current value of a user's pool shares in a market
+    pub fn get_supply_balance(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, PoolError> {
+        get_supply_balance(&env, &user, asset)
+    }
+
+    /// Get a market's liquidity index (value of one share, 9 decimals)
+    pub fn get_liquidity_index(env: Env, asset: Option<Address>) -> Result<i128, PoolError> {
+        get_liquidity_index(&env, asset)
+    }
+
+    /// Get a market's borrow index (growth of one unit of debt, 9 decimals)
+    pub fn get_borrow_index(env: Env, asset: Option<Address>) -> i128 {
+        cross_asset::get_borrow_index(&env, asset)
+    }
+
+    // ============================================================================
+    // Borrow Queue
+    // ============================================================================
+
+    /// Cap a cross-asset market's utilization (cross-asset admin only)
+    ///
+    /// Borrows that would push utilization above the cap fail with
+    /// `UtilizationCapExceeded` and can be queued with `ca_request_borrow`.
+    ///
+    /// # Arguments
+    /// * `asset` - The market (`None` for native XLM)
+    /// * `cap_bps` - Highest utilization in basis points, in (0, 10000]
+    pub fn set_utilization_cap(
+        env: Env,
+        asset: Option<Address>,
+        cap_bps: i128,
+    ) -> Result<(), BorrowQueueError> {
+        set_utilization_cap(&env, asset, cap_bps)
+    }
+
+    /// Get a market's utilization cap, if set
+    pub fn get_utilization_cap(env: Env, asset: Option<Address>) -> Option<i128> {
+        get_utilization_cap(&env, &AssetKey::from_option(asset))
+    }
+
+    /// Queue a cross-asset borrow until the market has room under its cap
+    ///
+    /// The borrow executes automatically when liquidity frees up, if the
+    /// position is still healthy and the request has not expired.
+    ///
+    /// # Arguments
+    /// * `user` - The borrower (must authorize)
+    /// * `asset` - The market (`None` for native XLM)
+    /// * `amount` - Amount to borrow
+    /// * `ttl_seconds` - How long the request stays queued (at most 7 days)
+    ///
+    /// # Returns
+    /// The request id
+    ///
+    /// # Events
+    /// Emits `borrow_requested`
+    pub fn ca_request_borrow(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        ttl_seconds: u64,
+    ) -> Result<u64, BorrowQueueError> {
+        record_call(&env, "ca_request_borrow");
+        request_borrow(&env, user, asset, amount, ttl_seconds)
+    }
+
+    /// Cancel a queued cross-asset borrow
+    ///
+    /// # Events
+    /// Emits `borrow_request_closed` with reason `cancelled`
+    pub fn ca_cancel_borrow_request(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        id: u64,
+    ) -> Result<(), BorrowQueueError> {
+        record_call(&env, "ca_cancel_borrow_request");
+        cancel_borrow_request(&env, user, asset, id)
+    }
+
+    /// Fill queued borrows of a market that now fit under its cap
+    ///
+    /// Runs automatically after deposits, repayments and liquidations; anyone
+    /// may also call it.
+    ///
+    /// # Returns
+    /// The number of requests filled
+    ///
+    /// # Events
+    /// Emits `borrow_request_filled` per executed request and
+    /// `borrow_request_closed` per dropped request
+    pub fn ca_process_borrow_queue(env: Env, asset: Option<Address>) -> u32 {
+        process_borrow_queue(&env, asset)
+    }
+
+    /// Get the queued borrows of a market, oldest first
+    pub fn get_borrow_queue(env: Env, asset: Option<Address>) -> Vec<BorrowRequest> {
+        get_borrow_queue(&env, asset)
+    }
+
+    // ============================================================================
+    // Credit History
+    // ============================================================================
+
+    /// Get a user's repayment record
+    ///
+    /// # Arguments
+    /// * `caller` - The user or an address they granted access (must authorize)
+    /// * `user` - Whose record to read
+    ///
+    /// # Returns
+    /// Loans opened and repaid, on-time repayments, liquidations and streaks
+    pub fn get_credit_summary(
+        env: Env,
+        caller: Address,
+        user: Address,
+    ) -> Result<CreditSummary, CreditError> {
+        get_credit_summary(&env, caller, user)
+    }
+
+    /// Let `grantee` read the user's credit summary
+    ///
+    /// # Events
+    /// Emits `credit_access_granted`
+    pub fn grant_credit_access(
+        env: Env,
+        user: Address,
+        grantee: Address,
+    ) -> Result<(), CreditError> {
+        grant_credit_access(&env, user, grantee)
+    }
+
+    /// Withdraw `grantee`'s access to the user's credit summary
+    ///
+    /// # Events
+    /// Emits `credit_access_revoked`
+    pub fn revoke_credit_access(
+        env: Env,
+        user: Address,
+        grantee: Address,
+    ) -> Result<(), CreditError> {
+        revoke_credit_access(&env, user, grantee)
+    }
+
+    /// Get the addresses allowed to read a user's credit summary
+    pub fn get_credit_grants(env: Env, user: Address) -> Vec<Address> {
+        get_credit_grants(&env, &user)
+    }
+
+    /// Dry-run an admin change without applying it
+    ///
+    /// Validates a risk parameter change, asset listing, asset config update
+    /// or rebate schedule like the real entrypoint would.
+    ///
+    /// # Returns
+    /// Positions scanned and how many would become liquidatable, with their debt
+    pub fn simulate_admin_action(
+        env: Env,
+        action: AdminAction,
+    ) -> Result<AdminActionEffects, AdminSimulationError> {
+        simulate_admin_action(&env, action)
+    }
+
+    // ============================================================================
+    // Cash Ledger
+    // ============================================================================
+
+    /// Compare an asset's recorded token movements with the contract's balance
+    ///
+    /// # Returns
+    /// The ledger balance, the actual token balance and their difference
+    ///
+    /// # Events
+    /// Emits `cash_reconciled`
+    pub fn reconcile_cash(env: Env, asset: Address) -> CashReconciliation {
+        reconcile_cash(&env, asset)
+    }
+
+    /// Get an asset's recorded inflows and outflows by reason
+    pub fn get_cash_ledger(env: Env, asset: Address) -> Map<CashReason, CashFlow> {
+        get_cash_ledger(&env, &asset)
+    }
+
+    /// Balance of an asset implied by its recorded movements
+    pub fn get_ledger_balance(env: Env, asset: Address) -> i128 {
+        get_ledger_balance(&env, &asset)
+    }
+
+    // ============================================================================
+    // Account Sweep
+    // ============================================================================
+
+    /// Prune the storage of abandoned empty accounts (keeper, anyone can call)
+    ///
+    /// # Arguments
+    /// * `users` - Candidate accounts, at most 25; accounts that are not dust are skipped
+    ///
+    /// # Returns
+    /// The number of accounts pruned
+    ///
+    /// # Events
+    /// Emits `account_pruned` per pruned account
+    pub fn sweep_dust_accounts(env: Env, users: Vec<Address>) -> Result<u32, AccountSweepError> {
+        sweep_dust_accounts(&env, users)
+    }
+
+    /// Whether an account is empty and idle long enough to be pruned
+    pub fn is_dust_account(env: Env, user: Address) -> bool {
+        is_dust_account(&env, &user)
+    }
+
+    /// Set the days without activity before an empty account may be pruned (risk admin only)
+    pub fn set_sweep_idle_days(
+        env: Env,
+        caller: Address,
+        days: u32,
+    ) -> Result<(), AccountSweepError> {
+        set_sweep_idle_days(&env, caller, days)
+    }
+
+    /// Days without activity before an empty account may be pruned
+    pub fn get_sweep_idle_days(env: Env) -> u32 {
+        get_sweep_idle_days(&env)
+    }
+
+    // ============================================================================
+    // Position Closure
+    // ============================================================================
+
+    /// Repay all debt, withdraw all collateral and prune the account in one call
+    ///
+    /// # Arguments
+    /// * `user` - The account to close (must authorize)
+    /// * `asset_preferences` - Tokens to repay and withdraw core balances in,
+    ///   most preferred first; the user must approve the repayment pulls
+    ///
+    /// # Returns
+    /// What was repaid and withdrawn, and whether the account was pruned
+    ///
+    /// # Events
+    /// Emits the events of each repay and withdraw, then `position_closed_event`
+    pub fn close_position(
+        env: Env,
+        user: Address,
+        asset_preferences: Vec<Address>,
+    ) -> Result<PositionClosure, ClosureError> {
+        record_call(&env, "close_position");
+        extend_instance_ttl(&env);
+        close_position(&env, user, asset_preferences)
+    }
+
+    // ============================================================================
+    // Position Transfer
+    // ============================================================================
+
+    /// Move all core and cross-asset collateral and debt to an address that
+    /// holds none
+    ///
+    /// # Arguments
+    /// * `from` - The current holder (must authorize; a sub-account's owner
+    ///   authorizes for it)
+    /// * `to` - The new holder, which takes on the debt (must authorize)
+    ///
+    /// # Returns
+    /// The core balances and cross-asset positions moved
+    ///
+    /// # Events
+    /// Emits `position_transferred_event`
+    pub fn transfer_position(
+        env: Env,
+        from: Address,
+        to: Address,
+    ) -> Result<PositionTransfer, TransferError> {
+        record_call(&env, "transfer_position");
+        extend_instance_ttl(&env);
+        transfer_position(&env, from, to)
+    }
+
+    /// Move all collateral and debt of one of the user's sub-accounts to an
+    /// address that holds none
+    ///
+    /// # Arguments
+    /// * `user` - The sub-account's owner (must authorize)
+    /// * `sub_id` - The owner's number for the sub-account
+    /// * `to` - The new holder, which takes on the debt (must authorize)
+    pub fn ca_sub_transfer_position(
+        env: Env,
+        user: Address,
+        sub_id: u32,
+        to: Address,
+    ) -> Result<PositionTransfer, TransferError> {
+        record_call(&env, "ca_sub_transfer_position");
+        extend_instance_ttl(&env);
+        transfer_sub_account_position(&env, user, sub_id, to)
+    }
+
+    // ============================================================================
+    // LP Share Collateral
+    // ============================================================================
+
+    /// List an AMM pool share token as collateral, priced at the pool's fair
+    /// value per share
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or an `AssetListingAdmin`
+    /// * `lp_token` - The share token
+    /// * `pool` - The pool, which must expose `get_reserves` and
+    ///   `total_shares`, and its two listed underlying assets
+    ///
+    /// # Returns
+    /// The share token's configuration: collateral only, with the lower
+    /// underlying collateral factor less a 20% haircut
+    pub fn list_lp_token(
+        env: Env,
+        caller: Address,
+        lp_token: Address,
+        pool: LpPool,
+    ) -> Result<AssetConfig, CrossAssetError> {
+        record_call(&env, "list_lp_token");
+        extend_instance_ttl(&env);
+        list_lp_token(&env, caller, lp_token, pool)
+    }
+
+    /// Reprice a listed share token from its pool's reserves and the
+    /// underlying prices (anyone may call)
+    ///
+    /// # Events
+    /// Emits `lp_price_refreshed_event`
+    pub fn refresh_lp_price(env: Env, lp_token: Address) -> Result<i128, CrossAssetError> {
+        record_call(&env, "refresh_lp_price");
+        extend_instance_ttl(&env);
+        refresh_lp_price(&env, lp_token)
+    }
+
+    /// Get the pool behind a share token, if it is listed as one
+    pub fn get_lp_pool(env: Env, lp_token: Address) -> Option<LpPool> {
+        get_lp_pool(&env, &lp_token)
+    }
+
+    // ============================================================================
+    // Batch Operations
+    // ============================================================================
+
+    /// Execute cross-asset deposits, borrows, repayments and withdrawals in
+    /// order, checking the health factor only after the last one
+    ///
+    /// # Arguments
+    /// * `user` - The user the operations run for (must authorize)
+    /// * `operations` - Up to 10 operations
+    ///
+    /// # Returns
+    /// The user's position summary after the batch
+    ///
+    /// # Events
+    /// Emits each operation's own event, then `batch_executed_event`
+    pub fn batch(
+        env: Env,
+        user: Address,
+        operations: Vec<Operation>,
+    ) -> Result<UserPositionSummary, CrossAssetError> {
+        record_call(&env, "batch");
+        extend_instance_ttl(&env);
+        batch(&env, user, operations)
+    }
+
+    // ============================================================================
+    // Storage TTL
+    // ============================================================================
+
+    /// Extend the TTL of all of a user's stored entries (keeper, anyone can call)
+    ///
+    /// # Arguments
+    /// * `user` - The user whose position, analytics and credit entries to extend
+    pub fn bump_position(env: Env, user: Address) {
+        bump_position(&env, &user)
+    }
+
+    /// Set when and how far persistent entries are extended, in ledgers (admin only)
+    pub fn set_ttl_config(env: Env, caller: Address, config: TtlConfig) -> Result<(), TtlError> {
+        set_ttl_config(&env, caller, config)
+    }
+
+    /// Get when and how far persistent entries are extended, in ledgers
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    // ============================================================================
+    // Upgrades
+    // ============================================================================
+
+    /// Replace the contract's code, keeping its address and storage (admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The protocol admin
+    /// * `new_wasm_hash` - Hash of the already uploaded wasm
+    ///
+    /// # Events
+    /// Emits `contract_upgraded`
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), UpgradeError> {
+        upgrade::upgrade(&env, caller, new_wasm_hash)
+    }
+
+    /// Run the storage migrations up to schema `version` (admin only)
+    ///
+    /// # Events
+    /// Emits `contract_migrated`
+    pub fn migrate(env: Env, caller: Address, version: u32) -> Result<(), UpgradeError> {
+        upgrade::migrate(&env, caller, version)
+    }
+
+    /// Storage schema version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+}
+
+// ============================================================================
+// Flash Loans
+// ============================================================================
+
+#[cfg(feature = "flash-loans")]
+#[contractimpl]
+impl HelloContract {
+    /// Execute a flash loan through a receiver callback
+    ///
+    /// Transfers the loan to `receiver`, calls its
+    /// `on_flash_loan(asset, amount, fee, params)` and requires principal + fee
+    /// to be returned before the callback finishes. The fee goes to the asset's
+    /// protocol reserves.
+    ///
+    /// # Arguments
+    /// * `receiver` - The contract receiving the loan (must authorize)
+    /// * `asset` - The address of the asset contract to borrow
+    /// * `amount` - The amount to borrow
+    /// * `params` - Opaque data passed through to the callback
+    ///
+    /// # Returns
+    /// Returns the fee charged
+    ///
+    /// # Events
+    /// Emits `flash_loan_initiated` and `flash_loan_repaid`
+    pub fn flash_loan(
+        env: Env,
+        receiver: Address,
+        asset: Address,
+        amount: i128,
+        params: Bytes,
+    ) -> Result<i128, FlashLoanError> {
+        record_call(&env, "flash_loan");
+        flash_loan(&env, receiver, asset, amount, params)
+    }
+
+    /// Set flash loan fee (admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must be admin)
+    /// * `fee_bps` - The new fee in basis points
+    pub fn set_flash_loan_fee(env: Env, caller: Address, fee_bps: i128) {
+        set_flash_loan_fee(&env, caller, fee_bps)
+            .unwrap_or_else(|e| panic!("Flash loan error: {:?}", e))
+    }
+
+    /// Configure flash loan parameters (admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The caller address (must be admin)
+    /// * `config` - The new flash loan configuration
+    pub fn configure_flash_loan(env: Env, caller: Address, config: FlashLoanConfig) {
+        configure_flash_loan(&env, caller, config)
+            .unwrap_or_else(|e| panic!("Flash loan error: {:?}", e))
+    }
+}
+
+// ============================================================================
+// Rate Locks
+// ============================================================================
+
+#[cfg(feature = "rate-locks")]
+#[contractimpl]
+impl HelloContract {
+    /// Enable rate locks in a market, or change their caps (admin or risk admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a risk admin
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `config` - Spread below the supply rate, longest term and locked value cap
+    pub fn set_rate_lock_config(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        config: RateLockConfig,
+    ) -> Result<(), RateLockError> {
+        set_rate_lock_config(&env, caller, asset, config)
+    }
+
+    /// Get a market's rate lock parameters, if locks are enabled
+    pub fn get_rate_lock_config(env: Env, asset: Option<Address>) -> Option<RateLockConfig> {
+        get_rate_lock_config(&env, asset)
+    }
+
+    /// Get a market's current pool supply rate (in basis points)
+    pub fn get_pool_supply_rate(env: Env, asset: Option<Address>) -> Result<i128, RateLockError> {
+        get_pool_supply_rate(&env, asset)
+    }
+
+    /// Get the fixed rate a lock in the market would get now (in basis points)
+    pub fn get_rate_lock_quote(env: Env, asset: Option<Address>) -> Result<i128, RateLockError> {
+        get_rate_lock_quote(&env, asset)
+    }
+
+    /// Fix the yield on some of the caller's pool shares for a term
+    ///
+    /// The shares cannot be redeemed until the lock is settled.
+    ///
+    /// # Arguments
+    /// * `user` - The supplier (must authorize)
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `shares` - Shares to lock
+    /// * `term` - Lock length in seconds
+    ///
+    /// # Events
+    /// Emits `rate_locked_event`
+    pub fn lock_supply_rate(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        shares: i128,
+        term: u64,
+    ) -> Result<RateLock, RateLockError> {
+        record_call(&env, "lock_supply_rate");
+        lock_supply_rate(&env, user, asset, shares, term)
+    }
+
+    /// Settle a matured rate lock against reserves (anyone can call)
+    ///
+    /// Returns the value paid to the supplier (positive) or taken into
+    /// reserves (negative).
+    ///
+    /// # Events
+    /// Emits `rate_lock_settled_event`
+    pub fn settle_rate_lock(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, RateLockError> {
+        record_call(&env, "settle_rate_lock");
+        settle_rate_lock(&env, user, asset)
+    }
+
+    /// Get a supplier's rate lock in a market, if any
+    pub fn get_rate_lock(env: Env, user: Address, asset: Option<Address>) -> Option<RateLock> {
+        get_rate_lock(&env, &user, asset)
+    }
+
+    /// Get the value locked across a market's open rate locks
+    pub fn get_locked_value(env: Env, asset: Option<Address>) -> i128 {
+        get_locked_value(&env, asset)
+    }
+}
+
+// ============================================================================
+// Supplier Insurance
+// ============================================================================
+
+#[cfg(feature = "insurance")]
+#[contractimpl]
+impl HelloContract {
+    /// Offer pool coverage in a market, or change its terms (cross-asset admin only)
+    ///
+    /// # Arguments
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `premium_bps` - Premium charged on covered suppliers' yield, in [0, 10000]
+    /// * `coverage_cap` - Highest value of covered shares (0 = unlimited)
+    ///
+    /// # Events
+    /// Emits `insurance_config_updated`
+    pub fn set_insurance_config(
+        env: Env,
+        asset: Option<Address>,
+        premium_bps: i128,
+        coverage_cap: i128,
+    ) -> Result<(), InsuranceError> {
+        set_insurance_config(&env, asset, premium_bps, coverage_cap)
+    }
+
+    /// Add to a market's insurance fund
+    ///
+    /// `from` (typically the safety module) must have approved the contract
+    /// to transfer `amount`.
+    ///
+    /// # Events
+    /// Emits `insurance_funded`
+    pub fn fund_insurance(
+        env: Env,
+        from: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<(), InsuranceError> {
+        fund_insurance(&env, from, asset, amount)
+    }
+
+    /// Cover all of a supplier's pool shares in a market
+    ///
+    /// # Events
+    /// Emits `coverage_updated`
+    pub fn opt_in_coverage(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<Coverage, InsuranceError> {
+        record_call(&env, "opt_in_coverage");
+        opt_in_coverage(&env, user, asset)
+    }
+
+    /// End a supplier's coverage in a market, paying out unclaimed payouts
+    ///
+    /// # Returns
+    /// The payout transferred to the user
+    ///
+    /// # Events
+    /// Emits `coverage_updated`
+    pub fn opt_out_coverage(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, InsuranceError> {
+        record_call(&env, "opt_out_coverage");
+        opt_out_coverage(&env, user, asset)
+    }
+
+    /// Record a loss to a market's pool (cross-asset admin only)
+    ///
+    /// Covered suppliers are paid their part of the loss from the insurance
+    /// fund, up to the coverage cap and the fund balance.
+    ///
+    /// # Returns
+    /// The payout owed to covered suppliers
+    ///
+    /// # Events
+    /// Emits `shortfall_reported`
+    pub fn report_shortfall(
+        env: Env,
+        asset: Option<Address>,
+        loss: i128,
+    ) -> Result<i128, InsuranceError> {
+        report_shortfall(&env, asset, loss)
+    }
+
+    /// Collect a covered supplier's shortfall payouts in a market
+    ///
+    /// # Returns
+    /// The amount transferred to the user
+    ///
+    /// # Events
+    /// Emits `coverage_claimed` when anything is paid
+    pub fn claim_coverage(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, InsuranceError> {
+        record_call(&env, "claim_coverage");
+        claim_coverage(&env, user, asset)
+    }
+
+    /// Get a market's coverage terms, if it offers coverage
+    pub fn get_insurance_config(env: Env, asset: Option<Address>) -> Option<InsuranceConfig> {
+        get_insurance_config(&env, asset)
+    }
+
+    /// Get a market's insurance fund
+    pub fn get_insurance_fund(env: Env, asset: Option<Address>) -> InsuranceFund {
+        get_insurance_fund(&env, asset)
+    }
+
+    /// Get a supplier's coverage in a market, as of its last checkpoint
+    pub fn get_coverage(env: Env, user: Address, asset: Option<Address>) -> Option<Coverage> {
+        get_coverage(&env, &user, asset)
+    }
+}
+
+// ============================================================================
+// Isolated Markets
+// ============================================================================
+
+#[cfg(feature = "isolated-markets")]
+#[contractimpl]
+impl HelloContract {
+    /// Set the uploaded wasm new isolated markets are deployed from (protocol admin only)
+    ///
+    /// # Events
+    /// Emits `market_wasm_updated`
+    pub fn set_market_wasm(
+        env: Env,
+        caller: Address,
+        wasm_hash: BytesN<32>,
+    ) -> Result<(), MarketFactoryError> {
+        set_market_wasm(&env, caller, wasm_hash)
+    }
+
+    /// Get the wasm hash new isolated markets are deployed from
+    pub fn get_market_wasm(env: Env) -> Option<BytesN<32>> {
+        get_market_wasm(&env)
+    }
+
+    /// Deploy an isolated market and add it to the registry (asset listing admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - Account creating the market (must hold AssetListingAdmin)
+    /// * `params` - Admin, unique name and assets of the new market
+    ///
+    /// # Returns
+    /// The registry record, including the deployed market's address
+    ///
+    /// # Events
+    /// Emits `market_created`
+    pub fn create_isolated_market(
+        env: Env,
+        caller: Address,
+        params: IsolatedMarketParams,
+    ) -> Result<IsolatedMarket, MarketFactoryError> {
+        create_isolated_market(&env, caller, params)
+    }
+
+    /// Get a registered isolated market by id
+    pub fn get_isolated_market(env: Env, id: u32) -> Option<IsolatedMarket> {
+        get_isolated_market(&env, id)
+    }
+
+    /// Get a registered isolated market by name
+    pub fn get_isolated_market_by_name(env: Env, name: Symbol) -> Option<IsolatedMarket> {
+        get_isolated_market_by_name(&env, name)
+    }
+
+    /// Number of registered isolated markets
+    pub fn get_isolated_market_count(env: Env) -> u32 {
+        get_isolated_market_count(&env)
+    }
+
+    /// Page through registered isolated markets in creation order
+    ///
+    /// # Arguments
+    /// * `limit` - Markets per page (at most 50)
+    /// * `offset` - Registry id to start from
+    pub fn get_isolated_markets(env: Env, limit: u32, offset: u32) -> Vec<IsolatedMarket> {
+        get_isolated_markets(&env, limit, offset)
+    }
+}
+
+// ============================================================================
+// Admin Recovery
+// ============================================================================
+
+#[cfg(feature = "admin-recovery")]
+#[contractimpl]
+impl HelloContract {
+    /// Set the guardians that can rotate a lost admin (admin only)
+    ///
+    /// Cancels any pending recovery.
+    pub fn set_guardians(
+        env: Env,
+        caller: Address,
+        config: GuardianConfig,
+    ) -> Result<(), RecoveryError> {
+        set_guardians(&env, caller, config)
+    }
+
+    /// Get the guardian set, if configured
+    pub fn get_guardians(env: Env) -> Option<GuardianConfig> {
+        get_guardians(&env)
+    }
+
+    /// Propose a new protocol admin (guardian only)
+    ///
+    /// # Arguments
+    /// * `guardian` - The proposing guardian; the proposal counts as its approval
+    /// * `new_admin` - The proposed admin
+    pub fn propose_recovery(
+        env: Env,
+        guardian: Address,
+        new_admin: Address,
+    ) -> Result<(), RecoveryError> {
+        propose_recovery(&env, guardian, new_admin)
+    }
+
+    /// Approve the pending recovery (guardian only)
+    pub fn approve_recovery(env: Env, guardian: Address) -> Result<(), RecoveryError> {
+        approve_recovery(&env, guardian)
+    }
+
+    /// Cancel the pending recovery (current admin only)
+    pub fn cancel_recovery(env: Env, caller: Address) -> Result<(), RecoveryError> {
+        cancel_recovery(&env, caller)
+    }
+
+    /// Rotate the admin once quorum was reached and the delay has passed (anyone can call)
+    pub fn execute_recovery(env: Env) -> Result<(), RecoveryError> {
+        execute_recovery(&env)
+    }
+
+    /// Get the pending recovery, if any
+    pub fn get_recovery(env: Env) -> Option<RecoveryRequest> {
+        get_recovery(&env)
+    }
+}
+
+// ============================================================================
+// Reward Emissions
+// ============================================================================
+
+#[cfg(feature = "rewards")]
+#[contractimpl]
+impl HelloContract {
+    /// Set the token rewards are paid in, once (admin or treasury admin only)
+    pub fn set_reward_token(env: Env, caller: Address, token: Address) -> Result<(), RewardsError> {
+        set_reward_token(&env, caller, token)
+    }
+
+    /// Get the token rewards are paid in, if set
+    pub fn get_reward_token(env: Env) -> Option<Address> {
+        get_reward_token(&env)
+    }
+
+    /// Set a market's reward emissions (admin or treasury admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The cross-asset admin or a treasury admin
+    /// * `asset` - The market's asset (`None` for native XLM)
+    /// * `rates` - Reward tokens emitted per second to pool suppliers
+    ///
+    /// # Events
+    /// Emits `emission_rates_updated_event`
+    pub fn set_emission_rates(
+        env: Env,
+        caller: Address,
+        asset: Option<Address>,
+        rates: EmissionRates,
+    ) -> Result<(), RewardsError> {
+        set_emission_rates(&env, caller, asset, rates)
+    }
+
+    /// Get a market's reward emissions; zero if it has none
+    pub fn get_emission_rates(env: Env, asset: Option<Address>) -> EmissionRates {
+        get_emission_rates(&env, asset)
+    }
+
+    /// Get a market's reward index as of now
+    pub fn get_reward_index(env: Env, asset: Option<Address>) -> RewardIndex {
+        get_reward_index(&env, asset)
+    }
+
+    /// Get a reward program's emission spent, unique claimants, average boost
+    /// and incremental TVL over its window, up to now if it still runs
+    pub fn get_program_report(env: Env, program_id: u32) -> Result<ProgramReport, RewardsError> {
+        get_program_report(&env, program_id)
+    }
+
+    /// Add reward tokens to the balance claims are paid from (anyone can fund)
+    ///
+    /// # Events
+    /// Emits `rewards_funded_event`
+    pub fn fund_rewards(env: Env, from: Address, amount: i128) -> Result<(), RewardsError> {
+        fund_rewards(&env, from, amount)
+    }
+
+    /// Get the funded rewards not yet paid out
+    pub fn get_rewards_balance(env: Env) -> i128 {
+        get_rewards_balance(&env)
+    }
+
+    /// Pay a user's accrued rewards to the user, up to the funded balance
+    ///
+    /// Returns the amount paid.
+    ///
+    /// # Arguments
+    /// * `caller` - The user or their claim delegate
+    /// * `user` - The user whose rewards are claimed
+    ///
+    /// # Events
+    /// Emits `rewards_claimed_event`
+    pub fn claim_rewards(env: Env, caller: Address, user: Address) -> Result<i128, RewardsError> {
+        record_call(&env, "claim_rewards");
+        claim_rewards(&env, caller, user)
+    }
+
+    /// Name the account allowed to claim the user's rewards on their behalf,
+    /// or remove it with `None`
+    ///
+    /// # Events
+    /// Emits `reward_delegate_updated_event`
+    pub fn set_reward_delegate(env: Env, user: Address, delegate: Option<Address>) {
+        set_reward_delegate(&env, user, delegate)
+    }
+
+    /// Get the account allowed to claim a user's rewards, if any
+    pub fn get_reward_delegate(env: Env, user: Address) -> Option<Address> {
+        get_reward_delegate(&env, &user)
+    }
+
+    /// Get a user's unclaimed rewards across all markets
+    pub fn get_pending_rewards(env: Env, user: Address) -> i128 {
+        get_pending_rewards(&env, &user)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(all(test, feature = "flash-loans"))]
 mod flash_loan_test;
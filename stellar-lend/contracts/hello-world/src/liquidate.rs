@@ -11,30 +11,42 @@
 //!
 //! ## Cross-Asset Liquidation
 //! When debt and collateral are different assets, oracle prices are used to
-//! convert between asset values. A default price of 1.0 (8 decimals) is used
-//! as fallback when oracle prices are not configured.
+//! convert between asset values. A liquidation fails with `PriceNotAvailable`
+//! if either asset has no oracle price.
 //!
 //! ## Invariants
 //! - Only undercollateralized positions (below liquidation threshold) can be liquidated.
 //! - Liquidation amount cannot exceed the close factor percentage of total debt.
 //! - Collateral seized cannot exceed the borrower's available collateral.
+//! - A liquidation that clears the whole debt closes the position: collateral
+//!   left after the seizure is returned to the borrower, not kept by the protocol.
 //! - Interest is accrued on the borrower's position before liquidation.
 
 #![allow(unused)]
+use crate::analytics::{record_tvl, ActivityType};
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::BASIS_POINTS_SCALE;
+use crate::credit::record_liquidation;
 use crate::events::{emit_liquidation, LiquidationEvent};
+use crate::liquidation_history::{log_liquidation, LiquidationRecord};
+use crate::loyalty::refresh_tier;
 use soroban_sdk::{contracterror, Address, Env, IntoVal, Map, Symbol, Val, Vec};
+use stellarlend_common::pause::PAUSE_LIQUIDATE;
 
 use crate::deposit::{
-    add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
-    emit_user_activity_tracked_event, update_protocol_analytics, AssetParams, DepositDataKey,
-    Position, ProtocolAnalytics, UserAnalytics,
+    add_activity_log, adjust_asset_totals, emit_analytics_updated_event,
+    emit_position_updated_event, emit_user_activity_tracked_event, update_protocol_analytics,
+    DepositDataKey, Position, ProtocolAnalytics, UserAnalytics,
 };
 use crate::oracle::get_price;
+use crate::reentrancy::{OperationGuard, Reentered};
 use crate::risk_management::{
-    can_be_liquidated, get_close_factor, get_liquidation_incentive,
-    get_liquidation_incentive_amount, get_max_liquidatable_amount, is_emergency_paused,
+    can_position_be_liquidated, collateral_ratio_health_factor, get_close_factor,
+    get_health_factor_incentive, get_liquidation_incentive_amount, get_max_liquidatable_amount,
+    get_position_liquidation_threshold, get_target_repay_limit, is_emergency_paused,
     is_operation_paused, require_operation_not_paused, RiskManagementError,
 };
+use crate::storage;
 
 /// Errors that can occur during liquidation operations
 #[contracterror]
@@ -63,6 +75,14 @@ pub enum LiquidationError {
     PriceNotAvailable = 10,
     /// Liquidation would leave position undercollateralized
     InsufficientLiquidation = 11,
+    /// Reentrancy detected
+    Reentrancy = 12,
+}
+
+impl From<Reentered> for LiquidationError {
+    fn from(_: Reentered) -> Self {
+        LiquidationError::Reentrancy
+    }
 }
 
 /// Annual interest rate in basis points (e.g., 500 = 5% per year)
@@ -71,9 +91,11 @@ pub enum LiquidationError {
 // See interest_rate module for details
 /// Calculate interest accrued since last accrual time
 /// Calculate accrued interest using dynamic interest rate
-/// Uses the current borrow rate based on protocol utilization
+/// Uses the current borrow rate based on protocol utilization, less any
+/// staker discount the user qualifies for
 fn calculate_accrued_interest(
     env: &Env,
+    user: &Address,
     principal: i128,
     last_accrual_time: u64,
     current_time: u64,
@@ -88,7 +110,7 @@ fn calculate_accrued_interest(
 
     // Get current borrow rate (in basis points)
     let rate_bps =
-        crate::interest_rate::calculate_borrow_rate(env).map_err(|_| LiquidationError::Overflow)?;
+        crate::rate_mode::accrual_rate(env, user).map_err(|_| LiquidationError::Overflow)?;
 
     // Calculate interest using the dynamic rate
     crate::interest_rate::calculate_accrued_interest(
@@ -101,7 +123,11 @@ fn calculate_accrued_interest(
 }
 
 /// Accrue interest on a position
-fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), LiquidationError> {
+fn accrue_interest(
+    env: &Env,
+    user: &Address,
+    position: &mut Position,
+) -> Result<(), LiquidationError> {
     let current_time = env.ledger().timestamp();
 
     if position.debt == 0 {
@@ -111,8 +137,13 @@ fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), Liquidation
     }
 
     // Calculate new interest accrued using dynamic rate
-    let new_interest =
-        calculate_accrued_interest(env, position.debt, position.last_accrual_time, current_time)?;
+    let new_interest = calculate_accrued_interest(
+        env,
+        user,
+        position.debt,
+        position.last_accrual_time,
+        current_time,
+    )?;
 
     // Add to existing interest
     position.borrow_interest = position
@@ -128,11 +159,8 @@ fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), Liquidation
 
 /// Get asset price from oracle
 /// Returns price in base units (scaled by decimals)
-/// Falls back to default price if oracle doesn't have a price set
-fn get_asset_price(env: &Env, asset: &Address) -> i128 {
-    // Try to get price from oracle, but fallback to default if not available
-    // This allows liquidation to work even when prices aren't set up in tests
-    get_price(env, asset).unwrap_or(1_00000000i128) // Default: 1 XLM with 8 decimals
+fn get_asset_price(env: &Env, asset: &Address) -> Result<i128, LiquidationError> {
+    get_price(env, asset).map_err(|_| LiquidationError::PriceNotAvailable)
 }
 
 /// Calculate collateral value in debt asset terms
@@ -172,7 +200,8 @@ fn calculate_debt_value(debt: i128, interest: i128) -> Result<i128, LiquidationE
 /// * `borrower` - The address of the borrower being liquidated
 /// * `debt_asset` - The address of the debt asset to repay (None for native XLM)
 /// * `collateral_asset` - The address of the collateral asset to receive (None for native XLM)
-/// * `debt_amount` - The amount of debt to liquidate
+/// * `debt_amount` - The amount of debt to liquidate; reduced to what
+///   restores the liquidation target health factor, if one is set
 ///
 /// # Returns
 /// Returns a tuple (debt_liquidated, collateral_seized, incentive_amount)
@@ -204,6 +233,8 @@ pub fn liquidate(
     collateral_asset: Option<Address>,
     debt_amount: i128,
 ) -> Result<(i128, i128, i128), LiquidationError> {
+    let _guard = OperationGuard::enter(env)?;
+
     // Validate amount
     if debt_amount <= 0 {
         return Err(LiquidationError::InvalidAmount);
@@ -215,13 +246,11 @@ pub fn liquidate(
     }
 
     // Check if liquidations are paused
-    require_operation_not_paused(env, Symbol::new(env, "pause_liquidate")).map_err(
-        |e| match e {
-            RiskManagementError::OperationPaused => LiquidationError::LiquidationPaused,
-            RiskManagementError::EmergencyPaused => LiquidationError::LiquidationPaused,
-            _ => LiquidationError::LiquidationPaused,
-        },
-    )?;
+    require_operation_not_paused(env, Symbol::new(env, PAUSE_LIQUIDATE)).map_err(|e| match e {
+        RiskManagementError::OperationPaused => LiquidationError::LiquidationPaused,
+        RiskManagementError::EmergencyPaused => LiquidationError::LiquidationPaused,
+        _ => LiquidationError::LiquidationPaused,
+    })?;
 
     // Validate assets
     if let Some(ref debt_addr) = debt_asset {
@@ -241,22 +270,16 @@ pub fn liquidate(
 
     // Get borrower position
     let position_key = DepositDataKey::Position(borrower.clone());
-    let mut position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
+    let mut position = storage::get::<DepositDataKey, Position>(env, &position_key)
         .ok_or(LiquidationError::NotLiquidatable)?;
 
     // Accrue interest before liquidation
-    accrue_interest(env, &mut position)?;
+    accrue_interest(env, &borrower, &mut position)?;
 
     // Get collateral balance
     let collateral_key = DepositDataKey::CollateralBalance(borrower.clone());
-    let collateral_balance = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    let collateral_balance =
+        storage::get::<DepositDataKey, i128>(env, &collateral_key).unwrap_or(0);
 
     // Calculate total debt (principal + interest)
     let total_debt = calculate_debt_value(position.debt, position.borrow_interest)?;
@@ -270,14 +293,14 @@ pub fn liquidate(
     } else {
         // Need to convert between different assets using prices
         let debt_price = if let Some(ref debt_addr) = debt_asset {
-            get_asset_price(env, debt_addr)
+            get_asset_price(env, debt_addr)?
         } else {
             // Default price for native XLM (1:1, no decimals)
             1i128
         };
 
         let collateral_price = if let Some(ref collateral_addr) = collateral_asset {
-            get_asset_price(env, collateral_addr)
+            get_asset_price(env, collateral_addr)?
         } else {
             // Default price for native XLM (1:1, no decimals)
             1i128
@@ -288,7 +311,7 @@ pub fn liquidate(
     };
 
     // Check if position can be liquidated
-    let can_liquidate = can_be_liquidated(env, collateral_value, total_debt)
+    let can_liquidate = can_position_be_liquidated(env, &borrower, collateral_value, total_debt)
         .map_err(|_| LiquidationError::NotLiquidatable)?;
 
     if !can_liquidate {
@@ -304,17 +327,29 @@ pub fn liquidate(
         return Err(LiquidationError::ExceedsCloseFactor);
     }
 
-    // Ensure we don't liquidate more than total debt
-    let actual_debt_liquidated = if debt_amount > total_debt {
+    // Calculate liquidation incentive, scaled by how far the position is underwater
+    let threshold = get_position_liquidation_threshold(env, &borrower)
+        .map_err(|_| LiquidationError::Overflow)?;
+    let health_factor = collateral_ratio_health_factor(collateral_value, total_debt, threshold)
+        .map_err(|_| LiquidationError::Overflow)?;
+    let incentive_bps =
+        get_health_factor_incentive(env, health_factor).map_err(|_| LiquidationError::Overflow)?;
+
+    // Ensure we don't liquidate more than total debt, or more than restores
+    // the position to the liquidation target
+    let mut actual_debt_liquidated = if debt_amount > total_debt {
         total_debt
     } else {
         debt_amount
     };
-
-    // Calculate liquidation incentive
-    let incentive_bps = get_liquidation_incentive(env).map_err(|_| LiquidationError::Overflow)?;
-    let incentive_amount = get_liquidation_incentive_amount(env, actual_debt_liquidated)
-        .map_err(|_| LiquidationError::Overflow)?;
+    if let Some(limit) =
+        get_target_repay_limit(env, collateral_value, total_debt, threshold, incentive_bps)
+    {
+        actual_debt_liquidated = actual_debt_liquidated.min(limit);
+    }
+    let incentive_amount =
+        get_liquidation_incentive_amount(env, actual_debt_liquidated, health_factor)
+            .map_err(|_| LiquidationError::Overflow)?;
 
     // Calculate collateral to seize
     // Liquidator repays debt_liquidated amount of debt asset
@@ -327,13 +362,13 @@ pub fn liquidate(
     } else {
         // Need to convert between different assets using prices
         let debt_price = if let Some(ref debt_addr) = debt_asset {
-            get_asset_price(env, debt_addr)
+            get_asset_price(env, debt_addr)?
         } else {
             1i128 // Native XLM
         };
 
         let collateral_price = if let Some(ref collateral_addr) = collateral_asset {
-            get_asset_price(env, collateral_addr)
+            get_asset_price(env, collateral_addr)?
         } else {
             1i128 // Native XLM
         };
@@ -347,9 +382,9 @@ pub fn liquidate(
 
     // Apply incentive: collateral_seized = collateral_value_liquidated * (1 + incentive_bps / 10000)
     let collateral_seized = collateral_value_liquidated
-        .checked_mul(10000 + incentive_bps)
+        .checked_mul(BASIS_POINTS_SCALE.get() + incentive_bps)
         .ok_or(LiquidationError::Overflow)?
-        .checked_div(10000)
+        .checked_div(BASIS_POINTS_SCALE.get())
         .ok_or(LiquidationError::Overflow)?;
 
     // Ensure we don't seize more than available collateral
@@ -374,15 +409,33 @@ pub fn liquidate(
             &env.current_contract_address(), // to (this contract)
             &actual_debt_liquidated,
         );
+        cash_ledger::record_inflow(
+            env,
+            debt_addr,
+            CashReason::Liquidation,
+            actual_debt_liquidated,
+        );
     } else {
         // Native XLM handling - placeholder for now
     }
 
+    // A full close returns the collateral left after the seizure to the borrower
+    let surplus_returned = if actual_debt_liquidated == total_debt {
+        collateral_balance
+            .checked_sub(actual_collateral_seized)
+            .ok_or(LiquidationError::Overflow)?
+    } else {
+        0
+    };
+    let collateral_released = actual_collateral_seized
+        .checked_add(surplus_returned)
+        .ok_or(LiquidationError::Overflow)?;
+
     // Check contract has sufficient collateral to transfer
     if let Some(ref collateral_addr) = collateral_asset {
         let token_client = soroban_sdk::token::Client::new(env, collateral_addr);
         let contract_balance = token_client.balance(&env.current_contract_address());
-        if contract_balance < actual_collateral_seized {
+        if contract_balance < collateral_released {
             return Err(LiquidationError::InsufficientBalance);
         }
 
@@ -392,6 +445,20 @@ pub fn liquidate(
             &liquidator,                     // to (liquidator)
             &actual_collateral_seized,
         );
+
+        if surplus_returned > 0 {
+            token_client.transfer(
+                &env.current_contract_address(), // from (this contract)
+                &borrower,                       // to (borrower)
+                &surplus_returned,
+            );
+        }
+        cash_ledger::record_outflow(
+            env,
+            collateral_addr,
+            CashReason::Liquidation,
+            collateral_released,
+        );
     } else {
         // Native XLM handling - placeholder for now
     }
@@ -416,17 +483,17 @@ pub fn liquidate(
 
     // Update borrower's collateral balance
     let new_collateral_balance = collateral_balance
-        .checked_sub(actual_collateral_seized)
+        .checked_sub(collateral_released)
         .ok_or(LiquidationError::Overflow)?;
-    env.storage()
-        .persistent()
-        .set(&collateral_key, &new_collateral_balance);
+    storage::set(env, &collateral_key, &new_collateral_balance);
 
     // Update position collateral
     position.collateral = new_collateral_balance;
 
     // Save updated position
-    env.storage().persistent().set(&position_key, &position);
+    storage::set(env, &position_key, &position);
+    adjust_asset_totals(env, &debt_asset, 0, -principal_to_pay);
+    adjust_asset_totals(env, &collateral_asset, -collateral_released, 0);
 
     // Update analytics
     update_liquidation_analytics(
@@ -434,24 +501,39 @@ pub fn liquidate(
         &borrower,
         &liquidator,
         actual_debt_liquidated,
-        actual_collateral_seized,
+        collateral_released,
         timestamp,
     )?;
+    record_liquidation(env, &borrower, actual_debt_liquidated == total_debt);
 
     // Add to activity log
     add_activity_log(
         env,
         &borrower,
-        Symbol::new(env, "liquidate"),
+        ActivityType::Liquidation,
         actual_debt_liquidated,
         debt_asset.clone(),
         timestamp,
+        None,
     )
     .map_err(|e| match e {
         crate::deposit::DepositError::Overflow => LiquidationError::Overflow,
         _ => LiquidationError::Overflow,
     })?;
 
+    log_liquidation(
+        env,
+        LiquidationRecord {
+            liquidator: liquidator.clone(),
+            borrower: borrower.clone(),
+            debt_asset: debt_asset.clone(),
+            collateral_asset: collateral_asset.clone(),
+            debt_repaid: actual_debt_liquidated,
+            collateral_seized: actual_collateral_seized,
+            timestamp,
+        },
+    );
+
     // Emit liquidation event
     emit_liquidation(
         env,
@@ -463,6 +545,7 @@ pub fn liquidate(
             debt_liquidated: actual_debt_liquidated,
             collateral_seized: actual_collateral_seized,
             incentive_amount,
+            surplus_returned,
             timestamp,
         },
     );
@@ -501,31 +584,30 @@ fn update_liquidation_analytics(
     borrower: &Address,
     liquidator: &Address,
     debt_liquidated: i128,
-    collateral_seized: i128,
+    collateral_released: i128,
     timestamp: u64,
 ) -> Result<(), LiquidationError> {
     // Update borrower analytics
     let borrower_analytics_key = DepositDataKey::UserAnalytics(borrower.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut borrower_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, UserAnalytics>(&borrower_analytics_key)
-        .unwrap_or_else(|| UserAnalytics {
-            total_deposits: 0,
-            total_borrows: 0,
-            total_withdrawals: 0,
-            total_repayments: 0,
-            collateral_value: 0,
-            debt_value: 0,
-            collateralization_ratio: 0,
-            activity_score: 0,
-            transaction_count: 0,
-            first_interaction: timestamp,
-            last_activity: timestamp,
-            risk_level: 0,
-            loyalty_tier: 0,
-        });
+    let mut borrower_analytics =
+        storage::get::<DepositDataKey, UserAnalytics>(env, &borrower_analytics_key).unwrap_or_else(
+            || UserAnalytics {
+                total_deposits: 0,
+                total_borrows: 0,
+                total_withdrawals: 0,
+                total_repayments: 0,
+                collateral_value: 0,
+                debt_value: 0,
+                collateralization_ratio: 0,
+                activity_score: 0,
+                transaction_count: 0,
+                first_interaction: timestamp,
+                last_activity: timestamp,
+                risk_level: 0,
+                loyalty_tier: 0,
+            },
+        );
 
     // Update debt value (subtract liquidated amount)
     borrower_analytics.debt_value = borrower_analytics
@@ -533,17 +615,17 @@ fn update_liquidation_analytics(
         .checked_sub(debt_liquidated)
         .unwrap_or(0);
 
-    // Update collateral value (subtract seized amount)
+    // Update collateral value (subtract seized and returned collateral)
     borrower_analytics.collateral_value = borrower_analytics
         .collateral_value
-        .checked_sub(collateral_seized)
+        .checked_sub(collateral_released)
         .unwrap_or(0);
 
     // Recalculate collateralization ratio
     if borrower_analytics.debt_value > 0 && borrower_analytics.collateral_value > 0 {
         borrower_analytics.collateralization_ratio = borrower_analytics
             .collateral_value
-            .checked_mul(10000)
+            .checked_mul(BASIS_POINTS_SCALE.get())
             .and_then(|v| v.checked_div(borrower_analytics.debt_value))
             .unwrap_or(0);
     } else {
@@ -552,32 +634,29 @@ fn update_liquidation_analytics(
 
     borrower_analytics.transaction_count = borrower_analytics.transaction_count.saturating_add(1);
     borrower_analytics.last_activity = timestamp;
+    refresh_tier(env, borrower, &mut borrower_analytics);
 
-    env.storage()
-        .persistent()
-        .set(&borrower_analytics_key, &borrower_analytics);
+    storage::set(env, &borrower_analytics_key, &borrower_analytics);
 
     // Update protocol analytics
     let protocol_analytics_key = DepositDataKey::ProtocolAnalytics;
-    let mut protocol_analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, ProtocolAnalytics>(&protocol_analytics_key)
-        .unwrap_or(ProtocolAnalytics {
-            total_deposits: 0,
-            total_borrows: 0,
-            total_value_locked: 0,
-        });
-
-    // Update total value locked (subtract seized collateral)
+    let mut protocol_analytics =
+        storage::get::<DepositDataKey, ProtocolAnalytics>(env, &protocol_analytics_key).unwrap_or(
+            ProtocolAnalytics {
+                total_deposits: 0,
+                total_borrows: 0,
+                total_value_locked: 0,
+            },
+        );
+
+    // Update total value locked (subtract seized and returned collateral)
     protocol_analytics.total_value_locked = protocol_analytics
         .total_value_locked
-        .checked_sub(collateral_seized)
+        .checked_sub(collateral_released)
         .unwrap_or(0);
 
-    env.storage()
-        .persistent()
-        .set(&protocol_analytics_key, &protocol_analytics);
+    storage::set(env, &protocol_analytics_key, &protocol_analytics);
+    record_tvl(env, protocol_analytics.total_value_locked);
 
     Ok(())
 }
@@ -0,0 +1,138 @@
+//! # Liquidation History
+//!
+//! Append-only log of every liquidation, core and cross-asset, with running
+//! totals per liquidator for dashboards and keeper reward programs.
+//!
+//! Each record is its own persistent entry, indexed in order of execution,
+//! so logging costs a constant amount however long the history grows. A
+//! cross-asset liquidation that splits its seizure across collateral assets
+//! logs one record per seizure.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Map, Vec};
+
+use crate::cross_asset::AssetKey;
+use crate::storage;
+
+/// Maximum number of records per `get_liquidation_history` page
+pub const MAX_HISTORY_PAGE_SIZE: u32 = 50;
+
+/// Storage keys for the liquidation history
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum LiquidationHistoryDataKey {
+    /// Number of records logged: u32
+    Count,
+    /// One record, by index from 0: LiquidationRecord
+    Record(u32),
+    /// Running totals of one liquidator: LiquidatorStats
+    Stats(Address),
+}
+
+/// Errors that can occur while reading the liquidation history
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LiquidationHistoryError {
+    /// `limit` is zero or above [`MAX_HISTORY_PAGE_SIZE`]
+    InvalidLimit = 1,
+}
+
+/// One liquidation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationRecord {
+    pub liquidator: Address,
+    pub borrower: Address,
+    /// Asset of the debt repaid; `None` for XLM
+    pub debt_asset: Option<Address>,
+    /// Asset of the collateral seized; `None` for XLM
+    pub collateral_asset: Option<Address>,
+    /// Debt repaid, in the debt asset's native units
+    pub debt_repaid: i128,
+    /// Collateral seized, incentive included, in the collateral asset's
+    /// native units
+    pub collateral_seized: i128,
+    pub timestamp: u64,
+}
+
+/// Running totals of one liquidator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidatorStats {
+    /// Records logged for the liquidator
+    pub liquidations: u32,
+    /// Debt repaid, by debt asset
+    pub debt_repaid: Map<AssetKey, i128>,
+    /// Collateral seized, by collateral asset
+    pub collateral_seized: Map<AssetKey, i128>,
+    /// Timestamp of the latest liquidation; `0` if none
+    pub last_liquidation: u64,
+}
+
+/// Append a liquidation to the history and the liquidator's totals
+pub(crate) fn log_liquidation(env: &Env, record: LiquidationRecord) {
+    let count = get_liquidation_count(env);
+    let mut stats = get_liquidator_stats(env, &record.liquidator);
+    stats.liquidations += 1;
+    let debt_key = AssetKey::from_option(record.debt_asset.clone());
+    stats.debt_repaid.set(
+        debt_key.clone(),
+        stats.debt_repaid.get(debt_key).unwrap_or(0) + record.debt_repaid,
+    );
+    let collateral_key = AssetKey::from_option(record.collateral_asset.clone());
+    stats.collateral_seized.set(
+        collateral_key.clone(),
+        stats.collateral_seized.get(collateral_key).unwrap_or(0) + record.collateral_seized,
+    );
+    stats.last_liquidation = record.timestamp;
+
+    storage::set(
+        env,
+        &LiquidationHistoryDataKey::Stats(record.liquidator.clone()),
+        &stats,
+    );
+    storage::set(env, &LiquidationHistoryDataKey::Record(count), &record);
+    storage::set(env, &LiquidationHistoryDataKey::Count, &(count + 1));
+}
+
+/// Number of liquidation records logged
+pub fn get_liquidation_count(env: &Env) -> u32 {
+    storage::get(env, &LiquidationHistoryDataKey::Count).unwrap_or(0)
+}
+
+/// Get up to `limit` liquidation records, oldest first, skipping the first
+/// `offset`
+///
+/// # Errors
+/// * `InvalidLimit` - `limit` is zero or above [`MAX_HISTORY_PAGE_SIZE`]
+pub fn get_liquidation_history(
+    env: &Env,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<LiquidationRecord>, LiquidationHistoryError> {
+    if limit == 0 || limit > MAX_HISTORY_PAGE_SIZE {
+        return Err(LiquidationHistoryError::InvalidLimit);
+    }
+
+    let end = offset.saturating_add(limit).min(get_liquidation_count(env));
+    let mut records = Vec::new(env);
+    for i in offset..end {
+        if let Some(record) = storage::get(env, &LiquidationHistoryDataKey::Record(i)) {
+            records.push_back(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Get a liquidator's running totals; all zero if it never liquidated
+pub fn get_liquidator_stats(env: &Env, liquidator: &Address) -> LiquidatorStats {
+    storage::get(env, &LiquidationHistoryDataKey::Stats(liquidator.clone())).unwrap_or(
+        LiquidatorStats {
+            liquidations: 0,
+            debt_repaid: Map::new(env),
+            collateral_seized: Map::new(env),
+            last_liquidation: 0,
+        },
+    )
+}
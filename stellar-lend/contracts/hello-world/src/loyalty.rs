@@ -0,0 +1,166 @@
+//! # Loyalty Tiers
+//!
+//! Borrow-rate discounts for established core users, earned from the activity
+//! recorded in their `UserAnalytics`.
+//!
+//! A risk admin sets a schedule of tiers with [`set_loyalty_tiers`]. A user
+//! reaches a tier once both their transaction count and their volume (the sum
+//! of their deposits, withdrawals, borrows and repayments) meet its
+//! thresholds; tiers are numbered from 1 and 0 means none was reached. The
+//! tier's discount is taken off the core borrow rate at accrual time, together
+//! with any staker discount, never below the rate floor (see `interest_rate`).
+//!
+//! The tier is computed from the current schedule whenever it is read.
+//! `UserAnalytics::loyalty_tier` keeps the tier as of the user's last
+//! activity, and a `loyalty_tier_changed` event is emitted when activity
+//! moves the user to another tier.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Symbol, Vec};
+
+use crate::constants::Bps;
+use crate::deposit::{DepositDataKey, UserAnalytics};
+use crate::events::{
+    emit_admin_action, emit_loyalty_tier_changed, AdminActionEvent, LoyaltyTierChangedEvent,
+};
+use crate::roles::{require_role, Role};
+use crate::storage;
+
+/// Maximum number of loyalty tiers
+pub const MAX_LOYALTY_TIERS: u32 = 10;
+
+/// Errors that can occur while configuring loyalty tiers
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LoyaltyError {
+    /// Caller is neither the protocol admin nor a risk admin
+    Unauthorized = 1,
+    /// Too many tiers, a discount outside 0-10000 bps, or tiers out of order
+    InvalidTiers = 2,
+}
+
+/// Storage keys for loyalty data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum LoyaltyDataKey {
+    /// Tier schedule, lowest first: Vec<LoyaltyTier>
+    Tiers,
+}
+
+/// Activity required for a loyalty tier, and its discount
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoyaltyTier {
+    /// Core transactions required
+    pub min_transactions: u64,
+    /// Deposit, withdrawal, borrow and repayment volume required
+    pub min_volume: i128,
+    /// Reduction of the core borrow rate, in basis points
+    pub discount_bps: i128,
+}
+
+/// Set the loyalty tier schedule (admin or `RiskAdmin` only)
+///
+/// Each tier must require at least as many transactions and as much volume
+/// as the one before, and more of one of them, with a discount in
+/// [0, 10000] bps no lower than the one before. An empty list disables
+/// discounts.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the protocol admin nor a risk admin
+/// * `InvalidTiers` - See [`LoyaltyError::InvalidTiers`]
+pub fn set_loyalty_tiers(
+    env: &Env,
+    caller: Address,
+    tiers: Vec<LoyaltyTier>,
+) -> Result<(), LoyaltyError> {
+    require_role(env, &caller, Role::RiskAdmin).map_err(|_| LoyaltyError::Unauthorized)?;
+    if tiers.len() > MAX_LOYALTY_TIERS {
+        return Err(LoyaltyError::InvalidTiers);
+    }
+    let mut previous: Option<LoyaltyTier> = None;
+    for tier in tiers.iter() {
+        if !Bps::new(tier.discount_bps).is_ratio() || tier.min_volume < 0 {
+            return Err(LoyaltyError::InvalidTiers);
+        }
+        if let Some(prev) = previous {
+            if tier.min_transactions < prev.min_transactions
+                || tier.min_volume < prev.min_volume
+                || (tier.min_transactions == prev.min_transactions
+                    && tier.min_volume == prev.min_volume)
+                || tier.discount_bps < prev.discount_bps
+            {
+                return Err(LoyaltyError::InvalidTiers);
+            }
+        }
+        previous = Some(tier);
+    }
+
+    storage::set(env, &LoyaltyDataKey::Tiers, &tiers);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_loyalty_tiers"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get the loyalty tier schedule, lowest first
+pub fn get_loyalty_tiers(env: &Env) -> Vec<LoyaltyTier> {
+    storage::get(env, &LoyaltyDataKey::Tiers).unwrap_or(Vec::new(env))
+}
+
+/// Get a user's loyalty tier; 0 if they have reached none
+pub fn get_user_tier(env: &Env, user: &Address) -> u32 {
+    storage::get::<_, UserAnalytics>(env, &DepositDataKey::UserAnalytics(user.clone()))
+        .map_or(0, |analytics| tier_of(env, &analytics).0)
+}
+
+/// Borrow-rate discount of a user's loyalty tier, in basis points
+pub(crate) fn get_loyalty_discount(env: &Env, user: &Address) -> i128 {
+    storage::get::<_, UserAnalytics>(env, &DepositDataKey::UserAnalytics(user.clone()))
+        .map_or(0, |analytics| tier_of(env, &analytics).1)
+}
+
+/// Update the tier stored in a user's analytics before they are saved,
+/// emitting an event if it changed
+pub(crate) fn refresh_tier(env: &Env, user: &Address, analytics: &mut UserAnalytics) {
+    let (tier, _) = tier_of(env, analytics);
+    if tier == analytics.loyalty_tier {
+        return;
+    }
+
+    emit_loyalty_tier_changed(
+        env,
+        LoyaltyTierChangedEvent {
+            user: user.clone(),
+            previous_tier: analytics.loyalty_tier,
+            tier,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    analytics.loyalty_tier = tier;
+}
+
+/// Highest tier the analytics reach, numbered from 1, and its discount
+fn tier_of(env: &Env, analytics: &UserAnalytics) -> (u32, i128) {
+    let volume = analytics
+        .total_deposits
+        .saturating_add(analytics.total_withdrawals)
+        .saturating_add(analytics.total_borrows)
+        .saturating_add(analytics.total_repayments);
+
+    let mut reached = (0, 0);
+    for (i, tier) in get_loyalty_tiers(env).iter().enumerate() {
+        if analytics.transaction_count < tier.min_transactions || volume < tier.min_volume {
+            break;
+        }
+        reached = (i as u32 + 1, tier.discount_bps);
+    }
+    reached
+}
@@ -0,0 +1,227 @@
+//! # LP Share Collateral
+//!
+//! Lists AMM liquidity pool share tokens as cross-asset collateral, so
+//! liquidity providers can borrow against their pool positions.
+//!
+//! ## Pricing
+//! A share token is priced by this module, not by `update_asset_price`. Its
+//! price is the pool's fair value per share: `2 * sqrt(value_a * value_b)`
+//! divided by the pool's total shares, where `value_a` and `value_b` are the
+//! pool's reserves of its two underlying assets valued at their market
+//! prices: the `AssetConfig` price of each underlying, which follows the
+//! oracle's published median and any active emergency override (see
+//! `cross_asset`). For a constant-product pool this is the value the
+//! reserves would have if the pool traded at those prices, so moving the
+//! reserves along the curve, e.g. with a flash swap, cannot raise it.
+//!
+//! The pool contract must expose `get_reserves() -> (i128, i128)`, ordered as
+//! the underlying assets are configured, and `total_shares() -> i128`. Both
+//! underlying assets must be listed markets.
+//!
+//! [`refresh_lp_price`] recomputes the price from the current reserves and
+//! underlying prices; anyone may call it. The recorded price is dated as of
+//! the older of the two underlying prices, so it goes stale with them and
+//! operations on the position fail with `PriceStale` until it is refreshed.
+//!
+//! ## Haircut
+//! A share token is listed as collateral only. Its default collateral factor
+//! is the lower of its underlying assets' factors, reduced by a further
+//! [`LP_COLLATERAL_HAIRCUT_BPS`] for impermanent loss and the cost of
+//! unwinding the pool position in a liquidation. `update_asset_config` can
+//! change it afterwards.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol, Val, Vec};
+
+use crate::constants::{BASIS_POINTS_SCALE, MAX_PRICE_AGE};
+use crate::cross_asset::{self, AssetConfig, AssetKey, CrossAssetError};
+use crate::events::{emit_lp_price_refreshed, LpPriceRefreshedEvent};
+use crate::roles::Role;
+use crate::storage;
+
+/// Share of the lower underlying collateral factor withheld from a share
+/// token's default collateral factor, in basis points
+pub const LP_COLLATERAL_HAIRCUT_BPS: i128 = 2_000;
+
+/// Scale of cross-asset prices (7 decimals)
+const PRICE_SCALE: i128 = 10_000_000;
+
+/// Storage keys for LP share collateral
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum LpCollateralDataKey {
+    /// Pool behind a listed share token: LpPool
+    Pool(Address),
+}
+
+/// Pool whose reserves back a share token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LpPool {
+    /// Pool contract reporting reserves and total shares
+    pub pool: Address,
+    /// First underlying asset (`None` for XLM)
+    pub asset_a: Option<Address>,
+    /// Second underlying asset (`None` for XLM)
+    pub asset_b: Option<Address>,
+}
+
+/// List a pool share token as collateral, priced from its pool
+///
+/// # Arguments
+/// * `caller` - The cross-asset admin or an `AssetListingAdmin`
+/// * `lp_token` - The share token
+/// * `pool` - The pool and its underlying assets
+///
+/// # Returns
+/// The share token's [`AssetConfig`].
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is neither the admin nor an `AssetListingAdmin`
+/// * `AssetNotConfigured` - An underlying asset is not listed
+/// * `PriceStale` - An underlying price is stale
+/// * `PoolQueryFailed` - The pool did not report positive reserves and shares
+/// * `UnsupportedToken` - The share token lacks a required interface function
+pub fn list_lp_token(
+    env: &Env,
+    caller: Address,
+    lp_token: Address,
+    pool: LpPool,
+) -> Result<AssetConfig, CrossAssetError> {
+    cross_asset::require_role(env, &caller, Role::AssetListingAdmin)?;
+
+    let config_a =
+        cross_asset::get_asset_config(env, &AssetKey::from_option(pool.asset_a.clone()))?;
+    let config_b =
+        cross_asset::get_asset_config(env, &AssetKey::from_option(pool.asset_b.clone()))?;
+    let (_, price, price_updated_at) = fair_price(env, &pool)?;
+    let scale = BASIS_POINTS_SCALE.get();
+    let collateral_factor = config_a.collateral_factor.min(config_b.collateral_factor)
+        * (scale - LP_COLLATERAL_HAIRCUT_BPS)
+        / scale;
+
+    let config = AssetConfig {
+        asset: Some(lp_token.clone()),
+        collateral_factor,
+        borrow_factor: scale,
+        reserve_factor: 0,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: false,
+        price,
+        price_updated_at,
+    };
+    storage::set(env, &LpCollateralDataKey::Pool(lp_token.clone()), &pool);
+    cross_asset::register_asset(env, caller, Some(lp_token), config.clone())?;
+    Ok(config)
+}
+
+/// Pool behind a share token, if it is listed as one
+pub fn get_lp_pool(env: &Env, lp_token: &Address) -> Option<LpPool> {
+    storage::get(env, &LpCollateralDataKey::Pool(lp_token.clone()))
+}
+
+/// Recompute a share token's price from its pool's current reserves and the
+/// underlying prices (permissionless)
+///
+/// # Returns
+/// The new price.
+///
+/// # Errors
+/// * `NotLpToken` - The asset is not a listed share token
+/// * `PriceStale` - An underlying price is stale
+/// * `PoolQueryFailed` - The pool did not report positive reserves and shares
+/// * `InvalidPrice` - A share is worth less than the smallest price unit
+/// * `Overflow` - The pool's value overflowed
+pub fn refresh_lp_price(env: &Env, lp_token: Address) -> Result<i128, CrossAssetError> {
+    let pool = get_lp_pool(env, &lp_token).ok_or(CrossAssetError::NotLpToken)?;
+    let (pool_value, price, price_updated_at) = fair_price(env, &pool)?;
+    cross_asset::set_asset_price(env, Some(lp_token.clone()), price, price_updated_at)?;
+
+    emit_lp_price_refreshed(
+        env,
+        LpPriceRefreshedEvent {
+            asset: lp_token,
+            pool_value,
+            price,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(price)
+}
+
+/// The pool's fair value, its value per share and the time of the older
+/// underlying price
+fn fair_price(env: &Env, pool: &LpPool) -> Result<(i128, i128, u64), CrossAssetError> {
+    let config_a =
+        cross_asset::get_asset_config(env, &AssetKey::from_option(pool.asset_a.clone()))?;
+    let config_b =
+        cross_asset::get_asset_config(env, &AssetKey::from_option(pool.asset_b.clone()))?;
+    let updated_at = config_a.price_updated_at.min(config_b.price_updated_at);
+    let now = env.ledger().timestamp();
+    if now > updated_at && now - updated_at > MAX_PRICE_AGE.get() {
+        return Err(CrossAssetError::PriceStale);
+    }
+
+    let (reserve_a, reserve_b, shares) = query_pool(env, &pool.pool)?;
+    let value_a = reserve_a
+        .checked_mul(config_a.price)
+        .ok_or(CrossAssetError::Overflow)?
+        / PRICE_SCALE;
+    let value_b = reserve_b
+        .checked_mul(config_b.price)
+        .ok_or(CrossAssetError::Overflow)?
+        / PRICE_SCALE;
+    // Splitting the root only rounds the value down
+    let root = match value_a.checked_mul(value_b) {
+        Some(product) => isqrt(product),
+        None => isqrt(value_a) * isqrt(value_b),
+    };
+    let pool_value = root.checked_mul(2).ok_or(CrossAssetError::Overflow)?;
+    let price = pool_value
+        .checked_mul(PRICE_SCALE)
+        .ok_or(CrossAssetError::Overflow)?
+        / shares;
+    if price <= 0 {
+        return Err(CrossAssetError::InvalidPrice);
+    }
+    Ok((pool_value, price, updated_at))
+}
+
+/// A pool's reserves and total shares, all positive
+fn query_pool(env: &Env, pool: &Address) -> Result<(i128, i128, i128), CrossAssetError> {
+    let no_args: Vec<Val> = Vec::new(env);
+    let reserves = env.try_invoke_contract::<(i128, i128), soroban_sdk::Error>(
+        pool,
+        &Symbol::new(env, "get_reserves"),
+        no_args.clone(),
+    );
+    let shares = env.try_invoke_contract::<i128, soroban_sdk::Error>(
+        pool,
+        &Symbol::new(env, "total_shares"),
+        no_args,
+    );
+    match (reserves, shares) {
+        (Ok(Ok((reserve_a, reserve_b))), Ok(Ok(shares)))
+            if reserve_a > 0 && reserve_b > 0 && shares > 0 =>
+        {
+            Ok((reserve_a, reserve_b, shares))
+        }
+        _ => Err(CrossAssetError::PoolQueryFailed),
+    }
+}
+
+/// Integer square root, rounded down
+fn isqrt(value: i128) -> i128 {
+    if value < 2 {
+        return value.max(0);
+    }
+    let mut x = value;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
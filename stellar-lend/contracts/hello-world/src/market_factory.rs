@@ -0,0 +1,217 @@
+//! # Market Factory
+//!
+//! Deploys isolated-market contracts from an uploaded wasm and keeps an
+//! on-chain registry of them, so a new market does not need manual deploy
+//! tooling.
+//!
+//! The protocol admin uploads the market wasm and points the factory at it
+//! with [`set_market_wasm`]. An asset listing admin then calls
+//! [`create_isolated_market`], which deploys a fresh instance with its admin
+//! passed to the market's constructor (so nobody can claim it first; the new
+//! admin must authorize the deployment), and records it in the registry. The market admin
+//! configures the market's assets on the market contract itself.
+//!
+//! ## Addresses
+//! Each market is deployed from this contract with a salt derived from its
+//! registry id, so market addresses are deterministic and never collide.
+
+use soroban_sdk::{contracterror, contracttype, Address, BytesN, Env, Symbol, Vec};
+
+use crate::events::{
+    emit_market_created, emit_market_wasm_updated, MarketCreatedEvent, MarketWasmUpdatedEvent,
+};
+use crate::roles::{self, Role};
+use crate::storage;
+
+/// Maximum number of markets returned per page by [`get_isolated_markets`]
+pub const MAX_MARKETS_PAGE: u32 = 50;
+
+/// Errors that can occur during market factory operations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MarketFactoryError {
+    /// Caller lacks the required role
+    Unauthorized = 1,
+    /// No market wasm has been set
+    WasmNotSet = 2,
+    /// The market name is already registered
+    NameTaken = 3,
+}
+
+/// Storage keys for market factory data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum MarketFactoryDataKey {
+    /// Wasm hash new markets are deployed from: BytesN<32>
+    MarketWasm,
+    /// Registered market by id: IsolatedMarket
+    Market(u32),
+    /// Market id by name: u32
+    MarketByName(Symbol),
+    /// Number of registered markets: u32
+    MarketCount,
+}
+
+/// Parameters for a new isolated market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IsolatedMarketParams {
+    /// Admin of the deployed market
+    pub admin: Address,
+    /// Unique name the market is registered under
+    pub name: Symbol,
+    /// Assets the market is intended to list
+    pub assets: Vec<Address>,
+}
+
+/// A registered isolated market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IsolatedMarket {
+    /// Registry id
+    pub id: u32,
+    /// Address of the deployed market contract
+    pub address: Address,
+    /// Admin of the market
+    pub admin: Address,
+    /// Name the market is registered under
+    pub name: Symbol,
+    /// Assets the market is intended to list
+    pub assets: Vec<Address>,
+    /// Wasm hash the market was deployed from
+    pub wasm_hash: BytesN<32>,
+    /// Ledger timestamp of the deployment
+    pub created_at: u64,
+}
+
+/// Set the wasm new isolated markets are deployed from (protocol admin only)
+///
+/// The wasm must already be uploaded to the network. Markets deployed earlier
+/// keep the code they were deployed with.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+pub fn set_market_wasm(
+    env: &Env,
+    caller: Address,
+    wasm_hash: BytesN<32>,
+) -> Result<(), MarketFactoryError> {
+    roles::require_protocol_admin(env, &caller).map_err(|_| MarketFactoryError::Unauthorized)?;
+
+    storage::set(env, &MarketFactoryDataKey::MarketWasm, &wasm_hash);
+
+    emit_market_wasm_updated(
+        env,
+        MarketWasmUpdatedEvent {
+            admin: caller,
+            wasm_hash,
+        },
+    );
+    Ok(())
+}
+
+/// Get the wasm hash new markets are deployed from, if set
+pub fn get_market_wasm(env: &Env) -> Option<BytesN<32>> {
+    storage::get(env, &MarketFactoryDataKey::MarketWasm)
+}
+
+/// Deploy and register a new isolated market (asset listing admin only)
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `caller` - Account creating the market (must hold AssetListingAdmin)
+/// * `params` - Admin, name and assets of the new market
+///
+/// # Returns
+/// The registry record of the new market.
+///
+/// # Errors
+/// * `Unauthorized` - Caller lacks the AssetListingAdmin role
+/// * `WasmNotSet` - No market wasm has been set
+/// * `NameTaken` - A market with this name is already registered
+pub fn create_isolated_market(
+    env: &Env,
+    caller: Address,
+    params: IsolatedMarketParams,
+) -> Result<IsolatedMarket, MarketFactoryError> {
+    roles::require_role(env, &caller, Role::AssetListingAdmin)
+        .map_err(|_| MarketFactoryError::Unauthorized)?;
+
+    let wasm_hash = get_market_wasm(env).ok_or(MarketFactoryError::WasmNotSet)?;
+    let name_key = MarketFactoryDataKey::MarketByName(params.name.clone());
+    if storage::has(env, &name_key) {
+        return Err(MarketFactoryError::NameTaken);
+    }
+
+    let id = get_isolated_market_count(env);
+    let address = env
+        .deployer()
+        .with_current_contract(market_salt(env, id))
+        .deploy_v2(wasm_hash.clone(), (params.admin.clone(),));
+
+    let record = IsolatedMarket {
+        id,
+        address: address.clone(),
+        admin: params.admin.clone(),
+        name: params.name,
+        assets: params.assets,
+        wasm_hash: wasm_hash.clone(),
+        created_at: env.ledger().timestamp(),
+    };
+    storage::set(env, &MarketFactoryDataKey::Market(id), &record);
+    storage::set(env, &name_key, &id);
+    storage::set(env, &MarketFactoryDataKey::MarketCount, &(id + 1));
+
+    emit_market_created(
+        env,
+        MarketCreatedEvent {
+            id,
+            market: address,
+            admin: params.admin,
+            wasm_hash,
+            timestamp: record.created_at,
+        },
+    );
+    Ok(record)
+}
+
+/// Get a registered market by id
+pub fn get_isolated_market(env: &Env, id: u32) -> Option<IsolatedMarket> {
+    storage::get(env, &MarketFactoryDataKey::Market(id))
+}
+
+/// Get a registered market by name
+pub fn get_isolated_market_by_name(env: &Env, name: Symbol) -> Option<IsolatedMarket> {
+    let id: u32 = storage::get(env, &MarketFactoryDataKey::MarketByName(name))?;
+    get_isolated_market(env, id)
+}
+
+/// Number of registered markets
+pub fn get_isolated_market_count(env: &Env) -> u32 {
+    storage::get(env, &MarketFactoryDataKey::MarketCount).unwrap_or(0)
+}
+
+/// Page through registered markets in creation order
+///
+/// Returns up to `limit` markets (at most [`MAX_MARKETS_PAGE`]) starting at
+/// registry id `offset`.
+pub fn get_isolated_markets(env: &Env, limit: u32, offset: u32) -> Vec<IsolatedMarket> {
+    let end =
+        get_isolated_market_count(env).min(offset.saturating_add(limit.min(MAX_MARKETS_PAGE)));
+    let mut markets = Vec::new(env);
+    for id in offset..end {
+        if let Some(record) = get_isolated_market(env, id) {
+            markets.push_back(record);
+        }
+    }
+    markets
+}
+
+/// Deployment salt for the market with registry id `id`
+fn market_salt(env: &Env, id: u32) -> BytesN<32> {
+    let mut salt = [0u8; 32];
+    salt[28..].copy_from_slice(&id.to_be_bytes());
+    BytesN::from_array(env, &salt)
+}
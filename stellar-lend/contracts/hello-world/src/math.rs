@@ -0,0 +1,157 @@
+//! # Checked Arithmetic
+//!
+//! Newtypes for token amounts and vault shares with checked arithmetic.
+//!
+//! Every operation returns a [`MathError`] instead of panicking, wrapping, or
+//! saturating, so overflow is handled the same way everywhere. Module error
+//! enums implement `From<MathError>`, which lets call sites propagate failures
+//! with `?`. Basis point scaling goes through [`Bps::apply`].
+//!
+//! Storage structs keep plain `i128` fields; values are wrapped for the
+//! calculation and unwrapped with `.get()` when written back.
+
+#![allow(unused)]
+use crate::constants::{Bps, BASIS_POINTS_SCALE};
+
+/// Failure of a checked arithmetic operation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MathError {
+    /// Result does not fit in an `i128`
+    Overflow,
+    /// Division by zero
+    DivisionByZero,
+}
+
+/// `value * numerator / denominator`, checked
+fn mul_div(value: i128, numerator: i128, denominator: i128) -> Result<i128, MathError> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    value
+        .checked_mul(numerator)
+        .ok_or(MathError::Overflow)?
+        .checked_div(denominator)
+        .ok_or(MathError::Overflow)
+}
+
+/// A token amount in the asset's native units
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Amount(i128);
+
+impl Amount {
+    /// Zero amount
+    pub const ZERO: Amount = Amount(0);
+
+    /// Wrap a raw amount
+    pub const fn new(value: i128) -> Self {
+        Amount(value)
+    }
+
+    /// Raw amount
+    pub const fn get(self) -> i128 {
+        self.0
+    }
+
+    /// `self + other`
+    pub fn checked_add(self, other: Amount) -> Result<Amount, MathError> {
+        self.0
+            .checked_add(other.0)
+            .map(Amount)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// `self - other`
+    pub fn checked_sub(self, other: Amount) -> Result<Amount, MathError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Amount)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// `self * numerator / denominator`, rounding toward zero
+    pub fn mul_div(self, numerator: i128, denominator: i128) -> Result<Amount, MathError> {
+        mul_div(self.0, numerator, denominator).map(Amount)
+    }
+
+    /// `self * numerator / denominator`, rounding up
+    pub fn mul_div_ceil(self, numerator: i128, denominator: i128) -> Result<Amount, MathError> {
+        let quotient = mul_div(self.0, numerator, denominator)?;
+        // mul_div has checked the product and the division
+        let product = self.0 * numerator;
+        if product % denominator != 0 && (product > 0) == (denominator > 0) {
+            Ok(Amount(quotient + 1))
+        } else {
+            Ok(Amount(quotient))
+        }
+    }
+
+    /// Ratio of `self` to `other` in basis points
+    pub fn ratio_bps(self, other: Amount) -> Result<Bps, MathError> {
+        mul_div(self.0, BASIS_POINTS_SCALE.get(), other.0).map(Bps::new)
+    }
+
+    /// Convert to shares given pool totals, rounding down
+    pub fn to_shares(
+        self,
+        total_amount: Amount,
+        total_shares: Shares,
+    ) -> Result<Shares, MathError> {
+        mul_div(self.0, total_shares.0, total_amount.0).map(Shares)
+    }
+}
+
+/// A claim on a share of a pool, as opposed to an amount of the pool's asset
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Shares(i128);
+
+impl Shares {
+    /// Zero shares
+    pub const ZERO: Shares = Shares(0);
+
+    /// Wrap a raw share count
+    pub const fn new(value: i128) -> Self {
+        Shares(value)
+    }
+
+    /// Raw share count
+    pub const fn get(self) -> i128 {
+        self.0
+    }
+
+    /// `self + other`
+    pub fn checked_add(self, other: Shares) -> Result<Shares, MathError> {
+        self.0
+            .checked_add(other.0)
+            .map(Shares)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// `self - other`
+    pub fn checked_sub(self, other: Shares) -> Result<Shares, MathError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Shares)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// `self * numerator / denominator`, rounding toward zero
+    pub fn mul_div(self, numerator: i128, denominator: i128) -> Result<Shares, MathError> {
+        mul_div(self.0, numerator, denominator).map(Shares)
+    }
+
+    /// Convert to the underlying amount given pool totals
+    pub fn to_amount(
+        self,
+        total_amount: Amount,
+        total_shares: Shares,
+    ) -> Result<Amount, MathError> {
+        mul_div(self.0, total_amount.0, total_shares.0).map(Amount)
+    }
+}
+
+impl Bps {
+    /// `amount * self / 10_000`, rounding toward zero
+    pub fn apply(self, amount: Amount) -> Result<Amount, MathError> {
+        amount.mul_div(self.get(), BASIS_POINTS_SCALE.get())
+    }
+}
@@ -0,0 +1,75 @@
+//! # Operational Metrics
+//!
+//! Per-entrypoint call counters so operators can spot anomalies, such as a
+//! surge of liquidations or a market whose borrows stopped, from on-chain
+//! state alone.
+//!
+//! Each state-changing user entrypoint calls [`record_call`] on entry, which
+//! costs one persistent read and write of a small counter. The first call of
+//! an entrypoint also adds its name to the list [`get_ops_metrics`] reads.
+//!
+//! Only successful calls are counted: a failed invocation rolls back every
+//! write it made, its counter increment included. Failure rates come from
+//! transaction results, where each entrypoint's typed error code is reported.
+
+use soroban_sdk::{contracttype, Env, Map, Symbol, Vec};
+
+use crate::storage;
+
+/// Storage keys for operational metrics
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum OpsMetricsDataKey {
+    /// Entrypoints called at least once, in first-call order: Vec<Symbol>
+    Entrypoints,
+    /// Counters of one entrypoint: EntrypointMetrics
+    Calls(Symbol),
+}
+
+/// Counters of one entrypoint
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntrypointMetrics {
+    /// Successful calls
+    pub calls: u64,
+    /// Ledger timestamp of the last successful call
+    pub last_call: u64,
+}
+
+/// Count a call of entrypoint `name`
+pub(crate) fn record_call(env: &Env, name: &str) {
+    let name = Symbol::new(env, name);
+    let key = OpsMetricsDataKey::Calls(name.clone());
+    let calls = match storage::get::<_, EntrypointMetrics>(env, &key) {
+        Some(metrics) => metrics.calls.saturating_add(1),
+        None => {
+            let mut names: Vec<Symbol> =
+                storage::get(env, &OpsMetricsDataKey::Entrypoints).unwrap_or(Vec::new(env));
+            names.push_back(name);
+            storage::set(env, &OpsMetricsDataKey::Entrypoints, &names);
+            1
+        }
+    };
+    storage::set(
+        env,
+        &key,
+        &EntrypointMetrics {
+            calls,
+            last_call: env.ledger().timestamp(),
+        },
+    );
+}
+
+/// Get the counters of every entrypoint called so far, by entrypoint name
+pub fn get_ops_metrics(env: &Env) -> Map<Symbol, EntrypointMetrics> {
+    let mut metrics = Map::new(env);
+    let names: Vec<Symbol> =
+        storage::get(env, &OpsMetricsDataKey::Entrypoints).unwrap_or(Vec::new(env));
+    for name in names.iter() {
+        if let Some(entry) = storage::get(env, &OpsMetricsDataKey::Calls(name.clone())) {
+            metrics.set(name, entry);
+        }
+    }
+    metrics
+}
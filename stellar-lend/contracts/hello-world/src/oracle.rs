@@ -4,21 +4,48 @@
 //! guards, caching, and fallback oracle support.
 //!
 //! ## Price Resolution Order
-//! 1. **Cache**: returns a cached price if the TTL has not expired.
-//! 2. **Primary feed**: reads the on-chain `PriceFeed` entry; rejects if stale.
-//! 3. **Fallback oracle**: if the primary is stale or missing, queries a
+//! 1. **Emergency override**: an admin-set price that has not yet expired.
+//! 2. **Source median**: if the asset has oracle sources, the median of their
+//!    fresh prices, as long as enough of them are fresh.
+//! 3. **Cache**: returns a cached price if the TTL has not expired.
+//! 4. **Primary feed**: reads the on-chain `PriceFeed` entry; rejects if stale.
+//! 5. **Fallback oracle**: if the primary is stale or missing, queries a
 //!    configured fallback oracle address.
 //!
+//! ## Oracle Sources
+//! The admin or an `AssetListingAdmin` may give an asset up to
+//! [`MAX_ORACLE_SOURCES`] sources and a quorum, `min_fresh`. Each source
+//! pushes its own feed with `update_price_feed`, deviation guarded against
+//! its previous price. Once at least `min_fresh` feeds are no older than the
+//! staleness threshold, their median is the effective price, so no single
+//! source moves it alone; with fewer, resolution continues with the cache
+//! and the single feeds. The median is recomputed on every read rather than
+//! cached, and each source update that reaches quorum also prices the
+//! asset's cross-asset market (see `cross_asset`).
+//! [`get_price_detail`] shows each source's price and freshness next to the
+//! effective price.
+//!
 //! ## Safety
 //! - Price deviation between consecutive updates is bounded (default ±5%).
 //! - Staleness threshold defaults to 1 hour; configurable by admin.
 //! - Sanity-check bounds on min/max price are enforced on every update.
 //! - Only the admin or the designated oracle address may submit price updates.
+//! - Emergency overrides skip the deviation guard but always expire after
+//!   1 hour; every override is counted and reported in protocol analytics.
 
 #![allow(unused)]
+use crate::constants::{BASIS_POINTS_SCALE, SECONDS_PER_HOUR};
+use crate::cross_asset;
 use crate::deposit::DepositDataKey;
-use crate::events::{emit_price_updated, PriceUpdatedEvent};
+use crate::events::{
+    emit_admin_action, emit_emergency_price_cleared, emit_emergency_price_set,
+    emit_oracle_source_updated, emit_oracle_sources_set, emit_price_updated, AdminActionEvent,
+    EmergencyPriceClearedEvent, EmergencyPriceSetEvent, OracleSourceUpdatedEvent,
+    OracleSourcesSetEvent, PriceUpdatedEvent,
+};
 use crate::risk_management::get_admin;
+use crate::roles::{require_role, Role};
+use crate::storage;
 use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
 /// Errors that can occur during oracle operations
@@ -65,6 +92,14 @@ pub enum OracleDataKey {
     OracleConfig,
     /// Pause switches for oracle operations
     PauseSwitches,
+    /// Emergency price overrides: Map<Address, EmergencyPrice>
+    EmergencyPrice(Address),
+    /// Number of emergency price overrides ever set: u32
+    EmergencyPriceCount,
+    /// Oracle sources aggregated by median: OracleSources
+    Sources(Address),
+    /// Price feed pushed by one source for an asset: PriceFeed
+    SourceFeed(Address, Address),
 }
 
 /// Price feed data structure
@@ -109,9 +144,71 @@ pub struct OracleConfig {
     pub max_price: i128,
 }
 
+/// Admin-set price used while a feed is failing
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmergencyPrice {
+    /// Override price
+    pub price: i128,
+    /// Timestamp when the override was set
+    pub set_at: u64,
+    /// Timestamp after which the override is ignored
+    pub expires_at: u64,
+    /// Admin that set the override
+    pub actor: Address,
+}
+
+/// Oracle sources aggregated by median for an asset
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OracleSources {
+    /// Source addresses, in configuration order
+    pub sources: Vec<Address>,
+    /// Fresh source prices needed before the median applies
+    pub min_fresh: u32,
+}
+
+/// One oracle source's latest price for an asset
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourcePrice {
+    /// Oracle source address
+    pub source: Address,
+    /// Latest price, or `None` if the source has not reported
+    pub price: Option<i128>,
+    /// Timestamp of the latest price (0 if none)
+    pub last_updated: u64,
+    /// Whether the price counts towards the median
+    pub is_fresh: bool,
+}
+
+/// How an asset's price is derived from its oracle sources
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceDetail {
+    /// Price `get_price` resolves to, or `None` if it fails
+    pub price: Option<i128>,
+    /// Median of the fresh source prices, if at least `min_fresh` are fresh
+    pub median: Option<i128>,
+    /// Fresh source prices needed before the median applies
+    pub min_fresh: u32,
+    /// Every configured source, in configuration order
+    pub sources: Vec<SourcePrice>,
+}
+
+/// Lifetime of an emergency price override
+pub const EMERGENCY_PRICE_TTL_SECONDS: u64 = SECONDS_PER_HOUR.get();
+
+/// Decimals of prices that carry none of their own, such as emergency
+/// overrides: the 8 the protocol's feeds report in
+pub const DEFAULT_PRICE_DECIMALS: u32 = 8;
+
+/// Maximum number of oracle sources per asset
+pub const MAX_ORACLE_SOURCES: u32 = 5;
+
 /// Default configuration values
 const DEFAULT_MAX_DEVIATION_BPS: i128 = 500; // 5%
-const DEFAULT_MAX_STALENESS_SECONDS: u64 = 3600; // 1 hour
+const DEFAULT_MAX_STALENESS_SECONDS: u64 = SECONDS_PER_HOUR.get();
 const DEFAULT_CACHE_TTL_SECONDS: u64 = 300; // 5 minutes
 const DEFAULT_MIN_PRICE: i128 = 1;
 const DEFAULT_MAX_PRICE: i128 = i128::MAX;
@@ -128,11 +225,9 @@ fn get_default_config() -> OracleConfig {
 }
 
 /// Get oracle configuration
-fn get_oracle_config(env: &Env) -> OracleConfig {
+pub(crate) fn get_oracle_config(env: &Env) -> OracleConfig {
     let config_key = OracleDataKey::OracleConfig;
-    env.storage()
-        .persistent()
-        .get::<OracleDataKey, OracleConfig>(&config_key)
+    storage::get_instance::<OracleDataKey, OracleConfig>(env, &config_key)
         .unwrap_or_else(get_default_config)
 }
 
@@ -153,7 +248,7 @@ fn get_fallback_oracle(env: &Env, asset: &Address) -> Option<Address> {
 }
 
 /// Validate price against sanity checks
-fn validate_price(env: &Env, price: i128) -> Result<(), OracleError> {
+pub(crate) fn validate_price(env: &Env, price: i128) -> Result<(), OracleError> {
     if price <= 0 {
         return Err(OracleError::InvalidPrice);
     }
@@ -199,7 +294,7 @@ fn check_price_deviation(env: &Env, new_price: i128, old_price: i128) -> Result<
     };
 
     let deviation_bps = diff
-        .checked_mul(10000)
+        .checked_mul(BASIS_POINTS_SCALE.get())
         .ok_or(OracleError::Overflow)?
         .checked_div(old_price)
         .ok_or(OracleError::Overflow)?;
@@ -216,7 +311,7 @@ fn get_cached_price(env: &Env, asset: &Address) -> Option<i128> {
     let cache_key = OracleDataKey::PriceCache(asset.clone());
     if let Some(cached) = env
         .storage()
-        .persistent()
+        .temporary()
         .get::<OracleDataKey, CachedPrice>(&cache_key)
     {
         let current_time = env.ledger().timestamp();
@@ -238,7 +333,7 @@ fn cache_price(env: &Env, asset: &Address, price: i128) {
         cached_at: env.ledger().timestamp(),
         ttl: config.cache_ttl_seconds,
     };
-    env.storage().persistent().set(&cache_key, &cached);
+    env.storage().temporary().set(&cache_key, &cached);
 }
 
 /// Update price feed from oracle
@@ -261,18 +356,8 @@ pub fn update_price_feed(
     decimals: u32,
     oracle: Address,
 ) -> Result<i128, OracleError> {
-    // Check if oracle updates are paused
-    let pause_key = OracleDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<OracleDataKey, Map<Symbol, bool>>(&pause_key)
-    {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_oracle")) {
-            if paused {
-                return Err(OracleError::OraclePaused);
-            }
-        }
+    if is_oracle_paused(env) {
+        return Err(OracleError::OraclePaused);
     }
 
     // Validate caller authorization
@@ -282,8 +367,9 @@ pub fn update_price_feed(
 
     let is_primary = primary.map(|p| p == caller).unwrap_or(false);
     let is_fallback = fallback.map(|f| f == caller).unwrap_or(false);
+    let is_source = get_oracle_sources(env, &asset).sources.contains(&caller);
 
-    if !is_admin && !is_primary && !is_fallback {
+    if !is_admin && !is_primary && !is_fallback && !is_source {
         return Err(OracleError::Unauthorized);
     }
 
@@ -296,7 +382,10 @@ pub fn update_price_feed(
     validate_price(env, price)?;
 
     // Determine target storage key and get current feed for deviation check
-    let feed_key = if is_fallback && !is_primary && !is_admin {
+    let is_source_only = is_source && !is_primary && !is_fallback && !is_admin;
+    let feed_key = if is_source_only {
+        OracleDataKey::SourceFeed(asset.clone(), caller.clone())
+    } else if is_fallback && !is_primary && !is_admin {
         OracleDataKey::FallbackFeed(asset.clone())
     } else {
         OracleDataKey::PriceFeed(asset.clone())
@@ -325,14 +414,16 @@ pub fn update_price_feed(
     // Update storage
     env.storage().persistent().set(&feed_key, &new_feed);
 
-    // Update cache
-    cache_price(env, &asset, price);
+    // Update cache; a single source's price is not the asset's price
+    if !is_source_only {
+        cache_price(env, &asset, price);
+    }
 
     // Emit price update event
     emit_price_updated(
         env,
         PriceUpdatedEvent {
-            actor: caller,
+            actor: caller.clone(),
             asset: asset.clone(),
             price,
             decimals,
@@ -341,9 +432,88 @@ pub fn update_price_feed(
         },
     );
 
+    // Once the sources reach quorum, their median prices the asset's market
+    if is_source {
+        let sources = get_oracle_sources(env, &asset);
+        if let Some(median_price) =
+            source_median(env, &sources, &get_source_prices(env, &asset, &sources))
+        {
+            cross_asset::sync_oracle_price(env, caller, &asset, median_price, decimals);
+        }
+    }
+
     Ok(price)
 }
 
+/// Whether oracle price updates are paused
+pub(crate) fn is_oracle_paused(env: &Env) -> bool {
+    storage::get_instance::<OracleDataKey, Map<Symbol, bool>>(env, &OracleDataKey::PauseSwitches)
+        .and_then(|pause_map| pause_map.get(Symbol::new(env, "pause_oracle")))
+        .unwrap_or(false)
+}
+
+/// Record an aggregated price as the asset's primary feed
+///
+/// The feed's oracle is this contract. Skips the deviation guard, which
+/// bounds a single source; callers aggregate several.
+pub(crate) fn publish_aggregate_price(
+    env: &Env,
+    actor: Address,
+    asset: Address,
+    price: i128,
+    decimals: u32,
+) {
+    let timestamp = env.ledger().timestamp();
+    let oracle = env.current_contract_address();
+    env.storage().persistent().set(
+        &OracleDataKey::PriceFeed(asset.clone()),
+        &PriceFeed {
+            price,
+            last_updated: timestamp,
+            oracle: oracle.clone(),
+            decimals,
+        },
+    );
+    cache_price(env, &asset, price);
+
+    emit_price_updated(
+        env,
+        PriceUpdatedEvent {
+            actor: actor.clone(),
+            asset: asset.clone(),
+            price,
+            decimals,
+            oracle,
+            timestamp,
+        },
+    );
+    cross_asset::sync_oracle_price(env, actor, &asset, price, decimals);
+}
+
+/// Median of `values`, or `None` if there are none
+///
+/// With an even number of values, the mean of the middle two, rounded down.
+pub(crate) fn median(values: &Vec<i128>) -> Option<i128> {
+    let mut sorted: Vec<i128> = Vec::new(values.env());
+    for value in values.iter() {
+        let position = sorted.iter().position(|other| other > value);
+        match position {
+            Some(index) => sorted.insert(index as u32, value),
+            None => sorted.push_back(value),
+        }
+    }
+    let len = sorted.len();
+    if len == 0 {
+        return None;
+    }
+    let upper = sorted.get_unchecked(len / 2);
+    if len % 2 == 1 {
+        return Some(upper);
+    }
+    let lower = sorted.get_unchecked(len / 2 - 1);
+    Some(lower + (upper - lower) / 2)
+}
+
 /// Get price for an asset with fallback support
 ///
 /// # Arguments
@@ -353,6 +523,19 @@ pub fn update_price_feed(
 /// # Returns
 /// Returns the current price, using cache or fallback if needed
 pub fn get_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
+    // An active emergency override takes precedence over every feed
+    if let Some(emergency) = get_emergency_price(env, asset) {
+        return Ok(emergency.price);
+    }
+
+    // Sources, once enough are fresh, take precedence over the single feeds
+    let sources = get_oracle_sources(env, asset);
+    if let Some(median_price) =
+        source_median(env, &sources, &get_source_prices(env, asset, &sources))
+    {
+        return Ok(median_price);
+    }
+
     // Try cache first
     if let Some(cached_price) = get_cached_price(env, asset) {
         return Ok(cached_price);
@@ -386,6 +569,62 @@ pub fn get_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
     get_fallback_price(env, asset)
 }
 
+/// Median of the sources' fresh prices, if at least the quorum is fresh
+fn source_median(env: &Env, sources: &OracleSources, prices: &Vec<SourcePrice>) -> Option<i128> {
+    let mut fresh = Vec::new(env);
+    for source in prices.iter() {
+        if let (Some(price), true) = (source.price, source.is_fresh) {
+            fresh.push_back(price);
+        }
+    }
+    if fresh.len() < sources.min_fresh {
+        return None;
+    }
+    median(&fresh)
+}
+
+/// Latest price of each of the asset's sources
+fn get_source_prices(env: &Env, asset: &Address, sources: &OracleSources) -> Vec<SourcePrice> {
+    let mut prices = Vec::new(env);
+    for source in sources.sources.iter() {
+        let feed =
+            env.storage()
+                .persistent()
+                .get::<OracleDataKey, PriceFeed>(&OracleDataKey::SourceFeed(
+                    asset.clone(),
+                    source.clone(),
+                ));
+        prices.push_back(match feed {
+            Some(feed) => SourcePrice {
+                source,
+                price: Some(feed.price),
+                last_updated: feed.last_updated,
+                is_fresh: !is_price_stale(env, feed.last_updated),
+            },
+            None => SourcePrice {
+                source,
+                price: None,
+                last_updated: 0,
+                is_fresh: false,
+            },
+        });
+    }
+    prices
+}
+
+/// Get the effective price of an asset with each oracle source's price and
+/// freshness
+pub fn get_price_detail(env: &Env, asset: &Address) -> PriceDetail {
+    let sources = get_oracle_sources(env, asset);
+    let prices = get_source_prices(env, asset, &sources);
+    PriceDetail {
+        price: get_price(env, asset).ok(),
+        median: source_median(env, &sources, &prices),
+        min_fresh: sources.min_fresh,
+        sources: prices,
+    }
+}
+
 /// Get price from fallback oracle
 fn get_fallback_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
     let fallback_key = OracleDataKey::FallbackOracle(asset.clone());
@@ -433,11 +672,22 @@ pub fn set_primary_oracle(
     }
 
     // Set primary oracle
-    let primary_key = OracleDataKey::PrimaryOracle(asset);
+    let primary_key = OracleDataKey::PrimaryOracle(asset.clone());
     env.storage()
         .persistent()
         .set(&primary_key, &primary_oracle);
 
+    emit_oracle_source_updated(
+        env,
+        OracleSourceUpdatedEvent {
+            actor: caller,
+            asset,
+            oracle: primary_oracle,
+            is_fallback: false,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
     Ok(())
 }
 
@@ -467,14 +717,101 @@ pub fn set_fallback_oracle(
     }
 
     // Set fallback oracle
-    let fallback_key = OracleDataKey::FallbackOracle(asset);
+    let fallback_key = OracleDataKey::FallbackOracle(asset.clone());
     env.storage()
         .persistent()
         .set(&fallback_key, &fallback_oracle);
 
+    emit_oracle_source_updated(
+        env,
+        OracleSourceUpdatedEvent {
+            actor: caller,
+            asset,
+            oracle: fallback_oracle,
+            is_fallback: true,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Set the oracle sources aggregated by median for an asset
+///
+/// An empty list removes them, leaving the primary and fallback feeds. Prices
+/// already pushed by a removed source are ignored.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The address calling this function (must be admin or an
+///   `AssetListingAdmin`, and authorize)
+/// * `asset` - The asset address
+/// * `sources` - Up to [`MAX_ORACLE_SOURCES`] distinct source addresses
+/// * `min_fresh` - Fresh source prices needed before the median applies,
+///   from 1 to the number of sources
+pub fn set_oracle_sources(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    sources: Vec<Address>,
+    min_fresh: u32,
+) -> Result<(), OracleError> {
+    // Check authorization
+    require_role(env, &caller, Role::AssetListingAdmin).map_err(|_| OracleError::Unauthorized)?;
+
+    // Validate sources
+    if sources.len() > MAX_ORACLE_SOURCES
+        || (!sources.is_empty() && (min_fresh == 0 || min_fresh > sources.len()))
+    {
+        return Err(OracleError::InvalidOracle);
+    }
+    for (index, source) in sources.iter().enumerate() {
+        if source == env.current_contract_address()
+            || sources.first_index_of(&source) != Some(index as u32)
+        {
+            return Err(OracleError::InvalidOracle);
+        }
+    }
+
+    let sources_key = OracleDataKey::Sources(asset.clone());
+    if sources.is_empty() {
+        env.storage().persistent().remove(&sources_key);
+    } else {
+        env.storage().persistent().set(
+            &sources_key,
+            &OracleSources {
+                sources: sources.clone(),
+                min_fresh,
+            },
+        );
+    }
+
+    emit_oracle_sources_set(
+        env,
+        OracleSourcesSetEvent {
+            actor: caller,
+            asset,
+            sources,
+            min_fresh,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
     Ok(())
 }
 
+/// Get the oracle sources aggregated by median for an asset; none, with a
+/// quorum of 0, if it has none
+pub fn get_oracle_sources(env: &Env, asset: &Address) -> OracleSources {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, OracleSources>(&OracleDataKey::Sources(asset.clone()))
+        .unwrap_or(OracleSources {
+            sources: Vec::new(env),
+            min_fresh: 0,
+        })
+}
+
 /// Configure oracle parameters
 ///
 /// # Arguments
@@ -494,7 +831,7 @@ pub fn configure_oracle(
     }
 
     // Validate configuration
-    if config.max_deviation_bps <= 0 || config.max_deviation_bps > 10000 {
+    if config.max_deviation_bps <= 0 || config.max_deviation_bps > BASIS_POINTS_SCALE.get() {
         return Err(OracleError::InvalidPrice);
     }
 
@@ -504,7 +841,139 @@ pub fn configure_oracle(
 
     // Update configuration
     let config_key = OracleDataKey::OracleConfig;
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "configure_oracle"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     Ok(())
 }
+
+/// Set an emergency price override (admin only)
+///
+/// Intended for incident response when a feed has failed. The override is
+/// not subject to the deviation guard, but it always expires after
+/// [`EMERGENCY_PRICE_TTL_SECONDS`] and every use increments a counter
+/// surfaced in protocol analytics. While active it also prices the asset's
+/// cross-asset market, in [`DEFAULT_PRICE_DECIMALS`] (see `cross_asset`).
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The address calling this function (must be admin)
+/// * `asset` - The asset address
+/// * `price` - The override price (must pass sanity bounds)
+///
+/// # Returns
+/// Returns the stored override
+pub fn set_emergency_price(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    price: i128,
+) -> Result<EmergencyPrice, OracleError> {
+    let admin = get_admin(env).ok_or(OracleError::Unauthorized)?;
+    if caller != admin {
+        return Err(OracleError::Unauthorized);
+    }
+    caller.require_auth();
+
+    validate_price(env, price)?;
+
+    let now = env.ledger().timestamp();
+    let emergency = EmergencyPrice {
+        price,
+        set_at: now,
+        expires_at: now.saturating_add(EMERGENCY_PRICE_TTL_SECONDS),
+        actor: caller.clone(),
+    };
+    env.storage()
+        .persistent()
+        .set(&OracleDataKey::EmergencyPrice(asset.clone()), &emergency);
+
+    let count = get_emergency_price_count(env).saturating_add(1);
+    env.storage()
+        .persistent()
+        .set(&OracleDataKey::EmergencyPriceCount, &count);
+
+    // Drop any cached feed price so the override is visible immediately
+    env.storage()
+        .temporary()
+        .remove(&OracleDataKey::PriceCache(asset.clone()));
+
+    emit_emergency_price_set(
+        env,
+        EmergencyPriceSetEvent {
+            actor: caller,
+            asset,
+            price,
+            expires_at: emergency.expires_at,
+            timestamp: now,
+        },
+    );
+
+    Ok(emergency)
+}
+
+/// Clear an emergency price override before it expires (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The address calling this function (must be admin)
+/// * `asset` - The asset address
+pub fn clear_emergency_price(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+) -> Result<(), OracleError> {
+    let admin = get_admin(env).ok_or(OracleError::Unauthorized)?;
+    if caller != admin {
+        return Err(OracleError::Unauthorized);
+    }
+    caller.require_auth();
+
+    let key = OracleDataKey::EmergencyPrice(asset.clone());
+    if !env.storage().persistent().has(&key) {
+        return Err(OracleError::AssetNotSupported);
+    }
+    env.storage().persistent().remove(&key);
+
+    emit_emergency_price_cleared(
+        env,
+        EmergencyPriceClearedEvent {
+            actor: caller,
+            asset,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the active emergency price override for an asset
+///
+/// Returns `None` if no override was set or it has expired.
+pub fn get_emergency_price(env: &Env, asset: &Address) -> Option<EmergencyPrice> {
+    let emergency = env
+        .storage()
+        .persistent()
+        .get::<OracleDataKey, EmergencyPrice>(&OracleDataKey::EmergencyPrice(asset.clone()))?;
+
+    if env.ledger().timestamp() >= emergency.expires_at {
+        return None;
+    }
+    Some(emergency)
+}
+
+/// Get the number of emergency price overrides ever set
+pub fn get_emergency_price_count(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, u32>(&OracleDataKey::EmergencyPriceCount)
+        .unwrap_or(0)
+}
@@ -0,0 +1,198 @@
+//! # Position Closure
+//!
+//! Lets a user exit the protocol in one transaction instead of repaying,
+//! withdrawing and cleaning up step by step.
+//!
+//! [`close_position`] runs, in order:
+//! 1. Repays the core debt, interest included, by pulling tokens from the user
+//!    in the order of `asset_preferences`, each up to the user's balance. The
+//!    user must have approved the contract to spend them.
+//! 2. Withdraws the core collateral in the same order, each asset up to the
+//!    contract's balance of it.
+//! 3. Repays every cross-asset debt, then withdraws every cross-asset
+//!    collateral balance.
+//! 4. Prunes the account's storage if nothing is left in it (see
+//!    `account_sweep`). Pool supply shares are left alone and keep the
+//!    account from being pruned.
+//!
+//! Each step goes through the regular repay and withdraw paths, so pause
+//! switches, the post-borrow withdraw cooldown and their events all apply.
+//! If any step fails the whole closure is rolled back.
+//!
+//! Rewards are not claimed. They accrue only on pool supply shares (see
+//! `rewards`), which closure leaves alone, so the closed positions earned
+//! none; rewards on the shares keep accruing and are claimed with
+//! `rewards::claim_rewards`.
+
+use soroban_sdk::{contracterror, contracttype, token, Address, Env, Map, Vec};
+
+use crate::account_sweep::prune_if_empty;
+use crate::cross_asset::{self, AssetKey};
+use crate::deposit::{DepositDataKey, Position};
+use crate::events::{emit_position_closed, PositionClosedEvent};
+use crate::repay::repay_debt;
+use crate::storage;
+use crate::withdraw::withdraw_collateral;
+
+/// Errors that can occur while closing a position
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ClosureError {
+    /// The user's balances of the preferred assets do not cover the debt
+    InsufficientRepayBalance = 1,
+    /// The contract's balances of the preferred assets do not cover the collateral
+    InsufficientLiquidity = 2,
+    /// A core repayment was rejected, e.g. because repayments are paused
+    RepayFailed = 3,
+    /// A core withdrawal was rejected, e.g. during the post-borrow cooldown
+    WithdrawFailed = 4,
+    /// A cross-asset repayment or withdrawal was rejected
+    CrossAssetFailed = 5,
+}
+
+/// What [`close_position`] repaid and withdrew
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionClosure {
+    /// Core debt repaid, interest included
+    pub debt_repaid: i128,
+    /// Core collateral withdrawn
+    pub collateral_withdrawn: i128,
+    /// Cross-asset debt repaid per asset, interest included
+    pub asset_debt_repaid: Map<AssetKey, i128>,
+    /// Cross-asset collateral withdrawn per asset
+    pub asset_collateral_withdrawn: Map<AssetKey, i128>,
+    /// Whether the account's storage was pruned
+    pub pruned: bool,
+}
+
+/// Repay all debt, withdraw all collateral and prune the account (user only)
+///
+/// # Arguments
+/// * `user` - The account to close (must authorize)
+/// * `asset_preferences` - Tokens to repay and withdraw core balances in, most
+///   preferred first
+///
+/// # Errors
+/// * `InsufficientRepayBalance` - The preferred assets cannot cover the debt
+/// * `InsufficientLiquidity` - The preferred assets cannot cover the collateral
+/// * `RepayFailed` / `WithdrawFailed` - A core repay or withdraw was rejected
+/// * `CrossAssetFailed` - A cross-asset repay or withdraw was rejected
+pub fn close_position(
+    env: &Env,
+    user: Address,
+    asset_preferences: Vec<Address>,
+) -> Result<PositionClosure, ClosureError> {
+    user.require_auth();
+
+    let debt_repaid = repay_core_debt(env, &user, &asset_preferences)?;
+    let collateral_withdrawn = withdraw_core_collateral(env, &user, &asset_preferences)?;
+
+    let mut asset_debt_repaid = Map::new(env);
+    let mut asset_collateral_withdrawn = Map::new(env);
+    let positions = cross_asset::get_user_asset_positions(env, &user);
+    // Repay everything first so no withdrawal is blocked by remaining debt
+    for asset_key in positions.keys().iter() {
+        let position = cross_asset::accrue_interest(env, &user, asset_key.to_option())
+            .map_err(|_| ClosureError::CrossAssetFailed)?;
+        let debt = position.debt_principal + position.accrued_interest;
+        if debt > 0 {
+            cross_asset::repay_authorized(env, user.clone(), asset_key.to_option(), debt)
+                .map_err(|_| ClosureError::CrossAssetFailed)?;
+            asset_debt_repaid.set(asset_key, debt);
+        }
+    }
+    for (asset_key, position) in positions.iter() {
+        if position.collateral > 0 {
+            cross_asset::withdraw_authorized(
+                env,
+                user.clone(),
+                asset_key.to_option(),
+                position.collateral,
+            )
+            .map_err(|_| ClosureError::CrossAssetFailed)?;
+            asset_collateral_withdrawn.set(asset_key, position.collateral);
+        }
+    }
+
+    let pruned = prune_if_empty(env, &user);
+
+    emit_position_closed(
+        env,
+        PositionClosedEvent {
+            user,
+            debt_repaid,
+            collateral_withdrawn,
+            pruned,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(PositionClosure {
+        debt_repaid,
+        collateral_withdrawn,
+        asset_debt_repaid,
+        asset_collateral_withdrawn,
+        pruned,
+    })
+}
+
+/// Repay the core debt from the preferred assets; returns the amount repaid
+fn repay_core_debt(
+    env: &Env,
+    user: &Address,
+    asset_preferences: &Vec<Address>,
+) -> Result<i128, ClosureError> {
+    let Some(position) = storage::get::<_, Position>(env, &DepositDataKey::Position(user.clone()))
+    else {
+        return Ok(0);
+    };
+    if position.debt == 0 && position.borrow_interest == 0 {
+        return Ok(0);
+    }
+
+    let mut repaid = 0;
+    for asset in asset_preferences.iter() {
+        let balance = token::Client::new(env, &asset).balance(user);
+        if balance <= 0 {
+            continue;
+        }
+        // Capped at the total debt, interest accrued up to now included
+        let (remaining, interest_paid, principal_paid) =
+            repay_debt(env, user.clone(), Some(asset), balance)
+                .map_err(|_| ClosureError::RepayFailed)?;
+        repaid += interest_paid + principal_paid;
+        if remaining == 0 {
+            return Ok(repaid);
+        }
+    }
+    Err(ClosureError::InsufficientRepayBalance)
+}
+
+/// Withdraw the core collateral in the preferred assets; returns the amount withdrawn
+fn withdraw_core_collateral(
+    env: &Env,
+    user: &Address,
+    asset_preferences: &Vec<Address>,
+) -> Result<i128, ClosureError> {
+    let collateral: i128 =
+        storage::get(env, &DepositDataKey::CollateralBalance(user.clone())).unwrap_or(0);
+    if collateral <= 0 {
+        return Ok(0);
+    }
+
+    let mut remaining = collateral;
+    for asset in asset_preferences.iter() {
+        let available = token::Client::new(env, &asset).balance(&env.current_contract_address());
+        let amount = remaining.min(available);
+        if amount <= 0 {
+            continue;
+        }
+        remaining = withdraw_collateral(env, user.clone(), Some(asset), amount)
+            .map_err(|_| ClosureError::WithdrawFailed)?;
+        if remaining == 0 {
+            return Ok(collateral);
+        }
+    }
+    Err(ClosureError::InsufficientLiquidity)
+}
@@ -0,0 +1,246 @@
+//! # Position Transfer
+//!
+//! Moves an account's collateral and debt to another address in one
+//! transaction, for account migration and smart-wallet key rotation.
+//!
+//! [`transfer_position`] moves, from `from` to `to`:
+//! 1. The core position, with interest accrued up to now, its collateral
+//!    balance, last borrow time and any locked stable rate.
+//! 2. Every cross-asset position, with interest accrued up to now and any
+//!    soft liquidation progress (see `cross_asset`).
+//!
+//! `to` must hold no core or cross-asset position, so the moved positions are
+//! exactly what `from` held, and both addresses must authorize: `from` gives
+//! up the collateral and `to` takes on the debt. The moved positions must not
+//! be liquidatable at `to`; a position that already is cannot be transferred.
+//!
+//! A sub-account (see `sub_account`) can be either side. Its owner authorizes
+//! for it, and [`transfer_sub_account_position`] names it by `sub_id`.
+//! Sub-accounts hold cross-asset positions only, so a core position cannot be
+//! moved into one.
+//!
+//! Lending pool supply shares, activity analytics, credit history, accrued
+//! rewards and interest statements stay with `from`.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Map};
+
+use crate::borrow::accrue_interest;
+use crate::cross_asset::{self, AssetKey, AssetPosition, CrossAssetError};
+use crate::deposit::{DepositDataKey, Position};
+use crate::events::{emit_position_transferred, PositionTransferredEvent};
+use crate::rate_mode::{get_stable_borrow, RateModeDataKey};
+use crate::risk_management::{can_position_be_liquidated, is_emergency_paused};
+use crate::storage;
+use crate::sub_account::{controller, get_sub_account, get_sub_account_owner};
+
+/// Errors that can occur while transferring a position
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TransferError {
+    /// `from` and `to` are the same address
+    SameAccount = 1,
+    /// `from` holds no core or cross-asset position
+    NothingToTransfer = 2,
+    /// `to` already holds a core or cross-asset position
+    DestinationNotEmpty = 3,
+    /// The moved positions would be liquidatable
+    Insolvent = 4,
+    /// A core position cannot be moved into a sub-account
+    CoreToSubAccount = 5,
+    /// The emergency pause is active
+    ProtocolPaused = 6,
+    /// A price the cross-asset position depends on is stale
+    PriceStale = 7,
+    /// Interest accrual overflowed
+    Overflow = 8,
+    /// The cross-asset position could not be moved or valued for another
+    /// reason, such as an unconfigured asset or an invalid price
+    CrossAsset = 9,
+}
+
+impl From<CrossAssetError> for TransferError {
+    fn from(err: CrossAssetError) -> Self {
+        match err {
+            CrossAssetError::ProtocolPaused => TransferError::ProtocolPaused,
+            CrossAssetError::PriceStale => TransferError::PriceStale,
+            CrossAssetError::Overflow => TransferError::Overflow,
+            _ => TransferError::CrossAsset,
+        }
+    }
+}
+
+/// What [`transfer_position`] moved
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionTransfer {
+    /// Core collateral moved
+    pub collateral: i128,
+    /// Core debt moved, interest included
+    pub debt: i128,
+    /// Cross-asset positions moved, by asset
+    pub asset_positions: Map<AssetKey, AssetPosition>,
+}
+
+/// Move all collateral and debt of `from` to `to` (both must authorize)
+///
+/// For a sub-account on either side, its owner authorizes instead.
+///
+/// # Errors
+/// * `SameAccount` - `from` and `to` are the same address
+/// * `NothingToTransfer` - `from` holds no position
+/// * `DestinationNotEmpty` - `to` already holds a position
+/// * `CoreToSubAccount` - `from` has a core position and `to` is a sub-account
+/// * `Insolvent` - The moved positions would be liquidatable
+/// * `ProtocolPaused` - The emergency pause is active
+/// * `PriceStale` - The cross-asset position cannot be valued
+/// * `Overflow` - Interest accrual overflowed
+/// * `CrossAsset` - Any other cross-asset failure
+pub fn transfer_position(
+    env: &Env,
+    from: Address,
+    to: Address,
+) -> Result<PositionTransfer, TransferError> {
+    let giver = controller(env, &from);
+    move_position(env, from, to, giver)
+}
+
+/// Move all collateral and debt of one of `owner`'s sub-accounts to `to`
+///
+/// `owner` authorizes for the sub-account; `to` authorizes as in
+/// [`transfer_position`].
+///
+/// # Errors
+/// Any error of [`transfer_position`]
+pub fn transfer_sub_account_position(
+    env: &Env,
+    owner: Address,
+    sub_id: u32,
+    to: Address,
+) -> Result<PositionTransfer, TransferError> {
+    move_position(env, get_sub_account(env, &owner, sub_id), to, owner)
+}
+
+/// Move `from`'s positions to `to`, with `giver` authorizing for `from`
+fn move_position(
+    env: &Env,
+    from: Address,
+    to: Address,
+    giver: Address,
+) -> Result<PositionTransfer, TransferError> {
+    if from == to {
+        return Err(TransferError::SameAccount);
+    }
+    // One owner may control both sides, and authorizes once
+    let taker = controller(env, &to);
+    giver.require_auth();
+    if taker != giver {
+        taker.require_auth();
+    }
+    if is_emergency_paused(env) {
+        return Err(TransferError::ProtocolPaused);
+    }
+    if has_core_position(env, &to) || !cross_asset::get_user_asset_positions(env, &to).is_empty() {
+        return Err(TransferError::DestinationNotEmpty);
+    }
+
+    let core = move_core_position(env, &from, &to)?;
+    let asset_positions = cross_asset::move_user_positions(env, &from, &to)?;
+    if core.is_none() && asset_positions.is_empty() {
+        return Err(TransferError::NothingToTransfer);
+    }
+
+    let (collateral, debt) = core.map_or((0, 0), |(position, balance)| {
+        (balance, position.debt + position.borrow_interest)
+    });
+    if debt > 0
+        && can_position_be_liquidated(env, &to, collateral, debt)
+            .map_err(|_| TransferError::Insolvent)?
+    {
+        return Err(TransferError::Insolvent);
+    }
+    if !asset_positions.is_empty()
+        && cross_asset::get_user_position_summary(env, &to)?.is_liquidatable
+    {
+        return Err(TransferError::Insolvent);
+    }
+
+    emit_position_transferred(
+        env,
+        PositionTransferredEvent {
+            from,
+            to,
+            collateral,
+            debt,
+            assets: asset_positions.keys(),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(PositionTransfer {
+        collateral,
+        debt,
+        asset_positions,
+    })
+}
+
+fn has_core_position(env: &Env, user: &Address) -> bool {
+    let position: Option<Position> = storage::get(env, &DepositDataKey::Position(user.clone()));
+    let balance: i128 =
+        storage::get(env, &DepositDataKey::CollateralBalance(user.clone())).unwrap_or(0);
+    balance != 0
+        || position.is_some_and(|position| {
+            position.collateral != 0 || position.debt != 0 || position.borrow_interest != 0
+        })
+}
+
+/// Move the core position of `from` to `to`, returning it and its collateral
+/// balance if there was one
+fn move_core_position(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+) -> Result<Option<(Position, i128)>, TransferError> {
+    if !has_core_position(env, from) {
+        return Ok(None);
+    }
+    if get_sub_account_owner(env, to).is_some() {
+        return Err(TransferError::CoreToSubAccount);
+    }
+
+    let position_key = DepositDataKey::Position(from.clone());
+    let mut position: Position = storage::get(env, &position_key).unwrap_or(Position {
+        collateral: 0,
+        debt: 0,
+        borrow_interest: 0,
+        last_accrual_time: env.ledger().timestamp(),
+    });
+    accrue_interest(env, from, &mut position).map_err(|_| TransferError::Overflow)?;
+    let balance_key = DepositDataKey::CollateralBalance(from.clone());
+    let balance: i128 = storage::get(env, &balance_key).unwrap_or(0);
+
+    storage::set(env, &DepositDataKey::Position(to.clone()), &position);
+    storage::set(
+        env,
+        &DepositDataKey::CollateralBalance(to.clone()),
+        &balance,
+    );
+    storage::remove(env, &position_key);
+    storage::remove(env, &balance_key);
+
+    let borrow_time_key = DepositDataKey::LastBorrowTime(from.clone());
+    if let Some(borrowed_at) = storage::get::<_, u64>(env, &borrow_time_key) {
+        storage::set(
+            env,
+            &DepositDataKey::LastBorrowTime(to.clone()),
+            &borrowed_at,
+        );
+        storage::remove(env, &borrow_time_key);
+    }
+    if let Some(stable) = get_stable_borrow(env, from) {
+        storage::set(env, &RateModeDataKey::Stable(to.clone()), &stable);
+        storage::remove(env, &RateModeDataKey::Stable(from.clone()));
+    }
+
+    cross_asset::register_user(env, to);
+    Ok(Some((position, balance)))
+}
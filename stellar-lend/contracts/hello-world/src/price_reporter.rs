@@ -0,0 +1,353 @@
+//! # Price Reporters
+//!
+//! Push-based price updates from a whitelist of reporters, aggregated into
+//! the oracle's primary feed (see `oracle`).
+//!
+//! A reporter is an address registered with an ed25519 public key by an
+//! `AssetListingAdmin`. It signs the SHA-256 hash of
+//! `(contract, asset, price, timestamp, nonce)` in XDR off-chain, and anyone,
+//! typically a keeper relaying for it, submits the signed price with
+//! [`submit_price`]. The nonce is the reporter's count of accepted
+//! submissions ([`get_reporter_nonce`]), so each signature is accepted once.
+//! The timestamp is when the reporter observed the price: a submission is
+//! rejected if it is in the future or older than the oracle's staleness
+//! threshold, so a relayer cannot hold a signature back and land it later.
+//!
+//! ## Rate Limit and Incentive
+//! Each asset that takes reports has a [`ReportingConfig`]. A reporter's
+//! submissions for the asset must land at least `min_update_interval`
+//! seconds of ledger time apart, whatever their signed timestamps, so
+//! backdated signatures cannot be stacked into one ledger. An accepted
+//! submission that is counted in a published median pays the reporter
+//! `incentive` of `incentive_asset` from that market's protocol reserves (see
+//! `lending_pool`), or nothing once the reserves run dry; a submission short
+//! of the quorum is accepted unpaid.
+//!
+//! ## Aggregation
+//! After each accepted submission, the median of the latest price from every
+//! registered reporter, counting only prices signed no earlier than the
+//! oracle's staleness threshold, becomes the asset's primary feed once at least
+//! `min_reporters` of them are fresh. The median only moves when half the
+//! reporters move, so the deviation guard that bounds a single oracle is not
+//! applied to it. A published median also prices the asset's cross-asset
+//! market (see `cross_asset`).
+
+use soroban_sdk::{
+    contracterror, contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, Symbol, Vec,
+};
+
+use crate::events::{
+    emit_admin_action, emit_price_submitted, AdminActionEvent, PriceSubmittedEvent,
+};
+use crate::lending_pool::pay_incentive;
+use crate::oracle::{
+    get_oracle_config, is_oracle_paused, median, publish_aggregate_price, validate_price,
+};
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::roles::{require_role, Role};
+use crate::storage;
+
+/// Maximum number of registered reporters
+pub const MAX_REPORTERS: u32 = 10;
+
+/// Errors that can occur while reporting prices
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ReporterError {
+    /// Caller is neither the admin nor an `AssetListingAdmin`
+    Unauthorized = 1,
+    /// The address is not a registered reporter
+    NotReporter = 2,
+    /// The asset takes no reports
+    AssetNotConfigured = 3,
+    /// Price is zero, negative or outside the oracle's sanity bounds
+    InvalidPrice = 4,
+    /// The reporter's last submission for the asset is too recent
+    TooFrequent = 5,
+    /// A reporting setting is out of range
+    InvalidConfig = 6,
+    /// [`MAX_REPORTERS`] are already registered
+    TooManyReporters = 7,
+    /// Oracle updates are paused
+    OraclePaused = 8,
+    /// A submission was entered while another operation was in progress
+    Reentrancy = 9,
+    /// The signed timestamp is in the future or older than the oracle's
+    /// staleness threshold
+    InvalidTimestamp = 10,
+}
+
+impl From<Reentered> for ReporterError {
+    fn from(_: Reentered) -> Self {
+        ReporterError::Reentrancy
+    }
+}
+
+/// Storage keys for price reporting
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum PriceReporterDataKey {
+    /// Registered reporters and their ed25519 public keys: Map<Address, BytesN<32>>
+    Reporters,
+    /// Reporting settings per asset: ReportingConfig
+    Config(Address),
+    /// Latest accepted report per reporter for an asset: Map<Address, PriceReport>
+    Reports(Address),
+    /// Accepted submissions per reporter: u64
+    Nonce(Address),
+}
+
+/// How an asset takes reports
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReportingConfig {
+    /// Minimum seconds between one reporter's submissions for the asset
+    pub min_update_interval: u64,
+    /// Fresh reports needed before the median is published
+    pub min_reporters: u32,
+    /// Decimals of the reported prices
+    pub decimals: u32,
+    /// Market whose reserves pay the incentive
+    pub incentive_asset: Address,
+    /// Incentive per submission counted in a published median
+    pub incentive: i128,
+}
+
+/// A reporter's latest accepted price for an asset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceReport {
+    /// Reported price
+    pub price: i128,
+    /// Reporter-signed timestamp of the price
+    pub submitted_at: u64,
+    /// Ledger timestamp at which the report was accepted
+    pub accepted_at: u64,
+}
+
+/// Outcome of an accepted submission
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceSubmission {
+    /// The median published to the feed, if enough reports were fresh
+    pub median: Option<i128>,
+    /// Incentive paid to the reporter; nothing unless a median was published
+    pub incentive_paid: i128,
+}
+
+/// Register a reporter with its ed25519 public key, or remove it with `None`
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor an `AssetListingAdmin`
+/// * `TooManyReporters` - [`MAX_REPORTERS`] are already registered
+pub fn set_price_reporter(
+    env: &Env,
+    caller: Address,
+    reporter: Address,
+    public_key: Option<BytesN<32>>,
+) -> Result<(), ReporterError> {
+    require_role(env, &caller, Role::AssetListingAdmin).map_err(|_| ReporterError::Unauthorized)?;
+
+    let mut reporters = get_price_reporters(env);
+    match public_key {
+        Some(public_key) => {
+            if !reporters.contains_key(reporter.clone()) && reporters.len() >= MAX_REPORTERS {
+                return Err(ReporterError::TooManyReporters);
+            }
+            reporters.set(reporter, public_key);
+        }
+        None => {
+            reporters.remove(reporter);
+        }
+    }
+    storage::set(env, &PriceReporterDataKey::Reporters, &reporters);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_price_reporter"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Registered reporters and their ed25519 public keys
+pub fn get_price_reporters(env: &Env) -> Map<Address, BytesN<32>> {
+    storage::get(env, &PriceReporterDataKey::Reporters).unwrap_or(Map::new(env))
+}
+
+/// Let an asset take reports, or stop it with `None`
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor an `AssetListingAdmin`
+/// * `InvalidConfig` - `min_reporters` is not in 1..=[`MAX_REPORTERS`], or
+///   the incentive is negative
+pub fn set_reporting_config(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    config: Option<ReportingConfig>,
+) -> Result<(), ReporterError> {
+    require_role(env, &caller, Role::AssetListingAdmin).map_err(|_| ReporterError::Unauthorized)?;
+
+    let key = PriceReporterDataKey::Config(asset);
+    match config {
+        Some(config) => {
+            if config.min_reporters == 0
+                || config.min_reporters > MAX_REPORTERS
+                || config.incentive < 0
+            {
+                return Err(ReporterError::InvalidConfig);
+            }
+            storage::set(env, &key, &config);
+        }
+        None => storage::remove(env, &key),
+    }
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_reporting_config"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// How an asset takes reports, if it does
+pub fn get_reporting_config(env: &Env, asset: &Address) -> Option<ReportingConfig> {
+    storage::get(env, &PriceReporterDataKey::Config(asset.clone()))
+}
+
+/// Latest accepted report per reporter for an asset
+pub fn get_price_reports(env: &Env, asset: &Address) -> Map<Address, PriceReport> {
+    storage::get(env, &PriceReporterDataKey::Reports(asset.clone())).unwrap_or(Map::new(env))
+}
+
+/// Nonce the reporter's next signed submission must carry
+pub fn get_reporter_nonce(env: &Env, reporter: &Address) -> u64 {
+    storage::get(env, &PriceReporterDataKey::Nonce(reporter.clone())).unwrap_or(0)
+}
+
+/// Submit a reporter's signed price for an asset (anyone may relay it)
+///
+/// The call fails if `signature` is not the reporter's signature over the
+/// submission, its `timestamp` and its current nonce.
+///
+/// # Returns
+/// The median published, if any, and the incentive paid.
+///
+/// # Errors
+/// * `OraclePaused` - Oracle updates are paused
+/// * `NotReporter` - `reporter` is not registered
+/// * `AssetNotConfigured` - The asset takes no reports
+/// * `InvalidPrice` - Price is outside the oracle's sanity bounds
+/// * `InvalidTimestamp` - `timestamp` is in the future or stale
+/// * `TooFrequent` - The reporter's last report for the asset was accepted
+///   less than `min_update_interval` seconds ago
+pub fn submit_price(
+    env: &Env,
+    reporter: Address,
+    asset: Address,
+    price: i128,
+    timestamp: u64,
+    signature: BytesN<64>,
+) -> Result<PriceSubmission, ReporterError> {
+    let _guard = OperationGuard::enter(env)?;
+    if is_oracle_paused(env) {
+        return Err(ReporterError::OraclePaused);
+    }
+    let reporters = get_price_reporters(env);
+    let public_key = reporters
+        .get(reporter.clone())
+        .ok_or(ReporterError::NotReporter)?;
+    let config = get_reporting_config(env, &asset).ok_or(ReporterError::AssetNotConfigured)?;
+    validate_price(env, price).map_err(|_| ReporterError::InvalidPrice)?;
+
+    let nonce = get_reporter_nonce(env, &reporter);
+    let payload = (
+        env.current_contract_address(),
+        asset.clone(),
+        price,
+        timestamp,
+        nonce,
+    )
+        .to_xdr(env);
+    let digest: BytesN<32> = env.crypto().sha256(&payload).into();
+    env.crypto()
+        .ed25519_verify(&public_key, &Bytes::from(digest), &signature);
+
+    let now = env.ledger().timestamp();
+    let max_age = get_oracle_config(env).max_staleness_seconds;
+    if timestamp > now || now - timestamp > max_age {
+        return Err(ReporterError::InvalidTimestamp);
+    }
+    let mut reports = get_price_reports(env, &asset);
+    if let Some(last) = reports.get(reporter.clone()) {
+        if now < last.accepted_at.saturating_add(config.min_update_interval) {
+            return Err(ReporterError::TooFrequent);
+        }
+    }
+    reports.set(
+        reporter.clone(),
+        PriceReport {
+            price,
+            submitted_at: timestamp,
+            accepted_at: now,
+        },
+    );
+    storage::set(env, &PriceReporterDataKey::Reports(asset.clone()), &reports);
+    storage::set(
+        env,
+        &PriceReporterDataKey::Nonce(reporter.clone()),
+        &(nonce + 1),
+    );
+
+    let mut fresh = Vec::new(env);
+    for (address, report) in reports.iter() {
+        if reporters.contains_key(address) && now - report.submitted_at <= max_age {
+            fresh.push_back(report.price);
+        }
+    }
+    let median = if fresh.len() >= config.min_reporters {
+        median(&fresh)
+    } else {
+        None
+    };
+    if let Some(median) = median {
+        publish_aggregate_price(
+            env,
+            reporter.clone(),
+            asset.clone(),
+            median,
+            config.decimals,
+        );
+    }
+
+    // The report is fresh and its reporter registered, so it is in any median
+    let incentive_paid = if median.is_some() {
+        pay_incentive(env, &config.incentive_asset, &reporter, config.incentive)
+    } else {
+        0
+    };
+    emit_price_submitted(
+        env,
+        PriceSubmittedEvent {
+            reporter,
+            asset,
+            price,
+            median,
+            incentive: incentive_paid,
+            timestamp: now,
+        },
+    );
+    Ok(PriceSubmission {
+        median,
+        incentive_paid,
+    })
+}
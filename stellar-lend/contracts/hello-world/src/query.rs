@@ -0,0 +1,143 @@
+//! # Batched Queries
+//!
+//! Lets front-ends read a full dashboard (markets, rates, account data) in one
+//! simulated invocation instead of one RPC round-trip per view.
+//!
+//! A batch is a list of [`Query`] values, each naming one of the contract's
+//! read-only views. Results come back index-aligned with the queries, each
+//! encoded as a `Val` of the type the matching view returns. A query that
+//! fails is encoded as its contract error (a `Val` of type `Error`) in its
+//! slot, so one failing view does not hide the rest of the dashboard.
+//!
+//! Queries never mutate state.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Val, Vec};
+
+use crate::analytics::{generate_protocol_report, generate_user_report};
+use crate::cross_asset::{
+    get_asset_config_by_address, get_asset_list, get_market_summary, get_supply_statement,
+    get_tenure_rebates, get_user_asset_position, get_user_position_summary,
+};
+use crate::interest_rate::{
+    calculate_user_borrow_rate, get_current_borrow_rate, get_current_supply_rate,
+    get_current_utilization, get_staker_discount,
+};
+use crate::oracle::get_price;
+use crate::risk_management::{get_protocol_health, get_risk_config, is_emergency_paused};
+
+/// Maximum number of queries per `query_batch` call
+pub const MAX_QUERY_BATCH_SIZE: u32 = 25;
+
+/// Errors that can occur when running a query batch
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum QueryError {
+    /// More than [`MAX_QUERY_BATCH_SIZE`] queries were requested
+    BatchTooLarge = 1,
+}
+
+/// A read-only view that can be included in a batch
+///
+/// Each variant documents the type its result decodes to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Query {
+    /// `Vec<AssetKey>`: all registered cross-asset markets
+    AssetList,
+    /// `AssetConfig`: configuration of one market
+    AssetConfig(Option<Address>),
+    /// `MarketSummary`: balances and last activity of one market
+    MarketSummary(Option<Address>),
+    /// `i128`: protocol utilization in basis points
+    Utilization,
+    /// `i128`: pool borrow rate in basis points
+    BorrowRate,
+    /// `i128`: pool supply rate in basis points
+    SupplyRate,
+    /// `i128`: oracle price of an asset
+    Price(Address),
+    /// `Option<RiskConfig>`: current risk parameters
+    RiskConfig,
+    /// `bool`: whether the emergency pause is active
+    EmergencyPaused,
+    /// `ProtocolHealth`: pause state and active recovery haircut
+    ProtocolHealth,
+    /// `Option<StakerDiscountConfig>`: staker borrow-rate discount schedule
+    StakerDiscount,
+    /// `Vec<TenureRebateTier>`: supply tenure rebate schedule
+    TenureRebates,
+    /// `ProtocolReport`: protocol-wide metrics
+    ProtocolReport,
+    /// `UserReport`: a user's core position, metrics and recent activity
+    UserReport(Address),
+    /// `i128`: borrow rate applied to a user after staker discounts
+    UserBorrowRate(Address),
+    /// `UserPositionSummary`: a user's cross-asset totals and health factor
+    UserPositionSummary(Address),
+    /// `AssetPosition`: a user's position in one market
+    UserAssetPosition(Address, Option<Address>),
+    /// `SupplyStatement`: a user's supplied balance, tenure and rebate in one market
+    SupplyStatement(Address, Option<Address>),
+}
+
+/// Encode a view result, placing the contract error in the slot on failure.
+fn encode<T, E>(env: &Env, result: Result<T, E>) -> Val
+where
+    T: IntoVal<Env, Val>,
+    E: Into<soroban_sdk::Error>,
+{
+    match result {
+        Ok(value) => value.into_val(env),
+        Err(error) => error.into().to_val(),
+    }
+}
+
+/// Run a single query.
+fn run(env: &Env, query: Query) -> Val {
+    match query {
+        Query::AssetList => get_asset_list(env).into_val(env),
+        Query::AssetConfig(asset) => encode(env, get_asset_config_by_address(env, asset)),
+        Query::MarketSummary(asset) => encode(env, get_market_summary(env, asset)),
+        Query::Utilization => encode(env, get_current_utilization(env)),
+        Query::BorrowRate => encode(env, get_current_borrow_rate(env)),
+        Query::SupplyRate => encode(env, get_current_supply_rate(env)),
+        Query::Price(asset) => encode(env, get_price(env, &asset)),
+        Query::RiskConfig => get_risk_config(env).into_val(env),
+        Query::EmergencyPaused => is_emergency_paused(env).into_val(env),
+        Query::ProtocolHealth => get_protocol_health(env).into_val(env),
+        Query::StakerDiscount => get_staker_discount(env).into_val(env),
+        Query::TenureRebates => get_tenure_rebates(env).into_val(env),
+        Query::ProtocolReport => encode(env, generate_protocol_report(env)),
+        Query::UserReport(user) => encode(env, generate_user_report(env, &user)),
+        Query::UserBorrowRate(user) => encode(env, calculate_user_borrow_rate(env, &user)),
+        Query::UserPositionSummary(user) => encode(env, get_user_position_summary(env, &user)),
+        Query::UserAssetPosition(user, asset) => {
+            get_user_asset_position(env, &user, asset).into_val(env)
+        }
+        Query::SupplyStatement(user, asset) => encode(env, get_supply_statement(env, &user, asset)),
+    }
+}
+
+/// Run a batch of read-only queries.
+///
+/// # Arguments
+/// * `queries` - Up to [`MAX_QUERY_BATCH_SIZE`] views to evaluate
+///
+/// # Returns
+/// One encoded result per query, in input order. Failed queries hold their
+/// contract error instead of a value.
+///
+/// # Errors
+/// Returns `QueryError::BatchTooLarge` if the batch is too large.
+pub fn query_batch(env: &Env, queries: &Vec<Query>) -> Result<Vec<Val>, QueryError> {
+    if queries.len() > MAX_QUERY_BATCH_SIZE {
+        return Err(QueryError::BatchTooLarge);
+    }
+
+    let mut results = Vec::new(env);
+    for query in queries.iter() {
+        results.push_back(run(env, query));
+    }
+    Ok(results)
+}
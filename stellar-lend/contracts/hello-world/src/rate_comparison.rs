@@ -0,0 +1,200 @@
+//! # Rate Comparison
+//!
+//! Read-only comparison of this protocol's borrow rates with peer lending
+//! pools on Stellar, for rate-aware routing built on top of the protocol.
+//!
+//! The cross-asset admin registers peer pool contracts with
+//! [`add_peer_pool`]. [`compare_rates`] then asks each peer for its current
+//! borrow rate on an asset and returns the quotes next to the local rate.
+//!
+//! ## Peer Interface
+//! A peer must expose this protocol's rate view:
+//!
+//! `get_asset_borrow_rate(asset: Option<Address>) -> i128` (basis points)
+//!
+//! Another deployment of this contract can be registered directly; pools
+//! with a different interface (such as Blend) are registered through a small
+//! adapter contract that implements the view. Stellar asset contracts have
+//! the same address everywhere, so the asset is passed through unchanged.
+//!
+//! A peer whose call fails (missing contract, wrong interface, error or
+//! negative rate) is listed as unavailable instead of failing the comparison.
+
+use soroban_sdk::{
+    contracterror, contracttype, vec, Address, Env, IntoVal, Symbol, TryIntoVal, Val, Vec,
+};
+
+use crate::cross_asset::require_admin;
+use crate::events::{emit_admin_action, AdminActionEvent};
+use crate::rate_strategy::get_asset_borrow_rate;
+use crate::storage;
+
+/// Maximum number of registered peer pools
+pub const MAX_PEER_POOLS: u32 = 10;
+
+/// Errors that can occur while managing or querying peer pools
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RateComparisonError {
+    /// Caller is not authorized (not admin)
+    NotAuthorized = 1,
+    /// [`MAX_PEER_POOLS`] peers are already registered
+    TooManyPeers = 2,
+    /// The pool is already registered
+    DuplicatePeer = 3,
+    /// The pool is not registered
+    PeerNotFound = 4,
+    /// This contract cannot be its own peer
+    InvalidPeer = 5,
+    /// The local borrow rate could not be computed
+    RateUnavailable = 6,
+}
+
+/// Storage keys for rate comparison data
+#[contracttype]
+#[derive(Clone)]
+pub enum RateComparisonDataKey {
+    /// Registered peer pool contracts: Vec<Address>
+    PeerPools,
+}
+
+/// A borrow rate quoted by one pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateQuote {
+    /// Pool contract address
+    pub pool: Address,
+    /// Current borrow rate in basis points
+    pub borrow_rate_bps: i128,
+}
+
+/// Borrow rates for an asset across this protocol and its peers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateComparison {
+    /// Borrow rate of this protocol's market, in basis points
+    pub local_borrow_rate_bps: i128,
+    /// Quotes from the peers that answered, in registration order
+    pub quotes: Vec<RateQuote>,
+    /// Peers that could not be queried
+    pub unavailable: Vec<Address>,
+    /// Pool with the lowest borrow rate (this contract on ties)
+    pub best_pool: Address,
+    /// Lowest borrow rate, in basis points
+    pub best_borrow_rate_bps: i128,
+}
+
+/// Register a peer pool (admin only)
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is not the cross-asset admin
+/// * `InvalidPeer` - The pool is this contract
+/// * `DuplicatePeer` - The pool is already registered
+/// * `TooManyPeers` - [`MAX_PEER_POOLS`] peers are already registered
+pub fn add_peer_pool(env: &Env, pool: Address) -> Result<(), RateComparisonError> {
+    let admin = require_admin(env).map_err(|_| RateComparisonError::NotAuthorized)?;
+
+    if pool == env.current_contract_address() {
+        return Err(RateComparisonError::InvalidPeer);
+    }
+    let mut peers = get_peer_pools(env);
+    if peers.contains(&pool) {
+        return Err(RateComparisonError::DuplicatePeer);
+    }
+    if peers.len() >= MAX_PEER_POOLS {
+        return Err(RateComparisonError::TooManyPeers);
+    }
+
+    peers.push_back(pool);
+    storage::set_instance(env, &RateComparisonDataKey::PeerPools, &peers);
+    emit_peer_action(env, admin, "add_peer_pool");
+    Ok(())
+}
+
+/// Unregister a peer pool (admin only)
+///
+/// # Errors
+/// * `NotAuthorized` - Caller is not the cross-asset admin
+/// * `PeerNotFound` - The pool is not registered
+pub fn remove_peer_pool(env: &Env, pool: Address) -> Result<(), RateComparisonError> {
+    let admin = require_admin(env).map_err(|_| RateComparisonError::NotAuthorized)?;
+
+    let mut peers = get_peer_pools(env);
+    let index = peers
+        .first_index_of(&pool)
+        .ok_or(RateComparisonError::PeerNotFound)?;
+    peers.remove(index);
+    storage::set_instance(env, &RateComparisonDataKey::PeerPools, &peers);
+    emit_peer_action(env, admin, "remove_peer_pool");
+    Ok(())
+}
+
+/// Get the registered peer pools
+pub fn get_peer_pools(env: &Env) -> Vec<Address> {
+    storage::get_instance(env, &RateComparisonDataKey::PeerPools).unwrap_or(Vec::new(env))
+}
+
+/// Compare the borrow rate of an asset here and at every peer pool.
+///
+/// # Arguments
+/// * `asset` - The asset (`None` for native XLM)
+///
+/// # Errors
+/// * `RateUnavailable` - The local market's rate could not be computed
+pub fn compare_rates(
+    env: &Env,
+    asset: Option<Address>,
+) -> Result<RateComparison, RateComparisonError> {
+    let local_borrow_rate_bps = get_asset_borrow_rate(env, asset.clone())
+        .map_err(|_| RateComparisonError::RateUnavailable)?;
+
+    let mut comparison = RateComparison {
+        local_borrow_rate_bps,
+        quotes: Vec::new(env),
+        unavailable: Vec::new(env),
+        best_pool: env.current_contract_address(),
+        best_borrow_rate_bps: local_borrow_rate_bps,
+    };
+
+    for pool in get_peer_pools(env).iter() {
+        match query_peer_rate(env, &pool, &asset) {
+            Some(borrow_rate_bps) => {
+                if borrow_rate_bps < comparison.best_borrow_rate_bps {
+                    comparison.best_pool = pool.clone();
+                    comparison.best_borrow_rate_bps = borrow_rate_bps;
+                }
+                comparison.quotes.push_back(RateQuote {
+                    pool,
+                    borrow_rate_bps,
+                });
+            }
+            None => comparison.unavailable.push_back(pool),
+        }
+    }
+
+    Ok(comparison)
+}
+
+/// Ask a peer for its borrow rate; `None` if the call fails in any way
+fn query_peer_rate(env: &Env, pool: &Address, asset: &Option<Address>) -> Option<i128> {
+    let args: Vec<Val> = vec![env, asset.into_val(env)];
+    let result = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+        pool,
+        &Symbol::new(env, "get_asset_borrow_rate"),
+        args,
+    );
+    let rate: i128 = result.ok()?.ok()?.try_into_val(env).ok()?;
+    (rate >= 0).then_some(rate)
+}
+
+fn emit_peer_action(env: &Env, actor: Address, action: &str) {
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor,
+            action: Symbol::new(env, action),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+}
@@ -0,0 +1,326 @@
+//! # Supply Rate Locks
+//!
+//! Lets a pool supplier fix the yield on some of their shares for a term,
+//! for predictable-yield products built on the protocol.
+//!
+//! A lock is an internal rate swap against the market's reserves: the
+//! supplier gives up the pool's variable yield on the locked shares and is
+//! guaranteed a fixed rate instead. At lock time the shares' value is
+//! recorded and the rate set to the market's current supply rate less the
+//! market's lock spread. When the term ends, [`settle_rate_lock`] compares
+//! the shares' value with the locked value grown at the fixed rate:
+//! - if the shares earned less, the shortfall is paid from reserves to the
+//!   supplier as new shares, up to the reserves available;
+//! - if they earned more, the excess is moved from the supplier's shares
+//!   into reserves.
+//!
+//! ## Caps
+//! Locks are disabled in a market until a risk admin configures them with
+//! [`set_rate_lock_config`], which sets the spread, the longest term and the
+//! most value that may be locked in the market at once. Terms are at least
+//! [`MIN_LOCK_TERM`] and at most [`MAX_LOCK_TERM`]. A supplier has at most one
+//! lock per market, and locked shares cannot be redeemed before settlement.
+//!
+//! ## Supply Rate
+//! A market's supply rate is its borrow rate scaled by utilization, less the
+//! reserve factor: the yield suppliers earn from the interest that accrues
+//! while the rate holds.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env};
+
+use crate::constants::{BASIS_POINTS_SCALE, SECONDS_PER_DAY, SECONDS_PER_YEAR};
+use crate::cross_asset::{get_asset_config_by_address, require_role, AssetKey};
+use crate::events::{
+    emit_rate_lock_settled, emit_rate_locked, RateLockSettledEvent, RateLockedEvent,
+};
+use crate::lending_pool::{
+    get_reserves, get_supply_balance, get_supply_shares, swap_with_reserves,
+};
+use crate::math::{Amount, MathError};
+use crate::rate_strategy::get_asset_supply_rate;
+use crate::roles::Role;
+use crate::storage;
+
+/// Shortest lock term (7 days)
+pub const MIN_LOCK_TERM: u64 = 7 * SECONDS_PER_DAY.get();
+
+/// Longest lock term a market may allow (365 days)
+pub const MAX_LOCK_TERM: u64 = SECONDS_PER_YEAR.get();
+
+/// Errors that can occur while locking or settling supply rates
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RateLockError {
+    /// Caller is neither the admin nor a risk admin
+    Unauthorized = 1,
+    /// Spread outside 0-10000 bps, negative cap, or maximum term outside
+    /// [`MIN_LOCK_TERM`]..=[`MAX_LOCK_TERM`]
+    InvalidConfig = 2,
+    /// Rate locks are not configured for the market
+    NotEnabled = 3,
+    /// Term is outside [`MIN_LOCK_TERM`] and the market's maximum
+    InvalidTerm = 4,
+    /// Share count is not positive or exceeds the unlocked shares held
+    InvalidShares = 5,
+    /// The supplier already has a lock in the market
+    LockExists = 6,
+    /// The lock would take the market's locked value above its cap
+    CapExceeded = 7,
+    /// The supplier has no lock in the market
+    NoLock = 8,
+    /// The lock's term has not ended
+    NotMatured = 9,
+    /// The market is not registered or its rate could not be calculated
+    RateUnavailable = 10,
+    /// Overflow occurred during calculation
+    Overflow = 11,
+}
+
+impl From<MathError> for RateLockError {
+    fn from(_: MathError) -> Self {
+        RateLockError::Overflow
+    }
+}
+
+/// Storage keys for rate lock data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum RateLockDataKey {
+    /// Lock parameters per market: RateLockConfig
+    Config(AssetKey),
+    /// A supplier's lock in a market: RateLock
+    Lock(Address, AssetKey),
+    /// Value locked across a market's open locks: i128
+    Locked(AssetKey),
+}
+
+/// Rate lock parameters of one market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLockConfig {
+    /// Subtracted from the supply rate to give the fixed rate (basis points)
+    pub spread_bps: i128,
+    /// Longest term a supplier may choose, in seconds
+    pub max_term: u64,
+    /// Most value that may be locked across the market's open locks
+    pub max_locked: i128,
+}
+
+/// A supplier's fixed-rate lock on pool shares
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLock {
+    /// Shares whose yield is fixed
+    pub shares: i128,
+    /// Value of the shares when locked
+    pub value: i128,
+    /// Fixed annual rate, in basis points
+    pub rate_bps: i128,
+    /// When the lock was taken
+    pub start: u64,
+    /// When the lock can be settled
+    pub maturity: u64,
+}
+
+/// Configure rate locks in a market (admin or `RiskAdmin` only)
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor a risk admin
+/// * `InvalidConfig` - See [`RateLockError::InvalidConfig`]
+pub fn set_rate_lock_config(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    config: RateLockConfig,
+) -> Result<(), RateLockError> {
+    require_role(env, &caller, Role::RiskAdmin).map_err(|_| RateLockError::Unauthorized)?;
+    if !(0..=BASIS_POINTS_SCALE.get()).contains(&config.spread_bps)
+        || !(MIN_LOCK_TERM..=MAX_LOCK_TERM).contains(&config.max_term)
+        || config.max_locked < 0
+    {
+        return Err(RateLockError::InvalidConfig);
+    }
+    storage::set(
+        env,
+        &RateLockDataKey::Config(AssetKey::from_option(asset)),
+        &config,
+    );
+    Ok(())
+}
+
+/// Get a market's rate lock parameters, if locks are enabled
+pub fn get_rate_lock_config(env: &Env, asset: Option<Address>) -> Option<RateLockConfig> {
+    storage::get(env, &RateLockDataKey::Config(AssetKey::from_option(asset)))
+}
+
+/// Current supply rate of a market's pool, in basis points
+pub fn get_pool_supply_rate(env: &Env, asset: Option<Address>) -> Result<i128, RateLockError> {
+    let config = get_asset_config_by_address(env, asset.clone())
+        .map_err(|_| RateLockError::RateUnavailable)?;
+    get_asset_supply_rate(env, asset, config.reserve_factor)
+        .map_err(|_| RateLockError::RateUnavailable)
+}
+
+/// Fixed rate a lock in the market would get now, in basis points
+///
+/// # Errors
+/// * `NotEnabled` - Rate locks are not configured for the market
+/// * `RateUnavailable` - The market's supply rate could not be calculated
+pub fn get_rate_lock_quote(env: &Env, asset: Option<Address>) -> Result<i128, RateLockError> {
+    let config = get_rate_lock_config(env, asset.clone()).ok_or(RateLockError::NotEnabled)?;
+    Ok((get_pool_supply_rate(env, asset)? - config.spread_bps).max(0))
+}
+
+/// Get a supplier's lock in a market, if any
+pub fn get_rate_lock(env: &Env, user: &Address, asset: Option<Address>) -> Option<RateLock> {
+    storage::get(
+        env,
+        &RateLockDataKey::Lock(user.clone(), AssetKey::from_option(asset)),
+    )
+}
+
+/// Get the value locked across a market's open locks
+pub fn get_locked_value(env: &Env, asset: Option<Address>) -> i128 {
+    storage::get(env, &RateLockDataKey::Locked(AssetKey::from_option(asset))).unwrap_or(0)
+}
+
+/// Shares of a supplier that are locked in a market
+pub(crate) fn locked_shares(env: &Env, user: &Address, asset: Option<Address>) -> i128 {
+    get_rate_lock(env, user, asset).map_or(0, |lock| lock.shares)
+}
+
+/// Fix the yield on `shares` pool shares for `term` seconds (supplier only)
+///
+/// # Returns
+/// The new lock.
+///
+/// # Errors
+/// * `NotEnabled` - Rate locks are not configured for the market
+/// * `InvalidTerm` - `term` is outside [`MIN_LOCK_TERM`] and the market's maximum
+/// * `InvalidShares` - `shares` is not positive or exceeds the shares held
+/// * `LockExists` - The supplier already has a lock in the market
+/// * `CapExceeded` - The market's locked value would exceed its cap
+pub fn lock_supply_rate(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    shares: i128,
+    term: u64,
+) -> Result<RateLock, RateLockError> {
+    user.require_auth();
+    let config = get_rate_lock_config(env, asset.clone()).ok_or(RateLockError::NotEnabled)?;
+    if term < MIN_LOCK_TERM || term > config.max_term {
+        return Err(RateLockError::InvalidTerm);
+    }
+    if get_rate_lock(env, &user, asset.clone()).is_some() {
+        return Err(RateLockError::LockExists);
+    }
+    let held = get_supply_shares(env, &user, asset.clone());
+    if shares <= 0 || shares > held {
+        return Err(RateLockError::InvalidShares);
+    }
+
+    let balance =
+        get_supply_balance(env, &user, asset.clone()).map_err(|_| RateLockError::Overflow)?;
+    let value = Amount::new(balance).mul_div(shares, held)?.get();
+    let locked = get_locked_value(env, asset.clone())
+        .checked_add(value)
+        .ok_or(RateLockError::Overflow)?;
+    if locked > config.max_locked {
+        return Err(RateLockError::CapExceeded);
+    }
+
+    let start = env.ledger().timestamp();
+    let lock = RateLock {
+        shares,
+        value,
+        rate_bps: get_rate_lock_quote(env, asset.clone())?,
+        start,
+        maturity: start + term,
+    };
+    let asset_key = AssetKey::from_option(asset.clone());
+    storage::set(
+        env,
+        &RateLockDataKey::Lock(user.clone(), asset_key.clone()),
+        &lock,
+    );
+    storage::set(env, &RateLockDataKey::Locked(asset_key), &locked);
+
+    emit_rate_locked(
+        env,
+        RateLockedEvent {
+            user,
+            asset,
+            shares,
+            value,
+            rate_bps: lock.rate_bps,
+            maturity: lock.maturity,
+            timestamp: start,
+        },
+    );
+    Ok(lock)
+}
+
+/// Settle a matured lock against reserves and release its shares (anyone can call)
+///
+/// # Returns
+/// The value moved to the supplier: positive when paid from reserves,
+/// negative when taken into reserves.
+///
+/// # Errors
+/// * `NoLock` - The supplier has no lock in the market
+/// * `NotMatured` - The lock's term has not ended
+pub fn settle_rate_lock(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+) -> Result<i128, RateLockError> {
+    let lock = get_rate_lock(env, &user, asset.clone()).ok_or(RateLockError::NoLock)?;
+    let now = env.ledger().timestamp();
+    if now < lock.maturity {
+        return Err(RateLockError::NotMatured);
+    }
+
+    let year = BASIS_POINTS_SCALE
+        .get()
+        .checked_mul(SECONDS_PER_YEAR.get() as i128)
+        .ok_or(RateLockError::Overflow)?;
+    let rate_time = lock
+        .rate_bps
+        .checked_mul((lock.maturity - lock.start) as i128)
+        .ok_or(RateLockError::Overflow)?;
+    let guaranteed = Amount::new(lock.value)
+        .checked_add(Amount::new(lock.value).mul_div(rate_time, year)?)?
+        .get();
+
+    let held = get_supply_shares(env, &user, asset.clone());
+    let balance =
+        get_supply_balance(env, &user, asset.clone()).map_err(|_| RateLockError::Overflow)?;
+    let actual = Amount::new(balance).mul_div(lock.shares, held)?.get();
+    let reserves = get_reserves(env, asset.clone());
+    let settlement = (guaranteed - actual).min(reserves);
+    if settlement != 0 {
+        swap_with_reserves(env, &user, asset.clone(), settlement)
+            .map_err(|_| RateLockError::Overflow)?;
+    }
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    storage::remove(env, &RateLockDataKey::Lock(user.clone(), asset_key.clone()));
+    let locked = (get_locked_value(env, asset.clone()) - lock.value).max(0);
+    storage::set(env, &RateLockDataKey::Locked(asset_key), &locked);
+
+    emit_rate_lock_settled(
+        env,
+        RateLockSettledEvent {
+            user,
+            asset,
+            guaranteed,
+            actual,
+            settlement,
+            timestamp: now,
+        },
+    );
+    Ok(settlement)
+}
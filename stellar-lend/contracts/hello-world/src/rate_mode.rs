@@ -0,0 +1,327 @@
+//! # Borrow Rate Modes
+//!
+//! Core borrowers choose how their debt is priced:
+//! - **Variable** (default): accrues at the pool borrow rate, which follows
+//!   utilization (see `interest_rate`), less any staker discount.
+//! - **Stable**: accrues at a rate locked when the debt is taken on: the pool
+//!   rate at that moment plus the stable premium. Further stable borrows lock
+//!   the debt-weighted average of the old and new rates.
+//!
+//! A position has one mode for all of its debt. The mode is chosen with
+//! `borrow_asset_with_rate_mode` when a loan is opened and changed later with
+//! [`swap_rate_mode`]; interest up to the swap accrues at the old rate. Once
+//! the debt is fully repaid the next loan starts over in the mode it asks for.
+//!
+//! ## Rebalancing
+//! A locked rate can drift far from the market. Anyone may call
+//! [`rebalance_stable_rate`] to re-lock it at the current stable rate when
+//! either:
+//! - the pool rate has risen to at least the locked rate plus the rebalance
+//!   threshold, so stable borrowers are no longer paying for liquidity, or
+//! - the locked rate is at least the rebalance threshold above the current
+//!   stable rate, so the borrower is overpaying.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env};
+
+use crate::borrow::accrue_interest;
+use crate::deposit::{DepositDataKey, Position};
+use crate::events::{
+    emit_rate_mode_swapped, emit_stable_rate_rebalanced, RateModeSwappedEvent,
+    StableRateRebalancedEvent,
+};
+use crate::interest_rate::{calculate_borrow_rate, calculate_user_borrow_rate, InterestRateError};
+use crate::roles::{self, Role};
+use crate::storage;
+
+/// Premium over the pool rate used until a risk admin sets one (2%)
+pub const DEFAULT_STABLE_PREMIUM_BPS: i128 = 200;
+
+/// Rebalance threshold used until a risk admin sets one (5%)
+pub const DEFAULT_REBALANCE_THRESHOLD_BPS: i128 = 500;
+
+/// Errors that can occur while managing borrow rate modes
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RateModeError {
+    /// Caller is not a risk admin
+    Unauthorized = 1,
+    /// Premium is negative or the rebalance threshold is not positive
+    InvalidConfig = 2,
+    /// The user has no debt
+    NoDebt = 3,
+    /// The position is not in stable mode
+    NotStable = 4,
+    /// Neither rebalancing condition holds
+    RebalanceNotAllowed = 5,
+    /// The rate could not be calculated
+    RateUnavailable = 6,
+    /// Borrow mode differs from the mode of the existing debt
+    ModeMismatch = 7,
+}
+
+/// How a position's debt accrues interest
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RateMode {
+    /// Pool borrow rate, following utilization
+    Variable,
+    /// Rate locked at origination
+    Stable,
+}
+
+/// Storage keys for rate mode data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum RateModeDataKey {
+    /// Stable premium and rebalance threshold: StableRateConfig
+    Config,
+    /// Locked rate of a stable-mode position: StableBorrow
+    Stable(Address),
+}
+
+/// Parameters of stable-mode borrowing, in basis points
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StableRateConfig {
+    /// Added to the pool rate to give the stable rate
+    pub premium_bps: i128,
+    /// Distance from the market at which a locked rate may be rebalanced
+    pub rebalance_threshold_bps: i128,
+}
+
+/// The locked rate of a stable-mode position
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StableBorrow {
+    /// Annual rate the debt accrues at, in basis points
+    pub rate_bps: i128,
+    /// When the rate was last locked
+    pub locked_at: u64,
+}
+
+/// Set the stable premium and rebalance threshold (risk admin only)
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not a risk admin
+/// * `InvalidConfig` - Negative premium or non-positive threshold
+pub fn set_stable_rate_config(
+    env: &Env,
+    caller: Address,
+    config: StableRateConfig,
+) -> Result<(), RateModeError> {
+    roles::require_role(env, &caller, Role::RiskAdmin).map_err(|_| RateModeError::Unauthorized)?;
+    if config.premium_bps < 0 || config.rebalance_threshold_bps <= 0 {
+        return Err(RateModeError::InvalidConfig);
+    }
+    storage::set(env, &RateModeDataKey::Config, &config);
+    Ok(())
+}
+
+/// Get the stable premium and rebalance threshold
+pub fn get_stable_rate_config(env: &Env) -> StableRateConfig {
+    storage::get(env, &RateModeDataKey::Config).unwrap_or(StableRateConfig {
+        premium_bps: DEFAULT_STABLE_PREMIUM_BPS,
+        rebalance_threshold_bps: DEFAULT_REBALANCE_THRESHOLD_BPS,
+    })
+}
+
+/// Rate a stable borrow would lock now, in basis points
+pub fn get_stable_rate(env: &Env) -> Result<i128, RateModeError> {
+    let pool_rate = calculate_borrow_rate(env).map_err(|_| RateModeError::RateUnavailable)?;
+    Ok(pool_rate + get_stable_rate_config(env).premium_bps)
+}
+
+/// Get the locked rate of a user's position, if it is in stable mode
+pub fn get_stable_borrow(env: &Env, user: &Address) -> Option<StableBorrow> {
+    storage::get(env, &RateModeDataKey::Stable(user.clone()))
+}
+
+/// Get the rate mode of a user's position
+pub fn get_rate_mode(env: &Env, user: &Address) -> RateMode {
+    if get_stable_borrow(env, user).is_some() {
+        RateMode::Stable
+    } else {
+        RateMode::Variable
+    }
+}
+
+/// Annual rate a user's debt accrues at, in basis points
+pub(crate) fn accrual_rate(env: &Env, user: &Address) -> Result<i128, InterestRateError> {
+    match get_stable_borrow(env, user) {
+        Some(stable) => Ok(stable.rate_bps),
+        None => calculate_user_borrow_rate(env, user),
+    }
+}
+
+/// Check a borrow's requested mode against the existing debt and return the
+/// mode the position will have
+///
+/// `prior_debt` is the principal before the borrow, with interest accrued.
+pub(crate) fn resolve_borrow_mode(
+    env: &Env,
+    user: &Address,
+    prior_debt: i128,
+    requested: Option<RateMode>,
+) -> Result<RateMode, RateModeError> {
+    let current = if prior_debt > 0 {
+        get_rate_mode(env, user)
+    } else {
+        RateMode::Variable
+    };
+    match requested {
+        Some(mode) if prior_debt > 0 && mode != current => Err(RateModeError::ModeMismatch),
+        Some(mode) => Ok(mode),
+        None => Ok(current),
+    }
+}
+
+/// Record a borrow of `amount` on top of `prior_debt` in `mode`
+pub(crate) fn record_borrow(
+    env: &Env,
+    user: &Address,
+    prior_debt: i128,
+    amount: i128,
+    mode: RateMode,
+) -> Result<(), RateModeError> {
+    let key = RateModeDataKey::Stable(user.clone());
+    if mode == RateMode::Variable {
+        storage::remove(env, &key);
+        return Ok(());
+    }
+
+    let new_rate = get_stable_rate(env)?;
+    let rate_bps = match get_stable_borrow(env, user) {
+        Some(stable) if prior_debt > 0 => stable
+            .rate_bps
+            .checked_mul(prior_debt)
+            .zip(new_rate.checked_mul(amount))
+            .and_then(|(old, new)| old.checked_add(new))
+            .and_then(|weighted| weighted.checked_div(prior_debt.checked_add(amount)?))
+            .ok_or(RateModeError::RateUnavailable)?,
+        _ => new_rate,
+    };
+    storage::set(
+        env,
+        &key,
+        &StableBorrow {
+            rate_bps,
+            locked_at: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Return a fully repaid position to variable mode
+pub(crate) fn clear_rate_mode(env: &Env, user: &Address) {
+    storage::remove(env, &RateModeDataKey::Stable(user.clone()));
+}
+
+/// Switch a user's debt between variable and stable mode (user only)
+///
+/// Interest up to now accrues at the old rate; a swap to stable locks the
+/// current stable rate.
+///
+/// # Returns
+/// The new mode.
+///
+/// # Errors
+/// * `NoDebt` - The user has no debt
+/// * `RateUnavailable` - Interest or the stable rate could not be calculated
+pub fn swap_rate_mode(env: &Env, user: Address) -> Result<RateMode, RateModeError> {
+    user.require_auth();
+    let position = accrued_position(env, &user)?;
+
+    let mode = match get_rate_mode(env, &user) {
+        RateMode::Stable => RateMode::Variable,
+        RateMode::Variable => RateMode::Stable,
+    };
+    let key = RateModeDataKey::Stable(user.clone());
+    let rate_bps = match mode {
+        RateMode::Stable => {
+            let rate_bps = get_stable_rate(env)?;
+            storage::set(
+                env,
+                &key,
+                &StableBorrow {
+                    rate_bps,
+                    locked_at: env.ledger().timestamp(),
+                },
+            );
+            rate_bps
+        }
+        RateMode::Variable => {
+            storage::remove(env, &key);
+            calculate_user_borrow_rate(env, &user).map_err(|_| RateModeError::RateUnavailable)?
+        }
+    };
+    storage::set(env, &DepositDataKey::Position(user.clone()), &position);
+
+    emit_rate_mode_swapped(
+        env,
+        RateModeSwappedEvent {
+            user,
+            mode,
+            rate_bps,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(mode)
+}
+
+/// Re-lock a stable position at the current stable rate (anyone can call)
+///
+/// Allowed only under the conditions in the module documentation.
+///
+/// # Returns
+/// The new locked rate.
+///
+/// # Errors
+/// * `NotStable` - The position is not in stable mode
+/// * `NoDebt` - The user has no debt
+/// * `RebalanceNotAllowed` - The locked rate is within the threshold of the market
+/// * `RateUnavailable` - Interest or the rates could not be calculated
+pub fn rebalance_stable_rate(env: &Env, user: Address) -> Result<i128, RateModeError> {
+    let stable = get_stable_borrow(env, &user).ok_or(RateModeError::NotStable)?;
+    let position = accrued_position(env, &user)?;
+
+    let config = get_stable_rate_config(env);
+    let pool_rate = calculate_borrow_rate(env).map_err(|_| RateModeError::RateUnavailable)?;
+    let stable_rate = pool_rate + config.premium_bps;
+    let too_low = pool_rate >= stable.rate_bps + config.rebalance_threshold_bps;
+    let too_high = stable.rate_bps >= stable_rate + config.rebalance_threshold_bps;
+    if !too_low && !too_high {
+        return Err(RateModeError::RebalanceNotAllowed);
+    }
+
+    storage::set(env, &DepositDataKey::Position(user.clone()), &position);
+    storage::set(
+        env,
+        &RateModeDataKey::Stable(user.clone()),
+        &StableBorrow {
+            rate_bps: stable_rate,
+            locked_at: env.ledger().timestamp(),
+        },
+    );
+
+    emit_stable_rate_rebalanced(
+        env,
+        StableRateRebalancedEvent {
+            user,
+            old_rate_bps: stable.rate_bps,
+            new_rate_bps: stable_rate,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(stable_rate)
+}
+
+/// Load a user's indebted position with interest accrued at the current mode's rate
+fn accrued_position(env: &Env, user: &Address) -> Result<Position, RateModeError> {
+    let mut position: Position = storage::get(env, &DepositDataKey::Position(user.clone()))
+        .filter(|position: &Position| position.debt > 0)
+        .ok_or(RateModeError::NoDebt)?;
+    accrue_interest(env, user, &mut position).map_err(|_| RateModeError::RateUnavailable)?;
+    Ok(position)
+}
@@ -0,0 +1,190 @@
+//! # Interest Rate Strategies
+//!
+//! Per-asset two-slope (kinked) borrow rate model for cross-asset markets.
+//!
+//! ## Rate Model
+//! Each market's borrow rate follows its own utilization
+//! (`total_borrow / total_supply` of that asset):
+//! - **Up to optimal**: `rate = base + slope1 * utilization / optimal`
+//! - **Above optimal**: `rate = base + slope1 + slope2 * (utilization - optimal) / (100% - optimal)`
+//!
+//! A market without a strategy accrues no interest. Strategies are set by the
+//! interest rate admin with [`set_rate_strategy`].
+//!
+//! ## Accrual
+//! Interest is charged on the position's principal and added to the market's
+//! total borrow whenever the position is next touched (deposit, withdraw,
+//! borrow, repay, liquidation). By default it goes to `accrued_interest`,
+//! which earns no interest; a compounding market adds it to the principal
+//! instead (see `cross_asset::set_interest_mode`). The rate used is the
+//! market's rate at that moment. Health checks estimate interest not yet
+//! accrued on other markets (see `cross_asset`).
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::constants::{Bps, BASIS_POINTS_SCALE};
+use crate::cross_asset::{get_market_utilization, AssetKey};
+use crate::events::{emit_admin_action, AdminActionEvent};
+use crate::interest_rate::{InterestRateDataKey, InterestRateError};
+use crate::math::Amount;
+use crate::storage;
+
+/// Highest borrow rate a strategy may reach at 100% utilization (1000% APR)
+pub const MAX_STRATEGY_RATE_BPS: i128 = 100_000;
+
+/// Storage keys for rate strategies
+#[contracttype]
+#[derive(Clone)]
+pub enum RateStrategyDataKey {
+    /// Strategy per cross-asset market
+    Strategy(AssetKey),
+}
+
+/// Two-slope borrow rate model for one asset (all values in basis points)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateStrategy {
+    /// Borrow rate at 0% utilization
+    pub base_rate_bps: i128,
+    /// Rate added between 0% and optimal utilization
+    pub slope1_bps: i128,
+    /// Rate added between optimal and 100% utilization
+    pub slope2_bps: i128,
+    /// Utilization at which the slope changes (the kink)
+    pub optimal_utilization_bps: i128,
+}
+
+impl RateStrategy {
+    /// Borrow rate at the given utilization, in basis points
+    pub fn borrow_rate(&self, utilization_bps: i128) -> Result<i128, InterestRateError> {
+        let utilization = utilization_bps.clamp(0, BASIS_POINTS_SCALE.get());
+
+        if utilization <= self.optimal_utilization_bps {
+            let increase = utilization
+                .checked_mul(self.slope1_bps)
+                .ok_or(InterestRateError::Overflow)?
+                .checked_div(self.optimal_utilization_bps)
+                .ok_or(InterestRateError::DivisionByZero)?;
+            return self
+                .base_rate_bps
+                .checked_add(increase)
+                .ok_or(InterestRateError::Overflow);
+        }
+
+        let excess = utilization - self.optimal_utilization_bps;
+        let excess_range = BASIS_POINTS_SCALE.get() - self.optimal_utilization_bps;
+        let increase = excess
+            .checked_mul(self.slope2_bps)
+            .ok_or(InterestRateError::Overflow)?
+            .checked_div(excess_range)
+            .ok_or(InterestRateError::DivisionByZero)?;
+        self.base_rate_bps
+            .checked_add(self.slope1_bps)
+            .and_then(|rate| rate.checked_add(increase))
+            .ok_or(InterestRateError::Overflow)
+    }
+
+    fn validate(&self) -> Result<(), InterestRateError> {
+        if self.base_rate_bps < 0
+            || self.slope1_bps < 0
+            || self.slope2_bps < 0
+            || self.optimal_utilization_bps <= 0
+            || !Bps::new(self.optimal_utilization_bps).is_ratio()
+        {
+            return Err(InterestRateError::InvalidParameter);
+        }
+
+        let max_rate = self
+            .base_rate_bps
+            .checked_add(self.slope1_bps)
+            .and_then(|rate| rate.checked_add(self.slope2_bps))
+            .ok_or(InterestRateError::Overflow)?;
+        if max_rate > MAX_STRATEGY_RATE_BPS {
+            return Err(InterestRateError::InvalidParameter);
+        }
+        Ok(())
+    }
+}
+
+/// Set the borrow rate strategy for a cross-asset market (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be the interest rate admin)
+/// * `asset` - Market to configure (`None` for XLM)
+/// * `strategy` - Base rate, slopes and optimal utilization
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the admin
+/// * `InvalidParameter` - A value is negative, the optimal utilization is not
+///   in (0%, 100%], or the maximum rate exceeds [`MAX_STRATEGY_RATE_BPS`]
+pub fn set_rate_strategy(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    strategy: RateStrategy,
+) -> Result<(), InterestRateError> {
+    let admin =
+        storage::get_instance::<InterestRateDataKey, Address>(env, &InterestRateDataKey::Admin)
+            .ok_or(InterestRateError::Unauthorized)?;
+
+    if caller != admin {
+        return Err(InterestRateError::Unauthorized);
+    }
+    caller.require_auth();
+
+    strategy.validate()?;
+
+    let key = RateStrategyDataKey::Strategy(AssetKey::from_option(asset));
+    storage::set_instance(env, &key, &strategy);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_rate_strategy"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the rate strategy of a market, if set
+pub fn get_rate_strategy(env: &Env, asset: Option<Address>) -> Option<RateStrategy> {
+    let key = RateStrategyDataKey::Strategy(AssetKey::from_option(asset));
+    storage::get_instance::<RateStrategyDataKey, RateStrategy>(env, &key)
+}
+
+/// Current borrow rate of a market, in basis points
+///
+/// Markets without a strategy have a rate of zero.
+pub fn get_asset_borrow_rate(env: &Env, asset: Option<Address>) -> Result<i128, InterestRateError> {
+    let asset_key = AssetKey::from_option(asset.clone());
+    match get_rate_strategy(env, asset) {
+        Some(strategy) => strategy.borrow_rate(get_market_utilization(env, &asset_key)),
+        None => Ok(0),
+    }
+}
+
+/// Current supply rate of a market, in basis points
+///
+/// The borrow rate scaled by utilization, less the market's reserve factor:
+/// the yield suppliers earn from the interest that accrues while the rate holds.
+pub(crate) fn get_asset_supply_rate(
+    env: &Env,
+    asset: Option<Address>,
+    reserve_factor: i128,
+) -> Result<i128, InterestRateError> {
+    let utilization = get_market_utilization(env, &AssetKey::from_option(asset.clone()));
+    let earned = Bps::new(utilization)
+        .apply(Amount::new(get_asset_borrow_rate(env, asset)?))
+        .map_err(|_| InterestRateError::Overflow)?;
+    let reserves = Bps::new(reserve_factor)
+        .apply(earned)
+        .map_err(|_| InterestRateError::Overflow)?;
+    Ok(earned
+        .checked_sub(reserves)
+        .map_err(|_| InterestRateError::Overflow)?
+        .get())
+}
@@ -0,0 +1,299 @@
+//! # Admin Recovery
+//!
+//! Lets a guardian quorum replace the protocol admin when the admin key is
+//! lost or compromised.
+//!
+//! The admin configures a guardian set with [`set_guardians`]: M guardians, a
+//! threshold N, and a delay of at least [`MIN_RECOVERY_DELAY`]. Recovery then
+//! runs in three steps:
+//! 1. A guardian proposes a new admin with [`propose_recovery`].
+//! 2. Other guardians approve with [`approve_recovery`]. The delay starts once
+//!    N guardians (the proposer included) have approved.
+//! 3. After the delay anyone can call [`execute_recovery`], which makes the
+//!    proposed address the protocol admin, interest rate admin and, if it was
+//!    held by the old admin, cross-asset admin.
+//!
+//! Until execution the current admin can cancel with [`cancel_recovery`], so a
+//! guardian quorum cannot take over a protocol whose admin is still active.
+//! Only one recovery can be pending; changing the guardian set cancels it.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Vec};
+
+use crate::cross_asset::CrossAssetDataKey;
+use crate::events::{
+    emit_admin_recovered, emit_guardians_updated, emit_recovery_approved, emit_recovery_cancelled,
+    emit_recovery_proposed, AdminRecoveredEvent, GuardiansUpdatedEvent, RecoveryApprovedEvent,
+    RecoveryCancelledEvent, RecoveryProposedEvent,
+};
+use crate::interest_rate::InterestRateDataKey;
+use crate::risk_management::{get_admin, RiskDataKey};
+use crate::roles::require_protocol_admin;
+use crate::storage;
+
+/// Shortest delay between quorum and execution (2 days)
+pub const MIN_RECOVERY_DELAY: u64 = 2 * 86_400;
+
+/// Largest guardian set
+pub const MAX_GUARDIANS: u32 = 10;
+
+/// Errors that can occur during admin recovery
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RecoveryError {
+    /// Caller is not the protocol admin
+    Unauthorized = 1,
+    /// Empty, oversized or duplicated guardian set, threshold outside
+    /// `1..=guardians`, or delay below [`MIN_RECOVERY_DELAY`]
+    InvalidConfig = 2,
+    /// Caller is not a guardian
+    NotGuardian = 3,
+    /// A recovery is already pending
+    RecoveryPending = 4,
+    /// No recovery is pending
+    NoRecovery = 5,
+    /// The guardian already approved the pending recovery
+    AlreadyApproved = 6,
+    /// Fewer guardians than the threshold have approved
+    QuorumNotReached = 7,
+    /// The delay since quorum has not elapsed
+    DelayNotElapsed = 8,
+}
+
+/// Storage keys for recovery data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum RecoveryDataKey {
+    /// Guardian set: GuardianConfig
+    Guardians,
+    /// Pending recovery: RecoveryRequest
+    Request,
+}
+
+/// Guardians allowed to rotate the admin
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianConfig {
+    /// Guardian addresses
+    pub guardians: Vec<Address>,
+    /// Approvals needed to start the delay
+    pub threshold: u32,
+    /// Seconds between quorum and execution
+    pub delay: u64,
+}
+
+/// A pending admin rotation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryRequest {
+    /// Proposed protocol admin
+    pub new_admin: Address,
+    /// Guardians that approved, proposer first
+    pub approvals: Vec<Address>,
+    /// When the recovery was proposed
+    pub proposed_at: u64,
+    /// When the threshold was reached
+    pub quorum_at: Option<u64>,
+}
+
+/// Set the guardian set (protocol admin only)
+///
+/// Cancels any pending recovery, whose approvals may come from replaced
+/// guardians.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+/// * `InvalidConfig` - See [`RecoveryError::InvalidConfig`]
+pub fn set_guardians(
+    env: &Env,
+    caller: Address,
+    config: GuardianConfig,
+) -> Result<(), RecoveryError> {
+    require_protocol_admin(env, &caller).map_err(|_| RecoveryError::Unauthorized)?;
+    let count = config.guardians.len();
+    if count == 0
+        || count > MAX_GUARDIANS
+        || config.threshold == 0
+        || config.threshold > count
+        || config.delay < MIN_RECOVERY_DELAY
+    {
+        return Err(RecoveryError::InvalidConfig);
+    }
+    for (i, guardian) in config.guardians.iter().enumerate() {
+        if config.guardians.first_index_of(&guardian) != Some(i as u32) {
+            return Err(RecoveryError::InvalidConfig);
+        }
+    }
+
+    storage::remove(env, &RecoveryDataKey::Request);
+    storage::set(env, &RecoveryDataKey::Guardians, &config);
+
+    emit_guardians_updated(
+        env,
+        GuardiansUpdatedEvent {
+            admin: caller,
+            guardians: config.guardians,
+            threshold: config.threshold,
+            delay: config.delay,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get the guardian set, if configured
+pub fn get_guardians(env: &Env) -> Option<GuardianConfig> {
+    storage::get(env, &RecoveryDataKey::Guardians)
+}
+
+/// Get the pending recovery, if any
+pub fn get_recovery(env: &Env) -> Option<RecoveryRequest> {
+    storage::get(env, &RecoveryDataKey::Request)
+}
+
+/// Propose `new_admin` as protocol admin (guardian only)
+///
+/// The proposal counts as the proposer's approval.
+///
+/// # Errors
+/// * `NotGuardian` - Caller is not a guardian
+/// * `RecoveryPending` - Another recovery is pending
+pub fn propose_recovery(
+    env: &Env,
+    guardian: Address,
+    new_admin: Address,
+) -> Result<(), RecoveryError> {
+    let config = require_guardian(env, &guardian)?;
+    if get_recovery(env).is_some() {
+        return Err(RecoveryError::RecoveryPending);
+    }
+
+    let now = env.ledger().timestamp();
+    let request = RecoveryRequest {
+        new_admin: new_admin.clone(),
+        approvals: Vec::from_array(env, [guardian.clone()]),
+        proposed_at: now,
+        quorum_at: (config.threshold == 1).then_some(now),
+    };
+    storage::set(env, &RecoveryDataKey::Request, &request);
+
+    emit_recovery_proposed(
+        env,
+        RecoveryProposedEvent {
+            guardian,
+            new_admin,
+            timestamp: now,
+        },
+    );
+    Ok(())
+}
+
+/// Approve the pending recovery (guardian only)
+///
+/// # Errors
+/// * `NotGuardian` - Caller is not a guardian
+/// * `NoRecovery` - No recovery is pending
+/// * `AlreadyApproved` - Caller already approved
+pub fn approve_recovery(env: &Env, guardian: Address) -> Result<(), RecoveryError> {
+    let config = require_guardian(env, &guardian)?;
+    let mut request = get_recovery(env).ok_or(RecoveryError::NoRecovery)?;
+    if request.approvals.contains(&guardian) {
+        return Err(RecoveryError::AlreadyApproved);
+    }
+
+    let now = env.ledger().timestamp();
+    request.approvals.push_back(guardian.clone());
+    if request.quorum_at.is_none() && request.approvals.len() >= config.threshold {
+        request.quorum_at = Some(now);
+    }
+    storage::set(env, &RecoveryDataKey::Request, &request);
+
+    emit_recovery_approved(
+        env,
+        RecoveryApprovedEvent {
+            guardian,
+            approvals: request.approvals.len(),
+            timestamp: now,
+        },
+    );
+    Ok(())
+}
+
+/// Cancel the pending recovery (protocol admin only)
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+/// * `NoRecovery` - No recovery is pending
+pub fn cancel_recovery(env: &Env, caller: Address) -> Result<(), RecoveryError> {
+    require_protocol_admin(env, &caller).map_err(|_| RecoveryError::Unauthorized)?;
+    let request = get_recovery(env).ok_or(RecoveryError::NoRecovery)?;
+    storage::remove(env, &RecoveryDataKey::Request);
+
+    emit_recovery_cancelled(
+        env,
+        RecoveryCancelledEvent {
+            admin: caller,
+            new_admin: request.new_admin,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Make the proposed address the admin once the delay has passed (anyone can call)
+///
+/// # Errors
+/// * `NoRecovery` - No recovery is pending
+/// * `QuorumNotReached` - Fewer guardians than the threshold approved
+/// * `DelayNotElapsed` - The delay since quorum has not elapsed
+pub fn execute_recovery(env: &Env) -> Result<(), RecoveryError> {
+    let request = get_recovery(env).ok_or(RecoveryError::NoRecovery)?;
+    let quorum_at = request.quorum_at.ok_or(RecoveryError::QuorumNotReached)?;
+    let config = get_guardians(env).ok_or(RecoveryError::NoRecovery)?;
+    let now = env.ledger().timestamp();
+    if now < quorum_at.saturating_add(config.delay) {
+        return Err(RecoveryError::DelayNotElapsed);
+    }
+
+    let old_admin = get_admin(env);
+    rotate_admin(env, old_admin.as_ref(), &request.new_admin);
+    storage::remove(env, &RecoveryDataKey::Request);
+
+    emit_admin_recovered(
+        env,
+        AdminRecoveredEvent {
+            old_admin,
+            new_admin: request.new_admin,
+            timestamp: now,
+        },
+    );
+    Ok(())
+}
+
+/// Replace the admin everywhere the old admin was recorded
+fn rotate_admin(env: &Env, old_admin: Option<&Address>, new_admin: &Address) {
+    storage::set_instance(env, &RiskDataKey::Admin, new_admin);
+
+    let Some(old_admin) = old_admin else {
+        return;
+    };
+    let rate_admin: Option<Address> = storage::get_instance(env, &InterestRateDataKey::Admin);
+    if rate_admin.as_ref() == Some(old_admin) {
+        storage::set_instance(env, &InterestRateDataKey::Admin, new_admin);
+    }
+    let cross_asset_admin: Option<Address> = storage::get(env, &CrossAssetDataKey::Admin);
+    if cross_asset_admin.as_ref() == Some(old_admin) {
+        storage::set(env, &CrossAssetDataKey::Admin, new_admin);
+    }
+}
+
+/// Check that `guardian` is in the guardian set and authorized the call
+fn require_guardian(env: &Env, guardian: &Address) -> Result<GuardianConfig, RecoveryError> {
+    let config = get_guardians(env).ok_or(RecoveryError::NotGuardian)?;
+    if !config.guardians.contains(guardian) {
+        return Err(RecoveryError::NotGuardian);
+    }
+    guardian.require_auth();
+    Ok(config)
+}
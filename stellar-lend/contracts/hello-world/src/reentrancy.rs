@@ -0,0 +1,60 @@
+//! # Re-entrancy Guard
+//!
+//! Marks a state-changing operation as in progress for the duration of its
+//! external calls (token transfers today; flash loan receivers, swap adapters,
+//! and hooks later).
+//!
+//! Every guarded operation takes an [`OperationGuard`] before doing anything
+//! else. The guard sets a scratch flag in temporary storage (see `storage`),
+//! which is cheaper to write than instance storage, and clears it when dropped,
+//! so a token or hook contract that calls back into any guarded operation
+//! mid-flight is rejected with the module's `Reentrancy` error. A failed
+//! operation is rolled back by the host, flag included, so the flag cannot be
+//! left set.
+//!
+//! The Soroban host currently refuses re-entry into a contract that is already
+//! on the call stack, so a nested call is usually stopped before it reaches the
+//! guard. The guard keeps the invariant in contract state instead of relying on
+//! host behaviour, and lets each operation report it with its own error.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::storage;
+
+/// Storage key for the operation-in-progress flag
+#[contracttype]
+#[derive(Clone)]
+pub enum GuardDataKey {
+    /// Set while a guarded operation is executing
+    OperationInProgress,
+}
+
+/// Returned when a guarded operation is entered while another is in progress
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Reentered;
+
+/// Holds the operation-in-progress flag until dropped
+pub struct OperationGuard<'a> {
+    env: &'a Env,
+}
+
+impl<'a> OperationGuard<'a> {
+    /// Mark an operation as in progress.
+    ///
+    /// Fails with [`Reentered`] if another guarded operation is already
+    /// executing in this invocation.
+    pub fn enter(env: &'a Env) -> Result<Self, Reentered> {
+        let key = GuardDataKey::OperationInProgress;
+        if storage::has_scratch(env, &key) {
+            return Err(Reentered);
+        }
+        storage::set_scratch(env, &key, &true);
+        Ok(OperationGuard { env })
+    }
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        storage::remove_scratch(self.env, &GuardDataKey::OperationInProgress);
+    }
+}
@@ -9,20 +9,36 @@
 //! 1. Accrued interest is paid first.
 //! 2. Any remaining repayment amount reduces the principal debt.
 //!
+//! Interest paid in the token of a listed market is credited to that
+//! market's lending pool, less the reserve factor (see `lending_pool`).
+//!
 //! ## Invariants
 //! - Repay amount must be strictly positive.
 //! - User must have outstanding debt to repay.
+//! - Repayments must clear the debt or leave at least the asset's minimum debt.
 //! - Token transfers use `transfer_from`, requiring prior user approval.
 
 #![allow(unused)]
 use soroban_sdk::{contracterror, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
+use crate::analytics::ActivityType;
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::BASIS_POINTS_SCALE;
+use crate::credit::record_loan_repaid;
+use crate::cross_asset::{get_asset_config_by_address, is_dust_debt, AssetKey};
 use crate::deposit::{
-    add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
-    emit_user_activity_tracked_event, update_protocol_analytics, update_user_analytics, Activity,
-    DepositDataKey, Position, ProtocolAnalytics, UserAnalytics,
+    add_activity_log, adjust_asset_totals, emit_analytics_updated_event,
+    emit_position_updated_event, emit_user_activity_tracked_event, update_protocol_analytics,
+    update_user_analytics, DepositDataKey, Position, ProtocolAnalytics, UserAnalytics,
 };
 use crate::events::{emit_repay, RepayEvent};
+use crate::lending_pool::distribute_interest;
+use crate::loyalty::refresh_tier;
+use crate::rate_mode::clear_rate_mode;
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::risk_management::is_emergency_paused;
+use crate::storage;
+use stellarlend_common::pause::{is_switch_set, PAUSE_REPAY};
 
 /// Errors that can occur during repay operations
 #[contracterror]
@@ -43,6 +59,15 @@ pub enum RepayError {
     Overflow = 6,
     /// Reentrancy detected
     Reentrancy = 7,
+    /// Debt left after the repayment would be nonzero but below the asset's
+    /// minimum debt
+    DebtBelowMinimum = 8,
+}
+
+impl From<Reentered> for RepayError {
+    fn from(_: Reentered) -> Self {
+        RepayError::Reentrancy
+    }
 }
 
 /// Annual interest rate in basis points (e.g., 500 = 5% per year)
@@ -52,9 +77,11 @@ pub enum RepayError {
 /// Calculate interest accrued since last accrual time
 /// Uses simple interest: interest = principal * rate * time
 /// Calculate accrued interest using dynamic interest rate
-/// Uses the current borrow rate based on protocol utilization
+/// Uses the current borrow rate based on protocol utilization, less any
+/// staker discount the user qualifies for
 fn calculate_accrued_interest(
     env: &Env,
+    user: &Address,
     principal: i128,
     last_accrual_time: u64,
     current_time: u64,
@@ -68,8 +95,7 @@ fn calculate_accrued_interest(
     }
 
     // Get current borrow rate (in basis points)
-    let rate_bps =
-        crate::interest_rate::calculate_borrow_rate(env).map_err(|_| RepayError::Overflow)?;
+    let rate_bps = crate::rate_mode::accrual_rate(env, user).map_err(|_| RepayError::Overflow)?;
 
     // Calculate interest using the dynamic rate
     crate::interest_rate::calculate_accrued_interest(
@@ -83,7 +109,7 @@ fn calculate_accrued_interest(
 
 /// Accrue interest on a position
 /// Updates the position's borrow_interest and last_accrual_time
-fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), RepayError> {
+fn accrue_interest(env: &Env, user: &Address, position: &mut Position) -> Result<(), RepayError> {
     let current_time = env.ledger().timestamp();
 
     if position.debt == 0 {
@@ -93,8 +119,13 @@ fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), RepayError>
     }
 
     // Calculate new interest accrued using dynamic rate
-    let new_interest =
-        calculate_accrued_interest(env, position.debt, position.last_accrual_time, current_time)?;
+    let new_interest = calculate_accrued_interest(
+        env,
+        user,
+        position.debt,
+        position.last_accrual_time,
+        current_time,
+    )?;
 
     // Add to existing interest
     position.borrow_interest = position
@@ -126,9 +157,11 @@ fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), RepayError>
 /// * `RepayError::InvalidAmount` - If amount is zero or negative
 /// * `RepayError::InvalidAsset` - If asset address is invalid
 /// * `RepayError::InsufficientBalance` - If user doesn't have enough balance
-/// * `RepayError::RepayPaused` - If repayments are paused
+/// * `RepayError::RepayPaused` - If repayments or the protocol are paused
 /// * `RepayError::NoDebt` - If user has no debt to repay
 /// * `RepayError::Overflow` - If calculation overflow occurs
+/// * `RepayError::DebtBelowMinimum` - If the remaining debt would be nonzero but below the
+///   asset's minimum debt
 ///
 /// # Security
 /// * Validates repay amount > 0
@@ -146,6 +179,8 @@ pub fn repay_debt(
     asset: Option<Address>,
     amount: i128,
 ) -> Result<(i128, i128, i128), RepayError> {
+    let _guard = OperationGuard::enter(env)?;
+
     // Validate amount
     if amount <= 0 {
         return Err(RepayError::InvalidAmount);
@@ -153,18 +188,19 @@ pub fn repay_debt(
 
     // Check if repayments are paused
     let pause_switches_key = DepositDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Map<Symbol, bool>>(&pause_switches_key)
+    if let Some(pause_map) =
+        storage::get::<DepositDataKey, Map<Symbol, bool>>(env, &pause_switches_key)
     {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_repay")) {
-            if paused {
-                return Err(RepayError::RepayPaused);
-            }
+        if is_switch_set(env, &pause_map, PAUSE_REPAY) {
+            return Err(RepayError::RepayPaused);
         }
     }
 
+    // Check emergency pause
+    if is_emergency_paused(env) {
+        return Err(RepayError::RepayPaused);
+    }
+
     // Get current timestamp
     let timestamp = env.ledger().timestamp();
 
@@ -178,11 +214,8 @@ pub fn repay_debt(
 
     // Get user position
     let position_key = DepositDataKey::Position(user.clone());
-    let mut position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
-        .ok_or(RepayError::NoDebt)?;
+    let mut position =
+        storage::get::<DepositDataKey, Position>(env, &position_key).ok_or(RepayError::NoDebt)?;
 
     // Check if user has debt
     if position.debt == 0 && position.borrow_interest == 0 {
@@ -190,7 +223,7 @@ pub fn repay_debt(
     }
 
     // Accrue interest before repayment
-    accrue_interest(env, &mut position)?;
+    accrue_interest(env, &user, &mut position)?;
 
     // Calculate total debt (principal + interest)
     let total_debt = position
@@ -206,6 +239,13 @@ pub fn repay_debt(
         // Partial repayment
         amount
     };
+    if is_dust_debt(
+        env,
+        &AssetKey::from_option(asset.clone()),
+        total_debt - repay_amount,
+    ) {
+        return Err(RepayError::DebtBelowMinimum);
+    }
 
     // Handle asset transfer - user pays the contract
     if let Some(ref asset_addr) = asset {
@@ -224,6 +264,7 @@ pub fn repay_debt(
             &env.current_contract_address(), // to (this contract)
             &repay_amount,
         );
+        cash_ledger::record_inflow(env, asset_addr, CashReason::Repay, repay_amount);
     } else {
         // Native XLM repayment - in Soroban, native assets are handled differently
         // For now, we'll track it but actual XLM handling depends on Soroban's native asset support
@@ -250,25 +291,39 @@ pub fn repay_debt(
 
     position.debt = position.debt.checked_sub(principal_paid).unwrap_or(0); // Should not underflow, but handle gracefully
 
+    // Interest received in a listed market's token is earned by its pool
+    if asset.is_some() && interest_paid > 0 {
+        if let Ok(config) = get_asset_config_by_address(env, asset.clone()) {
+            distribute_interest(env, asset.clone(), interest_paid, config.reserve_factor)
+                .map_err(|_| RepayError::Overflow)?;
+        }
+    }
+
     position.last_accrual_time = timestamp;
 
     // Save updated position
-    env.storage().persistent().set(&position_key, &position);
+    storage::set(env, &position_key, &position);
+    adjust_asset_totals(env, &asset, 0, -principal_paid);
 
     // Update user analytics
     update_user_analytics_repay(env, &user, repay_amount, timestamp)?;
 
     // Update protocol analytics
     update_protocol_analytics_repay(env, repay_amount)?;
+    if position.debt == 0 && position.borrow_interest == 0 {
+        record_loan_repaid(env, &user);
+        clear_rate_mode(env, &user);
+    }
 
     // Add to activity log
     add_activity_log(
         env,
         &user,
-        Symbol::new(env, "repay"),
+        ActivityType::Repay,
         repay_amount,
         asset.clone(),
         timestamp,
+        None,
     )
     .map_err(|e| match e {
         crate::deposit::DepositError::Overflow => RepayError::Overflow,
@@ -318,10 +373,7 @@ fn update_user_analytics_repay(
 ) -> Result<(), RepayError> {
     let analytics_key = DepositDataKey::UserAnalytics(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, UserAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, UserAnalytics>(env, &analytics_key)
         .unwrap_or_else(|| UserAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -350,7 +402,7 @@ fn update_user_analytics_repay(
     if analytics.debt_value > 0 && analytics.collateral_value > 0 {
         analytics.collateralization_ratio = analytics
             .collateral_value
-            .checked_mul(10000)
+            .checked_mul(BASIS_POINTS_SCALE.get())
             .and_then(|v| v.checked_div(analytics.debt_value))
             .unwrap_or(0);
     } else {
@@ -359,18 +411,16 @@ fn update_user_analytics_repay(
 
     analytics.transaction_count = analytics.transaction_count.saturating_add(1);
     analytics.last_activity = timestamp;
+    refresh_tier(env, user, &mut analytics);
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
     Ok(())
 }
 
 /// Update protocol analytics after repayment
 fn update_protocol_analytics_repay(env: &Env, amount: i128) -> Result<(), RepayError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, ProtocolAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, ProtocolAnalytics>(env, &analytics_key)
         .unwrap_or(ProtocolAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -381,6 +431,6 @@ fn update_protocol_analytics_repay(env: &Env, amount: i128) -> Result<(), RepayE
     // In a more sophisticated model, you might track active borrows separately
     // For now, we just update the analytics structure
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
     Ok(())
 }
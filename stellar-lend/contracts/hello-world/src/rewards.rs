@@ -0,0 +1,599 @@
+//! # Reward Emissions
+//!
+//! Liquidity mining for cross-asset markets: a treasury admin picks a reward
+//! token and sets, per market, how many reward tokens are emitted each second
+//! to its lending pool suppliers (see `lending_pool`). Emissions are shared
+//! pro rata to supply shares.
+//!
+//! ## Token-Backed Balances
+//! Rewards pay out real tokens, so they are only earned on balances backed by
+//! tokens the contract holds. Pool shares are minted for tokens pulled in by
+//! `supply`. Cross-asset collateral and debt are accounting entries that move
+//! no tokens, so they earn nothing: weighting emissions by them would let
+//! anyone record an unbacked deposit and take nearly all of a market's
+//! emissions. For the same reason there is no token-backed debt to share
+//! emissions across, so borrowers earn none.
+//!
+//! ## Indexes
+//! Every market with emissions keeps a supply reward index: the rewards
+//! earned per unit of pool shares since emissions began, scaled by
+//! [`REWARD_INDEX_ONE`]. Whenever a user's supply shares in the market change
+//! (supply, redeem, rate lock settlement, insurance premium), the index is
+//! brought up to date and the user's rewards since their last checkpoint are
+//! added to their accrued rewards at the balance they held over that period.
+//! Cross-asset deposits, withdrawals and liquidation seizures move no pool
+//! shares, but settle the users' rewards in the market all the same.
+//! Shares supplied before a market's emissions started earn from their
+//! holder's next interaction.
+//!
+//! Emissions while a market has no supply are not distributed. Setting the
+//! rate to zero pauses a market's emissions; its index keeps being
+//! checkpointed so it stays consistent if emissions resume.
+//!
+//! ## Funding and Claims
+//! Rewards are paid from a balance funded with [`fund_rewards`].
+//! [`claim_rewards`] pays a user's accrued rewards up to that balance; any
+//! remainder stays accrued until the balance is topped up.
+//!
+//! ## Programs
+//! Each period a market emits at one rate is a reward program, numbered from
+//! 1 in the order programs start. Setting a market's rate to a new non-zero
+//! value ends its running program and starts another; setting it to zero
+//! ends it. [`get_program_report`] evaluates a program over its window: the
+//! rewards it emitted, the users who earned them, their average boost and the
+//! change in the market's pool liquidity. A user counts as a claimant once
+//! their rewards are settled (by a claim or a change in their balance) while
+//! the program runs. Emissions carry no boosts, so every claimant earns at
+//! 1x.
+//!
+//! ## Claim Delegates
+//! A user may name a delegate with [`set_reward_delegate`], such as an
+//! automation service, that can claim on their behalf. Claims always pay the
+//! user, so a delegate never takes custody of the rewards.
+
+use soroban_sdk::{contracterror, contracttype, token, Address, Env, Map};
+
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::BASIS_POINTS_SCALE;
+use crate::cross_asset::{get_asset_config_by_address, require_role, AssetKey};
+use crate::events::{
+    emit_emission_rates_updated, emit_reward_delegate_updated, emit_rewards_claimed,
+    emit_rewards_funded, EmissionRatesUpdatedEvent, RewardDelegateUpdatedEvent,
+    RewardsClaimedEvent, RewardsFundedEvent,
+};
+use crate::lending_pool::{get_pool_state, get_supply_shares};
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::roles::Role;
+use crate::storage;
+
+/// Scale of reward indexes: rewards per unit of balance times 10^18
+pub const REWARD_INDEX_ONE: i128 = 1_000_000_000_000_000_000;
+
+/// Errors that can occur while configuring or claiming rewards
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RewardsError {
+    /// Caller is neither the admin nor a treasury admin
+    Unauthorized = 1,
+    /// The reward token has already been set
+    TokenAlreadySet = 2,
+    /// No reward token has been set
+    TokenNotSet = 3,
+    /// The asset is not a registered cross-asset market
+    AssetNotConfigured = 4,
+    /// A rate is negative, or a funding amount is not positive
+    InvalidAmount = 5,
+    /// Reentrant call detected
+    Reentrancy = 6,
+    /// Caller is neither the user nor their claim delegate
+    NotDelegate = 7,
+    /// No reward program has this id
+    ProgramNotFound = 8,
+}
+
+impl From<Reentered> for RewardsError {
+    fn from(_: Reentered) -> Self {
+        RewardsError::Reentrancy
+    }
+}
+
+/// Storage keys for reward emissions
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum RewardsDataKey {
+    /// Token rewards are paid in: Address
+    Token,
+    /// Emission rates of every market that has had emissions:
+    /// Map<AssetKey, EmissionRates>
+    Emissions,
+    /// Reward index of a market: RewardIndex
+    Index(AssetKey),
+    /// A user's last checkpoint in a market: UserRewardIndex
+    User(Address, AssetKey),
+    /// A user's unclaimed rewards: i128
+    Accrued(Address),
+    /// Funded rewards not yet paid out: i128
+    Balance,
+    /// Account allowed to claim a user's rewards: Address
+    Delegate(Address),
+    /// Reward programs started so far: u32
+    ProgramCount,
+    /// A reward program: RewardProgram
+    Program(u32),
+    /// Id of a market's running program: u32
+    ActiveProgram(AssetKey),
+    /// Whether a user has earned rewards from a program: bool
+    ProgramClaimant(u32, Address),
+}
+
+/// Reward tokens emitted per second to a market
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EmissionRates {
+    /// Shared by the market's pool suppliers
+    pub supply_rate: i128,
+}
+
+/// Reward index of one market
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardIndex {
+    /// Rewards per unit supplied, scaled by [`REWARD_INDEX_ONE`]
+    pub supply_index: i128,
+    /// Supply earning rewards: the sum of users' checkpointed balances
+    pub total_supplied: i128,
+    /// Rewards emitted to the market's suppliers since emissions began
+    pub distributed: i128,
+    /// When the index was last brought up to date
+    pub last_updated: u64,
+}
+
+/// A period of a market's emissions at one rate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardProgram {
+    /// The market's asset (`None` for native XLM)
+    pub asset: Option<Address>,
+    /// Reward tokens emitted per second to the market's pool suppliers
+    pub supply_rate: i128,
+    /// When the program started
+    pub started_at: u64,
+    /// When the program ended; `None` while it runs
+    pub ended_at: Option<u64>,
+    /// The market's distributed rewards when the program started
+    pub distributed_at_start: i128,
+    /// The market's distributed rewards when the program ended
+    pub distributed_at_end: i128,
+    /// The market's pool liquidity when the program started
+    pub liquidity_at_start: i128,
+    /// The market's pool liquidity when the program ended
+    pub liquidity_at_end: i128,
+    /// Users who have earned rewards from the program
+    pub claimants: u32,
+}
+
+/// A reward program's results over its window, up to now if it still runs
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProgramReport {
+    /// The program's id
+    pub program_id: u32,
+    /// The market's asset (`None` for native XLM)
+    pub asset: Option<Address>,
+    /// When the program started
+    pub started_at: u64,
+    /// When the program ended; `None` while it runs
+    pub ended_at: Option<u64>,
+    /// Rewards emitted to the market's suppliers during the window
+    pub emission_spent: i128,
+    /// Distinct users who earned rewards from the program
+    pub unique_claimants: u32,
+    /// Claimants' average reward multiplier in basis points: 10,000 (1x)
+    /// once anyone has earned, as emissions carry no boosts; 0 before
+    pub average_boost_bps: i128,
+    /// Change in the market's pool liquidity over the window
+    pub incremental_tvl: i128,
+}
+
+/// A user's balances and the market's indexes at their last checkpoint
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserRewardIndex {
+    /// Pool supply shares held since the checkpoint
+    pub supplied: i128,
+    /// The market's supply index at the checkpoint
+    pub supply_index: i128,
+}
+
+/// Set the token rewards are paid in (admin or `TreasuryAdmin` only)
+///
+/// The token can be set once, so accrued rewards always refer to it.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor a treasury admin
+/// * `TokenAlreadySet` - The reward token has already been set
+pub fn set_reward_token(env: &Env, caller: Address, token: Address) -> Result<(), RewardsError> {
+    require_role(env, &caller, Role::TreasuryAdmin).map_err(|_| RewardsError::Unauthorized)?;
+    if storage::has(env, &RewardsDataKey::Token) {
+        return Err(RewardsError::TokenAlreadySet);
+    }
+    storage::set(env, &RewardsDataKey::Token, &token);
+    Ok(())
+}
+
+/// Get the token rewards are paid in, if set
+pub fn get_reward_token(env: &Env) -> Option<Address> {
+    storage::get(env, &RewardsDataKey::Token)
+}
+
+/// Set a market's emission rates (admin or `TreasuryAdmin` only)
+///
+/// Rewards earned at the previous rates are indexed first.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is neither the admin nor a treasury admin
+/// * `TokenNotSet` - No reward token has been set
+/// * `AssetNotConfigured` - The asset is not a registered cross-asset market
+/// * `InvalidAmount` - The rate is negative
+pub fn set_emission_rates(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    rates: EmissionRates,
+) -> Result<(), RewardsError> {
+    require_role(env, &caller, Role::TreasuryAdmin).map_err(|_| RewardsError::Unauthorized)?;
+    if get_reward_token(env).is_none() {
+        return Err(RewardsError::TokenNotSet);
+    }
+    get_asset_config_by_address(env, asset.clone())
+        .map_err(|_| RewardsError::AssetNotConfigured)?;
+    if rates.supply_rate < 0 {
+        return Err(RewardsError::InvalidAmount);
+    }
+
+    let asset_key = AssetKey::from_option(asset.clone());
+    let mut emissions = get_all_emission_rates(env);
+    let current = emissions.get(asset_key.clone());
+    let index = accrue_index(env, &asset_key, &current.clone().unwrap_or_default());
+    if current.is_some() {
+        storage::set(env, &RewardsDataKey::Index(asset_key.clone()), &index);
+    }
+    if current.unwrap_or_default() != rates {
+        switch_program(env, &asset_key, rates.supply_rate, index.distributed);
+    }
+    emissions.set(asset_key.clone(), rates.clone());
+    storage::set(env, &RewardsDataKey::Emissions, &emissions);
+
+    emit_emission_rates_updated(
+        env,
+        EmissionRatesUpdatedEvent {
+            admin: caller,
+            asset,
+            supply_rate: rates.supply_rate,
+            program_id: storage::get(env, &RewardsDataKey::ActiveProgram(asset_key)),
+        },
+    );
+    Ok(())
+}
+
+/// End a market's running program, if any, and start one at `supply_rate`
+/// unless it is zero.
+fn switch_program(env: &Env, asset_key: &AssetKey, supply_rate: i128, distributed: i128) {
+    let now = env.ledger().timestamp();
+    let liquidity = get_pool_state(env, asset_key.to_option()).total_liquidity;
+    let active_key = RewardsDataKey::ActiveProgram(asset_key.clone());
+
+    if let Some(program_id) = storage::get::<_, u32>(env, &active_key) {
+        let program_key = RewardsDataKey::Program(program_id);
+        if let Some(mut program) = storage::get::<_, RewardProgram>(env, &program_key) {
+            program.ended_at = Some(now);
+            program.distributed_at_end = distributed;
+            program.liquidity_at_end = liquidity;
+            storage::set(env, &program_key, &program);
+        }
+        storage::remove(env, &active_key);
+    }
+    if supply_rate == 0 {
+        return;
+    }
+
+    let program_id = storage::get::<_, u32>(env, &RewardsDataKey::ProgramCount).unwrap_or(0) + 1;
+    storage::set(env, &RewardsDataKey::ProgramCount, &program_id);
+    storage::set(
+        env,
+        &RewardsDataKey::Program(program_id),
+        &RewardProgram {
+            asset: asset_key.to_option(),
+            supply_rate,
+            started_at: now,
+            ended_at: None,
+            distributed_at_start: distributed,
+            distributed_at_end: 0,
+            liquidity_at_start: liquidity,
+            liquidity_at_end: 0,
+            claimants: 0,
+        },
+    );
+    storage::set(env, &active_key, &program_id);
+}
+
+/// Get a reward program's results over its window, up to now if it still
+/// runs
+///
+/// # Errors
+/// * `ProgramNotFound` - No reward program has this id
+pub fn get_program_report(env: &Env, program_id: u32) -> Result<ProgramReport, RewardsError> {
+    let program: RewardProgram = storage::get(env, &RewardsDataKey::Program(program_id))
+        .ok_or(RewardsError::ProgramNotFound)?;
+    let (distributed, liquidity) = match program.ended_at {
+        Some(_) => (program.distributed_at_end, program.liquidity_at_end),
+        None => {
+            let asset_key = AssetKey::from_option(program.asset.clone());
+            let rates = get_emission_rates(env, program.asset.clone());
+            (
+                accrue_index(env, &asset_key, &rates).distributed,
+                get_pool_state(env, program.asset.clone()).total_liquidity,
+            )
+        }
+    };
+    Ok(ProgramReport {
+        program_id,
+        asset: program.asset,
+        started_at: program.started_at,
+        ended_at: program.ended_at,
+        emission_spent: distributed - program.distributed_at_start,
+        unique_claimants: program.claimants,
+        average_boost_bps: if program.claimants > 0 {
+            BASIS_POINTS_SCALE.get()
+        } else {
+            0
+        },
+        incremental_tvl: liquidity - program.liquidity_at_start,
+    })
+}
+
+/// Count a user as a claimant of the market's running program, once
+fn record_claimant(env: &Env, user: &Address, asset_key: &AssetKey) {
+    let Some(program_id) =
+        storage::get::<_, u32>(env, &RewardsDataKey::ActiveProgram(asset_key.clone()))
+    else {
+        return;
+    };
+    let claimant_key = RewardsDataKey::ProgramClaimant(program_id, user.clone());
+    if storage::has(env, &claimant_key) {
+        return;
+    }
+    let program_key = RewardsDataKey::Program(program_id);
+    if let Some(mut program) = storage::get::<_, RewardProgram>(env, &program_key) {
+        program.claimants += 1;
+        storage::set(env, &program_key, &program);
+        storage::set(env, &claimant_key, &true);
+    }
+}
+
+/// Get a market's emission rates; zero if it has none
+pub fn get_emission_rates(env: &Env, asset: Option<Address>) -> EmissionRates {
+    get_all_emission_rates(env)
+        .get(AssetKey::from_option(asset))
+        .unwrap_or_default()
+}
+
+fn get_all_emission_rates(env: &Env) -> Map<AssetKey, EmissionRates> {
+    storage::get(env, &RewardsDataKey::Emissions).unwrap_or(Map::new(env))
+}
+
+/// Add reward tokens to the balance claims are paid from (anyone can fund)
+///
+/// The contract pulls `amount` of the reward token from `from`, which must
+/// have approved it.
+///
+/// # Errors
+/// * `TokenNotSet` - No reward token has been set
+/// * `InvalidAmount` - `amount` is not positive
+pub fn fund_rewards(env: &Env, from: Address, amount: i128) -> Result<(), RewardsError> {
+    let _guard = OperationGuard::enter(env)?;
+    from.require_auth();
+    let token = get_reward_token(env).ok_or(RewardsError::TokenNotSet)?;
+    if amount <= 0 {
+        return Err(RewardsError::InvalidAmount);
+    }
+
+    token::Client::new(env, &token).transfer_from(
+        &env.current_contract_address(),
+        &from,
+        &env.current_contract_address(),
+        &amount,
+    );
+    cash_ledger::record_inflow(env, &token, CashReason::Rewards, amount);
+    storage::set(
+        env,
+        &RewardsDataKey::Balance,
+        &(get_rewards_balance(env) + amount),
+    );
+
+    emit_rewards_funded(
+        env,
+        RewardsFundedEvent {
+            from,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Funded rewards not yet paid out
+pub fn get_rewards_balance(env: &Env) -> i128 {
+    storage::get(env, &RewardsDataKey::Balance).unwrap_or(0)
+}
+
+/// Name the account allowed to claim a user's rewards, or remove it with
+/// `None`
+pub fn set_reward_delegate(env: &Env, user: Address, delegate: Option<Address>) {
+    user.require_auth();
+    let key = RewardsDataKey::Delegate(user.clone());
+    match &delegate {
+        Some(delegate) => storage::set(env, &key, delegate),
+        None => storage::remove(env, &key),
+    }
+
+    emit_reward_delegate_updated(env, RewardDelegateUpdatedEvent { user, delegate });
+}
+
+/// Get the account allowed to claim a user's rewards, if any
+pub fn get_reward_delegate(env: &Env, user: &Address) -> Option<Address> {
+    storage::get(env, &RewardsDataKey::Delegate(user.clone()))
+}
+
+/// Pay a user's accrued rewards to the user, up to the funded balance
+///
+/// # Arguments
+/// * `caller` - The user or their claim delegate
+/// * `user` - The user whose rewards are claimed
+///
+/// # Returns
+/// The amount paid; anything beyond the balance stays accrued.
+///
+/// # Errors
+/// * `NotDelegate` - Caller is neither the user nor their claim delegate
+/// * `TokenNotSet` - No reward token has been set
+pub fn claim_rewards(env: &Env, caller: Address, user: Address) -> Result<i128, RewardsError> {
+    let _guard = OperationGuard::enter(env)?;
+    caller.require_auth();
+    if caller != user && get_reward_delegate(env, &user).as_ref() != Some(&caller) {
+        return Err(RewardsError::NotDelegate);
+    }
+    let token = get_reward_token(env).ok_or(RewardsError::TokenNotSet)?;
+
+    for asset_key in get_all_emission_rates(env).keys() {
+        let shares = get_supply_shares(env, &user, asset_key.to_option());
+        checkpoint(env, &user, &asset_key, shares);
+    }
+
+    let accrued_key = RewardsDataKey::Accrued(user.clone());
+    let accrued: i128 = storage::get(env, &accrued_key).unwrap_or(0);
+    let balance = get_rewards_balance(env);
+    let amount = accrued.min(balance);
+    if amount <= 0 {
+        return Ok(0);
+    }
+
+    storage::set(env, &accrued_key, &(accrued - amount));
+    storage::set(env, &RewardsDataKey::Balance, &(balance - amount));
+    token::Client::new(env, &token).transfer(&env.current_contract_address(), &user, &amount);
+    cash_ledger::record_outflow(env, &token, CashReason::Rewards, amount);
+
+    emit_rewards_claimed(
+        env,
+        RewardsClaimedEvent {
+            caller,
+            user,
+            token,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(amount)
+}
+
+/// A user's unclaimed rewards, including those earned since their last
+/// checkpoint
+pub fn get_pending_rewards(env: &Env, user: &Address) -> i128 {
+    let mut pending: i128 = storage::get(env, &RewardsDataKey::Accrued(user.clone())).unwrap_or(0);
+    for (asset_key, rates) in get_all_emission_rates(env).iter() {
+        let Some(checkpoint) = storage::get::<_, UserRewardIndex>(
+            env,
+            &RewardsDataKey::User(user.clone(), asset_key.clone()),
+        ) else {
+            continue;
+        };
+        pending += earned_since(&checkpoint, &accrue_index(env, &asset_key, &rates));
+    }
+    pending
+}
+
+/// Settle a user's rewards in a market up to now and record their new
+/// supply shares.
+///
+/// Called whenever a user's pool supply shares are written; does nothing for
+/// markets that have never had emissions.
+pub(crate) fn checkpoint(env: &Env, user: &Address, asset_key: &AssetKey, supplied: i128) {
+    let Some(rates) = get_all_emission_rates(env).get(asset_key.clone()) else {
+        return;
+    };
+    let mut index = accrue_index(env, asset_key, &rates);
+    let user_key = RewardsDataKey::User(user.clone(), asset_key.clone());
+    let previous: Option<UserRewardIndex> = storage::get(env, &user_key);
+
+    if let Some(previous) = &previous {
+        let earned = earned_since(previous, &index);
+        if earned > 0 {
+            let accrued_key = RewardsDataKey::Accrued(user.clone());
+            let accrued: i128 = storage::get(env, &accrued_key).unwrap_or(0);
+            storage::set(env, &accrued_key, &(accrued + earned));
+            record_claimant(env, user, asset_key);
+        }
+    }
+
+    index.total_supplied += supplied - previous.map_or(0, |p| p.supplied);
+    storage::set(
+        env,
+        &user_key,
+        &UserRewardIndex {
+            supplied,
+            supply_index: index.supply_index,
+        },
+    );
+    storage::set(env, &RewardsDataKey::Index(asset_key.clone()), &index);
+}
+
+/// Settle a user's rewards in a market at the pool shares they hold.
+///
+/// Called on balance changes in a market that move no pool shares, such as
+/// cross-asset deposits, withdrawals and liquidation seizures, so a user's
+/// rewards are checkpointed on every balance change whether or not it earns.
+pub(crate) fn settle(env: &Env, user: &Address, asset_key: &AssetKey) {
+    let shares = get_supply_shares(env, user, asset_key.to_option());
+    // Share changes keep the checkpoint in step, so without shares there is
+    // nothing earned to settle
+    if shares > 0 {
+        checkpoint(env, user, asset_key, shares);
+    }
+}
+
+/// Get a market's reward index as of now
+pub fn get_reward_index(env: &Env, asset: Option<Address>) -> RewardIndex {
+    let asset_key = AssetKey::from_option(asset.clone());
+    accrue_index(env, &asset_key, &get_emission_rates(env, asset))
+}
+
+/// A market's stored index, grown by its emissions since it was last
+/// updated
+fn accrue_index(env: &Env, asset_key: &AssetKey, rates: &EmissionRates) -> RewardIndex {
+    let now = env.ledger().timestamp();
+    let mut index =
+        storage::get(env, &RewardsDataKey::Index(asset_key.clone())).unwrap_or(RewardIndex {
+            supply_index: 0,
+            total_supplied: 0,
+            distributed: 0,
+            last_updated: now,
+        });
+    let elapsed = now.saturating_sub(index.last_updated) as i128;
+    if index.total_supplied > 0 {
+        let emitted = rates.supply_rate.saturating_mul(elapsed);
+        index.supply_index += emitted.saturating_mul(REWARD_INDEX_ONE) / index.total_supplied;
+        index.distributed = index.distributed.saturating_add(emitted);
+    }
+    index.last_updated = now;
+    index
+}
+
+/// Rewards earned on a checkpoint's shares as the index grew to `index`
+fn earned_since(checkpoint: &UserRewardIndex, index: &RewardIndex) -> i128 {
+    checkpoint
+        .supplied
+        .saturating_mul(index.supply_index - checkpoint.supply_index)
+        / REWARD_INDEX_ONE
+}
@@ -8,21 +8,53 @@
 //! - **Close factor** (default 50%): max percentage of debt liquidatable per transaction
 //! - **Liquidation incentive** (default 10%): bonus awarded to liquidators
 //!
+//! ## Incentive Curve
+//! A flat incentive over-penalizes positions that are barely liquidatable.
+//! With a [`LiquidationIncentiveCurve`] configured, the bonus instead rises
+//! linearly with the position's shortfall: the minimum applies at a health
+//! factor of 1.0 (the liquidation threshold), and the maximum at and below the
+//! curve's `max_bonus_health_factor`.
+//!
 //! ## Pause Controls
 //! - Per-operation pause switches (deposit, withdraw, borrow, repay, liquidate)
 //! - Global emergency pause that halts all operations immediately
+//! - Recovery haircut: after the emergency pause is lifted, collateral is
+//!   valued conservatively for new borrows and withdrawals, decaying linearly
+//!   to zero over a configurable window
+//!
+//! ## Liquidation Target
+//! With a target health factor configured, a liquidation repays only enough
+//! debt to bring the position back to the target, even where the close
+//! factor would allow more. Positions whose health factor falls as debt is
+//! repaid, because the incentive outweighs the repayment, stay limited by the
+//! close factor alone.
+//!
+//! ## Threshold Migration
+//! With a migration delay configured, raising the liquidation threshold does
+//! not immediately expose existing borrowers. A position whose last borrow
+//! predates the change keeps the previous threshold until the delay ends;
+//! positions that borrow after the change use the new threshold at once.
 //!
 //! ## Safety
 //! - Parameter changes are limited to ±10% per update to prevent drastic shifts.
 //! - Min collateral ratio must always be ≥ liquidation threshold.
-//! - Only the admin address can modify risk parameters.
+//! - Only holders of the matching role can modify risk parameters or pause
+//!   operations (see [`crate::roles`]).
 
 #![allow(unused)]
+use crate::constants::{BASIS_POINTS_SCALE, HEALTH_FACTOR_ONE, SECONDS_PER_DAY};
+use crate::deposit::DepositDataKey;
 use crate::events::{
-    emit_admin_action, emit_pause_state_changed, emit_risk_params_updated, AdminActionEvent,
-    PauseStateChangedEvent, RiskParamsUpdatedEvent,
+    emit_admin_action, emit_pause_state_changed, emit_risk_params_updated,
+    emit_threshold_migration, AdminActionEvent, PauseStateChangedEvent, RiskParamsUpdatedEvent,
+    ThresholdMigrationEvent,
 };
+use crate::roles::{self, Role};
+use crate::storage;
 use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
+use stellarlend_common::pause::{
+    PAUSE_BORROW, PAUSE_DEPOSIT, PAUSE_LIQUIDATE, PAUSE_REPAY, PAUSE_WITHDRAW,
+};
 
 /// Errors that can occur during risk management operations
 #[contracterror]
@@ -69,6 +101,20 @@ pub enum RiskDataKey {
     EmergencyPause,
     /// Parameter change timelock (for safety)
     ParameterChangeTimelock,
+    /// Minimum seconds between a borrow and a collateral withdrawal
+    WithdrawCooldown,
+    /// Post-pause collateral haircut settings
+    RecoveryHaircut,
+    /// Timestamp at which the emergency pause was last lifted
+    PauseLiftedAt,
+    /// Grace period before a raised liquidation threshold reaches existing positions
+    ThresholdMigrationDelay,
+    /// Liquidation threshold increase still being phased in
+    ThresholdMigration,
+    /// Health-factor dependent liquidation incentive
+    IncentiveCurve,
+    /// Health factor a liquidation restores a position to, at most
+    LiquidationTarget,
 }
 
 /// Risk configuration parameters
@@ -93,6 +139,66 @@ pub struct RiskConfig {
     pub last_update: u64,
 }
 
+/// Risk parameters to change; `None` keeps the current value
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskParamsChange {
+    /// New minimum collateral ratio (in basis points)
+    pub min_collateral_ratio: Option<i128>,
+    /// New liquidation threshold (in basis points)
+    pub liquidation_threshold: Option<i128>,
+    /// New close factor (in basis points)
+    pub close_factor: Option<i128>,
+    /// New liquidation incentive (in basis points)
+    pub liquidation_incentive: Option<i128>,
+}
+
+/// Collateral haircut applied while the protocol recovers from an emergency pause
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoveryHaircutConfig {
+    /// Haircut in basis points applied when the pause is lifted
+    pub haircut_bps: i128,
+    /// Seconds over which the haircut decays linearly to zero
+    pub window_seconds: u64,
+}
+
+/// A liquidation threshold increase being phased in for existing positions
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThresholdMigration {
+    /// Threshold kept by positions whose last borrow predates `started_at`
+    pub previous_threshold: i128,
+    /// Timestamp of the threshold increase
+    pub started_at: u64,
+    /// Timestamp from which every position uses the current threshold
+    pub effective_at: u64,
+}
+
+/// Liquidation incentive that scales with how far a position is underwater
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationIncentiveCurve {
+    /// Incentive at a health factor of 1.0, in basis points
+    pub min_incentive_bps: i128,
+    /// Incentive at and below `max_bonus_health_factor`, in basis points
+    pub max_incentive_bps: i128,
+    /// Health factor, in basis points, from which the maximum applies
+    pub max_bonus_health_factor: i128,
+}
+
+/// Snapshot of the protocol's pause and recovery state
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProtocolHealth {
+    /// Whether the emergency pause is active
+    pub emergency_paused: bool,
+    /// Collateral haircut currently applied, in basis points
+    pub recovery_haircut_bps: i128,
+    /// Timestamp at which the haircut reaches zero (0 when none is active)
+    pub recovery_ends_at: u64,
+}
+
 /// Pause switch operation types
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -112,16 +218,20 @@ pub enum PauseOperation {
 }
 
 /// Constants for parameter validation
-const BASIS_POINTS_SCALE: i128 = 10_000; // 100% = 10,000 basis points
-const MIN_COLLATERAL_RATIO_MIN: i128 = 10_000; // 100% minimum
+const MIN_COLLATERAL_RATIO_MIN: i128 = BASIS_POINTS_SCALE.get(); // 100% minimum
 const MIN_COLLATERAL_RATIO_MAX: i128 = 50_000; // 500% maximum
-const LIQUIDATION_THRESHOLD_MIN: i128 = 10_000; // 100% minimum
+const LIQUIDATION_THRESHOLD_MIN: i128 = BASIS_POINTS_SCALE.get(); // 100% minimum
 const LIQUIDATION_THRESHOLD_MAX: i128 = 50_000; // 500% maximum
 const CLOSE_FACTOR_MIN: i128 = 0; // 0% minimum
-const CLOSE_FACTOR_MAX: i128 = BASIS_POINTS_SCALE; // 100% maximum
+const CLOSE_FACTOR_MAX: i128 = BASIS_POINTS_SCALE.get(); // 100% maximum
 const LIQUIDATION_INCENTIVE_MIN: i128 = 0; // 0% minimum
 const LIQUIDATION_INCENTIVE_MAX: i128 = 5_000; // 50% maximum (safety limit)
 const MAX_PARAMETER_CHANGE_BPS: i128 = 1_000; // 10% maximum change per update
+const MAX_WITHDRAW_COOLDOWN_SECONDS: u64 = SECONDS_PER_DAY.get(); // 1 day maximum cooldown
+const MAX_RECOVERY_HAIRCUT_BPS: i128 = 5_000; // 50% maximum haircut
+const MAX_RECOVERY_WINDOW_SECONDS: u64 = 7 * SECONDS_PER_DAY.get(); // 7 days maximum window
+const MAX_THRESHOLD_MIGRATION_DELAY_SECONDS: u64 = 30 * SECONDS_PER_DAY.get(); // 30 days maximum delay
+const MAX_LIQUIDATION_TARGET_HEALTH_FACTOR: i128 = 20_000; // 2.0 maximum target
 
 /// Initialize risk management system
 ///
@@ -140,12 +250,12 @@ const MAX_PARAMETER_CHANGE_BPS: i128 = 1_000; // 10% maximum change per update
 pub fn initialize_risk_management(env: &Env, admin: Address) -> Result<(), RiskManagementError> {
     // Guard against double initialization – admin key must not exist yet.
     let admin_key = RiskDataKey::Admin;
-    if env.storage().persistent().has::<RiskDataKey>(&admin_key) {
+    if storage::has_instance::<RiskDataKey>(env, &admin_key) {
         return Err(RiskManagementError::AlreadyInitialized);
     }
 
     // Set admin
-    env.storage().persistent().set(&admin_key, &admin);
+    storage::set_instance(env, &admin_key, &admin);
 
     // Initialize default risk config
     let default_config = RiskConfig {
@@ -161,11 +271,11 @@ pub fn initialize_risk_management(env: &Env, admin: Address) -> Result<(), RiskM
     validate_risk_config(&default_config)?;
 
     let config_key = RiskDataKey::RiskConfig;
-    env.storage().persistent().set(&config_key, &default_config);
+    storage::set_instance(env, &config_key, &default_config);
 
     // Initialize emergency pause as false
     let emergency_key = RiskDataKey::EmergencyPause;
-    env.storage().persistent().set(&emergency_key, &false);
+    storage::set_instance(env, &emergency_key, &false);
 
     emit_admin_action(
         env,
@@ -182,46 +292,38 @@ pub fn initialize_risk_management(env: &Env, admin: Address) -> Result<(), RiskM
 /// Create default pause switches map
 fn create_default_pause_switches(env: &Env) -> Map<Symbol, bool> {
     let mut switches = Map::new(env);
-    switches.set(Symbol::new(env, "pause_deposit"), false);
-    switches.set(Symbol::new(env, "pause_withdraw"), false);
-    switches.set(Symbol::new(env, "pause_borrow"), false);
-    switches.set(Symbol::new(env, "pause_repay"), false);
-    switches.set(Symbol::new(env, "pause_liquidate"), false);
+    switches.set(Symbol::new(env, PAUSE_DEPOSIT), false);
+    switches.set(Symbol::new(env, PAUSE_WITHDRAW), false);
+    switches.set(Symbol::new(env, PAUSE_BORROW), false);
+    switches.set(Symbol::new(env, PAUSE_REPAY), false);
+    switches.set(Symbol::new(env, PAUSE_LIQUIDATE), false);
     switches
 }
 
 /// Get the admin address
 pub fn get_admin(env: &Env) -> Option<Address> {
     let admin_key = RiskDataKey::Admin;
-    env.storage()
-        .persistent()
-        .get::<RiskDataKey, Address>(&admin_key)
+    storage::get_instance::<RiskDataKey, Address>(env, &admin_key)
 }
 
-/// Check if caller is admin
-pub fn require_admin(env: &Env, caller: &Address) -> Result<(), RiskManagementError> {
-    let admin = get_admin(env).ok_or(RiskManagementError::Unauthorized)?;
-    if admin != *caller {
-        return Err(RiskManagementError::Unauthorized);
-    }
-    Ok(())
+/// Check that caller holds `role` and authorized the call
+pub fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), RiskManagementError> {
+    roles::require_role(env, caller, role).map_err(|_| RiskManagementError::Unauthorized)
 }
 
 /// Get current risk configuration
 pub fn get_risk_config(env: &Env) -> Option<RiskConfig> {
     let config_key = RiskDataKey::RiskConfig;
-    env.storage()
-        .persistent()
-        .get::<RiskDataKey, RiskConfig>(&config_key)
+    storage::get_instance::<RiskDataKey, RiskConfig>(env, &config_key)
 }
 
-/// Set risk parameters (admin only)
+/// Set risk parameters (risk admin only)
 ///
 /// Updates risk parameters with validation and change limits.
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
-/// * `caller` - The caller address (must be admin)
+/// * `caller` - The caller address (must hold the RiskAdmin role)
 /// * `min_collateral_ratio` - New minimum collateral ratio (in basis points)
 /// * `liquidation_threshold` - New liquidation threshold (in basis points)
 /// * `close_factor` - New close factor (in basis points)
@@ -231,9 +333,12 @@ pub fn get_risk_config(env: &Env) -> Option<RiskConfig> {
 /// Returns Ok(()) on success
 ///
 /// # Errors
-/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::Unauthorized` - If caller lacks the RiskAdmin role
 /// * `RiskManagementError::InvalidParameter` - If parameters are invalid
 /// * `RiskManagementError::ParameterChangeTooLarge` - If change exceeds maximum allowed
+///
+/// Raising the liquidation threshold while a migration delay is configured
+/// starts a [`ThresholdMigration`].
 pub fn set_risk_params(
     env: &Env,
     caller: Address,
@@ -243,8 +348,45 @@ pub fn set_risk_params(
     liquidation_incentive: Option<i128>,
 ) -> Result<(), RiskManagementError> {
     // Check admin
-    require_admin(env, &caller)?;
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    let mut config = project_risk_params(
+        env,
+        &RiskParamsChange {
+            min_collateral_ratio,
+            liquidation_threshold,
+            close_factor,
+            liquidation_incentive,
+        },
+    )?;
+
+    // Update timestamp
+    config.last_update = env.ledger().timestamp();
+
+    // Phase in a tighter liquidation threshold for existing positions
+    let previous = get_liquidation_threshold(env)?;
+    if config.liquidation_threshold > previous {
+        start_threshold_migration(env, previous, config.liquidation_threshold);
+    }
 
+    // Save config
+    let config_key = RiskDataKey::RiskConfig;
+    storage::set_instance(env, &config_key, &config);
+
+    // Emit event
+    emit_risk_params_updated_event(env, &caller, &config);
+
+    Ok(())
+}
+
+/// Validate a risk parameter change and return the resulting config without
+/// storing it.
+///
+/// Applies the same checks as [`set_risk_params`] apart from the admin check.
+pub(crate) fn project_risk_params(
+    env: &Env,
+    change: &RiskParamsChange,
+) -> Result<RiskConfig, RiskManagementError> {
     // Check emergency pause
     check_emergency_pause(env)?;
 
@@ -252,22 +394,22 @@ pub fn set_risk_params(
     let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
 
     // Update parameters if provided
-    if let Some(mcr) = min_collateral_ratio {
+    if let Some(mcr) = change.min_collateral_ratio {
         validate_parameter_change(config.min_collateral_ratio, mcr)?;
         config.min_collateral_ratio = mcr;
     }
 
-    if let Some(lt) = liquidation_threshold {
+    if let Some(lt) = change.liquidation_threshold {
         validate_parameter_change(config.liquidation_threshold, lt)?;
         config.liquidation_threshold = lt;
     }
 
-    if let Some(cf) = close_factor {
+    if let Some(cf) = change.close_factor {
         validate_parameter_change(config.close_factor, cf)?;
         config.close_factor = cf;
     }
 
-    if let Some(li) = liquidation_incentive {
+    if let Some(li) = change.liquidation_incentive {
         validate_parameter_change(config.liquidation_incentive, li)?;
         config.liquidation_incentive = li;
     }
@@ -275,17 +417,7 @@ pub fn set_risk_params(
     // Validate the updated config
     validate_risk_config(&config)?;
 
-    // Update timestamp
-    config.last_update = env.ledger().timestamp();
-
-    // Save config
-    let config_key = RiskDataKey::RiskConfig;
-    env.storage().persistent().set(&config_key, &config);
-
-    // Emit event
-    emit_risk_params_updated_event(env, &caller, &config);
-
-    Ok(())
+    Ok(config)
 }
 
 /// Validate risk configuration
@@ -333,7 +465,7 @@ fn validate_parameter_change(old_value: i128, new_value: i128) -> Result<(), Ris
     };
 
     // Calculate maximum allowed change (10% of old value)
-    let max_change = (old_value * MAX_PARAMETER_CHANGE_BPS) / BASIS_POINTS_SCALE;
+    let max_change = (old_value * MAX_PARAMETER_CHANGE_BPS) / BASIS_POINTS_SCALE.get();
 
     if change > max_change {
         return Err(RiskManagementError::ParameterChangeTooLarge);
@@ -342,13 +474,13 @@ fn validate_parameter_change(old_value: i128, new_value: i128) -> Result<(), Ris
     Ok(())
 }
 
-/// Set pause switches (admin only)
+/// Set pause switches (pause guardian only)
 ///
 /// Updates pause switches for different operations.
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
-/// * `caller` - The caller address (must be admin)
+/// * `caller` - The caller address (must hold the PauseGuardian role)
 /// * `operation` - The operation to pause/unpause (as Symbol)
 /// * `paused` - Whether to pause (true) or unpause (false)
 ///
@@ -356,7 +488,7 @@ fn validate_parameter_change(old_value: i128, new_value: i128) -> Result<(), Ris
 /// Returns Ok(()) on success
 ///
 /// # Errors
-/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::Unauthorized` - If caller lacks the PauseGuardian role
 pub fn set_pause_switch(
     env: &Env,
     caller: Address,
@@ -364,7 +496,7 @@ pub fn set_pause_switch(
     paused: bool,
 ) -> Result<(), RiskManagementError> {
     // Check admin
-    require_admin(env, &caller)?;
+    require_role(env, &caller, Role::PauseGuardian)?;
 
     // Get current config
     let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
@@ -377,7 +509,7 @@ pub fn set_pause_switch(
 
     // Save config
     let config_key = RiskDataKey::RiskConfig;
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
 
     // Emit event
     emit_pause_switch_updated_event(env, &caller, &operation, paused);
@@ -385,11 +517,11 @@ pub fn set_pause_switch(
     Ok(())
 }
 
-/// Set multiple pause switches at once (admin only)
+/// Set multiple pause switches at once (pause guardian only)
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
-/// * `caller` - The caller address (must be admin)
+/// * `caller` - The caller address (must hold the PauseGuardian role)
 /// * `switches` - Map of operation symbols to pause states
 ///
 /// # Returns
@@ -400,7 +532,7 @@ pub fn set_pause_switches(
     switches: Map<Symbol, bool>,
 ) -> Result<(), RiskManagementError> {
     // Check admin
-    require_admin(env, &caller)?;
+    require_role(env, &caller, Role::PauseGuardian)?;
 
     // Get current config
     let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
@@ -415,7 +547,7 @@ pub fn set_pause_switches(
 
     // Save config
     let config_key = RiskDataKey::RiskConfig;
-    env.storage().persistent().set(&config_key, &config);
+    storage::set_instance(env, &config_key, &config);
 
     // Emit event
     emit_pause_switches_updated_event(env, &caller, &switches);
@@ -454,13 +586,18 @@ pub fn check_operation_paused(env: &Env, operation: Symbol) -> bool {
     is_operation_paused(env, operation)
 }
 
-/// Set emergency pause (admin only)
+/// Set emergency pause (pause guardian pauses, protocol admin unpauses)
 ///
-/// Emergency pause stops all operations immediately.
+/// Emergency pause stops all user operations immediately: deposits,
+/// withdrawals, borrows, repayments and liquidations, including their
+/// cross-asset variants. A guardian can trip it, but only the protocol admin
+/// can lift it, so a compromised guardian key cannot toggle the protocol
+/// back on.
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
-/// * `caller` - The caller address (must be admin)
+/// * `caller` - The caller address (must hold the PauseGuardian role to
+///   pause, and be the protocol admin to unpause)
 /// * `paused` - Whether to enable (true) or disable (false) emergency pause
 ///
 /// # Returns
@@ -471,11 +608,21 @@ pub fn set_emergency_pause(
     paused: bool,
 ) -> Result<(), RiskManagementError> {
     // Check admin
-    require_admin(env, &caller)?;
+    if paused {
+        require_role(env, &caller, Role::PauseGuardian)?;
+    } else {
+        roles::require_protocol_admin(env, &caller)
+            .map_err(|_| RiskManagementError::Unauthorized)?;
+    }
+
+    // Start the recovery window when an active pause is lifted
+    if !paused && is_emergency_paused(env) {
+        storage::set_instance(env, &RiskDataKey::PauseLiftedAt, &env.ledger().timestamp());
+    }
 
     // Set emergency pause
     let emergency_key = RiskDataKey::EmergencyPause;
-    env.storage().persistent().set(&emergency_key, &paused);
+    storage::set_instance(env, &emergency_key, &paused);
 
     // Emit event
     emit_emergency_pause_event(env, &caller, paused);
@@ -486,10 +633,7 @@ pub fn set_emergency_pause(
 /// Check if emergency pause is active
 pub fn is_emergency_paused(env: &Env) -> bool {
     let emergency_key = RiskDataKey::EmergencyPause;
-    env.storage()
-        .persistent()
-        .get::<RiskDataKey, bool>(&emergency_key)
-        .unwrap_or(false)
+    storage::get_instance::<RiskDataKey, bool>(env, &emergency_key).unwrap_or(false)
 }
 
 /// Require that emergency pause is not active
@@ -500,6 +644,392 @@ pub fn check_emergency_pause(env: &Env) -> Result<(), RiskManagementError> {
     Ok(())
 }
 
+/// Set the withdrawal cooldown after a borrow (risk admin only)
+///
+/// While the cooldown is running, a borrower with outstanding debt cannot
+/// withdraw collateral. This blunts strategies that borrow against a
+/// manipulated price and pull the collateral out in the same ledger.
+/// A value of 0 disables the cooldown.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must hold the RiskAdmin role)
+/// * `seconds` - Cooldown length in seconds (at most 1 day)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller lacks the RiskAdmin role
+/// * `RiskManagementError::InvalidParameter` - If the cooldown exceeds 1 day
+pub fn set_withdraw_cooldown(
+    env: &Env,
+    caller: Address,
+    seconds: u64,
+) -> Result<(), RiskManagementError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    if seconds > MAX_WITHDRAW_COOLDOWN_SECONDS {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    storage::set_instance(env, &RiskDataKey::WithdrawCooldown, &seconds);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_withdraw_cooldown"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the withdrawal cooldown in seconds (0 when disabled)
+pub fn get_withdraw_cooldown(env: &Env) -> u64 {
+    storage::get_instance::<RiskDataKey, u64>(env, &RiskDataKey::WithdrawCooldown).unwrap_or(0)
+}
+
+/// Set the liquidation threshold migration delay (risk admin only)
+///
+/// Later threshold increases reach positions that last borrowed before the
+/// increase only after `seconds`. A delay of 0 applies increases to every
+/// position immediately. Migrations already in progress are unaffected.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must hold the RiskAdmin role)
+/// * `seconds` - Delay in seconds (at most 30 days)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller lacks the RiskAdmin role
+/// * `RiskManagementError::InvalidParameter` - If the delay exceeds the maximum
+pub fn set_threshold_migration_delay(
+    env: &Env,
+    caller: Address,
+    seconds: u64,
+) -> Result<(), RiskManagementError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    if seconds > MAX_THRESHOLD_MIGRATION_DELAY_SECONDS {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    storage::set_instance(env, &RiskDataKey::ThresholdMigrationDelay, &seconds);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_threshold_migration_delay"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Set the health factor liquidations restore positions to (risk admin only)
+///
+/// A liquidation then repays only enough debt to bring the position to the
+/// target, within the close factor. A target of 0 disables the limit, so
+/// liquidators may always repay up to the close factor.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must hold the RiskAdmin role)
+/// * `target` - Target health factor in basis points, above 1.0 and at most 2.0
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller lacks the RiskAdmin role
+/// * `RiskManagementError::InvalidParameter` - If the target is out of range
+pub fn set_liquidation_target(
+    env: &Env,
+    caller: Address,
+    target: i128,
+) -> Result<(), RiskManagementError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    if target != 0
+        && (target <= HEALTH_FACTOR_ONE.get() || target > MAX_LIQUIDATION_TARGET_HEALTH_FACTOR)
+    {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    storage::set_instance(env, &RiskDataKey::LiquidationTarget, &target);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_liquidation_target"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the health factor liquidations restore positions to (0 when disabled)
+pub fn get_liquidation_target(env: &Env) -> i128 {
+    storage::get_instance::<RiskDataKey, i128>(env, &RiskDataKey::LiquidationTarget).unwrap_or(0)
+}
+
+/// Debt that can be repaid before a core position reaches the liquidation
+/// target, or `None` if no target applies
+///
+/// Each unit repaid releases `1 + incentive` of collateral, so the collateral
+/// ratio `(C - r * (1 + i)) / (D - r)` reaches the target ratio `R` at
+/// `r = (R * D - C) / (R - 1 - i)`, rounded up.
+///
+/// # Arguments
+/// * `collateral_value` - Collateral value in debt asset terms
+/// * `debt_value` - Total debt, including interest
+/// * `threshold` - Liquidation threshold that applies to the position
+/// * `incentive_bps` - Liquidation incentive paid on the repayment
+pub fn get_target_repay_limit(
+    env: &Env,
+    collateral_value: i128,
+    debt_value: i128,
+    threshold: i128,
+    incentive_bps: i128,
+) -> Option<i128> {
+    let target = get_liquidation_target(env);
+    let scale = BASIS_POINTS_SCALE.get();
+    let release = scale + incentive_bps;
+    // Repaying only improves the ratio while it exceeds 1 + incentive
+    if target == 0 || collateral_value * scale <= debt_value * release {
+        return None;
+    }
+
+    let target_ratio = target * threshold / HEALTH_FACTOR_ONE.get();
+    let shortfall = target_ratio * debt_value - scale * collateral_value;
+    if shortfall <= 0 {
+        return Some(0);
+    }
+    let denominator = target_ratio - release;
+    Some((shortfall + denominator - 1) / denominator)
+}
+
+/// Get the liquidation threshold migration delay in seconds (0 when disabled)
+pub fn get_threshold_migration_delay(env: &Env) -> u64 {
+    storage::get_instance::<RiskDataKey, u64>(env, &RiskDataKey::ThresholdMigrationDelay)
+        .unwrap_or(0)
+}
+
+/// Get the liquidation threshold migration in progress, if any
+pub fn get_threshold_migration(env: &Env) -> Option<ThresholdMigration> {
+    storage::get_instance::<RiskDataKey, ThresholdMigration>(env, &RiskDataKey::ThresholdMigration)
+        .filter(|migration| env.ledger().timestamp() < migration.effective_at)
+}
+
+/// Record a threshold increase from `previous_threshold`.
+///
+/// If a migration is still running, positions it protects keep their
+/// original threshold and the delay restarts from now.
+fn start_threshold_migration(env: &Env, previous_threshold: i128, new_threshold: i128) {
+    let delay = get_threshold_migration_delay(env);
+    if delay == 0 {
+        return;
+    }
+
+    let now = env.ledger().timestamp();
+    let migration = ThresholdMigration {
+        previous_threshold: get_threshold_migration(env)
+            .map_or(previous_threshold, |running| running.previous_threshold),
+        started_at: now,
+        effective_at: now.saturating_add(delay),
+    };
+    storage::set_instance(env, &RiskDataKey::ThresholdMigration, &migration);
+
+    emit_threshold_migration(
+        env,
+        ThresholdMigrationEvent {
+            previous_threshold: migration.previous_threshold,
+            new_threshold,
+            effective_at: migration.effective_at,
+            timestamp: now,
+        },
+    );
+}
+
+/// Liquidation threshold that applies to a user's position
+///
+/// Positions whose last borrow predates a running [`ThresholdMigration`]
+/// keep its previous threshold (or the current one, if lower); all others
+/// use the current threshold.
+pub fn get_position_liquidation_threshold(
+    env: &Env,
+    user: &Address,
+) -> Result<i128, RiskManagementError> {
+    let threshold = get_liquidation_threshold(env)?;
+    let Some(migration) = get_threshold_migration(env) else {
+        return Ok(threshold);
+    };
+
+    // Positions with no recorded borrow predate borrow time tracking
+    let last_borrow =
+        storage::get::<DepositDataKey, u64>(env, &DepositDataKey::LastBorrowTime(user.clone()));
+    match last_borrow {
+        Some(borrowed_at) if borrowed_at >= migration.started_at => Ok(threshold),
+        _ => Ok(threshold.min(migration.previous_threshold)),
+    }
+}
+
+/// Configure the post-pause collateral haircut (risk admin only)
+///
+/// When the emergency pause is lifted, collateral counts for
+/// `haircut_bps` less toward new borrows and withdrawals, and the haircut
+/// shrinks linearly to zero over `window_seconds`. A haircut of 0 disables it.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must hold the RiskAdmin role)
+/// * `haircut_bps` - Initial haircut in basis points (at most 50%)
+/// * `window_seconds` - Decay window in seconds (at most 7 days)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller lacks the RiskAdmin role
+/// * `RiskManagementError::InvalidParameter` - If the haircut or window is out of range
+pub fn set_recovery_haircut(
+    env: &Env,
+    caller: Address,
+    haircut_bps: i128,
+    window_seconds: u64,
+) -> Result<(), RiskManagementError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    if !(0..=MAX_RECOVERY_HAIRCUT_BPS).contains(&haircut_bps)
+        || window_seconds > MAX_RECOVERY_WINDOW_SECONDS
+    {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    storage::set_instance(
+        env,
+        &RiskDataKey::RecoveryHaircut,
+        &RecoveryHaircutConfig {
+            haircut_bps,
+            window_seconds,
+        },
+    );
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_recovery_haircut"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the post-pause haircut settings, if configured
+pub fn get_recovery_haircut_config(env: &Env) -> Option<RecoveryHaircutConfig> {
+    storage::get_instance(env, &RiskDataKey::RecoveryHaircut)
+}
+
+/// Collateral haircut currently in effect and the time it reaches zero.
+fn active_recovery_haircut(env: &Env) -> (i128, u64) {
+    let (Some(config), Some(lifted_at)) = (
+        get_recovery_haircut_config(env),
+        storage::get_instance::<RiskDataKey, u64>(env, &RiskDataKey::PauseLiftedAt),
+    ) else {
+        return (0, 0);
+    };
+
+    let ends_at = lifted_at.saturating_add(config.window_seconds);
+    let now = env.ledger().timestamp();
+    if is_emergency_paused(env) || now >= ends_at || config.haircut_bps == 0 {
+        return (0, 0);
+    }
+
+    let remaining = (ends_at - now) as i128;
+    let haircut = config.haircut_bps * remaining / config.window_seconds as i128;
+    (haircut, ends_at)
+}
+
+/// Get the collateral haircut currently in effect, in basis points
+///
+/// Equal to the configured haircut when the pause is lifted, decaying
+/// linearly to zero by the end of the recovery window.
+pub fn get_recovery_haircut(env: &Env) -> i128 {
+    active_recovery_haircut(env).0
+}
+
+/// Reduce a collateral factor by the active recovery haircut
+pub fn apply_recovery_haircut(env: &Env, collateral_factor: i128) -> i128 {
+    let haircut = get_recovery_haircut(env);
+    collateral_factor * (BASIS_POINTS_SCALE.get() - haircut) / BASIS_POINTS_SCALE.get()
+}
+
+/// Configure the liquidation incentive curve, or remove it with `None` to
+/// return to the flat incentive (risk admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must hold the RiskAdmin role)
+/// * `curve` - New curve, or `None` for the flat incentive
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller lacks the RiskAdmin role
+/// * `RiskManagementError::InvalidLiquidationIncentive` - If the incentives are
+///   out of range or the minimum exceeds the maximum
+/// * `RiskManagementError::InvalidParameter` - If `max_bonus_health_factor`
+///   is not between 0 and 1.0
+pub fn set_liquidation_incentive_curve(
+    env: &Env,
+    caller: Address,
+    curve: Option<LiquidationIncentiveCurve>,
+) -> Result<(), RiskManagementError> {
+    require_role(env, &caller, Role::RiskAdmin)?;
+
+    match curve {
+        Some(curve) => {
+            if curve.min_incentive_bps < LIQUIDATION_INCENTIVE_MIN
+                || curve.max_incentive_bps > LIQUIDATION_INCENTIVE_MAX
+                || curve.min_incentive_bps > curve.max_incentive_bps
+            {
+                return Err(RiskManagementError::InvalidLiquidationIncentive);
+            }
+            if curve.max_bonus_health_factor <= 0
+                || curve.max_bonus_health_factor >= HEALTH_FACTOR_ONE.get()
+            {
+                return Err(RiskManagementError::InvalidParameter);
+            }
+            storage::set_instance(env, &RiskDataKey::IncentiveCurve, &curve);
+        }
+        None => storage::remove_instance(env, &RiskDataKey::IncentiveCurve),
+    }
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_liquidation_incentive_curve"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the liquidation incentive curve, if configured
+pub fn get_liquidation_incentive_curve(env: &Env) -> Option<LiquidationIncentiveCurve> {
+    storage::get_instance(env, &RiskDataKey::IncentiveCurve)
+}
+
+/// Get the protocol's pause and recovery state
+pub fn get_protocol_health(env: &Env) -> ProtocolHealth {
+    let (recovery_haircut_bps, recovery_ends_at) = active_recovery_haircut(env);
+    ProtocolHealth {
+        emergency_paused: is_emergency_paused(env),
+        recovery_haircut_bps,
+        recovery_ends_at,
+    }
+}
+
 /// Check if user meets minimum collateral ratio requirement
 ///
 /// # Arguments
@@ -522,7 +1052,7 @@ pub fn require_min_collateral_ratio(
     }
 
     // Calculate collateral ratio: (collateral / debt) * 10000 (basis points)
-    let ratio = (collateral_value * BASIS_POINTS_SCALE)
+    let ratio = (collateral_value * BASIS_POINTS_SCALE.get())
         .checked_div(debt_value)
         .ok_or(RiskManagementError::Overflow)?;
 
@@ -551,19 +1081,40 @@ pub fn can_be_liquidated(
     debt_value: i128,
 ) -> Result<bool, RiskManagementError> {
     let config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    is_below_threshold(collateral_value, debt_value, config.liquidation_threshold)
+}
+
+/// Check if a user's position can be liquidated
+///
+/// Like [`can_be_liquidated`], but against the threshold that applies to
+/// the position during a threshold migration.
+pub fn can_position_be_liquidated(
+    env: &Env,
+    user: &Address,
+    collateral_value: i128,
+    debt_value: i128,
+) -> Result<bool, RiskManagementError> {
+    let threshold = get_position_liquidation_threshold(env, user)?;
+    is_below_threshold(collateral_value, debt_value, threshold)
+}
 
+fn is_below_threshold(
+    collateral_value: i128,
+    debt_value: i128,
+    threshold: i128,
+) -> Result<bool, RiskManagementError> {
     // If no debt, cannot be liquidated
     if debt_value == 0 {
         return Ok(false);
     }
 
     // Calculate collateral ratio
-    let ratio = (collateral_value * BASIS_POINTS_SCALE)
+    let ratio = (collateral_value * BASIS_POINTS_SCALE.get())
         .checked_div(debt_value)
         .ok_or(RiskManagementError::Overflow)?;
 
     // Can be liquidated if ratio < liquidation threshold
-    Ok(ratio < config.liquidation_threshold)
+    Ok(ratio < threshold)
 }
 
 /// Calculate maximum liquidatable amount
@@ -582,9 +1133,9 @@ pub fn get_max_liquidatable_amount(
 ) -> Result<i128, RiskManagementError> {
     let config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
 
-    // Calculate: debt * close_factor / BASIS_POINTS_SCALE
+    // Calculate: debt * close_factor / BASIS_POINTS_SCALE.get()
     let max_amount = (debt_value * config.close_factor)
-        .checked_div(BASIS_POINTS_SCALE)
+        .checked_div(BASIS_POINTS_SCALE.get())
         .ok_or(RiskManagementError::Overflow)?;
 
     Ok(max_amount)
@@ -597,18 +1148,21 @@ pub fn get_max_liquidatable_amount(
 /// # Arguments
 /// * `env` - The Soroban environment
 /// * `liquidated_amount` - Amount being liquidated (in base units)
+/// * `health_factor` - Health factor of the position being liquidated (in
+///   basis points); only used with an incentive curve
 ///
 /// # Returns
 /// Liquidation incentive amount
 pub fn get_liquidation_incentive_amount(
     env: &Env,
     liquidated_amount: i128,
+    health_factor: i128,
 ) -> Result<i128, RiskManagementError> {
-    let config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    let incentive_bps = get_health_factor_incentive(env, health_factor)?;
 
-    // Calculate: amount * liquidation_incentive / BASIS_POINTS_SCALE
-    let incentive = (liquidated_amount * config.liquidation_incentive)
-        .checked_div(BASIS_POINTS_SCALE)
+    // Calculate: amount * incentive_bps / BASIS_POINTS_SCALE.get()
+    let incentive = (liquidated_amount * incentive_bps)
+        .checked_div(BASIS_POINTS_SCALE.get())
         .ok_or(RiskManagementError::Overflow)?;
 
     Ok(incentive)
@@ -638,6 +1192,50 @@ pub fn get_liquidation_incentive(env: &Env) -> Result<i128, RiskManagementError>
     Ok(config.liquidation_incentive)
 }
 
+/// Get the liquidation incentive, in basis points, for a position at
+/// `health_factor`
+///
+/// Follows the [`LiquidationIncentiveCurve`] if one is configured, and is the
+/// flat liquidation incentive otherwise.
+pub fn get_health_factor_incentive(
+    env: &Env,
+    health_factor: i128,
+) -> Result<i128, RiskManagementError> {
+    let Some(curve) = get_liquidation_incentive_curve(env) else {
+        return get_liquidation_incentive(env);
+    };
+
+    let one = HEALTH_FACTOR_ONE.get();
+    if health_factor >= one {
+        return Ok(curve.min_incentive_bps);
+    }
+    if health_factor <= curve.max_bonus_health_factor {
+        return Ok(curve.max_incentive_bps);
+    }
+    let shortfall = one - health_factor;
+    let span = one - curve.max_bonus_health_factor;
+    Ok(curve.min_incentive_bps
+        + (curve.max_incentive_bps - curve.min_incentive_bps) * shortfall / span)
+}
+
+/// Health factor of a core position, in basis points: its collateral ratio
+/// relative to the liquidation threshold
+pub fn collateral_ratio_health_factor(
+    collateral_value: i128,
+    debt_value: i128,
+    threshold: i128,
+) -> Result<i128, RiskManagementError> {
+    if debt_value == 0 {
+        return Ok(i128::MAX);
+    }
+    collateral_value
+        .checked_mul(BASIS_POINTS_SCALE.get())
+        .and_then(|value| value.checked_mul(HEALTH_FACTOR_ONE.get()))
+        .and_then(|value| value.checked_div(debt_value))
+        .and_then(|ratio| ratio.checked_div(threshold))
+        .ok_or(RiskManagementError::Overflow)
+}
+
 /// Emit risk parameters updated event
 fn emit_risk_params_updated_event(env: &Env, caller: &Address, config: &RiskConfig) {
     emit_risk_params_updated(
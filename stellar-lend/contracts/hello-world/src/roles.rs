@@ -0,0 +1,149 @@
+//! # Roles
+//!
+//! Role-based access control for admin-gated operations.
+//!
+//! Each admin operation requires one [`Role`]:
+//! - **RiskAdmin**: risk parameters, asset caps and configuration updates,
+//!   withdrawal cooldown, threshold migration delay, recovery haircut, tenure
+//!   rebates, per-ledger borrow size caps, and activity score decay
+//! - **PauseGuardian**: pause switches, the emergency pause, and the
+//!   analytics write freeze (lifting the emergency pause is reserved for the
+//!   protocol admin)
+//! - **AssetListingAdmin**: listing assets, pushing their prices, and creating
+//!   isolated markets
+//! - **TreasuryAdmin**: withdrawing protocol reserves and writing off bad debt
+//!
+//! The protocol admin (set by `initialize`) holds every role implicitly and is
+//! the only address that can grant or revoke roles or set the isolated market
+//! wasm. Existing single-admin
+//! deployments therefore keep working without any grants.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env};
+
+use crate::events::{emit_role_updated, RoleUpdatedEvent};
+use crate::risk_management::get_admin;
+use crate::storage;
+
+/// Errors that can occur when managing roles
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RoleError {
+    /// Caller is not the protocol admin, or lacks the required role
+    Unauthorized = 1,
+    /// The account already holds the role
+    AlreadyGranted = 2,
+    /// The account does not hold the role
+    NotGranted = 3,
+}
+
+/// An admin role
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// Sets risk parameters and asset configuration
+    RiskAdmin,
+    /// Pauses and unpauses operations
+    PauseGuardian,
+    /// Lists assets, maintains their prices, and creates isolated markets
+    AssetListingAdmin,
+    /// Withdraws protocol reserves and manages reward emissions
+    TreasuryAdmin,
+}
+
+/// Storage keys for role data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum RoleDataKey {
+    /// Whether an account has been granted a role: bool
+    Member(Role, Address),
+}
+
+/// Grant `role` to `account` (protocol admin only).
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+/// * `AlreadyGranted` - The account already holds the role
+pub fn grant_role(
+    env: &Env,
+    caller: Address,
+    role: Role,
+    account: Address,
+) -> Result<(), RoleError> {
+    require_protocol_admin(env, &caller)?;
+    let key = RoleDataKey::Member(role, account.clone());
+    if storage::has(env, &key) {
+        return Err(RoleError::AlreadyGranted);
+    }
+    storage::set(env, &key, &true);
+
+    emit_role_updated(
+        env,
+        RoleUpdatedEvent {
+            admin: caller,
+            role,
+            account,
+            granted: true,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Revoke `role` from `account` (protocol admin only).
+///
+/// The protocol admin's implicit roles cannot be revoked.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+/// * `NotGranted` - The account was never granted the role
+pub fn revoke_role(
+    env: &Env,
+    caller: Address,
+    role: Role,
+    account: Address,
+) -> Result<(), RoleError> {
+    require_protocol_admin(env, &caller)?;
+    let key = RoleDataKey::Member(role, account.clone());
+    if !storage::has(env, &key) {
+        return Err(RoleError::NotGranted);
+    }
+    storage::remove(env, &key);
+
+    emit_role_updated(
+        env,
+        RoleUpdatedEvent {
+            admin: caller,
+            role,
+            account,
+            granted: false,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Whether `account` holds `role`, either by grant or as the protocol admin
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    get_admin(env).as_ref() == Some(account)
+        || storage::has(env, &RoleDataKey::Member(role, account.clone()))
+}
+
+/// Require `caller` to hold `role` and to have authorized the call
+pub fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), RoleError> {
+    if !has_role(env, role, caller) {
+        return Err(RoleError::Unauthorized);
+    }
+    caller.require_auth();
+    Ok(())
+}
+
+/// Check that caller is the protocol admin and authorized the call
+pub(crate) fn require_protocol_admin(env: &Env, caller: &Address) -> Result<(), RoleError> {
+    if get_admin(env).as_ref() != Some(caller) {
+        return Err(RoleError::Unauthorized);
+    }
+    caller.require_auth();
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! # State Export
+//!
+//! Paginated, read-only export of protocol state, so an off-chain archive
+//! can rebuild it for audits or for a redeployment after a critical
+//! incident.
+//!
+//! [`export_state`] reads one [`ExportSection`] at a time. Each page holds
+//! up to `limit` entries starting at `cursor` and returns the cursor of the
+//! next page, or `None` once the section is exhausted.
+//!
+//! ## Order
+//! - `Positions`: users in order of first activity ([`get_user_list`]), each
+//!   with their core position, cross-asset positions and pool shares
+//! - `AssetParams`, `Indices`, `Reserves`: markets in listing order
+//!   ([`get_asset_list`])
+//!
+//! Both lists only grow, so a cursor stays valid between pages. Pages are
+//! read at different ledgers; an archive wanting a consistent cut exports
+//! while the emergency pause is active.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Map, Symbol, Vec};
+
+use crate::cross_asset::{
+    get_asset_config_by_address, get_asset_list, get_asset_pauses, get_collateral_factor_ramp,
+    get_interest_mode, get_isolation_debt_ceiling, get_min_borrow_amount,
+    get_soft_liquidation_config, get_soft_liquidation_state, get_total_borrow, get_total_supply,
+    get_user_asset_positions, get_user_list, is_asset_delisted, AssetConfig, AssetKey,
+    AssetPosition, CollateralFactorRamp, InterestMode, SoftLiquidationConfig, SoftLiquidationState,
+};
+use crate::deposit::{DepositDataKey, Position};
+use crate::lending_pool::{get_bad_debt, get_pool_state, get_supply_shares};
+use crate::rate_strategy::{get_rate_strategy, RateStrategy};
+use crate::storage;
+
+/// Maximum number of entries per `export_state` page
+pub const MAX_EXPORT_PAGE_SIZE: u32 = 25;
+
+/// Errors that can occur while exporting state
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum StateExportError {
+    /// `limit` is zero or above [`MAX_EXPORT_PAGE_SIZE`]
+    InvalidLimit = 1,
+}
+
+/// A part of protocol state exported on its own
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportSection {
+    /// Every user's positions
+    Positions,
+    /// Every market's configuration, rate strategy and risk switches
+    AssetParams,
+    /// Every market's pool and borrow totals
+    Indices,
+    /// Every market's reserves and bad debt
+    Reserves,
+}
+
+/// One user's positions
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserExport {
+    pub user: Address,
+    /// Core position; all zero if the user has none
+    pub core: Position,
+    /// Cross-asset positions holding collateral, debt or interest
+    pub positions: Map<AssetKey, AssetPosition>,
+    /// Pool shares held, by market
+    pub supply_shares: Map<AssetKey, i128>,
+    /// Collateral converted by soft liquidation, by collateral market
+    pub soft_liquidations: Map<AssetKey, SoftLiquidationState>,
+}
+
+/// One market's parameters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetParamsExport {
+    pub asset: AssetKey,
+    /// Configuration at the current point of any collateral factor ramp
+    pub config: AssetConfig,
+    /// Collateral factor ramp still in progress, if any (at most one)
+    pub collateral_factor_ramp: Vec<CollateralFactorRamp>,
+    /// Rate strategy; all zero if the market has none
+    pub rate_strategy: RateStrategy,
+    pub interest_mode: InterestMode,
+    /// Debt ceiling, if the asset is isolated
+    pub isolation_debt_ceiling: Option<i128>,
+    /// Smallest nonzero debt; `0` for none
+    pub min_borrow_amount: i128,
+    /// Soft liquidation bands, if enabled (at most one)
+    pub soft_liquidation: Vec<SoftLiquidationConfig>,
+    /// Paused operations
+    pub pauses: Map<Symbol, bool>,
+    pub delisted: bool,
+}
+
+/// One market's pool and borrow totals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IndexExport {
+    pub asset: AssetKey,
+    /// Asset owed to pool suppliers
+    pub pool_liquidity: i128,
+    /// Pool shares outstanding; liquidity per share is the supply index
+    pub pool_shares: i128,
+    /// Total collateral deposited
+    pub total_supplied: i128,
+    /// Total debt, including accrued interest
+    pub total_borrowed: i128,
+}
+
+/// One market's reserves
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReservesExport {
+    pub asset: AssetKey,
+    pub reserves: i128,
+    pub bad_debt: i128,
+}
+
+/// One exported entry, by section
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+// Contract types cannot be boxed
+#[allow(clippy::large_enum_variant)]
+pub enum ExportEntry {
+    Positions(UserExport),
+    AssetParams(AssetParamsExport),
+    Indices(IndexExport),
+    Reserves(ReservesExport),
+}
+
+/// A page of exported entries
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportPage {
+    pub entries: Vec<ExportEntry>,
+    /// Cursor of the next page; `None` once the section is exhausted
+    pub next_cursor: Option<u32>,
+}
+
+/// Export up to `limit` entries of a section, starting at `cursor`
+///
+/// # Errors
+/// * `InvalidLimit` - `limit` is zero or above [`MAX_EXPORT_PAGE_SIZE`]
+pub fn export_state(
+    env: &Env,
+    section: ExportSection,
+    cursor: u32,
+    limit: u32,
+) -> Result<ExportPage, StateExportError> {
+    if limit == 0 || limit > MAX_EXPORT_PAGE_SIZE {
+        return Err(StateExportError::InvalidLimit);
+    }
+
+    let assets = get_asset_list(env);
+    let users = match section {
+        ExportSection::Positions => get_user_list(env),
+        _ => Vec::new(env),
+    };
+    let len = match section {
+        ExportSection::Positions => users.len(),
+        _ => assets.len(),
+    };
+    let end = cursor.saturating_add(limit).min(len);
+    let mut entries = Vec::new(env);
+    for i in cursor..end {
+        entries.push_back(match section {
+            ExportSection::Positions => {
+                ExportEntry::Positions(export_user(env, &assets, users.get(i).unwrap()))
+            }
+            ExportSection::AssetParams => {
+                ExportEntry::AssetParams(export_asset_params(env, assets.get(i).unwrap()))
+            }
+            ExportSection::Indices => {
+                ExportEntry::Indices(export_index(env, assets.get(i).unwrap()))
+            }
+            ExportSection::Reserves => {
+                ExportEntry::Reserves(export_reserves(env, assets.get(i).unwrap()))
+            }
+        });
+    }
+
+    Ok(ExportPage {
+        entries,
+        next_cursor: (end < len).then_some(end),
+    })
+}
+
+fn export_user(env: &Env, assets: &Vec<AssetKey>, user: Address) -> UserExport {
+    let mut supply_shares = Map::new(env);
+    let mut soft_liquidations = Map::new(env);
+    for asset in assets.iter() {
+        let shares = get_supply_shares(env, &user, asset.to_option());
+        if shares != 0 {
+            supply_shares.set(asset.clone(), shares);
+        }
+        if let Some(state) = get_soft_liquidation_state(env, &user, asset.to_option()) {
+            soft_liquidations.set(asset, state);
+        }
+    }
+    UserExport {
+        core: storage::get(env, &DepositDataKey::Position(user.clone())).unwrap_or(Position {
+            collateral: 0,
+            debt: 0,
+            borrow_interest: 0,
+            last_accrual_time: 0,
+        }),
+        positions: get_user_asset_positions(env, &user),
+        supply_shares,
+        soft_liquidations,
+        user,
+    }
+}
+
+fn export_asset_params(env: &Env, asset: AssetKey) -> AssetParamsExport {
+    let option = asset.to_option();
+    AssetParamsExport {
+        // Listed assets always have a configuration
+        config: get_asset_config_by_address(env, option.clone()).unwrap(),
+        collateral_factor_ramp: Vec::from_iter(
+            env,
+            get_collateral_factor_ramp(env, option.clone()),
+        ),
+        rate_strategy: get_rate_strategy(env, option.clone()).unwrap_or(RateStrategy {
+            base_rate_bps: 0,
+            slope1_bps: 0,
+            slope2_bps: 0,
+            optimal_utilization_bps: 0,
+        }),
+        interest_mode: get_interest_mode(env, &asset),
+        isolation_debt_ceiling: get_isolation_debt_ceiling(env, option.clone()),
+        min_borrow_amount: get_min_borrow_amount(env, option.clone()),
+        soft_liquidation: Vec::from_iter(env, get_soft_liquidation_config(env, option.clone())),
+        pauses: get_asset_pauses(env, option),
+        delisted: is_asset_delisted(env, &asset),
+        asset,
+    }
+}
+
+fn export_index(env: &Env, asset: AssetKey) -> IndexExport {
+    let pool = get_pool_state(env, asset.to_option());
+    IndexExport {
+        pool_liquidity: pool.total_liquidity,
+        pool_shares: pool.total_shares,
+        total_supplied: get_total_supply(env, &asset),
+        total_borrowed: get_total_borrow(env, &asset),
+        asset,
+    }
+}
+
+fn export_reserves(env: &Env, asset: AssetKey) -> ReservesExport {
+    let option = asset.to_option();
+    ReservesExport {
+        reserves: get_pool_state(env, option.clone()).reserves,
+        bad_debt: get_bad_debt(env, option),
+        asset,
+    }
+}
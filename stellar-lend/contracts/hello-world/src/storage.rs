@@ -0,0 +1,597 @@
+//! # Namespaced Storage
+//!
+//! Routes persistent storage access for the deposit, analytics, cross-asset,
+//! lending pool, borrow queue, credit history, insurance, and roles modules through a single key scheme.
+//!
+//! Every key is a [`StorageKey`]: a module tag (the variant) wrapping that
+//! module's own key. Two modules can therefore declare keys with the same name
+//! without sharing a storage slot. The borrow, withdraw, repay, and liquidate modules operate on
+//! deposit positions and use the `Deposit` namespace.
+//!
+//! ## Compatibility
+//! Entries written before namespacing was introduced stay where they are:
+//! [`legacy_location`] maps every pre-existing key to its original,
+//! un-namespaced encoding, so an upgraded contract reads the same data. Keys
+//! without a legacy location are stored under the full namespaced encoding.
+//! The matches in [`legacy_location`] are exhaustive on purpose: adding a key
+//! variant fails to compile until it is classified, and new variants must
+//! return `None`.
+//!
+//! `AnalyticsDataKey::ActivityLog` and `DepositDataKey::ActivityLog` have
+//! always resolved to the same slot, the legacy activity log that the
+//! analytics feed still reads.
+//! The alias is now explicit in [`legacy_location`] rather than a side effect
+//! of both enums having a variant with the same name.
+//!
+//! ## Storage Tiers
+//! - **Instance**: small global configuration (admins, risk and rate configs,
+//!   pause switches). Shares the contract's TTL, which core operations extend
+//!   with [`extend_instance_ttl`]. Use the `*_instance` helpers.
+//! - **Persistent**: per-user and per-asset data. Namespaced entries get their
+//!   TTL extended on every read and write, per the policy in the `ttl` module.
+//! - **Temporary**: caches that are safe to lose (oracle price cache),
+//!   accessed directly by their modules, and scratch state that only has to
+//!   outlive the operation that writes it (the re-entrancy guard's flag, the
+//!   deferred health check of a batch). Use the `*_scratch` helpers for the
+//!   latter: a temporary write is cheaper than a persistent or instance one,
+//!   and state that is written and cleared within an operation never needs
+//!   to be archived. State that must survive from one transaction to the
+//!   next, however briefly, is persistent: a temporary entry can lapse in
+//!   between.
+//!
+//! Entries that moved from persistent to instance storage are still read from
+//! their persistent location until the next write moves them.
+
+use soroban_sdk::{contracttype, symbol_short, Env, IntoVal, TryFromVal, Val};
+
+use crate::account_sweep::AccountSweepDataKey;
+use crate::analytics::AnalyticsDataKey;
+use crate::borrow_queue::BorrowQueueDataKey;
+use crate::cash_ledger::CashLedgerDataKey;
+use crate::constants::{INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::contact_hint::ContactHintDataKey;
+use crate::credit::CreditDataKey;
+use crate::cross_asset::CrossAssetDataKey;
+use crate::deposit::DepositDataKey;
+use crate::insurance::InsuranceDataKey;
+use crate::interest_history::InterestHistoryDataKey;
+use crate::lending_pool::PoolDataKey;
+use crate::liquidation_history::LiquidationHistoryDataKey;
+use crate::loyalty::LoyaltyDataKey;
+use crate::lp_collateral::LpCollateralDataKey;
+use crate::market_factory::MarketFactoryDataKey;
+use crate::ops_metrics::OpsMetricsDataKey;
+use crate::price_reporter::PriceReporterDataKey;
+use crate::rate_lock::RateLockDataKey;
+use crate::rate_mode::RateModeDataKey;
+use crate::recovery::RecoveryDataKey;
+use crate::rewards::RewardsDataKey;
+use crate::roles::RoleDataKey;
+use crate::sub_account::SubAccountDataKey;
+use crate::ttl::{get_ttl_config, TtlDataKey};
+use crate::upgrade::UpgradeDataKey;
+
+/// A module-tagged storage key
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum StorageKey {
+    /// Collateral, positions, and user/protocol analytics counters
+    Deposit(DepositDataKey),
+    /// Cached metrics and the analytics activity log
+    Analytics(AnalyticsDataKey),
+    /// Cross-asset configs, positions, and registries
+    CrossAsset(CrossAssetDataKey),
+    /// Lending pool totals and supplier shares
+    Pool(PoolDataKey),
+    /// Utilization caps and queued borrow requests
+    BorrowQueue(BorrowQueueDataKey),
+    /// Repayment records and credit summary grants
+    Credit(CreditDataKey),
+    /// Supplier coverage terms, funds, and positions
+    Insurance(InsuranceDataKey),
+    /// Role grants
+    Roles(RoleDataKey),
+    /// Isolated market wasm and registry
+    MarketFactory(MarketFactoryDataKey),
+    /// Token movements by reason
+    CashLedger(CashLedgerDataKey),
+    /// Dust sweep settings
+    AccountSweep(AccountSweepDataKey),
+    /// Storage TTL policy
+    Ttl(TtlDataKey),
+    /// Storage schema version
+    Upgrade(UpgradeDataKey),
+    /// Admin recovery guardians and requests
+    Recovery(RecoveryDataKey),
+    /// Stable borrow rates and their configuration
+    RateMode(RateModeDataKey),
+    /// Supply rate locks and their per-market caps
+    RateLock(RateLockDataKey),
+    /// Per-entrypoint call counters
+    OpsMetrics(OpsMetricsDataKey),
+    /// Borrower contact hints
+    ContactHint(ContactHintDataKey),
+    /// Liquidation records and per-liquidator totals
+    LiquidationHistory(LiquidationHistoryDataKey),
+    /// Reward token, emission rates, indexes, programs and accrued rewards
+    Rewards(RewardsDataKey),
+    /// Interest accrual checkpoints per cross-asset debt
+    InterestHistory(InterestHistoryDataKey),
+    /// Loyalty tier schedule
+    Loyalty(LoyaltyDataKey),
+    /// Sub-account registry
+    SubAccount(SubAccountDataKey),
+    /// Pools behind LP share tokens listed as collateral
+    LpCollateral(LpCollateralDataKey),
+    /// Price reporters, their settings and latest reports
+    PriceReporter(PriceReporterDataKey),
+}
+
+/// A module key that can be placed in the shared storage namespace
+pub trait Namespaced {
+    /// Wrap the key with its module tag
+    fn namespaced(&self) -> StorageKey;
+}
+
+impl Namespaced for DepositDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Deposit(self.clone())
+    }
+}
+
+impl Namespaced for AnalyticsDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Analytics(self.clone())
+    }
+}
+
+impl Namespaced for CrossAssetDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::CrossAsset(*self)
+    }
+}
+
+impl Namespaced for PoolDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Pool(self.clone())
+    }
+}
+
+impl Namespaced for BorrowQueueDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::BorrowQueue(self.clone())
+    }
+}
+
+impl Namespaced for CreditDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Credit(self.clone())
+    }
+}
+
+impl Namespaced for InsuranceDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Insurance(self.clone())
+    }
+}
+
+impl Namespaced for RoleDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Roles(self.clone())
+    }
+}
+
+impl Namespaced for MarketFactoryDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::MarketFactory(self.clone())
+    }
+}
+
+impl Namespaced for CashLedgerDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::CashLedger(self.clone())
+    }
+}
+
+impl Namespaced for AccountSweepDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::AccountSweep(self.clone())
+    }
+}
+
+impl Namespaced for TtlDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Ttl(self.clone())
+    }
+}
+
+impl Namespaced for UpgradeDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Upgrade(self.clone())
+    }
+}
+
+impl Namespaced for RecoveryDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Recovery(self.clone())
+    }
+}
+
+impl Namespaced for RateModeDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::RateMode(self.clone())
+    }
+}
+
+impl Namespaced for RateLockDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::RateLock(self.clone())
+    }
+}
+
+impl Namespaced for OpsMetricsDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::OpsMetrics(self.clone())
+    }
+}
+
+impl Namespaced for ContactHintDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::ContactHint(self.clone())
+    }
+}
+
+impl Namespaced for LiquidationHistoryDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::LiquidationHistory(self.clone())
+    }
+}
+
+impl Namespaced for RewardsDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Rewards(self.clone())
+    }
+}
+
+impl Namespaced for InterestHistoryDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::InterestHistory(self.clone())
+    }
+}
+
+impl Namespaced for LoyaltyDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::Loyalty(self.clone())
+    }
+}
+
+impl Namespaced for SubAccountDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::SubAccount(self.clone())
+    }
+}
+
+impl Namespaced for LpCollateralDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::LpCollateral(self.clone())
+    }
+}
+
+impl Namespaced for PriceReporterDataKey {
+    fn namespaced(&self) -> StorageKey {
+        StorageKey::PriceReporter(self.clone())
+    }
+}
+
+/// Original encoding of a key that predates namespacing, if any.
+pub fn legacy_location(env: &Env, key: &StorageKey) -> Option<Val> {
+    match key {
+        StorageKey::Deposit(inner) => match inner {
+            DepositDataKey::CollateralBalance(_)
+            | DepositDataKey::AssetParams(_)
+            | DepositDataKey::PauseSwitches
+            | DepositDataKey::Admin
+            | DepositDataKey::Position(_)
+            | DepositDataKey::ProtocolAnalytics
+            | DepositDataKey::UserAnalytics(_)
+            | DepositDataKey::ActivityLog
+            | DepositDataKey::LastBorrowTime(_) => Some(inner.into_val(env)),
+            DepositDataKey::AssetTotals(_) => None,
+        },
+        StorageKey::Analytics(inner) => match inner {
+            AnalyticsDataKey::ProtocolMetrics
+            | AnalyticsDataKey::UserMetrics(_)
+            | AnalyticsDataKey::TotalUsers
+            | AnalyticsDataKey::TotalTransactions => Some(inner.into_val(env)),
+            // One protocol activity log, formerly appended to by both modules
+            AnalyticsDataKey::ActivityLog => Some(DepositDataKey::ActivityLog.into_val(env)),
+            AnalyticsDataKey::UserActivity(_)
+            | AnalyticsDataKey::ActivitySlot(_)
+            | AnalyticsDataKey::ActivityCount
+            | AnalyticsDataKey::ActivityDecay
+            | AnalyticsDataKey::TagAttribution(_)
+            | AnalyticsDataKey::WritesFrozen
+            | AnalyticsDataKey::Queued
+            | AnalyticsDataKey::TvlHistory
+            | AnalyticsDataKey::RiskBadgeConfig => None,
+        },
+        StorageKey::CrossAsset(inner) => {
+            let symbol = match inner {
+                CrossAssetDataKey::MarketActivity
+                | CrossAssetDataKey::TokenCapabilities
+                | CrossAssetDataKey::BorrowerList
+                | CrossAssetDataKey::MaxBorrowSize
+                | CrossAssetDataKey::LedgerBorrows
+                | CrossAssetDataKey::CollateralFactorRamps
+                | CrossAssetDataKey::AssetPauses
+                | CrossAssetDataKey::DelistedAssets
+                | CrossAssetDataKey::IsolationCeilings
+                | CrossAssetDataKey::InterestModes
+                | CrossAssetDataKey::MinBorrowAmounts
+                | CrossAssetDataKey::SoftLiquidationConfigs
+                | CrossAssetDataKey::SoftLiquidations
+                | CrossAssetDataKey::SwapAdapter
+                | CrossAssetDataKey::AssetMetadata
+                | CrossAssetDataKey::CategoryCeilings
+                | CrossAssetDataKey::CategoryDebts
+                | CrossAssetDataKey::CategoryDebtShares
+                | CrossAssetDataKey::BorrowIndexes => return None,
+                CrossAssetDataKey::AssetConfigs => symbol_short!("configs"),
+                CrossAssetDataKey::UserPositions => symbol_short!("positions"),
+                CrossAssetDataKey::TotalSupplies => symbol_short!("supplies"),
+                CrossAssetDataKey::TotalBorrows => symbol_short!("borrows"),
+                CrossAssetDataKey::AssetList => symbol_short!("assets"),
+                CrossAssetDataKey::Admin => symbol_short!("admin"),
+                CrossAssetDataKey::UserList => symbol_short!("users"),
+                CrossAssetDataKey::StressCache => symbol_short!("stress"),
+                CrossAssetDataKey::StressProgress => symbol_short!("stress_ip"),
+                CrossAssetDataKey::TenureTiers => symbol_short!("tenure"),
+            };
+            Some(symbol.into_val(env))
+        }
+        StorageKey::Pool(inner) => match inner {
+            PoolDataKey::Pool(_)
+            | PoolDataKey::Shares(_, _)
+            | PoolDataKey::ReservesAccrued
+            | PoolDataKey::BadDebt(_)
+            | PoolDataKey::Revenue(_)
+            | PoolDataKey::RevenueClaimed => None,
+        },
+        StorageKey::BorrowQueue(inner) => match inner {
+            BorrowQueueDataKey::UtilizationCap(_)
+            | BorrowQueueDataKey::Queue(_)
+            | BorrowQueueDataKey::NextRequestId => None,
+        },
+        StorageKey::Credit(inner) => match inner {
+            CreditDataKey::Record(_) | CreditDataKey::Grants(_) => None,
+        },
+        StorageKey::Insurance(inner) => match inner {
+            InsuranceDataKey::Config(_)
+            | InsuranceDataKey::Fund(_)
+            | InsuranceDataKey::Coverage(_, _) => None,
+        },
+        StorageKey::Roles(inner) => match inner {
+            RoleDataKey::Member(_, _) => None,
+        },
+        StorageKey::MarketFactory(inner) => match inner {
+            MarketFactoryDataKey::MarketWasm
+            | MarketFactoryDataKey::Market(_)
+            | MarketFactoryDataKey::MarketByName(_)
+            | MarketFactoryDataKey::MarketCount => None,
+        },
+        StorageKey::CashLedger(inner) => match inner {
+            CashLedgerDataKey::Accounts(_) => None,
+        },
+        StorageKey::AccountSweep(inner) => match inner {
+            AccountSweepDataKey::IdleDays => None,
+        },
+        StorageKey::Ttl(inner) => match inner {
+            TtlDataKey::Config => None,
+        },
+        StorageKey::Upgrade(inner) => match inner {
+            UpgradeDataKey::Version => None,
+        },
+        StorageKey::Recovery(inner) => match inner {
+            RecoveryDataKey::Guardians | RecoveryDataKey::Request => None,
+        },
+        StorageKey::RateMode(inner) => match inner {
+            RateModeDataKey::Config | RateModeDataKey::Stable(_) => None,
+        },
+        StorageKey::RateLock(inner) => match inner {
+            RateLockDataKey::Config(_) | RateLockDataKey::Lock(..) | RateLockDataKey::Locked(_) => {
+                None
+            }
+        },
+        StorageKey::OpsMetrics(inner) => match inner {
+            OpsMetricsDataKey::Entrypoints | OpsMetricsDataKey::Calls(_) => None,
+        },
+        StorageKey::ContactHint(inner) => match inner {
+            ContactHintDataKey::Hint(_) => None,
+        },
+        StorageKey::LiquidationHistory(inner) => match inner {
+            LiquidationHistoryDataKey::Count
+            | LiquidationHistoryDataKey::Record(_)
+            | LiquidationHistoryDataKey::Stats(_) => None,
+        },
+        StorageKey::Rewards(inner) => match inner {
+            RewardsDataKey::Token
+            | RewardsDataKey::Emissions
+            | RewardsDataKey::Index(_)
+            | RewardsDataKey::User(..)
+            | RewardsDataKey::Accrued(_)
+            | RewardsDataKey::Balance
+            | RewardsDataKey::Delegate(_)
+            | RewardsDataKey::ProgramCount
+            | RewardsDataKey::Program(_)
+            | RewardsDataKey::ActiveProgram(_)
+            | RewardsDataKey::ProgramClaimant(..) => None,
+        },
+        StorageKey::InterestHistory(inner) => match inner {
+            InterestHistoryDataKey::Count(..) | InterestHistoryDataKey::Checkpoint(..) => None,
+        },
+        StorageKey::Loyalty(inner) => match inner {
+            LoyaltyDataKey::Tiers => None,
+        },
+        StorageKey::SubAccount(inner) => match inner {
+            SubAccountDataKey::Accounts(_) | SubAccountDataKey::Owner(_) => None,
+        },
+        StorageKey::LpCollateral(inner) => match inner {
+            LpCollateralDataKey::Pool(_) => None,
+        },
+        StorageKey::PriceReporter(inner) => match inner {
+            PriceReporterDataKey::Reporters
+            | PriceReporterDataKey::Config(_)
+            | PriceReporterDataKey::Reports(_)
+            | PriceReporterDataKey::Nonce(_) => None,
+        },
+    }
+}
+
+/// Whether a namespaced key holds global configuration kept in instance storage.
+fn is_global(key: &StorageKey) -> bool {
+    matches!(
+        key,
+        StorageKey::Deposit(DepositDataKey::PauseSwitches)
+            | StorageKey::Deposit(DepositDataKey::Admin)
+            | StorageKey::CrossAsset(CrossAssetDataKey::Admin)
+            | StorageKey::CrossAsset(CrossAssetDataKey::TenureTiers)
+            | StorageKey::Ttl(TtlDataKey::Config)
+            | StorageKey::Upgrade(UpgradeDataKey::Version)
+            | StorageKey::Recovery(_)
+            | StorageKey::RateMode(RateModeDataKey::Config)
+            | StorageKey::RateLock(RateLockDataKey::Config(_))
+            | StorageKey::Loyalty(LoyaltyDataKey::Tiers)
+            | StorageKey::Analytics(AnalyticsDataKey::RiskBadgeConfig)
+            | StorageKey::PriceReporter(PriceReporterDataKey::Reporters)
+    )
+}
+
+/// Physical storage location of a key.
+fn location<K: Namespaced>(env: &Env, key: &K) -> Val {
+    let key = key.namespaced();
+    legacy_location(env, &key).unwrap_or_else(|| key.into_val(env))
+}
+
+/// Read a namespaced entry, extending the TTL of persistent data.
+pub fn get<K: Namespaced, V: TryFromVal<Env, Val>>(env: &Env, key: &K) -> Option<V> {
+    let location = location(env, key);
+    if is_global(&key.namespaced()) {
+        return get_instance(env, &location);
+    }
+    let value = env.storage().persistent().get(&location);
+    if value.is_some() {
+        extend_persistent_ttl(env, &location);
+    }
+    value
+}
+
+/// Write a namespaced entry, extending the TTL of persistent data.
+pub fn set<K: Namespaced, V: IntoVal<Env, Val>>(env: &Env, key: &K, value: &V) {
+    let location = location(env, key);
+    if is_global(&key.namespaced()) {
+        set_instance(env, &location, value);
+        return;
+    }
+    env.storage().persistent().set(&location, value);
+    extend_persistent_ttl(env, &location);
+}
+
+/// Extend the TTL of a namespaced persistent entry, if it exists.
+///
+/// Global configuration lives as long as the instance and is left alone.
+pub fn extend_ttl<K: Namespaced>(env: &Env, key: &K) {
+    let location = location(env, key);
+    if is_global(&key.namespaced()) || !env.storage().persistent().has(&location) {
+        return;
+    }
+    extend_persistent_ttl(env, &location);
+}
+
+fn extend_persistent_ttl(env: &Env, location: &Val) {
+    let config = get_ttl_config(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(location, config.threshold, config.extend_to);
+}
+
+/// Whether a namespaced entry exists.
+pub fn has<K: Namespaced>(env: &Env, key: &K) -> bool {
+    let location = location(env, key);
+    if is_global(&key.namespaced()) {
+        return has_instance(env, &location);
+    }
+    env.storage().persistent().has(&location)
+}
+
+/// Delete a namespaced entry.
+pub fn remove<K: Namespaced>(env: &Env, key: &K) {
+    let location = location(env, key);
+    if is_global(&key.namespaced()) {
+        env.storage().instance().remove(&location);
+        return;
+    }
+    env.storage().persistent().remove(&location);
+}
+
+/// Read a global configuration entry.
+///
+/// Falls back to the persistent entry written before storage tiering.
+pub fn get_instance<K: IntoVal<Env, Val>, V: TryFromVal<Env, Val>>(
+    env: &Env,
+    key: &K,
+) -> Option<V> {
+    let key: Val = key.into_val(env);
+    env.storage()
+        .instance()
+        .get(&key)
+        .or_else(|| env.storage().persistent().get(&key))
+}
+
+/// Write a global configuration entry, removing any persistent copy.
+pub fn set_instance<K: IntoVal<Env, Val>, V: IntoVal<Env, Val>>(env: &Env, key: &K, value: &V) {
+    let key: Val = key.into_val(env);
+    env.storage().instance().set(&key, value);
+    if env.storage().persistent().has(&key) {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Remove a global configuration entry, including any persistent copy.
+pub fn remove_instance<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let key: Val = key.into_val(env);
+    env.storage().instance().remove(&key);
+    env.storage().persistent().remove(&key);
+}
+
+/// Whether a global configuration entry exists.
+pub fn has_instance<K: IntoVal<Env, Val>>(env: &Env, key: &K) -> bool {
+    let key: Val = key.into_val(env);
+    env.storage().instance().has(&key) || env.storage().persistent().has(&key)
+}
+
+/// Write a scratch entry.
+///
+/// It lives for the minimum temporary TTL, so clear it with
+/// [`remove_scratch`] before the operation that wrote it returns. Never use
+/// it for state read by a later transaction.
+pub fn set_scratch<K: IntoVal<Env, Val>, V: IntoVal<Env, Val>>(env: &Env, key: &K, value: &V) {
+    env.storage().temporary().set(key, value);
+}
+
+/// Remove a scratch entry.
+pub fn remove_scratch<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    env.storage().temporary().remove(key);
+}
+
+/// Whether a scratch entry exists.
+pub fn has_scratch<K: IntoVal<Env, Val>>(env: &Env, key: &K) -> bool {
+    env.storage().temporary().has(key)
+}
+
+/// Keep the contract instance, and with it all global configuration, alive.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
@@ -0,0 +1,192 @@
+//! # Sub-Accounts
+//!
+//! Lets one address run several isolated cross-asset positions, e.g. one per
+//! strategy, so that liquidating one leaves the others untouched.
+//!
+//! A sub-account is identified by its owner and a `sub_id` of the owner's
+//! choosing. Its positions are held under an address derived from both (see
+//! [`get_sub_account`]), which the contract never signs for, so only the
+//! owner can operate it through the `sub_account_*` functions here. Each
+//! sub-account has its own health factor, borrow capacity and interest
+//! history, and is liquidated on its own by passing its address as the
+//! borrower. Positions held under the owner's own address are a separate
+//! account again.
+//!
+//! A sub-account is registered to its owner on its first deposit. An owner
+//! may open up to [`MAX_SUB_ACCOUNTS`].
+//!
+//! Sub-accounts hold cross-asset positions only, which are not token-backed
+//! and earn no reward emissions (see `rewards`).
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, BytesN, Env, Map};
+
+use crate::cross_asset::{self, AssetPosition, CrossAssetError, UserPositionSummary};
+use crate::events::{emit_sub_account_opened, SubAccountOpenedEvent};
+use crate::storage;
+
+/// Maximum number of sub-accounts per owner
+pub const MAX_SUB_ACCOUNTS: u32 = 20;
+
+/// Storage keys for sub-account data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum SubAccountDataKey {
+    /// Sub-accounts an owner has opened, by sub_id: Map<u32, Address>
+    Accounts(Address),
+    /// Owner and sub_id of a sub-account address: SubAccount
+    Owner(Address),
+}
+
+/// The owner of a sub-account and its number
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubAccount {
+    /// The address that controls the sub-account
+    pub owner: Address,
+    /// The owner's number for the sub-account
+    pub sub_id: u32,
+}
+
+/// Address holding the positions of an owner's sub-account
+///
+/// Derived from the owner and `sub_id`, so it is known before the
+/// sub-account is opened.
+pub fn get_sub_account(env: &Env, owner: &Address, sub_id: u32) -> Address {
+    let salt: BytesN<32> = env
+        .crypto()
+        .sha256(&(owner.clone(), sub_id).to_xdr(env))
+        .into();
+    env.deployer()
+        .with_current_contract(salt)
+        .deployed_address()
+}
+
+/// Sub-accounts an owner has opened, by sub_id
+pub fn get_sub_accounts(env: &Env, owner: &Address) -> Map<u32, Address> {
+    storage::get(env, &SubAccountDataKey::Accounts(owner.clone())).unwrap_or(Map::new(env))
+}
+
+/// Owner and sub_id of a sub-account address, if it is one
+pub fn get_sub_account_owner(env: &Env, account: &Address) -> Option<SubAccount> {
+    storage::get(env, &SubAccountDataKey::Owner(account.clone()))
+}
+
+/// Address that authorizes for `account`: its owner for a sub-account, the
+/// account itself otherwise
+pub(crate) fn controller(env: &Env, account: &Address) -> Address {
+    get_sub_account_owner(env, account).map_or(account.clone(), |sub| sub.owner)
+}
+
+/// Health of one of an owner's sub-accounts
+///
+/// # Errors
+/// * `PriceStale` - Any asset with a non-zero position has a stale price
+pub fn get_sub_account_summary(
+    env: &Env,
+    owner: &Address,
+    sub_id: u32,
+) -> Result<UserPositionSummary, CrossAssetError> {
+    cross_asset::get_user_position_summary(env, &get_sub_account(env, owner, sub_id))
+}
+
+/// Deposit collateral into a sub-account, opening it if needed (owner only)
+///
+/// # Errors
+/// * `TooManySubAccounts` - The owner already has [`MAX_SUB_ACCOUNTS`]
+/// * Any error of `cross_asset::cross_asset_deposit`
+pub fn sub_account_deposit(
+    env: &Env,
+    owner: Address,
+    sub_id: u32,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    owner.require_auth();
+    let account = open(env, &owner, sub_id)?;
+    cross_asset::deposit_authorized(env, account, asset, amount)
+}
+
+/// Withdraw collateral from a sub-account (owner only)
+///
+/// Only the sub-account's health factor is checked.
+///
+/// # Errors
+/// Any error of `cross_asset::cross_asset_withdraw`
+pub fn sub_account_withdraw(
+    env: &Env,
+    owner: Address,
+    sub_id: u32,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    owner.require_auth();
+    let account = get_sub_account(env, &owner, sub_id);
+    cross_asset::withdraw_authorized(env, account, asset, amount)
+}
+
+/// Borrow against a sub-account's collateral (owner only)
+///
+/// # Errors
+/// Any error of `cross_asset::cross_asset_borrow`
+pub fn sub_account_borrow(
+    env: &Env,
+    owner: Address,
+    sub_id: u32,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    owner.require_auth();
+    let account = get_sub_account(env, &owner, sub_id);
+    cross_asset::borrow_authorized(env, account, asset, amount)
+}
+
+/// Repay a sub-account's debt (owner only)
+///
+/// # Errors
+/// Any error of `cross_asset::cross_asset_repay`
+pub fn sub_account_repay(
+    env: &Env,
+    owner: Address,
+    sub_id: u32,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<AssetPosition, CrossAssetError> {
+    owner.require_auth();
+    let account = get_sub_account(env, &owner, sub_id);
+    cross_asset::repay_authorized(env, account, asset, amount)
+}
+
+/// Register a sub-account to its owner on first use; returns its address
+fn open(env: &Env, owner: &Address, sub_id: u32) -> Result<Address, CrossAssetError> {
+    let mut accounts = get_sub_accounts(env, owner);
+    if let Some(account) = accounts.get(sub_id) {
+        return Ok(account);
+    }
+    if accounts.len() >= MAX_SUB_ACCOUNTS {
+        return Err(CrossAssetError::TooManySubAccounts);
+    }
+
+    let account = get_sub_account(env, owner, sub_id);
+    accounts.set(sub_id, account.clone());
+    storage::set(env, &SubAccountDataKey::Accounts(owner.clone()), &accounts);
+    storage::set(
+        env,
+        &SubAccountDataKey::Owner(account.clone()),
+        &SubAccount {
+            owner: owner.clone(),
+            sub_id,
+        },
+    );
+
+    emit_sub_account_opened(
+        env,
+        SubAccountOpenedEvent {
+            owner: owner.clone(),
+            sub_id,
+            account: account.clone(),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(account)
+}
@@ -0,0 +1,134 @@
+//! # Account Sweep Tests
+//!
+//! Tests that abandoned empty accounts are pruned by keeper sweeps while
+//! funded, recently active and unknown accounts are left alone.
+
+use crate::account_sweep::{AccountSweepError, MAX_SWEEP_BATCH};
+use crate::deposit::{AssetParams, DepositDataKey, Position};
+use crate::roles::Role;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+const DAY: u64 = 86_400;
+
+/// Registers the contract and a token enabled for deposits, and funds a user
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::AssetParams(token.clone()),
+            &AssetParams {
+                deposit_enabled: true,
+                collateral_factor: 7500,
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    });
+
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &token).mint(&user, &1_000);
+    TokenClient::new(env, &token).approve(&user, &contract_id, &1_000, &1_000);
+    (contract_id, client, admin, token, user)
+}
+
+fn has_position(env: &Env, contract_id: &Address, user: &Address) -> bool {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get::<_, Position>(&DepositDataKey::Position(user.clone()))
+            .is_some()
+    })
+}
+
+#[test]
+fn test_sweep_prunes_idle_empty_account() {
+    let env = Env::default();
+    let (contract_id, client, _, token, user) = setup(&env);
+    let asset = Some(token);
+
+    client.deposit_collateral(&user, &asset, &500);
+    client.withdraw_collateral(&user, &asset, &500);
+    assert!(has_position(&env, &contract_id, &user));
+    assert!(!client.is_dust_account(&user));
+
+    env.ledger().with_mut(|li| li.timestamp += 180 * DAY);
+    assert!(client.is_dust_account(&user));
+    assert_eq!(client.sweep_dust_accounts(&vec![&env, user.clone()]), 1);
+
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "account_pruned_event"),).into_val(&env)
+    );
+    assert!(!has_position(&env, &contract_id, &user));
+    assert!(!client.is_dust_account(&user));
+}
+
+#[test]
+fn test_sweep_skips_funded_recent_and_unknown_accounts() {
+    let env = Env::default();
+    let (_, client, _, token, funded) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&funded, &asset, &500);
+
+    env.ledger().with_mut(|li| li.timestamp += 200 * DAY);
+    let recent = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&recent, &100);
+    TokenClient::new(&env, &token).approve(&recent, &client.address, &100, &100_000);
+    client.deposit_collateral(&recent, &asset, &100);
+    client.withdraw_collateral(&recent, &asset, &100);
+    let unknown = Address::generate(&env);
+
+    let pruned = client.sweep_dust_accounts(&vec![&env, funded, recent, unknown]);
+    assert_eq!(pruned, 0);
+}
+
+#[test]
+fn test_sweep_rejects_oversized_batch() {
+    let env = Env::default();
+    let (_, client, _, _, _) = setup(&env);
+
+    let mut users = Vec::new(&env);
+    for _ in 0..=MAX_SWEEP_BATCH {
+        users.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        client.try_sweep_dust_accounts(&users),
+        Err(Ok(AccountSweepError::BatchTooLarge))
+    );
+}
+
+#[test]
+fn test_idle_period_requires_risk_admin() {
+    let env = Env::default();
+    let (_, client, admin, _, user) = setup(&env);
+    let risk_admin = Address::generate(&env);
+    client.grant_role(&admin, &Role::RiskAdmin, &risk_admin);
+
+    assert_eq!(
+        client.try_set_sweep_idle_days(&user, &90),
+        Err(Ok(AccountSweepError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_sweep_idle_days(&risk_admin, &7),
+        Err(Ok(AccountSweepError::InvalidIdlePeriod))
+    );
+    client.set_sweep_idle_days(&risk_admin, &90);
+    assert_eq!(client.get_sweep_idle_days(), 90);
+}
@@ -0,0 +1,214 @@
+//! # Admin Simulation Tests
+//!
+//! Tests for dry runs of admin changes: validation matches the real
+//! entrypoints, projected liquidations are counted, and nothing is stored.
+
+use crate::admin_simulation::{
+    AdminAction, AdminActionEffects, AdminSimulationError, AssetListing,
+};
+use crate::cross_asset::{AssetConfig, AssetConfigUpdate, TenureRebateTier};
+use crate::risk_management::RiskParamsChange;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+}
+
+fn asset_config(env: &Env, asset: &Option<Address>) -> AssetConfig {
+    AssetConfig {
+        asset: asset.clone(),
+        collateral_factor: 7500,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// Registers USDC and XLM at a price of 1.0 with 10,000 USDC supplied
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let market = Market {
+        admin: Address::generate(env),
+        usdc: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+    };
+    client.initialize(&market.admin);
+    client.initialize_ca(&market.admin);
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(&market.admin, asset, &asset_config(env, asset));
+    }
+    client.ca_deposit_collateral(&Address::generate(env), &market.usdc, &10_000);
+    (client, market)
+}
+
+/// A user with 10,000 XLM of collateral who borrows `amount` USDC
+fn borrower(env: &Env, client: &HelloContractClient, market: &Market, amount: i128) -> Address {
+    let user = Address::generate(env);
+    client.ca_deposit_collateral(&user, &market.xlm, &10_000);
+    client.ca_borrow_asset(&user, &market.usdc, &amount);
+    user
+}
+
+fn collateral_factor_update(asset: &Option<Address>, collateral_factor: i128) -> AdminAction {
+    AdminAction::UpdateAssetConfig(AssetConfigUpdate {
+        asset: asset.clone(),
+        collateral_factor: Some(collateral_factor),
+        borrow_factor: None,
+        max_supply: None,
+        max_borrow: None,
+        can_collateralize: None,
+        can_borrow: None,
+    })
+}
+
+fn risk_change(min_collateral_ratio: i128) -> AdminAction {
+    AdminAction::RiskParams(RiskParamsChange {
+        min_collateral_ratio: Some(min_collateral_ratio),
+        liquidation_threshold: None,
+        close_factor: None,
+        liquidation_incentive: None,
+    })
+}
+
+#[test]
+fn test_simulate_collateral_factor_cut_counts_new_liquidations() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let at_risk = borrower(&env, &client, &market, 7_000);
+    borrower(&env, &client, &market, 2_000);
+
+    // 6,500 of weighted collateral no longer covers 7,000 of debt
+    let effects = client.simulate_admin_action(&collateral_factor_update(&market.xlm, 6500));
+    assert_eq!(
+        effects,
+        AdminActionEffects {
+            // The USDC supplier is registered too
+            positions_scanned: 3,
+            liquidatable_before: 0,
+            liquidatable_after: 1,
+            newly_liquidatable: 1,
+            newly_liquidatable_debt: 7_000,
+        }
+    );
+
+    // Nothing changed
+    assert_eq!(client.get_asset_config(&market.xlm).collateral_factor, 7500);
+    assert!(!client.get_user_position_summary(&at_risk).is_liquidatable);
+}
+
+#[test]
+fn test_simulate_listing_validates_config() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    borrower(&env, &client, &market, 7_000);
+
+    let eurc = Some(create_token(&env));
+    let mut config = asset_config(&env, &eurc);
+    let listing = AdminAction::ListAsset(AssetListing {
+        asset: eurc.clone(),
+        config: config.clone(),
+    });
+    let effects = client.simulate_admin_action(&listing);
+    assert_eq!(effects.positions_scanned, 2);
+    assert_eq!(effects.newly_liquidatable, 0);
+    assert_eq!(client.get_asset_list().len(), 2);
+
+    config.price = 0;
+    let result = client.try_simulate_admin_action(&AdminAction::ListAsset(AssetListing {
+        asset: eurc,
+        config,
+    }));
+    assert_eq!(result, Err(Ok(AdminSimulationError::OutOfBounds)));
+}
+
+#[test]
+fn test_simulate_asset_update_errors() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+
+    let unlisted = Some(Address::generate(&env));
+    assert_eq!(
+        client.try_simulate_admin_action(&collateral_factor_update(&unlisted, 5000)),
+        Err(Ok(AdminSimulationError::AssetNotConfigured))
+    );
+    assert_eq!(
+        client.try_simulate_admin_action(&collateral_factor_update(&market.xlm, 10_001)),
+        Err(Ok(AdminSimulationError::OutOfBounds))
+    );
+
+    // Positions with stale prices cannot be projected
+    borrower(&env, &client, &market, 1_000);
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    assert_eq!(
+        client.try_simulate_admin_action(&collateral_factor_update(&market.xlm, 5000)),
+        Err(Ok(AdminSimulationError::PriceStale))
+    );
+}
+
+#[test]
+fn test_simulate_risk_params_applies_change_limits() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+
+    // 10% above the 110% default is allowed
+    let effects = client.simulate_admin_action(&risk_change(12_100));
+    assert_eq!(effects, AdminActionEffects::default());
+    assert_eq!(client.get_min_collateral_ratio(), 11_000);
+
+    assert_eq!(
+        client.try_simulate_admin_action(&risk_change(15_000)),
+        Err(Ok(AdminSimulationError::ChangeTooLarge))
+    );
+    // Below the liquidation threshold
+    assert_eq!(
+        client.try_simulate_admin_action(&risk_change(10_000)),
+        Err(Ok(AdminSimulationError::OutOfBounds))
+    );
+
+    client.set_emergency_pause(&market.admin, &true);
+    assert_eq!(
+        client.try_simulate_admin_action(&risk_change(12_100)),
+        Err(Ok(AdminSimulationError::EmergencyPaused))
+    );
+}
+
+#[test]
+fn test_simulate_tenure_rebates() {
+    let env = Env::default();
+    let (client, _market) = setup(&env);
+
+    let tiers = vec![
+        &env,
+        TenureRebateTier {
+            min_tenure_seconds: 86_400,
+            rebate_bps: 500,
+        },
+        TenureRebateTier {
+            min_tenure_seconds: 604_800,
+            rebate_bps: 1000,
+        },
+    ];
+    client.simulate_admin_action(&AdminAction::TenureRebates(tiers.clone()));
+    assert!(client.get_tenure_rebates().is_empty());
+
+    // Out of order
+    let reversed = vec![&env, tiers.get(1).unwrap(), tiers.get(0).unwrap()];
+    assert_eq!(
+        client.try_simulate_admin_action(&AdminAction::TenureRebates(reversed)),
+        Err(Ok(AdminSimulationError::OutOfBounds))
+    );
+}
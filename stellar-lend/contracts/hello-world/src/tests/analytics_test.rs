@@ -4,9 +4,16 @@
 //! updated on core actions (deposit, borrow, repay, withdraw) and exposed via getters.
 //! Covers get_protocol_report, get_user_report, edge cases (first deposit, full withdraw).
 
-use crate::deposit::{DepositDataKey, ProtocolAnalytics};
+use crate::analytics::{
+    get_activity_by_type, ActivityType, AnalyticsError, LegacyActivityEntry, TagAttribution,
+};
+use crate::deposit::{DepositDataKey, ProtocolAnalytics, TAG_METADATA_KEY};
 use crate::{HelloContract, HelloContractClient};
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    xdr::ToXdr,
+    Address, BytesN, Env, Map, Symbol, Vec,
+};
 
 fn create_test_env() -> Env {
     let env = Env::default();
@@ -156,3 +163,384 @@ fn test_analytics_average_borrow_rate_non_negative() {
     let report = client.get_protocol_report();
     assert!(report.metrics.average_borrow_rate >= 0);
 }
+
+// =============================================================================
+// Report hashing
+// =============================================================================
+
+#[test]
+fn test_hash_user_report_matches_report_xdr() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &5000);
+
+    let hash = client.hash_user_report(&user);
+    let report = client.get_user_report(&user);
+    let expected: BytesN<32> = env.crypto().sha256(&report.to_xdr(&env)).into();
+    assert_eq!(hash, expected);
+
+    // Deterministic within a ledger
+    assert_eq!(client.hash_user_report(&user), hash);
+}
+
+#[test]
+fn test_hash_user_report_tracks_state() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &5000);
+    let before = client.hash_user_report(&user);
+
+    client.deposit_collateral(&user, &None, &1000);
+    let after_deposit = client.hash_user_report(&user);
+    assert_ne!(after_deposit, before);
+
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    assert_ne!(client.hash_user_report(&user), after_deposit);
+}
+
+#[test]
+fn test_hash_user_report_unknown_user() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+
+    let result = client.try_hash_user_report(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(AnalyticsError::DataNotFound)));
+}
+
+// =============================================================================
+// Activity score decay
+// =============================================================================
+
+const NINETY_DAYS: u64 = 90 * 86_400;
+
+#[test]
+fn test_activity_score_decays_while_idle() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    for _ in 0..5 {
+        client.deposit_collateral(&user, &None, &1000);
+    }
+
+    // 5 * 100 + 5000 / 1000
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 505);
+
+    // Halved after one half-life, three quarters of that after half another
+    env.ledger().with_mut(|li| li.timestamp += NINETY_DAYS);
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 252);
+    env.ledger().with_mut(|li| li.timestamp += NINETY_DAYS / 2);
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 189);
+
+    // New activity restores the full score
+    client.deposit_collateral(&user, &None, &1000);
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 606);
+}
+
+#[test]
+fn test_active_user_outranks_idle_whale() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let whale = Address::generate(&env);
+    client.deposit_collateral(&whale, &None, &1_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp += 4 * NINETY_DAYS);
+    let newcomer = Address::generate(&env);
+    for _ in 0..2 {
+        client.deposit_collateral(&newcomer, &None, &1000);
+    }
+
+    // 1,100 / 16 against 202
+    assert_eq!(client.get_user_report(&whale).metrics.activity_score, 68);
+    assert_eq!(
+        client.get_user_report(&newcomer).metrics.activity_score,
+        202
+    );
+}
+
+#[test]
+fn test_set_activity_decay() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1000);
+
+    let config = client.get_activity_decay();
+    assert_eq!(config.half_life, NINETY_DAYS);
+    assert_eq!(config.grace_period, 0);
+
+    // Within the grace period, nothing decays
+    client.set_activity_decay(&admin, &86_400, &(7 * 86_400));
+    env.ledger().with_mut(|li| li.timestamp += 7 * 86_400);
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 101);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 50);
+
+    // A zero half-life turns decay off
+    client.set_activity_decay(&admin, &0, &0);
+    assert_eq!(client.get_user_report(&user).metrics.activity_score, 101);
+
+    let result = client.try_set_activity_decay(&Address::generate(&env), &86_400, &0);
+    assert_eq!(result, Err(Ok(AnalyticsError::Unauthorized)));
+}
+
+// =============================================================================
+// Integrator tags
+// =============================================================================
+
+#[test]
+fn test_tagged_flows_recorded_in_activity() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    let vault = Symbol::new(&env, "vault_v1");
+
+    client.deposit_collateral_tagged(&user, &None, &5000, &vault);
+    client.borrow_asset_tagged(&user, &None, &1000, &vault);
+    client.repay_debt(&user, &None, &500);
+
+    let activity = client.get_user_activity(&user, &10, &0);
+    assert_eq!(activity.len(), 3);
+    // Most recent first; repayments carry no tag
+    assert!(activity.get(0).unwrap().metadata.is_empty());
+    for entry in [activity.get(1).unwrap(), activity.get(2).unwrap()] {
+        assert_eq!(entry.metadata.get(TAG_METADATA_KEY), Some(vault.clone()));
+    }
+
+    // Tags do not change the position
+    let report = client.get_user_report(&user);
+    assert_eq!(report.position.collateral, 5000);
+    assert_eq!(report.position.debt, 500);
+}
+
+#[test]
+fn test_tag_attribution_totals() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let vault = Symbol::new(&env, "vault_v1");
+    let aggregator = Symbol::new(&env, "agg");
+
+    let user = Address::generate(&env);
+    client.deposit_collateral_tagged(&user, &None, &5000, &vault);
+    client.borrow_asset_tagged(&user, &None, &1000, &vault);
+    // Adds to the open loan
+    client.borrow_asset_tagged(&user, &None, &500, &vault);
+
+    let other = Address::generate(&env);
+    client.deposit_collateral_tagged(&other, &None, &2000, &aggregator);
+    client.deposit_collateral(&other, &None, &3000);
+
+    assert_eq!(
+        client.get_tag_attribution(&vault),
+        TagAttribution {
+            total_deposits: 5000,
+            total_borrows: 1500,
+            loans_opened: 1,
+            last_activity: env.ledger().timestamp(),
+        }
+    );
+    assert_eq!(client.get_tag_attribution(&aggregator).total_deposits, 2000);
+    assert_eq!(
+        client.get_tag_attribution(&Symbol::new(&env, "unused")),
+        TagAttribution::default()
+    );
+}
+
+// =============================================================================
+// Maintenance freeze
+// =============================================================================
+
+#[test]
+fn test_frozen_analytics_writes_are_queued() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let vault = Symbol::new(&env, "vault_v1");
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &5000);
+
+    client.set_analytics_frozen(&admin, &true);
+    assert!(client.is_analytics_frozen());
+    client.deposit_collateral_tagged(&user, &None, &2000, &vault);
+    client.borrow_asset_tagged(&user, &None, &1000, &vault);
+
+    // Lending is unaffected; the activity log and tag attribution wait
+    assert_eq!(client.get_user_report(&user).position.collateral, 7000);
+    assert_eq!(client.get_user_activity(&user, &10, &0).len(), 1);
+    assert_eq!(
+        client.get_tag_attribution(&vault),
+        TagAttribution::default()
+    );
+    let queued = client.get_queued_analytics();
+    assert_eq!(queued.activities.len(), 2);
+    assert_eq!(
+        queued.tag_flows.get(vault.clone()).unwrap().total_borrows,
+        1000
+    );
+
+    client.set_analytics_frozen(&admin, &false);
+    assert!(!client.is_analytics_frozen());
+    let activity = client.get_user_activity(&user, &10, &0);
+    assert_eq!(activity.len(), 3);
+    assert_eq!(activity.get(0).unwrap().activity_type, ActivityType::Borrow);
+    assert_eq!(
+        client.get_tag_attribution(&vault),
+        TagAttribution {
+            total_deposits: 2000,
+            total_borrows: 1000,
+            loans_opened: 1,
+            last_activity: env.ledger().timestamp(),
+        }
+    );
+    assert!(client.get_queued_analytics().activities.is_empty());
+}
+
+#[test]
+fn test_set_analytics_frozen_requires_pause_guardian() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+
+    let result = client.try_set_analytics_frozen(&Address::generate(&env), &true);
+    assert_eq!(result, Err(Ok(AnalyticsError::Unauthorized)));
+    assert!(!client.is_analytics_frozen());
+}
+
+// =============================================================================
+// Activity storage
+// =============================================================================
+
+#[test]
+fn test_user_activity_feed_is_per_user_and_newest_first() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let u1 = Address::generate(&env);
+    let u2 = Address::generate(&env);
+
+    client.deposit_collateral(&u1, &None, &100);
+    client.deposit_collateral(&u2, &None, &999);
+    client.deposit_collateral(&u1, &None, &200);
+    client.deposit_collateral(&u1, &None, &300);
+
+    let feed = client.get_user_activity(&u1, &2, &0);
+    assert_eq!(feed.len(), 2);
+    assert_eq!(feed.get(0).unwrap().amount, 300);
+    assert_eq!(feed.get(1).unwrap().amount, 200);
+    let page2 = client.get_user_activity(&u1, &2, &2);
+    assert_eq!(page2.len(), 1);
+    assert_eq!(page2.get(0).unwrap().amount, 100);
+
+    let recent = client.get_recent_activity(&10, &0);
+    assert_eq!(recent.len(), 4);
+    assert_eq!(recent.get(2).unwrap().user, u2);
+}
+
+#[test]
+fn test_legacy_activity_log_is_read_after_new_entries() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    let mut legacy = Vec::new(&env);
+    legacy.push_back(LegacyActivityEntry {
+        user: user.clone(),
+        activity_type: Symbol::new(&env, "deposit"),
+        amount: 42,
+        asset: None,
+        timestamp: 0,
+        metadata: Map::new(&env),
+    });
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::ActivityLog, &legacy);
+    });
+
+    client.deposit_collateral(&user, &None, &500);
+
+    let recent = client.get_recent_activity(&10, &0);
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent.get(0).unwrap().amount, 500);
+    assert_eq!(recent.get(1).unwrap().amount, 42);
+    let feed = client.get_user_activity(&user, &10, &1);
+    assert_eq!(feed.len(), 1);
+    assert_eq!(feed.get(0).unwrap().amount, 42);
+    assert_eq!(feed.get(0).unwrap().activity_type, ActivityType::Deposit);
+}
+
+#[test]
+fn test_activity_type_symbol_shim_round_trips() {
+    let env = create_test_env();
+    for activity_type in [
+        ActivityType::Deposit,
+        ActivityType::Withdraw,
+        ActivityType::Borrow,
+        ActivityType::Repay,
+        ActivityType::Liquidation,
+        ActivityType::Claim,
+        ActivityType::AdminAction(Symbol::new(&env, "set_risk_params")),
+    ] {
+        let symbol = activity_type.to_symbol(&env);
+        assert_eq!(ActivityType::from_symbol(&env, &symbol), activity_type);
+    }
+    assert_eq!(
+        ActivityType::Liquidation.to_symbol(&env),
+        Symbol::new(&env, "liquidate")
+    );
+}
+
+#[test]
+fn test_activity_filtered_by_type() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1_000);
+    client.deposit_collateral(&user, &None, &500);
+
+    env.as_contract(&contract_id, || {
+        let deposits = get_activity_by_type(&env, ActivityType::Deposit, 10).unwrap();
+        assert_eq!(deposits.len(), 2);
+        assert_eq!(deposits.get(0).unwrap().amount, 500);
+        let borrows = get_activity_by_type(&env, ActivityType::Borrow, 10).unwrap();
+        assert_eq!(borrows.len(), 1);
+    });
+}
+
+// =============================================================================
+// TVL history
+// =============================================================================
+
+#[test]
+fn test_tvl_high_water_mark_and_drawdown() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.withdraw_collateral(&user, &None, &4_000);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    let metrics = client.get_protocol_report().metrics;
+    assert_eq!(metrics.total_value_locked, 7_000);
+    assert_eq!(metrics.tvl_all_time_high, 10_000);
+    assert_eq!(metrics.tvl_drawdown, 3_000);
+    assert_eq!(metrics.tvl_max_drawdown, 4_000);
+    assert_eq!(metrics.tvl_daily_change, 7_000);
+}
+
+#[test]
+fn test_tvl_daily_change_resets_each_day() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &5_000);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(client.get_protocol_report().metrics.tvl_daily_change, 0);
+
+    client.withdraw_collateral(&user, &None, &1_500);
+    let metrics = client.get_protocol_report().metrics;
+    assert_eq!(metrics.tvl_daily_change, -1_500);
+    assert_eq!(metrics.tvl_max_drawdown, 3_000);
+}
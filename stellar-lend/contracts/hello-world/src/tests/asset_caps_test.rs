@@ -0,0 +1,216 @@
+//! # Asset Cap Tests
+//!
+//! Tests for per-asset supply and borrow caps: the admin setter, enforcement
+//! at deposit and borrow time, and the totals kept by withdrawals and repayments.
+//! Listed assets keep their caps in their cross-asset configuration.
+
+use crate::cross_asset::AssetConfig;
+use crate::deposit::{AssetTotals, DepositError};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
+
+struct Market<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    token: Address,
+}
+
+fn setup(env: &Env) -> Market<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    Market {
+        client,
+        admin,
+        token,
+    }
+}
+
+/// Lists the market's token with the cross-asset module
+fn list_token(env: &Env, market: &Market, can_borrow: bool) {
+    market.client.initialize_ca(&market.admin);
+    market.client.initialize_asset(
+        &market.admin,
+        &Some(market.token.clone()),
+        &AssetConfig {
+            asset: Some(market.token.clone()),
+            collateral_factor: 5000,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+}
+
+/// A user holding `balance` tokens, all approved to the contract
+fn funded_user(env: &Env, market: &Market, balance: i128) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &market.token).mint(&user, &balance);
+    TokenClient::new(env, &market.token).approve(
+        &user,
+        &market.client.address,
+        &balance,
+        &(env.ledger().sequence() + 100),
+    );
+    user
+}
+
+#[test]
+fn test_set_asset_caps_creates_params() {
+    let env = Env::default();
+    let market = setup(&env);
+    assert_eq!(market.client.get_asset_params(&market.token), None);
+
+    market
+        .client
+        .set_asset_caps(&market.admin, &market.token, &5_000, &2_000);
+    let params = market.client.get_asset_params(&market.token).unwrap();
+    assert!(params.deposit_enabled);
+    assert_eq!(params.max_deposit, 0);
+    assert_eq!(params.supply_cap, 5_000);
+    assert_eq!(params.borrow_cap, 2_000);
+}
+
+#[test]
+fn test_set_asset_caps_rejects_non_admin_and_negative_caps() {
+    let env = Env::default();
+    let market = setup(&env);
+
+    let result =
+        market
+            .client
+            .try_set_asset_caps(&Address::generate(&env), &market.token, &5_000, &0);
+    assert_eq!(result, Err(Ok(DepositError::Unauthorized)));
+
+    let result = market
+        .client
+        .try_set_asset_caps(&market.admin, &market.token, &-1, &0);
+    assert_eq!(result, Err(Ok(DepositError::InvalidAmount)));
+}
+
+#[test]
+#[should_panic(expected = "Deposit error: SupplyCapExceeded")]
+fn test_deposit_above_supply_cap_fails() {
+    let env = Env::default();
+    let market = setup(&env);
+    market
+        .client
+        .set_asset_caps(&market.admin, &market.token, &5_000, &0);
+
+    let first = funded_user(&env, &market, 10_000);
+    let second = funded_user(&env, &market, 10_000);
+    market
+        .client
+        .deposit_collateral(&first, &Some(market.token.clone()), &3_000);
+    market
+        .client
+        .deposit_collateral(&second, &Some(market.token.clone()), &2_001);
+}
+
+#[test]
+fn test_withdraw_frees_supply_cap() {
+    let env = Env::default();
+    let market = setup(&env);
+    market
+        .client
+        .set_asset_caps(&market.admin, &market.token, &5_000, &0);
+    let asset = Some(market.token.clone());
+
+    let user = funded_user(&env, &market, 10_000);
+    market.client.deposit_collateral(&user, &asset, &5_000);
+    market.client.withdraw_collateral(&user, &asset, &1_000);
+    market.client.deposit_collateral(&user, &asset, &1_000);
+    assert_eq!(
+        market.client.get_asset_totals(&market.token),
+        AssetTotals {
+            supplied: 5_000,
+            borrowed: 0,
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Borrow error: BorrowCapExceeded")]
+fn test_borrow_above_borrow_cap_fails() {
+    let env = Env::default();
+    let market = setup(&env);
+    market
+        .client
+        .set_asset_caps(&market.admin, &market.token, &0, &1_500);
+    let asset = Some(market.token.clone());
+
+    let user = funded_user(&env, &market, 10_000);
+    market.client.deposit_collateral(&user, &asset, &10_000);
+    market.client.borrow_asset(&user, &asset, &1_000);
+    market.client.borrow_asset(&user, &asset, &501);
+}
+
+#[test]
+fn test_repay_frees_borrow_cap() {
+    let env = Env::default();
+    let market = setup(&env);
+    market
+        .client
+        .set_asset_caps(&market.admin, &market.token, &0, &1_500);
+    let asset = Some(market.token.clone());
+
+    let user = funded_user(&env, &market, 20_000);
+    market.client.deposit_collateral(&user, &asset, &10_000);
+    market.client.borrow_asset(&user, &asset, &1_500);
+    market.client.repay_debt(&user, &asset, &1_000);
+    assert_eq!(market.client.get_asset_totals(&market.token).borrowed, 500);
+
+    market.client.borrow_asset(&user, &asset, &1_000);
+    assert_eq!(
+        market.client.get_asset_totals(&market.token).borrowed,
+        1_500
+    );
+}
+
+#[test]
+fn test_listed_asset_caps_update_asset_config() {
+    let env = Env::default();
+    let market = setup(&env);
+    list_token(&env, &market, true);
+
+    market
+        .client
+        .set_asset_caps(&market.admin, &market.token, &5_000, &2_000);
+    let config = market.client.get_asset_config(&Some(market.token.clone()));
+    assert_eq!((config.max_supply, config.max_borrow), (5_000, 2_000));
+    assert_eq!(market.client.get_asset_params(&market.token), None);
+
+    let user = funded_user(&env, &market, 10_000);
+    let asset = Some(market.token.clone());
+    market.client.deposit_collateral(&user, &asset, &5_000);
+    assert!(market
+        .client
+        .try_deposit_collateral(&user, &asset, &1)
+        .is_err());
+}
+
+#[test]
+fn test_listed_asset_config_governs_core_flows() {
+    let env = Env::default();
+    let market = setup(&env);
+    list_token(&env, &market, false);
+    let asset = Some(market.token.clone());
+
+    let user = funded_user(&env, &market, 10_000);
+    market.client.deposit_collateral(&user, &asset, &10_000);
+    assert!(market.client.try_borrow_asset(&user, &asset, &100).is_err());
+}
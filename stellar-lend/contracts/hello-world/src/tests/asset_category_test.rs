@@ -0,0 +1,157 @@
+//! # Asset Category Tests
+//!
+//! Tests that listed assets can be tagged with an issuer and category, and
+//! that supply and borrow value is aggregated per category in the protocol
+//! metrics.
+
+use crate::cross_asset::{
+    AssetCategory, AssetConfig, AssetMetadata, CategoryExposure, CrossAssetError,
+};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    (admin, client)
+}
+
+fn list_asset(
+    env: &Env,
+    client: &HelloContractClient<'_>,
+    admin: &Address,
+    price: i128,
+) -> Address {
+    let asset = create_token(env);
+    client.initialize_asset(
+        admin,
+        &Some(asset.clone()),
+        &AssetConfig {
+            asset: Some(asset.clone()),
+            collateral_factor: 7500,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    asset
+}
+
+#[test]
+fn test_exposure_aggregated_per_category() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin, 10_000_000);
+    let eurc = list_asset(&env, &client, &admin, 11_000_000);
+    let xlm = list_asset(&env, &client, &admin, 2_000_000);
+    let issuer = Address::generate(&env);
+    for asset in [&usdc, &eurc] {
+        client.set_asset_metadata(
+            &admin,
+            &Some(asset.clone()),
+            &Some(AssetMetadata {
+                category: AssetCategory::Stablecoin,
+                issuer: Some(issuer.clone()),
+            }),
+        );
+    }
+
+    let supplier = Address::generate(&env);
+    client.ca_deposit_collateral(&supplier, &Some(usdc.clone()), &1_000);
+    client.ca_deposit_collateral(&supplier, &Some(eurc.clone()), &1_000);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &5_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &400);
+
+    let exposure = client.get_category_exposure();
+    assert_eq!(exposure.len(), 2);
+    assert_eq!(
+        exposure.get(AssetCategory::Stablecoin),
+        Some(CategoryExposure {
+            assets: 2,
+            supplied_value: 2_100,
+            borrowed_value: 400,
+            backed_debt_value: 0,
+        })
+    );
+    // XLM is untagged
+    assert_eq!(
+        exposure.get(AssetCategory::Other),
+        Some(CategoryExposure {
+            assets: 1,
+            supplied_value: 1_000,
+            borrowed_value: 0,
+            backed_debt_value: 0,
+        })
+    );
+    assert_eq!(
+        client.get_protocol_report().metrics.category_exposure,
+        exposure
+    );
+}
+
+#[test]
+fn test_metadata_can_be_retagged_and_cleared() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let steth = list_asset(&env, &client, &admin, 10_000_000);
+    assert_eq!(client.get_asset_metadata(&Some(steth.clone())), None);
+
+    let metadata = AssetMetadata {
+        category: AssetCategory::LiquidStaking,
+        issuer: None,
+    };
+    client.set_asset_metadata(&admin, &Some(steth.clone()), &Some(metadata.clone()));
+    assert_eq!(
+        client.get_asset_metadata(&Some(steth.clone())),
+        Some(metadata)
+    );
+    assert!(client
+        .get_category_exposure()
+        .contains_key(AssetCategory::LiquidStaking));
+
+    client.set_asset_metadata(&admin, &Some(steth.clone()), &None);
+    assert_eq!(client.get_asset_metadata(&Some(steth)), None);
+    assert!(client
+        .get_category_exposure()
+        .contains_key(AssetCategory::Other));
+}
+
+#[test]
+fn test_set_metadata_rejections() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let asset = list_asset(&env, &client, &admin, 10_000_000);
+    let metadata = Some(AssetMetadata {
+        category: AssetCategory::RealWorldAsset,
+        issuer: None,
+    });
+
+    let result = client.try_set_asset_metadata(&Address::generate(&env), &Some(asset), &metadata);
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = client.try_set_asset_metadata(&admin, &Some(create_token(&env)), &metadata);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+}
+
+#[test]
+fn test_exposure_overflow_is_an_error() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let asset = list_asset(&env, &client, &admin, i128::MAX / 100);
+    client.ca_deposit_collateral(&Address::generate(&env), &Some(asset), &1_000);
+
+    assert_eq!(
+        client.try_get_category_exposure(),
+        Err(Ok(CrossAssetError::Overflow))
+    );
+}
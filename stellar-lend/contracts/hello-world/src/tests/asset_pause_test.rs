@@ -0,0 +1,184 @@
+//! # Per-Asset Pause Tests
+//!
+//! Tests for pausing one operation for one asset while the rest of the
+//! protocol keeps running.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::roles::Role;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Symbol};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    (admin, client)
+}
+
+fn list_asset(env: &Env, client: &HelloContractClient, admin: &Address) -> Option<Address> {
+    let asset = Some(create_token(env));
+    client.initialize_asset(
+        admin,
+        &asset,
+        &AssetConfig {
+            asset: asset.clone(),
+            collateral_factor: 7500,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    asset
+}
+
+#[test]
+fn test_paused_borrow_leaves_deposits_open() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let volatile = list_asset(&env, &client, &admin);
+    let stable = list_asset(&env, &client, &admin);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &stable, &1_000);
+
+    let pause_borrow = Symbol::new(&env, "pause_borrow");
+    client.set_asset_pause(&admin, &volatile, &pause_borrow, &true);
+    assert_eq!(
+        client.get_asset_pauses(&volatile).get(pause_borrow.clone()),
+        Some(true)
+    );
+
+    let result = client.try_ca_borrow_asset(&user, &volatile, &100);
+    assert_eq!(result.map(|_| ()), Err(Ok(CrossAssetError::AssetPaused)));
+    assert_eq!(
+        client
+            .ca_deposit_collateral(&user, &volatile, &500)
+            .collateral,
+        500
+    );
+    assert_eq!(
+        client.ca_borrow_asset(&user, &stable, &100).debt_principal,
+        100
+    );
+
+    client.set_asset_pause(&admin, &volatile, &pause_borrow, &false);
+    assert!(client.get_asset_pauses(&volatile).is_empty());
+    assert_eq!(
+        client
+            .ca_borrow_asset(&user, &volatile, &100)
+            .debt_principal,
+        100
+    );
+}
+
+#[test]
+fn test_each_operation_checks_its_switch() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin);
+    let xlm = list_asset(&env, &client, &admin);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &xlm, &1_000);
+    client.ca_borrow_asset(&user, &usdc, &100);
+
+    let paused = Err(Ok(CrossAssetError::AssetPaused));
+    for name in ["pause_deposit", "pause_withdraw"] {
+        client.set_asset_pause(&admin, &xlm, &Symbol::new(&env, name), &true);
+    }
+    for name in ["pause_repay", "pause_liquidate"] {
+        client.set_asset_pause(&admin, &usdc, &Symbol::new(&env, name), &true);
+    }
+
+    assert_eq!(
+        client
+            .try_ca_deposit_collateral(&user, &xlm, &100)
+            .map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_ca_withdraw_collateral(&user, &xlm, &100)
+            .map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client.try_ca_repay_debt(&user, &usdc, &50).map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_ca_liquidate(
+                &Address::generate(&env),
+                &user,
+                &usdc,
+                &vec![&env, (xlm.clone(), 50)],
+            )
+            .map(|_| ()),
+        paused
+    );
+}
+
+#[test]
+fn test_set_asset_pause_requires_guardian() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin);
+    let pause_borrow = Symbol::new(&env, "pause_borrow");
+
+    let risk_admin = Address::generate(&env);
+    client.grant_role(&admin, &Role::RiskAdmin, &risk_admin);
+    let result = client.try_set_asset_pause(&risk_admin, &usdc, &pause_borrow, &true);
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+
+    let guardian = Address::generate(&env);
+    client.grant_role(&admin, &Role::PauseGuardian, &guardian);
+    client.set_asset_pause(&guardian, &usdc, &pause_borrow, &true);
+    assert_eq!(client.get_asset_pauses(&usdc).get(pause_borrow), Some(true));
+}
+
+#[test]
+fn test_combined_pause_state() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin);
+    let xlm = list_asset(&env, &client, &admin);
+    let pause_borrow = Symbol::new(&env, "pause_borrow");
+    let pause_deposit = Symbol::new(&env, "pause_deposit");
+
+    client.set_asset_pause(&admin, &usdc, &pause_borrow, &true);
+    assert!(client.is_asset_operation_paused(&usdc, &pause_borrow));
+    assert!(!client.is_asset_operation_paused(&usdc, &pause_deposit));
+    assert!(!client.is_asset_operation_paused(&xlm, &pause_borrow));
+
+    // The emergency pause blocks every operation of every asset
+    client.set_emergency_pause(&admin, &true);
+    assert!(client.is_asset_operation_paused(&xlm, &pause_deposit));
+    client.set_emergency_pause(&admin, &false);
+    assert!(!client.is_asset_operation_paused(&xlm, &pause_deposit));
+
+    // Delisting freezes deposits and borrows but not withdrawals
+    client.delist_asset(&admin, &xlm);
+    assert!(client.is_asset_operation_paused(&xlm, &pause_deposit));
+    assert!(!client.is_asset_operation_paused(&xlm, &Symbol::new(&env, "pause_withdraw")));
+
+    assert!(!client.is_asset_operation_paused(&usdc, &Symbol::new(&env, "pause_flash")));
+}
+
+#[test]
+fn test_unknown_operation_is_rejected() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin);
+
+    let result = client.try_set_asset_pause(&admin, &usdc, &Symbol::new(&env, "borrow"), &true);
+    assert_eq!(result, Err(Ok(CrossAssetError::UnknownOperation)));
+    assert!(client.get_asset_pauses(&usdc).is_empty());
+}
@@ -0,0 +1,179 @@
+//! # Asset Registry Tests
+//!
+//! Tests for supported-asset enumeration and for delisting, which freezes
+//! new deposits and borrows while positions can still be unwound.
+
+use crate::cross_asset::{AssetConfig, AssetKey, CrossAssetError};
+use crate::lending_pool::PoolError;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, Vec,
+};
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 7500,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// Lists `count` tokens at a price of 1.0
+fn setup(env: &Env, count: u32) -> (Address, HelloContractClient<'_>, Address, Vec<Address>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let mut assets = Vec::new(env);
+    for _ in 0..count {
+        let asset = create_token(env);
+        client.initialize_asset(&admin, &Some(asset.clone()), &asset_config(env, &asset));
+        assets.push_back(asset);
+    }
+    (contract_id, client, admin, assets)
+}
+
+/// Mints `amount` of `asset` to a new user and approves the contract
+fn funded_user(env: &Env, contract_id: &Address, asset: &Address, amount: i128) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, asset).mint(&user, &amount);
+    TokenClient::new(env, asset).approve(
+        &user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    user
+}
+
+#[test]
+fn test_supported_assets_are_paged_in_listing_order() {
+    let env = Env::default();
+    let (_, client, _, assets) = setup(&env, 3);
+    let key = |i| AssetKey::Token(assets.get(i).unwrap());
+
+    assert_eq!(
+        client.get_supported_assets(&2, &0),
+        vec![&env, key(0), key(1)]
+    );
+    assert_eq!(client.get_supported_assets(&2, &2), vec![&env, key(2)]);
+    assert!(client.get_supported_assets(&2, &3).is_empty());
+
+    for limit in [0, 51] {
+        let result = client.try_get_supported_assets(&limit, &0);
+        assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    }
+}
+
+#[test]
+fn test_delisting_freezes_new_activity_but_allows_unwinding() {
+    let env = Env::default();
+    let (contract_id, client, admin, assets) = setup(&env, 2);
+    let usdc = Some(assets.get(0).unwrap());
+    let xlm = Some(assets.get(1).unwrap());
+
+    let supplier = funded_user(&env, &contract_id, &assets.get(1).unwrap(), 10_000);
+    client.supply(&supplier, &xlm, &10_000);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &usdc, &10_000);
+    client.ca_borrow_asset(&borrower, &xlm, &2_000);
+
+    client.delist_asset(&admin, &xlm);
+    assert!(client.is_asset_delisted(&xlm));
+    assert!(!client.is_asset_delisted(&usdc));
+    assert_eq!(
+        client.get_supported_assets(&10, &0),
+        vec![&env, AssetKey::Token(assets.get(0).unwrap())]
+    );
+    // Delisting does not unlist: the asset keeps its configuration
+    assert_eq!(client.get_asset_list().len(), 2);
+    assert_eq!(client.get_asset_config(&xlm).collateral_factor, 7500);
+    let data = client.get_reserve_data(&xlm);
+    assert!(data.delisted && data.deposit_paused && data.borrow_paused);
+    assert!(!data.withdraw_paused && !data.repay_paused && !data.liquidate_paused);
+
+    let result = client.try_ca_borrow_asset(&borrower, &xlm, &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetDelisted)));
+    let result = client.try_ca_deposit_collateral(&borrower, &xlm, &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetDelisted)));
+    let result = client.try_supply(&supplier, &xlm, &1);
+    assert_eq!(result, Err(Ok(PoolError::AssetDelisted)));
+
+    // Repayments and withdrawals continue
+    client.ca_repay_debt(&borrower, &xlm, &2_000);
+    client.delist_asset(&admin, &usdc);
+    client.ca_withdraw_collateral(&borrower, &usdc, &10_000);
+    client.redeem(&supplier, &xlm, &10_000);
+    assert!(client.get_supported_assets(&10, &0).is_empty());
+}
+
+#[test]
+fn test_listing_again_relists_a_delisted_asset() {
+    let env = Env::default();
+    let (_, client, admin, assets) = setup(&env, 1);
+    let asset = Some(assets.get(0).unwrap());
+    client.delist_asset(&admin, &asset);
+
+    client.initialize_asset(&admin, &asset, &asset_config(&env, &assets.get(0).unwrap()));
+    assert!(!client.is_asset_delisted(&asset));
+    assert_eq!(client.get_supported_assets(&10, &0).len(), 1);
+    client.ca_deposit_collateral(&Address::generate(&env), &asset, &1_000);
+}
+
+#[test]
+#[should_panic(expected = "Deposit error: AssetNotEnabled")]
+fn test_core_deposit_of_delisted_asset_fails() {
+    let env = Env::default();
+    let (contract_id, client, admin, assets) = setup(&env, 1);
+    let asset = Some(assets.get(0).unwrap());
+    let user = funded_user(&env, &contract_id, &assets.get(0).unwrap(), 10_000);
+    client.deposit_collateral(&user, &asset, &5_000);
+
+    client.delist_asset(&admin, &asset);
+    client.withdraw_collateral(&user, &asset, &1_000);
+    client.deposit_collateral(&user, &asset, &1_000);
+}
+
+#[test]
+#[should_panic(expected = "Borrow error: AssetNotEnabled")]
+fn test_core_borrow_of_delisted_asset_fails() {
+    let env = Env::default();
+    let (contract_id, client, admin, assets) = setup(&env, 1);
+    let asset = Some(assets.get(0).unwrap());
+    let user = funded_user(&env, &contract_id, &assets.get(0).unwrap(), 10_000);
+    client.deposit_collateral(&user, &asset, &5_000);
+    client.borrow_asset(&user, &asset, &1_000);
+
+    client.delist_asset(&admin, &asset);
+    client.repay_debt(&user, &asset, &500);
+    client.borrow_asset(&user, &asset, &1_000);
+}
+
+#[test]
+fn test_delist_asset_rejections() {
+    let env = Env::default();
+    let (_, client, admin, assets) = setup(&env, 1);
+    let asset = Some(assets.get(0).unwrap());
+
+    let result = client.try_delist_asset(&Address::generate(&env), &asset);
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = client.try_delist_asset(&admin, &Some(Address::generate(&env)));
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+
+    client.delist_asset(&admin, &asset);
+    let result = client.try_delist_asset(&admin, &asset);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetDelisted)));
+}
@@ -0,0 +1,237 @@
+//! # Bad Debt Tests
+//!
+//! Tests for debt left behind when a liquidation seizes all of a borrower's
+//! collateral, and for writing it off against reserves or suppliers.
+
+use crate::cross_asset::AssetConfig;
+use crate::lending_pool::{BadDebtWriteOff, PoolError};
+use crate::tests::lending_pool_test::repay_core_interest;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+}
+
+/// Registers USDC and XLM at a price of 1.0 with a 75% collateral factor
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (contract_id, client, market)
+}
+
+/// Borrower with 1,100 XLM of collateral and 800 USDC of debt
+fn open_position(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &market.xlm, &1_100);
+    client.ca_borrow_asset(&borrower, &market.usdc, &800);
+    borrower
+}
+
+/// Liquidator holding 1,000 USDC of collateral to repay with
+fn liquidator(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let liquidator = Address::generate(env);
+    client.ca_deposit_collateral(&liquidator, &market.usdc, &1_000);
+    liquidator
+}
+
+/// Drops XLM to 0.25 and liquidates 250 USDC of the borrower's debt, which
+/// seizes all 1,100 XLM of collateral
+fn liquidate_all_collateral(
+    env: &Env,
+    client: &HelloContractClient,
+    market: &Market,
+    borrower: &Address,
+) {
+    client.update_asset_price(&market.admin, &market.usdc, &10_000_000);
+    client.update_asset_price(&market.admin, &market.xlm, &2_500_000);
+    client.ca_liquidate(
+        &liquidator(env, client, market),
+        borrower,
+        &market.usdc,
+        &vec![env, (market.xlm.clone(), 250)],
+    );
+}
+
+/// A supplier of `amount` USDC to the pool
+fn supplier(
+    env: &Env,
+    contract_id: &Address,
+    client: &HelloContractClient,
+    market: &Market,
+    amount: i128,
+) -> Address {
+    let usdc = market.usdc.clone().unwrap();
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &usdc).mint(&user, &amount);
+    TokenClient::new(env, &usdc).approve(
+        &user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&user, &market.usdc, &amount);
+    user
+}
+
+#[test]
+fn test_liquidation_without_collateral_records_bad_debt() {
+    let env = Env::default();
+    let (_contract_id, client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+
+    liquidate_all_collateral(&env, &client, &market, &borrower);
+
+    assert_eq!(client.get_bad_debt(&market.usdc), 550);
+    assert_eq!(client.get_bad_debt(&market.xlm), 0);
+    let position = client.get_user_asset_position(&borrower, &market.usdc);
+    assert_eq!(position.debt_principal, 0);
+    assert_eq!(position.accrued_interest, 0);
+    assert_eq!(
+        client
+            .get_user_asset_position(&borrower, &market.xlm)
+            .collateral,
+        0
+    );
+}
+
+#[test]
+fn test_partial_liquidation_records_no_bad_debt() {
+    let env = Env::default();
+    let (_contract_id, client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    client.update_asset_price(&market.admin, &market.xlm, &2_500_000);
+
+    client.ca_liquidate(
+        &liquidator(&env, &client, &market),
+        &borrower,
+        &market.usdc,
+        &vec![&env, (market.xlm.clone(), 100)],
+    );
+
+    assert_eq!(client.get_bad_debt(&market.usdc), 0);
+    let position = client.get_user_asset_position(&borrower, &market.usdc);
+    assert_eq!(position.debt_principal, 700);
+}
+
+#[test]
+fn test_write_off_against_reserves() {
+    let env = Env::default();
+    let (_contract_id, client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+
+    // With no suppliers, interest repaid in USDC goes to reserves
+    repay_core_interest(&env, &client, market.usdc.as_ref().unwrap(), 100);
+    liquidate_all_collateral(&env, &client, &market, &borrower);
+    assert_eq!(client.get_reserves(&market.usdc), 100);
+    assert_eq!(client.get_bad_debt(&market.usdc), 550);
+
+    let result = client.try_write_off_bad_debt(
+        &market.admin,
+        &market.usdc,
+        &101,
+        &BadDebtWriteOff::Reserves,
+    );
+    assert_eq!(result, Err(Ok(PoolError::InsufficientReserves)));
+
+    client.write_off_bad_debt(
+        &market.admin,
+        &market.usdc,
+        &100,
+        &BadDebtWriteOff::Reserves,
+    );
+    assert_eq!(client.get_reserves(&market.usdc), 0);
+    assert_eq!(client.get_bad_debt(&market.usdc), 450);
+}
+
+#[test]
+fn test_write_off_socialized_across_suppliers() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let first = supplier(&env, &contract_id, &client, &market, 5_000);
+    let second = supplier(&env, &contract_id, &client, &market, 5_000);
+    let borrower = open_position(&env, &client, &market);
+    liquidate_all_collateral(&env, &client, &market, &borrower);
+
+    client.write_off_bad_debt(
+        &market.admin,
+        &market.usdc,
+        &550,
+        &BadDebtWriteOff::Suppliers,
+    );
+
+    assert_eq!(client.get_bad_debt(&market.usdc), 0);
+    assert_eq!(client.get_pool_state(&market.usdc).total_liquidity, 9_450);
+    assert_eq!(client.get_supply_balance(&first, &market.usdc), 4_725);
+    assert_eq!(client.get_supply_balance(&second, &market.usdc), 4_725);
+}
+
+#[test]
+fn test_write_off_errors() {
+    let env = Env::default();
+    let (_contract_id, client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    liquidate_all_collateral(&env, &client, &market, &borrower);
+
+    let result = client.try_write_off_bad_debt(
+        &Address::generate(&env),
+        &market.usdc,
+        &100,
+        &BadDebtWriteOff::Reserves,
+    );
+    assert_eq!(result, Err(Ok(PoolError::Unauthorized)));
+    let result =
+        client.try_write_off_bad_debt(&market.admin, &market.usdc, &0, &BadDebtWriteOff::Reserves);
+    assert_eq!(result, Err(Ok(PoolError::InvalidAmount)));
+    let result = client.try_write_off_bad_debt(
+        &market.admin,
+        &market.usdc,
+        &551,
+        &BadDebtWriteOff::Reserves,
+    );
+    assert_eq!(result, Err(Ok(PoolError::ExceedsBadDebt)));
+
+    // The pool has no liquidity to take the loss from
+    let result = client.try_write_off_bad_debt(
+        &market.admin,
+        &market.usdc,
+        &550,
+        &BadDebtWriteOff::Suppliers,
+    );
+    assert_eq!(result, Err(Ok(PoolError::InvalidAmount)));
+}
@@ -0,0 +1,170 @@
+//! # Batch Operation Tests
+//!
+//! Tests that `batch` runs cross-asset operations in order, checks the health
+//! factor only after the last one, and rolls back every operation when any
+//! step or the final check fails.
+
+use crate::batch::Operation;
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+/// Registers the contract with USDC and XLM cross-asset markets at a price of
+/// 1.0 and a 75% collateral factor
+fn setup(env: &Env) -> (HelloContractClient<'_>, Option<Address>, Option<Address>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = Some(create_token(env));
+    let xlm = Some(create_token(env));
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, usdc, xlm)
+}
+
+#[test]
+fn test_batch_borrows_before_depositing() {
+    let env = Env::default();
+    let (client, usdc, xlm) = setup(&env);
+    let user = Address::generate(&env);
+
+    // A borrow with no collateral yet would fail on its own
+    let summary = client.batch(
+        &user,
+        &vec![
+            &env,
+            Operation::Borrow(usdc.clone(), 500),
+            Operation::Deposit(xlm.clone(), 1_000),
+        ],
+    );
+
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        event.1,
+        vec![
+            &env,
+            Symbol::new(&env, "batch_executed_event").into_val(&env)
+        ]
+    );
+    assert_eq!(summary.total_debt_value, 500);
+    assert_eq!(summary.total_collateral_value, 1_000);
+    assert_eq!(summary, client.get_user_position_summary(&user));
+}
+
+#[test]
+fn test_batch_deleverages_through_an_unhealthy_step() {
+    let env = Env::default();
+    let (client, usdc, xlm) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &xlm, &1_000);
+    client.ca_borrow_asset(&user, &usdc, &700);
+    assert_eq!(
+        client.try_ca_withdraw_collateral(&user, &xlm, &500),
+        Err(Ok(CrossAssetError::UnhealthyPosition))
+    );
+
+    client.batch(
+        &user,
+        &vec![
+            &env,
+            Operation::Withdraw(xlm.clone(), 500),
+            Operation::Repay(usdc.clone(), 400),
+        ],
+    );
+
+    assert_eq!(client.get_user_asset_position(&user, &xlm).collateral, 500);
+    assert_eq!(
+        client.get_user_asset_position(&user, &usdc).debt_principal,
+        300
+    );
+
+    // The deferral ends with the batch
+    assert_eq!(
+        client.try_ca_withdraw_collateral(&user, &xlm, &500),
+        Err(Ok(CrossAssetError::UnhealthyPosition))
+    );
+}
+
+#[test]
+fn test_failed_batch_rolls_back_every_operation() {
+    let env = Env::default();
+    let (client, usdc, xlm) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &xlm, &1_000);
+    let before = client.get_user_position_summary(&user);
+
+    // Unhealthy at the end
+    assert_eq!(
+        client.try_batch(
+            &user,
+            &vec![
+                &env,
+                Operation::Borrow(usdc.clone(), 700),
+                Operation::Withdraw(xlm.clone(), 500),
+            ],
+        ),
+        Err(Ok(CrossAssetError::UnhealthyPosition))
+    );
+    // A failing step
+    assert_eq!(
+        client.try_batch(
+            &user,
+            &vec![
+                &env,
+                Operation::Borrow(usdc.clone(), 100),
+                Operation::Withdraw(xlm.clone(), 2_000),
+            ],
+        ),
+        Err(Ok(CrossAssetError::InsufficientCollateral))
+    );
+
+    assert_eq!(client.get_user_position_summary(&user), before);
+}
+
+#[test]
+fn test_batch_size_is_bounded() {
+    let env = Env::default();
+    let (client, _, xlm) = setup(&env);
+    let user = Address::generate(&env);
+
+    assert_eq!(
+        client.try_batch(&user, &Vec::new(&env)),
+        Err(Ok(CrossAssetError::InvalidBatch))
+    );
+    let mut operations = Vec::new(&env);
+    for _ in 0..11 {
+        operations.push_back(Operation::Deposit(xlm.clone(), 1));
+    }
+    assert_eq!(
+        client.try_batch(&user, &operations),
+        Err(Ok(CrossAssetError::InvalidBatch))
+    );
+
+    operations.pop_back();
+    client.batch(&user, &operations);
+    assert_eq!(client.get_user_asset_position(&user, &xlm).collateral, 10);
+}
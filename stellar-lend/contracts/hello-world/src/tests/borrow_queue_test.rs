@@ -0,0 +1,225 @@
+//! # Borrow Queue Tests
+//!
+//! Tests for market utilization caps and the queue of borrows waiting for
+//! liquidity under them.
+
+use crate::borrow_queue::{BorrowQueueError, MAX_BORROW_REQUEST_TTL};
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const ONE_DAY: u64 = 86_400;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    lender: Address,
+}
+
+/// Registers USDC and XLM at a price of 1.0, supplies 1,000 USDC and caps
+/// USDC utilization at 50%
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+        lender: Address::generate(env),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.ca_deposit_collateral(&market.lender, &market.usdc, &1_000);
+    client.set_utilization_cap(&market.usdc, &5000);
+    (client, market)
+}
+
+/// A borrower with 10,000 XLM of collateral
+fn borrower(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &market.xlm, &10_000);
+    borrower
+}
+
+fn debt(client: &HelloContractClient, user: &Address, asset: &Option<Address>) -> i128 {
+    client.get_user_asset_position(user, asset).debt_principal
+}
+
+#[test]
+fn test_borrow_above_utilization_cap_rejected() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = borrower(&env, &client, &market);
+
+    assert_eq!(client.get_utilization_cap(&market.usdc), Some(5000));
+    let result = client.try_ca_borrow_asset(&borrower, &market.usdc, &501);
+    assert_eq!(result, Err(Ok(CrossAssetError::UtilizationCapExceeded)));
+
+    client.ca_borrow_asset(&borrower, &market.usdc, &500);
+    assert_eq!(debt(&client, &borrower, &market.usdc), 500);
+}
+
+#[test]
+fn test_queued_borrow_filled_when_liquidity_added() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = borrower(&env, &client, &market);
+
+    let id = client.ca_request_borrow(&borrower, &market.usdc, &600, &ONE_DAY);
+    assert_eq!(client.ca_process_borrow_queue(&market.usdc), 0);
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 1);
+    assert_eq!(client.get_borrow_queue(&market.usdc).get(0).unwrap().id, id);
+
+    // 600 / 1,200 is exactly 50%
+    client.ca_deposit_collateral(&market.lender, &market.usdc, &200);
+
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 0);
+    assert_eq!(debt(&client, &borrower, &market.usdc), 600);
+}
+
+#[test]
+fn test_queued_borrow_filled_when_debt_repaid() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let first = borrower(&env, &client, &market);
+    let second = borrower(&env, &client, &market);
+    client.ca_borrow_asset(&first, &market.usdc, &500);
+
+    client.ca_request_borrow(&second, &market.usdc, &200, &ONE_DAY);
+    client.ca_repay_debt(&first, &market.usdc, &200);
+
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 0);
+    assert_eq!(debt(&client, &second, &market.usdc), 200);
+}
+
+#[test]
+fn test_queue_filled_in_order() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let large = borrower(&env, &client, &market);
+    let small = borrower(&env, &client, &market);
+
+    client.ca_request_borrow(&large, &market.usdc, &800, &ONE_DAY);
+    client.ca_request_borrow(&small, &market.usdc, &100, &ONE_DAY);
+
+    // The small request fits but waits behind the large one
+    assert_eq!(client.ca_process_borrow_queue(&market.usdc), 0);
+    assert_eq!(debt(&client, &small, &market.usdc), 0);
+
+    client.ca_deposit_collateral(&market.lender, &market.usdc, &800);
+
+    assert_eq!(debt(&client, &large, &market.usdc), 800);
+    assert_eq!(debt(&client, &small, &market.usdc), 100);
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 0);
+}
+
+#[test]
+fn test_expired_request_dropped() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = borrower(&env, &client, &market);
+    client.ca_request_borrow(&borrower, &market.usdc, &600, &ONE_DAY);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY + 1);
+    client.update_asset_price(&market.admin, &market.usdc, &10_000_000);
+    client.update_asset_price(&market.admin, &market.xlm, &10_000_000);
+    client.ca_deposit_collateral(&market.lender, &market.usdc, &1_000);
+
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 0);
+    assert_eq!(debt(&client, &borrower, &market.usdc), 0);
+}
+
+#[test]
+fn test_unhealthy_request_rejected() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &market.xlm, &100);
+
+    client.ca_request_borrow(&borrower, &market.usdc, &600, &ONE_DAY);
+    client.ca_deposit_collateral(&market.lender, &market.usdc, &1_000);
+
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 0);
+    assert_eq!(debt(&client, &borrower, &market.usdc), 0);
+}
+
+#[test]
+fn test_cancel_borrow_request() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = borrower(&env, &client, &market);
+    let id = client.ca_request_borrow(&borrower, &market.usdc, &600, &ONE_DAY);
+
+    let result = client.try_ca_cancel_borrow_request(&Address::generate(&env), &market.usdc, &id);
+    assert_eq!(result, Err(Ok(BorrowQueueError::RequestNotFound)));
+
+    client.ca_cancel_borrow_request(&borrower, &market.usdc, &id);
+    assert_eq!(client.get_borrow_queue(&market.usdc).len(), 0);
+
+    let result = client.try_ca_cancel_borrow_request(&borrower, &market.usdc, &id);
+    assert_eq!(result, Err(Ok(BorrowQueueError::RequestNotFound)));
+}
+
+#[test]
+fn test_invalid_requests_rejected() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = borrower(&env, &client, &market);
+
+    let result = client.try_ca_request_borrow(&borrower, &market.usdc, &0, &ONE_DAY);
+    assert_eq!(result, Err(Ok(BorrowQueueError::InvalidAmount)));
+    let result = client.try_ca_request_borrow(&borrower, &market.usdc, &100, &0);
+    assert_eq!(result, Err(Ok(BorrowQueueError::InvalidExpiry)));
+    let result =
+        client.try_ca_request_borrow(&borrower, &market.usdc, &100, &(MAX_BORROW_REQUEST_TTL + 1));
+    assert_eq!(result, Err(Ok(BorrowQueueError::InvalidExpiry)));
+    let result =
+        client.try_ca_request_borrow(&borrower, &Some(Address::generate(&env)), &100, &ONE_DAY);
+    assert_eq!(result, Err(Ok(BorrowQueueError::AssetNotConfigured)));
+}
+
+#[test]
+fn test_invalid_utilization_cap_rejected() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+
+    let result = client.try_set_utilization_cap(&market.usdc, &0);
+    assert_eq!(result, Err(Ok(BorrowQueueError::InvalidCap)));
+    let result = client.try_set_utilization_cap(&market.usdc, &10_001);
+    assert_eq!(result, Err(Ok(BorrowQueueError::InvalidCap)));
+    assert_eq!(client.get_utilization_cap(&market.xlm), None);
+
+    client.set_utilization_cap(&market.usdc, &10_000);
+    assert_eq!(client.get_utilization_cap(&market.usdc), Some(10_000));
+}
@@ -0,0 +1,158 @@
+//! # Borrow Size Cap Tests
+//!
+//! Tests for the per-ledger cap on borrows as a share of a market's available
+//! liquidity.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::roles::Role;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 7500,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// Registers USDC and XLM at a price of 1.0 with 10,000 USDC supplied
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(env, &usdc));
+    client.initialize_asset(&admin, &Some(xlm.clone()), &asset_config(env, &xlm));
+    let market = Market {
+        admin,
+        usdc: Some(usdc),
+        xlm: Some(xlm),
+    };
+    client.ca_deposit_collateral(&Address::generate(env), &market.usdc, &10_000);
+    (client, market)
+}
+
+/// A user with 100,000 XLM of collateral
+fn borrower(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let user = Address::generate(env);
+    client.ca_deposit_collateral(&user, &market.xlm, &100_000);
+    user
+}
+
+fn next_ledger(env: &Env) {
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+}
+
+#[test]
+fn test_borrow_above_cap_rejected() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let user = borrower(&env, &client, &market);
+    client.set_max_borrow_size(&market.admin, &market.usdc, &1000);
+    assert_eq!(client.get_max_borrow_size(&market.usdc), Some(1000));
+
+    // 10% of 10,000 available
+    assert_eq!(
+        client.try_ca_borrow_asset(&user, &market.usdc, &1_001),
+        Err(Ok(CrossAssetError::BorrowSizeExceeded))
+    );
+    client.ca_borrow_asset(&user, &market.usdc, &1_000);
+}
+
+#[test]
+fn test_borrows_in_one_ledger_share_allowance() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.set_max_borrow_size(&market.admin, &market.usdc, &1000);
+
+    let first = borrower(&env, &client, &market);
+    let second = borrower(&env, &client, &market);
+    client.ca_borrow_asset(&first, &market.usdc, &600);
+    assert_eq!(
+        client.try_ca_borrow_asset(&second, &market.usdc, &401),
+        Err(Ok(CrossAssetError::BorrowSizeExceeded))
+    );
+    client.ca_borrow_asset(&second, &market.usdc, &400);
+
+    // A new ledger gets a fresh allowance: 10% of the 9,000 left
+    next_ledger(&env);
+    assert_eq!(
+        client.try_ca_borrow_asset(&first, &market.usdc, &901),
+        Err(Ok(CrossAssetError::BorrowSizeExceeded))
+    );
+    client.ca_borrow_asset(&first, &market.usdc, &900);
+}
+
+#[test]
+fn test_failed_borrow_uses_no_allowance() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.set_max_borrow_size(&market.admin, &market.usdc, &1000);
+
+    // Unhealthy: no collateral
+    let unbacked = Address::generate(&env);
+    assert_eq!(
+        client.try_ca_borrow_asset(&unbacked, &market.usdc, &1_000),
+        Err(Ok(CrossAssetError::ExceedsBorrowCapacity))
+    );
+    let user = borrower(&env, &client, &market);
+    client.ca_borrow_asset(&user, &market.usdc, &1_000);
+}
+
+#[test]
+fn test_remove_cap() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let user = borrower(&env, &client, &market);
+    client.set_max_borrow_size(&market.admin, &market.usdc, &1000);
+
+    client.set_max_borrow_size(&market.admin, &market.usdc, &0);
+    assert_eq!(client.get_max_borrow_size(&market.usdc), None);
+    client.ca_borrow_asset(&user, &market.usdc, &5_000);
+}
+
+#[test]
+fn test_set_max_borrow_size_validation() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+
+    assert_eq!(
+        client.try_set_max_borrow_size(&market.admin, &market.usdc, &10_001),
+        Err(Ok(CrossAssetError::AssetNotConfigured))
+    );
+    assert_eq!(
+        client.try_set_max_borrow_size(&market.admin, &Some(create_token(&env)), &1000),
+        Err(Ok(CrossAssetError::AssetNotConfigured))
+    );
+
+    let risk_admin = Address::generate(&env);
+    assert_eq!(
+        client.try_set_max_borrow_size(&risk_admin, &market.usdc, &1000),
+        Err(Ok(CrossAssetError::NotAuthorized))
+    );
+    client.grant_role(&market.admin, &Role::RiskAdmin, &risk_admin);
+    client.set_max_borrow_size(&risk_admin, &market.usdc, &1000);
+}
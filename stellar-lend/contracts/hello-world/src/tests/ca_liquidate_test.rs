@@ -0,0 +1,299 @@
+//! # Cross-Asset Liquidation Tests
+//!
+//! Tests for `ca_liquidate`, where the liquidator chooses which collateral
+//! assets to seize or splits the seizure across several of them.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+    eth: Address,
+}
+
+/// Registers USDC, XLM and ETH at a price of 1.0 with a 75% collateral factor
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: create_token(env),
+        xlm: create_token(env),
+        eth: create_token(env),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm, &market.eth] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, market)
+}
+
+/// Borrower with 1,000 XLM and 1,000 ETH of collateral and 1,400 USDC of debt
+fn open_position(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(market.xlm.clone()), &1_000);
+    client.ca_deposit_collateral(&borrower, &Some(market.eth.clone()), &1_000);
+    client.ca_borrow_asset(&borrower, &Some(market.usdc.clone()), &1_400);
+    borrower
+}
+
+/// Liquidator holding 1,000 USDC of collateral to repay with
+fn fund_liquidator(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let liquidator = Address::generate(env);
+    client.ca_deposit_collateral(&liquidator, &Some(market.usdc.clone()), &1_000);
+    liquidator
+}
+
+/// Halve the XLM price, leaving weighted collateral at 1,125 against 1,400 of debt
+fn crash_xlm(client: &HelloContractClient, market: &Market) {
+    client.update_asset_price(&market.admin, &Some(market.xlm.clone()), &5_000_000);
+}
+
+#[test]
+fn test_liquidator_chooses_collateral_asset() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    crash_xlm(&client, &market);
+    let liquidator = fund_liquidator(&env, &client, &market);
+
+    let result = client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![&env, (Some(market.eth.clone()), 500)],
+    );
+
+    // 500 USDC of debt plus the 10% incentive, paid entirely in ETH
+    assert_eq!(result.debt_repaid, 500);
+    assert_eq!(result.seized, vec![&env, (Some(market.eth.clone()), 550)]);
+
+    let eth = client.get_user_asset_position(&borrower, &Some(market.eth.clone()));
+    let xlm = client.get_user_asset_position(&borrower, &Some(market.xlm.clone()));
+    let usdc = client.get_user_asset_position(&borrower, &Some(market.usdc.clone()));
+    assert_eq!(eth.collateral, 450);
+    assert_eq!(xlm.collateral, 1_000);
+    assert_eq!(usdc.debt_principal, 900);
+
+    let received = client.get_user_asset_position(&liquidator, &Some(market.eth.clone()));
+    assert_eq!(received.collateral, 550);
+    let paid = client.get_user_asset_position(&liquidator, &Some(market.usdc.clone()));
+    assert_eq!(paid.collateral, 500);
+    assert_eq!(
+        result.health_factor,
+        client.get_user_position_summary(&borrower).health_factor
+    );
+}
+
+#[test]
+fn test_seizure_split_across_assets() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    crash_xlm(&client, &market);
+    let liquidator = fund_liquidator(&env, &client, &market);
+
+    let result = client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![
+            &env,
+            (Some(market.xlm.clone()), 200),
+            (Some(market.eth.clone()), 300),
+        ],
+    );
+
+    // XLM is at half price, so covering 200 USDC takes twice as many units
+    assert_eq!(result.debt_repaid, 500);
+    assert_eq!(
+        result.seized,
+        vec![
+            &env,
+            (Some(market.xlm.clone()), 440),
+            (Some(market.eth.clone()), 330),
+        ]
+    );
+    assert_eq!(
+        client
+            .get_user_asset_position(&borrower, &Some(market.xlm.clone()))
+            .collateral,
+        560
+    );
+    assert_eq!(
+        client
+            .get_user_asset_position(&borrower, &Some(market.eth.clone()))
+            .collateral,
+        670
+    );
+}
+
+#[test]
+fn test_liquidator_pays_with_debt_asset_collateral() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    crash_xlm(&client, &market);
+    let unfunded = Address::generate(&env);
+    let seizures = vec![&env, (Some(market.eth.clone()), 500)];
+
+    let result =
+        client.try_ca_liquidate(&unfunded, &borrower, &Some(market.usdc.clone()), &seizures);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+
+    let liquidator = fund_liquidator(&env, &client, &market);
+    let supply_before = client
+        .get_reserve_data(&Some(market.usdc.clone()))
+        .total_supplied;
+    client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &seizures,
+    );
+    assert_eq!(
+        client
+            .get_user_asset_position(&liquidator, &Some(market.usdc.clone()))
+            .collateral,
+        500
+    );
+    assert_eq!(
+        client
+            .get_reserve_data(&Some(market.usdc.clone()))
+            .total_supplied,
+        supply_before - 500
+    );
+}
+
+#[test]
+fn test_seizure_limited_by_available_collateral() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    crash_xlm(&client, &market);
+    let liquidator = Address::generate(&env);
+
+    // 600 USDC would need 1,320 XLM; the borrower only has 1,000
+    let result = client.try_ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![&env, (Some(market.xlm.clone()), 600)],
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+
+    // Nothing was written
+    assert_eq!(
+        client
+            .get_user_asset_position(&borrower, &Some(market.usdc.clone()))
+            .debt_principal,
+        1_400
+    );
+}
+
+#[test]
+fn test_savings_collateral_cannot_be_seized() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    client.ca_deposit_collateral(&borrower, &Some(market.usdc.clone()), &1_000);
+    client.set_use_as_collateral(&borrower, &Some(market.usdc.clone()), &false);
+    crash_xlm(&client, &market);
+
+    let result = client.try_ca_liquidate(
+        &Address::generate(&env),
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![&env, (Some(market.usdc.clone()), 100)],
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetDisabled)));
+}
+
+#[test]
+fn test_healthy_position_not_liquidatable() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+
+    let result = client.try_ca_liquidate(
+        &Address::generate(&env),
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![&env, (Some(market.eth.clone()), 100)],
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::NotLiquidatable)));
+}
+
+#[test]
+fn test_split_total_capped_by_close_factor() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    crash_xlm(&client, &market);
+
+    // Close factor is 50% of 1,400
+    let result = client.try_ca_liquidate(
+        &Address::generate(&env),
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![
+            &env,
+            (Some(market.xlm.clone()), 201),
+            (Some(market.eth.clone()), 500),
+        ],
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::ExceedsCloseFactor)));
+}
+
+#[test]
+fn test_invalid_seizures_rejected() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let borrower = open_position(&env, &client, &market);
+    crash_xlm(&client, &market);
+    let liquidator = Address::generate(&env);
+
+    let empty = client.try_ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![&env],
+    );
+    assert_eq!(empty, Err(Ok(CrossAssetError::InvalidAmount)));
+
+    let zero = client.try_ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![&env, (Some(market.eth.clone()), 0)],
+    );
+    assert_eq!(zero, Err(Ok(CrossAssetError::InvalidAmount)));
+}
@@ -0,0 +1,157 @@
+//! # Category Debt Ceiling Tests
+//!
+//! Tests that debt backed by collateral of a category is attributed pro rata
+//! to collateral value, tracked as positions change, and capped at the
+//! category's share of supplied value at borrow time.
+
+use crate::cross_asset::{AssetCategory, AssetConfig, AssetMetadata, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    Address, Env, IntoVal, Symbol,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    rwa: Option<Address>,
+    xlm: Option<Address>,
+}
+
+/// Lists USDC, a real-world asset and untagged XLM at a price of 1.0, and
+/// supplies 8,000 USDC of collateral from a lender
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: Some(create_token(env)),
+        rwa: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+        admin,
+    };
+    for asset in [&market.usdc, &market.rwa, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_asset_metadata(
+        &market.admin,
+        &market.rwa,
+        &Some(AssetMetadata {
+            category: AssetCategory::RealWorldAsset,
+            issuer: None,
+        }),
+    );
+    client.ca_deposit_collateral(&Address::generate(env), &market.usdc, &8_000);
+    (client, market)
+}
+
+fn backed_debt(client: &HelloContractClient<'_>, category: AssetCategory) -> i128 {
+    client
+        .get_category_exposure()
+        .get(category)
+        .map_or(0, |exposure| exposure.backed_debt_value)
+}
+
+#[test]
+fn test_borrow_above_ceiling_rejected() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.set_category_debt_ceiling(&market.admin, &AssetCategory::RealWorldAsset, &Some(1_000));
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &market.rwa, &2_000);
+
+    // 10% of 10,000 supplied
+    client.ca_borrow_asset(&borrower, &market.usdc, &900);
+    assert_eq!(backed_debt(&client, AssetCategory::RealWorldAsset), 900);
+    let result = client.try_ca_borrow_asset(&borrower, &market.usdc, &101);
+    assert_eq!(result, Err(Ok(CrossAssetError::CategoryCeilingExceeded)));
+    assert_eq!(backed_debt(&client, AssetCategory::RealWorldAsset), 900);
+
+    // Reaching the ceiling is allowed, and reported
+    client.ca_borrow_asset(&borrower, &market.usdc, &100);
+    let reached = env.events().all().iter().any(|(_, topics, _)| {
+        topics == (Symbol::new(&env, "category_ceiling_reached_event"),).into_val(&env)
+    });
+    assert!(reached);
+    assert_eq!(backed_debt(&client, AssetCategory::RealWorldAsset), 1_000);
+}
+
+#[test]
+fn test_debt_attributed_pro_rata_and_released_on_repay() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.set_category_debt_ceiling(&market.admin, &AssetCategory::RealWorldAsset, &Some(5_000));
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &market.rwa, &1_000);
+    client.ca_deposit_collateral(&borrower, &market.xlm, &3_000);
+
+    client.ca_borrow_asset(&borrower, &market.usdc, &800);
+    assert_eq!(backed_debt(&client, AssetCategory::RealWorldAsset), 200);
+    assert_eq!(backed_debt(&client, AssetCategory::Other), 600);
+
+    client.ca_repay_debt(&borrower, &market.usdc, &400);
+    assert_eq!(backed_debt(&client, AssetCategory::RealWorldAsset), 100);
+    assert_eq!(backed_debt(&client, AssetCategory::Other), 300);
+
+    // Withdrawing the real-world collateral moves its share to XLM
+    client.ca_withdraw_collateral(&borrower, &market.rwa, &1_000);
+    assert_eq!(backed_debt(&client, AssetCategory::RealWorldAsset), 0);
+    assert_eq!(backed_debt(&client, AssetCategory::Other), 400);
+}
+
+#[test]
+fn test_uncapped_category_unaffected() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.set_category_debt_ceiling(&market.admin, &AssetCategory::RealWorldAsset, &Some(0));
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &market.xlm, &2_000);
+    client.ca_borrow_asset(&borrower, &market.usdc, &1_000);
+
+    let rwa_borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&rwa_borrower, &market.rwa, &2_000);
+    let result = client.try_ca_borrow_asset(&rwa_borrower, &market.usdc, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::CategoryCeilingExceeded)));
+
+    client.set_category_debt_ceiling(&market.admin, &AssetCategory::RealWorldAsset, &None);
+    client.ca_borrow_asset(&rwa_borrower, &market.usdc, &1);
+}
+
+#[test]
+fn test_set_category_debt_ceiling_rejections() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let category = AssetCategory::LiquidStaking;
+    assert_eq!(client.get_category_debt_ceiling(&category), None);
+
+    let result =
+        client.try_set_category_debt_ceiling(&Address::generate(&env), &category, &Some(1_000));
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    for ceiling in [-1, 10_001] {
+        let result = client.try_set_category_debt_ceiling(&market.admin, &category, &Some(ceiling));
+        assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    }
+
+    client.set_category_debt_ceiling(&market.admin, &category, &Some(3_000));
+    assert_eq!(client.get_category_debt_ceiling(&category), Some(3_000));
+}
@@ -0,0 +1,158 @@
+//! # Collateral Factor Ramp Tests
+//!
+//! Tests for collateral factor changes phased in linearly over several days
+//! and read at their interpolated value.
+
+use crate::cross_asset::{AssetConfig, CollateralFactorRamp, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const ONE_DAY: u64 = 86_400;
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+}
+
+/// Registers USDC and XLM at a price of 1.0 with a 75% collateral factor
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let market = Market {
+        admin: Address::generate(env),
+        usdc: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+    };
+    client.initialize(&market.admin);
+    client.initialize_ca(&market.admin);
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, market)
+}
+
+/// Advances the ledger by `days` and refreshes both prices
+fn advance_days(env: &Env, client: &HelloContractClient, market: &Market, days: u64) {
+    env.ledger().with_mut(|li| li.timestamp += days * ONE_DAY);
+    for asset in [&market.usdc, &market.xlm] {
+        client.update_asset_price(&market.admin, asset, &10_000_000);
+    }
+}
+
+#[test]
+fn test_ramp_interpolates_collateral_factor() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let start = env.ledger().timestamp();
+
+    client.ramp_collateral_factor(&market.admin, &market.xlm, &5000, &10);
+    assert_eq!(client.get_asset_config(&market.xlm).collateral_factor, 7500);
+    assert_eq!(
+        client.get_collateral_factor_ramp(&market.xlm),
+        Some(CollateralFactorRamp {
+            start_factor: 7500,
+            target_factor: 5000,
+            start_time: start,
+            end_time: start + 10 * ONE_DAY,
+        })
+    );
+
+    advance_days(&env, &client, &market, 4);
+    assert_eq!(client.get_asset_config(&market.xlm).collateral_factor, 6500);
+
+    advance_days(&env, &client, &market, 6);
+    assert_eq!(client.get_asset_config(&market.xlm).collateral_factor, 5000);
+    assert_eq!(client.get_collateral_factor_ramp(&market.xlm), None);
+}
+
+#[test]
+fn test_ramp_phases_in_liquidation_risk() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.ca_deposit_collateral(&Address::generate(&env), &market.usdc, &10_000);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &market.xlm, &10_000);
+    client.ca_borrow_asset(&borrower, &market.usdc, &7_000);
+
+    // An instant cut to 65% would make the position liquidatable at once
+    client.ramp_collateral_factor(&market.admin, &market.xlm, &6500, &10);
+    assert!(!client.get_user_position_summary(&borrower).is_liquidatable);
+
+    advance_days(&env, &client, &market, 5);
+    let summary = client.get_user_position_summary(&borrower);
+    assert_eq!(summary.weighted_collateral_value, 7_000);
+    assert!(!summary.is_liquidatable);
+
+    advance_days(&env, &client, &market, 5);
+    assert!(client.get_user_position_summary(&borrower).is_liquidatable);
+}
+
+#[test]
+fn test_ramp_replaced_by_new_changes() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+
+    // A new ramp starts from the factor in effect
+    client.ramp_collateral_factor(&market.admin, &market.xlm, &5500, &10);
+    advance_days(&env, &client, &market, 5);
+    client.ramp_collateral_factor(&market.admin, &market.xlm, &8000, &2);
+    let ramp = client.get_collateral_factor_ramp(&market.xlm).unwrap();
+    assert_eq!(ramp.start_factor, 6500);
+    assert_eq!(ramp.target_factor, 8000);
+
+    // A direct update applies at once and cancels the ramp
+    client.update_asset_config(
+        &market.admin,
+        &market.xlm,
+        &Some(7000),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(client.get_collateral_factor_ramp(&market.xlm), None);
+    advance_days(&env, &client, &market, 1);
+    assert_eq!(client.get_asset_config(&market.xlm).collateral_factor, 7000);
+
+    // So does a ramp of zero days
+    client.ramp_collateral_factor(&market.admin, &market.xlm, &6000, &0);
+    assert_eq!(client.get_asset_config(&market.xlm).collateral_factor, 6000);
+    assert_eq!(client.get_collateral_factor_ramp(&market.xlm), None);
+}
+
+#[test]
+fn test_ramp_validation() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+
+    let result =
+        client.try_ramp_collateral_factor(&Address::generate(&env), &market.xlm, &5000, &10);
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = client.try_ramp_collateral_factor(&market.admin, &market.xlm, &10_001, &10);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+    let unlisted = Some(Address::generate(&env));
+    let result = client.try_ramp_collateral_factor(&market.admin, &unlisted, &5000, &10);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+}
@@ -0,0 +1,34 @@
+//! # Protocol Constants Tests
+//!
+//! Tests for the shared unit newtypes and the values other modules rely on.
+
+use crate::constants::{
+    Bps, BASIS_POINTS_SCALE, DEFAULT_COLLATERAL_FACTOR, HEALTH_FACTOR_ONE, MIN_COLLATERAL_RATIO,
+    SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_YEAR,
+};
+
+#[test]
+fn test_bps_ratio_bounds() {
+    assert!(Bps::new(0).is_ratio());
+    assert!(Bps::new(10_000).is_ratio());
+    assert!(!Bps::new(-1).is_ratio());
+    assert!(!Bps::new(10_001).is_ratio());
+    // 150% is a valid basis point value but not a ratio
+    assert!(!MIN_COLLATERAL_RATIO.is_ratio());
+}
+
+#[test]
+fn test_unit_values() {
+    assert_eq!(BASIS_POINTS_SCALE.get(), 10_000);
+    assert_eq!(HEALTH_FACTOR_ONE, BASIS_POINTS_SCALE);
+    assert_eq!(DEFAULT_COLLATERAL_FACTOR, BASIS_POINTS_SCALE);
+    assert_eq!(SECONDS_PER_HOUR.get(), 3_600);
+    assert_eq!(SECONDS_PER_DAY.get(), 86_400);
+    assert_eq!(SECONDS_PER_YEAR.get(), 31_536_000);
+}
+
+#[test]
+fn test_bps_ordering() {
+    assert!(Bps::new(50) < BASIS_POINTS_SCALE);
+    assert!(MIN_COLLATERAL_RATIO > BASIS_POINTS_SCALE);
+}
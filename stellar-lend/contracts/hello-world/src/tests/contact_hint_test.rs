@@ -0,0 +1,178 @@
+//! # Contact Hint Tests
+//!
+//! Tests that borrowers can store an opaque contact hint and that liquidation
+//! warnings carry it as their notification preference allows.
+
+use crate::contact_hint::{ContactHintError, NotificationPreference};
+use crate::cross_asset::AssetConfig;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Bytes, Env, IntoVal, Map, Symbol, TryFromVal, Val,
+};
+
+struct Market<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    borrower: Address,
+}
+
+/// Lists USDC and XLM at a price of 1.0, supplies 10,000 USDC and gives the
+/// borrower 10,000 XLM of collateral (7,500 of borrowing power)
+fn setup(env: &Env) -> Market<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    let supplier = Address::generate(env);
+    StellarAssetClient::new(env, &usdc).mint(&supplier, &10_000);
+    TokenClient::new(env, &usdc).approve(
+        &supplier,
+        &contract_id,
+        &10_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&supplier, &Some(usdc.clone()), &10_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &10_000);
+
+    Market {
+        client,
+        admin,
+        usdc: Some(usdc),
+        xlm: Some(xlm),
+        borrower,
+    }
+}
+
+fn hint(env: &Env) -> Bytes {
+    Bytes::from_array(env, &[0xab; 32])
+}
+
+/// Health factor and contact hint of the last event, if it is a liquidation
+/// warning
+fn last_warning(env: &Env) -> Option<(i128, Bytes)> {
+    let events = env.events().all();
+    let (_, topics, data) = events.last().unwrap();
+    if topics != (Symbol::new(env, "liquidation_warning_event"),).into_val(env) {
+        return None;
+    }
+    let data: Map<Symbol, Val> = data.into_val(env);
+    let field = |name| data.get(Symbol::new(env, name)).unwrap();
+    Some((
+        i128::try_from_val(env, &field("health_factor")).unwrap(),
+        Bytes::try_from_val(env, &field("contact_hint")).unwrap(),
+    ))
+}
+
+#[test]
+fn test_contact_hint_is_stored_and_cleared() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+    assert_eq!(client.get_contact_hint(&market.borrower), None);
+
+    client.set_contact_hint(
+        &market.borrower,
+        &hint(&env),
+        &NotificationPreference::AtRisk,
+    );
+    let contact = client.get_contact_hint(&market.borrower).unwrap();
+    assert_eq!(contact.hint, hint(&env));
+    assert_eq!(contact.preference, NotificationPreference::AtRisk);
+
+    client.clear_contact_hint(&market.borrower);
+    assert_eq!(client.get_contact_hint(&market.borrower), None);
+
+    for len in [0, 129] {
+        let result = client.try_set_contact_hint(
+            &market.borrower,
+            &Bytes::from_slice(&env, &[1; 129][..len]),
+            &NotificationPreference::AtRisk,
+        );
+        assert_eq!(result, Err(Ok(ContactHintError::InvalidHint)));
+    }
+}
+
+#[test]
+fn test_risky_borrow_warns_with_hint() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+    client.set_contact_hint(
+        &market.borrower,
+        &hint(&env),
+        &NotificationPreference::AtRisk,
+    );
+
+    client.ca_borrow_asset(&market.borrower, &market.usdc, &1_000);
+    assert_eq!(last_warning(&env), None);
+
+    // 7,500 of borrowing power against 7,000 of debt
+    client.ca_borrow_asset(&market.borrower, &market.usdc, &6_000);
+    assert_eq!(last_warning(&env), Some((10_714, hint(&env))));
+}
+
+#[test]
+fn test_preference_controls_when_hint_is_surfaced() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+    client.set_contact_hint(
+        &market.borrower,
+        &hint(&env),
+        &NotificationPreference::Liquidatable,
+    );
+
+    client.ca_borrow_asset(&market.borrower, &market.usdc, &7_000);
+    assert_eq!(last_warning(&env), Some((10_714, Bytes::new(&env))));
+
+    // At 0.9 the position can be liquidated
+    client.update_asset_price(&market.admin, &market.xlm, &9_000_000);
+    assert!(client.warn_liquidation_risk(&market.borrower));
+    assert_eq!(last_warning(&env), Some((9_642, hint(&env))));
+
+    client.set_contact_hint(&market.borrower, &hint(&env), &NotificationPreference::Off);
+    assert!(client.warn_liquidation_risk(&market.borrower));
+    assert_eq!(last_warning(&env), Some((9_642, Bytes::new(&env))));
+}
+
+#[test]
+fn test_healthy_positions_are_not_warned() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+
+    assert!(!client.warn_liquidation_risk(&market.borrower));
+    client.ca_borrow_asset(&market.borrower, &market.usdc, &1_000);
+    assert!(!client.warn_liquidation_risk(&market.borrower));
+    assert!(!client.warn_liquidation_risk(&Address::generate(&env)));
+}
@@ -0,0 +1,177 @@
+//! # Credit History Tests
+//!
+//! Tests for the repayment record built from borrows, repayments and
+//! liquidations, and for the grants that control who can read it.
+
+use crate::credit::{CreditError, CreditSummary, MAX_CREDIT_GRANTS};
+use crate::cross_asset::AssetConfig;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+}
+
+/// Registers USDC and XLM at a price of 1.0 with a 75% collateral factor
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, market)
+}
+
+fn summary(client: &HelloContractClient, user: &Address) -> CreditSummary {
+    client.get_credit_summary(user, user)
+}
+
+#[test]
+fn test_repaid_loans_build_streak() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &market.xlm, &1_000);
+
+    client.ca_borrow_asset(&user, &market.usdc, &300);
+    // Adding to an open loan does not open another
+    client.ca_borrow_asset(&user, &market.usdc, &200);
+    client.ca_repay_debt(&user, &market.usdc, &100);
+    client.ca_repay_debt(&user, &market.usdc, &400);
+    client.ca_borrow_asset(&user, &market.usdc, &100);
+    client.ca_repay_debt(&user, &market.usdc, &100);
+
+    let record = summary(&client, &user);
+    assert_eq!(record.loans_opened, 2);
+    assert_eq!(record.loans_repaid, 2);
+    assert_eq!(record.loans_repaid_on_time, 2);
+    assert_eq!(record.liquidations, 0);
+    assert_eq!(record.current_streak, 2);
+    assert_eq!(record.longest_streak, 2);
+    assert_eq!(record.first_loan_at, env.ledger().timestamp());
+}
+
+#[test]
+fn test_liquidation_resets_streak() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &market.xlm, &1_000);
+    client.ca_borrow_asset(&user, &market.usdc, &100);
+    client.ca_repay_debt(&user, &market.usdc, &100);
+
+    client.ca_borrow_asset(&user, &market.usdc, &700);
+    client.update_asset_price(&market.admin, &market.xlm, &5_000_000);
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &market.usdc, &1_000);
+    client.ca_liquidate(
+        &liquidator,
+        &user,
+        &market.usdc,
+        &vec![&env, (market.xlm.clone(), 200)],
+    );
+    // Repaying the rest closes the loan, but not on time
+    client.ca_repay_debt(&user, &market.usdc, &500);
+
+    let record = summary(&client, &user);
+    assert_eq!(record.loans_opened, 2);
+    assert_eq!(record.loans_repaid, 2);
+    assert_eq!(record.loans_repaid_on_time, 1);
+    assert_eq!(record.liquidations, 1);
+    assert_eq!(record.current_streak, 0);
+    assert_eq!(record.longest_streak, 1);
+}
+
+#[test]
+fn test_core_borrow_and_repay_recorded() {
+    let env = create_test_env();
+    let (client, _) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+
+    client.borrow_asset(&user, &None, &1_000);
+    client.repay_debt(&user, &None, &1_000);
+
+    let record = summary(&client, &user);
+    assert_eq!(record.loans_opened, 1);
+    assert_eq!(record.loans_repaid_on_time, 1);
+    assert_eq!(record.current_streak, 1);
+}
+
+#[test]
+fn test_summary_requires_grant() {
+    let env = create_test_env();
+    let (client, _) = setup(&env);
+    let user = Address::generate(&env);
+    let lender = Address::generate(&env);
+
+    let result = client.try_get_credit_summary(&lender, &user);
+    assert_eq!(result, Err(Ok(CreditError::NotAuthorized)));
+
+    client.grant_credit_access(&user, &lender);
+    assert_eq!(client.get_credit_grants(&user), vec![&env, lender.clone()]);
+    assert_eq!(
+        client.get_credit_summary(&lender, &user),
+        CreditSummary::default()
+    );
+
+    client.revoke_credit_access(&user, &lender);
+    let result = client.try_get_credit_summary(&lender, &user);
+    assert_eq!(result, Err(Ok(CreditError::NotAuthorized)));
+}
+
+#[test]
+fn test_invalid_grants_rejected() {
+    let env = create_test_env();
+    let (client, _) = setup(&env);
+    let user = Address::generate(&env);
+    let lender = Address::generate(&env);
+
+    let result = client.try_grant_credit_access(&user, &user);
+    assert_eq!(result, Err(Ok(CreditError::InvalidGrantee)));
+    let result = client.try_revoke_credit_access(&user, &lender);
+    assert_eq!(result, Err(Ok(CreditError::GrantNotFound)));
+
+    client.grant_credit_access(&user, &lender);
+    let result = client.try_grant_credit_access(&user, &lender);
+    assert_eq!(result, Err(Ok(CreditError::AlreadyGranted)));
+
+    for _ in 1..MAX_CREDIT_GRANTS {
+        client.grant_credit_access(&user, &Address::generate(&env));
+    }
+    let result = client.try_grant_credit_access(&user, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(CreditError::TooManyGrants)));
+}
@@ -361,7 +361,7 @@ fn test_liquidation_incentive_amount() {
     let (_id, _admin, client) = setup(&e);
 
     let liquidated = 1_000_i128;
-    let incentive = client.get_liquidation_incentive_amount(&liquidated);
+    let incentive = client.get_liquidation_incentive_amount(&liquidated, &9_500);
     // 10 % incentive → 1000 × 1000 / 10000 = 100
     assert_eq!(
         incentive, 100,
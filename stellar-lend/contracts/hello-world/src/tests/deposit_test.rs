@@ -44,6 +44,8 @@ fn set_asset_params(
             deposit_enabled,
             collateral_factor,
             max_deposit,
+            supply_cap: 0,
+            borrow_cap: 0,
         };
         let key = DepositDataKey::AssetParams(asset.clone());
         env.storage().persistent().set(&key, &params);
@@ -419,18 +421,12 @@ fn test_deposit_collateral_activity_log_limit() {
 
     let user = Address::generate(&env);
 
-    // Make deposits to hit the 1000 activity log limit
-    for _ in 0..1001 {
+    // Make deposits to hit the 200 entry per-user activity log limit
+    for _ in 0..201 {
         client.deposit_collateral(&user, &None, &1);
     }
 
-    env.as_contract(&contract_id, || {
-        let log: Vec<deposit::Activity> = env
-            .storage()
-            .persistent()
-            .get(&DepositDataKey::ActivityLog)
-            .unwrap();
-        assert!(log.len() <= 1000);
-        assert_eq!(log.len(), 1000); // Verify limit was maintained
-    });
+    // The user's log is trimmed; the global ring buffer keeps every entry
+    assert_eq!(client.get_user_activity(&user, &1000, &0).len(), 200);
+    assert_eq!(client.get_recent_activity(&1000, &0).len(), 201);
 }
@@ -0,0 +1,143 @@
+//! # Emergency Pause Tests
+//!
+//! Tests that the emergency pause blocks user operations across the core and
+//! cross-asset entrypoints, and that only the protocol admin can lift it.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::risk_management::RiskManagementError;
+use crate::roles::Role;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    (admin, client)
+}
+
+fn list_asset(env: &Env, client: &HelloContractClient, admin: &Address) -> Option<Address> {
+    let asset = Some(create_token(env));
+    client.initialize_asset(
+        admin,
+        &asset,
+        &AssetConfig {
+            asset: asset.clone(),
+            collateral_factor: 7500,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    asset
+}
+
+#[test]
+fn test_pause_blocks_core_operations() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &3_000);
+    client.borrow_asset(&user, &None, &500);
+
+    client.set_emergency_pause(&admin, &true);
+
+    assert!(client.try_deposit_collateral(&user, &None, &100).is_err());
+    assert!(client.try_withdraw_collateral(&user, &None, &100).is_err());
+    assert!(client.try_borrow_asset(&user, &None, &100).is_err());
+    assert!(client.try_repay_debt(&user, &None, &100).is_err());
+    assert!(client
+        .try_liquidate(&Address::generate(&env), &user, &None, &None, &100)
+        .is_err());
+}
+
+#[test]
+fn test_pause_blocks_cross_asset_operations() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin);
+    let xlm = list_asset(&env, &client, &admin);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &xlm, &1_000);
+    client.ca_borrow_asset(&user, &usdc, &100);
+
+    client.set_emergency_pause(&admin, &true);
+
+    let paused = Err(Ok(CrossAssetError::ProtocolPaused));
+    assert_eq!(
+        client
+            .try_ca_deposit_collateral(&user, &xlm, &100)
+            .map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_ca_withdraw_collateral(&user, &xlm, &100)
+            .map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client.try_ca_borrow_asset(&user, &usdc, &100).map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client.try_ca_repay_debt(&user, &usdc, &100).map(|_| ()),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_ca_liquidate(
+                &Address::generate(&env),
+                &user,
+                &usdc,
+                &vec![&env, (xlm.clone(), 50)],
+            )
+            .map(|_| ()),
+        paused
+    );
+}
+
+#[test]
+fn test_guardian_can_pause_but_not_unpause() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let guardian = Address::generate(&env);
+    client.grant_role(&admin, &Role::PauseGuardian, &guardian);
+
+    client.set_emergency_pause(&guardian, &true);
+    assert!(client.is_emergency_paused());
+
+    let result = client.try_set_emergency_pause(&guardian, &false);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    assert!(client.is_emergency_paused());
+
+    client.set_emergency_pause(&admin, &false);
+    assert!(!client.is_emergency_paused());
+}
+
+#[test]
+fn test_unpause_restores_operations() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let usdc = list_asset(&env, &client, &admin);
+    let user = Address::generate(&env);
+
+    client.set_emergency_pause(&admin, &true);
+    assert!(client.try_deposit_collateral(&user, &None, &100).is_err());
+
+    client.set_emergency_pause(&admin, &false);
+    assert_eq!(client.deposit_collateral(&user, &None, &100), 100);
+    assert_eq!(
+        client.ca_deposit_collateral(&user, &usdc, &100).collateral,
+        100
+    );
+}
@@ -0,0 +1,197 @@
+//! # Emergency Price Override Tests
+//!
+//! Tests for the admin-only, auto-expiring oracle price override used during
+//! feed incidents. Covers precedence, expiry, clearing, authorization, the
+//! usage counter reported in protocol analytics and cross-asset valuation.
+
+use crate::cross_asset::AssetConfig;
+use crate::oracle::EMERGENCY_PRICE_TTL_SECONDS;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+#[test]
+fn test_emergency_price_overrides_feed() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    client.update_price_feed(&admin, &asset, &100_000_000, &8, &oracle);
+    assert_eq!(client.get_price(&asset), 100_000_000);
+
+    // Far outside the 5% deviation guard
+    let emergency = client.set_emergency_price(&admin, &asset, &40_000_000);
+    assert_eq!(emergency.expires_at, 10_000 + EMERGENCY_PRICE_TTL_SECONDS);
+    assert_eq!(client.get_price(&asset), 40_000_000);
+}
+
+#[test]
+fn test_emergency_price_works_without_feed() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+
+    client.set_emergency_price(&admin, &asset, &55_000_000);
+    assert_eq!(client.get_price(&asset), 55_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Oracle error")]
+fn test_emergency_price_expires() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+
+    client.set_emergency_price(&admin, &asset, &55_000_000);
+    env.ledger()
+        .with_mut(|li| li.timestamp += EMERGENCY_PRICE_TTL_SECONDS);
+
+    assert!(client.get_emergency_price(&asset).is_none());
+    // No feed configured, so the lookup fails once the override lapses
+    client.get_price(&asset);
+}
+
+#[test]
+fn test_feed_resumes_after_expiry() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    client.set_emergency_price(&admin, &asset, &55_000_000);
+    env.ledger()
+        .with_mut(|li| li.timestamp += EMERGENCY_PRICE_TTL_SECONDS);
+    client.update_price_feed(&admin, &asset, &100_000_000, &8, &oracle);
+
+    assert_eq!(client.get_price(&asset), 100_000_000);
+}
+
+#[test]
+fn test_clear_emergency_price() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    client.update_price_feed(&admin, &asset, &100_000_000, &8, &oracle);
+    client.set_emergency_price(&admin, &asset, &40_000_000);
+    client.clear_emergency_price(&admin, &asset);
+
+    assert!(client.get_emergency_price(&asset).is_none());
+    assert_eq!(client.get_price(&asset), 100_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_non_admin_cannot_set_emergency_price() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+
+    client.set_emergency_price(&Address::generate(&env), &asset, &40_000_000);
+}
+
+#[test]
+#[should_panic(expected = "InvalidPrice")]
+fn test_emergency_price_must_be_positive() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+
+    client.set_emergency_price(&admin, &Address::generate(&env), &0);
+}
+
+#[test]
+fn test_usage_counter_in_protocol_report() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+
+    assert_eq!(
+        client
+            .get_protocol_report()
+            .metrics
+            .emergency_price_overrides,
+        0
+    );
+
+    client.set_emergency_price(&admin, &asset, &40_000_000);
+    client.set_emergency_price(&admin, &asset, &41_000_000);
+
+    assert_eq!(
+        client
+            .get_protocol_report()
+            .metrics
+            .emergency_price_overrides,
+        2
+    );
+}
+
+fn cross_asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 8000,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+#[test]
+fn test_emergency_price_values_cross_asset_positions() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    client.initialize_ca(&admin);
+    let xlm = create_token(&env);
+    let usdc = create_token(&env);
+    client.initialize_asset(&admin, &Some(xlm.clone()), &cross_asset_config(&env, &xlm));
+    client.initialize_asset(
+        &admin,
+        &Some(usdc.clone()),
+        &cross_asset_config(&env, &usdc),
+    );
+
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&Address::generate(&env), &Some(usdc.clone()), &5_000);
+    client.ca_deposit_collateral(&user, &Some(xlm.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &700);
+    assert!(!client.get_user_position_summary(&user).is_liquidatable);
+
+    // 0.6 in the oracle's 8 decimals: 480 of weighted collateral against 700
+    client.set_emergency_price(&admin, &xlm, &60_000_000);
+    let summary = client.get_user_position_summary(&user);
+    assert_eq!(summary.total_collateral_value, 600);
+    assert!(summary.is_liquidatable);
+
+    // The override is never written into the market's own price
+    assert_eq!(client.get_asset_config(&Some(xlm.clone())).price, 6_000_000);
+    client.clear_emergency_price(&admin, &xlm);
+    assert_eq!(
+        client.get_asset_config(&Some(xlm.clone())).price,
+        10_000_000
+    );
+    assert!(!client.get_user_position_summary(&user).is_liquidatable);
+}
@@ -1,3 +1,4 @@
+use crate::cross_asset::AssetConfig;
 /// # Event Logging System – Tests
 ///
 /// Comprehensive tests verifying every `emit_*` helper in the `events` module
@@ -16,11 +17,12 @@ use crate::events::{
     DepositEvent, FlashLoanInitiatedEvent, FlashLoanRepaidEvent, LiquidationEvent,
     PauseStateChangedEvent, PriceUpdatedEvent, RepayEvent, RiskParamsUpdatedEvent, WithdrawalEvent,
 };
+use crate::tests::token_interface_test::create_token;
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{
     contracttype,
     testutils::{Address as _, Events},
-    Address, Env, Symbol, TryFromVal,
+    Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -73,6 +75,7 @@ pub struct TestLiquidationEvent {
     pub debt_liquidated: i128,
     pub collateral_seized: i128,
     pub incentive_amount: i128,
+    pub surplus_returned: i128,
     pub timestamp: u64,
 }
 
@@ -305,6 +308,7 @@ fn test_liquidation_event_structure() {
                 debt_liquidated: 1_000,
                 collateral_seized: 1_100,
                 incentive_amount: 100,
+                surplus_returned: 0,
                 timestamp: 999,
             },
         );
@@ -322,6 +326,7 @@ fn test_liquidation_event_structure() {
         assert_eq!(decoded.debt_liquidated, 1_000);
         assert_eq!(decoded.collateral_seized, 1_100);
         assert_eq!(decoded.incentive_amount, 100);
+        assert_eq!(decoded.surplus_returned, 0);
         assert_eq!(decoded.timestamp, 999);
         // Security: liquidator ≠ borrower
         assert_ne!(decoded.liquidator, decoded.borrower);
@@ -351,6 +356,7 @@ fn test_liquidation_event_with_token_assets() {
                 debt_liquidated: 2_000,
                 collateral_seized: 2_200,
                 incentive_amount: 200,
+                surplus_returned: 0,
                 timestamp: 500,
             },
         );
@@ -656,6 +662,7 @@ fn test_all_event_helpers_emit_one_event_each() {
                 debt_liquidated: 1,
                 collateral_seized: 1,
                 incentive_amount: 0,
+                surplus_returned: 0,
                 timestamp: 0,
             },
         );
@@ -815,6 +822,7 @@ fn test_no_sensitive_data_in_liquidation_event() {
                 debt_liquidated: 500,
                 collateral_seized: 550,
                 incentive_amount: 50,
+                surplus_returned: 0,
                 timestamp: 777,
             },
         );
@@ -863,3 +871,96 @@ fn test_event_sequence_deposit_borrow_repay() {
         "Repay should emit additional events"
     );
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Integration: cross-asset flows and parameter changes
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Topics of the last event emitted by the previous call.
+fn last_topics(env: &Env) -> Vec<Val> {
+    env.events().all().last().unwrap().1
+}
+
+fn topics(env: &Env, name: &str) -> Vec<Val> {
+    (Symbol::new(env, name),).into_val(env)
+}
+
+/// Cross-asset deposits, borrows, repayments and withdrawals emit the same
+/// events as their single-asset counterparts.
+#[test]
+fn test_cross_asset_flows_emit_events() {
+    let (env, _contract, client) = setup();
+    let admin = Address::generate(&env);
+    init(&client, &admin);
+    client.initialize_ca(&admin);
+
+    let asset = Some(create_token(&env));
+    client.initialize_asset(
+        &admin,
+        &asset,
+        &AssetConfig {
+            asset: asset.clone(),
+            collateral_factor: 7500,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    assert_eq!(
+        last_topics(&env),
+        topics(&env, "asset_config_updated_event")
+    );
+
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &asset, &10_000);
+    let (_c, t, data) = env.events().all().last().unwrap();
+    assert_eq!(t, topics(&env, "deposit_event"));
+    let decoded = TestDepositEvent::try_from_val(&env, &data).unwrap();
+    assert_eq!(decoded.user, user);
+    assert_eq!(decoded.asset, asset);
+    assert_eq!(decoded.amount, 10_000);
+
+    client.ca_borrow_asset(&user, &asset, &2_000);
+    assert_eq!(last_topics(&env), topics(&env, "borrow_event"));
+
+    client.ca_repay_debt(&user, &asset, &2_000);
+    let (_c, t, data) = env.events().all().last().unwrap();
+    assert_eq!(t, topics(&env, "repay_event"));
+    assert_eq!(
+        TestRepayEvent::try_from_val(&env, &data).unwrap().amount,
+        2_000
+    );
+
+    client.ca_withdraw_collateral(&user, &asset, &10_000);
+    assert_eq!(last_topics(&env), topics(&env, "withdrawal_event"));
+}
+
+/// Admin parameter changes without a dedicated event record an admin action.
+#[test]
+fn test_parameter_changes_emit_events() {
+    let (env, _contract, client) = setup();
+    let admin = Address::generate(&env);
+    init(&client, &admin);
+
+    #[cfg(feature = "flash-loans")]
+    {
+        client.set_flash_loan_fee(&admin, &20);
+        let (_c, t, data) = env.events().all().last().unwrap();
+        assert_eq!(t, topics(&env, "admin_action_event"));
+        let decoded = TestAdminActionEvent::try_from_val(&env, &data).unwrap();
+        assert_eq!(decoded.actor, admin);
+        assert_eq!(decoded.action, Symbol::new(&env, "set_flash_loan_fee"));
+    }
+
+    let asset = Address::generate(&env);
+    client.set_primary_oracle(&admin, &asset, &Address::generate(&env));
+    assert_eq!(
+        last_topics(&env),
+        topics(&env, "oracle_source_updated_event")
+    );
+}
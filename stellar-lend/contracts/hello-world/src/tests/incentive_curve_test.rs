@@ -0,0 +1,135 @@
+//! # Liquidation Incentive Curve Tests
+//!
+//! Tests that the liquidation incentive rises from its minimum at a health
+//! factor of 1.0 to its maximum for deeply underwater positions, and that
+//! cross-asset liquidations pay it.
+
+use crate::cross_asset::AssetConfig;
+use crate::risk_management::{LiquidationIncentiveCurve, RiskManagementError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (admin, client)
+}
+
+/// 5% at a health factor of 1.0, rising to 15% at 0.8
+fn curve() -> LiquidationIncentiveCurve {
+    LiquidationIncentiveCurve {
+        min_incentive_bps: 500,
+        max_incentive_bps: 1_500,
+        max_bonus_health_factor: 8_000,
+    }
+}
+
+#[test]
+fn test_incentive_follows_curve() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    assert_eq!(client.get_liquidation_incentive_curve(), None);
+    assert_eq!(client.get_health_factor_incentive(&5_000), 1_000);
+
+    client.set_liquidation_incentive_curve(&admin, &Some(curve()));
+    assert_eq!(client.get_liquidation_incentive_curve(), Some(curve()));
+    assert_eq!(client.get_health_factor_incentive(&10_000), 500);
+    assert_eq!(client.get_health_factor_incentive(&9_000), 1_000);
+    assert_eq!(client.get_health_factor_incentive(&8_000), 1_500);
+    assert_eq!(client.get_health_factor_incentive(&5_000), 1_500);
+    assert_eq!(client.get_liquidation_incentive_amount(&1_000, &9_500), 75);
+
+    // Removing the curve restores the flat incentive
+    client.set_liquidation_incentive_curve(&admin, &None);
+    assert_eq!(client.get_health_factor_incentive(&9_000), 1_000);
+}
+
+#[test]
+fn test_cross_asset_liquidation_pays_curve_incentive() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    client.initialize_ca(&admin);
+    let usdc = create_token(&env);
+    let xlm = create_token(&env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_liquidation_incentive_curve(&admin, &Some(curve()));
+
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &2_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &1_400);
+    // Weighted collateral falls to 1,275 against 1,400 of debt
+    client.update_asset_price(&admin, &Some(xlm.clone()), &8_500_000);
+    let health_factor = client.get_user_position_summary(&borrower).health_factor;
+    assert_eq!(health_factor, 9_107);
+
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &Some(usdc.clone()), &1_000);
+    let result = client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(usdc.clone()),
+        &vec![&env, (Some(xlm.clone()), 425)],
+    );
+
+    // 500 + 1,000 * 893 / 2,000 = 946 bps on 500 XLM worth of debt
+    assert_eq!(client.get_health_factor_incentive(&health_factor), 946);
+    assert_eq!(result.seized, vec![&env, (Some(xlm), 547)]);
+}
+
+#[test]
+fn test_set_curve_rejections() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+
+    let result =
+        client.try_set_liquidation_incentive_curve(&Address::generate(&env), &Some(curve()));
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+
+    for (min, max) in [(1_500, 500), (-1, 500), (500, 5_001)] {
+        let result = client.try_set_liquidation_incentive_curve(
+            &admin,
+            &Some(LiquidationIncentiveCurve {
+                min_incentive_bps: min,
+                max_incentive_bps: max,
+                ..curve()
+            }),
+        );
+        assert_eq!(
+            result,
+            Err(Ok(RiskManagementError::InvalidLiquidationIncentive))
+        );
+    }
+
+    for health_factor in [0, 10_000] {
+        let result = client.try_set_liquidation_incentive_curve(
+            &admin,
+            &Some(LiquidationIncentiveCurve {
+                max_bonus_health_factor: health_factor,
+                ..curve()
+            }),
+        );
+        assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+    }
+    assert_eq!(client.get_liquidation_incentive_curve(), None);
+}
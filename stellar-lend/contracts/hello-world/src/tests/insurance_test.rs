@@ -0,0 +1,285 @@
+//! # Supplier Insurance Tests
+//!
+//! Tests for opt-in pool coverage: premiums charged on covered yield,
+//! shortfall payouts to covered suppliers, and the coverage cap.
+
+use crate::cross_asset::AssetConfig;
+use crate::insurance::{InsuranceError, InsuranceFund};
+use crate::rate_strategy::RateStrategy;
+use crate::tests::lending_pool_test::repay_core_interest;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+}
+
+/// Registers USDC and XLM at a price of 1.0 with a 10% reserve factor, and a
+/// 12% borrow rate at 80% utilization on USDC
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: create_token(env),
+        xlm: create_token(env),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &market.admin,
+        &Some(market.usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    (contract_id, client, market)
+}
+
+/// Mints `amount` USDC to a new account and approves the contract
+fn funded(env: &Env, contract_id: &Address, market: &Market, amount: i128) -> Address {
+    let account = Address::generate(env);
+    StellarAssetClient::new(env, &market.usdc).mint(&account, &amount);
+    TokenClient::new(env, &market.usdc).approve(
+        &account,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    account
+}
+
+/// A supplier of `amount` USDC to the pool
+fn supplier(
+    env: &Env,
+    contract_id: &Address,
+    client: &HelloContractClient,
+    market: &Market,
+    amount: i128,
+) -> Address {
+    let user = funded(env, contract_id, market, amount);
+    client.supply(&user, &Some(market.usdc.clone()), &amount);
+    user
+}
+
+/// Repays a year of interest at 12% on 8,000 USDC: 960 USDC, of which 864
+/// goes to suppliers
+fn accrue_one_year(env: &Env, client: &HelloContractClient, market: &Market) {
+    repay_core_interest(env, client, &market.usdc, 960);
+}
+
+#[test]
+fn test_premium_charged_on_covered_yield() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_insurance_config(&usdc, &1000, &0);
+
+    let covered = supplier(&env, &contract_id, &client, &market, 10_000);
+    client.opt_in_coverage(&covered, &usdc);
+    accrue_one_year(&env, &client, &market);
+
+    // 10% of 864 of yield, paid with 79 shares worth 85
+    assert_eq!(client.claim_coverage(&covered, &usdc), 0);
+    assert_eq!(client.get_supply_shares(&covered, &usdc), 9_921);
+    assert_eq!(client.get_supply_balance(&covered, &usdc), 10_779);
+    assert_eq!(
+        client.get_insurance_fund(&usdc),
+        InsuranceFund {
+            balance: 85,
+            covered_shares: 9_921,
+            payout_index: 0,
+            premiums_collected: 85,
+            payouts: 0,
+        }
+    );
+
+    // Yield already charged is not charged again
+    client.claim_coverage(&covered, &usdc);
+    assert_eq!(client.get_insurance_fund(&usdc).premiums_collected, 85);
+}
+
+#[test]
+fn test_uncovered_suppliers_pay_no_premium() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_insurance_config(&usdc, &1000, &0);
+
+    let uncovered = supplier(&env, &contract_id, &client, &market, 10_000);
+    accrue_one_year(&env, &client, &market);
+
+    assert_eq!(client.redeem(&uncovered, &usdc, &10_000), 10_864);
+    assert_eq!(client.get_insurance_fund(&usdc), InsuranceFund::default());
+}
+
+#[test]
+fn test_shortfall_makes_covered_suppliers_whole() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_insurance_config(&usdc, &0, &0);
+    let safety_module = funded(&env, &contract_id, &market, 2_000);
+    client.fund_insurance(&safety_module, &usdc, &2_000);
+
+    let covered = supplier(&env, &contract_id, &client, &market, 10_000);
+    let uncovered = supplier(&env, &contract_id, &client, &market, 10_000);
+    client.opt_in_coverage(&covered, &usdc);
+
+    // Half of the pool is covered, so the fund pays half of the loss
+    assert_eq!(client.report_shortfall(&usdc, &1_000), 500);
+    assert_eq!(client.get_supply_balance(&covered, &usdc), 9_500);
+    assert_eq!(client.get_supply_balance(&uncovered, &usdc), 9_500);
+
+    assert_eq!(client.claim_coverage(&covered, &usdc), 500);
+    assert_eq!(TokenClient::new(&env, &market.usdc).balance(&covered), 500);
+    assert_eq!(
+        client.try_claim_coverage(&uncovered, &usdc),
+        Err(Ok(InsuranceError::NotCovered))
+    );
+
+    let fund = client.get_insurance_fund(&usdc);
+    assert_eq!(fund.balance, 1_500);
+    assert_eq!(fund.payouts, 500);
+}
+
+#[test]
+fn test_shortfall_payout_bounded_by_cap_and_fund() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_insurance_config(&usdc, &0, &5_000);
+
+    let covered = supplier(&env, &contract_id, &client, &market, 4_000);
+    client.opt_in_coverage(&covered, &usdc);
+    // Later supply is covered too
+    StellarAssetClient::new(&env, &market.usdc).mint(&covered, &6_000);
+    TokenClient::new(&env, &market.usdc).approve(
+        &covered,
+        &contract_id,
+        &6_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&covered, &usdc, &6_000);
+    assert_eq!(client.get_coverage(&covered, &usdc).unwrap().shares, 10_000);
+    supplier(&env, &contract_id, &client, &market, 10_000);
+
+    // Covered value is past the cap
+    let late = supplier(&env, &contract_id, &client, &market, 1_000);
+    assert_eq!(
+        client.try_opt_in_coverage(&late, &usdc),
+        Err(Ok(InsuranceError::CoverageCapReached))
+    );
+
+    // 2,000 of loss on 21,000 of value, 5,000 of it covered: 476, of which
+    // the fund holds 100
+    let safety_module = funded(&env, &contract_id, &market, 100);
+    client.fund_insurance(&safety_module, &usdc, &100);
+    assert_eq!(client.report_shortfall(&usdc, &2_000), 100);
+    assert_eq!(client.get_insurance_fund(&usdc).balance, 0);
+}
+
+#[test]
+fn test_opt_out_pays_unclaimed_payouts() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_insurance_config(&usdc, &0, &0);
+    let safety_module = funded(&env, &contract_id, &market, 1_000);
+    client.fund_insurance(&safety_module, &usdc, &1_000);
+
+    let covered = supplier(&env, &contract_id, &client, &market, 10_000);
+    client.opt_in_coverage(&covered, &usdc);
+    client.report_shortfall(&usdc, &400);
+
+    assert_eq!(client.opt_out_coverage(&covered, &usdc), 400);
+    assert_eq!(TokenClient::new(&env, &market.usdc).balance(&covered), 400);
+    assert_eq!(client.get_coverage(&covered, &usdc), None);
+    assert_eq!(client.get_insurance_fund(&usdc).covered_shares, 0);
+
+    // Uncovered again: later losses are not paid
+    assert_eq!(client.report_shortfall(&usdc, &400), 0);
+}
+
+#[test]
+fn test_insurance_errors() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 1_000);
+
+    assert_eq!(
+        client.try_opt_in_coverage(&user, &usdc),
+        Err(Ok(InsuranceError::CoverageNotOffered))
+    );
+    assert_eq!(
+        client.try_fund_insurance(&user, &usdc, &100),
+        Err(Ok(InsuranceError::CoverageNotOffered))
+    );
+    assert_eq!(
+        client.try_set_insurance_config(&usdc, &10_001, &0),
+        Err(Ok(InsuranceError::InvalidParameter))
+    );
+    assert_eq!(
+        client.try_set_insurance_config(&Some(create_token(&env)), &100, &0),
+        Err(Ok(InsuranceError::AssetNotConfigured))
+    );
+
+    client.set_insurance_config(&usdc, &100, &0);
+    assert_eq!(
+        client.try_opt_in_coverage(&Address::generate(&env), &usdc),
+        Err(Ok(InsuranceError::NoSupply))
+    );
+    client.opt_in_coverage(&user, &usdc);
+    assert_eq!(
+        client.try_opt_in_coverage(&user, &usdc),
+        Err(Ok(InsuranceError::AlreadyCovered))
+    );
+    assert_eq!(
+        client.try_report_shortfall(&usdc, &1_001),
+        Err(Ok(InsuranceError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_report_shortfall_requires_admin() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    supplier(&env, &contract_id, &client, &market, 1_000);
+
+    env.set_auths(&[]);
+    assert!(client.try_report_shortfall(&usdc, &100).is_err());
+    assert_eq!(client.get_pool_state(&usdc).total_liquidity, 1_000);
+}
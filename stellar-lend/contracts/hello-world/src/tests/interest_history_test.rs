@@ -0,0 +1,174 @@
+//! # Interest History Tests
+//!
+//! Tests that the interest charged over a period is summed from accrual
+//! checkpoints, prorated at the period's edges, and includes interest not
+//! yet accrued.
+
+use crate::cross_asset::AssetConfig;
+use crate::interest_history::InterestHistoryError;
+use crate::rate_strategy::RateStrategy;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+const ONE_YEAR: u64 = 31_536_000;
+const START: u64 = 1_000;
+
+struct Market<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    borrower: Address,
+}
+
+/// Lists USDC and XLM at a price of 1.0 with a 12% USDC borrow rate at 80%
+/// utilization, and opens a borrower with 8,000 USDC of debt at `START`
+fn setup(env: &Env) -> Market<'_> {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = START);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &admin,
+        &Some(usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+
+    let supplier = Address::generate(env);
+    StellarAssetClient::new(env, &usdc).mint(&supplier, &10_000);
+    TokenClient::new(env, &usdc).approve(
+        &supplier,
+        &contract_id,
+        &10_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&supplier, &Some(usdc.clone()), &10_000);
+    client.ca_deposit_collateral(&Address::generate(env), &Some(usdc.clone()), &10_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &20_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &8_000);
+
+    Market {
+        client,
+        admin,
+        usdc: Some(usdc),
+        xlm: Some(xlm),
+        borrower,
+    }
+}
+
+/// Advances time, refreshes prices and accrues the borrower's interest by
+/// repaying 1 USDC
+fn accrue(env: &Env, market: &Market, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+    market
+        .client
+        .update_asset_price(&market.admin, &market.usdc, &10_000_000);
+    market
+        .client
+        .update_asset_price(&market.admin, &market.xlm, &10_000_000);
+    market
+        .client
+        .ca_repay_debt(&market.borrower, &market.usdc, &1);
+}
+
+#[test]
+fn test_interest_paid_from_checkpoints() {
+    let env = Env::default();
+    let market = setup(&env);
+    accrue(&env, &market, ONE_YEAR);
+    let paid = |from, to| {
+        market
+            .client
+            .get_interest_paid(&market.borrower, &market.usdc, &from, &to)
+    };
+
+    // 12% of 8,000 over the year
+    assert_eq!(paid(START, START + ONE_YEAR), 960);
+    assert_eq!(paid(START, START + ONE_YEAR / 2), 480);
+    assert_eq!(paid(START + ONE_YEAR / 4, START + ONE_YEAR / 2), 240);
+    assert_eq!(paid(0, START), 0);
+    assert_eq!(paid(0, START + 2 * ONE_YEAR), 960);
+    assert_eq!(
+        market
+            .client
+            .get_interest_paid(&Address::generate(&env), &market.usdc, &0, &START),
+        0
+    );
+}
+
+#[test]
+fn test_interest_paid_includes_unaccrued_interest() {
+    let env = Env::default();
+    let market = setup(&env);
+    accrue(&env, &market, ONE_YEAR / 2);
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR / 2);
+    let second_half = START + ONE_YEAR / 2;
+    let end = START + ONE_YEAR;
+
+    let estimated =
+        market
+            .client
+            .get_interest_paid(&market.borrower, &market.usdc, &second_half, &end);
+    assert!(estimated > 0);
+    accrue(&env, &market, 0);
+    assert_eq!(
+        market
+            .client
+            .get_interest_paid(&market.borrower, &market.usdc, &second_half, &end),
+        estimated
+    );
+    assert_eq!(
+        market
+            .client
+            .get_interest_paid(&market.borrower, &market.usdc, &START, &end),
+        480 + estimated
+    );
+}
+
+#[test]
+fn test_interest_paid_rejects_empty_period() {
+    let env = Env::default();
+    let market = setup(&env);
+    for (from, to) in [(START, START), (START + 1, START)] {
+        let result =
+            market
+                .client
+                .try_get_interest_paid(&market.borrower, &market.usdc, &from, &to);
+        assert_eq!(result, Err(Ok(InterestHistoryError::InvalidPeriod)));
+    }
+}
@@ -0,0 +1,232 @@
+//! # Interest Mode Tests
+//!
+//! Tests for simple markets, where borrower interest accrues separately, and
+//! compounding markets, where it is capitalized into principal.
+
+use crate::cross_asset::{
+    AssetConfig, AssetPosition, CrossAssetError, InterestMode, BORROW_INDEX_ONE,
+};
+use crate::rate_strategy::RateStrategy;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, IntoVal, Map, Symbol, TryFromVal, Val,
+};
+
+const ONE_YEAR: u64 = 31_536_000;
+
+struct Market<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    borrower: Address,
+}
+
+/// Lists USDC and XLM at a price of 1.0 with a 12% USDC borrow rate at 80%
+/// utilization, in `mode`, and opens a borrower with 20,000 XLM of collateral
+/// and 8,000 USDC of debt
+fn setup(env: &Env, mode: InterestMode) -> Market<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &admin,
+        &Some(usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    client.set_interest_mode(&admin, &Some(usdc.clone()), &mode);
+
+    let supplier = Address::generate(env);
+    StellarAssetClient::new(env, &usdc).mint(&supplier, &10_000);
+    TokenClient::new(env, &usdc).approve(
+        &supplier,
+        &contract_id,
+        &10_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&supplier, &Some(usdc.clone()), &10_000);
+    client.ca_deposit_collateral(&Address::generate(env), &Some(usdc.clone()), &10_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &20_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &8_000);
+
+    Market {
+        client,
+        admin,
+        usdc: Some(usdc),
+        xlm: Some(xlm),
+        borrower,
+    }
+}
+
+/// Advances time, refreshes prices and accrues the borrower's interest by
+/// repaying 1 USDC
+fn accrue(env: &Env, market: &Market, seconds: u64) -> AssetPosition {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+    market
+        .client
+        .update_asset_price(&market.admin, &market.usdc, &10_000_000);
+    market
+        .client
+        .update_asset_price(&market.admin, &market.xlm, &10_000_000);
+    market
+        .client
+        .ca_repay_debt(&market.borrower, &market.usdc, &1)
+}
+
+#[test]
+fn test_simple_mode_accrues_interest_separately() {
+    let env = Env::default();
+    let market = setup(&env, InterestMode::Simple);
+    assert_eq!(
+        market.client.get_interest_mode(&market.usdc),
+        InterestMode::Simple
+    );
+
+    // 12% of 8,000, less the 1 repaid from interest first
+    let position = accrue(&env, &market, ONE_YEAR);
+    assert_eq!(position.debt_principal, 8_000);
+    assert_eq!(position.accrued_interest, 959);
+}
+
+#[test]
+fn test_compounding_mode_capitalizes_interest() {
+    let env = Env::default();
+    let market = setup(&env, InterestMode::Compounding);
+    assert_eq!(
+        market.client.get_interest_mode(&market.usdc),
+        InterestMode::Compounding
+    );
+
+    let position = accrue(&env, &market, ONE_YEAR);
+    assert_eq!(position.debt_principal, 8_959);
+    assert_eq!(position.accrued_interest, 0);
+}
+
+#[test]
+fn test_compounding_charges_interest_on_interest() {
+    let debt_after_two_accruals = |mode| {
+        let env = Env::default();
+        let market = setup(&env, mode);
+        accrue(&env, &market, ONE_YEAR / 2);
+        let position = accrue(&env, &market, ONE_YEAR / 2);
+        position.debt_principal + position.accrued_interest
+    };
+
+    assert!(
+        debt_after_two_accruals(InterestMode::Compounding)
+            > debt_after_two_accruals(InterestMode::Simple)
+    );
+}
+
+#[test]
+fn test_both_modes_share_repayment_and_liquidation() {
+    let debt_after_liquidation = |mode| {
+        let env = Env::default();
+        let market = setup(&env, mode);
+        accrue(&env, &market, ONE_YEAR);
+        // Weighted collateral falls to 7,500 against 8,959 of debt
+        market
+            .client
+            .update_asset_price(&market.admin, &market.xlm, &5_000_000);
+        let liquidator = Address::generate(&env);
+        market
+            .client
+            .ca_deposit_collateral(&liquidator, &market.usdc, &1_000);
+        let result = market.client.ca_liquidate(
+            &liquidator,
+            &market.borrower,
+            &market.usdc,
+            &vec![&env, (market.xlm.clone(), 1_000)],
+        );
+        assert_eq!(result.debt_repaid, 1_000);
+        let position = market
+            .client
+            .get_user_asset_position(&market.borrower, &market.usdc);
+        position.debt_principal + position.accrued_interest
+    };
+
+    assert_eq!(debt_after_liquidation(InterestMode::Simple), 7_959);
+    assert_eq!(debt_after_liquidation(InterestMode::Compounding), 7_959);
+}
+
+#[test]
+fn test_accrual_advances_borrow_index() {
+    let env = Env::default();
+    let market = setup(&env, InterestMode::Simple);
+    assert_eq!(
+        market.client.get_borrow_index(&market.usdc),
+        BORROW_INDEX_ONE
+    );
+
+    // The accrual's event reports the new borrow index; nothing is credited
+    // to the pool
+    accrue(&env, &market, ONE_YEAR);
+    let expected_topics = (Symbol::new(&env, "interest_accrued_event"),).into_val(&env);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| *topics == expected_topics)
+        .unwrap();
+    let fields = Map::<Symbol, Val>::try_from_val(&env, &data).unwrap();
+    let field = |name: &str| {
+        i128::try_from_val(&env, &fields.get(Symbol::new(&env, name)).unwrap()).unwrap()
+    };
+    assert_eq!(field("borrow_interest"), 960);
+    assert_eq!(field("reserves"), 0);
+    assert_eq!(field("borrow_index"), 1_120_000_000);
+    assert_eq!(field("liquidity_index"), 1_000_000_000);
+    assert_eq!(market.client.get_borrow_index(&market.usdc), 1_120_000_000);
+}
+
+#[test]
+fn test_set_interest_mode_rejections() {
+    let env = Env::default();
+    let market = setup(&env, InterestMode::Simple);
+
+    let result = market.client.try_set_interest_mode(
+        &Address::generate(&env),
+        &market.usdc,
+        &InterestMode::Compounding,
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = market.client.try_set_interest_mode(
+        &market.admin,
+        &Some(Address::generate(&env)),
+        &InterestMode::Compounding,
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+}
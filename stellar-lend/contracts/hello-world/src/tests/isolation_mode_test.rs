@@ -0,0 +1,143 @@
+//! # Isolation Mode Tests
+//!
+//! Tests that isolated collateral cannot be mixed with other collateral and
+//! that positions backed by it stay under the asset's debt ceiling.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+/// Lists USDC and a risky asset at a price of 1.0, isolates the risky asset
+/// with a debt ceiling of 1,000 and supplies 10,000 USDC to the pool
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let risky = create_token(env);
+    for asset in [&usdc, &risky] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_isolation_mode(&admin, &Some(risky.clone()), &Some(1_000));
+
+    let supplier = funded_user(env, &contract_id, &usdc, 10_000);
+    client.supply(&supplier, &Some(usdc.clone()), &10_000);
+    (contract_id, client, admin, usdc, risky)
+}
+
+/// Mints `amount` of `asset` to a new user and approves the contract
+fn funded_user(env: &Env, contract_id: &Address, asset: &Address, amount: i128) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, asset).mint(&user, &amount);
+    TokenClient::new(env, asset).approve(
+        &user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    user
+}
+
+#[test]
+fn test_isolated_collateral_cannot_be_mixed() {
+    let env = Env::default();
+    let (_, client, _, usdc, risky) = setup(&env);
+    let usdc = Some(usdc);
+    let risky = Some(risky);
+
+    let isolated = Address::generate(&env);
+    client.ca_deposit_collateral(&isolated, &risky, &5_000);
+    client.ca_deposit_collateral(&isolated, &risky, &1_000);
+    let result = client.try_ca_deposit_collateral(&isolated, &usdc, &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::IsolationConflict)));
+
+    let mixed = Address::generate(&env);
+    client.ca_deposit_collateral(&mixed, &usdc, &1_000);
+    let result = client.try_ca_deposit_collateral(&mixed, &risky, &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::IsolationConflict)));
+}
+
+#[test]
+fn test_savings_deposits_do_not_break_isolation() {
+    let env = Env::default();
+    let (_, client, _, usdc, risky) = setup(&env);
+    let usdc = Some(usdc);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(risky), &5_000);
+
+    client.set_use_as_collateral(&user, &usdc, &false);
+    client.ca_deposit_collateral(&user, &usdc, &1_000);
+    let result = client.try_set_use_as_collateral(&user, &usdc, &true);
+    assert_eq!(result, Err(Ok(CrossAssetError::IsolationConflict)));
+}
+
+#[test]
+fn test_borrows_against_isolated_collateral_respect_debt_ceiling() {
+    let env = Env::default();
+    let (_, client, admin, usdc, risky) = setup(&env);
+    let usdc = Some(usdc);
+    let risky = Some(risky);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &risky, &5_000);
+
+    // Borrowing power is 3,750, but the ceiling stops the position at 1,000
+    client.ca_borrow_asset(&user, &usdc, &1_000);
+    let result = client.try_ca_borrow_asset(&user, &usdc, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtCeilingExceeded)));
+    assert_eq!(
+        client.get_user_asset_position(&user, &usdc).debt_principal,
+        1_000
+    );
+
+    client.set_isolation_mode(&admin, &risky, &None);
+    assert_eq!(client.get_isolation_debt_ceiling(&risky), None);
+    client.ca_borrow_asset(&user, &usdc, &500);
+}
+
+#[test]
+#[should_panic(expected = "Deposit error: AssetIsolated")]
+fn test_core_deposit_of_isolated_asset_fails() {
+    let env = Env::default();
+    let (contract_id, client, _, _, risky) = setup(&env);
+    let user = funded_user(&env, &contract_id, &risky, 1_000);
+    client.deposit_collateral(&user, &Some(risky), &1_000);
+}
+
+#[test]
+fn test_set_isolation_mode_rejections() {
+    let env = Env::default();
+    let (_, client, admin, usdc, _) = setup(&env);
+    let usdc = Some(usdc);
+
+    let result = client.try_set_isolation_mode(&Address::generate(&env), &usdc, &Some(1_000));
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = client.try_set_isolation_mode(&admin, &Some(Address::generate(&env)), &Some(1));
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+    let result = client.try_set_isolation_mode(&admin, &usdc, &Some(-1));
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    assert_eq!(client.get_isolation_debt_ceiling(&usdc), None);
+}
@@ -0,0 +1,457 @@
+//! # Lending Pool Tests
+//!
+//! Tests for supply-side pool shares and the borrower interest credited to
+//! them. Interest reaches a pool only when a core borrower repays it in the
+//! pool's token; interest on cross-asset debt is never credited.
+
+use crate::cross_asset::{AssetConfig, BORROW_INDEX_ONE};
+use crate::deposit::{DepositDataKey, Position};
+use crate::lending_pool::{PoolError, PoolState, ProtocolRevenue, LIQUIDITY_INDEX_ONE};
+use crate::rate_strategy::RateStrategy;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, IntoVal, Map, Symbol, TryFromVal, Val,
+};
+
+const ONE_YEAR: u64 = 31_536_000;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+}
+
+/// Registers USDC (a token contract) and XLM at a price of 1.0 with a 10%
+/// reserve factor, and a 12% borrow rate at 80% utilization on USDC
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Market) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address(),
+        xlm: create_token(env),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &market.admin,
+        &Some(market.usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    (contract_id, client, market)
+}
+
+/// Mints `amount` USDC to a new supplier and approves the contract
+fn funded_supplier(env: &Env, contract_id: &Address, market: &Market, amount: i128) -> Address {
+    let supplier = Address::generate(env);
+    StellarAssetClient::new(env, &market.usdc).mint(&supplier, &amount);
+    TokenClient::new(env, &market.usdc).approve(
+        &supplier,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    supplier
+}
+
+/// Has a new core borrower repay `interest` of accrued interest in `asset`,
+/// which must be a stellar asset contract
+pub(crate) fn repay_core_interest(
+    env: &Env,
+    client: &HelloContractClient,
+    asset: &Address,
+    interest: i128,
+) {
+    let borrower = Address::generate(env);
+    StellarAssetClient::new(env, asset).mint(&borrower, &interest);
+    TokenClient::new(env, asset).approve(
+        &borrower,
+        &client.address,
+        &interest,
+        &(env.ledger().sequence() + 100),
+    );
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(
+            &DepositDataKey::Position(borrower.clone()),
+            &Position {
+                collateral: 0,
+                debt: 8_000,
+                borrow_interest: interest,
+                last_accrual_time: env.ledger().timestamp(),
+            },
+        );
+    });
+    client.repay_debt(&borrower, &Some(asset.clone()), &interest);
+}
+
+/// Repays a year of interest at 12% on 8,000 USDC: 960 USDC
+fn accrue_one_year(env: &Env, client: &HelloContractClient, market: &Market) {
+    repay_core_interest(env, client, &market.usdc, 960);
+}
+
+/// Moves `amount` USDC out of the contract, as lending it to core borrowers
+/// does
+fn lend_out(env: &Env, contract_id: &Address, market: &Market, amount: i128) {
+    TokenClient::new(env, &market.usdc).transfer(contract_id, Address::generate(env), &amount);
+}
+
+#[test]
+fn test_first_supply_mints_shares_one_to_one() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 10_000);
+
+    assert_eq!(client.supply(&supplier, &usdc, &10_000), 10_000);
+
+    assert_eq!(client.get_supply_shares(&supplier, &usdc), 10_000);
+    assert_eq!(client.get_supply_balance(&supplier, &usdc), 10_000);
+    assert_eq!(client.get_liquidity_index(&usdc), LIQUIDITY_INDEX_ONE);
+    assert_eq!(
+        client.get_pool_state(&usdc),
+        PoolState {
+            total_liquidity: 10_000,
+            total_shares: 10_000,
+            reserves: 0,
+        }
+    );
+    let token = TokenClient::new(&env, &market.usdc);
+    assert_eq!(token.balance(&supplier), 0);
+    assert_eq!(token.balance(&contract_id), 10_000);
+}
+
+#[test]
+fn test_interest_raises_liquidity_index() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 10_000);
+    client.supply(&supplier, &usdc, &10_000);
+
+    accrue_one_year(&env, &client, &market);
+
+    // 960 of interest: 10% to reserves, the rest to suppliers
+    assert_eq!(
+        client.get_pool_state(&usdc),
+        PoolState {
+            total_liquidity: 10_864,
+            total_shares: 10_000,
+            reserves: 96,
+        }
+    );
+    assert_eq!(client.get_liquidity_index(&usdc), 1_086_400_000);
+    assert_eq!(client.get_supply_balance(&supplier, &usdc), 10_864);
+}
+
+#[test]
+fn test_cross_asset_interest_is_not_credited() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 10_000);
+    client.supply(&supplier, &usdc, &10_000);
+
+    // Cross-asset debt moves no tokens, so its interest backs no redemption
+    client.ca_deposit_collateral(&Address::generate(&env), &usdc, &10_000);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(market.xlm.clone()), &100_000);
+    client.ca_borrow_asset(&borrower, &usdc, &8_000);
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+    client.update_asset_price(&market.admin, &usdc, &10_000_000);
+    client.update_asset_price(&market.admin, &Some(market.xlm.clone()), &10_000_000);
+    client.ca_repay_debt(&borrower, &usdc, &1);
+
+    assert_eq!(
+        client.get_pool_state(&usdc),
+        PoolState {
+            total_liquidity: 10_000,
+            total_shares: 10_000,
+            reserves: 0,
+        }
+    );
+    assert_eq!(client.get_liquidity_index(&usdc), LIQUIDITY_INDEX_ONE);
+}
+
+#[test]
+fn test_interest_accrual_emits_event() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 10_000);
+    client.supply(&supplier, &usdc, &10_000);
+
+    accrue_one_year(&env, &client, &market);
+
+    let expected_topics = (Symbol::new(&env, "interest_accrued_event"),).into_val(&env);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(contract, topics, _)| *contract == contract_id && *topics == expected_topics)
+        .unwrap();
+    let fields = Map::<Symbol, Val>::try_from_val(&env, &data).unwrap();
+    let field = |name: &str| {
+        i128::try_from_val(&env, &fields.get(Symbol::new(&env, name)).unwrap()).unwrap()
+    };
+    assert_eq!(field("borrow_interest"), 960);
+    assert_eq!(field("reserves"), 96);
+    assert_eq!(field("borrow_index"), BORROW_INDEX_ONE);
+    assert_eq!(field("liquidity_index"), 1_086_400_000);
+}
+
+#[test]
+fn test_later_supplier_receives_fewer_shares() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let early = funded_supplier(&env, &contract_id, &market, 10_000);
+    client.supply(&early, &usdc, &10_000);
+    accrue_one_year(&env, &client, &market);
+
+    let late = funded_supplier(&env, &contract_id, &market, 10_864);
+    assert_eq!(client.supply(&late, &usdc, &10_864), 10_000);
+
+    // Both suppliers hold the same share of the pool
+    assert_eq!(client.get_supply_balance(&early, &usdc), 10_864);
+    assert_eq!(client.get_supply_balance(&late, &usdc), 10_864);
+}
+
+#[test]
+fn test_redeem_pays_out_interest() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 10_000);
+    client.supply(&supplier, &usdc, &10_000);
+    accrue_one_year(&env, &client, &market);
+
+    assert_eq!(client.redeem(&supplier, &usdc, &5_000), 5_432);
+    assert_eq!(client.redeem(&supplier, &usdc, &5_000), 5_432);
+
+    assert_eq!(
+        TokenClient::new(&env, &market.usdc).balance(&supplier),
+        10_864
+    );
+    assert_eq!(client.get_supply_shares(&supplier, &usdc), 0);
+    assert_eq!(
+        client.get_pool_state(&usdc),
+        PoolState {
+            total_liquidity: 0,
+            total_shares: 0,
+            reserves: 96,
+        }
+    );
+}
+
+#[test]
+fn test_redeem_insufficient_liquidity() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 10_000);
+    client.supply(&supplier, &usdc, &10_000);
+    accrue_one_year(&env, &client, &market);
+
+    // Liquidity lent out cannot be redeemed until it is repaid
+    lend_out(&env, &contract_id, &market, 1_000);
+    let result = client.try_redeem(&supplier, &usdc, &10_000);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientLiquidity)));
+    assert_eq!(client.get_supply_shares(&supplier, &usdc), 10_000);
+}
+
+#[test]
+fn test_redeem_insufficient_shares() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 1_000);
+    client.supply(&supplier, &usdc, &1_000);
+
+    let result = client.try_redeem(&supplier, &usdc, &1_001);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientShares)));
+
+    let result = client.try_redeem(&Address::generate(&env), &usdc, &1);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientShares)));
+}
+
+#[test]
+fn test_invalid_amounts_rejected() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let supplier = funded_supplier(&env, &contract_id, &market, 1_000);
+
+    let result = client.try_supply(&supplier, &usdc, &0);
+    assert_eq!(result, Err(Ok(PoolError::InvalidAmount)));
+    let result = client.try_redeem(&supplier, &usdc, &-1);
+    assert_eq!(result, Err(Ok(PoolError::InvalidAmount)));
+}
+
+#[test]
+fn test_supply_unconfigured_asset() {
+    let env = create_test_env();
+    let (_, client, _) = setup(&env);
+
+    let result = client.try_supply(
+        &Address::generate(&env),
+        &Some(Address::generate(&env)),
+        &100,
+    );
+    assert_eq!(result, Err(Ok(PoolError::AssetNotConfigured)));
+}
+
+#[test]
+fn test_interest_without_suppliers_goes_to_reserves() {
+    let env = create_test_env();
+    let (_, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+
+    accrue_one_year(&env, &client, &market);
+
+    assert_eq!(
+        client.get_pool_state(&usdc),
+        PoolState {
+            total_liquidity: 0,
+            total_shares: 0,
+            reserves: 960,
+        }
+    );
+    assert_eq!(client.get_liquidity_index(&usdc), LIQUIDITY_INDEX_ONE);
+}
+
+#[test]
+fn test_withdraw_reserves_to_treasury() {
+    let env = create_test_env();
+    let (_, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    accrue_one_year(&env, &client, &market);
+
+    let treasury = Address::generate(&env);
+    client.withdraw_reserves(&market.admin, &usdc, &treasury, &500);
+
+    assert_eq!(TokenClient::new(&env, &market.usdc).balance(&treasury), 500);
+    assert_eq!(client.get_reserves(&usdc), 460);
+    // Accrual is cumulative; withdrawals do not reduce it
+    assert_eq!(
+        client.get_protocol_report().metrics.total_reserves_accrued,
+        960
+    );
+}
+
+#[test]
+fn test_withdraw_reserves_errors() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let treasury = Address::generate(&env);
+    accrue_one_year(&env, &client, &market);
+
+    let result = client.try_withdraw_reserves(&market.admin, &usdc, &treasury, &0);
+    assert_eq!(result, Err(Ok(PoolError::InvalidAmount)));
+    let result = client.try_withdraw_reserves(&market.admin, &usdc, &treasury, &961);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientReserves)));
+    // Reserves lent out cannot be withdrawn until they are repaid
+    lend_out(&env, &contract_id, &market, 1);
+    let result = client.try_withdraw_reserves(&market.admin, &usdc, &treasury, &960);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientLiquidity)));
+    assert_eq!(client.get_reserves(&usdc), 960);
+}
+
+#[test]
+fn test_withdraw_reserves_requires_admin() {
+    let env = create_test_env();
+    let (_, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    accrue_one_year(&env, &client, &market);
+
+    env.set_auths(&[]);
+    let result = client.try_withdraw_reserves(&market.admin, &usdc, &Address::generate(&env), &100);
+    assert!(result.is_err());
+    assert_eq!(client.get_reserves(&usdc), 960);
+}
+
+#[test]
+fn test_claim_revenue_tracks_income_and_claims() {
+    let env = create_test_env();
+    let (_, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    accrue_one_year(&env, &client, &market);
+
+    let treasury = Address::generate(&env);
+    client.withdraw_reserves(&market.admin, &usdc, &treasury, &100);
+    assert_eq!(client.claim_revenue(&market.admin, &usdc, &treasury), 860);
+
+    assert_eq!(TokenClient::new(&env, &market.usdc).balance(&treasury), 960);
+    assert_eq!(client.get_reserves(&usdc), 0);
+    assert_eq!(
+        client.get_protocol_revenue(&usdc),
+        ProtocolRevenue {
+            reserve_interest: 960,
+            flash_loan_fees: 0,
+            claimed: 960,
+        }
+    );
+    assert_eq!(
+        client.get_protocol_revenue(&Some(market.xlm.clone())),
+        ProtocolRevenue::default()
+    );
+    assert_eq!(
+        client.get_protocol_report().metrics.total_revenue_claimed,
+        960
+    );
+}
+
+#[test]
+fn test_claim_revenue_errors() {
+    let env = create_test_env();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let treasury = Address::generate(&env);
+
+    let result = client.try_claim_revenue(&market.admin, &usdc, &treasury);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientReserves)));
+    accrue_one_year(&env, &client, &market);
+    let result = client.try_claim_revenue(&Address::generate(&env), &usdc, &treasury);
+    assert_eq!(result, Err(Ok(PoolError::Unauthorized)));
+    // Reserves lent out cannot be claimed until they are repaid
+    lend_out(&env, &contract_id, &market, 1);
+    let result = client.try_claim_revenue(&market.admin, &usdc, &treasury);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientLiquidity)));
+    assert_eq!(client.get_protocol_revenue(&usdc).claimed, 0);
+}
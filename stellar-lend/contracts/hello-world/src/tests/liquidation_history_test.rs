@@ -0,0 +1,173 @@
+//! # Liquidation History Tests
+//!
+//! Tests that liquidations are logged in order of execution, one record per
+//! seizure, and that per-liquidator totals add up across liquidations.
+
+use crate::cross_asset::{AssetConfig, AssetKey};
+use crate::liquidation_history::LiquidationHistoryError;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+    eth: Address,
+}
+
+/// Registers USDC, XLM and ETH at a price of 1.0 with a 75% collateral factor
+/// and opens a borrower with 1,000 XLM and 1,000 ETH of collateral and 1,400
+/// USDC of debt, then halves the XLM price so it can be liquidated
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: create_token(env),
+        xlm: create_token(env),
+        eth: create_token(env),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm, &market.eth] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(market.xlm.clone()), &1_000);
+    client.ca_deposit_collateral(&borrower, &Some(market.eth.clone()), &1_000);
+    client.ca_borrow_asset(&borrower, &Some(market.usdc.clone()), &1_400);
+    client.update_asset_price(&market.admin, &Some(market.xlm.clone()), &5_000_000);
+    (client, market, borrower)
+}
+
+#[test]
+fn test_split_seizure_logs_one_record_per_asset() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &Some(market.usdc.clone()), &1_000);
+    assert_eq!(client.get_liquidation_count(), 0);
+
+    let result = client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![
+            &env,
+            (Some(market.xlm.clone()), 200),
+            (Some(market.eth.clone()), 300),
+        ],
+    );
+
+    assert_eq!(client.get_liquidation_count(), 2);
+    let history = client.get_liquidation_history(&10, &0);
+    assert_eq!(history.len(), 2);
+    for (i, (asset, seized)) in result.seized.iter().enumerate() {
+        let record = history.get(i as u32).unwrap();
+        assert_eq!(record.liquidator, liquidator);
+        assert_eq!(record.borrower, borrower);
+        assert_eq!(record.debt_asset, Some(market.usdc.clone()));
+        assert_eq!(record.collateral_asset, asset);
+        assert_eq!(record.collateral_seized, seized);
+        assert_eq!(record.timestamp, 1_000);
+    }
+    assert_eq!(history.get(0).unwrap().debt_repaid, 200);
+    assert_eq!(history.get(1).unwrap().debt_repaid, 300);
+}
+
+#[test]
+fn test_liquidator_stats_accumulate() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &Some(market.usdc.clone()), &1_000);
+    let usdc = AssetKey::Token(market.usdc.clone());
+    let eth = AssetKey::Token(market.eth.clone());
+
+    let stats = client.get_liquidator_stats(&liquidator);
+    assert_eq!(stats.liquidations, 0);
+    assert!(stats.debt_repaid.is_empty() && stats.collateral_seized.is_empty());
+
+    let mut seized = 0;
+    for timestamp in [100, 200] {
+        env.ledger().with_mut(|li| li.timestamp = timestamp);
+        client.update_asset_price(&market.admin, &Some(market.usdc.clone()), &10_000_000);
+        client.update_asset_price(&market.admin, &Some(market.eth.clone()), &10_000_000);
+        client.update_asset_price(&market.admin, &Some(market.xlm.clone()), &5_000_000);
+        let result = client.ca_liquidate(
+            &liquidator,
+            &borrower,
+            &Some(market.usdc.clone()),
+            &vec![&env, (Some(market.eth.clone()), 100)],
+        );
+        seized += result.seized.get(0).unwrap().1;
+    }
+
+    let stats = client.get_liquidator_stats(&liquidator);
+    assert_eq!(stats.liquidations, 2);
+    assert_eq!(stats.debt_repaid.get(usdc), Some(200));
+    assert_eq!(stats.collateral_seized.get(eth), Some(seized));
+    assert_eq!(stats.last_liquidation, 200);
+    assert_eq!(
+        client
+            .get_liquidator_stats(&Address::generate(&env))
+            .liquidations,
+        0
+    );
+}
+
+#[test]
+fn test_history_pages_and_invalid_limits() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &Some(market.usdc.clone()), &1_000);
+    client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(market.usdc.clone()),
+        &vec![
+            &env,
+            (Some(market.xlm.clone()), 100),
+            (Some(market.eth.clone()), 100),
+            (Some(market.eth.clone()), 100),
+        ],
+    );
+
+    let page = client.get_liquidation_history(&2, &1);
+    assert_eq!(page.len(), 2);
+    assert_eq!(
+        page.get(0).unwrap().collateral_asset,
+        Some(market.eth.clone())
+    );
+    assert!(client.get_liquidation_history(&2, &3).is_empty());
+
+    for limit in [0, 51] {
+        let result = client.try_get_liquidation_history(&limit, &0);
+        assert_eq!(result, Err(Ok(LiquidationHistoryError::InvalidLimit)));
+    }
+}
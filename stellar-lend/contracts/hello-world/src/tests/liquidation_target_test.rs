@@ -0,0 +1,148 @@
+//! # Liquidation Target Tests
+//!
+//! Tests that, with a target health factor set, liquidations repay only
+//! enough debt to restore the position to it.
+
+use crate::cross_asset::AssetConfig;
+use crate::deposit::{DepositDataKey, Position};
+use crate::risk_management::{LiquidationIncentiveCurve, RiskManagementError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+fn setup(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+/// Core position holding `collateral` of native collateral against `debt`
+fn open_core_position(env: &Env, contract_id: &Address, collateral: i128, debt: i128) -> Address {
+    let borrower = Address::generate(env);
+    env.as_contract(contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::CollateralBalance(borrower.clone()),
+            &collateral,
+        );
+        env.storage().persistent().set(
+            &DepositDataKey::Position(borrower.clone()),
+            &Position {
+                collateral,
+                debt,
+                borrow_interest: 0,
+                last_accrual_time: env.ledger().timestamp(),
+            },
+        );
+    });
+    borrower
+}
+
+#[test]
+fn test_core_liquidation_stops_at_target() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    // A small incentive, so repaying debt improves a 104% collateral ratio
+    client.set_liquidation_incentive_curve(
+        &admin,
+        &Some(LiquidationIncentiveCurve {
+            min_incentive_bps: 0,
+            max_incentive_bps: 200,
+            max_bonus_health_factor: 5_000,
+        }),
+    );
+    client.set_liquidation_target(&admin, &10_100);
+    let borrower = open_core_position(&env, &contract_id, 1_040, 1_000);
+
+    // The close factor allows 500, but 341 restores a 106.05% ratio
+    let (debt_liquidated, collateral_seized, _) =
+        client.liquidate(&Address::generate(&env), &borrower, &None, &None, &500);
+    assert_eq!(debt_liquidated, 341);
+    assert_eq!(collateral_seized, 341);
+    assert!((1_040 - collateral_seized) * 10_000 / (1_000 - debt_liquidated) >= 10_605);
+}
+
+#[test]
+fn test_core_liquidation_uncapped_when_repaying_cannot_help() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    client.set_liquidation_target(&admin, &10_500);
+    // At a 100% ratio, each repayment releases 110% of collateral
+    let borrower = open_core_position(&env, &contract_id, 1_000, 1_000);
+
+    let (debt_liquidated, _, _) =
+        client.liquidate(&Address::generate(&env), &borrower, &None, &None, &500);
+    assert_eq!(debt_liquidated, 500);
+}
+
+#[test]
+fn test_cross_asset_liquidation_stops_at_target() {
+    let env = Env::default();
+    let (_, admin, client) = setup(&env);
+    client.initialize_ca(&admin);
+    let usdc = create_token(&env);
+    let xlm = create_token(&env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_liquidation_target(&admin, &10_500);
+
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &2_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &1_400);
+    // Weighted collateral falls to 1,350 against 1,400 of debt
+    client.update_asset_price(&admin, &Some(xlm.clone()), &9_000_000);
+
+    // The close factor allows 700, but about 534 restores the target, so the
+    // second seizure is cut short
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &Some(usdc.clone()), &1_000);
+    let result = client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(usdc.clone()),
+        &vec![&env, (Some(xlm.clone()), 300), (Some(xlm.clone()), 400)],
+    );
+    assert!((534..=540).contains(&result.debt_repaid));
+    assert!((10_500..10_550).contains(&result.health_factor));
+    assert_eq!(
+        result.health_factor,
+        client.get_user_position_summary(&borrower).health_factor
+    );
+}
+
+#[test]
+fn test_set_liquidation_target_rejections() {
+    let env = Env::default();
+    let (_, admin, client) = setup(&env);
+    assert_eq!(client.get_liquidation_target(), 0);
+
+    let result = client.try_set_liquidation_target(&Address::generate(&env), &10_500);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    for target in [-1, 10_000, 20_001] {
+        let result = client.try_set_liquidation_target(&admin, &target);
+        assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+    }
+
+    client.set_liquidation_target(&admin, &20_000);
+    assert_eq!(client.get_liquidation_target(), 20_000);
+    client.set_liquidation_target(&admin, &0);
+    assert_eq!(client.get_liquidation_target(), 0);
+}
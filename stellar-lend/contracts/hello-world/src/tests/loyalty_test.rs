@@ -0,0 +1,101 @@
+//! # Loyalty Tier Tests
+//!
+//! Tests that core activity moves users through the loyalty tiers, that the
+//! tier's discount comes off their borrow rate, and that malformed schedules
+//! are rejected.
+
+use crate::loyalty::{LoyaltyError, LoyaltyTier, MAX_LOYALTY_TIERS};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (admin, client)
+}
+
+fn tier(min_transactions: u64, min_volume: i128, discount_bps: i128) -> LoyaltyTier {
+    LoyaltyTier {
+        min_transactions,
+        min_volume,
+        discount_bps,
+    }
+}
+
+fn tier_changed(env: &Env) -> bool {
+    env.events().all().iter().any(|(_, topics, _)| {
+        topics == (Symbol::new(env, "loyalty_tier_changed_event"),).into_val(env)
+    })
+}
+
+#[test]
+fn test_activity_raises_tier_and_discount() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    client.set_loyalty_tiers(
+        &admin,
+        &vec![&env, tier(2, 0, 20), tier(4, 5_000_000_000, 50)],
+    );
+    let user = Address::generate(&env);
+    assert_eq!(client.get_user_tier(&user), 0);
+
+    client.deposit_collateral(&user, &None, &2_000_000_000);
+    assert!(!tier_changed(&env));
+    client.borrow_asset(&user, &None, &1_000_000_000);
+    assert!(tier_changed(&env));
+    assert_eq!(client.get_user_tier(&user), 1);
+    assert_eq!(
+        client.get_user_borrow_rate(&user),
+        client.get_borrow_rate() - 20
+    );
+
+    // Four transactions, but only 3,000,000,001 of volume
+    client.repay_debt(&user, &None, &1);
+    assert_eq!(client.get_user_tier(&user), 1);
+    client.deposit_collateral(&user, &None, &2_000_000_000);
+    assert!(tier_changed(&env));
+    assert_eq!(client.get_user_tier(&user), 2);
+    assert_eq!(
+        client.get_user_borrow_rate(&user),
+        client.get_borrow_rate() - 50
+    );
+
+    // A new schedule applies at once
+    client.set_loyalty_tiers(&admin, &Vec::new(&env));
+    assert_eq!(client.get_user_tier(&user), 0);
+    assert_eq!(client.get_user_borrow_rate(&user), client.get_borrow_rate());
+}
+
+#[test]
+fn test_set_loyalty_tiers_rejections() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+
+    let result =
+        client.try_set_loyalty_tiers(&Address::generate(&env), &vec![&env, tier(1, 0, 10)]);
+    assert_eq!(result, Err(Ok(LoyaltyError::Unauthorized)));
+
+    let mut too_many = Vec::new(&env);
+    for i in 0..=MAX_LOYALTY_TIERS {
+        too_many.push_back(tier(i as u64, 0, 10));
+    }
+    for tiers in [
+        too_many,
+        vec![&env, tier(1, 0, 10_001)],
+        vec![&env, tier(1, -1, 10)],
+        vec![&env, tier(2, 0, 10), tier(1, 100, 20)],
+        vec![&env, tier(1, 100, 10), tier(1, 100, 20)],
+        vec![&env, tier(1, 0, 20), tier(2, 0, 10)],
+    ] {
+        let result = client.try_set_loyalty_tiers(&admin, &tiers);
+        assert_eq!(result, Err(Ok(LoyaltyError::InvalidTiers)));
+    }
+    assert!(client.get_loyalty_tiers().is_empty());
+}
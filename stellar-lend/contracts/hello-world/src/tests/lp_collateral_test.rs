@@ -0,0 +1,245 @@
+//! # LP Share Collateral Tests
+//!
+//! Tests that pool share tokens are listed as collateral at the pool's fair
+//! value per share with a haircut, that the price ignores moves along the
+//! pool's curve, that it follows emergency prices of the underlying assets,
+//! and that it goes stale with the underlying prices. A minimal
+//! mock pool reports configurable reserves and shares.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::lp_collateral::LpPool;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+#[contract]
+pub struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn set_state(env: Env, reserve_a: i128, reserve_b: i128, shares: i128) {
+        env.storage()
+            .instance()
+            .set(&0u32, &(reserve_a, reserve_b, shares));
+    }
+
+    pub fn get_reserves(env: Env) -> (i128, i128) {
+        let (reserve_a, reserve_b, _): (i128, i128, i128) =
+            env.storage().instance().get(&0u32).unwrap();
+        (reserve_a, reserve_b)
+    }
+
+    pub fn total_shares(env: Env) -> i128 {
+        let (_, _, shares): (i128, i128, i128) = env.storage().instance().get(&0u32).unwrap();
+        shares
+    }
+}
+
+struct Setup<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    lp: Address,
+    pool: MockPoolClient<'a>,
+}
+
+/// Lists USDC at 1.0 with an 80% collateral factor and XLM at 0.5 with 75%,
+/// and a USDC/XLM pool holding 4,000,000 USDC and 8,000,000 XLM against
+/// 1,000,000 shares
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = Some(create_token(env));
+    let xlm = Some(create_token(env));
+    for (asset, collateral_factor, price) in [(&usdc, 8000, 10_000_000), (&xlm, 7500, 5_000_000)] {
+        client.initialize_asset(
+            &admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    let pool = MockPoolClient::new(env, &env.register(MockPool, ()));
+    pool.set_state(&4_000_000, &8_000_000, &1_000_000);
+    Setup {
+        client,
+        admin,
+        usdc,
+        xlm,
+        lp: create_token(env),
+        pool,
+    }
+}
+
+fn lp_pool(s: &Setup<'_>) -> LpPool {
+    LpPool {
+        pool: s.pool.address.clone(),
+        asset_a: s.usdc.clone(),
+        asset_b: s.xlm.clone(),
+    }
+}
+
+#[test]
+fn test_listing_prices_shares_at_fair_value_with_haircut() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let config = s.client.list_lp_token(&s.admin, &s.lp, &lp_pool(&s));
+
+    // 2 * sqrt(4,000,000 * 4,000,000) / 1,000,000 shares = 8.0 per share
+    assert_eq!(config.price, 80_000_000);
+    // 75% less a 20% haircut
+    assert_eq!(config.collateral_factor, 6000);
+    assert!(config.can_collateralize);
+    assert!(!config.can_borrow);
+    assert_eq!(s.client.get_lp_pool(&s.lp), Some(lp_pool(&s)));
+    assert_eq!(s.client.get_lp_pool(&s.xlm.clone().unwrap()), None);
+}
+
+#[test]
+fn test_price_ignores_moves_along_the_curve() {
+    let env = Env::default();
+    let s = setup(&env);
+    s.client.list_lp_token(&s.admin, &s.lp, &lp_pool(&s));
+
+    // The same invariant, skewed towards XLM: the reserves are now worth
+    // 10,000,000 at oracle prices, but not if the pool traded at them
+    s.pool.set_state(&2_000_000, &16_000_000, &1_000_000);
+    assert_eq!(s.client.refresh_lp_price(&s.lp), 80_000_000);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        event.1,
+        vec![
+            &env,
+            Symbol::new(&env, "lp_price_refreshed_event").into_val(&env)
+        ]
+    );
+
+    // Fees and deposits that grow the reserves per share raise it
+    s.pool.set_state(&4_400_000, &8_800_000, &1_000_000);
+    assert_eq!(s.client.refresh_lp_price(&s.lp), 88_000_000);
+}
+
+#[test]
+fn test_price_follows_underlying_emergency_prices() {
+    let env = Env::default();
+    let s = setup(&env);
+    s.client.list_lp_token(&s.admin, &s.lp, &lp_pool(&s));
+
+    // XLM at 0.125 in the oracle's 8 decimals: 2 * sqrt(4,000,000 * 1,000,000)
+    let xlm = s.xlm.clone().unwrap();
+    s.client.set_emergency_price(&s.admin, &xlm, &12_500_000);
+    assert_eq!(s.client.refresh_lp_price(&s.lp), 40_000_000);
+
+    s.client.clear_emergency_price(&s.admin, &xlm);
+    assert_eq!(s.client.refresh_lp_price(&s.lp), 80_000_000);
+}
+
+#[test]
+fn test_borrowing_against_shares() {
+    let env = Env::default();
+    let s = setup(&env);
+    let lp = Some(s.lp.clone());
+    s.client.list_lp_token(&s.admin, &s.lp, &lp_pool(&s));
+    let user = Address::generate(&env);
+
+    // 100 shares are worth 800, or 480 at a 60% collateral factor
+    s.client.ca_deposit_collateral(&user, &lp, &100);
+    assert_eq!(
+        s.client.try_ca_borrow_asset(&user, &s.usdc, &500),
+        Err(Ok(CrossAssetError::ExceedsBorrowCapacity))
+    );
+    s.client.ca_borrow_asset(&user, &s.usdc, &480);
+    assert_eq!(
+        s.client.try_ca_borrow_asset(&user, &lp, &1),
+        Err(Ok(CrossAssetError::AssetDisabled))
+    );
+
+    // Only the pool sets the price
+    assert_eq!(
+        s.client.try_update_asset_price(&s.admin, &lp, &90_000_000),
+        Err(Ok(CrossAssetError::PricedByAdapter))
+    );
+}
+
+#[test]
+fn test_price_goes_stale_with_underlying_prices() {
+    let env = Env::default();
+    let s = setup(&env);
+    let lp = Some(s.lp.clone());
+    s.client.list_lp_token(&s.admin, &s.lp, &lp_pool(&s));
+    let user = Address::generate(&env);
+    s.client.ca_deposit_collateral(&user, &lp, &100);
+
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    s.client.update_asset_price(&s.admin, &s.usdc, &10_000_000);
+    assert_eq!(
+        s.client.try_get_user_position_summary(&user),
+        Err(Ok(CrossAssetError::PriceStale))
+    );
+    assert_eq!(
+        s.client.try_refresh_lp_price(&s.lp),
+        Err(Ok(CrossAssetError::PriceStale))
+    );
+
+    s.client.update_asset_price(&s.admin, &s.xlm, &5_000_000);
+    s.client.refresh_lp_price(&s.lp);
+    assert_eq!(
+        s.client
+            .get_user_position_summary(&user)
+            .total_collateral_value,
+        800
+    );
+}
+
+#[test]
+fn test_listing_requires_a_priced_pool() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    assert_eq!(
+        s.client.try_refresh_lp_price(&s.lp),
+        Err(Ok(CrossAssetError::NotLpToken))
+    );
+    assert_eq!(
+        s.client
+            .try_list_lp_token(&Address::generate(&env), &s.lp, &lp_pool(&s)),
+        Err(Ok(CrossAssetError::NotAuthorized))
+    );
+    assert_eq!(
+        s.client.try_list_lp_token(
+            &s.admin,
+            &s.lp,
+            &LpPool {
+                asset_b: Some(create_token(&env)),
+                ..lp_pool(&s)
+            }
+        ),
+        Err(Ok(CrossAssetError::AssetNotConfigured))
+    );
+    s.pool.set_state(&0, &0, &0);
+    assert_eq!(
+        s.client.try_list_lp_token(&s.admin, &s.lp, &lp_pool(&s)),
+        Err(Ok(CrossAssetError::PoolQueryFailed))
+    );
+}
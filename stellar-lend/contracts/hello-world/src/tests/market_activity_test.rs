@@ -0,0 +1,138 @@
+//! # Market Activity Tests
+//!
+//! Tests for per-market last-interaction tracking, `get_market_summary` and
+//! `get_inactive_markets`.
+
+use crate::cross_asset::{AssetConfig, AssetKey, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const DAY: u64 = 86_400;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+fn register(env: &Env, client: &HelloContractClient, admin: &Address, asset: &Address) {
+    client.initialize_asset(
+        admin,
+        &Some(asset.clone()),
+        &AssetConfig {
+            asset: Some(asset.clone()),
+            collateral_factor: 8000,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+}
+
+/// Registers USDC and XLM markets at t = 1,000
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    register(env, &client, &admin, &usdc);
+    register(env, &client, &admin, &xlm);
+    (client, usdc, xlm, admin)
+}
+
+/// Move time forward and refresh prices so positions can still be valued
+fn advance(
+    env: &Env,
+    client: &HelloContractClient,
+    admin: &Address,
+    assets: &[&Address],
+    seconds: u64,
+) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+    for asset in assets {
+        client.update_asset_price(admin, &Some((*asset).clone()), &10_000_000);
+    }
+}
+
+#[test]
+fn test_new_market_idle_since_registration() {
+    let env = create_test_env();
+    let (client, usdc, _, admin) = setup(&env);
+    advance(&env, &client, &admin, &[], 3 * DAY);
+
+    let summary = client.get_market_summary(&Some(usdc.clone()));
+    assert_eq!(summary.asset, AssetKey::Token(usdc));
+    assert_eq!(summary.total_supply, 0);
+    assert_eq!(summary.total_borrow, 0);
+    assert_eq!(summary.last_interaction, 1_000);
+    assert_eq!(summary.idle_seconds, 3 * DAY);
+    assert_eq!(summary.borrow_idle_seconds, 3 * DAY);
+}
+
+#[test]
+fn test_activity_updates_timestamps() {
+    let env = create_test_env();
+    let (client, usdc, _, admin) = setup(&env);
+    let user = Address::generate(&env);
+
+    advance(&env, &client, &admin, &[&usdc], DAY);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    let deposited = client.get_market_summary(&Some(usdc.clone()));
+    assert_eq!(deposited.last_interaction, 1_000 + DAY);
+    assert_eq!(deposited.last_borrow, 1_000);
+    assert_eq!(deposited.total_supply, 1_000);
+
+    advance(&env, &client, &admin, &[&usdc], DAY);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &100);
+    let borrowed = client.get_market_summary(&Some(usdc.clone()));
+    assert_eq!(borrowed.last_interaction, 1_000 + 2 * DAY);
+    assert_eq!(borrowed.last_borrow, 1_000 + 2 * DAY);
+
+    advance(&env, &client, &admin, &[&usdc], DAY);
+    client.ca_repay_debt(&user, &Some(usdc.clone()), &100);
+    let repaid = client.get_market_summary(&Some(usdc.clone()));
+    assert_eq!(repaid.last_interaction, 1_000 + 3 * DAY);
+    assert_eq!(repaid.last_borrow, 1_000 + 2 * DAY);
+    assert_eq!(repaid.borrow_idle_seconds, DAY);
+}
+
+#[test]
+fn test_inactive_markets_filtered_by_threshold() {
+    let env = create_test_env();
+    let (client, usdc, xlm, admin) = setup(&env);
+    let user = Address::generate(&env);
+
+    advance(&env, &client, &admin, &[&usdc], 10 * DAY);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    advance(&env, &client, &admin, &[&usdc], DAY);
+
+    // XLM has been idle for 11 days, USDC for 1
+    let inactive = client.get_inactive_markets(&(7 * DAY));
+    assert_eq!(inactive.len(), 1);
+    assert_eq!(inactive.get(0).unwrap().asset, AssetKey::Token(xlm));
+
+    assert_eq!(client.get_inactive_markets(&DAY).len(), 2);
+    assert_eq!(client.get_inactive_markets(&(30 * DAY)).len(), 0);
+}
+
+#[test]
+fn test_market_summary_unknown_asset() {
+    let env = create_test_env();
+    let (client, _, _, _) = setup(&env);
+
+    let result = client.try_get_market_summary(&Some(Address::generate(&env)));
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+}
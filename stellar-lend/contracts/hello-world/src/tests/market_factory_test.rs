@@ -0,0 +1,67 @@
+//! # Market Factory Tests
+//!
+//! Tests for the isolated market factory's access control and registry.
+//! Deploying a market needs the market wasm, which is not built for
+//! native tests, so deployment itself is covered by network testing.
+
+use crate::market_factory::{IsolatedMarketParams, MarketFactoryError};
+use crate::roles::Role;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Symbol, Vec};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (admin, client)
+}
+
+fn params(env: &Env) -> IsolatedMarketParams {
+    IsolatedMarketParams {
+        admin: Address::generate(env),
+        name: Symbol::new(env, "usdc_eurc"),
+        assets: Vec::new(env),
+    }
+}
+
+#[test]
+fn test_only_protocol_admin_sets_market_wasm() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let hash = BytesN::from_array(&env, &[7; 32]);
+
+    let listing_admin = Address::generate(&env);
+    client.grant_role(&admin, &Role::AssetListingAdmin, &listing_admin);
+    let result = client.try_set_market_wasm(&listing_admin, &hash);
+    assert_eq!(result, Err(Ok(MarketFactoryError::Unauthorized)));
+    assert_eq!(client.get_market_wasm(), None);
+
+    client.set_market_wasm(&admin, &hash);
+    assert_eq!(client.get_market_wasm(), Some(hash));
+}
+
+#[test]
+fn test_create_market_requires_listing_role() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    client.set_market_wasm(&admin, &BytesN::from_array(&env, &[7; 32]));
+
+    let guardian = Address::generate(&env);
+    client.grant_role(&admin, &Role::PauseGuardian, &guardian);
+    let result = client.try_create_isolated_market(&guardian, &params(&env));
+    assert_eq!(result, Err(Ok(MarketFactoryError::Unauthorized)));
+}
+
+#[test]
+fn test_create_market_requires_wasm() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+
+    let result = client.try_create_isolated_market(&admin, &params(&env));
+    assert_eq!(result, Err(Ok(MarketFactoryError::WasmNotSet)));
+    assert_eq!(client.get_isolated_market_count(), 0);
+    assert!(client.get_isolated_markets(&10, &0).is_empty());
+    assert_eq!(client.get_isolated_market(&0), None);
+}
@@ -0,0 +1,81 @@
+//! # Checked Arithmetic Tests
+//!
+//! Tests for the `Amount`, `Shares`, and `Bps` newtypes and the conversion of
+//! math failures into module errors.
+
+use crate::borrow::BorrowError;
+use crate::constants::Bps;
+use crate::math::{Amount, MathError, Shares};
+
+#[test]
+fn test_amount_checked_add_sub() {
+    let a = Amount::new(700);
+    let b = Amount::new(300);
+    assert_eq!(a.checked_add(b), Ok(Amount::new(1_000)));
+    assert_eq!(a.checked_sub(b), Ok(Amount::new(400)));
+    assert_eq!(
+        Amount::new(i128::MAX).checked_add(Amount::new(1)),
+        Err(MathError::Overflow)
+    );
+    assert_eq!(
+        Amount::new(i128::MIN).checked_sub(Amount::new(1)),
+        Err(MathError::Overflow)
+    );
+}
+
+#[test]
+fn test_amount_mul_div() {
+    assert_eq!(Amount::new(1_000).mul_div(3, 4), Ok(Amount::new(750)));
+    // Rounds toward zero
+    assert_eq!(Amount::new(10).mul_div(1, 3), Ok(Amount::new(3)));
+    assert_eq!(
+        Amount::new(1_000).mul_div(1, 0),
+        Err(MathError::DivisionByZero)
+    );
+    assert_eq!(
+        Amount::new(i128::MAX).mul_div(2, 2),
+        Err(MathError::Overflow)
+    );
+}
+
+#[test]
+fn test_ratio_and_bps_apply() {
+    assert_eq!(
+        Amount::new(1_500).ratio_bps(Amount::new(1_000)),
+        Ok(Bps::new(15_000))
+    );
+    assert_eq!(
+        Amount::new(1).ratio_bps(Amount::ZERO),
+        Err(MathError::DivisionByZero)
+    );
+    assert_eq!(
+        Bps::new(8_000).apply(Amount::new(1_000)),
+        Ok(Amount::new(800))
+    );
+}
+
+#[test]
+fn test_shares_to_amount() {
+    let shares = Shares::new(250);
+    assert_eq!(
+        shares.to_amount(Amount::new(2_000), Shares::new(1_000)),
+        Ok(Amount::new(500))
+    );
+    assert_eq!(shares.checked_sub(Shares::new(50)), Ok(Shares::new(200)));
+    assert_eq!(
+        shares.to_amount(Amount::new(2_000), Shares::ZERO),
+        Err(MathError::DivisionByZero)
+    );
+}
+
+#[test]
+fn test_math_error_maps_to_overflow() {
+    assert_eq!(
+        BorrowError::from(MathError::Overflow),
+        BorrowError::Overflow
+    );
+    assert_eq!(
+        BorrowError::from(MathError::DivisionByZero),
+        BorrowError::Overflow
+    );
+}
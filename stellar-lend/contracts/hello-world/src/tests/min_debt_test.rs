@@ -0,0 +1,160 @@
+//! # Minimum Debt Tests
+//!
+//! Tests that borrows and repayments cannot leave a nonzero debt below the
+//! asset's minimum, in cross-asset and core positions.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+/// Lists USDC and XLM at a price of 1.0, sets a USDC minimum debt of 100 and
+/// supplies 10,000 USDC to the pool
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_min_borrow_amount(&admin, &Some(usdc.clone()), &100);
+
+    let supplier = funded_user(env, &contract_id, &usdc, 10_000);
+    client.supply(&supplier, &Some(usdc.clone()), &10_000);
+    (contract_id, client, admin, usdc, xlm)
+}
+
+/// Mints `amount` of `asset` to a new user and approves the contract
+fn funded_user(env: &Env, contract_id: &Address, asset: &Address, amount: i128) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, asset).mint(&user, &amount);
+    TokenClient::new(env, asset).approve(
+        &user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    user
+}
+
+#[test]
+fn test_cross_asset_borrows_must_reach_minimum() {
+    let env = Env::default();
+    let (_, client, _, usdc, xlm) = setup(&env);
+    let usdc = Some(usdc);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm), &20_000);
+    assert_eq!(client.get_min_borrow_amount(&usdc), 100);
+
+    let result = client.try_ca_borrow_asset(&borrower, &usdc, &99);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtBelowMinimum)));
+    client.ca_borrow_asset(&borrower, &usdc, &100);
+    // Once above the minimum, any further borrow is allowed
+    let position = client.ca_borrow_asset(&borrower, &usdc, &1);
+    assert_eq!(position.debt_principal, 101);
+}
+
+#[test]
+fn test_cross_asset_repayments_cannot_leave_dust() {
+    let env = Env::default();
+    let (_, client, _, usdc, xlm) = setup(&env);
+    let usdc = Some(usdc);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm), &20_000);
+    client.ca_borrow_asset(&borrower, &usdc, &1_000);
+
+    let result = client.try_ca_repay_debt(&borrower, &usdc, &901);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtBelowMinimum)));
+    assert_eq!(
+        client.ca_repay_debt(&borrower, &usdc, &900).debt_principal,
+        100
+    );
+    // Overpaying clears the debt
+    assert_eq!(
+        client.ca_repay_debt(&borrower, &usdc, &500).debt_principal,
+        0
+    );
+}
+
+#[test]
+fn test_repay_from_supply_cannot_leave_dust() {
+    let env = Env::default();
+    let (_, client, _, usdc, xlm) = setup(&env);
+    let usdc = Some(usdc);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm), &20_000);
+    client.ca_deposit_collateral(&borrower, &usdc, &950);
+    client.ca_borrow_asset(&borrower, &usdc, &1_000);
+
+    let result = client.try_repay_from_supply(&borrower, &usdc, &950);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtBelowMinimum)));
+    let position = client.repay_from_supply(&borrower, &usdc, &500);
+    assert_eq!(position.debt_principal, 500);
+}
+
+#[test]
+#[should_panic(expected = "Borrow error: DebtBelowMinimum")]
+fn test_core_borrow_below_minimum_fails() {
+    let env = Env::default();
+    let (contract_id, client, _, usdc, _) = setup(&env);
+    let user = funded_user(&env, &contract_id, &usdc, 5_000);
+    client.deposit_collateral(&user, &Some(usdc.clone()), &5_000);
+    client.borrow_asset(&user, &Some(usdc), &50);
+}
+
+#[test]
+#[should_panic(expected = "Repay error: DebtBelowMinimum")]
+fn test_core_repayment_leaving_dust_fails() {
+    let env = Env::default();
+    let (contract_id, client, _, usdc, _) = setup(&env);
+    let usdc = Some(usdc);
+    let user = funded_user(&env, &contract_id, usdc.as_ref().unwrap(), 10_000);
+    client.deposit_collateral(&user, &usdc, &5_000);
+    client.borrow_asset(&user, &usdc, &1_000);
+    client.repay_debt(&user, &usdc, &800);
+
+    client.repay_debt(&user, &usdc, &150);
+}
+
+#[test]
+fn test_set_min_borrow_amount_rejections() {
+    let env = Env::default();
+    let (_, client, admin, usdc, xlm) = setup(&env);
+    let usdc = Some(usdc);
+
+    let result = client.try_set_min_borrow_amount(&Address::generate(&env), &usdc, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = client.try_set_min_borrow_amount(&admin, &Some(Address::generate(&env)), &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+    let result = client.try_set_min_borrow_amount(&admin, &usdc, &-1);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+
+    assert_eq!(client.get_min_borrow_amount(&Some(xlm)), 0);
+    client.set_min_borrow_amount(&admin, &usdc, &0);
+    assert_eq!(client.get_min_borrow_amount(&usdc), 0);
+}
@@ -1,13 +1,87 @@
+pub mod account_sweep_test;
+pub mod admin_simulation_test;
 pub mod analytics_test;
+pub mod asset_caps_test;
+pub mod asset_category_test;
 pub mod asset_config_test;
+pub mod asset_pause_test;
+pub mod asset_registry_test;
+pub mod bad_debt_test;
+pub mod batch_test;
+pub mod borrow_queue_test;
+pub mod borrow_size_test;
+pub mod ca_liquidate_test;
+pub mod cash_ledger_test;
+pub mod category_ceiling_test;
+pub mod collateral_factor_ramp_test;
+pub mod constants_test;
+pub mod contact_hint_test;
+pub mod credit_test;
 pub mod deploy_test;
+pub mod emergency_pause_test;
+pub mod emergency_price_test;
+pub mod events_test;
+pub mod incentive_curve_test;
+#[cfg(feature = "insurance")]
+pub mod insurance_test;
 pub mod interest_accrual_test;
+pub mod interest_history_test;
+pub mod interest_mode_test;
 pub mod interest_rate_test;
+pub mod isolation_mode_test;
+pub mod lending_pool_test;
 pub mod liquidate_test;
+pub mod liquidation_history_test;
+pub mod liquidation_target_test;
+pub mod loyalty_test;
+pub mod lp_collateral_test;
+pub mod market_activity_test;
+#[cfg(feature = "isolated-markets")]
+pub mod market_factory_test;
+pub mod math_test;
+pub mod min_debt_test;
+pub mod ops_metrics_test;
+pub mod oracle_sources_test;
 pub mod oracle_test;
+pub mod position_closure_test;
+pub mod position_transfer_test;
+pub mod positions_batch_test;
+pub mod positions_below_health_test;
+pub mod price_reporter_test;
+pub mod query_batch_test;
+pub mod rate_comparison_test;
+#[cfg(feature = "rate-locks")]
+pub mod rate_lock_test;
+pub mod rate_mode_test;
+pub mod rate_strategy_test;
+pub mod recovery_haircut_test;
+#[cfg(feature = "admin-recovery")]
+pub mod recovery_test;
+pub mod reentrancy_test;
+pub mod repay_from_supply_test;
+pub mod reserve_data_test;
+#[cfg(feature = "rewards")]
+pub mod rewards_test;
+pub mod risk_badge_test;
 pub mod risk_params_test;
+pub mod roles_test;
+pub mod savings_mode_test;
 pub mod security_test;
+pub mod soft_liquidation_test;
+pub mod staker_discount_test;
+pub mod state_export_test;
+pub mod storage_test;
+pub mod storage_tiering_test;
+pub mod stress_position_test;
+pub mod stress_protocol_test;
+pub mod sub_account_test;
+pub mod supply_tenure_test;
+pub mod swap_repay_test;
 pub mod test;
+pub mod test_cross_asset;
+pub mod threshold_migration_test;
+pub mod token_interface_test;
+pub mod ttl_test;
+pub mod upgrade_test;
 pub mod views_test;
-// Cross-asset tests re-enabled when contract exposes full CA API (try_* return Result; get_user_asset_position; try_ca_repay_debt)
-// pub mod test_cross_asset;
+pub mod withdraw_cooldown_test;
@@ -0,0 +1,86 @@
+//! # Operational Metrics Tests
+//!
+//! Tests that user entrypoints count their successful calls and that failed
+//! calls leave the counters untouched.
+
+use crate::deposit::{AssetParams, DepositDataKey};
+use crate::ops_metrics::EntrypointMetrics;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, Symbol,
+};
+
+/// Registers the contract and a token enabled for deposits, and funds a user
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    client.initialize(&Address::generate(env));
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::AssetParams(token.clone()),
+            &AssetParams {
+                deposit_enabled: true,
+                collateral_factor: 7500,
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    });
+
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &token).mint(&user, &1_000);
+    TokenClient::new(env, &token).approve(&user, &contract_id, &10_000, &1_000);
+    (client, token, user)
+}
+
+#[test]
+fn test_calls_are_counted_per_entrypoint() {
+    let env = Env::default();
+    let (client, token, user) = setup(&env);
+    let asset = Some(token);
+    assert!(client.get_ops_metrics().is_empty());
+
+    client.deposit_collateral(&user, &asset, &500);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.deposit_collateral(&user, &asset, &500);
+    client.borrow_asset(&user, &asset, &100);
+
+    let metrics = client.get_ops_metrics();
+    assert_eq!(metrics.len(), 2);
+    assert_eq!(
+        metrics.get(Symbol::new(&env, "deposit_collateral")),
+        Some(EntrypointMetrics {
+            calls: 2,
+            last_call: 2_000,
+        })
+    );
+    assert_eq!(
+        metrics
+            .get(Symbol::new(&env, "borrow_asset"))
+            .unwrap()
+            .calls,
+        1
+    );
+}
+
+#[test]
+fn test_failed_calls_are_not_counted() {
+    let env = Env::default();
+    let (client, token, user) = setup(&env);
+    let asset = Some(token);
+    client.deposit_collateral(&user, &asset, &500);
+
+    assert!(client.try_withdraw_collateral(&user, &asset, &501).is_err());
+    let metrics = client.get_ops_metrics();
+    assert_eq!(metrics.len(), 1);
+    assert!(!metrics.contains_key(Symbol::new(&env, "withdraw_collateral")));
+}
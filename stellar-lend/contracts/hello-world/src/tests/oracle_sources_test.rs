@@ -0,0 +1,297 @@
+//! # Oracle Source Aggregation Tests
+//!
+//! Tests for the per-asset oracle sources whose fresh prices are aggregated by
+//! median. Covers precedence over the single feeds, discarding stale sources,
+//! the fresh source quorum, the per-source deviation guard, source validation
+//! and authorization, the price detail view and pricing cross-asset markets.
+
+use crate::cross_asset::AssetConfig;
+use crate::oracle::{OracleSources, SourcePrice, MAX_ORACLE_SOURCES};
+use crate::roles::Role;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, Vec,
+};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (admin, client)
+}
+
+/// Configures three sources with a quorum of two for the asset and returns
+/// them
+fn setup_sources(
+    env: &Env,
+    admin: &Address,
+    client: &HelloContractClient<'_>,
+    asset: &Address,
+) -> Vec<Address> {
+    let sources = vec![
+        env,
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    client.set_oracle_sources(admin, asset, &sources, &2);
+    sources
+}
+
+fn push(client: &HelloContractClient<'_>, source: &Address, asset: &Address, price: i128) {
+    client.update_price_feed(source, asset, &price, &8, source);
+}
+
+#[test]
+fn test_median_of_sources_is_effective_price() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let sources = setup_sources(&env, &admin, &client, &asset);
+
+    // The primary feed is superseded once a quorum of sources reports
+    client.update_price_feed(&admin, &asset, &90, &8, &admin);
+    push(&client, &sources.get(0).unwrap(), &asset, 100);
+    assert_eq!(client.get_price(&asset), 90);
+
+    push(&client, &sources.get(1).unwrap(), &asset, 104);
+    assert_eq!(client.get_price(&asset), 102);
+
+    // An outlier source cannot move the price past the others
+    push(&client, &sources.get(2).unwrap(), &asset, 1_000);
+    assert_eq!(client.get_price(&asset), 104);
+}
+
+#[test]
+fn test_stale_sources_are_discarded() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let sources = setup_sources(&env, &admin, &client, &asset);
+
+    push(&client, &sources.get(0).unwrap(), &asset, 100);
+    env.ledger().with_mut(|li| li.timestamp += 3_000);
+    push(&client, &sources.get(1).unwrap(), &asset, 102);
+    push(&client, &sources.get(2).unwrap(), &asset, 104);
+    assert_eq!(client.get_price(&asset), 102);
+
+    env.ledger().with_mut(|li| li.timestamp += 601);
+    assert_eq!(client.get_price(&asset), 103);
+
+    // Below the quorum, resolution falls back to the primary feed
+    client.update_price_feed(&admin, &asset, &95, &8, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 3_000);
+    push(&client, &sources.get(0).unwrap(), &asset, 101);
+    assert_eq!(client.get_price(&asset), 95);
+}
+
+#[test]
+fn test_price_detail_reports_each_source() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let sources = setup_sources(&env, &admin, &client, &asset);
+
+    push(&client, &sources.get(0).unwrap(), &asset, 100);
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    push(&client, &sources.get(1).unwrap(), &asset, 110);
+
+    // One fresh source is short of the quorum
+    let detail = client.get_price_detail(&asset);
+    assert_eq!(detail.price, None);
+    assert_eq!(detail.median, None);
+    assert_eq!(detail.min_fresh, 2);
+    assert_eq!(
+        detail.sources,
+        vec![
+            &env,
+            SourcePrice {
+                source: sources.get(0).unwrap(),
+                price: Some(100),
+                last_updated: 10_000,
+                is_fresh: false,
+            },
+            SourcePrice {
+                source: sources.get(1).unwrap(),
+                price: Some(110),
+                last_updated: 13_601,
+                is_fresh: true,
+            },
+            SourcePrice {
+                source: sources.get(2).unwrap(),
+                price: None,
+                last_updated: 0,
+                is_fresh: false,
+            },
+        ]
+    );
+
+    push(&client, &sources.get(2).unwrap(), &asset, 112);
+    let detail = client.get_price_detail(&asset);
+    assert_eq!(detail.price, Some(111));
+    assert_eq!(detail.median, Some(111));
+
+    // With no price at all, the detail still lists the sources
+    let unpriced = Address::generate(&env);
+    client.set_oracle_sources(&admin, &unpriced, &sources, &2);
+    let detail = client.get_price_detail(&unpriced);
+    assert_eq!(detail.price, None);
+    assert_eq!(detail.median, None);
+    assert_eq!(detail.sources.len(), 3);
+}
+
+#[test]
+fn test_source_updates_are_deviation_guarded() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let sources = setup_sources(&env, &admin, &client, &asset);
+    let source = sources.get(0).unwrap();
+
+    push(&client, &source, &asset, 100);
+    let result = client.try_update_price_feed(&source, &asset, &120, &8, &source);
+    assert!(result.is_err());
+
+    // Each source is guarded against its own previous price only
+    push(&client, &sources.get(1).unwrap(), &asset, 120);
+    assert_eq!(client.get_price(&asset), 110);
+    push(&client, &sources.get(2).unwrap(), &asset, 110);
+    assert_eq!(client.get_price(&asset), 110);
+}
+
+#[test]
+fn test_removed_sources_are_ignored() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let sources = setup_sources(&env, &admin, &client, &asset);
+    push(&client, &sources.get(0).unwrap(), &asset, 100);
+    push(&client, &sources.get(1).unwrap(), &asset, 104);
+
+    client.set_oracle_sources(&admin, &asset, &vec![&env, sources.get(1).unwrap()], &1);
+    assert_eq!(client.get_price(&asset), 104);
+    let removed = sources.get(0).unwrap();
+    assert!(client
+        .try_update_price_feed(&removed, &asset, &100, &8, &removed)
+        .is_err());
+
+    client.set_oracle_sources(&admin, &asset, &Vec::new(&env), &0);
+    assert!(client.get_oracle_sources(&asset).sources.is_empty());
+    assert!(client.try_get_price(&asset).is_err());
+}
+
+#[test]
+fn test_set_oracle_sources_validation() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let source = Address::generate(&env);
+
+    assert!(client
+        .try_set_oracle_sources(
+            &admin,
+            &asset,
+            &vec![&env, source.clone(), source.clone()],
+            &1
+        )
+        .is_err());
+    assert!(client
+        .try_set_oracle_sources(&admin, &asset, &vec![&env, client.address.clone()], &1)
+        .is_err());
+
+    // The quorum must be reachable and non-zero
+    assert!(client
+        .try_set_oracle_sources(&admin, &asset, &vec![&env, source.clone()], &0)
+        .is_err());
+    assert!(client
+        .try_set_oracle_sources(&admin, &asset, &vec![&env, source.clone()], &2)
+        .is_err());
+
+    let mut too_many = Vec::new(&env);
+    for _ in 0..=MAX_ORACLE_SOURCES {
+        too_many.push_back(Address::generate(&env));
+    }
+    assert!(client
+        .try_set_oracle_sources(&admin, &asset, &too_many, &3)
+        .is_err());
+    too_many.pop_back();
+    client.set_oracle_sources(&admin, &asset, &too_many, &3);
+    assert_eq!(
+        client.get_oracle_sources(&asset),
+        OracleSources {
+            sources: too_many,
+            min_fresh: 3,
+        }
+    );
+}
+
+#[test]
+fn test_set_oracle_sources_authorization() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let sources = vec![&env, Address::generate(&env)];
+
+    let lister = Address::generate(&env);
+    assert!(client
+        .try_set_oracle_sources(&lister, &asset, &sources, &1)
+        .is_err());
+    client.grant_role(&admin, &Role::AssetListingAdmin, &lister);
+    client.set_oracle_sources(&lister, &asset, &sources, &1);
+    assert_eq!(client.get_oracle_sources(&asset).sources, sources);
+
+    // Holding the role is not enough without the holder's authorization
+    env.set_auths(&[]);
+    assert!(client
+        .try_set_oracle_sources(&admin, &asset, &Vec::new(&env), &0)
+        .is_err());
+    assert_eq!(client.get_oracle_sources(&asset).sources, sources);
+}
+
+#[test]
+fn test_median_prices_cross_asset_market() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    let asset = create_token(&env);
+    client.initialize_ca(&admin);
+    client.initialize_asset(
+        &admin,
+        &Some(asset.clone()),
+        &AssetConfig {
+            asset: Some(asset.clone()),
+            collateral_factor: 8000,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    let sources = setup_sources(&env, &admin, &client, &asset);
+
+    // A single source is below quorum and leaves the market price alone
+    push(&client, &sources.get(0).unwrap(), &asset, 100_000_000);
+    assert_eq!(
+        client.get_asset_config(&Some(asset.clone())).price,
+        10_000_000
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    push(&client, &sources.get(1).unwrap(), &asset, 104_000_000);
+    let config = client.get_asset_config(&Some(asset.clone()));
+    assert_eq!(config.price, 10_200_000);
+    assert_eq!(config.price_updated_at, env.ledger().timestamp());
+}
@@ -69,7 +69,7 @@ fn get_cached_price(env: &Env, contract_id: &Address, asset: &Address) -> Option
     env.as_contract(contract_id, || {
         let key = OracleDataKey::PriceCache(asset.clone());
         env.storage()
-            .persistent()
+            .temporary()
             .get::<OracleDataKey, CachedPrice>(&key)
     })
 }
@@ -80,7 +80,7 @@ fn set_oracle_paused(env: &Env, contract_id: &Address, paused: bool) {
         let pause_key = OracleDataKey::PauseSwitches;
         let mut pause_map: Map<Symbol, bool> = Map::new(env);
         pause_map.set(Symbol::new(env, "pause_oracle"), paused);
-        env.storage().persistent().set(&pause_key, &pause_map);
+        env.storage().instance().set(&pause_key, &pause_map);
     });
 }
 
@@ -525,7 +525,7 @@ fn test_get_price_stale_no_fallback() {
     env.as_contract(&contract_id, || {
         let cache_key = OracleDataKey::PriceCache(asset.clone());
         env.storage()
-            .persistent()
+            .temporary()
             .remove::<OracleDataKey>(&cache_key);
     });
 
@@ -0,0 +1,157 @@
+//! # Position Closure Tests
+//!
+//! Tests that `close_position` repays, withdraws and prunes in one call, and
+//! rolls everything back when the preferred assets cannot cover a step.
+
+use crate::cross_asset::{AssetConfig, AssetKey};
+use crate::deposit::{AssetParams, DepositDataKey, Position};
+use crate::position_closure::ClosureError;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+/// Registers the contract and a token enabled for deposits, and funds a user
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::AssetParams(token.clone()),
+            &AssetParams {
+                deposit_enabled: true,
+                collateral_factor: 7500,
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    });
+
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &token).mint(&user, &1_000);
+    TokenClient::new(env, &token).approve(&user, &contract_id, &10_000, &1_000);
+    (contract_id, client, admin, token, user)
+}
+
+fn stored_position(env: &Env, contract_id: &Address, user: &Address) -> Option<Position> {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DepositDataKey::Position(user.clone()))
+    })
+}
+
+#[test]
+fn test_close_repays_withdraws_and_prunes() {
+    let env = Env::default();
+    let (contract_id, client, _, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000);
+    client.borrow_asset(&user, &asset, &300);
+
+    let closure = client.close_position(&user, &vec![&env, token.clone()]);
+    assert_eq!(closure.debt_repaid, 300);
+    assert_eq!(closure.collateral_withdrawn, 1_000);
+    assert!(closure.asset_debt_repaid.is_empty());
+    assert!(closure.pruned);
+
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "position_closed_event"),).into_val(&env)
+    );
+    assert_eq!(TokenClient::new(&env, &token).balance(&user), 1_000);
+    assert_eq!(stored_position(&env, &contract_id, &user), None);
+}
+
+#[test]
+fn test_close_uses_preferences_in_order() {
+    let env = Env::default();
+    let (contract_id, client, _, token, user) = setup(&env);
+    let empty = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.deposit_collateral(&user, &Some(token.clone()), &400);
+
+    // The contract holds none of the first preference, so the second pays out
+    let closure = client.close_position(&user, &vec![&env, empty.clone(), token.clone()]);
+    assert_eq!(closure.collateral_withdrawn, 400);
+    assert_eq!(TokenClient::new(&env, &token).balance(&user), 1_000);
+    assert_eq!(TokenClient::new(&env, &empty).balance(&user), 0);
+    assert!(stored_position(&env, &contract_id, &user).is_none());
+}
+
+#[test]
+fn test_close_fails_without_enough_preferred_assets() {
+    let env = Env::default();
+    let (contract_id, client, _, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000);
+    client.borrow_asset(&user, &asset, &300);
+    // Spend part of the borrowed tokens so the debt cannot be covered
+    TokenClient::new(&env, &token).transfer(&user, Address::generate(&env), &100);
+
+    assert_eq!(
+        client.try_close_position(&user, &vec![&env, token.clone()]),
+        Err(Ok(ClosureError::InsufficientRepayBalance))
+    );
+    assert_eq!(
+        client.try_close_position(&user, &Vec::new(&env)),
+        Err(Ok(ClosureError::InsufficientRepayBalance))
+    );
+    // Nothing was repaid
+    let position = stored_position(&env, &contract_id, &user).unwrap();
+    assert_eq!(position.debt, 300);
+    assert_eq!(TokenClient::new(&env, &token).balance(&user), 200);
+
+    let other = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&other, &50);
+    TokenClient::new(&env, &token).approve(&other, &contract_id, &50, &1_000);
+    client.deposit_collateral(&other, &asset, &50);
+    assert_eq!(
+        client.try_close_position(&other, &Vec::new(&env)),
+        Err(Ok(ClosureError::InsufficientLiquidity))
+    );
+}
+
+#[test]
+fn test_close_settles_cross_asset_positions() {
+    let env = Env::default();
+    let (_, client, admin, token, user) = setup(&env);
+    client.initialize_ca(&admin);
+    let config = AssetConfig {
+        asset: Some(token.clone()),
+        collateral_factor: 7500,
+        borrow_factor: 8000,
+        reserve_factor: 1000,
+        max_supply: 10_000_000,
+        max_borrow: 8_000_000,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 1_0000000,
+        price_updated_at: env.ledger().timestamp(),
+    };
+    client.initialize_asset(&admin, &Some(token.clone()), &config);
+    client.ca_deposit_collateral(&user, &Some(token.clone()), &5_000);
+    client.ca_borrow_asset(&user, &Some(token.clone()), &2_000);
+
+    let closure = client.close_position(&user, &Vec::new(&env));
+    let key = AssetKey::Token(token.clone());
+    assert_eq!(closure.asset_debt_repaid.get(key.clone()), Some(2_000));
+    assert_eq!(closure.asset_collateral_withdrawn.get(key), Some(5_000));
+    assert!(closure.pruned);
+    let position = client.get_user_asset_position(&user, &Some(token));
+    assert_eq!((position.collateral, position.debt_principal), (0, 0));
+}
@@ -0,0 +1,256 @@
+//! # Position Transfer Tests
+//!
+//! Tests that `transfer_position` moves core and cross-asset collateral and
+//! debt to an empty address, rejects transfers that would leave a
+//! liquidatable position, and handles sub-accounts on either side.
+
+use crate::cross_asset::{AssetConfig, AssetKey, CrossAssetError};
+use crate::deposit::{AssetParams, DepositDataKey, Position};
+use crate::position_transfer::TransferError;
+use crate::rate_mode::RateMode;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+struct Setup<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    token: Option<Address>,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+}
+
+/// Registers the contract with a token enabled for core deposits, and USDC
+/// and XLM cross-asset markets at a price of 1.0
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::AssetParams(token.clone()),
+            &AssetParams {
+                deposit_enabled: true,
+                collateral_factor: 7500,
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    });
+
+    let usdc = Some(create_token(env));
+    let xlm = Some(create_token(env));
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    Setup {
+        client,
+        admin,
+        token: Some(token),
+        usdc,
+        xlm,
+    }
+}
+
+/// A user holding and approving 1,000,000 of the core token
+fn fund(env: &Env, s: &Setup<'_>) -> Address {
+    let token = s.token.clone().unwrap();
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &token).mint(&user, &1_000_000);
+    TokenClient::new(env, &token).approve(&user, &s.client.address, &10_000_000, &1_000);
+    user
+}
+
+#[test]
+fn test_transfer_moves_core_and_cross_asset_positions() {
+    let env = Env::default();
+    let s = setup(&env);
+    let from = fund(&env, &s);
+    let to = Address::generate(&env);
+    s.client.deposit_collateral(&from, &s.token, &1_000_000);
+    s.client
+        .borrow_asset_with_rate_mode(&from, &s.token, &300_000, &RateMode::Stable);
+    s.client.ca_deposit_collateral(&from, &s.xlm, &1_000);
+    s.client.ca_borrow_asset(&from, &s.usdc, &500);
+    let summary = s.client.get_user_position_summary(&from);
+
+    let transfer = s.client.transfer_position(&from, &to);
+
+    assert_eq!(transfer.collateral, 1_000_000);
+    assert_eq!(transfer.debt, 300_000);
+    assert_eq!(
+        transfer.asset_positions.keys(),
+        vec![
+            &env,
+            AssetKey::from_option(s.usdc.clone()),
+            AssetKey::from_option(s.xlm.clone())
+        ]
+    );
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        event.1,
+        vec![
+            &env,
+            Symbol::new(&env, "position_transferred_event").into_val(&env)
+        ]
+    );
+
+    // The new holder carries the same position, rate mode included
+    assert_eq!(s.client.get_user_position_summary(&to), summary);
+    assert_eq!(s.client.get_rate_mode(&to), RateMode::Stable);
+    let position: Position = env.as_contract(&s.client.address, || {
+        env.storage()
+            .persistent()
+            .get(&DepositDataKey::Position(to.clone()))
+            .unwrap()
+    });
+    assert_eq!(position.debt, 300_000);
+    assert_eq!(
+        s.client.get_user_asset_position(&from, &s.xlm).collateral,
+        0
+    );
+    assert_eq!(s.client.get_rate_mode(&from), RateMode::Variable);
+
+    // and operates it as its own
+    s.client.ca_repay_debt(&to, &s.usdc, &500);
+    s.client.ca_withdraw_collateral(&to, &s.xlm, &1_000);
+    assert_eq!(
+        s.client.try_ca_withdraw_collateral(&from, &s.xlm, &1),
+        Err(Ok(
+            crate::cross_asset::CrossAssetError::InsufficientCollateral
+        ))
+    );
+}
+
+#[test]
+fn test_transfer_requires_distinct_empty_destination() {
+    let env = Env::default();
+    let s = setup(&env);
+    let from = Address::generate(&env);
+    let other = Address::generate(&env);
+    s.client.ca_deposit_collateral(&from, &s.xlm, &1_000);
+    s.client.ca_deposit_collateral(&other, &s.usdc, &10);
+
+    assert_eq!(
+        s.client.try_transfer_position(&from, &from),
+        Err(Ok(TransferError::SameAccount))
+    );
+    assert_eq!(
+        s.client.try_transfer_position(&from, &other),
+        Err(Ok(TransferError::DestinationNotEmpty))
+    );
+    assert_eq!(
+        s.client
+            .try_transfer_position(&Address::generate(&env), &Address::generate(&env)),
+        Err(Ok(TransferError::NothingToTransfer))
+    );
+}
+
+#[test]
+fn test_liquidatable_position_cannot_be_transferred() {
+    let env = Env::default();
+    let s = setup(&env);
+    let from = Address::generate(&env);
+    s.client.ca_deposit_collateral(&from, &s.xlm, &1_000);
+    s.client.ca_borrow_asset(&from, &s.usdc, &700);
+
+    s.client.update_asset_price(&s.admin, &s.xlm, &5_000_000);
+    assert_eq!(
+        s.client
+            .try_transfer_position(&from, &Address::generate(&env)),
+        Err(Ok(TransferError::Insolvent))
+    );
+}
+
+#[test]
+fn test_sub_account_positions_transfer() {
+    let env = Env::default();
+    let s = setup(&env);
+    let owner = fund(&env, &s);
+    let to = Address::generate(&env);
+    s.client
+        .ca_sub_deposit_collateral(&owner, &1, &s.xlm, &1_000);
+    s.client.ca_sub_borrow_asset(&owner, &1, &s.usdc, &300);
+    let account = s.client.get_sub_account(&owner, &1);
+
+    s.client.ca_sub_transfer_position(&owner, &1, &to);
+
+    assert_eq!(
+        s.client
+            .get_user_asset_position(&account, &s.usdc)
+            .debt_principal,
+        0
+    );
+    assert_eq!(
+        s.client
+            .get_user_asset_position(&to, &s.usdc)
+            .debt_principal,
+        300
+    );
+
+    // Back into the sub-account, which its owner authorizes for
+    s.client.transfer_position(&to, &account);
+    assert_eq!(
+        s.client
+            .get_sub_account_summary(&owner, &1)
+            .total_debt_value,
+        300
+    );
+
+    // Sub-accounts hold cross-asset positions only
+    s.client.deposit_collateral(&owner, &s.token, &1_000);
+    let other = s.client.get_sub_account(&owner, &2);
+    s.client.ca_sub_deposit_collateral(&owner, &2, &s.xlm, &1);
+    s.client.ca_sub_withdraw_collateral(&owner, &2, &s.xlm, &1);
+    assert_eq!(
+        s.client.try_transfer_position(&owner, &other),
+        Err(Ok(TransferError::CoreToSubAccount))
+    );
+}
+
+#[test]
+fn test_cross_asset_errors_keep_their_cause() {
+    assert_eq!(
+        TransferError::from(CrossAssetError::PriceStale),
+        TransferError::PriceStale
+    );
+    assert_eq!(
+        TransferError::from(CrossAssetError::Overflow),
+        TransferError::Overflow
+    );
+    assert_eq!(
+        TransferError::from(CrossAssetError::AssetNotConfigured),
+        TransferError::CrossAsset
+    );
+    assert_eq!(
+        TransferError::from(CrossAssetError::InvalidPrice),
+        TransferError::CrossAsset
+    );
+}
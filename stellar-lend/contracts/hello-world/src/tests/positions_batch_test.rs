@@ -0,0 +1,128 @@
+//! # Bulk Position Export Tests
+//!
+//! Tests for `get_positions_batch`, the indexer-facing bulk export of core and
+//! cross-asset positions.
+
+use crate::analytics::{AnalyticsError, MAX_POSITIONS_BATCH_SIZE};
+use crate::cross_asset::{AssetConfig, AssetKey};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Env, Vec};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+#[test]
+fn test_batch_returns_positions_in_order() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.deposit_collateral(&alice, &None, &2_000);
+    client.borrow_asset(&alice, &None, &1_000);
+    client.deposit_collateral(&bob, &None, &500);
+
+    let snapshots =
+        client.get_positions_batch(&Vec::from_array(&env, [alice.clone(), bob.clone()]));
+    assert_eq!(snapshots.len(), 2);
+
+    let a = snapshots.get(0).unwrap();
+    assert_eq!(a.user, alice);
+    assert_eq!(a.position.collateral, 2_000);
+    assert_eq!(a.position.debt, 1_000);
+    assert_eq!(a.health_factor, 20_000);
+
+    let b = snapshots.get(1).unwrap();
+    assert_eq!(b.user, bob);
+    assert_eq!(b.position.collateral, 500);
+    assert_eq!(b.health_factor, i128::MAX);
+    assert!(b.asset_positions.is_empty());
+    assert_eq!(b.cross_asset_health_factor, None);
+}
+
+#[test]
+fn test_unknown_user_returns_empty_snapshot() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+    let stranger = Address::generate(&env);
+
+    let snapshots = client.get_positions_batch(&Vec::from_array(&env, [stranger]));
+    let s = snapshots.get(0).unwrap();
+    assert_eq!(s.position.collateral, 0);
+    assert_eq!(s.position.debt, 0);
+    assert_eq!(s.health_factor, i128::MAX);
+}
+
+#[test]
+fn test_batch_includes_cross_asset_positions() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(&env);
+    client.initialize_asset(
+        &admin,
+        &Some(usdc.clone()),
+        &AssetConfig {
+            asset: Some(usdc.clone()),
+            collateral_factor: 8000,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &400);
+
+    let snapshots = client.get_positions_batch(&Vec::from_array(&env, [user]));
+    let s = snapshots.get(0).unwrap();
+    let pos = s.asset_positions.get(AssetKey::Token(usdc)).unwrap();
+    assert_eq!(pos.collateral, 1_000);
+    assert_eq!(pos.debt_principal, 400);
+    assert_eq!(s.cross_asset_health_factor, Some(20_000));
+}
+
+#[test]
+fn test_batch_size_limit() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+
+    let mut users = Vec::new(&env);
+    for _ in 0..MAX_POSITIONS_BATCH_SIZE {
+        users.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        client.get_positions_batch(&users).len(),
+        MAX_POSITIONS_BATCH_SIZE
+    );
+
+    users.push_back(Address::generate(&env));
+    let result = client.try_get_positions_batch(&users);
+    assert_eq!(result, Err(Ok(AnalyticsError::InvalidParameter)));
+}
+
+#[test]
+fn test_empty_batch() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+    assert_eq!(client.get_positions_batch(&Vec::new(&env)).len(), 0);
+}
@@ -0,0 +1,167 @@
+//! # Positions Below Health Tests
+//!
+//! Tests for the borrower registry and the paged `get_positions_below_health`
+//! scan liquidation keepers use to find at-risk positions.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, Vec,
+};
+
+struct Market {
+    admin: Address,
+    xlm: Option<Address>,
+    usdc: Option<Address>,
+    borrowers: Vec<Address>,
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 8000,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// A USDC supplier and three borrowers with 1000 XLM collateral each and
+/// 300 / 500 / 700 USDC debt
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let xlm = create_token(env);
+    let usdc = create_token(env);
+    client.initialize_asset(&admin, &Some(xlm.clone()), &asset_config(env, &xlm));
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(env, &usdc));
+    let mut market = Market {
+        admin,
+        xlm: Some(xlm),
+        usdc: Some(usdc),
+        borrowers: Vec::new(env),
+    };
+    client.ca_deposit_collateral(&Address::generate(env), &market.usdc, &5_000);
+
+    for debt in [300_i128, 500, 700] {
+        let user = Address::generate(env);
+        client.ca_deposit_collateral(&user, &market.xlm, &1_000);
+        client.ca_borrow_asset(&user, &market.usdc, &debt);
+        market.borrowers.push_back(user);
+    }
+    (client, market)
+}
+
+#[test]
+fn test_borrower_registry_excludes_suppliers() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    assert_eq!(client.get_borrower_count(), 3);
+
+    // Borrowing again does not register twice
+    let first = market.borrowers.get(0).unwrap();
+    client.ca_borrow_asset(&first, &market.usdc, &10);
+    assert_eq!(client.get_borrower_count(), 3);
+}
+
+#[test]
+fn test_finds_liquidatable_positions() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    assert!(client
+        .get_positions_below_health(&10_000, &50, &0)
+        .is_empty());
+
+    // 40% drop: 480 of weighted collateral against 500 and 700 of debt
+    client.update_asset_price(&market.admin, &market.xlm, &6_000_000);
+    let positions = client.get_positions_below_health(&10_000, &50, &0);
+    assert_eq!(positions.len(), 2);
+
+    let position = positions.get(0).unwrap();
+    assert_eq!(position.user, market.borrowers.get(1).unwrap());
+    assert_eq!(position.health_factor, 9_600);
+    assert_eq!(position.total_collateral_value, 600);
+    assert_eq!(position.total_debt_value, 500);
+    assert_eq!(positions.get(1).unwrap().health_factor, 6_857);
+}
+
+#[test]
+fn test_threshold_finds_positions_near_liquidation() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+
+    // 800 of weighted collateral: 2.67, 1.6 and 1.14
+    let positions = client.get_positions_below_health(&20_000, &50, &0);
+    assert_eq!(positions.len(), 2);
+    assert_eq!(
+        positions.get(0).unwrap().user,
+        market.borrowers.get(1).unwrap()
+    );
+    assert_eq!(
+        positions.get(1).unwrap().user,
+        market.borrowers.get(2).unwrap()
+    );
+
+    // Repaid borrowers stay registered but are never below a threshold
+    let first = market.borrowers.get(0).unwrap();
+    client.ca_repay_debt(&first, &market.usdc, &300);
+    let positions = client.get_positions_below_health(&i128::MAX, &50, &0);
+    assert_eq!(positions.len(), 2);
+}
+
+#[test]
+fn test_pages_through_registry() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.update_asset_price(&market.admin, &market.xlm, &6_000_000);
+
+    let first = client.get_positions_below_health(&10_000, &2, &0);
+    assert_eq!(first.len(), 1);
+    assert_eq!(first.get(0).unwrap().user, market.borrowers.get(1).unwrap());
+    let last = client.get_positions_below_health(&10_000, &2, &2);
+    assert_eq!(last.len(), 1);
+    assert_eq!(last.get(0).unwrap().user, market.borrowers.get(2).unwrap());
+    assert!(client
+        .get_positions_below_health(&10_000, &2, &4)
+        .is_empty());
+}
+
+#[test]
+fn test_skips_positions_with_stale_prices() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    client.update_asset_price(&market.admin, &market.xlm, &6_000_000);
+
+    // USDC is stale: none of the positions can be valued or liquidated
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    client.update_asset_price(&market.admin, &market.xlm, &6_000_000);
+    assert!(client
+        .get_positions_below_health(&10_000, &50, &0)
+        .is_empty());
+}
+
+#[test]
+fn test_page_size_validation() {
+    let env = Env::default();
+    let (client, _market) = setup(&env);
+
+    assert_eq!(
+        client.try_get_positions_below_health(&10_000, &0, &0),
+        Err(Ok(CrossAssetError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_get_positions_below_health(&10_000, &51, &0),
+        Err(Ok(CrossAssetError::InvalidAmount))
+    );
+}
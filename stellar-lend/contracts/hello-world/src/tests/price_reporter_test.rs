@@ -0,0 +1,379 @@
+//! # Price Reporter Tests
+//!
+//! Tests that signed reporter submissions are rate limited, bound to a fresh
+//! signed timestamp, paid from reserves, and aggregated into the oracle feed as the median of the fresh
+//! reports of registered reporters, which also prices the cross-asset market.
+
+use crate::cross_asset::AssetConfig;
+use crate::lending_pool::{add_reserves, get_reserves, RevenueSource};
+use crate::price_reporter::{ReporterError, ReportingConfig};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    xdr::ToXdr,
+    Address, BytesN, Env,
+};
+
+struct Setup<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    asset: Address,
+    incentive_asset: Address,
+}
+
+/// Registers the contract with 15 of an incentive token in reserves, and an
+/// asset taking reports at most once a minute per reporter, publishing once
+/// two are fresh, for an incentive of 10
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    let incentive_asset = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    StellarAssetClient::new(env, &incentive_asset).mint(&contract_id, &15);
+    env.as_contract(&contract_id, || {
+        add_reserves(
+            env,
+            Some(incentive_asset.clone()),
+            15,
+            RevenueSource::FlashLoanFee,
+        )
+        .unwrap();
+    });
+
+    let asset = create_token(env);
+    client.set_reporting_config(
+        &admin,
+        &asset,
+        &Some(ReportingConfig {
+            min_update_interval: 60,
+            min_reporters: 2,
+            decimals: 7,
+            incentive_asset: incentive_asset.clone(),
+            incentive: 10,
+        }),
+    );
+    Setup {
+        client,
+        admin,
+        asset,
+        incentive_asset,
+    }
+}
+
+/// Registers a reporter whose key is derived from `seed`
+fn reporter(env: &Env, s: &Setup<'_>, seed: u8) -> (Address, SigningKey) {
+    let address = Address::generate(env);
+    let key = SigningKey::from_bytes(&[seed; 32]);
+    s.client.set_price_reporter(
+        &s.admin,
+        &address,
+        &Some(BytesN::from_array(env, &key.verifying_key().to_bytes())),
+    );
+    (address, key)
+}
+
+/// Signs `price` for the asset at `timestamp` with the reporter's current
+/// nonce
+fn sign_at(
+    env: &Env,
+    s: &Setup<'_>,
+    reporter: &(Address, SigningKey),
+    price: i128,
+    timestamp: u64,
+) -> BytesN<64> {
+    let nonce = s.client.get_reporter_nonce(&reporter.0);
+    let payload = (
+        s.client.address.clone(),
+        s.asset.clone(),
+        price,
+        timestamp,
+        nonce,
+    )
+        .to_xdr(env);
+    let digest: BytesN<32> = env.crypto().sha256(&payload).into();
+    BytesN::from_array(env, &reporter.1.sign(&digest.to_array()).to_bytes())
+}
+
+/// Signs `price` for the asset at the current ledger time
+fn sign(env: &Env, s: &Setup<'_>, reporter: &(Address, SigningKey), price: i128) -> BytesN<64> {
+    sign_at(env, s, reporter, price, env.ledger().timestamp())
+}
+
+fn submit(
+    env: &Env,
+    s: &Setup<'_>,
+    reporter: &(Address, SigningKey),
+    price: i128,
+) -> Result<Option<i128>, ReporterError> {
+    let signature = sign(env, s, reporter, price);
+    match s.client.try_submit_price(
+        &reporter.0,
+        &s.asset,
+        &price,
+        &env.ledger().timestamp(),
+        &signature,
+    ) {
+        Ok(Ok(submission)) => Ok(submission.median),
+        Err(Ok(err)) => Err(err),
+        _ => panic!("submission failed"),
+    }
+}
+
+#[test]
+fn test_median_of_fresh_reports_becomes_the_feed() {
+    let env = Env::default();
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let second = reporter(&env, &s, 2);
+    let third = reporter(&env, &s, 3);
+
+    // One report is short of the quorum
+    assert_eq!(submit(&env, &s, &first, 100), Ok(None));
+    assert_eq!(submit(&env, &s, &second, 110), Ok(Some(105)));
+    assert_eq!(s.client.get_price(&s.asset), 105);
+
+    // An outlier moves the median only to the next report
+    assert_eq!(submit(&env, &s, &third, 1_000), Ok(Some(110)));
+    assert_eq!(s.client.get_price(&s.asset), 110);
+    assert_eq!(s.client.get_reporter_nonce(&third.0), 1);
+    assert_eq!(s.client.get_price_reports(&s.asset).len(), 3);
+}
+
+#[test]
+fn test_submissions_are_rate_limited_and_signed() {
+    let env = Env::default();
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let second = reporter(&env, &s, 2);
+    submit(&env, &s, &first, 100).unwrap();
+
+    assert_eq!(
+        submit(&env, &s, &first, 101),
+        Err(ReporterError::TooFrequent)
+    );
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    submit(&env, &s, &first, 101).unwrap();
+
+    // A signature is bound to its reporter, price, timestamp and nonce
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let now = env.ledger().timestamp();
+    let signature = sign(&env, &s, &first, 102);
+    assert!(s
+        .client
+        .try_submit_price(&first.0, &s.asset, &103, &now, &signature)
+        .is_err());
+    assert!(s
+        .client
+        .try_submit_price(&first.0, &s.asset, &102, &(now - 1), &signature)
+        .is_err());
+    assert!(s
+        .client
+        .try_submit_price(&second.0, &s.asset, &102, &now, &signature)
+        .is_err());
+    s.client
+        .submit_price(&first.0, &s.asset, &102, &now, &signature);
+    assert!(s
+        .client
+        .try_submit_price(&first.0, &s.asset, &102, &now, &signature)
+        .is_err());
+}
+
+#[test]
+fn test_signed_timestamp_must_be_fresh() {
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let second = reporter(&env, &s, 2);
+
+    // A signature held back past the staleness threshold is rejected
+    let signature = sign_at(&env, &s, &first, 100, 6_399);
+    assert_eq!(
+        s.client
+            .try_submit_price(&first.0, &s.asset, &100, &6_399, &signature),
+        Err(Ok(ReporterError::InvalidTimestamp))
+    );
+    let signature = sign_at(&env, &s, &first, 100, 10_001);
+    assert_eq!(
+        s.client
+            .try_submit_price(&first.0, &s.asset, &100, &10_001, &signature),
+        Err(Ok(ReporterError::InvalidTimestamp))
+    );
+
+    // The report is kept at its signed time, and counts only while fresh
+    let signature = sign_at(&env, &s, &first, 100, 6_400);
+    s.client
+        .submit_price(&first.0, &s.asset, &100, &6_400, &signature);
+    assert_eq!(
+        s.client
+            .get_price_reports(&s.asset)
+            .get(first.0.clone())
+            .unwrap()
+            .submitted_at,
+        6_400
+    );
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    assert_eq!(submit(&env, &s, &second, 110), Ok(None));
+}
+
+#[test]
+fn test_incentives_are_paid_from_reserves() {
+    let env = Env::default();
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let second = reporter(&env, &s, 2);
+    let token = TokenClient::new(&env, &s.incentive_asset);
+
+    // A report short of the quorum is accepted, unpaid
+    let signature = sign(&env, &s, &first, 100);
+    let now = env.ledger().timestamp();
+    let submission = s
+        .client
+        .submit_price(&first.0, &s.asset, &100, &now, &signature);
+    assert_eq!(submission.incentive_paid, 0);
+
+    let signature = sign(&env, &s, &second, 110);
+    let submission = s
+        .client
+        .submit_price(&second.0, &s.asset, &110, &now, &signature);
+    assert_eq!(submission.median, Some(105));
+    assert_eq!(submission.incentive_paid, 10);
+    assert_eq!(token.balance(&second.0), 10);
+    assert_eq!(
+        env.as_contract(&s.client.address, || get_reserves(
+            &env,
+            Some(s.incentive_asset.clone())
+        )),
+        5
+    );
+
+    // Once the reserves run short, updates are still accepted, unpaid
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let signature = sign(&env, &s, &first, 101);
+    let now = env.ledger().timestamp();
+    let submission = s
+        .client
+        .submit_price(&first.0, &s.asset, &101, &now, &signature);
+    assert_eq!(submission.median, Some(105));
+    assert_eq!(submission.incentive_paid, 0);
+    assert_eq!(token.balance(&first.0), 0);
+}
+
+#[test]
+fn test_backdated_submissions_are_rate_limited() {
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+
+    // Signed timestamps a minute apart cannot land in the same ledger
+    let signature = sign_at(&env, &s, &first, 100, 9_000);
+    s.client
+        .submit_price(&first.0, &s.asset, &100, &9_000, &signature);
+    let signature = sign_at(&env, &s, &first, 100, 9_060);
+    assert_eq!(
+        s.client
+            .try_submit_price(&first.0, &s.asset, &100, &9_060, &signature),
+        Err(Ok(ReporterError::TooFrequent))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    s.client
+        .submit_price(&first.0, &s.asset, &100, &9_060, &signature);
+    let report = s.client.get_price_reports(&s.asset).get(first.0).unwrap();
+    assert_eq!(report.submitted_at, 9_060);
+    assert_eq!(report.accepted_at, 10_060);
+}
+
+#[test]
+fn test_published_median_prices_cross_asset_market() {
+    let env = Env::default();
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let second = reporter(&env, &s, 2);
+    let asset = Some(s.asset.clone());
+    s.client.initialize_ca(&s.admin);
+    s.client.initialize_asset(
+        &s.admin,
+        &asset,
+        &AssetConfig {
+            asset: asset.clone(),
+            collateral_factor: 8000,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+
+    submit(&env, &s, &first, 12_000_000).unwrap();
+    submit(&env, &s, &second, 13_000_000).unwrap();
+    assert_eq!(s.client.get_asset_config(&asset).price, 12_500_000);
+}
+
+#[test]
+fn test_stale_and_removed_reporters_are_not_counted() {
+    let env = Env::default();
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let second = reporter(&env, &s, 2);
+    let third = reporter(&env, &s, 3);
+    submit(&env, &s, &first, 100).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    assert_eq!(submit(&env, &s, &second, 110), Ok(None));
+
+    s.client.set_price_reporter(&s.admin, &second.0, &None);
+    assert_eq!(submit(&env, &s, &third, 120), Ok(None));
+    assert_eq!(
+        submit(&env, &s, &second, 110),
+        Err(ReporterError::NotReporter)
+    );
+}
+
+#[test]
+fn test_reporting_requires_configuration() {
+    let env = Env::default();
+    let s = setup(&env);
+    let first = reporter(&env, &s, 1);
+    let config = s.client.get_reporting_config(&s.asset).unwrap();
+
+    assert_eq!(
+        s.client.try_set_reporting_config(
+            &s.admin,
+            &s.asset,
+            &Some(ReportingConfig {
+                min_reporters: 0,
+                ..config.clone()
+            })
+        ),
+        Err(Ok(ReporterError::InvalidConfig))
+    );
+    assert_eq!(
+        s.client
+            .try_set_reporting_config(&Address::generate(&env), &s.asset, &Some(config)),
+        Err(Ok(ReporterError::Unauthorized))
+    );
+    assert_eq!(
+        submit(&env, &s, &first, 0),
+        Err(ReporterError::InvalidPrice)
+    );
+
+    s.client.set_reporting_config(&s.admin, &s.asset, &None);
+    assert_eq!(
+        submit(&env, &s, &first, 100),
+        Err(ReporterError::AssetNotConfigured)
+    );
+}
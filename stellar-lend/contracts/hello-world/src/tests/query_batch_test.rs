@@ -0,0 +1,145 @@
+//! # Query Batch Tests
+//!
+//! Tests for `query_batch`, the single-call dashboard read over the
+//! contract's view functions.
+
+use crate::cross_asset::{AssetConfig, AssetKey, AssetPosition, CrossAssetError, SupplyStatement};
+use crate::query::{Query, QueryError, MAX_QUERY_BATCH_SIZE};
+use crate::risk_management::RiskConfig;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Env, TryFromVal, Val, Vec};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 8000,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// Initializes the core contract and cross-asset module with one market (USDC)
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(env, &usdc));
+    (client, usdc)
+}
+
+fn decode<T: TryFromVal<Env, Val>>(env: &Env, val: &Val) -> T {
+    T::try_from_val(env, val).unwrap_or_else(|_| panic!("unexpected result type"))
+}
+
+#[test]
+fn test_dashboard_in_one_call() {
+    let env = create_test_env();
+    let (client, usdc) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &5_000);
+
+    let queries = Vec::from_array(
+        &env,
+        [
+            Query::AssetList,
+            Query::AssetConfig(Some(usdc.clone())),
+            Query::RiskConfig,
+            Query::EmergencyPaused,
+            Query::UserAssetPosition(user.clone(), Some(usdc.clone())),
+            Query::SupplyStatement(user.clone(), Some(usdc.clone())),
+        ],
+    );
+    let results = client.query_batch(&queries);
+    assert_eq!(results.len(), queries.len());
+
+    let markets: Vec<AssetKey> = decode(&env, &results.get(0).unwrap());
+    assert_eq!(markets, client.get_asset_list());
+
+    let config: AssetConfig = decode(&env, &results.get(1).unwrap());
+    assert_eq!(config.asset, Some(usdc.clone()));
+    assert_eq!(config.collateral_factor, 8000);
+
+    let risk: Option<RiskConfig> = decode(&env, &results.get(2).unwrap());
+    assert_eq!(risk, client.get_risk_config());
+
+    let paused: bool = decode(&env, &results.get(3).unwrap());
+    assert!(!paused);
+
+    let position: AssetPosition = decode(&env, &results.get(4).unwrap());
+    assert_eq!(position.collateral, 5_000);
+
+    let statement: SupplyStatement = decode(&env, &results.get(5).unwrap());
+    assert_eq!(
+        statement,
+        client.get_supply_statement(&user, &Some(usdc.clone()))
+    );
+}
+
+#[test]
+fn test_failed_query_does_not_abort_batch() {
+    let env = create_test_env();
+    let (client, usdc) = setup(&env);
+    let unknown = Address::generate(&env);
+
+    let results = client.query_batch(&Vec::from_array(
+        &env,
+        [
+            Query::AssetConfig(Some(unknown)),
+            Query::AssetConfig(Some(usdc.clone())),
+        ],
+    ));
+    assert_eq!(results.len(), 2);
+
+    let error: soroban_sdk::Error = decode(&env, &results.get(0).unwrap());
+    assert_eq!(
+        error,
+        soroban_sdk::Error::from(CrossAssetError::AssetNotConfigured)
+    );
+
+    let config: AssetConfig = decode(&env, &results.get(1).unwrap());
+    assert_eq!(config.asset, Some(usdc));
+}
+
+#[test]
+fn test_empty_batch() {
+    let env = create_test_env();
+    let (client, _) = setup(&env);
+
+    let results = client.query_batch(&Vec::new(&env));
+    assert!(results.is_empty());
+}
+
+#[test]
+fn test_batch_size_limit() {
+    let env = create_test_env();
+    let (client, _) = setup(&env);
+
+    let mut queries = Vec::new(&env);
+    for _ in 0..MAX_QUERY_BATCH_SIZE {
+        queries.push_back(Query::EmergencyPaused);
+    }
+    assert_eq!(client.query_batch(&queries).len(), MAX_QUERY_BATCH_SIZE);
+
+    queries.push_back(Query::EmergencyPaused);
+    assert_eq!(
+        client.try_query_batch(&queries),
+        Err(Ok(QueryError::BatchTooLarge))
+    );
+}
@@ -0,0 +1,148 @@
+//! # Rate Comparison Tests
+//!
+//! Tests for peer pool registration and borrow rate comparison across pools.
+
+use crate::rate_comparison::{RateComparisonError, RateQuote, MAX_PEER_POOLS};
+use crate::rate_strategy::RateStrategy;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+/// Registers and initializes a pool whose borrow rate on `asset` is
+/// `base_rate_bps` (no utilization)
+fn pool_with_rate<'a>(
+    env: &'a Env,
+    asset: &Option<Address>,
+    base_rate_bps: i128,
+) -> HelloContractClient<'a> {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    client.set_rate_strategy(
+        &admin,
+        asset,
+        &RateStrategy {
+            base_rate_bps,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    client
+}
+
+#[test]
+fn test_compare_rates_picks_lowest() {
+    let env = create_test_env();
+    let asset = Some(Address::generate(&env));
+    let local = pool_with_rate(&env, &asset, 500);
+    let cheaper = pool_with_rate(&env, &asset, 300);
+    let dearer = pool_with_rate(&env, &asset, 700);
+    local.add_peer_pool(&cheaper.address);
+    local.add_peer_pool(&dearer.address);
+
+    let comparison = local.compare_rates(&asset);
+
+    assert_eq!(comparison.local_borrow_rate_bps, 500);
+    assert_eq!(
+        comparison.quotes,
+        vec![
+            &env,
+            RateQuote {
+                pool: cheaper.address.clone(),
+                borrow_rate_bps: 300,
+            },
+            RateQuote {
+                pool: dearer.address.clone(),
+                borrow_rate_bps: 700,
+            },
+        ]
+    );
+    assert!(comparison.unavailable.is_empty());
+    assert_eq!(comparison.best_pool, cheaper.address);
+    assert_eq!(comparison.best_borrow_rate_bps, 300);
+}
+
+#[test]
+fn test_compare_rates_local_best_without_peers() {
+    let env = create_test_env();
+    let asset = Some(Address::generate(&env));
+    let local = pool_with_rate(&env, &asset, 500);
+
+    let comparison = local.compare_rates(&asset);
+
+    assert!(comparison.quotes.is_empty());
+    assert_eq!(comparison.best_pool, local.address);
+    assert_eq!(comparison.best_borrow_rate_bps, 500);
+}
+
+#[test]
+fn test_unreachable_peer_listed_as_unavailable() {
+    let env = create_test_env();
+    let asset = Some(Address::generate(&env));
+    let local = pool_with_rate(&env, &asset, 500);
+    // A token contract does not expose the rate view
+    let wrong_interface = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let peer = pool_with_rate(&env, &asset, 400);
+    local.add_peer_pool(&wrong_interface);
+    local.add_peer_pool(&peer.address);
+
+    let comparison = local.compare_rates(&asset);
+
+    assert_eq!(comparison.unavailable, vec![&env, wrong_interface]);
+    assert_eq!(comparison.quotes.len(), 1);
+    assert_eq!(comparison.best_pool, peer.address);
+}
+
+#[test]
+fn test_peer_registration() {
+    let env = create_test_env();
+    let asset = Some(Address::generate(&env));
+    let local = pool_with_rate(&env, &asset, 500);
+    let peer = Address::generate(&env);
+
+    local.add_peer_pool(&peer);
+    assert_eq!(local.get_peer_pools(), vec![&env, peer.clone()]);
+
+    let result = local.try_add_peer_pool(&peer);
+    assert_eq!(result, Err(Ok(RateComparisonError::DuplicatePeer)));
+    let result = local.try_add_peer_pool(&local.address);
+    assert_eq!(result, Err(Ok(RateComparisonError::InvalidPeer)));
+
+    local.remove_peer_pool(&peer);
+    assert!(local.get_peer_pools().is_empty());
+    let result = local.try_remove_peer_pool(&peer);
+    assert_eq!(result, Err(Ok(RateComparisonError::PeerNotFound)));
+}
+
+#[test]
+fn test_peer_limit() {
+    let env = create_test_env();
+    let asset = Some(Address::generate(&env));
+    let local = pool_with_rate(&env, &asset, 500);
+    for _ in 0..MAX_PEER_POOLS {
+        local.add_peer_pool(&Address::generate(&env));
+    }
+
+    let result = local.try_add_peer_pool(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(RateComparisonError::TooManyPeers)));
+}
+
+#[test]
+fn test_add_peer_requires_cross_asset_admin() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let result = client.try_add_peer_pool(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(RateComparisonError::NotAuthorized)));
+}
@@ -0,0 +1,313 @@
+//! # Rate Lock Tests
+//!
+//! Tests for fixed-term supply rate locks: configuration, locking limits,
+//! redemption of locked shares, and settlement against reserves.
+
+use crate::cross_asset::AssetConfig;
+use crate::lending_pool::PoolError;
+use crate::rate_lock::{RateLockConfig, RateLockError, MIN_LOCK_TERM};
+use crate::rate_strategy::RateStrategy;
+use crate::roles::Role;
+use crate::tests::lending_pool_test::repay_core_interest;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, IntoVal, Symbol,
+};
+
+const ONE_YEAR: u64 = 31_536_000;
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+}
+
+/// Registers USDC and XLM at a price of 1.0 with a 10% reserve factor, and a
+/// 12% borrow rate at 80% utilization on USDC
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: create_token(env),
+        xlm: create_token(env),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &market.admin,
+        &Some(market.usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    (contract_id, client, market)
+}
+
+fn lock_config(spread_bps: i128) -> RateLockConfig {
+    RateLockConfig {
+        spread_bps,
+        max_term: ONE_YEAR,
+        max_locked: 100_000,
+    }
+}
+
+/// A supplier of `amount` USDC to the pool
+fn supplier(
+    env: &Env,
+    contract_id: &Address,
+    client: &HelloContractClient,
+    market: &Market,
+    amount: i128,
+) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, &market.usdc).mint(&user, &amount);
+    TokenClient::new(env, &market.usdc).approve(
+        &user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&user, &Some(market.usdc.clone()), &amount);
+    user
+}
+
+/// Borrows 8,000 USDC against XLM at 80% utilization: a 12% borrow rate and
+/// an 8.64% supply rate. Returns the borrower.
+fn open_borrow(env: &Env, client: &HelloContractClient, market: &Market) -> Address {
+    let usdc = Some(market.usdc.clone());
+    client.ca_deposit_collateral(&Address::generate(env), &usdc, &10_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(market.xlm.clone()), &100_000);
+    client.ca_borrow_asset(&borrower, &usdc, &8_000);
+    borrower
+}
+
+/// Moves one year ahead and repays that year's interest into the pool
+fn pass_one_year(env: &Env, client: &HelloContractClient, market: &Market, interest: i128) {
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+    repay_core_interest(env, client, &market.usdc, interest);
+}
+
+#[test]
+fn test_config_is_validated() {
+    let env = Env::default();
+    let (_, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+
+    let outsider = Address::generate(&env);
+    let result = client.try_set_rate_lock_config(&outsider, &usdc, &lock_config(0));
+    assert_eq!(result, Err(Ok(RateLockError::Unauthorized)));
+
+    for config in [
+        lock_config(10_001),
+        RateLockConfig {
+            max_term: MIN_LOCK_TERM - 1,
+            ..lock_config(0)
+        },
+        RateLockConfig {
+            max_term: ONE_YEAR + 1,
+            ..lock_config(0)
+        },
+        RateLockConfig {
+            max_locked: -1,
+            ..lock_config(0)
+        },
+    ] {
+        let result = client.try_set_rate_lock_config(&market.admin, &usdc, &config);
+        assert_eq!(result, Err(Ok(RateLockError::InvalidConfig)));
+    }
+
+    // A risk admin may configure locks too
+    let risk_admin = Address::generate(&env);
+    client.grant_role(&market.admin, &Role::RiskAdmin, &risk_admin);
+    client.set_rate_lock_config(&risk_admin, &usdc, &lock_config(100));
+    assert_eq!(client.get_rate_lock_config(&usdc), Some(lock_config(100)));
+}
+
+#[test]
+fn test_quote_is_supply_rate_less_spread() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    supplier(&env, &contract_id, &client, &market, 10_000);
+    open_borrow(&env, &client, &market);
+
+    let result = client.try_get_rate_lock_quote(&usdc);
+    assert_eq!(result, Err(Ok(RateLockError::NotEnabled)));
+
+    // 12% borrow rate x 80% utilization, less the 10% reserve factor
+    assert_eq!(client.get_pool_supply_rate(&usdc), 864);
+    client.set_rate_lock_config(&market.admin, &usdc, &lock_config(100));
+    assert_eq!(client.get_rate_lock_quote(&usdc), 764);
+}
+
+#[test]
+fn test_lock_limits() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 10_000);
+
+    let result = client.try_lock_supply_rate(&user, &usdc, &1_000, &ONE_YEAR);
+    assert_eq!(result, Err(Ok(RateLockError::NotEnabled)));
+
+    client.set_rate_lock_config(
+        &market.admin,
+        &usdc,
+        &RateLockConfig {
+            max_locked: 5_000,
+            ..lock_config(0)
+        },
+    );
+    for term in [MIN_LOCK_TERM - 1, ONE_YEAR + 1] {
+        let result = client.try_lock_supply_rate(&user, &usdc, &1_000, &term);
+        assert_eq!(result, Err(Ok(RateLockError::InvalidTerm)));
+    }
+    for shares in [0, 10_001] {
+        let result = client.try_lock_supply_rate(&user, &usdc, &shares, &ONE_YEAR);
+        assert_eq!(result, Err(Ok(RateLockError::InvalidShares)));
+    }
+    let result = client.try_lock_supply_rate(&user, &usdc, &5_001, &ONE_YEAR);
+    assert_eq!(result, Err(Ok(RateLockError::CapExceeded)));
+
+    let lock = client.lock_supply_rate(&user, &usdc, &4_000, &ONE_YEAR);
+    assert_eq!(lock.value, 4_000);
+    assert_eq!(lock.maturity, env.ledger().timestamp() + ONE_YEAR);
+    assert_eq!(client.get_rate_lock(&user, &usdc), Some(lock));
+    assert_eq!(client.get_locked_value(&usdc), 4_000);
+
+    let result = client.try_lock_supply_rate(&user, &usdc, &1_000, &ONE_YEAR);
+    assert_eq!(result, Err(Ok(RateLockError::LockExists)));
+    let other = supplier(&env, &contract_id, &client, &market, 10_000);
+    let result = client.try_lock_supply_rate(&other, &usdc, &1_001, &ONE_YEAR);
+    assert_eq!(result, Err(Ok(RateLockError::CapExceeded)));
+}
+
+#[test]
+fn test_lock_emits_event() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 10_000);
+    client.set_rate_lock_config(&market.admin, &usdc, &lock_config(0));
+
+    client.lock_supply_rate(&user, &usdc, &1_000, &ONE_YEAR);
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "rate_locked_event"),).into_val(&env)
+    );
+}
+
+#[test]
+fn test_locked_shares_cannot_be_redeemed() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 10_000);
+    client.set_rate_lock_config(&market.admin, &usdc, &lock_config(0));
+    client.lock_supply_rate(&user, &usdc, &6_000, &MIN_LOCK_TERM);
+
+    let result = client.try_redeem(&user, &usdc, &4_001);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientShares)));
+    client.redeem(&user, &usdc, &4_000);
+    assert_eq!(client.get_supply_shares(&user, &usdc), 6_000);
+}
+
+#[test]
+fn test_settle_before_maturity_rejected() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 10_000);
+    client.set_rate_lock_config(&market.admin, &usdc, &lock_config(0));
+
+    let result = client.try_settle_rate_lock(&user, &usdc);
+    assert_eq!(result, Err(Ok(RateLockError::NoLock)));
+
+    client.lock_supply_rate(&user, &usdc, &1_000, &MIN_LOCK_TERM);
+    env.ledger()
+        .with_mut(|li| li.timestamp += MIN_LOCK_TERM - 1);
+    let result = client.try_settle_rate_lock(&user, &usdc);
+    assert_eq!(result, Err(Ok(RateLockError::NotMatured)));
+}
+
+#[test]
+fn test_excess_yield_goes_to_reserves() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 10_000);
+    open_borrow(&env, &client, &market);
+    client.set_rate_lock_config(&market.admin, &usdc, &lock_config(100));
+    client.lock_supply_rate(&user, &usdc, &10_000, &ONE_YEAR);
+
+    // The pool earned 8.64% against a locked 7.64%
+    pass_one_year(&env, &client, &market, 960);
+    assert_eq!(client.get_supply_balance(&user, &usdc), 10_864);
+    assert_eq!(client.get_reserves(&usdc), 96);
+
+    assert_eq!(client.settle_rate_lock(&user, &usdc), -100);
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "rate_lock_settled_event"),).into_val(&env)
+    );
+    assert_eq!(client.get_supply_balance(&user, &usdc), 10_764);
+    assert_eq!(client.get_reserves(&usdc), 196);
+    assert_eq!(client.get_rate_lock(&user, &usdc), None);
+    assert_eq!(client.get_locked_value(&usdc), 0);
+}
+
+#[test]
+fn test_shortfall_paid_from_reserves_up_to_available() {
+    let env = Env::default();
+    let (contract_id, client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let user = supplier(&env, &contract_id, &client, &market, 10_000);
+    let borrower = open_borrow(&env, &client, &market);
+    client.set_rate_lock_config(&market.admin, &usdc, &lock_config(0));
+    client.lock_supply_rate(&user, &usdc, &10_000, &ONE_YEAR);
+
+    // Half the loan is repaid right after locking: 40% utilization, a 7%
+    // borrow rate and 280 of interest, 252 to the supplier and 28 to reserves
+    client.ca_repay_debt(&borrower, &usdc, &4_000);
+    pass_one_year(&env, &client, &market, 280);
+    assert_eq!(client.get_supply_balance(&user, &usdc), 10_252);
+    assert_eq!(client.get_reserves(&usdc), 28);
+
+    // 864 was guaranteed, but only the reserves can be paid out
+    assert_eq!(client.settle_rate_lock(&user, &usdc), 28);
+    assert_eq!(client.get_supply_balance(&user, &usdc), 10_280);
+    assert_eq!(client.get_reserves(&usdc), 0);
+}
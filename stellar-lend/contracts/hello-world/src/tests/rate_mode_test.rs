@@ -0,0 +1,253 @@
+//! # Rate Mode Tests
+//!
+//! Tests for stable and variable borrow rate modes: locking at origination,
+//! accrual at the locked rate, swapping modes, and rebalancing.
+
+use crate::borrow::BorrowError;
+use crate::deposit::{AssetParams, DepositDataKey, Position};
+use crate::rate_mode::{RateMode, RateModeError, StableRateConfig};
+use crate::roles::Role;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, IntoVal, Symbol,
+};
+
+const YEAR: u64 = 365 * 86_400;
+
+/// Registers the contract and a token enabled for deposits, and funds a user
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.grant_role(&admin, &Role::RiskAdmin, &admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::AssetParams(token.clone()),
+            &AssetParams {
+                deposit_enabled: true,
+                collateral_factor: 7500,
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    });
+
+    let user = fund(env, &contract_id, &token);
+    (contract_id, client, admin, token, user)
+}
+
+/// A user holding and approving 1,000,000 of `token`
+fn fund(env: &Env, contract_id: &Address, token: &Address) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, token).mint(&user, &1_000_000);
+    TokenClient::new(env, token).approve(&user, contract_id, &10_000_000, &1_000);
+    user
+}
+
+fn stored_position(env: &Env, contract_id: &Address, user: &Address) -> Position {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DepositDataKey::Position(user.clone()))
+            .unwrap()
+    })
+}
+
+#[test]
+fn test_stable_debt_accrues_at_locked_rate() {
+    let env = Env::default();
+    let (contract_id, client, _, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000_000);
+
+    let stable_rate = client.get_stable_rate();
+    assert_eq!(stable_rate, client.get_borrow_rate() + 200);
+    client.borrow_asset_with_rate_mode(&user, &asset, &300_000, &RateMode::Stable);
+    assert_eq!(client.get_rate_mode(&user), RateMode::Stable);
+    let locked = client.get_stable_borrow(&user).unwrap();
+    assert_eq!(locked.rate_bps, stable_rate);
+
+    // The borrow raised utilization, so the pool rate moved away from the lock
+    assert_ne!(client.get_borrow_rate() + 200, stable_rate);
+
+    env.ledger().with_mut(|li| li.timestamp += YEAR);
+    client.swap_rate_mode(&user);
+    let position = stored_position(&env, &contract_id, &user);
+    assert_eq!(position.borrow_interest, 300_000 * stable_rate / 10_000);
+}
+
+#[test]
+fn test_variable_is_default_and_modes_cannot_mix() {
+    let env = Env::default();
+    let (_, client, _, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000_000);
+
+    client.borrow_asset(&user, &asset, &100_000);
+    assert_eq!(client.get_rate_mode(&user), RateMode::Variable);
+    assert_eq!(client.get_stable_borrow(&user), None);
+    assert_eq!(
+        client.try_borrow_asset_with_rate_mode(&user, &asset, &10_000, &RateMode::Stable),
+        Err(Ok(BorrowError::RateModeMismatch))
+    );
+    client.borrow_asset_with_rate_mode(&user, &asset, &10_000, &RateMode::Variable);
+
+    // Full repayment ends the loan; the next one picks its own mode
+    client.repay_debt(&user, &asset, &110_000);
+    client.borrow_asset_with_rate_mode(&user, &asset, &10_000, &RateMode::Stable);
+    assert_eq!(client.get_rate_mode(&user), RateMode::Stable);
+    // Plain borrows keep the mode of the existing debt
+    client.borrow_asset(&user, &asset, &10_000);
+    assert_eq!(client.get_rate_mode(&user), RateMode::Stable);
+}
+
+#[test]
+fn test_repeat_stable_borrows_average_the_rate() {
+    let env = Env::default();
+    let (_, client, admin, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000_000);
+
+    client.set_stable_rate_config(
+        &admin,
+        &StableRateConfig {
+            premium_bps: 1_000,
+            rebalance_threshold_bps: 500,
+        },
+    );
+    let first_rate = client.get_stable_rate();
+    client.borrow_asset_with_rate_mode(&user, &asset, &100_000, &RateMode::Stable);
+
+    client.set_stable_rate_config(
+        &admin,
+        &StableRateConfig {
+            premium_bps: 0,
+            rebalance_threshold_bps: 500,
+        },
+    );
+    let second_rate = client.get_stable_rate();
+    client.borrow_asset_with_rate_mode(&user, &asset, &300_000, &RateMode::Stable);
+
+    let expected = (first_rate * 100_000 + second_rate * 300_000) / 400_000;
+    assert_eq!(client.get_stable_borrow(&user).unwrap().rate_bps, expected);
+}
+
+#[test]
+fn test_swap_rate_mode() {
+    let env = Env::default();
+    let (_, client, _, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000_000);
+
+    assert_eq!(
+        client.try_swap_rate_mode(&user),
+        Err(Ok(RateModeError::NoDebt))
+    );
+
+    client.borrow_asset(&user, &asset, &100_000);
+    let stable_rate = client.get_stable_rate();
+    assert_eq!(client.swap_rate_mode(&user), RateMode::Stable);
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "rate_mode_swapped_event"),).into_val(&env)
+    );
+    assert_eq!(
+        client.get_stable_borrow(&user).unwrap().rate_bps,
+        stable_rate
+    );
+
+    assert_eq!(client.swap_rate_mode(&user), RateMode::Variable);
+    assert_eq!(client.get_stable_borrow(&user), None);
+}
+
+#[test]
+fn test_rebalance_only_when_rate_drifted() {
+    let env = Env::default();
+    let (_, client, admin, token, user) = setup(&env);
+    let asset = Some(token.clone());
+    client.deposit_collateral(&user, &asset, &1_000_000);
+
+    assert_eq!(
+        client.try_rebalance_stable_rate(&user),
+        Err(Ok(RateModeError::NotStable))
+    );
+    client.set_stable_rate_config(
+        &admin,
+        &StableRateConfig {
+            premium_bps: 2_000,
+            rebalance_threshold_bps: 500,
+        },
+    );
+    client.borrow_asset_with_rate_mode(&user, &asset, &100_000, &RateMode::Stable);
+    assert_eq!(
+        client.try_rebalance_stable_rate(&user),
+        Err(Ok(RateModeError::RebalanceNotAllowed))
+    );
+
+    // Dropping the premium leaves the borrower overpaying by more than the threshold
+    client.set_stable_rate_config(
+        &admin,
+        &StableRateConfig {
+            premium_bps: 0,
+            rebalance_threshold_bps: 500,
+        },
+    );
+    let new_rate = client.rebalance_stable_rate(&user);
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "stable_rate_rebalanced_event"),).into_val(&env)
+    );
+    assert_eq!(new_rate, client.get_borrow_rate());
+    assert_eq!(client.get_stable_borrow(&user).unwrap().rate_bps, new_rate);
+}
+
+#[test]
+fn test_stable_rate_config_is_validated() {
+    let env = Env::default();
+    let (_, client, admin, _, user) = setup(&env);
+    let config = StableRateConfig {
+        premium_bps: 100,
+        rebalance_threshold_bps: 300,
+    };
+
+    assert_eq!(
+        client.try_set_stable_rate_config(&user, &config),
+        Err(Ok(RateModeError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_stable_rate_config(
+            &admin,
+            &StableRateConfig {
+                premium_bps: -1,
+                ..config.clone()
+            }
+        ),
+        Err(Ok(RateModeError::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_stable_rate_config(
+            &admin,
+            &StableRateConfig {
+                rebalance_threshold_bps: 0,
+                ..config.clone()
+            }
+        ),
+        Err(Ok(RateModeError::InvalidConfig))
+    );
+    client.set_stable_rate_config(&admin, &config);
+    assert_eq!(client.get_stable_rate_config(), config);
+}
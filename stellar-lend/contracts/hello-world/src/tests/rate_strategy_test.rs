@@ -0,0 +1,273 @@
+//! # Rate Strategy Tests
+//!
+//! Tests for per-asset two-slope borrow rates and their accrual on
+//! cross-asset debt.
+
+use crate::cross_asset::AssetConfig;
+use crate::interest_rate::InterestRateError;
+use crate::rate_strategy::RateStrategy;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const ONE_YEAR: u64 = 31_536_000;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+/// 2% base, 10% up to 80% utilization, 60% above it
+fn strategy() -> RateStrategy {
+    RateStrategy {
+        base_rate_bps: 200,
+        slope1_bps: 1000,
+        slope2_bps: 6000,
+        optimal_utilization_bps: 8000,
+    }
+}
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+}
+
+/// Registers USDC and XLM at a price of 1.0 with a 75% collateral factor
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        admin,
+        usdc: create_token(env),
+        xlm: create_token(env),
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, market)
+}
+
+/// 10,000 USDC supplied and `borrowed` USDC borrowed against XLM
+fn open_market(
+    env: &Env,
+    client: &HelloContractClient,
+    market: &Market,
+    borrowed: i128,
+) -> Address {
+    client.ca_deposit_collateral(&Address::generate(env), &Some(market.usdc.clone()), &10_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(market.xlm.clone()), &100_000);
+    client.ca_borrow_asset(&borrower, &Some(market.usdc.clone()), &borrowed);
+    borrower
+}
+
+fn advance(env: &Env, client: &HelloContractClient, market: &Market, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+    client.update_asset_price(&market.admin, &Some(market.usdc.clone()), &10_000_000);
+    client.update_asset_price(&market.admin, &Some(market.xlm.clone()), &10_000_000);
+}
+
+#[test]
+fn test_two_slope_curve() {
+    let model = strategy();
+    assert_eq!(model.borrow_rate(0), Ok(200));
+    assert_eq!(model.borrow_rate(4000), Ok(700));
+    assert_eq!(model.borrow_rate(8000), Ok(1200));
+    assert_eq!(model.borrow_rate(9000), Ok(4200));
+    assert_eq!(model.borrow_rate(10000), Ok(7200));
+    // Utilization beyond 100% is treated as 100%
+    assert_eq!(model.borrow_rate(12000), Ok(7200));
+}
+
+#[test]
+fn test_set_rate_strategy() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+
+    assert_eq!(client.get_rate_strategy(&usdc), None);
+    client.set_rate_strategy(&market.admin, &usdc, &strategy());
+    assert_eq!(client.get_rate_strategy(&usdc), Some(strategy()));
+
+    // Strategies are per asset
+    assert_eq!(client.get_rate_strategy(&Some(market.xlm.clone())), None);
+}
+
+#[test]
+fn test_set_rate_strategy_requires_admin() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+
+    let result = client.try_set_rate_strategy(
+        &Address::generate(&env),
+        &Some(market.usdc.clone()),
+        &strategy(),
+    );
+    assert_eq!(result, Err(Ok(InterestRateError::Unauthorized)));
+}
+
+#[test]
+fn test_invalid_strategy_rejected() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+
+    let invalid = [
+        RateStrategy {
+            base_rate_bps: -1,
+            ..strategy()
+        },
+        RateStrategy {
+            slope2_bps: -1,
+            ..strategy()
+        },
+        RateStrategy {
+            optimal_utilization_bps: 0,
+            ..strategy()
+        },
+        RateStrategy {
+            optimal_utilization_bps: 10_001,
+            ..strategy()
+        },
+        RateStrategy {
+            slope2_bps: 100_000,
+            ..strategy()
+        },
+    ];
+    for candidate in invalid {
+        assert_eq!(
+            client.try_set_rate_strategy(&market.admin, &usdc, &candidate),
+            Err(Ok(InterestRateError::InvalidParameter))
+        );
+    }
+}
+
+#[test]
+fn test_rate_follows_market_utilization() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_rate_strategy(&market.admin, &usdc, &strategy());
+
+    // No supply yet
+    assert_eq!(client.get_asset_borrow_rate(&usdc), 200);
+
+    let borrower = open_market(&env, &client, &market, 4_000);
+    assert_eq!(client.get_asset_borrow_rate(&usdc), 700);
+
+    client.ca_borrow_asset(&borrower, &usdc, &5_000);
+    assert_eq!(client.get_asset_borrow_rate(&usdc), 4200);
+
+    // Markets without a strategy charge nothing
+    assert_eq!(client.get_asset_borrow_rate(&Some(market.xlm.clone())), 0);
+}
+
+#[test]
+fn test_interest_accrues_on_repay() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_rate_strategy(&market.admin, &usdc, &strategy());
+    let borrower = open_market(&env, &client, &market, 8_000);
+
+    advance(&env, &client, &market, ONE_YEAR);
+    let position = client.ca_repay_debt(&borrower, &usdc, &1);
+
+    // 80% utilization: 12% of 8,000 over one year, less the 1 repaid
+    assert_eq!(position.debt_principal, 8_000);
+    assert_eq!(position.accrued_interest, 959);
+    assert_eq!(client.get_market_summary(&usdc).total_borrow, 8_959);
+}
+
+#[test]
+fn test_interest_accrues_before_borrow() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_rate_strategy(&market.admin, &usdc, &strategy());
+    let borrower = open_market(&env, &client, &market, 4_000);
+
+    advance(&env, &client, &market, ONE_YEAR);
+    let position = client.ca_borrow_asset(&borrower, &usdc, &1_000);
+
+    // The first year is charged at 40% utilization (7%), not the new 50%
+    assert_eq!(position.debt_principal, 5_000);
+    assert_eq!(position.accrued_interest, 280);
+}
+
+#[test]
+fn test_no_interest_without_strategy() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let borrower = open_market(&env, &client, &market, 8_000);
+
+    advance(&env, &client, &market, ONE_YEAR);
+    let position = client.ca_repay_debt(&borrower, &usdc, &1_000);
+
+    assert_eq!(position.debt_principal, 7_000);
+    assert_eq!(position.accrued_interest, 0);
+}
+
+#[test]
+fn test_health_check_estimates_unaccrued_interest() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_rate_strategy(&market.admin, &usdc, &strategy());
+    let borrower = open_market(&env, &client, &market, 8_000);
+
+    advance(&env, &client, &market, 10 * 86_400);
+
+    // 12% of 8,000 over ten days, estimated without touching the position
+    let summary = client.get_user_position_summary(&borrower);
+    assert_eq!(summary.total_debt_value, 8_026);
+    let position = client.get_user_asset_position(&borrower, &usdc);
+    assert_eq!(position.accrued_interest, 0);
+
+    // Accruing replaces the estimate with the same amount
+    client.ca_repay_debt(&borrower, &usdc, &1);
+    let summary = client.get_user_position_summary(&borrower);
+    assert_eq!(summary.total_debt_value, 8_025);
+}
+
+#[test]
+fn test_interest_estimate_is_bounded() {
+    let env = create_test_env();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_rate_strategy(&market.admin, &usdc, &strategy());
+    let borrower = open_market(&env, &client, &market, 8_000);
+
+    advance(&env, &client, &market, ONE_YEAR);
+
+    // Only 30 days are estimated; the full year is charged on the next touch
+    let summary = client.get_user_position_summary(&borrower);
+    assert_eq!(summary.total_debt_value, 8_078);
+    let position = client.ca_repay_debt(&borrower, &usdc, &1);
+    assert_eq!(position.accrued_interest, 959);
+}
@@ -0,0 +1,172 @@
+//! # Recovery Haircut Tests
+//!
+//! Tests for the collateral haircut applied after the emergency pause is
+//! lifted: activation, linear decay, reporting in `get_protocol_health`, and
+//! its effect on borrows and withdrawals.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::risk_management::RiskManagementError;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const WINDOW: u64 = 10_000;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+/// Initializes the contract with a 50% haircut decaying over `WINDOW` seconds
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.set_recovery_haircut(&admin, &5_000, &WINDOW);
+    (client, admin)
+}
+
+fn pause_and_resume(client: &HelloContractClient, admin: &Address) {
+    client.set_emergency_pause(admin, &true);
+    client.set_emergency_pause(admin, &false);
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+#[test]
+fn test_no_haircut_without_pause() {
+    let env = create_test_env();
+    let (client, _) = setup(&env);
+
+    let health = client.get_protocol_health();
+    assert!(!health.emergency_paused);
+    assert_eq!(health.recovery_haircut_bps, 0);
+    assert_eq!(health.recovery_ends_at, 0);
+}
+
+#[test]
+fn test_haircut_decays_linearly_after_pause() {
+    let env = create_test_env();
+    let (client, admin) = setup(&env);
+
+    client.set_emergency_pause(&admin, &true);
+    let paused = client.get_protocol_health();
+    assert!(paused.emergency_paused);
+    assert_eq!(paused.recovery_haircut_bps, 0);
+
+    client.set_emergency_pause(&admin, &false);
+    let health = client.get_protocol_health();
+    assert_eq!(health.recovery_haircut_bps, 5_000);
+    assert_eq!(health.recovery_ends_at, 1_000 + WINDOW);
+
+    advance(&env, WINDOW / 4);
+    assert_eq!(client.get_protocol_health().recovery_haircut_bps, 3_750);
+
+    advance(&env, WINDOW / 2);
+    assert_eq!(client.get_protocol_health().recovery_haircut_bps, 1_250);
+
+    advance(&env, WINDOW / 4);
+    let ended = client.get_protocol_health();
+    assert_eq!(ended.recovery_haircut_bps, 0);
+    assert_eq!(ended.recovery_ends_at, 0);
+}
+
+#[test]
+fn test_lifting_without_active_pause_does_not_start_window() {
+    let env = create_test_env();
+    let (client, admin) = setup(&env);
+
+    client.set_emergency_pause(&admin, &false);
+    assert_eq!(client.get_protocol_health().recovery_haircut_bps, 0);
+}
+
+#[test]
+fn test_haircut_limits_borrowing() {
+    let env = create_test_env();
+    let (client, admin) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &3_000);
+    pause_and_resume(&client, &admin);
+
+    // 3,000 collateral at a 150% minimum ratio supports 2,000 of debt; the
+    // 50% haircut halves that to 1,000
+    assert!(client.try_borrow_asset(&user, &None, &1_001).is_err());
+    client.borrow_asset(&user, &None, &1_000);
+
+    // Once the window has passed, full borrowing power returns
+    advance(&env, WINDOW);
+    client.borrow_asset(&user, &None, &1_000);
+}
+
+#[test]
+fn test_haircut_limits_withdrawals() {
+    let env = create_test_env();
+    let (client, admin) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &3_000);
+    client.borrow_asset(&user, &None, &1_000);
+    pause_and_resume(&client, &admin);
+
+    // 1,000 of debt needs 1,500 of collateral, or 3,000 under a 50% haircut
+    assert!(client.try_withdraw_collateral(&user, &None, &1).is_err());
+
+    advance(&env, WINDOW);
+    client.withdraw_collateral(&user, &None, &1_500);
+}
+
+#[test]
+fn test_haircut_limits_cross_asset_borrowing() {
+    let env = create_test_env();
+    let (client, admin) = setup(&env);
+    client.initialize_ca(&admin);
+    let usdc = create_token(&env);
+    client.initialize_asset(
+        &admin,
+        &Some(usdc.clone()),
+        &AssetConfig {
+            asset: Some(usdc.clone()),
+            collateral_factor: 8000,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    pause_and_resume(&client, &admin);
+
+    let result = client.try_ca_borrow_asset(&user, &Some(usdc.clone()), &401);
+    assert_eq!(result, Err(Ok(CrossAssetError::ExceedsBorrowCapacity)));
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &400);
+
+    // Liquidation eligibility is unaffected by the haircut
+    assert!(!client.get_user_position_summary(&user).is_liquidatable);
+}
+
+#[test]
+fn test_set_recovery_haircut_validation() {
+    let env = create_test_env();
+    let (client, admin) = setup(&env);
+
+    let too_deep = client.try_set_recovery_haircut(&admin, &5_001, &WINDOW);
+    assert_eq!(too_deep, Err(Ok(RiskManagementError::InvalidParameter)));
+
+    let too_long = client.try_set_recovery_haircut(&admin, &1_000, &(8 * 86_400));
+    assert_eq!(too_long, Err(Ok(RiskManagementError::InvalidParameter)));
+
+    let not_admin = client.try_set_recovery_haircut(&Address::generate(&env), &1_000, &WINDOW);
+    assert_eq!(not_admin, Err(Ok(RiskManagementError::Unauthorized)));
+}
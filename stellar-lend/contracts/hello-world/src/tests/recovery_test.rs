@@ -0,0 +1,279 @@
+//! # Admin Recovery Tests
+//!
+//! Tests for guardian set validation, the propose/approve/execute flow, the
+//! quorum delay, and admin cancellation.
+
+use crate::interest_rate::InterestRateDataKey;
+use crate::recovery::{GuardianConfig, RecoveryError, MAX_GUARDIANS, MIN_RECOVERY_DELAY};
+use crate::roles::{Role, RoleError};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, IntoVal, Symbol, Vec,
+};
+
+/// Registers and initializes the contract; returns the contract, client and admin
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, client, admin)
+}
+
+fn guardians(env: &Env, count: u32) -> Vec<Address> {
+    let mut guardians = Vec::new(env);
+    for _ in 0..count {
+        guardians.push_back(Address::generate(env));
+    }
+    guardians
+}
+
+fn config(guardians: &Vec<Address>, threshold: u32) -> GuardianConfig {
+    GuardianConfig {
+        guardians: guardians.clone(),
+        threshold,
+        delay: MIN_RECOVERY_DELAY,
+    }
+}
+
+fn assert_last_topic(env: &Env, topic: &str) {
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(topics, (Symbol::new(env, topic),).into_val(env));
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+#[test]
+fn test_set_guardians_validates_config() {
+    let env = Env::default();
+    let (_, client, admin) = setup(&env);
+    let three = guardians(&env, 3);
+    let invalid = Err(Ok(RecoveryError::InvalidConfig));
+
+    assert_eq!(
+        client.try_set_guardians(&admin, &config(&Vec::new(&env), 1)),
+        invalid
+    );
+    assert_eq!(
+        client.try_set_guardians(&admin, &config(&three, 0)),
+        invalid
+    );
+    assert_eq!(
+        client.try_set_guardians(&admin, &config(&three, 4)),
+        invalid
+    );
+    assert_eq!(
+        client.try_set_guardians(&admin, &config(&guardians(&env, MAX_GUARDIANS + 1), 2)),
+        invalid
+    );
+
+    let mut duplicated = three.clone();
+    duplicated.push_back(three.get(0).unwrap());
+    assert_eq!(
+        client.try_set_guardians(&admin, &config(&duplicated, 2)),
+        invalid
+    );
+
+    let short_delay = GuardianConfig {
+        delay: MIN_RECOVERY_DELAY - 1,
+        ..config(&three, 2)
+    };
+    assert_eq!(client.try_set_guardians(&admin, &short_delay), invalid);
+
+    assert_eq!(client.get_guardians(), None);
+    client.set_guardians(&admin, &config(&three, 2));
+    assert_eq!(client.get_guardians(), Some(config(&three, 2)));
+}
+
+#[test]
+fn test_only_admin_sets_guardians() {
+    let env = Env::default();
+    let (_, client, _) = setup(&env);
+    let three = guardians(&env, 3);
+
+    assert_eq!(
+        client.try_set_guardians(&three.get(0).unwrap(), &config(&three, 2)),
+        Err(Ok(RecoveryError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_only_guardians_propose_and_approve() {
+    let env = Env::default();
+    let (_, client, admin) = setup(&env);
+    let outsider = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    // Nobody is a guardian before a set is configured
+    assert_eq!(
+        client.try_propose_recovery(&outsider, &new_admin),
+        Err(Ok(RecoveryError::NotGuardian))
+    );
+
+    let three = guardians(&env, 3);
+    client.set_guardians(&admin, &config(&three, 2));
+    assert_eq!(
+        client.try_propose_recovery(&outsider, &new_admin),
+        Err(Ok(RecoveryError::NotGuardian))
+    );
+    assert_eq!(
+        client.try_approve_recovery(&three.get(1).unwrap()),
+        Err(Ok(RecoveryError::NoRecovery))
+    );
+
+    client.propose_recovery(&three.get(0).unwrap(), &new_admin);
+    assert_eq!(
+        client.try_approve_recovery(&outsider),
+        Err(Ok(RecoveryError::NotGuardian))
+    );
+    assert_eq!(
+        client.try_approve_recovery(&three.get(0).unwrap()),
+        Err(Ok(RecoveryError::AlreadyApproved))
+    );
+    assert_eq!(
+        client.try_propose_recovery(&three.get(1).unwrap(), &outsider),
+        Err(Ok(RecoveryError::RecoveryPending))
+    );
+}
+
+#[test]
+fn test_recovery_rotates_admin_after_quorum_and_delay() {
+    let env = Env::default();
+    let (contract_id, client, admin) = setup(&env);
+    let three = guardians(&env, 3);
+    let new_admin = Address::generate(&env);
+    client.set_guardians(&admin, &config(&three, 2));
+
+    client.propose_recovery(&three.get(0).unwrap(), &new_admin);
+    assert_eq!(
+        client.try_execute_recovery(),
+        Err(Ok(RecoveryError::QuorumNotReached))
+    );
+
+    // The delay runs from quorum, not from the proposal
+    advance(&env, MIN_RECOVERY_DELAY);
+    client.approve_recovery(&three.get(2).unwrap());
+    let request = client.get_recovery().unwrap();
+    assert_eq!(request.approvals.len(), 2);
+    assert_eq!(request.quorum_at, Some(1_000 + MIN_RECOVERY_DELAY));
+
+    advance(&env, MIN_RECOVERY_DELAY - 1);
+    assert_eq!(
+        client.try_execute_recovery(),
+        Err(Ok(RecoveryError::DelayNotElapsed))
+    );
+
+    advance(&env, 1);
+    client.execute_recovery();
+    assert_eq!(client.get_recovery(), None);
+
+    let account = Address::generate(&env);
+    assert_eq!(
+        client.try_grant_role(&admin, &Role::RiskAdmin, &account),
+        Err(Ok(RoleError::Unauthorized))
+    );
+    client.grant_role(&new_admin, &Role::RiskAdmin, &account);
+    assert!(client.has_role(&Role::RiskAdmin, &account));
+
+    let rate_admin: Option<Address> = env.as_contract(&contract_id, || {
+        env.storage().instance().get(&InterestRateDataKey::Admin)
+    });
+    assert_eq!(rate_admin, Some(new_admin));
+}
+
+#[test]
+fn test_single_guardian_threshold_reaches_quorum_on_proposal() {
+    let env = Env::default();
+    let (_, client, admin) = setup(&env);
+    let two = guardians(&env, 2);
+    let new_admin = Address::generate(&env);
+    client.set_guardians(&admin, &config(&two, 1));
+
+    client.propose_recovery(&two.get(1).unwrap(), &new_admin);
+    assert_eq!(client.get_recovery().unwrap().quorum_at, Some(1_000));
+
+    advance(&env, MIN_RECOVERY_DELAY);
+    client.execute_recovery();
+    assert_eq!(
+        client.try_set_guardians(&admin, &config(&two, 1)),
+        Err(Ok(RecoveryError::Unauthorized))
+    );
+    client.set_guardians(&new_admin, &config(&two, 1));
+}
+
+#[test]
+fn test_admin_cancels_recovery() {
+    let env = Env::default();
+    let (_, client, admin) = setup(&env);
+    let three = guardians(&env, 3);
+    client.set_guardians(&admin, &config(&three, 2));
+
+    assert_eq!(
+        client.try_cancel_recovery(&admin),
+        Err(Ok(RecoveryError::NoRecovery))
+    );
+
+    let attacker = Address::generate(&env);
+    client.propose_recovery(&three.get(0).unwrap(), &attacker);
+    client.approve_recovery(&three.get(1).unwrap());
+    assert_eq!(
+        client.try_cancel_recovery(&three.get(2).unwrap()),
+        Err(Ok(RecoveryError::Unauthorized))
+    );
+    client.cancel_recovery(&admin);
+    assert_eq!(client.get_recovery(), None);
+
+    advance(&env, MIN_RECOVERY_DELAY);
+    assert_eq!(
+        client.try_execute_recovery(),
+        Err(Ok(RecoveryError::NoRecovery))
+    );
+    // A new recovery can be proposed once the old one is gone
+    client.propose_recovery(&three.get(2).unwrap(), &attacker);
+}
+
+#[test]
+fn test_changing_guardians_cancels_pending_recovery() {
+    let env = Env::default();
+    let (_, client, admin) = setup(&env);
+    let three = guardians(&env, 3);
+    client.set_guardians(&admin, &config(&three, 2));
+    client.propose_recovery(&three.get(0).unwrap(), &Address::generate(&env));
+
+    let replacement = guardians(&env, 2);
+    client.set_guardians(&admin, &config(&replacement, 2));
+    assert_eq!(client.get_recovery(), None);
+    // Replaced guardians lose their power
+    assert_eq!(
+        client.try_propose_recovery(&three.get(0).unwrap(), &Address::generate(&env)),
+        Err(Ok(RecoveryError::NotGuardian))
+    );
+}
+
+#[test]
+fn test_recovery_emits_events() {
+    let env = Env::default();
+    let (_, client, admin) = setup(&env);
+    let two = guardians(&env, 2);
+
+    client.set_guardians(&admin, &config(&two, 2));
+    assert_last_topic(&env, "guardians_updated_event");
+    client.propose_recovery(&two.get(0).unwrap(), &Address::generate(&env));
+    assert_last_topic(&env, "recovery_proposed_event");
+    client.approve_recovery(&two.get(1).unwrap());
+    assert_last_topic(&env, "recovery_approved_event");
+    client.cancel_recovery(&admin);
+    assert_last_topic(&env, "recovery_cancelled_event");
+
+    client.propose_recovery(&two.get(1).unwrap(), &Address::generate(&env));
+    client.approve_recovery(&two.get(0).unwrap());
+    advance(&env, MIN_RECOVERY_DELAY);
+    client.execute_recovery();
+    assert_last_topic(&env, "admin_recovered_event");
+}
@@ -0,0 +1,231 @@
+//! # Re-entrancy Guard Tests
+//!
+//! Tests for the operation-in-progress guard. A malicious token calls back
+//! into the contract from inside its `transfer` / `transfer_from` hooks; the
+//! nested call must fail and the outer operation must be applied exactly once.
+
+use crate::borrow::{borrow_asset, BorrowError};
+use crate::deposit::{deposit_collateral, DepositDataKey, DepositError, Position};
+use crate::flash_loan::{flash_loan, FlashLoanError};
+use crate::liquidate::{liquidate, LiquidationError};
+use crate::reentrancy::GuardDataKey;
+use crate::repay::{repay_debt, RepayError};
+use crate::withdraw::{withdraw_collateral, WithdrawError};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, testutils::Address as _, Address, Bytes,
+    Env, Symbol,
+};
+
+#[contracttype]
+enum MaliciousKey {
+    Target,
+    Attack,
+    Reentered,
+}
+
+/// Token whose transfer hooks try to re-enter the lending contract
+#[contract]
+pub struct MaliciousToken;
+
+#[contractimpl]
+impl MaliciousToken {
+    /// Arm the token to call `attack` ("deposit", "withdraw" or "borrow") on `target`
+    pub fn arm(env: Env, target: Address, attack: Symbol) {
+        env.storage().instance().set(&MaliciousKey::Target, &target);
+        env.storage().instance().set(&MaliciousKey::Attack, &attack);
+    }
+
+    /// Outcome of the re-entry attempt: `Some(true)` if the nested call succeeded
+    pub fn reentered(env: Env) -> Option<bool> {
+        env.storage().instance().get(&MaliciousKey::Reentered)
+    }
+
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        1_000_000_000
+    }
+
+    pub fn transfer(env: Env, _from: Address, to: Address, amount: i128) {
+        Self::attack(&env, to, amount);
+    }
+
+    pub fn transfer_from(env: Env, _spender: Address, from: Address, _to: Address, amount: i128) {
+        Self::attack(&env, from, amount);
+    }
+}
+
+impl MaliciousToken {
+    fn attack(env: &Env, user: Address, amount: i128) {
+        let Some(target) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&MaliciousKey::Target)
+        else {
+            return;
+        };
+        let attack: Symbol = env.storage().instance().get(&MaliciousKey::Attack).unwrap();
+        let asset = Some(env.current_contract_address());
+        let client = HelloContractClient::new(env, &target);
+
+        let succeeded = if attack == symbol_short!("deposit") {
+            client
+                .try_deposit_collateral(&user, &asset, &amount)
+                .is_ok()
+        } else if attack == symbol_short!("withdraw") {
+            client
+                .try_withdraw_collateral(&user, &asset, &amount)
+                .is_ok()
+        } else {
+            client.try_borrow_asset(&user, &asset, &amount).is_ok()
+        };
+        env.storage()
+            .instance()
+            .set(&MaliciousKey::Reentered, &succeeded);
+    }
+}
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    client.initialize(&Address::generate(env));
+    (contract_id, client)
+}
+
+fn set_flag(env: &Env, contract_id: &Address) {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .temporary()
+            .set(&GuardDataKey::OperationInProgress, &true);
+    });
+}
+
+fn in_progress(env: &Env, contract_id: &Address) -> bool {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .temporary()
+            .has(&GuardDataKey::OperationInProgress)
+    })
+}
+
+fn position(env: &Env, contract_id: &Address, user: &Address) -> Position {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get::<DepositDataKey, Position>(&DepositDataKey::Position(user.clone()))
+            .unwrap()
+    })
+}
+
+#[test]
+fn test_guarded_operations_rejected_while_in_progress() {
+    let env = create_test_env();
+    let (contract_id, _) = setup(&env);
+    set_flag(&env, &contract_id);
+    let user = Address::generate(&env);
+    let asset = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            deposit_collateral(&env, user.clone(), None, 100, None),
+            Err(DepositError::Reentrancy)
+        );
+        assert_eq!(
+            withdraw_collateral(&env, user.clone(), None, 100),
+            Err(WithdrawError::Reentrancy)
+        );
+        assert_eq!(
+            borrow_asset(&env, user.clone(), None, 100, None, None),
+            Err(BorrowError::Reentrancy)
+        );
+        assert_eq!(
+            repay_debt(&env, user.clone(), None, 100),
+            Err(RepayError::Reentrancy)
+        );
+        assert_eq!(
+            liquidate(&env, user.clone(), user.clone(), None, None, 100),
+            Err(LiquidationError::Reentrancy)
+        );
+        assert_eq!(
+            flash_loan(&env, user.clone(), asset.clone(), 100, Bytes::new(&env)),
+            Err(FlashLoanError::Reentrancy)
+        );
+    });
+}
+
+#[test]
+fn test_guard_released_after_operation() {
+    let env = create_test_env();
+    let (contract_id, client) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+    assert!(!in_progress(&env, &contract_id));
+
+    // A later operation is not blocked by the earlier one
+    client.deposit_collateral(&user, &None, &1_000);
+    assert_eq!(position(&env, &contract_id, &user).collateral, 2_000);
+}
+
+#[test]
+fn test_guard_released_after_failed_operation() {
+    let env = create_test_env();
+    let (contract_id, client) = setup(&env);
+    let user = Address::generate(&env);
+
+    assert!(client.try_withdraw_collateral(&user, &None, &1).is_err());
+    assert!(!in_progress(&env, &contract_id));
+    client.deposit_collateral(&user, &None, &1_000);
+}
+
+#[test]
+fn test_token_cannot_reenter_deposit() {
+    let env = create_test_env();
+    let (contract_id, client) = setup(&env);
+    let token_id = env.register(MaliciousToken, ());
+    let token = MaliciousTokenClient::new(&env, &token_id);
+    token.arm(&contract_id, &symbol_short!("deposit"));
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &Some(token_id.clone()), &500);
+
+    assert_eq!(token.reentered(), Some(false));
+    assert_eq!(position(&env, &contract_id, &user).collateral, 500);
+}
+
+#[test]
+fn test_token_cannot_reenter_withdraw() {
+    let env = create_test_env();
+    let (contract_id, client) = setup(&env);
+    let token_id = env.register(MaliciousToken, ());
+    let token = MaliciousTokenClient::new(&env, &token_id);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &Some(token_id.clone()), &1_000);
+
+    token.arm(&contract_id, &symbol_short!("withdraw"));
+    client.withdraw_collateral(&user, &Some(token_id.clone()), &400);
+
+    assert_eq!(token.reentered(), Some(false));
+    assert_eq!(position(&env, &contract_id, &user).collateral, 600);
+}
+
+#[test]
+fn test_token_cannot_reenter_borrow() {
+    let env = create_test_env();
+    let (contract_id, client) = setup(&env);
+    let token_id = env.register(MaliciousToken, ());
+    let token = MaliciousTokenClient::new(&env, &token_id);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &Some(token_id.clone()), &10_000);
+
+    token.arm(&contract_id, &symbol_short!("borrow"));
+    client.borrow_asset(&user, &Some(token_id.clone()), &1_000);
+
+    assert_eq!(token.reentered(), Some(false));
+    assert_eq!(position(&env, &contract_id, &user).debt, 1_000);
+}
@@ -0,0 +1,120 @@
+//! # Repay From Supply Tests
+//!
+//! Tests for netting a user's supplied balance against debt in the same asset
+//! without token transfers.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn asset_config(
+    env: &Env,
+    asset: &Address,
+    collateral_factor: i128,
+    borrow_factor: i128,
+) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor,
+        borrow_factor,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    client.initialize_asset(
+        &admin,
+        &Some(usdc.clone()),
+        &asset_config(env, &usdc, 8000, 10000),
+    );
+    (client, usdc, admin)
+}
+
+#[test]
+fn test_repay_from_supply_nets_balances() {
+    let env = create_test_env();
+    let (client, usdc, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &400);
+
+    let position = client.repay_from_supply(&user, &Some(usdc.clone()), &150);
+    assert_eq!(position.collateral, 850);
+    assert_eq!(position.debt_principal, 250);
+}
+
+#[test]
+fn test_repay_from_supply_capped_at_debt() {
+    let env = create_test_env();
+    let (client, usdc, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &400);
+
+    let position = client.repay_from_supply(&user, &Some(usdc.clone()), &5_000);
+    assert_eq!(position.collateral, 600);
+    assert_eq!(position.debt_principal, 0);
+    assert_eq!(client.get_user_position_summary(&user).total_debt_value, 0);
+}
+
+#[test]
+fn test_repay_from_supply_requires_debt_and_supply() {
+    let env = create_test_env();
+    let (client, usdc, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    let result = client.try_repay_from_supply(&user, &Some(usdc.clone()), &100);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+
+    let result = client.try_repay_from_supply(&user, &Some(usdc), &0);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+}
+
+#[test]
+fn test_repay_from_supply_rejects_unhealthy_result() {
+    let env = create_test_env();
+    let (client, usdc, admin) = setup(&env);
+    let user = Address::generate(&env);
+
+    // High collateral factor, low borrow factor: netting removes far more
+    // collateral weight than debt weight.
+    let gov = create_token(&env);
+    client.initialize_asset(
+        &admin,
+        &Some(gov.clone()),
+        &asset_config(&env, &gov, 9000, 1000),
+    );
+
+    client.ca_deposit_collateral(&user, &Some(gov.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc), &700);
+    client.ca_borrow_asset(&user, &Some(gov.clone()), &1_000);
+
+    let result = client.try_repay_from_supply(&user, &Some(gov.clone()), &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::UnhealthyPosition)));
+
+    let position = client.get_user_asset_position(&user, &Some(gov));
+    assert_eq!(position.collateral, 1_000);
+    assert_eq!(position.debt_principal, 1_000);
+}
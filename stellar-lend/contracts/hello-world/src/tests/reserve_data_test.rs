@@ -0,0 +1,137 @@
+//! # Reserve Data Tests
+//!
+//! Tests for the per-asset aggregate view used by frontends.
+
+use crate::cross_asset::{AssetConfig, AssetKey, CrossAssetError};
+use crate::rate_strategy::RateStrategy;
+use crate::tests::lending_pool_test::repay_core_interest;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, Symbol,
+};
+
+/// Registers USDC and XLM at a price of 1.0 with a 10% reserve factor, and a
+/// 12% borrow rate at 80% utilization on USDC
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 50_000,
+                max_borrow: 40_000,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &admin,
+        &Some(usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    (contract_id, client, admin, usdc, xlm)
+}
+
+#[test]
+fn test_reserve_data_of_idle_market() {
+    let env = Env::default();
+    let (_, client, _, usdc, _) = setup(&env);
+
+    let data = client.get_reserve_data(&Some(usdc.clone()));
+    assert_eq!(data.asset, AssetKey::Token(usdc));
+    assert_eq!(data.total_supplied, 0);
+    assert_eq!(data.total_borrowed, 0);
+    assert_eq!(data.utilization, 0);
+    assert_eq!(data.borrow_apr, 200);
+    assert_eq!(data.supply_apy, 0);
+    assert_eq!(data.supply_cap, 50_000);
+    assert_eq!(data.borrow_cap, 40_000);
+    assert_eq!(data.collateral_factor, 7500);
+    assert_eq!(data.liquidation_threshold, 7500);
+    assert!(!data.deposit_paused && !data.borrow_paused && !data.liquidate_paused);
+    assert_eq!(data.reserves, 0);
+}
+
+#[test]
+fn test_reserve_data_tracks_borrows_and_reserves() {
+    let env = Env::default();
+    let (contract_id, client, _, usdc_address, xlm) = setup(&env);
+    let usdc = Some(usdc_address.clone());
+
+    let supplier = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_address).mint(&supplier, &10_000);
+    TokenClient::new(&env, &usdc_address).approve(
+        &supplier,
+        &contract_id,
+        &10_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&supplier, &usdc, &10_000);
+    client.ca_deposit_collateral(&Address::generate(&env), &usdc, &10_000);
+    let borrower = Address::generate(&env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &20_000);
+    client.ca_borrow_asset(&borrower, &usdc, &8_000);
+
+    let data = client.get_reserve_data(&usdc);
+    assert_eq!(data.total_supplied, 10_000);
+    assert_eq!(data.total_borrowed, 8_000);
+    assert_eq!(data.utilization, 8000);
+    assert_eq!(data.borrow_apr, 1200);
+    // 12% x 80% utilization, less the 10% reserve factor
+    assert_eq!(data.supply_apy, 864);
+
+    // A year of interest repaid in USDC, less the 10% reserve factor
+    repay_core_interest(&env, &client, &usdc_address, 960);
+    assert_eq!(client.get_reserve_data(&usdc).reserves, 96);
+}
+
+#[test]
+fn test_reserve_data_reports_pauses() {
+    let env = Env::default();
+    let (_, client, admin, usdc, xlm) = setup(&env);
+    let usdc = Some(usdc);
+    client.set_asset_pause(&admin, &usdc, &Symbol::new(&env, "pause_borrow"), &true);
+
+    let data = client.get_reserve_data(&usdc);
+    assert!(data.borrow_paused);
+    assert!(!data.deposit_paused && !data.withdraw_paused && !data.repay_paused);
+
+    // An emergency pause halts every operation on every market
+    client.set_emergency_pause(&admin, &true);
+    let data = client.get_reserve_data(&Some(xlm));
+    assert!(data.deposit_paused && data.withdraw_paused && data.borrow_paused);
+    assert!(data.repay_paused && data.liquidate_paused);
+}
+
+#[test]
+fn test_reserve_data_of_unlisted_asset() {
+    let env = Env::default();
+    let (_, client, _, _, _) = setup(&env);
+
+    let result = client.try_get_reserve_data(&Some(Address::generate(&env)));
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+}
@@ -0,0 +1,373 @@
+//! # Reward Emissions Tests
+//!
+//! Tests that emissions are shared pro rata between a market's pool
+//! suppliers, checkpointed on supply share changes and settled on cross-asset
+//! withdrawals and liquidation seizures, and claimed up to the funded balance
+//! by users or their delegates, that cross-asset positions, which are not
+//! token-backed, earn nothing, and that reward programs are reported over
+//! their windows.
+
+use crate::cross_asset::AssetConfig;
+use crate::cross_asset::AssetKey;
+use crate::rewards::{EmissionRates, RewardsDataKey, RewardsError, UserRewardIndex};
+use crate::storage;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+    reward: Address,
+}
+
+/// Lists USDC and XLM at a price of 1.0 and sets a reward token
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: create_token(env),
+        xlm: create_token(env),
+        reward: env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address(),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_reward_token(&market.admin, &market.reward);
+    (client, market)
+}
+
+/// Mints `amount` reward tokens to a funder and adds them to the balance
+fn fund(env: &Env, client: &HelloContractClient<'_>, market: &Market, amount: i128) {
+    let funder = Address::generate(env);
+    StellarAssetClient::new(env, &market.reward).mint(&funder, &amount);
+    TokenClient::new(env, &market.reward).approve(&funder, &client.address, &amount, &10_000);
+    client.fund_rewards(&funder, &amount);
+}
+
+/// Mints `amount` of the asset to a new user and supplies it to the pool
+fn supplier(env: &Env, client: &HelloContractClient<'_>, asset: &Address, amount: i128) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, asset).mint(&user, &amount);
+    TokenClient::new(env, asset).approve(&user, &client.address, &amount, &10_000);
+    client.supply(&user, &Some(asset.clone()), &amount);
+    user
+}
+
+/// The user's last reward checkpoint in the asset's market
+fn checkpoint_of(
+    env: &Env,
+    client: &HelloContractClient<'_>,
+    user: &Address,
+    asset: &Address,
+) -> UserRewardIndex {
+    env.as_contract(&client.address, || {
+        storage::get(
+            env,
+            &RewardsDataKey::User(user.clone(), AssetKey::Token(asset.clone())),
+        )
+        .unwrap()
+    })
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+#[test]
+fn test_suppliers_share_emissions_pro_rata() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    client.set_emission_rates(&market.admin, &xlm, &EmissionRates { supply_rate: 100 });
+
+    let small = supplier(&env, &client, &market.xlm, 1_000);
+    let large = supplier(&env, &client, &market.xlm, 3_000);
+    advance(&env, 100);
+    assert_eq!(client.get_pending_rewards(&small), 2_500);
+    assert_eq!(client.get_pending_rewards(&large), 7_500);
+
+    // Redeeming checkpoints the earlier balance before the new one applies
+    client.redeem(&large, &xlm, &2_000);
+    advance(&env, 100);
+    assert_eq!(client.get_pending_rewards(&small), 7_500);
+    assert_eq!(client.get_pending_rewards(&large), 12_500);
+
+    fund(&env, &client, &market, 20_000);
+    assert_eq!(client.claim_rewards(&small, &small), 7_500);
+    assert_eq!(
+        TokenClient::new(&env, &market.reward).balance(&small),
+        7_500
+    );
+    assert_eq!(client.get_pending_rewards(&small), 0);
+    assert_eq!(client.get_rewards_balance(), 12_500);
+}
+
+#[test]
+fn test_claims_paid_up_to_funded_balance() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    client.set_emission_rates(&market.admin, &usdc, &EmissionRates { supply_rate: 10 });
+
+    let user = supplier(&env, &client, &market.usdc, 1_000);
+    advance(&env, 50);
+    assert_eq!(client.get_pending_rewards(&user), 500);
+
+    fund(&env, &client, &market, 300);
+    assert_eq!(client.claim_rewards(&user, &user), 300);
+    assert_eq!(client.get_pending_rewards(&user), 200);
+    assert_eq!(client.get_rewards_balance(), 0);
+    assert_eq!(client.claim_rewards(&user, &user), 0);
+}
+
+#[test]
+fn test_delegate_claims_to_user() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    client.set_emission_rates(&market.admin, &xlm, &EmissionRates { supply_rate: 10 });
+    let user = supplier(&env, &client, &market.xlm, 1_000);
+    let keeper = Address::generate(&env);
+    advance(&env, 100);
+    fund(&env, &client, &market, 1_000);
+
+    let result = client.try_claim_rewards(&keeper, &user);
+    assert_eq!(result, Err(Ok(RewardsError::NotDelegate)));
+
+    client.set_reward_delegate(&user, &Some(keeper.clone()));
+    assert_eq!(client.get_reward_delegate(&user), Some(keeper.clone()));
+    assert_eq!(client.claim_rewards(&keeper, &user), 1_000);
+    let reward = TokenClient::new(&env, &market.reward);
+    assert_eq!(reward.balance(&user), 1_000);
+    assert_eq!(reward.balance(&keeper), 0);
+
+    client.set_reward_delegate(&user, &None);
+    assert_eq!(client.get_reward_delegate(&user), None);
+    let result = client.try_claim_rewards(&keeper, &user);
+    assert_eq!(result, Err(Ok(RewardsError::NotDelegate)));
+}
+
+#[test]
+fn test_cross_asset_positions_earn_nothing() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    client.set_emission_rates(&market.admin, &xlm, &EmissionRates { supply_rate: 10 });
+
+    // An unbacked collateral entry does not dilute the pool's suppliers
+    let depositor = Address::generate(&env);
+    client.ca_deposit_collateral(&depositor, &xlm, &1_000_000);
+    client.ca_borrow_asset(&depositor, &xlm, &1_000);
+    let user = supplier(&env, &client, &market.xlm, 1_000);
+    advance(&env, 100);
+
+    assert_eq!(client.get_pending_rewards(&depositor), 0);
+    assert_eq!(client.get_pending_rewards(&user), 1_000);
+    assert_eq!(client.get_reward_index(&xlm).total_supplied, 1_000);
+}
+
+#[test]
+fn test_redeem_checkpoints_rewards() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    client.set_emission_rates(&market.admin, &xlm, &EmissionRates { supply_rate: 10 });
+    let user = supplier(&env, &client, &market.xlm, 1_000);
+    advance(&env, 100);
+
+    client.redeem(&user, &xlm, &1_000);
+    let checkpoint = checkpoint_of(&env, &client, &user, &market.xlm);
+    assert_eq!(checkpoint.supplied, 0);
+    assert_eq!(
+        checkpoint.supply_index,
+        client.get_reward_index(&xlm).supply_index
+    );
+
+    // Nothing is earned once the shares are gone
+    supplier(&env, &client, &market.xlm, 1_000);
+    advance(&env, 100);
+    assert_eq!(client.get_pending_rewards(&user), 1_000);
+}
+
+#[test]
+fn test_withdraw_settles_rewards() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    client.set_emission_rates(&market.admin, &xlm, &EmissionRates { supply_rate: 10 });
+    let user = supplier(&env, &client, &market.xlm, 1_000);
+    client.ca_deposit_collateral(&user, &xlm, &500);
+    advance(&env, 100);
+
+    client.ca_withdraw_collateral(&user, &xlm, &500);
+    let checkpoint = checkpoint_of(&env, &client, &user, &market.xlm);
+    assert_eq!(checkpoint.supplied, 1_000);
+    assert_eq!(
+        checkpoint.supply_index,
+        client.get_reward_index(&xlm).supply_index
+    );
+    assert_eq!(client.get_pending_rewards(&user), 1_000);
+}
+
+#[test]
+fn test_liquidation_seizure_settles_rewards() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    let usdc = Some(market.usdc.clone());
+    client.set_emission_rates(&market.admin, &xlm, &EmissionRates { supply_rate: 10 });
+
+    // The borrower supplies XLM to the pool and borrows USDC against XLM
+    let borrower = supplier(&env, &client, &market.xlm, 1_000);
+    client.ca_deposit_collateral(&borrower, &xlm, &1_000);
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &usdc, &5_000);
+    client.ca_borrow_asset(&borrower, &usdc, &700);
+    advance(&env, 100);
+
+    client.update_asset_price(&market.admin, &xlm, &6_000_000);
+    client.ca_liquidate(
+        &liquidator,
+        &borrower,
+        &usdc,
+        &vec![&env, (xlm.clone(), 100)],
+    );
+    let checkpoint = checkpoint_of(&env, &client, &borrower, &market.xlm);
+    assert_eq!(checkpoint.supplied, 1_000);
+    assert_eq!(
+        checkpoint.supply_index,
+        client.get_reward_index(&xlm).supply_index
+    );
+    assert_eq!(client.get_pending_rewards(&borrower), 1_000);
+}
+
+#[test]
+fn test_rate_change_indexes_previous_rate() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    let rates = |supply_rate| EmissionRates { supply_rate };
+    client.set_emission_rates(&market.admin, &xlm, &rates(10));
+
+    let supplier = supplier(&env, &client, &market.xlm, 1_000);
+    advance(&env, 100);
+    client.set_emission_rates(&market.admin, &xlm, &rates(0));
+    advance(&env, 100);
+    assert_eq!(client.get_pending_rewards(&supplier), 1_000);
+    assert_eq!(client.get_emission_rates(&xlm), rates(0));
+
+    client.set_emission_rates(&market.admin, &xlm, &rates(5));
+    advance(&env, 100);
+    assert_eq!(client.get_pending_rewards(&supplier), 1_500);
+    assert_eq!(client.get_reward_index(&xlm).total_supplied, 1_000);
+}
+
+#[test]
+fn test_program_report_over_window() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let xlm = Some(market.xlm.clone());
+    let rates = |supply_rate| EmissionRates { supply_rate };
+    client.set_emission_rates(&market.admin, &xlm, &rates(10));
+
+    let first = supplier(&env, &client, &market.xlm, 1_000);
+    advance(&env, 100);
+    supplier(&env, &client, &market.xlm, 3_000);
+    advance(&env, 100);
+    fund(&env, &client, &market, 10_000);
+    assert_eq!(client.claim_rewards(&first, &first), 1_250);
+
+    let report = client.get_program_report(&1);
+    assert_eq!(report.asset, xlm);
+    assert_eq!(report.started_at, 1_000);
+    assert_eq!(report.ended_at, None);
+    assert_eq!(report.emission_spent, 2_000);
+    assert_eq!(report.unique_claimants, 1);
+    assert_eq!(report.average_boost_bps, 10_000);
+    assert_eq!(report.incremental_tvl, 4_000);
+
+    // A new rate closes the window and starts the next program
+    client.set_emission_rates(&market.admin, &xlm, &rates(20));
+    advance(&env, 100);
+    let report = client.get_program_report(&1);
+    assert_eq!(report.ended_at, Some(1_200));
+    assert_eq!(report.emission_spent, 2_000);
+    let report = client.get_program_report(&2);
+    assert_eq!(report.started_at, 1_200);
+    assert_eq!(report.emission_spent, 2_000);
+    assert_eq!(report.unique_claimants, 0);
+    assert_eq!(report.average_boost_bps, 0);
+    assert_eq!(report.incremental_tvl, 0);
+
+    // Stopping emissions ends the program without starting another
+    client.set_emission_rates(&market.admin, &xlm, &rates(0));
+    assert_eq!(client.get_program_report(&2).ended_at, Some(1_300));
+    let result = client.try_get_program_report(&3);
+    assert_eq!(result, Err(Ok(RewardsError::ProgramNotFound)));
+}
+
+#[test]
+fn test_rewards_rejections() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let rates = EmissionRates { supply_rate: 10 };
+
+    let result = client.try_set_reward_token(&market.admin, &market.usdc);
+    assert_eq!(result, Err(Ok(RewardsError::TokenAlreadySet)));
+    let result =
+        client.try_set_emission_rates(&Address::generate(&env), &Some(market.xlm.clone()), &rates);
+    assert_eq!(result, Err(Ok(RewardsError::Unauthorized)));
+    let result =
+        client.try_set_emission_rates(&market.admin, &Some(Address::generate(&env)), &rates);
+    assert_eq!(result, Err(Ok(RewardsError::AssetNotConfigured)));
+    let result = client.try_set_emission_rates(
+        &market.admin,
+        &Some(market.xlm.clone()),
+        &EmissionRates { supply_rate: -1 },
+    );
+    assert_eq!(result, Err(Ok(RewardsError::InvalidAmount)));
+    let result = client.try_fund_rewards(&market.admin, &0);
+    assert_eq!(result, Err(Ok(RewardsError::InvalidAmount)));
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = HelloContractClient::new(&env, &env.register(HelloContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    let result = client.try_set_emission_rates(&admin, &None, &rates);
+    assert_eq!(result, Err(Ok(RewardsError::TokenNotSet)));
+    let user = Address::generate(&env);
+    let result = client.try_claim_rewards(&user, &user);
+    assert_eq!(result, Err(Ok(RewardsError::TokenNotSet)));
+}
@@ -0,0 +1,196 @@
+//! # Risk Badge Tests
+//!
+//! Tests that `UserReport` flags high utilization, collateral concentration,
+//! a missing liquidation buffer and stable-rate heavy debt from the
+//! configured thresholds.
+
+use crate::analytics::{AnalyticsError, RiskBadge, RiskBadgeConfig};
+use crate::cross_asset::AssetConfig;
+use crate::deposit::{AssetParams, DepositDataKey};
+use crate::rate_mode::RateMode;
+use crate::roles::Role;
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env,
+};
+
+/// Registers the contract, initializes cross-asset lending and enables a
+/// token for core deposits
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    client.grant_role(&admin, &Role::RiskAdmin, &admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DepositDataKey::AssetParams(token.clone()),
+            &AssetParams {
+                deposit_enabled: true,
+                collateral_factor: 7500,
+                max_deposit: 0,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    });
+    (contract_id, client, admin, token)
+}
+
+/// A user holding and approving 1,000,000 of `token`
+fn fund(env: &Env, contract_id: &Address, token: &Address) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, token).mint(&user, &1_000_000);
+    TokenClient::new(env, token).approve(&user, contract_id, &10_000_000, &1_000);
+    user
+}
+
+/// Lists an asset for cross-asset lending at a price of 1.0 and 75% collateral factor
+fn list(env: &Env, client: &HelloContractClient<'_>, admin: &Address) -> Option<Address> {
+    let asset = Some(create_token(env));
+    client.initialize_asset(
+        admin,
+        &asset,
+        &AssetConfig {
+            asset: asset.clone(),
+            collateral_factor: 7500,
+            borrow_factor: 10000,
+            reserve_factor: 1000,
+            max_supply: 0,
+            max_borrow: 0,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 10_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        },
+    );
+    asset
+}
+
+#[test]
+fn test_no_badges_without_debt() {
+    let env = Env::default();
+    let (contract_id, client, _, token) = setup(&env);
+    let user = fund(&env, &contract_id, &token);
+
+    client.deposit_collateral(&user, &Some(token), &1_000_000);
+
+    assert!(client.get_user_report(&user).badges.is_empty());
+}
+
+#[test]
+fn test_core_badges_follow_thresholds() {
+    let env = Env::default();
+    let (contract_id, client, admin, token) = setup(&env);
+    let user = fund(&env, &contract_id, &token);
+    let asset = Some(token);
+    client.deposit_collateral(&user, &asset, &1_000_000);
+    client.borrow_asset_with_rate_mode(&user, &asset, &490_000, &RateMode::Stable);
+
+    // 49% utilization and a 2.04 collateral ratio clear the defaults
+    assert_eq!(
+        client.get_user_report(&user).badges,
+        vec![&env, RiskBadge::StableRateHeavy]
+    );
+
+    client.set_risk_badge_config(
+        &admin,
+        &RiskBadgeConfig {
+            high_utilization_bps: 4_500,
+            concentration_bps: 9_000,
+            liquidation_buffer_bps: 10_000,
+            stable_rate_share_bps: 5_000,
+        },
+    );
+    assert_eq!(
+        client.get_user_report(&user).badges,
+        vec![
+            &env,
+            RiskBadge::HighUtilization,
+            RiskBadge::NoLiquidationBuffer,
+            RiskBadge::StableRateHeavy
+        ]
+    );
+}
+
+#[test]
+fn test_concentrated_cross_asset_collateral() {
+    let env = Env::default();
+    let (_, client, admin, _) = setup(&env);
+    let usdc = list(&env, &client, &admin);
+    let xlm = list(&env, &client, &admin);
+    let eth = list(&env, &client, &admin);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    // Concentration only matters once the collateral backs debt
+    client.ca_deposit_collateral(&user, &xlm, &900);
+    client.ca_deposit_collateral(&user, &eth, &100);
+    assert!(client.get_user_report(&user).badges.is_empty());
+
+    client.ca_borrow_asset(&user, &usdc, &100);
+    assert_eq!(
+        client.get_user_report(&user).badges,
+        vec![&env, RiskBadge::CollateralConcentration]
+    );
+
+    client.ca_deposit_collateral(&user, &eth, &800);
+    assert!(client.get_user_report(&user).badges.is_empty());
+
+    // 700 of debt against 1,350 of weighted collateral: health factor 1.93
+    client.ca_borrow_asset(&user, &usdc, &600);
+    assert!(client.get_user_report(&user).badges.is_empty());
+    client.ca_borrow_asset(&user, &usdc, &600);
+    assert_eq!(
+        client.get_user_report(&user).badges,
+        vec![&env, RiskBadge::NoLiquidationBuffer]
+    );
+}
+
+#[test]
+fn test_badge_config_is_validated_and_restricted() {
+    let env = Env::default();
+    let (_, client, admin, _) = setup(&env);
+    let config = RiskBadgeConfig {
+        high_utilization_bps: 8_000,
+        concentration_bps: 9_000,
+        liquidation_buffer_bps: 0,
+        stable_rate_share_bps: 5_000,
+    };
+
+    assert_eq!(
+        client.try_set_risk_badge_config(&Address::generate(&env), &config),
+        Err(Ok(AnalyticsError::Unauthorized))
+    );
+    for invalid in [
+        RiskBadgeConfig {
+            high_utilization_bps: 0,
+            ..config.clone()
+        },
+        RiskBadgeConfig {
+            concentration_bps: 10_001,
+            ..config.clone()
+        },
+        RiskBadgeConfig {
+            liquidation_buffer_bps: -1,
+            ..config.clone()
+        },
+    ] {
+        assert_eq!(
+            client.try_set_risk_badge_config(&admin, &invalid),
+            Err(Ok(AnalyticsError::InvalidParameter))
+        );
+    }
+
+    client.set_risk_badge_config(&admin, &config);
+    assert_eq!(client.get_risk_badge_config(), config);
+}
@@ -290,9 +290,9 @@ fn risk_params_enforcement_liquidation_incentive_amount() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
 
-    assert_eq!(client.get_liquidation_incentive_amount(&1_000), 100);
+    assert_eq!(client.get_liquidation_incentive_amount(&1_000, &9_500), 100);
     client.set_risk_params(&admin, &None, &None, &None, &Some(1_100));
-    assert_eq!(client.get_liquidation_incentive_amount(&1_000), 110);
+    assert_eq!(client.get_liquidation_incentive_amount(&1_000, &9_500), 110);
 }
 
 // =============================================================================
@@ -423,7 +423,7 @@ fn risk_params_edge_max_liquidatable_zero_debt() {
 fn risk_params_edge_liquidation_incentive_zero_amount() {
     let env = create_test_env();
     let (_cid, _admin, client) = setup(&env);
-    assert_eq!(client.get_liquidation_incentive_amount(&0), 0);
+    assert_eq!(client.get_liquidation_incentive_amount(&0, &9_500), 0);
 }
 
 // =============================================================================
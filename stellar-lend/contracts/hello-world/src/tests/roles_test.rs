@@ -0,0 +1,165 @@
+//! # Role Tests
+//!
+//! Tests for role-based access control: grants and revocations by the
+//! protocol admin, and the role each admin operation requires.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::lending_pool::PoolError;
+use crate::risk_management::RiskManagementError;
+use crate::roles::{Role, RoleError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    Address, Env, IntoVal, Symbol,
+};
+
+fn setup(env: &Env) -> (Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    (admin, client)
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 7500,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+#[test]
+fn test_admin_holds_every_role() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+
+    for role in [
+        Role::RiskAdmin,
+        Role::PauseGuardian,
+        Role::AssetListingAdmin,
+        Role::TreasuryAdmin,
+    ] {
+        assert!(client.has_role(&role, &admin));
+        assert!(!client.has_role(&role, &Address::generate(&env)));
+    }
+}
+
+#[test]
+fn test_granted_role_is_scoped() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let guardian = Address::generate(&env);
+    client.grant_role(&admin, &Role::PauseGuardian, &guardian);
+
+    client.set_emergency_pause(&guardian, &true);
+    assert!(client.is_emergency_paused());
+
+    let result = client.try_set_risk_params(&guardian, &Some(12_000), &None, &None, &None);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+}
+
+#[test]
+fn test_revoke_role() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let risk_admin = Address::generate(&env);
+    client.grant_role(&admin, &Role::RiskAdmin, &risk_admin);
+    client.set_withdraw_cooldown(&risk_admin, &60);
+
+    client.revoke_role(&admin, &Role::RiskAdmin, &risk_admin);
+    assert!(!client.has_role(&Role::RiskAdmin, &risk_admin));
+    let result = client.try_set_withdraw_cooldown(&risk_admin, &120);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+}
+
+#[test]
+fn test_role_management_errors() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let account = Address::generate(&env);
+
+    // Role holders cannot grant roles
+    client.grant_role(&admin, &Role::RiskAdmin, &account);
+    let result = client.try_grant_role(&account, &Role::PauseGuardian, &account);
+    assert_eq!(result, Err(Ok(RoleError::Unauthorized)));
+
+    let result = client.try_grant_role(&admin, &Role::RiskAdmin, &account);
+    assert_eq!(result, Err(Ok(RoleError::AlreadyGranted)));
+    let result = client.try_revoke_role(&admin, &Role::TreasuryAdmin, &account);
+    assert_eq!(result, Err(Ok(RoleError::NotGranted)));
+}
+
+#[test]
+fn test_cross_asset_roles() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let lister = Address::generate(&env);
+    let usdc = create_token(&env);
+    client.grant_role(&admin, &Role::AssetListingAdmin, &lister);
+
+    client.initialize_asset(&lister, &Some(usdc.clone()), &asset_config(&env, &usdc));
+    client.update_asset_price(&lister, &Some(usdc.clone()), &9_000_000);
+
+    let result = client.try_update_asset_config(
+        &lister,
+        &Some(usdc.clone()),
+        &Some(5000),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+}
+
+#[test]
+fn test_treasury_admin_withdraws_reserves() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    let treasurer = Address::generate(&env);
+    let usdc = Some(create_token(&env));
+
+    let result = client.try_withdraw_reserves(&treasurer, &usdc, &treasurer, &100);
+    assert_eq!(result, Err(Ok(PoolError::Unauthorized)));
+
+    // Past the role check, the empty reserves are the only obstacle
+    client.grant_role(&admin, &Role::TreasuryAdmin, &treasurer);
+    let result = client.try_withdraw_reserves(&treasurer, &usdc, &treasurer, &100);
+    assert_eq!(result, Err(Ok(PoolError::InsufficientReserves)));
+}
+
+#[test]
+fn test_role_holder_must_authorize() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+
+    env.set_auths(&[]);
+    let result = client.try_set_emergency_pause(&admin, &true);
+    assert!(result.is_err());
+    assert!(!client.is_emergency_paused());
+}
+
+#[test]
+fn test_grant_emits_event() {
+    let env = Env::default();
+    let (admin, client) = setup(&env);
+    client.grant_role(&admin, &Role::PauseGuardian, &Address::generate(&env));
+
+    let events = env.events().all();
+    let expected_topics = (Symbol::new(&env, "role_updated_event"),).into_val(&env);
+    assert!(events
+        .iter()
+        .any(|(contract, topics, _)| contract == client.address && topics == expected_topics));
+}
@@ -0,0 +1,153 @@
+//! # Savings Mode Tests
+//!
+//! Tests for toggling cross-asset deposits between collateral and savings mode.
+//! Covers borrowing power, the disable health check and asset eligibility.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [usdc.clone(), xlm.clone()] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, usdc, xlm, admin)
+}
+
+#[test]
+fn test_deposits_default_to_collateral() {
+    let env = create_test_env();
+    let (client, usdc, _, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    let position = client.ca_deposit_collateral(&user, &Some(usdc), &1_000);
+    assert!(position.use_as_collateral);
+}
+
+#[test]
+fn test_savings_deposit_gives_no_borrowing_power() {
+    let env = create_test_env();
+    let (client, usdc, xlm, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    let position = client.set_use_as_collateral(&user, &Some(usdc.clone()), &false);
+    assert!(!position.use_as_collateral);
+    assert_eq!(position.collateral, 1_000);
+
+    let summary = client.get_user_position_summary(&user);
+    assert_eq!(summary.total_collateral_value, 1_000);
+    assert_eq!(summary.weighted_collateral_value, 0);
+    assert_eq!(summary.borrow_capacity, 0);
+
+    let result = client.try_ca_borrow_asset(&user, &Some(xlm), &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::ExceedsBorrowCapacity)));
+}
+
+#[test]
+fn test_savings_deposit_can_be_withdrawn_with_debt() {
+    let env = create_test_env();
+    let (client, usdc, xlm, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.ca_deposit_collateral(&user, &Some(xlm.clone()), &1_000);
+    client.set_use_as_collateral(&user, &Some(xlm.clone()), &false);
+    client.ca_borrow_asset(&user, &Some(usdc), &700);
+
+    let position = client.ca_withdraw_collateral(&user, &Some(xlm), &1_000);
+    assert_eq!(position.collateral, 0);
+}
+
+#[test]
+fn test_disable_rejected_when_position_becomes_unhealthy() {
+    let env = create_test_env();
+    let (client, usdc, xlm, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(xlm), &500);
+
+    let result = client.try_set_use_as_collateral(&user, &Some(usdc.clone()), &false);
+    assert_eq!(result, Err(Ok(CrossAssetError::UnhealthyPosition)));
+
+    let position = client.get_user_asset_position(&user, &Some(usdc));
+    assert!(position.use_as_collateral);
+}
+
+#[test]
+fn test_re_enable_restores_borrowing_power() {
+    let env = create_test_env();
+    let (client, usdc, xlm, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.set_use_as_collateral(&user, &Some(usdc.clone()), &false);
+    client.set_use_as_collateral(&user, &Some(usdc), &true);
+
+    let summary = client.get_user_position_summary(&user);
+    assert_eq!(summary.weighted_collateral_value, 750);
+    client.ca_borrow_asset(&user, &Some(xlm), &500);
+}
+
+#[test]
+fn test_enable_rejected_for_non_collateral_asset() {
+    let env = create_test_env();
+    let (client, usdc, _, admin) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.set_use_as_collateral(&user, &Some(usdc.clone()), &false);
+    client.update_asset_config(
+        &admin,
+        &Some(usdc.clone()),
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(false),
+        &None,
+    );
+
+    let result = client.try_set_use_as_collateral(&user, &Some(usdc), &true);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetDisabled)));
+}
+
+#[test]
+fn test_toggle_unconfigured_asset_fails() {
+    let env = create_test_env();
+    let (client, _, _, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    let result = client.try_set_use_as_collateral(&user, &Some(Address::generate(&env)), &false);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+}
@@ -0,0 +1,254 @@
+//! # Soft Liquidation Tests
+//!
+//! Tests that keepers convert a position's collateral toward its debt asset
+//! band by band as its price falls, and back as it recovers.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError, SoftLiquidationConfig};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+struct Market<'a> {
+    client: HelloContractClient<'a>,
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    keeper: Address,
+    borrower: Address,
+}
+
+/// Four bands of 0.05 health factor each, from 1.0 to 1.2, with a 1% fee
+fn bands() -> SoftLiquidationConfig {
+    SoftLiquidationConfig {
+        band_count: 4,
+        band_width_bps: 500,
+        fee_bps: 100,
+    }
+}
+
+/// Lists USDC and XLM at a price of 1.0 with soft liquidation on XLM, gives
+/// the keeper 20,000 USDC of collateral and opens a borrower with 20,000 XLM
+/// of collateral and 10,000 USDC of debt (health factor 1.5)
+fn setup(env: &Env) -> Market<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_soft_liquidation(&admin, &Some(xlm.clone()), &Some(bands()));
+
+    let supplier = Address::generate(env);
+    StellarAssetClient::new(env, &usdc).mint(&supplier, &20_000);
+    TokenClient::new(env, &usdc).approve(
+        &supplier,
+        &contract_id,
+        &20_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.supply(&supplier, &Some(usdc.clone()), &20_000);
+    let keeper = Address::generate(env);
+    client.ca_deposit_collateral(&keeper, &Some(usdc.clone()), &20_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &20_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &10_000);
+
+    Market {
+        client,
+        admin,
+        usdc: Some(usdc),
+        xlm: Some(xlm),
+        keeper,
+        borrower,
+    }
+}
+
+fn set_xlm_price(market: &Market, price: i128) {
+    market
+        .client
+        .update_asset_price(&market.admin, &market.xlm, &price);
+}
+
+#[test]
+fn test_falling_price_converts_collateral_band_by_band() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+
+    // Health factor 1.5 is above the bands
+    let result =
+        client.try_soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result, Err(Ok(CrossAssetError::NothingToConvert)));
+
+    // At 0.75 the health factor is 1.125, two bands into the range: half the
+    // XLM is sold at 0.7425
+    set_xlm_price(&market, 7_500_000);
+    let result = client.soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result.collateral_converted, 10_000);
+    assert_eq!(result.debt_repaid, 7_425);
+    assert_eq!(result.bands_converted, 2);
+    assert_eq!(result.health_factor, 21_844);
+
+    let borrower_xlm = client.get_user_asset_position(&market.borrower, &market.xlm);
+    assert_eq!(borrower_xlm.collateral, 10_000);
+    let borrower_usdc = client.get_user_asset_position(&market.borrower, &market.usdc);
+    assert_eq!(borrower_usdc.debt_principal, 2_575);
+    let keeper_xlm = client.get_user_asset_position(&market.keeper, &market.xlm);
+    assert_eq!(keeper_xlm.collateral, 10_000);
+    let keeper_usdc = client.get_user_asset_position(&market.keeper, &market.usdc);
+    assert_eq!(keeper_usdc.collateral, 12_575);
+
+    let state = client
+        .get_soft_liquidation_state(&market.borrower, &market.xlm)
+        .unwrap();
+    assert_eq!((state.converted, state.proceeds), (10_000, 7_425));
+
+    // The bands are judged as if nothing were converted, so the improved
+    // health factor does not undo the conversion
+    let result =
+        client.try_soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result, Err(Ok(CrossAssetError::NothingToConvert)));
+}
+
+#[test]
+fn test_recovering_price_converts_back() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+    set_xlm_price(&market, 7_500_000);
+    client.soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+
+    // At 0.9 the position is above the bands again; buying the XLM back at
+    // 0.909 costs more debt than selling it repaid
+    set_xlm_price(&market, 9_000_000);
+    let result = client.soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result.collateral_converted, -10_000);
+    assert_eq!(result.debt_repaid, -9_090);
+    assert_eq!(result.bands_converted, 0);
+
+    let borrower_xlm = client.get_user_asset_position(&market.borrower, &market.xlm);
+    assert_eq!(borrower_xlm.collateral, 20_000);
+    let borrower_usdc = client.get_user_asset_position(&market.borrower, &market.usdc);
+    assert_eq!(borrower_usdc.debt_principal, 11_665);
+    let keeper_usdc = client.get_user_asset_position(&market.keeper, &market.usdc);
+    assert_eq!(keeper_usdc.collateral, 21_665);
+    assert_eq!(
+        client
+            .get_user_asset_position(&market.keeper, &market.xlm)
+            .collateral,
+        0
+    );
+    assert_eq!(
+        client.get_soft_liquidation_state(&market.borrower, &market.xlm),
+        None
+    );
+}
+
+#[test]
+fn test_disabling_bands_lets_keepers_unwind() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+    set_xlm_price(&market, 7_500_000);
+    client.soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+
+    client.set_soft_liquidation(&market.admin, &market.xlm, &None);
+    assert_eq!(client.get_soft_liquidation_config(&market.xlm), None);
+    let result = client.soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result.collateral_converted, -10_000);
+    assert_eq!(result.debt_repaid, -7_500);
+
+    let result =
+        client.try_soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result, Err(Ok(CrossAssetError::SoftLiquidationDisabled)));
+}
+
+#[test]
+fn test_soft_liquidate_rejections() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+    set_xlm_price(&market, 7_500_000);
+
+    let result =
+        client.try_soft_liquidate(&market.keeper, &market.borrower, &market.usdc, &market.xlm);
+    assert_eq!(result, Err(Ok(CrossAssetError::SoftLiquidationDisabled)));
+    let result =
+        client.try_soft_liquidate(&market.keeper, &market.borrower, &market.xlm, &market.xlm);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtAssetMismatch)));
+    let result = client.try_soft_liquidate(
+        &market.borrower,
+        &market.borrower,
+        &market.xlm,
+        &market.usdc,
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+
+    let keeper = Address::generate(&env);
+    client.ca_deposit_collateral(&keeper, &market.usdc, &1_000);
+    let result = client.try_soft_liquidate(&keeper, &market.borrower, &market.xlm, &market.usdc);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+}
+
+#[test]
+fn test_set_soft_liquidation_rejections() {
+    let env = Env::default();
+    let market = setup(&env);
+    let client = &market.client;
+
+    let result =
+        client.try_set_soft_liquidation(&Address::generate(&env), &market.usdc, &Some(bands()));
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    let result = client.try_set_soft_liquidation(
+        &market.admin,
+        &Some(Address::generate(&env)),
+        &Some(bands()),
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotConfigured)));
+
+    for (band_count, band_width_bps, fee_bps) in [
+        (0, 500, 100),
+        (51, 100, 100),
+        (4, 0, 100),
+        (20, 600, 100),
+        (4, 500, 10_000),
+    ] {
+        let config = SoftLiquidationConfig {
+            band_count,
+            band_width_bps,
+            fee_bps,
+        };
+        let result = client.try_set_soft_liquidation(&market.admin, &market.usdc, &Some(config));
+        assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    }
+    assert_eq!(client.get_soft_liquidation_config(&market.usdc), None);
+    assert_eq!(
+        client.get_soft_liquidation_config(&market.xlm),
+        Some(bands())
+    );
+}
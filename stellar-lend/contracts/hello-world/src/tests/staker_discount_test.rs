@@ -0,0 +1,164 @@
+//! # Staker Borrow-Rate Discount Tests
+//!
+//! Tests for the borrow-rate discount granted to users staking in the safety
+//! module. A minimal mock staking contract supplies the balance lookup.
+
+use crate::deposit::{DepositDataKey, Position};
+use crate::interest_rate::InterestRateError;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+#[contract]
+pub struct MockStaking;
+
+#[contractimpl]
+impl MockStaking {
+    pub fn set_balance(env: Env, user: Address, amount: i128) {
+        env.storage().persistent().set(&user, &amount);
+    }
+
+    pub fn balance(env: Env, user: Address) -> i128 {
+        env.storage().persistent().get(&user).unwrap_or(0)
+    }
+}
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+fn setup_staking(env: &Env) -> (Address, MockStakingClient<'_>) {
+    let id = env.register(MockStaking, ());
+    let client = MockStakingClient::new(env, &id);
+    (id, client)
+}
+
+fn get_position(env: &Env, contract_id: &Address, user: &Address) -> Position {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DepositDataKey::Position(user.clone()))
+            .unwrap()
+    })
+}
+
+#[test]
+fn test_no_discount_by_default() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    assert!(client.get_staker_discount().is_none());
+    assert_eq!(client.get_user_borrow_rate(&user), client.get_borrow_rate());
+}
+
+#[test]
+fn test_discount_applies_above_threshold() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let (staking_id, staking) = setup_staking(&env);
+    client.set_staker_discount(&admin, &staking_id, &1_000, &30);
+
+    let staker = Address::generate(&env);
+    let small = Address::generate(&env);
+    staking.set_balance(&staker, &1_000);
+    staking.set_balance(&small, &999);
+
+    let pool_rate = client.get_borrow_rate();
+    assert_eq!(client.get_user_borrow_rate(&staker), pool_rate - 30);
+    assert_eq!(client.get_user_borrow_rate(&small), pool_rate);
+}
+
+#[test]
+fn test_discount_respects_rate_floor() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let (staking_id, staking) = setup_staking(&env);
+    client.set_staker_discount(&admin, &staking_id, &1, &10_000);
+
+    let staker = Address::generate(&env);
+    staking.set_balance(&staker, &1);
+
+    // Default rate floor is 0.5%
+    assert_eq!(client.get_user_borrow_rate(&staker), 50);
+}
+
+#[test]
+fn test_discount_reduces_accrued_interest() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let (staking_id, staking) = setup_staking(&env);
+    client.set_staker_discount(&admin, &staking_id, &1_000, &100);
+
+    let staker = Address::generate(&env);
+    let regular = Address::generate(&env);
+    staking.set_balance(&staker, &5_000);
+
+    for user in [&staker, &regular] {
+        client.deposit_collateral(user, &None, &2_000_000_000);
+        client.borrow_asset(user, &None, &1_000_000_000);
+    }
+
+    env.ledger().with_mut(|li| li.timestamp += 365 * 86_400);
+    client.repay_debt(&staker, &None, &1);
+    client.repay_debt(&regular, &None, &1);
+
+    let staker_interest = get_position(&env, &contract_id, &staker).borrow_interest;
+    let regular_interest = get_position(&env, &contract_id, &regular).borrow_interest;
+    assert!(staker_interest < regular_interest);
+    // 1% less over one year on 1,000,000,000 principal (minus the 1 repaid)
+    assert_eq!(regular_interest - staker_interest, 10_000_000);
+}
+
+#[test]
+fn test_failing_staking_lookup_gives_no_discount() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    // Not a contract: the balance call fails
+    let bogus = Address::generate(&env);
+    client.set_staker_discount(&admin, &bogus, &0, &100);
+
+    let user = Address::generate(&env);
+    assert_eq!(client.get_user_borrow_rate(&user), client.get_borrow_rate());
+}
+
+#[test]
+fn test_set_staker_discount_validation() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    let (staking_id, _) = setup_staking(&env);
+
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_set_staker_discount(&other, &staking_id, &1_000, &30),
+        Err(Ok(InterestRateError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_staker_discount(&admin, &staking_id, &-1, &30),
+        Err(Ok(InterestRateError::InvalidParameter))
+    );
+    assert_eq!(
+        client.try_set_staker_discount(&admin, &staking_id, &1_000, &10_001),
+        Err(Ok(InterestRateError::InvalidParameter))
+    );
+
+    client.set_staker_discount(&admin, &staking_id, &1_000, &30);
+    let config = client.get_staker_discount().unwrap();
+    assert_eq!(config.staking_contract, staking_id);
+    assert_eq!(config.min_stake, 1_000);
+    assert_eq!(config.discount_bps, 30);
+}
@@ -0,0 +1,205 @@
+//! # State Export Tests
+//!
+//! Tests that `export_state` pages through every section in its documented
+//! order and reports each user's and market's state.
+
+use crate::cross_asset::{AssetConfig, AssetKey, InterestMode};
+use crate::rate_strategy::RateStrategy;
+use crate::state_export::{ExportEntry, ExportSection, StateExportError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+struct Protocol<'a> {
+    client: HelloContractClient<'a>,
+    usdc: Address,
+    xlm: Address,
+    depositor: Address,
+    supplier: Address,
+    borrower: Address,
+}
+
+/// Mints `amount` of `asset` to a new user and approves the contract
+fn funded_user(env: &Env, contract_id: &Address, asset: &Address, amount: i128) -> Address {
+    let user = Address::generate(env);
+    StellarAssetClient::new(env, asset).mint(&user, &amount);
+    TokenClient::new(env, asset).approve(
+        &user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    user
+}
+
+/// Lists USDC and XLM, then opens a core deposit, a pool supply and a
+/// cross-asset borrow, in that order
+fn setup(env: &Env) -> Protocol<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    let xlm = create_token(env);
+    for asset in [&usdc, &xlm] {
+        client.initialize_asset(
+            &admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    client.set_rate_strategy(
+        &admin,
+        &Some(usdc.clone()),
+        &RateStrategy {
+            base_rate_bps: 200,
+            slope1_bps: 1000,
+            slope2_bps: 6000,
+            optimal_utilization_bps: 8000,
+        },
+    );
+    client.set_isolation_mode(&admin, &Some(xlm.clone()), &Some(50_000));
+
+    let depositor = funded_user(env, &contract_id, &usdc, 1_000);
+    client.deposit_collateral(&depositor, &Some(usdc.clone()), &1_000);
+    let supplier = funded_user(env, &contract_id, &usdc, 10_000);
+    client.supply(&supplier, &Some(usdc.clone()), &10_000);
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(xlm.clone()), &20_000);
+    client.ca_borrow_asset(&borrower, &Some(usdc.clone()), &8_000);
+
+    Protocol {
+        client,
+        usdc,
+        xlm,
+        depositor,
+        supplier,
+        borrower,
+    }
+}
+
+#[test]
+fn test_positions_are_paged_in_order_of_first_activity() {
+    let env = Env::default();
+    let protocol = setup(&env);
+    let usdc = AssetKey::Token(protocol.usdc.clone());
+
+    let page = protocol
+        .client
+        .export_state(&ExportSection::Positions, &0, &2);
+    assert_eq!(page.entries.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+    let ExportEntry::Positions(depositor) = page.entries.get(0).unwrap() else {
+        panic!("expected a position entry");
+    };
+    assert_eq!(depositor.user, protocol.depositor);
+    assert_eq!(depositor.core.collateral, 1_000);
+    let ExportEntry::Positions(supplier) = page.entries.get(1).unwrap() else {
+        panic!("expected a position entry");
+    };
+    assert_eq!(supplier.user, protocol.supplier);
+    assert_eq!(supplier.core.collateral, 0);
+    assert_eq!(supplier.supply_shares.get(usdc.clone()), Some(10_000));
+
+    let page = protocol
+        .client
+        .export_state(&ExportSection::Positions, &2, &2);
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.next_cursor, None);
+    let ExportEntry::Positions(borrower) = page.entries.get(0).unwrap() else {
+        panic!("expected a position entry");
+    };
+    assert_eq!(borrower.user, protocol.borrower);
+    assert_eq!(borrower.positions.get(usdc).unwrap().debt_principal, 8_000);
+    assert_eq!(
+        borrower
+            .positions
+            .get(AssetKey::Token(protocol.xlm.clone()))
+            .unwrap()
+            .collateral,
+        20_000
+    );
+}
+
+#[test]
+fn test_market_sections_follow_listing_order() {
+    let env = Env::default();
+    let protocol = setup(&env);
+
+    let page = protocol
+        .client
+        .export_state(&ExportSection::AssetParams, &0, &25);
+    assert_eq!(page.entries.len(), 2);
+    assert_eq!(page.next_cursor, None);
+    let ExportEntry::AssetParams(usdc) = page.entries.get(0).unwrap() else {
+        panic!("expected an asset params entry");
+    };
+    assert_eq!(usdc.asset, AssetKey::Token(protocol.usdc.clone()));
+    assert_eq!(usdc.rate_strategy.slope1_bps, 1000);
+    assert_eq!(usdc.interest_mode, InterestMode::Simple);
+    let ExportEntry::AssetParams(xlm) = page.entries.get(1).unwrap() else {
+        panic!("expected an asset params entry");
+    };
+    assert_eq!(xlm.config.collateral_factor, 7500);
+    assert_eq!(xlm.isolation_debt_ceiling, Some(50_000));
+    assert!(!xlm.delisted && xlm.pauses.is_empty());
+    assert!(xlm.collateral_factor_ramp.is_empty());
+
+    let page = protocol
+        .client
+        .export_state(&ExportSection::Indices, &0, &1);
+    assert_eq!(page.next_cursor, Some(1));
+    let ExportEntry::Indices(usdc) = page.entries.get(0).unwrap() else {
+        panic!("expected an index entry");
+    };
+    assert_eq!(usdc.pool_liquidity, 10_000);
+    assert_eq!(usdc.pool_shares, 10_000);
+    assert_eq!(usdc.total_borrowed, 8_000);
+
+    let page = protocol
+        .client
+        .export_state(&ExportSection::Reserves, &1, &1);
+    assert_eq!(page.next_cursor, None);
+    let ExportEntry::Reserves(xlm) = page.entries.get(0).unwrap() else {
+        panic!("expected a reserves entry");
+    };
+    assert_eq!(xlm.asset, AssetKey::Token(protocol.xlm));
+    assert_eq!((xlm.reserves, xlm.bad_debt), (0, 0));
+}
+
+#[test]
+fn test_export_past_the_end_and_invalid_limits() {
+    let env = Env::default();
+    let protocol = setup(&env);
+
+    let page = protocol
+        .client
+        .export_state(&ExportSection::Reserves, &5, &10);
+    assert!(page.entries.is_empty());
+    assert_eq!(page.next_cursor, None);
+
+    for limit in [0, 26] {
+        let result = protocol
+            .client
+            .try_export_state(&ExportSection::Positions, &0, &limit);
+        assert_eq!(result, Err(Ok(StateExportError::InvalidLimit)));
+    }
+}
@@ -0,0 +1,176 @@
+//! # Storage Tiering Tests
+//!
+//! Tests for the placement of global configuration in instance storage,
+//! per-user data in persistent storage, and caches and scratch state in
+//! temporary storage, and for how each tier behaves as ledgers advance.
+
+use crate::constants::{INSTANCE_LIFETIME_THRESHOLD, USER_DATA_BUMP_AMOUNT};
+use crate::deposit::DepositDataKey;
+use crate::interest_rate::InterestRateDataKey;
+use crate::oracle::OracleDataKey;
+use crate::reentrancy::{GuardDataKey, OperationGuard};
+use crate::risk_management::RiskDataKey;
+use crate::storage;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Ledger,
+    },
+    Address, Env,
+};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| li.sequence_number += ledgers);
+}
+
+#[test]
+fn test_global_config_lives_in_instance_storage() {
+    let env = create_test_env();
+    let (contract_id, _, _) = setup_contract_with_admin(&env);
+
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        assert!(instance.has(&RiskDataKey::Admin));
+        assert!(instance.has(&RiskDataKey::RiskConfig));
+        assert!(instance.has(&RiskDataKey::EmergencyPause));
+        assert!(instance.has(&InterestRateDataKey::InterestRateConfig));
+
+        let persistent = env.storage().persistent();
+        assert!(!persistent.has(&RiskDataKey::Admin));
+        assert!(!persistent.has(&RiskDataKey::RiskConfig));
+        assert!(!persistent.has(&InterestRateDataKey::Admin));
+    });
+}
+
+#[test]
+fn test_user_data_persistent_with_extended_ttl() {
+    let env = create_test_env();
+    let (contract_id, _, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+
+    env.as_contract(&contract_id, || {
+        let key = DepositDataKey::CollateralBalance(user.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert_eq!(
+            env.storage().persistent().get_ttl(&key),
+            USER_DATA_BUMP_AMOUNT
+        );
+        assert!(env.storage().instance().get_ttl() >= INSTANCE_LIFETIME_THRESHOLD);
+    });
+}
+
+#[test]
+fn test_state_survives_past_minimum_ttl() {
+    let env = create_test_env();
+    let (contract_id, _, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    // Well past the default minimum persistent TTL (4096 ledgers)
+    advance_ledgers(&env, 10_000);
+
+    client.deposit_collateral(&user, &None, &500);
+
+    env.as_contract(&contract_id, || {
+        let balance =
+            storage::get::<_, i128>(&env, &DepositDataKey::CollateralBalance(user.clone()));
+        assert_eq!(balance, Some(1_500));
+        assert!(storage::get_instance::<_, Address>(&env, &RiskDataKey::Admin).is_some());
+    });
+}
+
+#[test]
+fn test_price_cache_expires_from_temporary_storage() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    client.update_price_feed(&admin, &asset, &100_000_000, &8, &oracle);
+    let key = OracleDataKey::PriceCache(asset.clone());
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().temporary().has(&key));
+    });
+
+    // Past the minimum temporary TTL (16 ledgers); the cache is gone but the
+    // persistent feed still answers
+    advance_ledgers(&env, 100);
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().temporary().has(&key));
+    });
+    assert_eq!(client.get_price(&asset), 100_000_000);
+}
+
+#[test]
+fn test_pre_tiering_config_read_and_moved_on_write() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let admin = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        // Layout written by releases before storage tiering
+        env.storage().persistent().set(&RiskDataKey::Admin, &admin);
+        assert_eq!(
+            storage::get_instance::<_, Address>(&env, &RiskDataKey::Admin),
+            Some(admin.clone())
+        );
+        assert!(storage::has_instance(&env, &RiskDataKey::Admin));
+
+        storage::set_instance(&env, &RiskDataKey::Admin, &admin);
+        assert!(env.storage().instance().has(&RiskDataKey::Admin));
+        assert!(!env.storage().persistent().has(&RiskDataKey::Admin));
+    });
+}
+
+#[test]
+fn test_operation_guard_flag_is_scratch_state() {
+    let env = create_test_env();
+    let (contract_id, _, _) = setup_contract_with_admin(&env);
+    let key = GuardDataKey::OperationInProgress;
+
+    env.as_contract(&contract_id, || {
+        {
+            let _guard = OperationGuard::enter(&env).unwrap();
+            assert!(storage::has_scratch(&env, &key));
+            assert!(env.storage().temporary().has(&key));
+            assert!(!env.storage().instance().has(&key));
+            assert!(!env.storage().persistent().has(&key));
+        }
+        assert!(!storage::has_scratch(&env, &key));
+    });
+}
+
+#[test]
+fn test_scratch_entries_expire_from_temporary_storage() {
+    let env = create_test_env();
+    let (contract_id, _, _) = setup_contract_with_admin(&env);
+    let key = GuardDataKey::OperationInProgress;
+
+    env.as_contract(&contract_id, || {
+        storage::set_scratch(&env, &key, &true);
+        assert!(storage::has_scratch(&env, &key));
+    });
+
+    // An entry left behind by mistake lapses with the minimum temporary TTL
+    advance_ledgers(&env, 100);
+    env.as_contract(&contract_id, || {
+        assert!(!storage::has_scratch(&env, &key));
+    });
+}
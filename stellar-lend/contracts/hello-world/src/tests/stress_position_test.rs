@@ -0,0 +1,105 @@
+//! # Position Stress Test Query Tests
+//!
+//! Tests for `stress_test_position`, which reports a cross-asset position's
+//! health factor under hypothetical price shocks without touching storage.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Vec};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+fn asset_config(env: &Env, asset: &Address, collateral_factor: i128) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// User with 1000 XLM collateral (CF 80%) and 400 USDC debt, both at price 1.0
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let xlm = create_token(env);
+    let usdc = create_token(env);
+    client.initialize_asset(&admin, &Some(xlm.clone()), &asset_config(env, &xlm, 8000));
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(env, &usdc, 8000));
+
+    let user = Address::generate(env);
+    client.ca_deposit_collateral(&user, &Some(xlm.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &400);
+    (client, user, xlm, usdc)
+}
+
+#[test]
+fn test_no_shocks_matches_summary() {
+    let env = create_test_env();
+    let (client, user, _, _) = setup(&env);
+
+    let stressed = client.stress_test_position(&user, &Vec::new(&env));
+    assert_eq!(stressed, client.get_user_position_summary(&user));
+    assert_eq!(stressed.health_factor, 20_000);
+}
+
+#[test]
+fn test_collateral_price_drop_lowers_health() {
+    let env = create_test_env();
+    let (client, user, xlm, _) = setup(&env);
+
+    let stressed = client.stress_test_position(&user, &vec![&env, (Some(xlm.clone()), -2_500)]);
+    // 750 * 80% = 600 weighted collateral against 400 debt
+    assert_eq!(stressed.health_factor, 15_000);
+    assert!(!stressed.is_liquidatable);
+
+    let crash = client.stress_test_position(&user, &vec![&env, (Some(xlm), -5_500)]);
+    assert!(crash.is_liquidatable);
+}
+
+#[test]
+fn test_debt_price_rise_lowers_health() {
+    let env = create_test_env();
+    let (client, user, _, usdc) = setup(&env);
+
+    let stressed = client.stress_test_position(&user, &vec![&env, (Some(usdc), 10_000)]);
+    assert_eq!(stressed.total_debt_value, 800);
+    assert_eq!(stressed.health_factor, 10_000);
+}
+
+#[test]
+fn test_stress_does_not_modify_state() {
+    let env = create_test_env();
+    let (client, user, xlm, _) = setup(&env);
+
+    client.stress_test_position(&user, &vec![&env, (Some(xlm.clone()), -9_000)]);
+    let config = client.get_asset_config(&Some(xlm));
+    assert_eq!(config.price, 10_000_000);
+    assert_eq!(
+        client.get_user_position_summary(&user).health_factor,
+        20_000
+    );
+}
+
+#[test]
+fn test_shock_below_minus_100_percent_rejected() {
+    let env = create_test_env();
+    let (client, user, xlm, _) = setup(&env);
+
+    let result = client.try_stress_test_position(&user, &vec![&env, (Some(xlm), -10_001)]);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidPrice)));
+}
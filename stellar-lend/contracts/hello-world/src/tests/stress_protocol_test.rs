@@ -0,0 +1,126 @@
+//! # Protocol Stress Test Tests
+//!
+//! Tests for the paged, cached `stress_test_protocol` query over the
+//! cross-asset user registry.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 8000,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// Three users with 1000 XLM collateral each and 300 / 500 / 700 USDC debt
+fn setup(env: &Env) -> HelloContractClient<'_> {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let xlm = create_token(env);
+    let usdc = create_token(env);
+    client.initialize_asset(&admin, &Some(xlm.clone()), &asset_config(env, &xlm));
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(env, &usdc));
+
+    for debt in [300_i128, 500, 700] {
+        let user = Address::generate(env);
+        client.ca_deposit_collateral(&user, &Some(xlm.clone()), &1_000);
+        client.ca_borrow_asset(&user, &Some(usdc.clone()), &debt);
+    }
+    client
+}
+
+#[test]
+fn test_single_page_scan() {
+    let env = create_test_env();
+    let client = setup(&env);
+
+    // 40% drop: collateral 600, weighted 480 -> users with 500 and 700 debt liquidatable
+    let result = client.stress_test_protocol(&-4_000, &0, &50);
+    assert_eq!(result.users_scanned, 3);
+    assert_eq!(result.liquidatable_debt, 1_200);
+    // Only the 700 debt exceeds the 600 shocked collateral
+    assert_eq!(result.projected_bad_debt, 100);
+    assert_eq!(result.next_cursor, None);
+    assert_eq!(result.computed_at, 1_000);
+
+    assert_eq!(client.get_protocol_stress_result(&-4_000), Some(result));
+}
+
+#[test]
+fn test_paged_scan_matches_single_page() {
+    let env = create_test_env();
+    let client = setup(&env);
+
+    let first = client.stress_test_protocol(&-4_000, &0, &2);
+    assert_eq!(first.next_cursor, Some(2));
+    assert_eq!(client.get_protocol_stress_result(&-4_000), None);
+
+    let last = client.stress_test_protocol(&-4_000, &2, &2);
+    assert_eq!(last.next_cursor, None);
+    assert_eq!(last.users_scanned, 3);
+    assert_eq!(last.liquidatable_debt, 1_200);
+    assert_eq!(last.projected_bad_debt, 100);
+}
+
+#[test]
+fn test_results_cached_per_shock_level() {
+    let env = create_test_env();
+    let client = setup(&env);
+
+    client.stress_test_protocol(&-1_000, &0, &50);
+    client.stress_test_protocol(&-4_000, &0, &50);
+
+    let mild = client.get_protocol_stress_result(&-1_000).unwrap();
+    assert_eq!(mild.liquidatable_debt, 0);
+    assert_eq!(mild.projected_bad_debt, 0);
+    assert_eq!(
+        client
+            .get_protocol_stress_result(&-4_000)
+            .unwrap()
+            .liquidatable_debt,
+        1_200
+    );
+}
+
+#[test]
+fn test_out_of_sequence_cursor_rejected() {
+    let env = create_test_env();
+    let client = setup(&env);
+
+    let result = client.try_stress_test_protocol(&-4_000, &2, &2);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidPrice)));
+}
+
+#[test]
+fn test_invalid_parameters_rejected() {
+    let env = create_test_env();
+    let client = setup(&env);
+
+    assert!(client.try_stress_test_protocol(&-10_001, &0, &10).is_err());
+    assert!(client.try_stress_test_protocol(&-1_000, &0, &0).is_err());
+    assert!(client.try_stress_test_protocol(&-1_000, &0, &51).is_err());
+}
@@ -0,0 +1,157 @@
+//! # Sub-Account Tests
+//!
+//! Tests that an owner's sub-accounts hold independent positions with their
+//! own health factors, so liquidating one leaves the others untouched, and
+//! that sub-accounts are registered to their owner on first deposit.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::sub_account::{SubAccount, MAX_SUB_ACCOUNTS};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+struct Market {
+    admin: Address,
+    usdc: Option<Address>,
+    xlm: Option<Address>,
+    eth: Option<Address>,
+}
+
+/// Registers USDC, XLM and ETH at a price of 1.0 with a 75% collateral factor
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: Some(create_token(env)),
+        xlm: Some(create_token(env)),
+        eth: Some(create_token(env)),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm, &market.eth] {
+        client.initialize_asset(
+            &market.admin,
+            asset,
+            &AssetConfig {
+                asset: asset.clone(),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+    (client, market)
+}
+
+#[test]
+fn test_liquidation_is_isolated_to_one_sub_account() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let owner = Address::generate(&env);
+
+    // Sub-account 1 borrows against XLM, sub-account 2 against ETH
+    client.ca_sub_deposit_collateral(&owner, &1, &market.xlm, &1_000);
+    client.ca_sub_borrow_asset(&owner, &1, &market.usdc, &700);
+    client.ca_sub_deposit_collateral(&owner, &2, &market.eth, &1_000);
+    client.ca_sub_borrow_asset(&owner, &2, &market.usdc, &700);
+    client.ca_deposit_collateral(&owner, &market.eth, &500);
+
+    let first = client.get_sub_account(&owner, &1);
+    let second = client.get_sub_account(&owner, &2);
+    assert_ne!(first, second);
+    assert_ne!(first, owner);
+
+    // Halving XLM leaves 375 of weighted collateral against 700 of debt in
+    // sub-account 1 only
+    client.update_asset_price(&market.admin, &market.xlm, &5_000_000);
+    assert!(client.get_sub_account_summary(&owner, &1).is_liquidatable);
+    let healthy = client.get_sub_account_summary(&owner, &2);
+    assert!(!healthy.is_liquidatable);
+
+    let liquidator = Address::generate(&env);
+    client.ca_deposit_collateral(&liquidator, &market.usdc, &1_000);
+    client.ca_liquidate(
+        &liquidator,
+        &first,
+        &market.usdc,
+        &vec![&env, (market.xlm.clone(), 300)],
+    );
+    let usdc = client.get_user_asset_position(&first, &market.usdc);
+    assert_eq!(usdc.debt_principal, 400);
+
+    assert_eq!(client.get_sub_account_summary(&owner, &2), healthy);
+    let eth = client.get_user_asset_position(&second, &market.eth);
+    assert_eq!(eth.collateral, 1_000);
+    let eth = client.get_user_asset_position(&owner, &market.eth);
+    assert_eq!(eth.collateral, 500);
+}
+
+#[test]
+fn test_sub_account_health_checked_on_its_own() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let owner = Address::generate(&env);
+    client.ca_sub_deposit_collateral(&owner, &1, &market.xlm, &1_000);
+    client.ca_sub_deposit_collateral(&owner, &2, &market.eth, &1_000);
+
+    // Sub-account 2's collateral does not back sub-account 1's debt
+    let result = client.try_ca_sub_borrow_asset(&owner, &1, &market.usdc, &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::ExceedsBorrowCapacity)));
+    client.ca_sub_borrow_asset(&owner, &1, &market.usdc, &750);
+    let result = client.try_ca_sub_withdraw_collateral(&owner, &1, &market.xlm, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::UnhealthyPosition)));
+    client.ca_sub_withdraw_collateral(&owner, &2, &market.eth, &1_000);
+
+    client.ca_sub_repay_debt(&owner, &1, &market.usdc, &750);
+    client.ca_sub_withdraw_collateral(&owner, &1, &market.xlm, &1_000);
+    let first = client.get_sub_account(&owner, &1);
+    let xlm = client.get_user_asset_position(&first, &market.xlm);
+    assert_eq!(xlm.collateral, 0);
+}
+
+#[test]
+fn test_sub_accounts_registered_on_first_deposit() {
+    let env = Env::default();
+    let (client, market) = setup(&env);
+    let owner = Address::generate(&env);
+    let account = client.get_sub_account(&owner, &7);
+    assert!(client.get_sub_accounts(&owner).is_empty());
+    assert_eq!(client.get_sub_account_owner(&account), None);
+
+    client.ca_sub_deposit_collateral(&owner, &7, &market.xlm, &100);
+    let opened = env.events().all().iter().any(|(_, topics, _)| {
+        topics == (Symbol::new(&env, "sub_account_opened_event"),).into_val(&env)
+    });
+    assert!(opened);
+    client.ca_sub_deposit_collateral(&owner, &7, &market.xlm, &100);
+    assert_eq!(client.get_sub_accounts(&owner).len(), 1);
+    assert_eq!(
+        client.get_sub_accounts(&owner).get(7),
+        Some(account.clone())
+    );
+    assert_eq!(
+        client.get_sub_account_owner(&account),
+        Some(SubAccount { owner, sub_id: 7 })
+    );
+
+    let owner = Address::generate(&env);
+    for sub_id in 0..MAX_SUB_ACCOUNTS {
+        client.ca_sub_deposit_collateral(&owner, &sub_id, &market.xlm, &1);
+    }
+    let result = client.try_ca_sub_deposit_collateral(&owner, &MAX_SUB_ACCOUNTS, &market.xlm, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::TooManySubAccounts)));
+    client.ca_sub_deposit_collateral(&owner, &0, &market.xlm, &1);
+}
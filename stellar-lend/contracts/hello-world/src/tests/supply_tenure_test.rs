@@ -0,0 +1,185 @@
+//! # Supply Tenure Rebate Tests
+//!
+//! Tests for continuous supply tenure tracking and the reserve-factor rebate
+//! reported by `get_supply_statement`.
+
+use crate::cross_asset::{AssetConfig, CrossAssetError, TenureRebateTier};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+const DAY: u64 = 86_400;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 8000,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+/// Registers USDC and a 30-day / 90-day rebate schedule (1% / 2.5%)
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize_ca(&admin);
+
+    let usdc = create_token(env);
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(env, &usdc));
+    client.set_tenure_rebates(
+        &admin,
+        &vec![
+            env,
+            TenureRebateTier {
+                min_tenure_seconds: 30 * DAY,
+                rebate_bps: 100,
+            },
+            TenureRebateTier {
+                min_tenure_seconds: 90 * DAY,
+                rebate_bps: 250,
+            },
+        ],
+    );
+    (client, usdc, admin)
+}
+
+/// Move time forward and refresh the USDC price so it does not go stale
+fn advance(env: &Env, client: &HelloContractClient, admin: &Address, usdc: &Address, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+    client.update_asset_price(admin, &Some(usdc.clone()), &10_000_000);
+}
+
+#[test]
+fn test_statement_without_supply() {
+    let env = create_test_env();
+    let (client, usdc, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    let statement = client.get_supply_statement(&user, &Some(usdc));
+    assert_eq!(statement.supplied, 0);
+    assert_eq!(statement.supplied_since, 0);
+    assert_eq!(statement.tenure_seconds, 0);
+    assert_eq!(statement.rebate_bps, 0);
+    assert_eq!(statement.effective_reserve_factor, 1000);
+}
+
+#[test]
+fn test_rebate_tiers_unlock_with_tenure() {
+    let env = create_test_env();
+    let (client, usdc, admin) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+
+    advance(&env, &client, &admin, &usdc, 29 * DAY);
+    let statement = client.get_supply_statement(&user, &Some(usdc.clone()));
+    assert_eq!(statement.supplied_since, 1_000);
+    assert_eq!(statement.tenure_seconds, 29 * DAY);
+    assert_eq!(statement.rebate_bps, 0);
+
+    advance(&env, &client, &admin, &usdc, DAY);
+    let statement = client.get_supply_statement(&user, &Some(usdc.clone()));
+    assert_eq!(statement.rebate_bps, 100);
+    assert_eq!(statement.effective_reserve_factor, 900);
+
+    advance(&env, &client, &admin, &usdc, 60 * DAY);
+    let statement = client.get_supply_statement(&user, &Some(usdc));
+    assert_eq!(statement.rebate_bps, 250);
+    assert_eq!(statement.effective_reserve_factor, 750);
+}
+
+#[test]
+fn test_top_up_and_partial_withdraw_keep_tenure() {
+    let env = create_test_env();
+    let (client, usdc, admin) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+
+    advance(&env, &client, &admin, &usdc, 10 * DAY);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &500);
+    client.ca_withdraw_collateral(&user, &Some(usdc.clone()), &1_200);
+
+    let statement = client.get_supply_statement(&user, &Some(usdc));
+    assert_eq!(statement.supplied, 300);
+    assert_eq!(statement.supplied_since, 1_000);
+}
+
+#[test]
+fn test_full_withdraw_resets_tenure() {
+    let env = create_test_env();
+    let (client, usdc, admin) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+
+    advance(&env, &client, &admin, &usdc, 40 * DAY);
+    client.ca_withdraw_collateral(&user, &Some(usdc.clone()), &1_000);
+    let statement = client.get_supply_statement(&user, &Some(usdc.clone()));
+    assert_eq!(statement.supplied_since, 0);
+    assert_eq!(statement.rebate_bps, 0);
+
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    let statement = client.get_supply_statement(&user, &Some(usdc));
+    assert_eq!(statement.supplied_since, 1_000 + 40 * DAY);
+    assert_eq!(statement.tenure_seconds, 0);
+}
+
+#[test]
+fn test_rejected_withdraw_keeps_tenure() {
+    let env = create_test_env();
+    let (client, usdc, admin) = setup(&env);
+    let user = Address::generate(&env);
+    client.ca_deposit_collateral(&user, &Some(usdc.clone()), &1_000);
+    client.ca_borrow_asset(&user, &Some(usdc.clone()), &500);
+
+    advance(&env, &client, &admin, &usdc, DAY);
+    let result = client.try_ca_withdraw_collateral(&user, &Some(usdc.clone()), &1_000);
+    assert_eq!(result, Err(Ok(CrossAssetError::UnhealthyPosition)));
+    assert_eq!(
+        client
+            .get_supply_statement(&user, &Some(usdc))
+            .supplied_since,
+        1_000
+    );
+}
+
+#[test]
+fn test_invalid_schedules_rejected() {
+    let env = create_test_env();
+    let (client, _, admin) = setup(&env);
+    let tier = |min_tenure_seconds: u64, rebate_bps: i128| TenureRebateTier {
+        min_tenure_seconds,
+        rebate_bps,
+    };
+
+    let unordered = vec![&env, tier(90 * DAY, 100), tier(30 * DAY, 200)];
+    assert!(client.try_set_tenure_rebates(&admin, &unordered).is_err());
+
+    let decreasing = vec![&env, tier(30 * DAY, 200), tier(90 * DAY, 100)];
+    assert!(client.try_set_tenure_rebates(&admin, &decreasing).is_err());
+
+    let out_of_range = vec![&env, tier(30 * DAY, 10_001)];
+    assert!(client
+        .try_set_tenure_rebates(&admin, &out_of_range)
+        .is_err());
+
+    assert_eq!(client.get_tenure_rebates().len(), 2);
+    client.set_tenure_rebates(&admin, &vec![&env]);
+    assert_eq!(client.get_tenure_rebates().len(), 0);
+}
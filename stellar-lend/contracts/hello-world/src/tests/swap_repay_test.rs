@@ -0,0 +1,223 @@
+//! # Collateral Swap Repayment Tests
+//!
+//! Tests that debt can be repaid by swapping collateral of another asset
+//! through the swap adapter's AMM, within its slippage bound. A minimal mock
+//! AMM swaps at a configurable rate.
+
+use crate::cross_asset::{AmmSwapParams, AssetConfig, CrossAssetError, SwapAdapter};
+use crate::tests::token_interface_test::create_token;
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env};
+
+#[contract]
+pub struct MockAmm;
+
+#[contractimpl]
+impl MockAmm {
+    /// Output per unit of input, in basis points; `0` rejects swaps
+    pub fn set_rate(env: Env, rate_bps: i128) {
+        env.storage().instance().set(&0u32, &rate_bps);
+    }
+
+    pub fn execute_swap(env: Env, _user: Address, params: AmmSwapParams) -> i128 {
+        let rate_bps: i128 = env.storage().instance().get(&0u32).unwrap_or(0);
+        if rate_bps == 0 {
+            panic!("swap rejected");
+        }
+        params.amount_in * rate_bps / 10_000
+    }
+}
+
+struct Market {
+    admin: Address,
+    usdc: Address,
+    xlm: Address,
+    amm: MockAmmClient<'static>,
+}
+
+/// Registers USDC and XLM at a price of 1.0 and opens a borrower with 2,000
+/// XLM of collateral and 1,000 USDC of debt
+fn setup(env: &Env) -> (HelloContractClient<'_>, Market, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+
+    let market = Market {
+        usdc: create_token(env),
+        xlm: create_token(env),
+        amm: MockAmmClient::new(env, &env.register(MockAmm, ())),
+        admin,
+    };
+    for asset in [&market.usdc, &market.xlm] {
+        client.initialize_asset(
+            &market.admin,
+            &Some(asset.clone()),
+            &AssetConfig {
+                asset: Some(asset.clone()),
+                collateral_factor: 7500,
+                borrow_factor: 10000,
+                reserve_factor: 1000,
+                max_supply: 0,
+                max_borrow: 0,
+                can_collateralize: true,
+                can_borrow: true,
+                price: 10_000_000,
+                price_updated_at: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    let borrower = Address::generate(env);
+    client.ca_deposit_collateral(&borrower, &Some(market.xlm.clone()), &2_000);
+    client.ca_borrow_asset(&borrower, &Some(market.usdc.clone()), &1_000);
+    (client, market, borrower)
+}
+
+/// Sets the mock AMM as the adapter, with a 1% slippage bound
+fn set_adapter(env: &Env, client: &HelloContractClient<'_>, market: &Market) {
+    client.set_swap_adapter(
+        &market.admin,
+        &Some(SwapAdapter {
+            amm: market.amm.address.clone(),
+            protocol: Address::generate(env),
+            max_slippage_bps: 100,
+        }),
+    );
+}
+
+#[test]
+fn test_partial_repayment_swaps_max_pay_amount() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    set_adapter(&env, &client, &market);
+    market.amm.set_rate(&9_950);
+
+    let result = client.repay_with_collateral_asset(
+        &borrower,
+        &Some(market.usdc.clone()),
+        &Some(market.xlm.clone()),
+        &400,
+    );
+    assert_eq!(result.amount_paid, 400);
+    assert_eq!(result.amount_received, 398);
+    assert_eq!(result.debt_repaid, 398);
+
+    let usdc = client.get_user_asset_position(&borrower, &Some(market.usdc.clone()));
+    let xlm = client.get_user_asset_position(&borrower, &Some(market.xlm.clone()));
+    assert_eq!(usdc.debt_principal, 602);
+    assert_eq!(xlm.collateral, 1_600);
+    assert_eq!(
+        result.health_factor,
+        client.get_user_position_summary(&borrower).health_factor
+    );
+}
+
+#[test]
+fn test_full_repayment_supplies_surplus() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    set_adapter(&env, &client, &market);
+    market.amm.set_rate(&9_950);
+
+    // 1,011 XLM clears the debt even at the 1% slippage bound
+    let result = client.repay_with_collateral_asset(
+        &borrower,
+        &Some(market.usdc.clone()),
+        &Some(market.xlm.clone()),
+        &2_000,
+    );
+    assert_eq!(result.amount_paid, 1_011);
+    assert_eq!(result.amount_received, 1_005);
+    assert_eq!(result.debt_repaid, 1_000);
+
+    let usdc = client.get_user_asset_position(&borrower, &Some(market.usdc.clone()));
+    let xlm = client.get_user_asset_position(&borrower, &Some(market.xlm.clone()));
+    assert_eq!(usdc.debt_principal + usdc.accrued_interest, 0);
+    assert_eq!(usdc.collateral, 5);
+    assert_eq!(xlm.collateral, 989);
+}
+
+#[test]
+fn test_swap_below_slippage_bound_rejected() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    set_adapter(&env, &client, &market);
+
+    // 392 received against a bound of 396
+    market.amm.set_rate(&9_800);
+    let result = client.try_repay_with_collateral_asset(
+        &borrower,
+        &Some(market.usdc.clone()),
+        &Some(market.xlm.clone()),
+        &400,
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::SlippageExceeded)));
+
+    market.amm.set_rate(&0);
+    let result = client.try_repay_with_collateral_asset(
+        &borrower,
+        &Some(market.usdc.clone()),
+        &Some(market.xlm.clone()),
+        &400,
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::SwapFailed)));
+    assert_eq!(
+        client
+            .get_user_asset_position(&borrower, &Some(market.xlm.clone()))
+            .collateral,
+        2_000
+    );
+}
+
+#[test]
+fn test_repayment_rejections() {
+    let env = Env::default();
+    let (client, market, borrower) = setup(&env);
+    let usdc = Some(market.usdc.clone());
+    let xlm = Some(market.xlm.clone());
+
+    let result = client.try_repay_with_collateral_asset(&borrower, &usdc, &xlm, &400);
+    assert_eq!(result, Err(Ok(CrossAssetError::SwapAdapterNotSet)));
+
+    set_adapter(&env, &client, &market);
+    market.amm.set_rate(&10_000);
+    let result = client.try_repay_with_collateral_asset(&borrower, &usdc, &usdc, &400);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtAssetMismatch)));
+    let result = client.try_repay_with_collateral_asset(&borrower, &usdc, &xlm, &0);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    let result = client.try_repay_with_collateral_asset(&borrower, &xlm, &usdc, &400);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+}
+
+#[test]
+fn test_set_swap_adapter_rejections() {
+    let env = Env::default();
+    let (client, market, _) = setup(&env);
+    let adapter = SwapAdapter {
+        amm: market.amm.address.clone(),
+        protocol: Address::generate(&env),
+        max_slippage_bps: 100,
+    };
+
+    let result = client.try_set_swap_adapter(&Address::generate(&env), &Some(adapter.clone()));
+    assert_eq!(result, Err(Ok(CrossAssetError::NotAuthorized)));
+    for slippage in [-1, 1_001] {
+        let result = client.try_set_swap_adapter(
+            &market.admin,
+            &Some(SwapAdapter {
+                max_slippage_bps: slippage,
+                ..adapter.clone()
+            }),
+        );
+        assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    }
+    assert_eq!(client.get_swap_adapter(), None);
+
+    client.set_swap_adapter(&market.admin, &Some(adapter.clone()));
+    assert_eq!(client.get_swap_adapter(), Some(adapter));
+    client.set_swap_adapter(&market.admin, &None);
+    assert_eq!(client.get_swap_adapter(), None);
+}
@@ -58,6 +58,8 @@ fn set_asset_params(
         deposit_enabled,
         collateral_factor,
         max_deposit,
+        supply_cap: 0,
+        borrow_cap: 0,
     };
     let key = DepositDataKey::AssetParams(asset.clone());
     env.storage().persistent().set(&key, &params);
@@ -366,17 +368,8 @@ fn test_deposit_collateral_activity_log() {
     client.deposit_collateral(&user, &None, &amount);
 
     // Verify activity log was updated
-    let log = env.as_contract(&contract_id, || {
-        let log_key = DepositDataKey::ActivityLog;
-        env.storage()
-            .persistent()
-            .get::<DepositDataKey, soroban_sdk::Vec<deposit::Activity>>(&log_key)
-    });
-
-    assert!(log.is_some(), "Activity log should exist");
-    if let Some(activities) = log {
-        assert!(!activities.is_empty(), "Activity log should not be empty");
-    }
+    let activities = client.get_recent_activity(&10, &0);
+    assert!(!activities.is_empty(), "Activity log should not be empty");
 }
 
 // #[test]
@@ -893,7 +886,7 @@ fn test_get_liquidation_incentive_amount() {
 
     // Default liquidation_incentive is 1,000 (10%)
     // Liquidated amount: 1,000 -> Incentive: 100 (10%)
-    let incentive = client.get_liquidation_incentive_amount(&1_000);
+    let incentive = client.get_liquidation_incentive_amount(&1_000, &9_500);
     assert_eq!(incentive, 100);
 
     // Update liquidation_incentive to 11% (within 10% change limit: 1,000 * 1.1 = 1,100)
@@ -906,7 +899,7 @@ fn test_get_liquidation_incentive_amount() {
     );
 
     // Liquidated amount: 1,000 -> Incentive: 110 (11%)
-    let incentive = client.get_liquidation_incentive_amount(&1_000);
+    let incentive = client.get_liquidation_incentive_amount(&1_000, &9_500);
     assert_eq!(incentive, 110);
 }
 
@@ -1494,7 +1487,7 @@ fn test_withdraw_collateral_pause_switch() {
         let pause_key = DepositDataKey::PauseSwitches;
         let mut pause_map = soroban_sdk::Map::new(&env);
         pause_map.set(Symbol::new(&env, "pause_withdraw"), true);
-        env.storage().persistent().set(&pause_key, &pause_map);
+        env.storage().instance().set(&pause_key, &pause_map);
     });
 
     // Try to withdraw (should fail)
@@ -1772,7 +1765,7 @@ fn test_repay_debt_pause_switch() {
         let pause_key = DepositDataKey::PauseSwitches;
         let mut pause_map = soroban_sdk::Map::new(&env);
         pause_map.set(Symbol::new(&env, "pause_repay"), true);
-        env.storage().persistent().set(&pause_key, &pause_map);
+        env.storage().instance().set(&pause_key, &pause_map);
     });
 
     // Try to repay (should fail)
@@ -2098,7 +2091,7 @@ fn test_borrow_asset_pause_switch() {
         let pause_key = DepositDataKey::PauseSwitches;
         let mut pause_map = soroban_sdk::Map::new(&env);
         pause_map.set(Symbol::new(&env, "pause_borrow"), true);
-        env.storage().persistent().set(&pause_key, &pause_map);
+        env.storage().instance().set(&pause_key, &pause_map);
     });
 
     // Try to borrow (should fail)
@@ -2352,17 +2345,8 @@ fn test_borrow_asset_activity_log() {
     client.borrow_asset(&user, &None, &1000);
 
     // Verify activity log was updated
-    let log = env.as_contract(&contract_id, || {
-        let log_key = DepositDataKey::ActivityLog;
-        env.storage()
-            .persistent()
-            .get::<DepositDataKey, soroban_sdk::Vec<deposit::Activity>>(&log_key)
-    });
-
-    assert!(log.is_some(), "Activity log should exist");
-    if let Some(activities) = log {
-        assert!(!activities.is_empty(), "Activity log should not be empty");
-    }
+    let activities = client.get_recent_activity(&10, &0);
+    assert!(!activities.is_empty(), "Activity log should not be empty");
 }
 
 #[test]
@@ -2654,184 +2638,7 @@ fn test_configure_oracle() {
 
 // ==================== FLASH LOAN TESTS ====================
 
-// #[test]
-// #[should_panic(expected = "InsufficientLiquidity")]
-#[allow(dead_code)]
-fn test_execute_flash_loan_success() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let token_admin = Address::generate(&env);
-    let asset = create_token_contract(&env, &token_admin);
-    let callback = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Set asset parameters
-    env.as_contract(&contract_id, || {
-        set_asset_params(&env, &asset, true, 10000, 0);
-    });
-
-    // Note: In a real test environment with proper token setup, we would:
-    // 1. Mint tokens to user
-    // 2. User deposits tokens to contract (providing liquidity)
-    // 3. Execute flash loan
-    // For now, this test validates that flash loan correctly identifies insufficient liquidity
-    // when contract doesn't have tokens
-
-    // Execute flash loan (will fail with InsufficientLiquidity, which is correct)
-    let amount = 1000;
-    client.execute_flash_loan(&user, &asset, &amount, &callback);
-
-    // #[test]
-    // #[should_panic(expected = "InvalidAmount")]
-    fn test_execute_flash_loan_zero_amount() {
-        let env = create_test_env();
-        let contract_id = env.register(HelloContract, ());
-        let client = HelloContractClient::new(&env, &contract_id);
-
-        let admin = Address::generate(&env);
-        let user = Address::generate(&env);
-        let asset = Address::generate(&env);
-        let callback = Address::generate(&env);
-
-        client.initialize(&admin);
-
-        // Try to execute flash loan with zero amount
-        client.execute_flash_loan(&user, &asset, &0, &callback);
-    }
-
-    // #[test]
-    // #[should_panic(expected = "InvalidAmount")]
-    // fn test_execute_flash_loan_negative_amount() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let callback = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Try to execute flash loan with negative amount
-    client.execute_flash_loan(&user, &asset, &(-100), &callback);
-}
-
-// #[test]
-// #[should_panic(expected = "InvalidAsset")]
-#[allow(dead_code)]
-fn test_execute_flash_loan_invalid_asset() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let callback = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Try to use contract address as asset (invalid)
-    client.execute_flash_loan(&user, &contract_id, &1000, &callback);
-}
-
-// #[test]
-// #[should_panic(expected = "InvalidCallback")]
-#[allow(dead_code)]
-fn test_execute_flash_loan_invalid_callback() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Try to use contract address as callback (invalid)
-    client.execute_flash_loan(&user, &asset, &1000, &contract_id);
-}
-
-// #[test]
-// #[should_panic(expected = "InsufficientLiquidity")]
-// fn test_repay_flash_loan_success() {
-//     let env = create_test_env();
-//     let contract_id = env.register(HelloContract, ());
-//     let client = HelloContractClient::new(&env, &contract_id);
-
-//     let admin = Address::generate(&env);
-//     let user = Address::generate(&env);
-//     let token_admin = Address::generate(&env);
-//     let asset = create_token_contract(&env, &token_admin);
-//     let callback = Address::generate(&env);
-
-//     client.initialize(&admin);
-
-//     // Set asset parameters
-//     env.as_contract(&contract_id, || {
-//         set_asset_params(&env, &asset, true, 10000, 0);
-//     });
-
-//     // Note: Flash loan requires contract to have liquidity
-//     // This test validates repayment logic when flash loan is active
-//     // In a real scenario with proper token setup, this would work
-
-//     // Execute flash loan (will fail with InsufficientLiquidity without proper token setup)
-//     let amount = 1000;
-//     client.execute_flash_loan(&user, &asset, &amount, &callback);
-// }
-
-#[test]
-#[should_panic(expected = "NotRepaid")]
-fn test_repay_flash_loan_no_active_loan() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Try to repay without active flash loan
-    client.repay_flash_loan(&user, &asset, &1000);
-}
-
-#[test]
-#[should_panic(expected = "NotRepaid")]
-fn test_repay_flash_loan_insufficient_amount() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let token_admin = Address::generate(&env);
-    let asset = create_token_contract(&env, &token_admin);
-
-    client.initialize(&admin);
-
-    // Set asset parameters
-    env.as_contract(&contract_id, || {
-        set_asset_params(&env, &asset, true, 10000, 0);
-    });
-
-    // Test insufficient repayment validation
-    // This test validates that repay_flash_loan correctly rejects insufficient amounts
-    // In a real scenario, flash loan would be executed first, then repayment validated
-
-    // Try to repay without active flash loan (will fail with NotRepaid)
-    // This validates the repayment validation logic
-    client.repay_flash_loan(&user, &asset, &1000);
-}
-
+#[cfg(feature = "flash-loans")]
 #[test]
 fn test_set_flash_loan_fee() {
     let env = create_test_env();
@@ -2847,6 +2654,7 @@ fn test_set_flash_loan_fee() {
     client.set_flash_loan_fee(&admin, &new_fee);
 }
 
+#[cfg(feature = "flash-loans")]
 #[test]
 #[should_panic(expected = "InvalidCallback")]
 fn test_set_flash_loan_fee_unauthorized() {
@@ -2863,6 +2671,7 @@ fn test_set_flash_loan_fee_unauthorized() {
     client.set_flash_loan_fee(&user, &18);
 }
 
+#[cfg(feature = "flash-loans")]
 #[test]
 fn test_configure_flash_loan() {
     let env = create_test_env();
@@ -2884,71 +2693,6 @@ fn test_configure_flash_loan() {
     client.configure_flash_loan(&admin, &config);
 }
 
-// #[test]
-// #[should_panic(expected = "InsufficientLiquidity")]
-#[allow(dead_code)]
-fn test_flash_loan_fee_calculation_logic() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let token_admin = Address::generate(&env);
-    let asset = create_token_contract(&env, &token_admin);
-    let callback = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Set asset parameters
-    env.as_contract(&contract_id, || {
-        set_asset_params(&env, &asset, true, 10000, 0);
-    });
-
-    // Test fee calculation logic
-    // Fee should be 9 basis points (0.09%) = 10000 * 9 / 10000 = 9
-    // This test validates the fee calculation even if actual transfer fails
-    let amount = 10000;
-    let _expected_fee = 9;
-    let _expected_repayment = amount + _expected_fee;
-
-    // Execute flash loan (will fail with InsufficientLiquidity, but we can test fee calc separately)
-    client.execute_flash_loan(&user, &asset, &amount, &callback);
-}
-
-// #[test]
-// #[should_panic(expected = "InsufficientLiquidity")]
-#[allow(dead_code)]
-fn test_flash_loan_multiple_assets_validation() {
-    let env = create_test_env();
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let user = Address::generate(&env);
-    let token_admin = Address::generate(&env);
-    let asset1 = create_token_contract(&env, &token_admin);
-    let asset2 = create_token_contract(&env, &token_admin);
-    let callback = Address::generate(&env);
-
-    client.initialize(&admin);
-
-    // Set asset parameters
-    env.as_contract(&contract_id, || {
-        set_asset_params(&env, &asset1, true, 10000, 0);
-        set_asset_params(&env, &asset2, true, 10000, 0);
-    });
-
-    // Test that flash loans can be attempted for multiple assets
-    // In a real scenario with proper token setup, both would succeed
-    let amount1 = 1000;
-    let _amount2 = 2000;
-
-    // Both will fail with InsufficientLiquidity without proper token setup
-    // This validates that the function correctly handles multiple assets
-    client.execute_flash_loan(&user, &asset1, &amount1, &callback);
-}
-
 // ==================== LIQUIDATION TESTS ====================
 
 #[test]
@@ -3608,17 +3352,8 @@ fn test_liquidate_activity_log() {
     client.liquidate(&liquidator, &borrower, &None, &None, &300);
 
     // Verify activity log was updated
-    let log = env.as_contract(&contract_id, || {
-        let log_key = DepositDataKey::ActivityLog;
-        env.storage()
-            .persistent()
-            .get::<DepositDataKey, soroban_sdk::Vec<deposit::Activity>>(&log_key)
-    });
-
-    assert!(log.is_some(), "Activity log should exist");
-    if let Some(activities) = log {
-        assert!(!activities.is_empty(), "Activity log should not be empty");
-    }
+    let activities = client.get_recent_activity(&10, &0);
+    assert!(!activities.is_empty(), "Activity log should not be empty");
 }
 
 // ==================== INTEREST RATE MODEL TESTS ====================
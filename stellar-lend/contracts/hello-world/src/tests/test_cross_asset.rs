@@ -1,5 +1,6 @@
 #![cfg(test)]
 
+use crate::tests::token_interface_test::create_token;
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{testutils::Address as _, Address, Env};
 
@@ -91,10 +92,10 @@ fn test_initialize_single_asset() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
 
-    let result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(result.is_ok());
 
     // Verify asset was added to list
@@ -121,20 +122,20 @@ fn test_initialize_multiple_assets() {
     assert!(init_result.is_ok());
 
     // Initialize USDC
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let usdc_result = client.try_initialize_asset(&Some(usdc.clone()), &usdc_config);
+    let usdc_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config);
     assert!(usdc_result.is_ok());
 
     // Initialize ETH
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     let eth_config = create_asset_config(&env, Some(eth.clone()), 2000_0000000);
-    let eth_result = client.try_initialize_asset(&Some(eth.clone()), &eth_config);
+    let eth_result = client.try_initialize_asset(&admin, &Some(eth.clone()), &eth_config);
     assert!(eth_result.is_ok());
 
     // Initialize native XLM
     let xlm_config = create_asset_config(&env, None, 1000000);
-    let xlm_result = client.try_initialize_asset(&None, &xlm_config);
+    let xlm_result = client.try_initialize_asset(&admin, &None, &xlm_config);
     assert!(xlm_result.is_ok());
 
     // Verify all assets were added
@@ -148,11 +149,11 @@ fn test_initialize_asset_without_admin_fails() {
     let contract_id = env.register(HelloContract, ());
     let client = HelloContractClient::new(&env, &contract_id);
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
 
     // Should fail because admin not initialized
-    let result = client.try_initialize_asset(&Some(usdc), &config);
+    let result = client.try_initialize_asset(&Address::generate(&env), &Some(usdc), &config);
     assert!(result.is_err());
 }
 
@@ -170,13 +171,14 @@ fn test_update_asset_config_collateral_factor() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Update collateral factor
     let update_result = client.try_update_asset_config(
+        &admin,
         &Some(usdc.clone()),
         &Some(8000_i128), // new collateral_factor
         &None,
@@ -203,13 +205,14 @@ fn test_update_asset_config_multiple_params() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Update multiple parameters
     let update_result = client.try_update_asset_config(
+        &admin,
         &Some(usdc.clone()),
         &Some(8000_i128),               // collateral_factor
         &Some(8500_i128),               // borrow_factor
@@ -241,14 +244,14 @@ fn test_update_asset_price() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Update price
     let new_price = 1_0100000; // $1.01
-    let price_result = client.try_update_asset_price(&Some(usdc.clone()), &new_price);
+    let price_result = client.try_update_asset_price(&admin, &Some(usdc.clone()), &new_price);
     assert!(price_result.is_ok());
 
     // Verify price update
@@ -267,13 +270,13 @@ fn test_update_asset_price_invalid_fails() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Try to update with invalid price (zero or negative)
-    let price_result = client.try_update_asset_price(&Some(usdc), &0);
+    let price_result = client.try_update_asset_price(&admin, &Some(usdc), &0);
     assert!(price_result.is_err());
 }
 
@@ -287,13 +290,14 @@ fn test_disable_asset_collateralization() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Disable collateralization
     let update_result = client.try_update_asset_config(
+        &admin,
         &Some(usdc.clone()),
         &None,
         &None,
@@ -320,13 +324,14 @@ fn test_disable_asset_borrowing() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Disable borrowing
     let update_result = client.try_update_asset_config(
+        &admin,
         &Some(usdc.clone()),
         &None,
         &None,
@@ -359,9 +364,9 @@ fn test_deposit_collateral_single_asset() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Deposit
@@ -389,7 +394,7 @@ fn test_deposit_native_xlm() {
     assert!(init_result.is_ok());
 
     let xlm_config = create_asset_config(&env, None, 1000000);
-    let asset_result = client.try_initialize_asset(&None, &xlm_config);
+    let asset_result = client.try_initialize_asset(&admin, &None, &xlm_config);
     assert!(asset_result.is_ok());
 
     // Deposit XLM
@@ -414,10 +419,10 @@ fn test_deposit_exceeds_max_supply() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let mut config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     config.max_supply = 1000_0000000; // Set low max supply
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Try to deposit more than max supply
@@ -438,9 +443,9 @@ fn test_deposit_multiple_times_same_asset() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // First deposit
@@ -475,15 +480,15 @@ fn test_deposit_multiple_assets() {
     assert!(init_result.is_ok());
 
     // Initialize USDC
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let usdc_result = client.try_initialize_asset(&Some(usdc.clone()), &usdc_config);
+    let usdc_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config);
     assert!(usdc_result.is_ok());
 
     // Initialize ETH
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     let eth_config = create_asset_config(&env, Some(eth.clone()), 2000_0000000);
-    let eth_result = client.try_initialize_asset(&Some(eth.clone()), &eth_config);
+    let eth_result = client.try_initialize_asset(&admin, &Some(eth.clone()), &eth_config);
     assert!(eth_result.is_ok());
 
     // Deposit USDC
@@ -517,20 +522,22 @@ fn test_deposit_three_different_assets() {
     assert!(init_result.is_ok());
 
     // Setup USDC, ETH, and XLM
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &usdc_config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config)
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     let eth_config = create_asset_config(&env, Some(eth.clone()), 2000_0000000);
     assert!(client
-        .try_initialize_asset(&Some(eth.clone()), &eth_config)
+        .try_initialize_asset(&admin, &Some(eth.clone()), &eth_config)
         .is_ok());
 
     let xlm_config = create_asset_config(&env, None, 1000000);
-    assert!(client.try_initialize_asset(&None, &xlm_config).is_ok());
+    assert!(client
+        .try_initialize_asset(&admin, &None, &xlm_config)
+        .is_ok());
 
     // Deposit all three
     assert!(client
@@ -576,9 +583,9 @@ fn test_borrow_against_single_asset_collateral() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Deposit collateral
@@ -609,9 +616,9 @@ fn test_borrow_exceeds_health_factor() {
 
     client.initialize_ca(&admin);
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    client.initialize_asset(&Some(usdc.clone()), &config);
+    client.initialize_asset(&admin, &Some(usdc.clone()), &config);
 
     // Deposit collateral
     let collateral_amount = 1000_0000000;
@@ -634,10 +641,10 @@ fn test_borrow_exceeds_max_borrow_cap() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let mut config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     config.max_borrow = 500_0000000; // Low borrow cap
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Deposit large collateral
@@ -662,9 +669,9 @@ fn test_borrow_without_collateral_fails() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Try to borrow without any collateral
@@ -689,17 +696,17 @@ fn test_borrow_against_multi_asset_collateral() {
     assert!(init_result.is_ok());
 
     // Setup USDC
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &usdc_config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config)
         .is_ok());
 
     // Setup ETH (higher value)
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     let eth_config = create_asset_config(&env, Some(eth.clone()), 2000_0000000);
     assert!(client
-        .try_initialize_asset(&Some(eth.clone()), &eth_config)
+        .try_initialize_asset(&admin, &Some(eth.clone()), &eth_config)
         .is_ok());
 
     // Deposit both as collateral
@@ -737,16 +744,16 @@ fn test_borrow_different_asset_than_collateral() {
     assert!(init_result.is_ok());
 
     // Setup USDC and ETH
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &usdc_config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config)
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     let eth_config = create_asset_config(&env, Some(eth.clone()), 2000_0000000);
     assert!(client
-        .try_initialize_asset(&Some(eth.clone()), &eth_config)
+        .try_initialize_asset(&admin, &Some(eth.clone()), &eth_config)
         .is_ok());
 
     // Deposit USDC as collateral
@@ -783,25 +790,28 @@ fn test_borrow_with_three_asset_collateral() {
     assert!(init_result.is_ok());
 
     // Setup three assets
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
         .is_ok());
 
-    let btc = Address::generate(&env);
+    let btc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(btc.clone()),
             &create_asset_config(&env, Some(btc.clone()), 40000_0000000)
         )
@@ -839,17 +849,19 @@ fn test_borrow_multiple_assets_sequentially() {
     assert!(client.try_initialize_ca(&admin).is_ok());
 
     // Setup USDC and ETH
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
@@ -894,9 +906,9 @@ fn test_withdraw_collateral_no_debt() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
-    let asset_result = client.try_initialize_asset(&Some(usdc.clone()), &config);
+    let asset_result = client.try_initialize_asset(&admin, &Some(usdc.clone()), &config);
     assert!(asset_result.is_ok());
 
     // Deposit
@@ -927,9 +939,10 @@ fn test_withdraw_all_collateral() {
 
     assert!(client.try_initialize_ca(&admin).is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -962,10 +975,10 @@ fn test_withdraw_with_debt_maintains_health() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &config)
         .is_ok());
 
     // Deposit and borrow
@@ -998,10 +1011,10 @@ fn test_withdraw_breaks_health_factor_fails() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &config)
         .is_ok());
 
     // Deposit and borrow at limit
@@ -1029,10 +1042,10 @@ fn test_withdraw_insufficient_collateral_fails() {
     let init_result = client.try_initialize_ca(&admin);
     assert!(init_result.is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &config)
         .is_ok());
 
     // Deposit
@@ -1057,17 +1070,19 @@ fn test_withdraw_from_multi_asset_collateral() {
     assert!(client.try_initialize_ca(&admin).is_ok());
 
     // Setup USDC and ETH
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
@@ -1111,10 +1126,10 @@ fn test_repay_partial_debt() {
 
     assert!(client.try_initialize_ca(&admin).is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &config)
         .is_ok());
 
     // Deposit and borrow
@@ -1149,9 +1164,10 @@ fn test_repay_full_debt() {
 
     assert!(client.try_initialize_ca(&admin).is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1186,9 +1202,10 @@ fn test_repay_more_than_debt() {
 
     assert!(client.try_initialize_ca(&admin).is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1224,17 +1241,19 @@ fn test_repay_multi_asset_debt() {
     assert!(client.try_initialize_ca(&admin).is_ok());
 
     // Setup USDC and ETH
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
@@ -1307,10 +1326,10 @@ fn test_get_position_summary_single_asset() {
     assert!(client.try_initialize_ca(&admin).is_ok());
 
     // Setup USDC
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &usdc_config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config)
         .is_ok());
 
     // Deposit and borrow
@@ -1344,25 +1363,28 @@ fn test_get_position_summary_multi_asset() {
     assert!(client.try_initialize_ca(&admin).is_ok());
 
     // Setup multiple assets
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
 
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
         .is_ok());
 
-    let btc = Address::generate(&env);
+    let btc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(btc.clone()),
             &create_asset_config(&env, Some(btc.clone()), 40000_0000000)
         )
@@ -1409,9 +1431,10 @@ fn test_position_summary_health_factor_calculation() {
 
     assert!(client.try_initialize_ca(&admin).is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1444,16 +1467,18 @@ fn test_position_summary_liquidatable_status() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
@@ -1468,7 +1493,7 @@ fn test_position_summary_liquidatable_status() {
     assert!(summary_before.health_factor >= 10000);
     assert!(!summary_before.is_liquidatable);
     assert!(client
-        .try_update_asset_price(&Some(usdc.clone()), &5000000)
+        .try_update_asset_price(&admin, &Some(usdc.clone()), &5000000)
         .is_ok());
     let summary_after = client.get_user_position_summary(&user);
     assert!(summary_after.health_factor < 10000);
@@ -1486,8 +1511,9 @@ fn test_position_summary_borrow_capacity() {
 
     assert!(client.try_initialize_ca(&admin).is_ok());
 
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     client.initialize_asset(
+        &admin,
         &Some(usdc.clone()),
         &create_asset_config(&env, Some(usdc.clone()), 1_0000000),
     );
@@ -1520,9 +1546,10 @@ fn test_zero_amount_operations() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1541,11 +1568,11 @@ fn test_large_amount_operations() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let mut config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     config.max_supply = i128::MAX;
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &config)
         .is_ok());
     let large_amount = 10_000_000_000_000_000;
     let deposit_result =
@@ -1564,9 +1591,10 @@ fn test_multiple_users_same_asset() {
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1592,18 +1620,20 @@ fn test_asset_list_tracking() {
     assert!(client.try_initialize_ca(&admin).is_ok());
     let list = client.get_asset_list();
     assert_eq!(list.len(), 0);
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
     let list = client.get_asset_list();
     assert_eq!(list.len(), 1);
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
@@ -1611,7 +1641,7 @@ fn test_asset_list_tracking() {
     let list = client.get_asset_list();
     assert_eq!(list.len(), 2);
     assert!(client
-        .try_initialize_asset(&None, &create_asset_config(&env, None, 1000000))
+        .try_initialize_asset(&admin, &None, &create_asset_config(&env, None, 1000000))
         .is_ok());
     let list = client.get_asset_list();
     assert_eq!(list.len(), 3);
@@ -1625,29 +1655,34 @@ fn test_complex_multi_asset_scenario() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
         .is_ok());
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
         .is_ok());
-    let btc = Address::generate(&env);
+    let btc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(btc.clone()),
             &create_asset_config(&env, Some(btc.clone()), 40000_0000000)
         )
         .is_ok());
     let xlm_config = create_asset_config(&env, None, 1000000);
-    assert!(client.try_initialize_asset(&None, &xlm_config).is_ok());
+    assert!(client
+        .try_initialize_asset(&admin, &None, &xlm_config)
+        .is_ok());
     assert!(client
         .try_ca_deposit_collateral(&user, &Some(usdc.clone()), &10000_0000000)
         .is_ok());
@@ -1686,9 +1721,10 @@ fn test_position_summary_after_price_update() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(eth.clone()),
             &create_asset_config(&env, Some(eth.clone()), 2000_0000000)
         )
@@ -1699,7 +1735,7 @@ fn test_position_summary_after_price_update() {
     let summary1 = client.get_user_position_summary(&user);
     let initial_value = summary1.total_collateral_value;
     assert!(client
-        .try_update_asset_price(&Some(eth.clone()), &2500_0000000)
+        .try_update_asset_price(&admin, &Some(eth.clone()), &2500_0000000)
         .is_ok());
     let summary2 = client.get_user_position_summary(&user);
     assert!(summary2.total_collateral_value > initial_value);
@@ -1713,17 +1749,17 @@ fn test_different_collateral_factors() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let mut usdc_config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     usdc_config.collateral_factor = 9000;
     assert!(client
-        .try_initialize_asset(&Some(usdc.clone()), &usdc_config)
+        .try_initialize_asset(&admin, &Some(usdc.clone()), &usdc_config)
         .is_ok());
-    let eth = Address::generate(&env);
+    let eth = create_token(&env);
     let mut eth_config = create_asset_config(&env, Some(eth.clone()), 2000_0000000);
     eth_config.collateral_factor = 7000;
     assert!(client
-        .try_initialize_asset(&Some(eth.clone()), &eth_config)
+        .try_initialize_asset(&admin, &Some(eth.clone()), &eth_config)
         .is_ok());
     assert!(client
         .try_ca_deposit_collateral(&user, &Some(usdc.clone()), &10000_0000000)
@@ -1744,9 +1780,10 @@ fn test_update_asset_price_reflects_in_summary() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1759,7 +1796,7 @@ fn test_update_asset_price_reflects_in_summary() {
         .is_ok());
     let summary_before = client.get_user_position_summary(&user);
     assert!(client
-        .try_update_asset_price(&Some(usdc.clone()), &1_1000000)
+        .try_update_asset_price(&admin, &Some(usdc.clone()), &1_1000000)
         .is_ok());
     let summary_after = client.get_user_position_summary(&user);
     assert!(summary_after.total_collateral_value > summary_before.total_collateral_value);
@@ -1774,9 +1811,10 @@ fn test_withdraw_all_after_repay() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1806,9 +1844,10 @@ fn test_sequential_deposits_and_withdrawals() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1837,9 +1876,10 @@ fn test_borrow_repay_multiple_cycles() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1900,11 +1940,12 @@ fn test_native_and_token_assets_together() {
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
     assert!(client
-        .try_initialize_asset(&None, &create_asset_config(&env, None, 1000000))
+        .try_initialize_asset(&admin, &None, &create_asset_config(&env, None, 1000000))
         .is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -1930,11 +1971,11 @@ fn test_max_values_boundaries() {
     let client = HelloContractClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     let mut config = create_asset_config(&env, Some(usdc.clone()), 1_0000000);
     config.collateral_factor = 10000;
     config.borrow_factor = 10000;
-    let result = client.try_initialize_asset(&Some(usdc), &config);
+    let result = client.try_initialize_asset(&admin, &Some(usdc), &config);
     assert!(result.is_ok());
 }
 
@@ -1946,9 +1987,10 @@ fn test_position_with_only_debt_no_collateral() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     assert!(client.try_initialize_ca(&admin).is_ok());
-    let usdc = Address::generate(&env);
+    let usdc = create_token(&env);
     assert!(client
         .try_initialize_asset(
+            &admin,
             &Some(usdc.clone()),
             &create_asset_config(&env, Some(usdc.clone()), 1_0000000)
         )
@@ -0,0 +1,128 @@
+//! # Liquidation Threshold Migration Tests
+//!
+//! Tests for phasing in liquidation threshold increases: existing borrowers
+//! keep the previous threshold until the delay ends, new borrows use the new
+//! one at once, and a zero delay applies increases immediately.
+
+use crate::risk_management::{RiskManagementError, ThresholdMigration};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, IntoVal, Symbol,
+};
+
+const DELAY: u64 = 86_400;
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (admin, client)
+}
+
+fn borrower(env: &Env, client: &HelloContractClient<'_>) -> Address {
+    let user = Address::generate(env);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1_000);
+    user
+}
+
+/// Raise the liquidation threshold from the 105% default to 110%
+fn tighten(client: &HelloContractClient<'_>, admin: &Address) {
+    client.set_risk_params(admin, &None, &Some(11_000), &None, &None);
+}
+
+#[test]
+fn test_increase_applies_immediately_without_delay() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    assert_eq!(client.get_threshold_migration_delay(), 0);
+
+    let user = borrower(&env, &client);
+    tighten(&client, &admin);
+
+    assert_eq!(client.get_threshold_migration(), None);
+    assert_eq!(client.get_position_threshold(&user), 11_000);
+}
+
+#[test]
+fn test_existing_positions_keep_previous_threshold_until_delay_ends() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    client.set_threshold_migration_delay(&admin, &DELAY);
+
+    let existing = borrower(&env, &client);
+    let no_debt = Address::generate(&env);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    tighten(&client, &admin);
+
+    assert_eq!(
+        client.get_threshold_migration(),
+        Some(ThresholdMigration {
+            previous_threshold: 10_500,
+            started_at: 1_010,
+            effective_at: 1_010 + DELAY,
+        })
+    );
+    assert_eq!(client.get_liquidation_threshold(), 11_000);
+    assert_eq!(client.get_position_threshold(&existing), 10_500);
+    assert_eq!(client.get_position_threshold(&no_debt), 10_500);
+
+    // Borrowing after the change moves a position to the new threshold
+    let newcomer = borrower(&env, &client);
+    assert_eq!(client.get_position_threshold(&newcomer), 11_000);
+    client.borrow_asset(&existing, &None, &100);
+    assert_eq!(client.get_position_threshold(&existing), 11_000);
+
+    env.ledger().with_mut(|li| li.timestamp += DELAY);
+    assert_eq!(client.get_threshold_migration(), None);
+    assert_eq!(client.get_position_threshold(&no_debt), 11_000);
+}
+
+#[test]
+fn test_lowering_threshold_reaches_existing_positions() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    client.set_threshold_migration_delay(&admin, &DELAY);
+
+    let existing = borrower(&env, &client);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    tighten(&client, &admin);
+    client.set_risk_params(&admin, &None, &Some(10_000), &None, &None);
+
+    assert_eq!(client.get_position_threshold(&existing), 10_000);
+}
+
+#[test]
+fn test_migration_emits_event() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+    client.set_threshold_migration_delay(&admin, &DELAY);
+    tighten(&client, &admin);
+
+    let events = env.events().all();
+    let expected_topics = (Symbol::new(&env, "threshold_migration_event"),).into_val(&env);
+    assert!(events
+        .iter()
+        .any(|(contract, topics, _)| contract == client.address && topics == expected_topics));
+}
+
+#[test]
+fn test_set_threshold_migration_delay_validation() {
+    let env = create_test_env();
+    let (admin, client) = setup_contract_with_admin(&env);
+
+    let result = client.try_set_threshold_migration_delay(&Address::generate(&env), &DELAY);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+
+    let result = client.try_set_threshold_migration_delay(&admin, &(31 * DELAY));
+    assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+}
@@ -0,0 +1,160 @@
+//! # Token Interface Tests
+//!
+//! Tests for the token interface checks run when an asset is listed, and the
+//! shared [`create_token`] helper used by cross-asset tests to list real
+//! token contracts.
+
+use crate::admin_simulation::{AdminAction, AdminSimulationError, AssetListing};
+use crate::cross_asset::{AssetConfig, CrossAssetError};
+use crate::token_interface::{
+    TOKEN_CAP_ALLOWANCE, TOKEN_CAP_BALANCE, TOKEN_CAP_DECIMALS, TOKEN_CAP_NAME, TOKEN_CAP_SYMBOL,
+};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env};
+
+/// Register a Stellar asset contract to list as a cross-asset market
+pub(crate) fn create_token(env: &Env) -> Address {
+    env.register_stellar_asset_contract_v2(Address::generate(env))
+        .address()
+}
+
+/// A token that reports balances but has no allowance support
+#[contract]
+struct BalanceOnlyToken;
+
+#[contractimpl]
+impl BalanceOnlyToken {
+    pub fn decimals(_env: Env) -> u32 {
+        7
+    }
+
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        0
+    }
+}
+
+/// A token with more decimals than the protocol supports
+#[contract]
+struct HighPrecisionToken;
+
+#[contractimpl]
+impl HighPrecisionToken {
+    pub fn decimals(_env: Env) -> u32 {
+        24
+    }
+
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        0
+    }
+
+    pub fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
+        0
+    }
+}
+
+fn asset_config(env: &Env, asset: &Address) -> AssetConfig {
+    AssetConfig {
+        asset: Some(asset.clone()),
+        collateral_factor: 7500,
+        borrow_factor: 10000,
+        reserve_factor: 1000,
+        max_supply: 0,
+        max_borrow: 0,
+        can_collateralize: true,
+        can_borrow: true,
+        price: 10_000_000,
+        price_updated_at: env.ledger().timestamp(),
+    }
+}
+
+fn setup(env: &Env) -> (HelloContractClient<'_>, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.initialize_ca(&admin);
+    (client, admin)
+}
+
+#[test]
+fn test_listing_records_capabilities() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let usdc = create_token(&env);
+
+    client.initialize_asset(&admin, &Some(usdc.clone()), &asset_config(&env, &usdc));
+
+    assert_eq!(
+        client.get_token_capabilities(&Some(usdc)),
+        Some(
+            TOKEN_CAP_DECIMALS
+                | TOKEN_CAP_BALANCE
+                | TOKEN_CAP_ALLOWANCE
+                | TOKEN_CAP_NAME
+                | TOKEN_CAP_SYMBOL
+        )
+    );
+}
+
+#[test]
+fn test_native_xlm_skips_checks() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let mut config = asset_config(&env, &Address::generate(&env));
+    config.asset = None;
+    client.initialize_asset(&admin, &None, &config);
+
+    assert_eq!(client.get_asset_list().len(), 1);
+    assert_eq!(client.get_token_capabilities(&None), None);
+}
+
+#[test]
+fn test_listing_rejects_non_tokens() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    // No contract at all
+    let account = Address::generate(&env);
+    let result = client.try_initialize_asset(
+        &admin,
+        &Some(account.clone()),
+        &asset_config(&env, &account),
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::UnsupportedToken)));
+
+    // A contract without allowance support
+    let partial = env.register(BalanceOnlyToken, ());
+    let result = client.try_initialize_asset(
+        &admin,
+        &Some(partial.clone()),
+        &asset_config(&env, &partial),
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::UnsupportedToken)));
+
+    // Too many decimals
+    let precise = env.register(HighPrecisionToken, ());
+    let result = client.try_initialize_asset(
+        &admin,
+        &Some(precise.clone()),
+        &asset_config(&env, &precise),
+    );
+    assert_eq!(result, Err(Ok(CrossAssetError::UnsupportedToken)));
+
+    assert!(client.get_asset_list().is_empty());
+    assert_eq!(client.get_token_capabilities(&Some(partial)), None);
+}
+
+#[test]
+fn test_simulated_listing_checks_token() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let partial = env.register(BalanceOnlyToken, ());
+
+    let result = client.try_simulate_admin_action(&AdminAction::ListAsset(AssetListing {
+        asset: Some(partial.clone()),
+        config: asset_config(&env, &partial),
+    }));
+    assert_eq!(result, Err(Ok(AdminSimulationError::UnsupportedToken)));
+}
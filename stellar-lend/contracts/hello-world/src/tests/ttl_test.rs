@@ -0,0 +1,115 @@
+//! # Storage TTL Tests
+//!
+//! Tests that persistent entries are extended on access and by keeper bumps,
+//! and that the admin-set TTL policy is validated and applied.
+
+use crate::constants::{LEDGERS_PER_DAY, USER_DATA_BUMP_AMOUNT};
+use crate::deposit::DepositDataKey;
+use crate::ttl::{TtlConfig, TtlError};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{storage::Persistent as _, Address as _, Ledger},
+    Address, Env,
+};
+
+fn setup(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+fn advance_days(env: &Env, days: u32) {
+    env.ledger()
+        .with_mut(|li| li.sequence_number += days * LEDGERS_PER_DAY);
+}
+
+fn ttl(env: &Env, contract_id: &Address, key: &DepositDataKey) -> u32 {
+    env.as_contract(contract_id, || env.storage().persistent().get_ttl(key))
+}
+
+#[test]
+fn test_bump_position_extends_idle_entries() {
+    let env = Env::default();
+    let (contract_id, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+    let position = DepositDataKey::Position(user.clone());
+    let balance = DepositDataKey::CollateralBalance(user.clone());
+
+    advance_days(&env, 10);
+    assert_eq!(
+        ttl(&env, &contract_id, &position),
+        USER_DATA_BUMP_AMOUNT - 10 * LEDGERS_PER_DAY
+    );
+
+    client.bump_position(&user);
+    assert_eq!(ttl(&env, &contract_id, &position), USER_DATA_BUMP_AMOUNT);
+    assert_eq!(ttl(&env, &contract_id, &balance), USER_DATA_BUMP_AMOUNT);
+
+    // Users without stored entries are skipped
+    client.bump_position(&Address::generate(&env));
+}
+
+#[test]
+fn test_reads_extend_entries_past_threshold() {
+    let env = Env::default();
+    let (contract_id, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+    let analytics = DepositDataKey::UserAnalytics(user.clone());
+
+    // Still above the threshold: reads leave the TTL alone
+    advance_days(&env, 3);
+    client.get_user_report(&user);
+    assert_eq!(
+        ttl(&env, &contract_id, &analytics),
+        USER_DATA_BUMP_AMOUNT - 3 * LEDGERS_PER_DAY
+    );
+
+    advance_days(&env, 7);
+    client.get_user_report(&user);
+    assert_eq!(ttl(&env, &contract_id, &analytics), USER_DATA_BUMP_AMOUNT);
+}
+
+#[test]
+fn test_ttl_config_is_validated_and_applied() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    let config = TtlConfig {
+        threshold: 100 * LEDGERS_PER_DAY,
+        extend_to: 180 * LEDGERS_PER_DAY,
+    };
+
+    assert_eq!(
+        client.try_set_ttl_config(&user, &config),
+        Err(Ok(TtlError::Unauthorized))
+    );
+    let inverted = TtlConfig {
+        threshold: config.extend_to,
+        extend_to: config.threshold,
+    };
+    assert_eq!(
+        client.try_set_ttl_config(&admin, &inverted),
+        Err(Ok(TtlError::InvalidConfig))
+    );
+    let beyond_max = TtlConfig {
+        threshold: config.threshold,
+        extend_to: u32::MAX,
+    };
+    assert_eq!(
+        client.try_set_ttl_config(&admin, &beyond_max),
+        Err(Ok(TtlError::InvalidConfig))
+    );
+
+    client.set_ttl_config(&admin, &config);
+    assert_eq!(client.get_ttl_config(), config);
+    client.deposit_collateral(&user, &None, &1_000);
+    assert_eq!(
+        ttl(&env, &contract_id, &DepositDataKey::Position(user)),
+        config.extend_to
+    );
+}
@@ -0,0 +1,89 @@
+//! # Upgrade Tests
+//!
+//! Tests for schema versioning, admin-gated upgrades, and the storage
+//! migrations run between schema versions.
+
+use crate::risk_management::RiskDataKey;
+use crate::upgrade::{UpgradeError, CURRENT_VERSION};
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    Address, BytesN, Env, IntoVal, Symbol,
+};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+#[test]
+fn test_initialized_contract_is_current() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    assert_eq!(client.version(), CURRENT_VERSION);
+    assert_eq!(
+        client.try_migrate(&admin, &CURRENT_VERSION),
+        Err(Ok(UpgradeError::InvalidVersion))
+    );
+}
+
+#[test]
+fn test_migrate_moves_pre_tiering_config_to_instance() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+
+    // Layout written by releases before storage tiering and versioning
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        persistent.set(&RiskDataKey::Admin, &admin);
+        persistent.set(&RiskDataKey::EmergencyPause, &true);
+    });
+    assert_eq!(client.version(), 1);
+
+    assert_eq!(
+        client.try_migrate(&admin, &(CURRENT_VERSION + 1)),
+        Err(Ok(UpgradeError::InvalidVersion))
+    );
+    client.migrate(&admin, &2);
+
+    let events = env.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "contract_migrated_event"),).into_val(&env)
+    );
+    assert_eq!(client.version(), 2);
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        assert_eq!(instance.get(&RiskDataKey::Admin), Some(admin.clone()));
+        assert_eq!(instance.get(&RiskDataKey::EmergencyPause), Some(true));
+        let persistent = env.storage().persistent();
+        assert!(!persistent.has(&RiskDataKey::Admin));
+        assert!(!persistent.has(&RiskDataKey::EmergencyPause));
+    });
+}
+
+#[test]
+fn test_upgrade_and_migrate_require_admin() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+    let stranger = Address::generate(&env);
+
+    assert_eq!(
+        client.try_upgrade(&stranger, &BytesN::from_array(&env, &[7; 32])),
+        Err(Ok(UpgradeError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&stranger, &CURRENT_VERSION),
+        Err(Ok(UpgradeError::Unauthorized))
+    );
+}
@@ -0,0 +1,104 @@
+//! # Withdrawal Cooldown Tests
+//!
+//! Tests for the optional cooldown between borrowing and withdrawing collateral.
+//! Covers the default (disabled), enforcement, expiry and the repay exemption.
+
+use crate::{HelloContract, HelloContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+fn create_test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env
+}
+
+fn setup_contract_with_admin(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+fn borrower(env: &Env, client: &HelloContractClient<'_>) -> Address {
+    let user = Address::generate(env);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1_000);
+    user
+}
+
+#[test]
+fn test_cooldown_disabled_by_default() {
+    let env = create_test_env();
+    let (_, _, client) = setup_contract_with_admin(&env);
+    assert_eq!(client.get_withdraw_cooldown(), 0);
+
+    let user = borrower(&env, &client);
+    let remaining = client.withdraw_collateral(&user, &None, &1_000);
+    assert_eq!(remaining, 9_000);
+}
+
+#[test]
+#[should_panic(expected = "WithdrawCooldownActive")]
+fn test_withdraw_blocked_during_cooldown() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    client.set_withdraw_cooldown(&admin, &60);
+
+    let user = borrower(&env, &client);
+    env.ledger().with_mut(|li| li.timestamp += 59);
+    client.withdraw_collateral(&user, &None, &1_000);
+}
+
+#[test]
+fn test_withdraw_allowed_after_cooldown() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    client.set_withdraw_cooldown(&admin, &60);
+
+    let user = borrower(&env, &client);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let remaining = client.withdraw_collateral(&user, &None, &1_000);
+    assert_eq!(remaining, 9_000);
+}
+
+#[test]
+fn test_repay_then_withdraw_is_exempt() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    client.set_withdraw_cooldown(&admin, &60);
+
+    let user = borrower(&env, &client);
+    client.repay_debt(&user, &None, &1_000);
+    let remaining = client.withdraw_collateral(&user, &None, &10_000);
+    assert_eq!(remaining, 0);
+}
+
+#[test]
+fn test_depositor_without_debt_is_not_affected() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+    client.set_withdraw_cooldown(&admin, &60);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &5_000);
+    let remaining = client.withdraw_collateral(&user, &None, &5_000);
+    assert_eq!(remaining, 0);
+}
+
+#[test]
+fn test_set_cooldown_validation() {
+    let env = create_test_env();
+    let (_, admin, client) = setup_contract_with_admin(&env);
+
+    let other = Address::generate(&env);
+    assert!(client.try_set_withdraw_cooldown(&other, &60).is_err());
+    assert!(client.try_set_withdraw_cooldown(&admin, &86_401).is_err());
+
+    client.set_withdraw_cooldown(&admin, &86_400);
+    assert_eq!(client.get_withdraw_cooldown(), 86_400);
+}
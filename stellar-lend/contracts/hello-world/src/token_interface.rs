@@ -0,0 +1,68 @@
+//! # Token Interface Checks
+//!
+//! Probes a token contract with read-only cross-contract calls before it is
+//! listed as a cross-asset market, and summarizes which parts of the SEP-41
+//! token interface it answers as a capability bitmap.
+//!
+//! ## Required Capabilities
+//! - `decimals`, at most [`MAX_TOKEN_DECIMALS`]
+//! - `balance`, non-negative for the contract's own address
+//! - `allowance`, zero for a spender that was never approved; deposits,
+//!   repayments and pool supply pull funds with `transfer_from`, which relies
+//!   on approved allowances
+//!
+//! `name` and `symbol` are recorded when present but not required.
+//!
+//! Each probe uses a `try_` call, so a missing function, a panic in the token
+//! or an address without a contract clears the bit instead of aborting.
+
+use soroban_sdk::{token, Address, Env};
+
+/// Token answers `decimals` with a supported precision
+pub const TOKEN_CAP_DECIMALS: u32 = 1 << 0;
+/// Token answers `balance`
+pub const TOKEN_CAP_BALANCE: u32 = 1 << 1;
+/// Token answers `allowance`, backing `approve`/`transfer_from`
+pub const TOKEN_CAP_ALLOWANCE: u32 = 1 << 2;
+/// Token answers `name`
+pub const TOKEN_CAP_NAME: u32 = 1 << 3;
+/// Token answers `symbol`
+pub const TOKEN_CAP_SYMBOL: u32 = 1 << 4;
+
+/// Capabilities a token must have to be listed
+pub const REQUIRED_TOKEN_CAPABILITIES: u32 =
+    TOKEN_CAP_DECIMALS | TOKEN_CAP_BALANCE | TOKEN_CAP_ALLOWANCE;
+
+/// Highest token precision the protocol's fixed-point math supports
+pub const MAX_TOKEN_DECIMALS: u32 = 18;
+
+/// Probe `asset` and return its capability bitmap
+pub fn probe_token(env: &Env, asset: &Address) -> u32 {
+    let client = token::Client::new(env, asset);
+    let this = env.current_contract_address();
+    let mut capabilities = 0;
+
+    if matches!(client.try_decimals(), Ok(Ok(decimals)) if decimals <= MAX_TOKEN_DECIMALS) {
+        capabilities |= TOKEN_CAP_DECIMALS;
+    }
+    if matches!(client.try_balance(&this), Ok(Ok(balance)) if balance >= 0) {
+        capabilities |= TOKEN_CAP_BALANCE;
+    }
+    // The contract never approves itself, so a conforming token reports zero
+    if matches!(client.try_allowance(&this, &this), Ok(Ok(0))) {
+        capabilities |= TOKEN_CAP_ALLOWANCE;
+    }
+    if matches!(client.try_name(), Ok(Ok(_))) {
+        capabilities |= TOKEN_CAP_NAME;
+    }
+    if matches!(client.try_symbol(), Ok(Ok(_))) {
+        capabilities |= TOKEN_CAP_SYMBOL;
+    }
+
+    capabilities
+}
+
+/// Whether a capability bitmap includes every required capability
+pub fn has_required_capabilities(capabilities: u32) -> bool {
+    capabilities & REQUIRED_TOKEN_CAPABILITIES == REQUIRED_TOKEN_CAPABILITIES
+}
@@ -0,0 +1,109 @@
+//! # Storage TTL
+//!
+//! Keeps persistent entries from being archived while they are still in use.
+//!
+//! Every namespaced persistent entry has its TTL extended whenever it is read
+//! or written through the storage module: once fewer than `threshold` ledgers
+//! of life remain, it is extended to `extend_to` ledgers. The protocol admin
+//! can tune both with [`set_ttl_config`]; until then they default to
+//! extending to 90 days whenever fewer than 83 days remain.
+//!
+//! A position nobody touches would still expire, so [`bump_position`] lets
+//! anyone (typically a keeper) extend all of a user's entries without
+//! changing them.
+
+use soroban_sdk::{contracterror, contracttype, Address, Env, Symbol};
+
+use crate::analytics::AnalyticsDataKey;
+use crate::constants::{USER_DATA_BUMP_AMOUNT, USER_DATA_LIFETIME_THRESHOLD};
+use crate::credit::CreditDataKey;
+use crate::cross_asset::CrossAssetDataKey;
+use crate::deposit::DepositDataKey;
+use crate::events::{emit_admin_action, AdminActionEvent};
+use crate::rate_mode::RateModeDataKey;
+use crate::roles::require_protocol_admin;
+use crate::storage;
+
+/// Errors that can occur while managing storage TTLs
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TtlError {
+    /// Caller is not the protocol admin
+    Unauthorized = 1,
+    /// Threshold is zero or not below the extension, or the extension
+    /// exceeds the network's maximum TTL
+    InvalidConfig = 2,
+}
+
+/// Storage keys for TTL data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum TtlDataKey {
+    /// TTL extension policy for persistent entries: TtlConfig
+    Config,
+}
+
+/// When and how far persistent entries are extended, in ledgers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    /// Extend an entry once fewer than this many ledgers of life remain
+    pub threshold: u32,
+    /// Ledgers of life an extended entry is given
+    pub extend_to: u32,
+}
+
+/// Set the TTL extension policy for persistent entries (protocol admin only)
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+/// * `InvalidConfig` - `threshold` is zero or not below `extend_to`, or
+///   `extend_to` exceeds the network's maximum TTL
+pub fn set_ttl_config(env: &Env, caller: Address, config: TtlConfig) -> Result<(), TtlError> {
+    require_protocol_admin(env, &caller).map_err(|_| TtlError::Unauthorized)?;
+    if config.threshold == 0
+        || config.threshold >= config.extend_to
+        || config.extend_to > env.storage().max_ttl()
+    {
+        return Err(TtlError::InvalidConfig);
+    }
+    storage::set(env, &TtlDataKey::Config, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_ttl_config"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Get the TTL extension policy for persistent entries
+pub fn get_ttl_config(env: &Env) -> TtlConfig {
+    storage::get(env, &TtlDataKey::Config).unwrap_or(TtlConfig {
+        threshold: USER_DATA_LIFETIME_THRESHOLD,
+        extend_to: USER_DATA_BUMP_AMOUNT,
+    })
+}
+
+/// Extend the TTL of every stored entry belonging to `user` (anyone can call)
+///
+/// Covers the core position, collateral balance, locked stable rate,
+/// analytics, activity log and credit record, plus the shared cross-asset
+/// position map. Entries the user does not have are skipped.
+pub fn bump_position(env: &Env, user: &Address) {
+    storage::extend_ttl(env, &DepositDataKey::Position(user.clone()));
+    storage::extend_ttl(env, &DepositDataKey::CollateralBalance(user.clone()));
+    storage::extend_ttl(env, &DepositDataKey::UserAnalytics(user.clone()));
+    storage::extend_ttl(env, &DepositDataKey::LastBorrowTime(user.clone()));
+    storage::extend_ttl(env, &RateModeDataKey::Stable(user.clone()));
+    storage::extend_ttl(env, &AnalyticsDataKey::UserMetrics(user.clone()));
+    storage::extend_ttl(env, &AnalyticsDataKey::UserActivity(user.clone()));
+    storage::extend_ttl(env, &CreditDataKey::Record(user.clone()));
+    storage::extend_ttl(env, &CreditDataKey::Grants(user.clone()));
+    storage::extend_ttl(env, &CrossAssetDataKey::UserPositions);
+}
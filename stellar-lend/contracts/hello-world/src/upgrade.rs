@@ -0,0 +1,152 @@
+//! # Upgrades
+//!
+//! Replaces the contract's code in place and brings its storage up to the
+//! schema the new code expects, so parameters and positions survive upgrades.
+//!
+//! ## Procedure
+//! 1. The protocol admin uploads the new wasm and calls [`upgrade`] with its
+//!    hash. The contract address and all storage are kept.
+//! 2. The admin calls [`migrate`] with the new code's [`CURRENT_VERSION`],
+//!    which runs every migration step between the stored schema version and
+//!    that version, in order.
+//!
+//! Contracts initialized by this release start at [`CURRENT_VERSION`];
+//! contracts without a stored version predate versioning and are version 1.
+//!
+//! ## Schema Versions
+//! - **1**: global configuration may still be in persistent storage, where
+//!   releases before storage tiering wrote it.
+//! - **2**: that configuration is in instance storage.
+
+use soroban_sdk::{contracterror, contracttype, Address, BytesN, Env, IntoVal, Val};
+
+use crate::deposit::DepositDataKey;
+use crate::events::{
+    emit_contract_migrated, emit_contract_upgraded, ContractMigratedEvent, ContractUpgradedEvent,
+};
+use crate::flash_loan::FlashLoanDataKey;
+use crate::interest_rate::InterestRateDataKey;
+use crate::oracle::OracleDataKey;
+use crate::risk_management::RiskDataKey;
+use crate::roles::require_protocol_admin;
+use crate::storage;
+
+/// Storage schema version this code expects
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Schema version of contracts deployed before versioning
+const UNVERSIONED: u32 = 1;
+
+/// Errors that can occur during upgrades and migrations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum UpgradeError {
+    /// Caller is not the protocol admin
+    Unauthorized = 1,
+    /// Target version is not above the stored version, or is unknown to this code
+    InvalidVersion = 2,
+}
+
+/// Storage keys for upgrade data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum UpgradeDataKey {
+    /// Storage schema version: u32
+    Version,
+}
+
+/// Get the storage schema version
+pub fn version(env: &Env) -> u32 {
+    storage::get(env, &UpgradeDataKey::Version).unwrap_or(UNVERSIONED)
+}
+
+/// Record that a freshly initialized contract needs no migrations
+pub(crate) fn initialize_version(env: &Env) {
+    storage::set(env, &UpgradeDataKey::Version, &CURRENT_VERSION);
+}
+
+/// Replace the contract's code (protocol admin only)
+///
+/// Storage is untouched; call [`migrate`] afterwards if the new code expects
+/// a newer schema.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+pub fn upgrade(env: &Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), UpgradeError> {
+    require_protocol_admin(env, &caller).map_err(|_| UpgradeError::Unauthorized)?;
+
+    env.deployer()
+        .update_current_contract_wasm(new_wasm_hash.clone());
+
+    emit_contract_upgraded(
+        env,
+        ContractUpgradedEvent {
+            admin: caller,
+            wasm_hash: new_wasm_hash,
+            version: version(env),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Migrate storage to schema `target` (protocol admin only)
+///
+/// Runs each migration step after the stored version up to `target`.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the protocol admin
+/// * `InvalidVersion` - `target` is not above the stored version, or above
+///   [`CURRENT_VERSION`]
+pub fn migrate(env: &Env, caller: Address, target: u32) -> Result<(), UpgradeError> {
+    require_protocol_admin(env, &caller).map_err(|_| UpgradeError::Unauthorized)?;
+    let from_version = version(env);
+    if target <= from_version || target > CURRENT_VERSION {
+        return Err(UpgradeError::InvalidVersion);
+    }
+
+    for step in from_version + 1..=target {
+        match step {
+            2 => move_config_to_instance(env),
+            _ => return Err(UpgradeError::InvalidVersion),
+        }
+    }
+    storage::set(env, &UpgradeDataKey::Version, &target);
+
+    emit_contract_migrated(
+        env,
+        ContractMigratedEvent {
+            admin: caller,
+            from_version,
+            to_version: target,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Version 2: move global configuration written to persistent storage before
+/// storage tiering into instance storage. Reads already fall back to the
+/// persistent entries; this stops them from expiring.
+fn move_config_to_instance(env: &Env) {
+    move_to_instance(env, RiskDataKey::Admin);
+    move_to_instance(env, RiskDataKey::RiskConfig);
+    move_to_instance(env, RiskDataKey::EmergencyPause);
+    move_to_instance(env, InterestRateDataKey::Admin);
+    move_to_instance(env, InterestRateDataKey::InterestRateConfig);
+    move_to_instance(env, OracleDataKey::OracleConfig);
+    move_to_instance(env, OracleDataKey::PauseSwitches);
+    move_to_instance(env, FlashLoanDataKey::FlashLoanConfig);
+    move_to_instance(env, FlashLoanDataKey::PauseSwitches);
+    move_to_instance(env, DepositDataKey::Admin);
+    move_to_instance(env, DepositDataKey::PauseSwitches);
+}
+
+fn move_to_instance<K: IntoVal<Env, Val>>(env: &Env, key: K) {
+    let key: Val = key.into_val(env);
+    if let Some(value) = env.storage().persistent().get::<Val, Val>(&key) {
+        storage::set_instance(env, &key, &value);
+    }
+}
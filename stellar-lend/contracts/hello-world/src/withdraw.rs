@@ -15,12 +15,21 @@
 #![allow(unused)]
 use soroban_sdk::{contracterror, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
+use crate::analytics::{record_tvl, ActivityType};
+use crate::cash_ledger::{self, CashReason};
+use crate::constants::{BASIS_POINTS_SCALE, DEFAULT_COLLATERAL_FACTOR, MIN_COLLATERAL_RATIO};
 use crate::deposit::{
-    add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
-    emit_user_activity_tracked_event, update_protocol_analytics, update_user_analytics, Activity,
-    AssetParams, DepositDataKey, Position, ProtocolAnalytics, UserAnalytics,
+    add_activity_log, adjust_asset_totals, emit_analytics_updated_event,
+    emit_position_updated_event, emit_user_activity_tracked_event, get_core_asset_config,
+    update_protocol_analytics, update_user_analytics, DepositDataKey, Position, ProtocolAnalytics,
+    UserAnalytics,
 };
 use crate::events::{emit_withdrawal, WithdrawalEvent};
+use crate::loyalty::refresh_tier;
+use crate::reentrancy::{OperationGuard, Reentered};
+use crate::risk_management::{apply_recovery_haircut, get_withdraw_cooldown, is_emergency_paused};
+use crate::storage;
+use stellarlend_common::pause::{is_switch_set, PAUSE_WITHDRAW};
 
 /// Errors that can occur during withdraw operations
 #[contracterror]
@@ -43,11 +52,15 @@ pub enum WithdrawError {
     Reentrancy = 7,
     /// Position would become undercollateralized
     Undercollateralized = 8,
+    /// Collateral is locked by the post-borrow withdrawal cooldown
+    WithdrawCooldownActive = 9,
 }
 
-/// Minimum collateral ratio (in basis points, e.g., 15000 = 150%)
-/// This is the minimum ratio required: collateral_value / debt_value >= 1.5
-const MIN_COLLATERAL_RATIO_BPS: i128 = 15000; // 150%
+impl From<Reentered> for WithdrawError {
+    fn from(_: Reentered) -> Self {
+        WithdrawError::Reentrancy
+    }
+}
 
 /// Calculate collateral ratio
 /// Returns (collateral_value * collateral_factor) / (debt + interest)
@@ -66,10 +79,12 @@ fn calculate_collateral_ratio(
     // collateral_value = collateral * collateral_factor / 10000 (basis points)
     let collateral_value = collateral
         .checked_mul(collateral_factor)?
-        .checked_div(10000)?;
+        .checked_div(BASIS_POINTS_SCALE.get())?;
 
     // ratio = (collateral_value * 10000) / total_debt (in basis points)
-    collateral_value.checked_mul(10000)?.checked_div(total_debt)
+    collateral_value
+        .checked_mul(BASIS_POINTS_SCALE.get())?
+        .checked_div(total_debt)
 }
 
 /// Check if withdrawal would violate minimum collateral ratio
@@ -81,10 +96,7 @@ fn validate_collateral_ratio_after_withdraw(
 ) -> Result<(), WithdrawError> {
     // Get user position
     let position_key = DepositDataKey::Position(user.clone());
-    let position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
+    let position = storage::get::<DepositDataKey, Position>(env, &position_key)
         .ok_or(WithdrawError::InsufficientCollateral)?;
 
     // If no debt, withdrawal is always allowed (as long as sufficient collateral)
@@ -94,11 +106,8 @@ fn validate_collateral_ratio_after_withdraw(
 
     // Get current collateral balance
     let collateral_key = DepositDataKey::CollateralBalance(user.clone());
-    let current_collateral = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    let current_collateral =
+        storage::get::<DepositDataKey, i128>(env, &collateral_key).unwrap_or(0);
 
     // Calculate new collateral after withdrawal
     let new_collateral = current_collateral
@@ -107,20 +116,12 @@ fn validate_collateral_ratio_after_withdraw(
 
     // Get asset parameters for collateral factor
     // Default collateral factor if asset params not found
-    let collateral_factor = if let Some(asset_addr) = asset {
-        let asset_params_key = DepositDataKey::AssetParams(asset_addr.clone());
-        if let Some(params) = env
-            .storage()
-            .persistent()
-            .get::<DepositDataKey, AssetParams>(&asset_params_key)
-        {
-            params.collateral_factor
-        } else {
-            10000 // Default 100% if not configured
-        }
-    } else {
-        10000 // Default 100% for native XLM
-    };
+    let collateral_factor = asset
+        .and_then(|asset_addr| get_core_asset_config(env, asset_addr))
+        .map_or(DEFAULT_COLLATERAL_FACTOR.get(), |config| {
+            config.collateral_factor
+        });
+    let collateral_factor = apply_recovery_haircut(env, collateral_factor);
 
     // Calculate total debt (debt + accrued interest)
     let total_debt = position
@@ -135,7 +136,7 @@ fn validate_collateral_ratio_after_withdraw(
         position.borrow_interest,
         collateral_factor,
     ) {
-        if new_ratio < MIN_COLLATERAL_RATIO_BPS {
+        if new_ratio < MIN_COLLATERAL_RATIO.get() {
             return Err(WithdrawError::InsufficientCollateralRatio);
         }
     } else {
@@ -147,6 +148,35 @@ fn validate_collateral_ratio_after_withdraw(
     Ok(())
 }
 
+/// Reject withdrawals made too soon after a borrow
+///
+/// Only applies while the user still has outstanding debt, so a user who
+/// repays in full can withdraw immediately.
+fn check_withdraw_cooldown(env: &Env, user: &Address) -> Result<(), WithdrawError> {
+    let cooldown = get_withdraw_cooldown(env);
+    if cooldown == 0 {
+        return Ok(());
+    }
+
+    let has_debt =
+        storage::get::<DepositDataKey, Position>(env, &DepositDataKey::Position(user.clone()))
+            .map(|p| p.debt > 0 || p.borrow_interest > 0)
+            .unwrap_or(false);
+    if !has_debt {
+        return Ok(());
+    }
+
+    if let Some(last_borrow) =
+        storage::get::<DepositDataKey, u64>(env, &DepositDataKey::LastBorrowTime(user.clone()))
+    {
+        if env.ledger().timestamp() < last_borrow.saturating_add(cooldown) {
+            return Err(WithdrawError::WithdrawCooldownActive);
+        }
+    }
+
+    Ok(())
+}
+
 /// Withdraw collateral from the protocol
 ///
 /// Allows users to withdraw their deposited collateral, subject to:
@@ -167,9 +197,10 @@ fn validate_collateral_ratio_after_withdraw(
 /// * `WithdrawError::InvalidAmount` - If amount is zero or negative
 /// * `WithdrawError::InvalidAsset` - If asset address is invalid
 /// * `WithdrawError::InsufficientCollateral` - If user doesn't have enough collateral
-/// * `WithdrawError::WithdrawPaused` - If withdrawals are paused
+/// * `WithdrawError::WithdrawPaused` - If withdrawals or the protocol are paused
 /// * `WithdrawError::InsufficientCollateralRatio` - If withdrawal would violate minimum ratio
 /// * `WithdrawError::Overflow` - If calculation overflow occurs
+/// * `WithdrawError::WithdrawCooldownActive` - If the post-borrow cooldown has not elapsed
 ///
 /// # Security
 /// * Validates withdraw amount > 0
@@ -186,6 +217,8 @@ pub fn withdraw_collateral(
     asset: Option<Address>,
     amount: i128,
 ) -> Result<i128, WithdrawError> {
+    let _guard = OperationGuard::enter(env)?;
+
     // Validate amount
     if amount <= 0 {
         return Err(WithdrawError::InvalidAmount);
@@ -193,18 +226,19 @@ pub fn withdraw_collateral(
 
     // Check if withdrawals are paused
     let pause_switches_key = DepositDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Map<Symbol, bool>>(&pause_switches_key)
+    if let Some(pause_map) =
+        storage::get::<DepositDataKey, Map<Symbol, bool>>(env, &pause_switches_key)
     {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_withdraw")) {
-            if paused {
-                return Err(WithdrawError::WithdrawPaused);
-            }
+        if is_switch_set(env, &pause_map, PAUSE_WITHDRAW) {
+            return Err(WithdrawError::WithdrawPaused);
         }
     }
 
+    // Check emergency pause
+    if is_emergency_paused(env) {
+        return Err(WithdrawError::WithdrawPaused);
+    }
+
     // Get current timestamp
     let timestamp = env.ledger().timestamp();
 
@@ -218,17 +252,17 @@ pub fn withdraw_collateral(
 
     // Get current collateral balance
     let collateral_key = DepositDataKey::CollateralBalance(user.clone());
-    let current_collateral = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    let current_collateral =
+        storage::get::<DepositDataKey, i128>(env, &collateral_key).unwrap_or(0);
 
     // Check sufficient collateral
     if current_collateral < amount {
         return Err(WithdrawError::InsufficientCollateral);
     }
 
+    // Enforce the post-borrow cooldown
+    check_withdraw_cooldown(env, &user)?;
+
     // Validate collateral ratio after withdrawal
     validate_collateral_ratio_after_withdraw(env, &user, amount, asset.as_ref())?;
 
@@ -238,18 +272,13 @@ pub fn withdraw_collateral(
         .ok_or(WithdrawError::Overflow)?;
 
     // Update storage
-    env.storage()
-        .persistent()
-        .set(&collateral_key, &new_collateral);
+    storage::set(env, &collateral_key, &new_collateral);
 
     // Get or update user position
     let position_key = DepositDataKey::Position(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut position = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Position>(&position_key)
-        .unwrap_or_else(|| Position {
+    let mut position =
+        storage::get::<DepositDataKey, Position>(env, &position_key).unwrap_or_else(|| Position {
             collateral: 0,
             debt: 0,
             borrow_interest: 0,
@@ -259,7 +288,8 @@ pub fn withdraw_collateral(
     // Update position
     position.collateral = new_collateral;
     position.last_accrual_time = timestamp;
-    env.storage().persistent().set(&position_key, &position);
+    storage::set(env, &position_key, &position);
+    adjust_asset_totals(env, &asset, -amount, 0);
 
     // Handle asset transfer
     if let Some(ref asset_addr) = asset {
@@ -270,6 +300,7 @@ pub fn withdraw_collateral(
             &user,                           // to (user)
             &amount,
         );
+        cash_ledger::record_outflow(env, asset_addr, CashReason::Withdraw, amount);
     } else {
         // Native XLM withdrawal - in Soroban, native assets are handled differently
         // For now, we'll track it but actual XLM handling depends on Soroban's native asset support
@@ -286,10 +317,11 @@ pub fn withdraw_collateral(
     add_activity_log(
         env,
         &user,
-        Symbol::new(env, "withdraw"),
+        ActivityType::Withdraw,
         amount,
         asset.clone(),
         timestamp,
+        None,
     )
     .map_err(|e| match e {
         crate::deposit::DepositError::Overflow => WithdrawError::Overflow,
@@ -328,10 +360,7 @@ fn update_user_analytics_withdraw(
 ) -> Result<(), WithdrawError> {
     let analytics_key = DepositDataKey::UserAnalytics(user.clone());
     #[allow(clippy::unnecessary_lazy_evaluations)]
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, UserAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, UserAnalytics>(env, &analytics_key)
         .unwrap_or_else(|| UserAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -360,7 +389,7 @@ fn update_user_analytics_withdraw(
     if analytics.debt_value > 0 {
         analytics.collateralization_ratio = analytics
             .collateral_value
-            .checked_mul(10000)
+            .checked_mul(BASIS_POINTS_SCALE.get())
             .and_then(|v| v.checked_div(analytics.debt_value))
             .unwrap_or(0);
     } else {
@@ -369,18 +398,16 @@ fn update_user_analytics_withdraw(
 
     analytics.transaction_count = analytics.transaction_count.saturating_add(1);
     analytics.last_activity = timestamp;
+    refresh_tier(env, user, &mut analytics);
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
     Ok(())
 }
 
 /// Update protocol analytics after withdrawal
 fn update_protocol_analytics_withdraw(env: &Env, amount: i128) -> Result<(), WithdrawError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
-    let mut analytics = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, ProtocolAnalytics>(&analytics_key)
+    let mut analytics = storage::get::<DepositDataKey, ProtocolAnalytics>(env, &analytics_key)
         .unwrap_or(ProtocolAnalytics {
             total_deposits: 0,
             total_borrows: 0,
@@ -393,6 +420,7 @@ fn update_protocol_analytics_withdraw(env: &Env, amount: i128) -> Result<(), Wit
         .checked_sub(amount)
         .unwrap_or(0); // Don't error on underflow, just set to 0
 
-    env.storage().persistent().set(&analytics_key, &analytics);
+    storage::set(env, &analytics_key, &analytics);
+    record_tvl(env, analytics.total_value_locked);
     Ok(())
 }
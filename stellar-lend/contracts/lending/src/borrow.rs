@@ -9,8 +9,16 @@
 //!
 //! ## Collateral Requirements
 //! Minimum collateral ratio is 150% (15,000 basis points).
+//!
+//! ## Token Transfers
+//! Collateral is pulled from the borrower and the loan is paid out from the
+//! contract's balance of the borrowed asset.
 
 use soroban_sdk::{contracterror, contractevent, contracttype, Address, Env};
+use stellarlend_common::constants::{MIN_COLLATERAL_RATIO_BPS, SECONDS_PER_YEAR};
+use stellarlend_common::pause::{self, PAUSE_BORROW};
+
+use crate::token::{transfer_in, transfer_out};
 
 /// Errors that can occur during borrow operations.
 #[contracterror]
@@ -53,7 +61,8 @@ pub enum BorrowDataKey {
     CollateralRatio,
     /// Minimum borrow amount
     MinBorrowAmount,
-    /// Protocol pause flag
+    /// Protocol pause flag from before the shared pause map, read while the
+    /// borrow switch is unset
     Paused,
 }
 
@@ -81,6 +90,16 @@ pub struct CollateralPosition {
     pub asset: Address,
 }
 
+/// Event data emitted when the borrow settings are set.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BorrowSettingsUpdatedEvent {
+    /// Cap on total protocol debt
+    pub debt_ceiling: i128,
+    /// Smallest accepted borrow
+    pub min_borrow_amount: i128,
+}
+
 /// Event data emitted on each borrow operation.
 #[contractevent]
 #[derive(Clone, Debug)]
@@ -97,9 +116,7 @@ pub struct BorrowEvent {
     pub timestamp: u64,
 }
 
-const COLLATERAL_RATIO_MIN: i128 = 15000; // 150% in basis points
 const INTEREST_RATE_PER_YEAR: i128 = 500; // 5% in basis points
-const SECONDS_PER_YEAR: u64 = 31536000;
 
 /// Borrow assets against deposited collateral
 ///
@@ -114,6 +131,9 @@ const SECONDS_PER_YEAR: u64 = 31536000;
 /// # Returns
 /// Returns Ok(()) on success or BorrowError on failure
 ///
+/// The collateral is transferred from the user to the contract and the
+/// borrowed amount from the contract to the user.
+///
 /// # Security
 /// - Validates collateral ratio meets minimum requirements
 /// - Checks protocol is not paused
@@ -179,6 +199,9 @@ pub fn borrow(
     save_collateral_position(env, &user, &collateral_position);
     set_total_debt(env, new_total);
 
+    transfer_in(env, &collateral_asset, &user, collateral_amount);
+    transfer_out(env, &asset, &user, amount);
+
     emit_borrow_event(env, user, asset, amount, collateral_amount);
 
     Ok(())
@@ -191,7 +214,7 @@ fn validate_collateral_ratio(collateral: i128, borrow: i128) -> Result<(), Borro
     // Rearranged: collateral >= (borrow * 15000) / 10000
 
     let min_collateral = borrow
-        .checked_mul(COLLATERAL_RATIO_MIN)
+        .checked_mul(MIN_COLLATERAL_RATIO_BPS)
         .ok_or(BorrowError::Overflow)?
         .checked_div(10000)
         .ok_or(BorrowError::InvalidAmount)?;
@@ -281,13 +304,6 @@ fn get_min_borrow_amount(env: &Env) -> i128 {
         .unwrap_or(1000)
 }
 
-fn is_paused(env: &Env) -> bool {
-    env.storage()
-        .persistent()
-        .get(&BorrowDataKey::Paused)
-        .unwrap_or(false)
-}
-
 fn emit_borrow_event(env: &Env, user: Address, asset: Address, amount: i128, collateral: i128) {
     BorrowEvent {
         user,
@@ -311,20 +327,36 @@ pub fn initialize_borrow_settings(
     env.storage()
         .persistent()
         .set(&BorrowDataKey::MinBorrowAmount, &min_borrow_amount);
-    env.storage()
-        .persistent()
-        .set(&BorrowDataKey::Paused, &false);
+    set_paused(env, false)?;
+
+    BorrowSettingsUpdatedEvent {
+        debt_ceiling,
+        min_borrow_amount,
+    }
+    .publish(env);
     Ok(())
 }
 
 /// Set protocol pause state (admin only)
 pub fn set_paused(env: &Env, paused: bool) -> Result<(), BorrowError> {
-    env.storage()
-        .persistent()
-        .set(&BorrowDataKey::Paused, &paused);
+    pause::set_paused(env, PAUSE_BORROW, paused);
+    env.storage().persistent().remove(&BorrowDataKey::Paused);
     Ok(())
 }
 
+/// Whether borrowing is paused
+///
+/// The pause switch wins once set; until then, the persistent flag kept by
+/// deployments predating the shared pause map is honored.
+fn is_paused(env: &Env) -> bool {
+    pause::get_paused(env, PAUSE_BORROW).unwrap_or_else(|| {
+        env.storage()
+            .persistent()
+            .get(&BorrowDataKey::Paused)
+            .unwrap_or(false)
+    })
+}
+
 /// Get user's debt position
 pub fn get_user_debt(env: &Env, user: &Address) -> DebtPosition {
     let mut position = get_debt_position(env, user);
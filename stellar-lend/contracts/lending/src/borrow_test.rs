@@ -1,4 +1,5 @@
 use super::*;
+use crate::token_test::create_token;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
     Address, Env,
@@ -9,12 +10,12 @@ fn test_borrow_success() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
 
@@ -33,12 +34,12 @@ fn test_borrow_insufficient_collateral() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
 
@@ -51,12 +52,12 @@ fn test_borrow_protocol_paused() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
     client.set_paused(&true);
@@ -65,17 +66,47 @@ fn test_borrow_protocol_paused() {
     assert_eq!(result, Err(Ok(BorrowError::ProtocolPaused)));
 }
 
+#[test]
+fn test_legacy_borrow_pause_flag_is_honored() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
+    let client = LendingContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
+
+    client.initialize_borrow_settings(&1_000_000_000, &1000);
+
+    // A deployment paused before the shared pause map has only the old flag
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .remove(&stellarlend_common::pause::PauseDataKey::PauseSwitches);
+        env.storage()
+            .persistent()
+            .set(&crate::borrow::BorrowDataKey::Paused, &true);
+    });
+    let result = client.try_borrow(&user, &asset, &10_000, &collateral_asset, &20_000);
+    assert_eq!(result, Err(Ok(BorrowError::ProtocolPaused)));
+
+    client.set_paused(&false);
+    client.borrow(&user, &asset, &10_000, &collateral_asset, &20_000);
+}
+
 #[test]
 fn test_borrow_invalid_amount() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
 
@@ -91,12 +122,12 @@ fn test_borrow_below_minimum() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &5000);
 
@@ -109,12 +140,12 @@ fn test_borrow_debt_ceiling() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&50_000, &1000);
 
@@ -127,12 +158,12 @@ fn test_borrow_multiple_times() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
 
@@ -155,12 +186,12 @@ fn test_borrow_interest_accrual() {
         li.timestamp = 1000;
     });
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
     client.borrow(&user, &asset, &100_000, &collateral_asset, &200_000);
@@ -179,12 +210,12 @@ fn test_collateral_ratio_validation() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
 
@@ -202,12 +233,12 @@ fn test_pause_unpause() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&1_000_000_000, &1000);
 
@@ -224,12 +255,12 @@ fn test_overflow_protection() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&contract_id]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     client.initialize_borrow_settings(&i128::MAX, &1000);
 
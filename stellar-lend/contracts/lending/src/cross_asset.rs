@@ -1,4 +1,10 @@
-use soroban_sdk::{contracterror, contracttype, Address, Env, Symbol, Vec, Map};
+use soroban_sdk::{contracterror, contractevent, contracttype, Address, Env, Map};
+
+use stellarlend_common::constants::SECONDS_PER_YEAR;
+pub use stellarlend_common::interest::{BorrowIndex, BORROW_INDEX_ONE};
+
+use crate::oracle::{get_price, OracleError};
+use crate::token::{transfer_in, transfer_out};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -12,26 +18,58 @@ pub enum CrossAssetError {
     Unauthorized = 6,
     AssetNotSupported = 7,
     PriceUnavailable = 8,
+    PriceStale = 9,
+    AlreadyInitialized = 10,
+    NoPendingAdmin = 11,
+    InvalidRate = 12,
+    SupplyCapExceeded = 13,
+    BorrowCapExceeded = 14,
+    SameAssetBorrowCapExceeded = 15,
+}
+
+impl From<OracleError> for CrossAssetError {
+    fn from(err: OracleError) -> Self {
+        match err {
+            OracleError::PriceStale => CrossAssetError::PriceStale,
+            OracleError::Overflow => CrossAssetError::Overflow,
+            _ => CrossAssetError::PriceUnavailable,
+        }
+    }
 }
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct AssetParams {
-    pub ltv: i128,                // Loan to Value ratio (basis points)
+    pub ltv: i128,                   // Loan to Value ratio (basis points)
     pub liquidation_threshold: i128, // Liquidation threshold (basis points)
-    pub price_feed: Address,      // Oracle address for price
-    pub debt_ceiling: i128,       // Maximum debt allowed for this asset
+    pub price_feed: Address,         // Oracle address for price
+    pub debt_ceiling: i128,          // Maximum debt allowed for this asset
     pub is_active: bool,
+    /// Cap on the total collateral deposited (0 = unlimited)
+    pub supply_cap: i128,
+    /// Cap on the total debt, checked with `debt_ceiling` (0 = unlimited)
+    pub borrow_cap: i128,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct UserCrossPosition {
     pub collateral_balances: Map<Address, i128>,
+    /// Borrowed principal per asset
     pub debt_balances: Map<Address, i128>,
+    /// Unpaid interest per asset, settled up to `debt_indexes`
+    pub debt_interest: Map<Address, i128>,
+    /// Asset borrow index at which each debt was last settled
+    pub debt_indexes: Map<Address, i128>,
     pub last_update: u64,
 }
 
+/// Annual borrow rate of an asset without a configured rate (5%, as in `borrow`)
+pub const DEFAULT_BORROW_RATE_BPS: i128 = 500;
+
+/// Highest configurable annual borrow rate (1000%)
+pub const MAX_BORROW_RATE_BPS: i128 = 100_000;
+
 #[contracttype]
 #[derive(Clone)]
 pub enum CrossAssetDataKey {
@@ -39,8 +77,170 @@ pub enum CrossAssetDataKey {
     UserPosition(Address),
     TotalAssetDebt(Address),
     MinBorrowAmount,
-    Paused,
     Admin,
+    PendingAdmin,
+    BorrowRate(Address),
+    BorrowIndex(Address),
+    TotalCollateral(Address),
+    SameAssetBorrowCap(Address),
+}
+
+/// Emitted when the admin changes a market's parameters
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetParamsUpdatedEvent {
+    /// Admin that made the change
+    pub admin: Address,
+    /// Market asset
+    pub asset: Address,
+    /// Parameters before the change (`None` for a new market)
+    pub previous: Option<AssetParams>,
+    /// Parameters after the change
+    pub params: AssetParams,
+    /// Ledger timestamp of the change
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin changes a market's supply and borrow caps
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetCapsUpdatedEvent {
+    /// Admin that made the change
+    pub admin: Address,
+    /// Market asset
+    pub asset: Address,
+    /// New cap on total collateral (0 = unlimited)
+    pub supply_cap: i128,
+    /// New cap on total debt (0 = unlimited)
+    pub borrow_cap: i128,
+    /// Ledger timestamp of the change
+    pub timestamp: u64,
+}
+
+/// Emitted when an asset's borrow index is brought up to date
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InterestAccruedEvent {
+    /// Market asset
+    pub asset: Address,
+    /// Borrow index after accrual
+    pub index: i128,
+    /// Total debt of the asset after accrual
+    pub total_debt: i128,
+    /// Ledger timestamp of the accrual
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin nominates a successor
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminTransferProposedEvent {
+    /// Current admin
+    pub admin: Address,
+    /// Nominated admin, who must accept
+    pub pending_admin: Address,
+}
+
+/// Emitted when a nominated admin accepts the role
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminTransferredEvent {
+    /// Admin before the transfer
+    pub previous_admin: Address,
+    /// Admin after the transfer
+    pub new_admin: Address,
+}
+
+/// Emitted when the first admin is set
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminInitializedEvent {
+    /// The admin
+    pub admin: Address,
+}
+
+/// Emitted when the admin changes an asset's borrow rate
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BorrowRateUpdatedEvent {
+    /// Admin that made the change
+    pub admin: Address,
+    /// Market asset
+    pub asset: Address,
+    /// New annual borrow rate in basis points
+    pub rate_bps: i128,
+    /// Ledger timestamp of the change
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin sets or removes an asset's same-asset borrow cap
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SameAssetCapUpdatedEvent {
+    /// Admin that made the change
+    pub admin: Address,
+    /// Market asset
+    pub asset: Address,
+    /// New cap in basis points (`None` = removed)
+    pub cap_bps: Option<i128>,
+    /// Ledger timestamp of the change
+    pub timestamp: u64,
+}
+
+/// Emitted when a user deposits collateral into a cross-asset market
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CollateralDepositedEvent {
+    /// Depositor
+    pub user: Address,
+    /// Market asset
+    pub asset: Address,
+    /// Amount deposited
+    pub amount: i128,
+    /// Ledger timestamp of the deposit
+    pub timestamp: u64,
+}
+
+/// Emitted when a user withdraws collateral from a cross-asset market
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CollateralWithdrawnEvent {
+    /// Withdrawer
+    pub user: Address,
+    /// Market asset
+    pub asset: Address,
+    /// Amount withdrawn
+    pub amount: i128,
+    /// Ledger timestamp of the withdrawal
+    pub timestamp: u64,
+}
+
+/// Emitted when a user borrows from a cross-asset market
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetBorrowedEvent {
+    /// Borrower
+    pub user: Address,
+    /// Market asset
+    pub asset: Address,
+    /// Amount borrowed
+    pub amount: i128,
+    /// Ledger timestamp of the borrow
+    pub timestamp: u64,
+}
+
+/// Emitted when a user repays debt in a cross-asset market
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetRepaidEvent {
+    /// Repayer
+    pub user: Address,
+    /// Market asset
+    pub asset: Address,
+    /// Amount repaid, after capping at the outstanding debt
+    pub amount: i128,
+    /// Ledger timestamp of the repayment
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -56,11 +256,66 @@ pub fn set_asset_params(
     asset: Address,
     params: AssetParams,
 ) -> Result<(), CrossAssetError> {
-    check_admin(env)?;
-    env.storage().persistent().set(&CrossAssetDataKey::AssetParams(asset), &params);
+    let admin = check_admin(env)?;
+    let key = CrossAssetDataKey::AssetParams(asset.clone());
+    let previous = env.storage().persistent().get(&key);
+    env.storage().persistent().set(&key, &params);
+
+    AssetParamsUpdatedEvent {
+        admin,
+        asset,
+        previous,
+        params,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Set a market's supply and borrow caps (admin only)
+pub fn set_asset_caps(
+    env: &Env,
+    asset: Address,
+    supply_cap: i128,
+    borrow_cap: i128,
+) -> Result<(), CrossAssetError> {
+    let admin = check_admin(env)?;
+    if supply_cap < 0 || borrow_cap < 0 {
+        return Err(CrossAssetError::InvalidAmount);
+    }
+
+    let mut params = get_asset_params(env, &asset)?;
+    params.supply_cap = supply_cap;
+    params.borrow_cap = borrow_cap;
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::AssetParams(asset.clone()), &params);
+
+    AssetCapsUpdatedEvent {
+        admin,
+        asset,
+        supply_cap,
+        borrow_cap,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
     Ok(())
 }
 
+/// Total collateral deposited in an asset across all users
+pub fn get_total_collateral(env: &Env, asset: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::TotalCollateral(asset.clone()))
+        .unwrap_or(0)
+}
+
+fn set_total_collateral(env: &Env, asset: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::TotalCollateral(asset.clone()), &amount);
+}
+
 pub fn deposit_collateral_asset(
     env: &Env,
     user: Address,
@@ -71,21 +326,39 @@ pub fn deposit_collateral_asset(
     if amount <= 0 {
         return Err(CrossAssetError::InvalidAmount);
     }
-    
+
     let params = get_asset_params(env, &asset)?;
     if !params.is_active {
         return Err(CrossAssetError::AssetNotSupported);
     }
 
+    let total_collateral = get_total_collateral(env, &asset)
+        .checked_add(amount)
+        .ok_or(CrossAssetError::Overflow)?;
+    if params.supply_cap > 0 && total_collateral > params.supply_cap {
+        return Err(CrossAssetError::SupplyCapExceeded);
+    }
+
     let mut position = get_user_position(env, &user);
     let current_balance = position.collateral_balances.get(asset.clone()).unwrap_or(0);
-    position.collateral_balances.set(asset, current_balance.checked_add(amount).ok_or(CrossAssetError::Overflow)?);
-    
+    position.collateral_balances.set(
+        asset.clone(),
+        current_balance
+            .checked_add(amount)
+            .ok_or(CrossAssetError::Overflow)?,
+    );
+
     save_user_position(env, &user, &position);
-    
-    // In a real implementation, we would transfer tokens from user to contract here
-    // env.invoke_contract(...)
-    
+    set_total_collateral(env, &asset, total_collateral);
+    transfer_in(env, &asset, &user, amount);
+
+    CollateralDepositedEvent {
+        user,
+        asset,
+        amount,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
     Ok(())
 }
 
@@ -105,31 +378,53 @@ pub fn borrow_asset(
         return Err(CrossAssetError::AssetNotSupported);
     }
 
+    let index = accrue_interest(env, &asset)?;
     let total_debt = get_total_asset_debt(env, &asset);
-    if total_debt.checked_add(amount).ok_or(CrossAssetError::Overflow)? > params.debt_ceiling {
+    let new_total_debt = total_debt
+        .checked_add(amount)
+        .ok_or(CrossAssetError::Overflow)?;
+    if new_total_debt > params.debt_ceiling {
         return Err(CrossAssetError::DebtCeilingReached);
     }
+    if params.borrow_cap > 0 && new_total_debt > params.borrow_cap {
+        return Err(CrossAssetError::BorrowCapExceeded);
+    }
 
     let mut position = get_user_position(env, &user);
-    
+    settle_debt(&mut position, &asset, index)?;
+
+    let current_debt = position.debt_balances.get(asset.clone()).unwrap_or(0);
+    position.debt_balances.set(
+        asset.clone(),
+        current_debt
+            .checked_add(amount)
+            .ok_or(CrossAssetError::Overflow)?,
+    );
+
+    check_same_asset_borrow(env, &position, &asset)?;
+
     // Calculate new position health
-    let mut debt_balances = position.debt_balances.clone();
-    let current_debt = debt_balances.get(asset.clone()).unwrap_or(0);
-    debt_balances.set(asset.clone(), current_debt.checked_add(amount).ok_or(CrossAssetError::Overflow)?);
-    
+    let debt_balances = current_debt_balances(env, &position)?;
     let summary = calculate_position_summary(env, &position.collateral_balances, &debt_balances)?;
-    
+
     // Health factor must be > 1.0 (10000) after borrowing
     if summary.health_factor < 10000 {
         return Err(CrossAssetError::InsufficientCollateral);
     }
 
-    position.debt_balances = debt_balances;
     position.last_update = env.ledger().timestamp();
-    
+
     save_user_position(env, &user, &position);
-    set_total_asset_debt(env, &asset, total_debt.checked_add(amount).ok_or(CrossAssetError::Overflow)?);
+    set_total_asset_debt(env, &asset, new_total_debt);
+    transfer_out(env, &asset, &user, amount);
 
+    AssetBorrowedEvent {
+        user,
+        asset,
+        amount,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
     Ok(())
 }
 
@@ -144,18 +439,53 @@ pub fn repay_asset(
         return Err(CrossAssetError::InvalidAmount);
     }
 
+    let index = accrue_interest(env, &asset)?;
     let mut position = get_user_position(env, &user);
-    let current_debt = position.debt_balances.get(asset.clone()).unwrap_or(0);
-    
-    let repay_amount = if amount > current_debt { current_debt } else { amount };
-    
-    position.debt_balances.set(asset.clone(), current_debt.checked_sub(repay_amount).ok_or(CrossAssetError::Overflow)?);
-    
+    settle_debt(&mut position, &asset, index)?;
+
+    let principal = position.debt_balances.get(asset.clone()).unwrap_or(0);
+    let interest = position.debt_interest.get(asset.clone()).unwrap_or(0);
+    let current_debt = principal
+        .checked_add(interest)
+        .ok_or(CrossAssetError::Overflow)?;
+
+    let repay_amount = if amount > current_debt {
+        current_debt
+    } else {
+        amount
+    };
+
+    // Accrued interest is paid before principal
+    let interest_paid = repay_amount.min(interest);
+    position
+        .debt_interest
+        .set(asset.clone(), interest - interest_paid);
+    position.debt_balances.set(
+        asset.clone(),
+        principal
+            .checked_sub(repay_amount - interest_paid)
+            .ok_or(CrossAssetError::Overflow)?,
+    );
+    position.last_update = env.ledger().timestamp();
+
     save_user_position(env, &user, &position);
-    
+
+    // Rounding in per-user settlement can leave the total slightly below the
+    // sum of user debts; never let it go negative
     let total_debt = get_total_asset_debt(env, &asset);
-    set_total_asset_debt(env, &asset, total_debt.checked_sub(repay_amount).ok_or(CrossAssetError::Overflow)?);
+    set_total_asset_debt(env, &asset, (total_debt - repay_amount).max(0));
 
+    if repay_amount > 0 {
+        transfer_in(env, &asset, &user, repay_amount);
+    }
+
+    AssetRepaidEvent {
+        user,
+        asset,
+        amount: repay_amount,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
     Ok(())
 }
 
@@ -172,16 +502,22 @@ pub fn withdraw_asset(
 
     let mut position = get_user_position(env, &user);
     let current_balance = position.collateral_balances.get(asset.clone()).unwrap_or(0);
-    
+
     if amount > current_balance {
         return Err(CrossAssetError::InvalidAmount);
     }
 
     let mut collateral_balances = position.collateral_balances.clone();
-    collateral_balances.set(asset.clone(), current_balance.checked_sub(amount).ok_or(CrossAssetError::Overflow)?);
-    
-    let summary = calculate_position_summary(env, &collateral_balances, &position.debt_balances)?;
-    
+    collateral_balances.set(
+        asset.clone(),
+        current_balance
+            .checked_sub(amount)
+            .ok_or(CrossAssetError::Overflow)?,
+    );
+
+    let debt_balances = current_debt_balances(env, &position)?;
+    let summary = calculate_position_summary(env, &collateral_balances, &debt_balances)?;
+
     // Only allow withdrawal if health factor remains healthy
     if summary.total_debt_usd > 0 && summary.health_factor < 10000 {
         return Err(CrossAssetError::InsufficientCollateral);
@@ -189,91 +525,591 @@ pub fn withdraw_asset(
 
     position.collateral_balances = collateral_balances;
     save_user_position(env, &user, &position);
+    let total_collateral = get_total_collateral(env, &asset);
+    set_total_collateral(env, &asset, (total_collateral - amount).max(0));
+    transfer_out(env, &asset, &user, amount);
 
+    CollateralWithdrawnEvent {
+        user,
+        asset,
+        amount,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
     Ok(())
 }
 
-pub fn get_cross_position_summary(env: &Env, user: Address) -> Result<PositionSummary, CrossAssetError> {
+pub fn get_cross_position_summary(
+    env: &Env,
+    user: Address,
+) -> Result<PositionSummary, CrossAssetError> {
     let position = get_user_position(env, &user);
-    calculate_position_summary(env, &position.collateral_balances, &position.debt_balances)
+    let debt_balances = current_debt_balances(env, &position)?;
+    calculate_position_summary(env, &position.collateral_balances, &debt_balances)
+}
+
+/// A user's health factor (scaled by 10000) including interest accrued up to
+/// now
+pub fn get_health_factor(env: &Env, user: Address) -> Result<i128, CrossAssetError> {
+    Ok(get_cross_position_summary(env, user)?.health_factor)
+}
+
+/// The most of `asset` a user can borrow now without their health factor
+/// falling below 1.0, or exceeding the asset's debt ceiling, borrow cap or
+/// same-asset borrow cap.
+///
+/// Rounded down, so borrowing the returned amount passes the borrow checks.
+pub fn get_max_borrowable(
+    env: &Env,
+    user: Address,
+    asset: Address,
+) -> Result<i128, CrossAssetError> {
+    let params = get_asset_params(env, &asset)?;
+    if !params.is_active {
+        return Ok(0);
+    }
+
+    let position = get_user_position(env, &user);
+    let debt_balances = current_debt_balances(env, &position)?;
+    let values = calculate_position_values(env, &position.collateral_balances, &debt_balances)?;
+    let headroom_usd = values
+        .weighted_collateral_usd
+        .checked_sub(values.debt_usd)
+        .ok_or(CrossAssetError::Overflow)?;
+    if headroom_usd <= 0 {
+        return Ok(0);
+    }
+    let price = get_price(env, &asset)?;
+    if price <= 0 {
+        return Err(CrossAssetError::PriceUnavailable);
+    }
+    let mut max_amount = mul_div(headroom_usd, 10000000, price)?;
+
+    let stored = get_stored_borrow_index(env, &asset);
+    let index = projected_borrow_index(env, &asset, &stored)?.index;
+    let total_debt = mul_div(get_total_asset_debt(env, &asset), index, stored.index)?;
+    max_amount = max_amount.min(params.debt_ceiling.saturating_sub(total_debt));
+    if params.borrow_cap > 0 {
+        max_amount = max_amount.min(params.borrow_cap.saturating_sub(total_debt));
+    }
+    if let Some(limit) = same_asset_debt_limit(env, &position, &asset)? {
+        let debt = debt_balances.get(asset.clone()).unwrap_or(0);
+        max_amount = max_amount.min(limit.saturating_sub(debt));
+    }
+    Ok(max_amount.max(0))
+}
+
+/// The most of `asset` a user can withdraw now without their health factor
+/// falling below 1.0
+pub fn get_max_withdrawable(
+    env: &Env,
+    user: Address,
+    asset: Address,
+) -> Result<i128, CrossAssetError> {
+    let position = get_user_position(env, &user);
+    let balance = position.collateral_balances.get(asset.clone()).unwrap_or(0);
+    if balance <= 0 {
+        return Ok(0);
+    }
+
+    let debt_balances = current_debt_balances(env, &position)?;
+    let values = calculate_position_values(env, &position.collateral_balances, &debt_balances)?;
+    if values.debt_usd == 0 {
+        return Ok(balance);
+    }
+
+    // Weighted collateral the asset must keep backing once the rest of the
+    // position is counted
+    let params = get_asset_params(env, &asset)?;
+    let price = get_price(env, &asset)?;
+    let weighted_usd = weighted_value(usd_value(balance, price)?, params.ltv)?;
+    let required_usd = values
+        .debt_usd
+        .checked_sub(values.weighted_collateral_usd - weighted_usd)
+        .ok_or(CrossAssetError::Overflow)?;
+    if required_usd <= 0 {
+        return Ok(balance);
+    }
+    if params.ltv <= 0 || price <= 0 {
+        return Ok(0);
+    }
+
+    // Smallest balance whose rounded-down weighted value covers the requirement
+    let required_value_usd = div_ceil(
+        required_usd
+            .checked_mul(10000)
+            .ok_or(CrossAssetError::Overflow)?,
+        params.ltv,
+    )?;
+    let required_balance = div_ceil(
+        required_value_usd
+            .checked_mul(10000000)
+            .ok_or(CrossAssetError::Overflow)?,
+        price,
+    )?;
+    Ok((balance - required_balance).max(0))
+}
+
+/// Bring an asset's borrow index up to date and grow its total debt by the
+/// interest accrued since the last update.
+///
+/// Called on every borrow and repayment of the asset; anyone may also call it
+/// as a keeper.
+///
+/// # Returns
+/// The current borrow index.
+pub fn accrue_interest(env: &Env, asset: &Address) -> Result<i128, CrossAssetError> {
+    let previous = get_stored_borrow_index(env, asset);
+    let current = projected_borrow_index(env, asset, &previous)?;
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::BorrowIndex(asset.clone()), &current);
+    if current.index == previous.index {
+        return Ok(current.index);
+    }
+
+    let total_debt = mul_div(
+        get_total_asset_debt(env, asset),
+        current.index,
+        previous.index,
+    )?;
+    set_total_asset_debt(env, asset, total_debt);
+
+    InterestAccruedEvent {
+        asset: asset.clone(),
+        index: current.index,
+        total_debt,
+        timestamp: current.last_update,
+    }
+    .publish(env);
+    Ok(current.index)
+}
+
+/// Set an asset's annual borrow rate in basis points (admin only).
+///
+/// Interest up to now accrues at the previous rate.
+pub fn set_borrow_rate(env: &Env, asset: Address, rate_bps: i128) -> Result<(), CrossAssetError> {
+    let admin = check_admin(env)?;
+    if !(0..=MAX_BORROW_RATE_BPS).contains(&rate_bps) {
+        return Err(CrossAssetError::InvalidRate);
+    }
+    accrue_interest(env, &asset)?;
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::BorrowRate(asset.clone()), &rate_bps);
+
+    BorrowRateUpdatedEvent {
+        admin,
+        asset,
+        rate_bps,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Get an asset's annual borrow rate in basis points
+pub fn get_borrow_rate(env: &Env, asset: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::BorrowRate(asset.clone()))
+        .unwrap_or(DEFAULT_BORROW_RATE_BPS)
+}
+
+/// Cap borrowing an asset against itself (admin only).
+///
+/// Applies to users whose only collateral is `asset`: their debt in `asset`,
+/// including interest, may not exceed `cap_bps` of that collateral. This
+/// bounds recursive deposit-and-borrow loops that only farm incentives; a cap
+/// of 0 blocks same-asset borrowing entirely and `None` removes the cap.
+pub fn set_same_asset_borrow_cap(
+    env: &Env,
+    asset: Address,
+    cap_bps: Option<i128>,
+) -> Result<(), CrossAssetError> {
+    let admin = check_admin(env)?;
+    let key = CrossAssetDataKey::SameAssetBorrowCap(asset.clone());
+    match cap_bps {
+        Some(cap_bps) if !(0..=10000).contains(&cap_bps) => {
+            return Err(CrossAssetError::InvalidAmount)
+        }
+        Some(cap_bps) => env.storage().persistent().set(&key, &cap_bps),
+        None => env.storage().persistent().remove(&key),
+    }
+
+    SameAssetCapUpdatedEvent {
+        admin,
+        asset,
+        cap_bps,
+        timestamp: env.ledger().timestamp(),
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Get an asset's same-asset borrow cap in basis points, if set
+pub fn get_same_asset_borrow_cap(env: &Env, asset: &Address) -> Option<i128> {
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::SameAssetBorrowCap(asset.clone()))
+}
+
+/// Get an asset's borrow index as of now, without storing it
+pub fn get_borrow_index(env: &Env, asset: &Address) -> Result<i128, CrossAssetError> {
+    let stored = get_stored_borrow_index(env, asset);
+    Ok(projected_borrow_index(env, asset, &stored)?.index)
 }
 
 // Internal helpers
 
-fn check_admin(env: &Env) -> Result<(), CrossAssetError> {
-    let admin: Address = env.storage().persistent().get(&CrossAssetDataKey::Admin).ok_or(CrossAssetError::Unauthorized)?;
+/// Require the stored admin's authorization and return the admin
+pub(crate) fn check_admin(env: &Env) -> Result<Address, CrossAssetError> {
+    let admin: Address = env
+        .storage()
+        .persistent()
+        .get(&CrossAssetDataKey::Admin)
+        .ok_or(CrossAssetError::Unauthorized)?;
     admin.require_auth();
-    Ok(())
+    Ok(admin)
 }
 
-fn get_asset_params(env: &Env, asset: &Address) -> Result<AssetParams, CrossAssetError> {
-    env.storage().persistent().get(&CrossAssetDataKey::AssetParams(asset.clone())).ok_or(CrossAssetError::AssetNotSupported)
+pub(crate) fn get_asset_params(env: &Env, asset: &Address) -> Result<AssetParams, CrossAssetError> {
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::AssetParams(asset.clone()))
+        .ok_or(CrossAssetError::AssetNotSupported)
 }
 
 fn get_user_position(env: &Env, user: &Address) -> UserCrossPosition {
-    env.storage().persistent().get(&CrossAssetDataKey::UserPosition(user.clone())).unwrap_or(UserCrossPosition {
-        collateral_balances: Map::new(env),
-        debt_balances: Map::new(env),
-        last_update: env.ledger().timestamp(),
-    })
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::UserPosition(user.clone()))
+        .unwrap_or(UserCrossPosition {
+            collateral_balances: Map::new(env),
+            debt_balances: Map::new(env),
+            debt_interest: Map::new(env),
+            debt_indexes: Map::new(env),
+            last_update: env.ledger().timestamp(),
+        })
 }
 
 fn save_user_position(env: &Env, user: &Address, position: &UserCrossPosition) {
-    env.storage().persistent().set(&CrossAssetDataKey::UserPosition(user.clone()), position);
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::UserPosition(user.clone()), position);
 }
 
 fn get_total_asset_debt(env: &Env, asset: &Address) -> i128 {
-    env.storage().persistent().get(&CrossAssetDataKey::TotalAssetDebt(asset.clone())).unwrap_or(0)
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::TotalAssetDebt(asset.clone()))
+        .unwrap_or(0)
 }
 
 fn set_total_asset_debt(env: &Env, asset: &Address, amount: i128) {
-    env.storage().persistent().set(&CrossAssetDataKey::TotalAssetDebt(asset.clone()), &amount);
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::TotalAssetDebt(asset.clone()), &amount);
 }
 
-fn calculate_position_summary(
+fn get_stored_borrow_index(env: &Env, asset: &Address) -> BorrowIndex {
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::BorrowIndex(asset.clone()))
+        .unwrap_or(BorrowIndex {
+            index: BORROW_INDEX_ONE,
+            last_update: env.ledger().timestamp(),
+        })
+}
+
+/// Compound `stored` up to the current ledger time at the asset's rate
+fn projected_borrow_index(
+    env: &Env,
+    asset: &Address,
+    stored: &BorrowIndex,
+) -> Result<BorrowIndex, CrossAssetError> {
+    let now = env.ledger().timestamp();
+    let elapsed = now.saturating_sub(stored.last_update);
+    let growth = mul_div(
+        stored.index,
+        get_borrow_rate(env, asset)
+            .checked_mul(elapsed as i128)
+            .ok_or(CrossAssetError::Overflow)?,
+        10000 * SECONDS_PER_YEAR as i128,
+    )?;
+    Ok(BorrowIndex {
+        index: stored
+            .index
+            .checked_add(growth)
+            .ok_or(CrossAssetError::Overflow)?,
+        last_update: now,
+    })
+}
+
+/// Move interest accrued on a user's debt in `asset` up to `index` into
+/// `debt_interest`
+fn settle_debt(
+    position: &mut UserCrossPosition,
+    asset: &Address,
+    index: i128,
+) -> Result<(), CrossAssetError> {
+    let owed = owed_at(position, asset, index)?;
+    let principal = position.debt_balances.get(asset.clone()).unwrap_or(0);
+    position.debt_interest.set(
+        asset.clone(),
+        owed.checked_sub(principal)
+            .ok_or(CrossAssetError::Overflow)?,
+    );
+    position.debt_indexes.set(asset.clone(), index);
+    Ok(())
+}
+
+/// Highest debt a position may owe in `asset` under its same-asset borrow
+/// cap, or `None` if the cap does not apply (no cap, or other collateral)
+fn same_asset_debt_limit(
+    env: &Env,
+    position: &UserCrossPosition,
+    asset: &Address,
+) -> Result<Option<i128>, CrossAssetError> {
+    let Some(cap_bps) = get_same_asset_borrow_cap(env, asset) else {
+        return Ok(None);
+    };
+    let sole_collateral = position
+        .collateral_balances
+        .iter()
+        .all(|(collateral, amount)| collateral == *asset || amount == 0);
+    let collateral = position.collateral_balances.get(asset.clone()).unwrap_or(0);
+    if !sole_collateral || collateral == 0 {
+        return Ok(None);
+    }
+    Ok(Some(mul_div(collateral, cap_bps, 10000)?))
+}
+
+/// Enforce the same-asset borrow cap on a position that has just borrowed
+/// `asset` and whose debt in it is settled
+fn check_same_asset_borrow(
+    env: &Env,
+    position: &UserCrossPosition,
+    asset: &Address,
+) -> Result<(), CrossAssetError> {
+    let Some(limit) = same_asset_debt_limit(env, position, asset)? else {
+        return Ok(());
+    };
+    let debt = position
+        .debt_balances
+        .get(asset.clone())
+        .unwrap_or(0)
+        .checked_add(position.debt_interest.get(asset.clone()).unwrap_or(0))
+        .ok_or(CrossAssetError::Overflow)?;
+    if debt > limit {
+        return Err(CrossAssetError::SameAssetBorrowCapExceeded);
+    }
+    Ok(())
+}
+
+/// Principal plus interest a user owes in `asset` at borrow index `index`
+fn owed_at(
+    position: &UserCrossPosition,
+    asset: &Address,
+    index: i128,
+) -> Result<i128, CrossAssetError> {
+    let settled = position
+        .debt_balances
+        .get(asset.clone())
+        .unwrap_or(0)
+        .checked_add(position.debt_interest.get(asset.clone()).unwrap_or(0))
+        .ok_or(CrossAssetError::Overflow)?;
+    match position.debt_indexes.get(asset.clone()) {
+        Some(settled_index) if settled_index > 0 && settled_index != index => {
+            mul_div(settled, index, settled_index)
+        }
+        _ => Ok(settled),
+    }
+}
+
+/// Each asset's debt including interest accrued up to now
+fn current_debt_balances(
+    env: &Env,
+    position: &UserCrossPosition,
+) -> Result<Map<Address, i128>, CrossAssetError> {
+    let mut balances = Map::new(env);
+    for asset in position.debt_balances.keys().iter() {
+        let index = get_borrow_index(env, &asset)?;
+        balances.set(asset.clone(), owed_at(position, &asset, index)?);
+    }
+    Ok(balances)
+}
+
+fn mul_div(value: i128, mul: i128, div: i128) -> Result<i128, CrossAssetError> {
+    value
+        .checked_mul(mul)
+        .ok_or(CrossAssetError::Overflow)?
+        .checked_div(div)
+        .ok_or(CrossAssetError::Overflow)
+}
+
+/// `value / div` rounded up, for positive `div`
+fn div_ceil(value: i128, div: i128) -> Result<i128, CrossAssetError> {
+    value
+        .checked_add(div - 1)
+        .ok_or(CrossAssetError::Overflow)?
+        .checked_div(div)
+        .ok_or(CrossAssetError::Overflow)
+}
+
+/// Base currency value (7 decimals) of `amount` at `price`
+fn usd_value(amount: i128, price: i128) -> Result<i128, CrossAssetError> {
+    amount
+        .checked_mul(price)
+        .ok_or(CrossAssetError::Overflow)?
+        .checked_div(10000000)
+        .ok_or(CrossAssetError::Overflow)
+}
+
+/// Part of a collateral value that counts toward borrowing, at `ltv` basis points
+fn weighted_value(value_usd: i128, ltv: i128) -> Result<i128, CrossAssetError> {
+    value_usd
+        .checked_mul(ltv)
+        .ok_or(CrossAssetError::Overflow)?
+        .checked_div(10000)
+        .ok_or(CrossAssetError::Overflow)
+}
+
+/// Position totals in the base currency
+struct PositionValues {
+    collateral_usd: i128,
+    /// Collateral weighted by each asset's LTV
+    weighted_collateral_usd: i128,
+    debt_usd: i128,
+}
+
+fn calculate_position_values(
     env: &Env,
     collateral_balances: &Map<Address, i128>,
     debt_balances: &Map<Address, i128>,
-) -> Result<PositionSummary, CrossAssetError> {
-    let mut total_collateral_usd = 0i128;
-    let mut total_weighted_collateral_usd = 0i128;
-    let mut total_debt_usd = 0i128;
+) -> Result<PositionValues, CrossAssetError> {
+    let mut values = PositionValues {
+        collateral_usd: 0,
+        weighted_collateral_usd: 0,
+        debt_usd: 0,
+    };
 
     for (asset, amount) in collateral_balances.iter() {
         let params = get_asset_params(env, &asset)?;
-        let price = get_price(env, &params.price_feed)?;
-        let value_usd = amount.checked_mul(price).ok_or(CrossAssetError::Overflow)?.checked_div(10000000).ok_or(CrossAssetError::Overflow)?;
-        total_collateral_usd = total_collateral_usd.checked_add(value_usd).ok_or(CrossAssetError::Overflow)?;
-        
-        let weighted_value = value_usd.checked_mul(params.ltv).ok_or(CrossAssetError::Overflow)?.checked_div(10000).ok_or(CrossAssetError::Overflow)?;
-        total_weighted_collateral_usd = total_weighted_collateral_usd.checked_add(weighted_value).ok_or(CrossAssetError::Overflow)?;
+        let value_usd = usd_value(amount, get_price(env, &asset)?)?;
+        values.collateral_usd = values
+            .collateral_usd
+            .checked_add(value_usd)
+            .ok_or(CrossAssetError::Overflow)?;
+        values.weighted_collateral_usd = values
+            .weighted_collateral_usd
+            .checked_add(weighted_value(value_usd, params.ltv)?)
+            .ok_or(CrossAssetError::Overflow)?;
     }
 
     for (asset, amount) in debt_balances.iter() {
-        let params = get_asset_params(env, &asset)?;
-        let price = get_price(env, &params.price_feed)?;
-        let value_usd = amount.checked_mul(price).ok_or(CrossAssetError::Overflow)?.checked_div(10000000).ok_or(CrossAssetError::Overflow)?;
-        total_debt_usd = total_debt_usd.checked_add(value_usd).ok_or(CrossAssetError::Overflow)?;
+        let value_usd = usd_value(amount, get_price(env, &asset)?)?;
+        values.debt_usd = values
+            .debt_usd
+            .checked_add(value_usd)
+            .ok_or(CrossAssetError::Overflow)?;
     }
 
-    let health_factor = if total_debt_usd == 0 {
+    Ok(values)
+}
+
+fn calculate_position_summary(
+    env: &Env,
+    collateral_balances: &Map<Address, i128>,
+    debt_balances: &Map<Address, i128>,
+) -> Result<PositionSummary, CrossAssetError> {
+    let values = calculate_position_values(env, collateral_balances, debt_balances)?;
+
+    let health_factor = if values.debt_usd == 0 {
         1000000 // Very large number if no debt
     } else {
-        total_weighted_collateral_usd.checked_mul(10000).ok_or(CrossAssetError::Overflow)?.checked_div(total_debt_usd).ok_or(CrossAssetError::Overflow)?
+        values
+            .weighted_collateral_usd
+            .checked_mul(10000)
+            .ok_or(CrossAssetError::Overflow)?
+            .checked_div(values.debt_usd)
+            .ok_or(CrossAssetError::Overflow)?
     };
 
     Ok(PositionSummary {
-        total_collateral_usd,
-        total_debt_usd,
+        total_collateral_usd: values.collateral_usd,
+        total_debt_usd: values.debt_usd,
         health_factor,
     })
 }
 
-fn get_price(_env: &Env, _price_feed: &Address) -> Result<i128, CrossAssetError> {
-    // Mock price feed - in real app, call oracle contract
-    Ok(10000000) // $1.00 with 7 decimals
+/// Set the cross-asset admin (admin must authorize)
+///
+/// Called once, by the contract's constructor.
+pub fn initialize_admin(env: &Env, admin: Address) -> Result<(), CrossAssetError> {
+    if env.storage().persistent().has(&CrossAssetDataKey::Admin) {
+        return Err(CrossAssetError::AlreadyInitialized);
+    }
+    admin.require_auth();
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::Admin, &admin);
+
+    AdminInitializedEvent { admin }.publish(env);
+    Ok(())
+}
+
+/// Nominate a new admin (admin only). Takes effect once the nominee calls
+/// [`accept_admin`]; a later nomination replaces an earlier one.
+pub fn propose_admin(env: &Env, new_admin: Address) -> Result<(), CrossAssetError> {
+    let admin = check_admin(env)?;
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::PendingAdmin, &new_admin);
+
+    AdminTransferProposedEvent {
+        admin,
+        pending_admin: new_admin,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Accept a pending admin nomination (nominee only)
+pub fn accept_admin(env: &Env) -> Result<(), CrossAssetError> {
+    let pending: Address = env
+        .storage()
+        .persistent()
+        .get(&CrossAssetDataKey::PendingAdmin)
+        .ok_or(CrossAssetError::NoPendingAdmin)?;
+    pending.require_auth();
+
+    let previous_admin: Address = env
+        .storage()
+        .persistent()
+        .get(&CrossAssetDataKey::Admin)
+        .ok_or(CrossAssetError::Unauthorized)?;
+    env.storage()
+        .persistent()
+        .set(&CrossAssetDataKey::Admin, &pending);
+    env.storage()
+        .persistent()
+        .remove(&CrossAssetDataKey::PendingAdmin);
+
+    AdminTransferredEvent {
+        previous_admin,
+        new_admin: pending,
+    }
+    .publish(env);
+    Ok(())
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&CrossAssetDataKey::Admin)
 }
 
-pub fn initialize_admin(env: &Env, admin: Address) {
-    env.storage().persistent().set(&CrossAssetDataKey::Admin, &admin);
+pub fn get_pending_admin(env: &Env) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&CrossAssetDataKey::PendingAdmin)
 }
@@ -1,20 +1,23 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::testutils::{Address as _, Ledger};
-use soroban_sdk::{Address, Env};
+use crate::cross_asset::BORROW_INDEX_ONE;
+use crate::oracle::Asset;
+use crate::oracle_test::{price_feed, MockPriceFeedClient, ONE_DOLLAR};
+use crate::token_test::create_token;
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{Address, Env, IntoVal, Symbol};
 
 fn setup_test(env: &Env) -> (LendingContractClient<'static>, Address, Address, Address) {
     let admin = Address::generate(env);
     let user = Address::generate(env);
-    let asset1 = Address::generate(env);
     let _asset2 = Address::generate(env);
 
-    let contract_id = env.register_contract(None, LendingContract);
+    env.mock_all_auths();
+    let contract_id = env.register(LendingContract, (&admin,));
+    let asset1 = create_token(env, &[&user]);
     let client = LendingContractClient::new(env, &contract_id);
 
-    client.initialize_admin(&admin);
-
     (client, admin, user, asset1)
 }
 
@@ -26,9 +29,11 @@ fn test_set_asset_params() {
     let params = AssetParams {
         ltv: 8000,
         liquidation_threshold: 8500,
-        price_feed: Address::generate(&env),
+        price_feed: price_feed(&env, &asset1),
         debt_ceiling: 1000000,
         is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
     };
 
     env.mock_all_auths();
@@ -43,9 +48,11 @@ fn test_deposit_and_summary() {
     let params = AssetParams {
         ltv: 8000,
         liquidation_threshold: 8500,
-        price_feed: Address::generate(&env),
+        price_feed: price_feed(&env, &asset1),
         debt_ceiling: 1000000,
         is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
     };
 
     env.mock_all_auths();
@@ -67,17 +74,19 @@ fn test_borrow_success() {
     let params = AssetParams {
         ltv: 8000, // 80%
         liquidation_threshold: 8500,
-        price_feed: Address::generate(&env),
+        price_feed: price_feed(&env, &asset1),
         debt_ceiling: 1000000,
         is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
     };
 
     env.mock_all_auths();
     client.set_asset_params(&asset1, &params);
 
     client.deposit_collateral_asset(&user, &asset1, &2000); // $2000 collateral
-    // Max borrow = 2000 * 0.8 = 1600
-    
+                                                            // Max borrow = 2000 * 0.8 = 1600
+
     client.borrow_asset(&user, &asset1, &1000); // $1000 borrow
 
     let summary = client.get_cross_position_summary(&user);
@@ -88,7 +97,6 @@ fn test_borrow_success() {
 }
 
 #[test]
-#[should_panic(expected = "InsufficientCollateral")]
 fn test_borrow_insufficient_collateral() {
     let env = Env::default();
     let (client, _admin, user, asset1) = setup_test(&env);
@@ -96,18 +104,21 @@ fn test_borrow_insufficient_collateral() {
     let params = AssetParams {
         ltv: 5000, // 50%
         liquidation_threshold: 6000,
-        price_feed: Address::generate(&env),
+        price_feed: price_feed(&env, &asset1),
         debt_ceiling: 1000000,
         is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
     };
 
     env.mock_all_auths();
     client.set_asset_params(&asset1, &params);
 
     client.deposit_collateral_asset(&user, &asset1, &1000); // $1000 collateral
-    // Max borrow = 1000 * 0.5 = 500
-    
-    client.borrow_asset(&user, &asset1, &600); // Should panic
+                                                            // Max borrow = 1000 * 0.5 = 500
+
+    let result = client.try_borrow_asset(&user, &asset1, &600);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
 }
 
 #[test]
@@ -118,9 +129,11 @@ fn test_repay_and_withdraw() {
     let params = AssetParams {
         ltv: 8000,
         liquidation_threshold: 8500,
-        price_feed: Address::generate(&env),
+        price_feed: price_feed(&env, &asset1),
         debt_ceiling: 1000000,
         is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
     };
 
     env.mock_all_auths();
@@ -128,9 +141,9 @@ fn test_repay_and_withdraw() {
 
     client.deposit_collateral_asset(&user, &asset1, &1000);
     client.borrow_asset(&user, &asset1, &500);
-    
+
     client.repay_asset(&user, &asset1, &500);
-    
+
     let summary = client.get_cross_position_summary(&user);
     assert_eq!(summary.total_debt_usd, 0);
 
@@ -138,3 +151,466 @@ fn test_repay_and_withdraw() {
     let summary2 = client.get_cross_position_summary(&user);
     assert_eq!(summary2.total_collateral_usd, 0);
 }
+
+#[test]
+fn test_admin_is_set_at_deployment() {
+    let env = Env::default();
+    let (client, admin, _, _) = setup_test(&env);
+    assert_eq!(client.get_admin(), Some(admin));
+}
+
+#[test]
+#[should_panic]
+fn test_deployment_requires_admin_auth() {
+    let env = Env::default();
+    env.register(LendingContract, (Address::generate(&env),));
+}
+
+#[test]
+fn test_entrypoints_return_typed_errors() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+
+    // Unconfigured asset
+    let result = client.try_deposit_collateral_asset(&user, &asset1, &100);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotSupported)));
+
+    client.set_asset_params(
+        &asset1,
+        &AssetParams {
+            ltv: 8000,
+            liquidation_threshold: 8500,
+            price_feed: price_feed(&env, &asset1),
+            debt_ceiling: 500,
+            is_active: true,
+            supply_cap: 0,
+            borrow_cap: 0,
+        },
+    );
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+
+    let result = client.try_deposit_collateral_asset(&user, &asset1, &0);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    let result = client.try_repay_asset(&user, &asset1, &-1);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    let result = client.try_withdraw_asset(&user, &asset1, &2001);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+    let result = client.try_borrow_asset(&user, &asset1, &501);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtCeilingReached)));
+}
+
+fn market_params(env: &Env, asset: &Address) -> AssetParams {
+    AssetParams {
+        ltv: 8000,
+        liquidation_threshold: 8500,
+        price_feed: price_feed(env, asset),
+        debt_ceiling: 1000000,
+        is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
+    }
+}
+
+#[test]
+fn test_set_asset_params_requires_admin_auth() {
+    let env = Env::default();
+    let (client, _admin, _, asset1) = setup_test(&env);
+    let params = market_params(&env, &asset1);
+
+    // No authorization provided
+    env.set_auths(&[]);
+    assert!(client.try_set_asset_params(&asset1, &params).is_err());
+}
+
+#[test]
+fn test_set_asset_params_emits_event() {
+    let env = Env::default();
+    let (client, admin, _, asset1) = setup_test(&env);
+    env.mock_all_auths();
+
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+
+    assert_eq!(env.auths()[0].0, admin);
+    let events = env.events().all();
+    let last_event = events.last().unwrap();
+    let expected_topics = (Symbol::new(&env, "asset_params_updated_event"),).into_val(&env);
+    assert_eq!(last_event.0, client.address);
+    assert_eq!(last_event.1, expected_topics);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    let (client, admin, _, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let new_admin = Address::generate(&env);
+
+    client.propose_admin(&new_admin);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    // The current admin stays in charge until the nominee accepts
+    assert_eq!(client.get_admin(), Some(admin));
+
+    client.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.get_admin(), Some(new_admin.clone()));
+    assert_eq!(client.get_pending_admin(), None);
+
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    assert_eq!(env.auths()[0].0, new_admin);
+}
+
+#[test]
+fn test_accept_admin_without_nomination() {
+    let env = Env::default();
+    let (client, _admin, _, _) = setup_test(&env);
+    env.mock_all_auths();
+
+    let result = client.try_accept_admin();
+    assert_eq!(result, Err(Ok(CrossAssetError::NoPendingAdmin)));
+}
+
+const ONE_YEAR: u64 = 31_536_000;
+
+/// Advance one year and refresh the asset's $1 price so it is not stale
+fn advance_one_year(env: &Env, asset: &Address, feed: &Address) {
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+    MockPriceFeedClient::new(env, feed).set_price(
+        &Asset::Stellar(asset.clone()),
+        &ONE_DOLLAR,
+        &env.ledger().timestamp(),
+    );
+}
+
+#[test]
+fn test_debt_accrues_interest_over_time() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let params = market_params(&env, &asset1);
+    client.set_asset_params(&asset1, &params);
+    client.set_borrow_rate(&asset1, &1000);
+
+    client.deposit_collateral_asset(&user, &asset1, &5000);
+    client.borrow_asset(&user, &asset1, &1000);
+    advance_one_year(&env, &asset1, &params.price_feed);
+
+    // 10% over a year, before any interaction
+    assert_eq!(client.get_borrow_index(&asset1), 1_100_000_000);
+    assert_eq!(
+        client.get_cross_position_summary(&user).total_debt_usd,
+        1100
+    );
+
+    // Interest is paid first; the principal is untouched
+    client.repay_asset(&user, &asset1, &60);
+    assert_eq!(
+        client.get_cross_position_summary(&user).total_debt_usd,
+        1040
+    );
+
+    // Overpayment is capped at principal plus interest
+    client.repay_asset(&user, &asset1, &5000);
+    assert_eq!(client.get_cross_position_summary(&user).total_debt_usd, 0);
+}
+
+#[test]
+fn test_interest_counts_toward_debt_ceiling() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let mut params = market_params(&env, &asset1);
+    params.debt_ceiling = 1050;
+    client.set_asset_params(&asset1, &params);
+
+    client.deposit_collateral_asset(&user, &asset1, &5000);
+    client.borrow_asset(&user, &asset1, &1000);
+    advance_one_year(&env, &asset1, &params.price_feed);
+
+    // Default 5%: total debt is now 1050
+    assert_eq!(client.accrue_interest(&asset1), 1_050_000_000);
+    let result = client.try_borrow_asset(&user, &asset1, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::DebtCeilingReached)));
+}
+
+#[test]
+fn test_accrue_interest_emits_event() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let params = market_params(&env, &asset1);
+    client.set_asset_params(&asset1, &params);
+    client.deposit_collateral_asset(&user, &asset1, &5000);
+    client.borrow_asset(&user, &asset1, &1000);
+
+    // Nothing to accrue within the same ledger
+    assert_eq!(client.accrue_interest(&asset1), BORROW_INDEX_ONE);
+    assert!(env.events().all().is_empty());
+
+    advance_one_year(&env, &asset1, &params.price_feed);
+    client.accrue_interest(&asset1);
+    let events = env.events().all();
+    let expected_topics = (Symbol::new(&env, "interest_accrued_event"),).into_val(&env);
+    assert_eq!(events.last().unwrap().1, expected_topics);
+}
+
+#[test]
+fn test_set_borrow_rate_validation() {
+    let env = Env::default();
+    let (client, _admin, _, asset1) = setup_test(&env);
+    env.mock_all_auths();
+
+    assert_eq!(client.get_borrow_rate(&asset1), 500);
+    let result = client.try_set_borrow_rate(&asset1, &-1);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidRate)));
+    let result = client.try_set_borrow_rate(&asset1, &100_001);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidRate)));
+
+    client.set_borrow_rate(&asset1, &0);
+    assert_eq!(client.get_borrow_rate(&asset1), 0);
+}
+
+#[test]
+fn test_supply_cap_limits_total_collateral() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    client.set_asset_caps(&asset1, &3000, &0);
+    let events = env.events().all();
+    let expected_topics = (Symbol::new(&env, "asset_caps_updated_event"),).into_val(&env);
+    assert_eq!(events.last().unwrap().1, expected_topics);
+
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    let result = client.try_deposit_collateral_asset(&user, &asset1, &1001);
+    assert_eq!(result, Err(Ok(CrossAssetError::SupplyCapExceeded)));
+
+    // Withdrawals free room under the cap
+    client.withdraw_asset(&user, &asset1, &500);
+    client.deposit_collateral_asset(&user, &asset1, &1500);
+    assert_eq!(client.get_total_collateral(&asset1), 3000);
+}
+
+#[test]
+fn test_borrow_cap_limits_total_debt() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    client.set_asset_caps(&asset1, &0, &500);
+
+    client.borrow_asset(&user, &asset1, &400);
+    let result = client.try_borrow_asset(&user, &asset1, &101);
+    assert_eq!(result, Err(Ok(CrossAssetError::BorrowCapExceeded)));
+    client.borrow_asset(&user, &asset1, &100);
+}
+
+#[test]
+fn test_set_asset_caps_validation() {
+    let env = Env::default();
+    let (client, _admin, _, asset1) = setup_test(&env);
+    env.mock_all_auths();
+
+    let result = client.try_set_asset_caps(&asset1, &1000, &1000);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotSupported)));
+
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    let result = client.try_set_asset_caps(&asset1, &-1, &0);
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+}
+
+#[test]
+fn test_health_factor_includes_accrued_interest() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let params = market_params(&env, &asset1);
+    client.set_asset_params(&asset1, &params);
+    client.set_borrow_rate(&asset1, &1000);
+
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    assert_eq!(client.get_health_factor(&user), 1000000);
+    client.borrow_asset(&user, &asset1, &1000);
+    assert_eq!(client.get_health_factor(&user), 16000);
+
+    // 1600 of weighted collateral over 1100 of debt
+    advance_one_year(&env, &asset1, &params.price_feed);
+    assert_eq!(client.get_health_factor(&user), 14545);
+}
+
+#[test]
+fn test_max_borrowable() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let params = market_params(&env, &asset1);
+    client.set_asset_params(&asset1, &params);
+
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 0);
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 1600);
+    client.borrow_asset(&user, &asset1, &1000);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 600);
+
+    // Accrued interest uses up borrowing power
+    advance_one_year(&env, &asset1, &params.price_feed);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 550);
+    let result = client.try_borrow_asset(&user, &asset1, &551);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+    client.borrow_asset(&user, &asset1, &550);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 0);
+}
+
+#[test]
+fn test_max_borrowable_bounded_by_caps() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let mut params = market_params(&env, &asset1);
+    params.debt_ceiling = 1200;
+    client.set_asset_params(&asset1, &params);
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 1200);
+    client.set_asset_caps(&asset1, &0, &700);
+    client.borrow_asset(&user, &asset1, &500);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 200);
+
+    params.is_active = false;
+    client.set_asset_params(&asset1, &params);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 0);
+
+    let unlisted = Address::generate(&env);
+    let result = client.try_get_max_borrowable(&user, &unlisted);
+    assert_eq!(result, Err(Ok(CrossAssetError::AssetNotSupported)));
+}
+
+#[test]
+fn test_max_withdrawable() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let params = market_params(&env, &asset1);
+    client.set_asset_params(&asset1, &params);
+
+    assert_eq!(client.get_max_withdrawable(&user, &asset1), 0);
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    assert_eq!(client.get_max_withdrawable(&user, &asset1), 2000);
+
+    // 1000 of debt needs 1250 of collateral at 80% LTV
+    client.borrow_asset(&user, &asset1, &1000);
+    assert_eq!(client.get_max_withdrawable(&user, &asset1), 750);
+    let result = client.try_withdraw_asset(&user, &asset1, &751);
+    assert_eq!(result, Err(Ok(CrossAssetError::InsufficientCollateral)));
+
+    // 1050 of debt after a year needs 1313
+    advance_one_year(&env, &asset1, &params.price_feed);
+    assert_eq!(client.get_max_withdrawable(&user, &asset1), 687);
+    client.withdraw_asset(&user, &asset1, &687);
+    assert_eq!(client.get_max_withdrawable(&user, &asset1), 0);
+}
+
+#[test]
+fn test_same_asset_borrow_cap() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    client.set_same_asset_borrow_cap(&asset1, &Some(5000));
+    assert_eq!(client.get_same_asset_borrow_cap(&asset1), Some(5000));
+
+    // Half of the 2000 of collateral, well within the 80% LTV
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    assert_eq!(client.get_max_borrowable(&user, &asset1), 1000);
+    client.borrow_asset(&user, &asset1, &1000);
+    let result = client.try_borrow_asset(&user, &asset1, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::SameAssetBorrowCapExceeded)));
+
+    client.set_same_asset_borrow_cap(&asset1, &None);
+    client.borrow_asset(&user, &asset1, &1);
+}
+
+#[test]
+fn test_same_asset_borrow_blocked() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    client.set_same_asset_borrow_cap(&asset1, &Some(0));
+
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    let result = client.try_borrow_asset(&user, &asset1, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::SameAssetBorrowCapExceeded)));
+}
+
+#[test]
+fn test_same_asset_cap_ignores_mixed_collateral() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    let asset2 = create_token(&env, &[&user]);
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+    client.set_asset_params(&asset2, &market_params(&env, &asset2));
+    client.set_same_asset_borrow_cap(&asset1, &Some(0));
+
+    // asset1 is not the sole collateral
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    client.deposit_collateral_asset(&user, &asset2, &500);
+    client.borrow_asset(&user, &asset1, &1000);
+
+    // Once it is, further borrows of it are blocked
+    client.withdraw_asset(&user, &asset2, &500);
+    let result = client.try_borrow_asset(&user, &asset1, &1);
+    assert_eq!(result, Err(Ok(CrossAssetError::SameAssetBorrowCapExceeded)));
+
+    let result = client.try_set_same_asset_borrow_cap(&asset1, &Some(10_001));
+    assert_eq!(result, Err(Ok(CrossAssetError::InvalidAmount)));
+}
+
+#[test]
+fn test_user_operations_emit_events() {
+    let env = Env::default();
+    let (client, _admin, user, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+
+    let last_topic = |env: &Env| env.events().all().last().unwrap().1;
+
+    client.deposit_collateral_asset(&user, &asset1, &2000);
+    let expected_topics = (Symbol::new(&env, "collateral_deposited_event"),).into_val(&env);
+    assert_eq!(last_topic(&env), expected_topics);
+
+    client.borrow_asset(&user, &asset1, &500);
+    let expected_topics = (Symbol::new(&env, "asset_borrowed_event"),).into_val(&env);
+    assert_eq!(last_topic(&env), expected_topics);
+
+    client.repay_asset(&user, &asset1, &500);
+    let expected_topics = (Symbol::new(&env, "asset_repaid_event"),).into_val(&env);
+    assert_eq!(last_topic(&env), expected_topics);
+
+    client.withdraw_asset(&user, &asset1, &2000);
+    let expected_topics = (Symbol::new(&env, "collateral_withdrawn_event"),).into_val(&env);
+    assert_eq!(last_topic(&env), expected_topics);
+}
+
+#[test]
+fn test_rate_and_cap_changes_emit_events() {
+    let env = Env::default();
+    let (client, _admin, _, asset1) = setup_test(&env);
+    env.mock_all_auths();
+    client.set_asset_params(&asset1, &market_params(&env, &asset1));
+
+    client.set_borrow_rate(&asset1, &800);
+    let events = env.events().all();
+    let expected_topics = (Symbol::new(&env, "borrow_rate_updated_event"),).into_val(&env);
+    assert_eq!(events.last().unwrap().1, expected_topics);
+
+    client.set_same_asset_borrow_cap(&asset1, &Some(5000));
+    let events = env.events().all();
+    let expected_topics = (Symbol::new(&env, "same_asset_cap_updated_event"),).into_val(&env);
+    assert_eq!(events.last().unwrap().1, expected_topics);
+}
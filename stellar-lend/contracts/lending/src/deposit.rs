@@ -1,4 +1,7 @@
-use soroban_sdk::{contracterror, contracttype, Address, Env, Symbol};
+use soroban_sdk::{contracterror, contractevent, contracttype, Address, Env, Symbol};
+use stellarlend_common::pause::{self, PAUSE_DEPOSIT};
+
+use crate::token::transfer_in;
 
 /// Errors that can occur during deposit operations
 #[contracterror]
@@ -20,6 +23,8 @@ pub enum DepositDataKey {
     TotalDeposits,
     DepositCap,
     MinDepositAmount,
+    /// Pause flag from before the shared pause map, read while the deposit
+    /// switch is unset
     Paused,
 }
 
@@ -43,6 +48,16 @@ pub struct DepositEvent {
     pub timestamp: u64,
 }
 
+/// Emitted when the deposit settings are set
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct DepositSettingsUpdatedEvent {
+    /// Cap on total deposits
+    pub deposit_cap: i128,
+    /// Smallest accepted deposit
+    pub min_deposit_amount: i128,
+}
+
 /// Deposit collateral into the protocol
 ///
 /// # Arguments
@@ -53,6 +68,8 @@ pub struct DepositEvent {
 ///
 /// # Returns
 /// Returns the updated collateral balance on success
+///
+/// The amount is transferred from the user to the contract.
 pub fn deposit(
     env: &Env,
     user: Address,
@@ -94,6 +111,7 @@ pub fn deposit(
 
     save_collateral_position(env, &user, &position);
     set_total_deposits(env, new_total);
+    transfer_in(env, &asset, &user, amount);
     emit_deposit_event(env, user, asset, amount, position.amount);
 
     Ok(position.amount)
@@ -111,20 +129,36 @@ pub fn initialize_deposit_settings(
     env.storage()
         .persistent()
         .set(&DepositDataKey::MinDepositAmount, &min_deposit_amount);
-    env.storage()
-        .persistent()
-        .set(&DepositDataKey::Paused, &false);
+    set_paused(env, false)?;
+
+    DepositSettingsUpdatedEvent {
+        deposit_cap,
+        min_deposit_amount,
+    }
+    .publish(env);
     Ok(())
 }
 
 /// Set deposit pause state
 pub fn set_paused(env: &Env, paused: bool) -> Result<(), DepositError> {
-    env.storage()
-        .persistent()
-        .set(&DepositDataKey::Paused, &paused);
+    pause::set_paused(env, PAUSE_DEPOSIT, paused);
+    env.storage().persistent().remove(&DepositDataKey::Paused);
     Ok(())
 }
 
+/// Whether deposits are paused
+///
+/// The pause switch wins once set; until then, the persistent flag kept by
+/// deployments predating the shared pause map is honored.
+fn is_paused(env: &Env) -> bool {
+    pause::get_paused(env, PAUSE_DEPOSIT).unwrap_or_else(|| {
+        env.storage()
+            .persistent()
+            .get(&DepositDataKey::Paused)
+            .unwrap_or(false)
+    })
+}
+
 /// Get user's collateral position
 pub fn get_user_collateral(env: &Env, user: &Address, asset: &Address) -> CollateralPosition {
     get_collateral_position(env, user, asset)
@@ -174,13 +208,6 @@ fn get_min_deposit_amount(env: &Env) -> i128 {
         .unwrap_or(0)
 }
 
-fn is_paused(env: &Env) -> bool {
-    env.storage()
-        .persistent()
-        .get(&DepositDataKey::Paused)
-        .unwrap_or(false)
-}
-
 fn emit_deposit_event(env: &Env, user: Address, asset: Address, amount: i128, new_balance: i128) {
     let event = DepositEvent {
         user,
@@ -1,7 +1,8 @@
 use super::*;
+use crate::token_test::create_token;
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, Env,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, IntoVal, Symbol,
 };
 
 #[test]
@@ -9,11 +10,11 @@ fn test_deposit_success() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
 
@@ -29,11 +30,11 @@ fn test_deposit_invalid_amount_zero() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
 
@@ -46,11 +47,11 @@ fn test_deposit_invalid_amount_negative() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
 
@@ -63,11 +64,11 @@ fn test_deposit_below_minimum() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &5000);
 
@@ -80,11 +81,11 @@ fn test_deposit_paused() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
     client.set_deposit_paused(&true);
@@ -98,11 +99,11 @@ fn test_deposit_exceeds_cap() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&50_000, &100);
 
@@ -115,11 +116,11 @@ fn test_deposit_multiple_times() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
 
@@ -138,11 +139,11 @@ fn test_deposit_pause_unpause() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
 
@@ -155,16 +156,78 @@ fn test_deposit_pause_unpause() {
     assert_eq!(balance, 10_000);
 }
 
+#[test]
+fn test_deposit_pause_leaves_withdrawals_running() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
+    let client = LendingContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+
+    client.initialize_deposit_settings(&1_000_000_000, &100);
+    client.initialize_withdraw_settings(&100);
+    client.deposit(&user, &asset, &10_000);
+
+    // Both switches live in one pause map; setting one keeps the other
+    client.set_deposit_paused(&true);
+    assert_eq!(client.withdraw(&user, &asset, &1_000), 9_000);
+
+    client.set_withdraw_paused(&true);
+    client.set_deposit_paused(&false);
+    let result = client.try_withdraw(&user, &asset, &1_000);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawPaused)));
+}
+
+#[test]
+fn test_legacy_deposit_pause_flag_is_honored() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
+    let client = LendingContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+
+    client.initialize_deposit_settings(&1_000_000_000, &100);
+
+    // A deployment paused before the shared pause map has only the old flag
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .remove(&stellarlend_common::pause::PauseDataKey::PauseSwitches);
+        env.storage()
+            .persistent()
+            .set(&crate::deposit::DepositDataKey::Paused, &true);
+    });
+    let result = client.try_deposit(&user, &asset, &10_000);
+    assert_eq!(result, Err(Ok(DepositError::DepositPaused)));
+
+    // Setting the switch replaces the old flag
+    client.set_deposit_paused(&false);
+    let balance = client.deposit(&user, &asset, &10_000);
+    assert_eq!(balance, 10_000);
+    env.as_contract(&contract_id, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&crate::deposit::DepositDataKey::Paused));
+    });
+}
+
 #[test]
 fn test_deposit_overflow_protection() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&i128::MAX, &100);
 
@@ -184,11 +247,11 @@ fn test_deposit_updates_timestamp() {
         li.timestamp = 1000;
     });
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
     client.deposit(&user, &asset, &10_000);
@@ -211,12 +274,12 @@ fn test_deposit_separate_users() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user1, &user2]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
 
@@ -234,11 +297,11 @@ fn test_deposit_cap_boundary() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&50_000, &100);
 
@@ -250,3 +313,28 @@ fn test_deposit_cap_boundary() {
     let result = client.try_deposit(&user, &asset, &100);
     assert_eq!(result, Err(Ok(DepositError::ExceedsDepositCap)));
 }
+
+#[test]
+fn test_pause_toggle_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
+    let client = LendingContractClient::new(&env, &contract_id);
+    client.initialize_deposit_settings(&1_000_000_000, &100);
+
+    client.set_deposit_paused(&true);
+    let events = env.events().all();
+    let (contract, topics, data) = events.last().unwrap();
+    assert_eq!(contract, contract_id);
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "pause_toggled_event"),).into_val(&env)
+    );
+    let data: soroban_sdk::Map<Symbol, soroban_sdk::Val> = data.into_val(&env);
+    let paused: bool = data
+        .get(Symbol::new(&env, "paused"))
+        .unwrap()
+        .into_val(&env);
+    assert!(paused);
+}
@@ -6,6 +6,10 @@
 //! This contract is a simplified version of the main lending protocol,
 //! suitable for single-asset lending scenarios with a fixed 5% APY
 //! interest rate and 150% minimum collateral ratio.
+//!
+//! Deposits, borrows, repayments and withdrawals move tokens between the user
+//! and the contract through each asset's token contract; native XLM is used
+//! through its Stellar Asset Contract address.
 
 #![no_std]
 #![allow(deprecated)]
@@ -27,6 +31,21 @@ use deposit::{
 mod withdraw;
 use withdraw::{initialize_withdraw_settings, set_withdraw_paused, WithdrawError};
 
+mod cross_asset;
+use cross_asset::{
+    accept_admin, accrue_interest, borrow_asset, deposit_collateral_asset, get_admin,
+    get_borrow_index, get_borrow_rate, get_cross_position_summary, get_health_factor,
+    get_max_borrowable, get_max_withdrawable, get_pending_admin, get_same_asset_borrow_cap,
+    get_total_collateral, initialize_admin, propose_admin, repay_asset, set_asset_caps,
+    set_asset_params, set_borrow_rate, set_same_asset_borrow_cap, withdraw_asset, AssetParams,
+    CrossAssetError, PositionSummary,
+};
+
+mod oracle;
+use oracle::{get_price, get_price_source, set_price_source, OracleError, PriceSource};
+
+mod token;
+
 #[cfg(test)]
 mod borrow_test;
 
@@ -36,6 +55,15 @@ mod deposit_test;
 #[cfg(test)]
 mod withdraw_test;
 
+#[cfg(test)]
+mod cross_asset_test;
+
+#[cfg(test)]
+mod oracle_test;
+
+#[cfg(test)]
+mod token_test;
+
 #[contract]
 pub struct LendingContract;
 
@@ -200,4 +228,271 @@ impl LendingContract {
     pub fn set_withdraw_paused(env: Env, paused: bool) -> Result<(), WithdrawError> {
         set_withdraw_paused(&env, paused)
     }
+
+    /// Deploy the contract with its cross-asset admin (admin must authorize)
+    ///
+    /// The admin is set in the deployment itself, so no one can claim it
+    /// between deployment and a later initialization call.
+    pub fn __constructor(env: Env, admin: Address) -> Result<(), CrossAssetError> {
+        initialize_admin(&env, admin)
+    }
+
+    /// Nominate a new cross-asset admin (admin only)
+    ///
+    /// The nominee becomes admin once they call `accept_admin`.
+    ///
+    /// # Errors
+    /// - `Unauthorized` - No admin has been set
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), CrossAssetError> {
+        propose_admin(&env, new_admin)
+    }
+
+    /// Accept a pending admin nomination (nominee only)
+    ///
+    /// # Errors
+    /// - `NoPendingAdmin` - No nomination is pending
+    pub fn accept_admin(env: Env) -> Result<(), CrossAssetError> {
+        accept_admin(&env)
+    }
+
+    /// Get the cross-asset admin, if set
+    pub fn get_admin(env: Env) -> Option<Address> {
+        get_admin(&env)
+    }
+
+    /// Get the nominated admin awaiting acceptance, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        get_pending_admin(&env)
+    }
+
+    /// Configure a cross-asset market (admin only)
+    ///
+    /// # Arguments
+    /// * `asset` - The asset address
+    /// * `params` - LTV, liquidation threshold, default price feed and debt ceiling
+    ///
+    /// # Events
+    /// Emits `asset_params_updated` with the previous and new parameters
+    ///
+    /// # Errors
+    /// - `Unauthorized` - No admin has been set
+    pub fn set_asset_params(
+        env: Env,
+        asset: Address,
+        params: AssetParams,
+    ) -> Result<(), CrossAssetError> {
+        set_asset_params(&env, asset, params)
+    }
+
+    /// Deposit collateral into a cross-asset position
+    ///
+    /// # Errors
+    /// - `InvalidAmount` - Amount is zero or negative
+    /// - `AssetNotSupported` - The asset is not configured or not active
+    pub fn deposit_collateral_asset(
+        env: Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), CrossAssetError> {
+        deposit_collateral_asset(&env, user, asset, amount)
+    }
+
+    /// Borrow against cross-asset collateral
+    ///
+    /// # Errors
+    /// - `InsufficientCollateral` - Health factor would fall below 1.0
+    /// - `DebtCeilingReached` - The asset's debt ceiling would be exceeded
+    /// - `SameAssetBorrowCapExceeded` - Debt against the asset as sole
+    ///   collateral would exceed its same-asset cap
+    /// - `PriceUnavailable` / `PriceStale` - A position asset cannot be priced
+    pub fn borrow_asset(
+        env: Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), CrossAssetError> {
+        borrow_asset(&env, user, asset, amount)
+    }
+
+    /// Repay cross-asset debt
+    ///
+    /// Repayments above the outstanding debt are capped at the debt. Accrued
+    /// interest is paid before principal.
+    ///
+    /// # Errors
+    /// - `InvalidAmount` - Amount is zero or negative
+    pub fn repay_asset(
+        env: Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), CrossAssetError> {
+        repay_asset(&env, user, asset, amount)
+    }
+
+    /// Bring an asset's borrow index up to date (callable by anyone)
+    ///
+    /// # Returns
+    /// The current borrow index (9 decimals)
+    ///
+    /// # Events
+    /// Emits `interest_accrued` when the index grows
+    pub fn accrue_interest(env: Env, asset: Address) -> Result<i128, CrossAssetError> {
+        accrue_interest(&env, &asset)
+    }
+
+    /// Set an asset's supply and borrow caps (admin only)
+    ///
+    /// # Arguments
+    /// * `asset` - The asset address
+    /// * `supply_cap` - Cap on total collateral deposited (0 = unlimited)
+    /// * `borrow_cap` - Cap on total debt (0 = unlimited)
+    ///
+    /// # Errors
+    /// - `AssetNotSupported` - The asset has no parameters
+    /// - `InvalidAmount` - A cap is negative
+    pub fn set_asset_caps(
+        env: Env,
+        asset: Address,
+        supply_cap: i128,
+        borrow_cap: i128,
+    ) -> Result<(), CrossAssetError> {
+        set_asset_caps(&env, asset, supply_cap, borrow_cap)
+    }
+
+    /// Get the total collateral deposited in an asset
+    pub fn get_total_collateral(env: Env, asset: Address) -> i128 {
+        get_total_collateral(&env, &asset)
+    }
+
+    /// Set an asset's annual borrow rate (admin only)
+    ///
+    /// # Arguments
+    /// * `asset` - The asset address
+    /// * `rate_bps` - Annual rate in basis points, at most 100000
+    ///
+    /// # Errors
+    /// - `InvalidRate` - Rate is negative or above the maximum
+    pub fn set_borrow_rate(
+        env: Env,
+        asset: Address,
+        rate_bps: i128,
+    ) -> Result<(), CrossAssetError> {
+        set_borrow_rate(&env, asset, rate_bps)
+    }
+
+    /// Get an asset's annual borrow rate in basis points (5% unless set)
+    pub fn get_borrow_rate(env: Env, asset: Address) -> i128 {
+        get_borrow_rate(&env, &asset)
+    }
+
+    /// Get an asset's borrow index as of now (9 decimals)
+    pub fn get_borrow_index(env: Env, asset: Address) -> Result<i128, CrossAssetError> {
+        get_borrow_index(&env, &asset)
+    }
+
+    /// Cap borrowing an asset against itself as sole collateral (admin only)
+    ///
+    /// # Arguments
+    /// * `asset` - The asset address
+    /// * `cap_bps` - Highest debt as a share of the collateral, in basis
+    ///   points (0 blocks such borrows, `None` removes the cap)
+    ///
+    /// # Errors
+    /// - `InvalidAmount` - Cap is outside [0, 10000]
+    pub fn set_same_asset_borrow_cap(
+        env: Env,
+        asset: Address,
+        cap_bps: Option<i128>,
+    ) -> Result<(), CrossAssetError> {
+        set_same_asset_borrow_cap(&env, asset, cap_bps)
+    }
+
+    /// Get an asset's same-asset borrow cap in basis points, if set
+    pub fn get_same_asset_borrow_cap(env: Env, asset: Address) -> Option<i128> {
+        get_same_asset_borrow_cap(&env, &asset)
+    }
+
+    /// Withdraw cross-asset collateral
+    ///
+    /// # Errors
+    /// - `InvalidAmount` - Amount is not positive or exceeds the deposited balance
+    /// - `InsufficientCollateral` - Health factor would fall below 1.0
+    /// - `PriceUnavailable` / `PriceStale` - A position asset cannot be priced
+    pub fn withdraw_asset(
+        env: Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), CrossAssetError> {
+        withdraw_asset(&env, user, asset, amount)
+    }
+
+    /// Get a user's cross-asset position valued in the base currency
+    ///
+    /// # Returns
+    /// Collateral and debt values (7 decimals) and the health factor
+    pub fn get_cross_position_summary(
+        env: Env,
+        user: Address,
+    ) -> Result<PositionSummary, CrossAssetError> {
+        get_cross_position_summary(&env, user)
+    }
+
+    /// Get a user's health factor, including accrued interest
+    ///
+    /// # Returns
+    /// Weighted collateral over debt, scaled by 10000 (1000000 without debt)
+    pub fn get_health_factor(env: Env, user: Address) -> Result<i128, CrossAssetError> {
+        get_health_factor(&env, user)
+    }
+
+    /// Get the most of an asset a user can borrow now
+    ///
+    /// Bounded by the user's health factor and the asset's debt ceiling and
+    /// borrow cap; 0 for an inactive asset.
+    ///
+    /// # Errors
+    /// - `AssetNotSupported` - The asset has no parameters
+    pub fn get_max_borrowable(
+        env: Env,
+        user: Address,
+        asset: Address,
+    ) -> Result<i128, CrossAssetError> {
+        get_max_borrowable(&env, user, asset)
+    }
+
+    /// Get the most of an asset's collateral a user can withdraw now
+    pub fn get_max_withdrawable(
+        env: Env,
+        user: Address,
+        asset: Address,
+    ) -> Result<i128, CrossAssetError> {
+        get_max_withdrawable(&env, user, asset)
+    }
+
+    /// Set the price source for an asset (admin only)
+    ///
+    /// # Arguments
+    /// * `asset` - The asset to price
+    /// * `source` - A Reflector-compatible feed, its identifier for the asset,
+    ///   and the maximum accepted price age
+    pub fn set_price_source(
+        env: Env,
+        asset: Address,
+        source: PriceSource,
+    ) -> Result<(), OracleError> {
+        set_price_source(&env, asset, source)
+    }
+
+    /// Get the price source for an asset, if any
+    pub fn get_price_source(env: Env, asset: Address) -> Option<PriceSource> {
+        get_price_source(&env, &asset)
+    }
+
+    /// Get the price of an asset in the base currency (7 decimals)
+    pub fn get_price(env: Env, asset: Address) -> Result<i128, OracleError> {
+        get_price(&env, &asset)
+    }
 }
@@ -0,0 +1,201 @@
+//! # Price Oracle
+//!
+//! Values cross-asset positions in a common base currency.
+//!
+//! Prices are read from external price feed contracts implementing the
+//! [`PriceFeed`] interface, which matches the Reflector oracle (`lastprice`
+//! and `decimals`). Any feed exposing the same two functions can be plugged in.
+//!
+//! ## Price Sources
+//! Each asset is priced by its [`PriceSource`]: a feed contract, the feed's
+//! identifier for the asset, and a maximum price age. The admin can set a
+//! source per asset with [`set_price_source`]. Assets without an explicit
+//! source use the `price_feed` from their asset params, queried as
+//! `Asset::Stellar(asset)` with [`DEFAULT_MAX_PRICE_AGE`].
+//!
+//! All prices are normalized to [`PRICE_DECIMALS`] decimals, so `10_000_000`
+//! is one unit of the base currency.
+
+use soroban_sdk::{
+    contractclient, contracterror, contractevent, contracttype, Address, Env, Symbol,
+};
+
+/// Decimals of normalized prices
+pub const PRICE_DECIMALS: u32 = 7;
+
+/// Maximum price age for assets priced through their asset params (1 hour)
+pub const DEFAULT_MAX_PRICE_AGE: u64 = 3600;
+
+/// Errors that can occur while reading prices
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OracleError {
+    /// Caller is not the admin
+    Unauthorized = 1,
+    /// The asset has no price source
+    PriceSourceNotSet = 2,
+    /// The feed returned no price or could not be called
+    PriceUnavailable = 3,
+    /// The latest price is older than the source's maximum age
+    PriceStale = 4,
+    /// The feed returned a zero or negative price
+    InvalidPrice = 5,
+    /// Arithmetic overflow while normalizing the price
+    Overflow = 6,
+}
+
+/// Asset identifier used by Reflector-compatible feeds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    /// A Stellar asset, identified by its contract address
+    Stellar(Address),
+    /// An off-chain asset, identified by its ticker
+    Other(Symbol),
+}
+
+/// A price reported by a feed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Price scaled by the feed's `decimals`
+    pub price: i128,
+    /// Timestamp of the price, in seconds
+    pub timestamp: u64,
+}
+
+/// Interface of a pluggable price feed (Reflector-compatible)
+#[allow(dead_code)]
+#[contractclient(name = "PriceFeedClient")]
+pub trait PriceFeed {
+    /// Most recent price of `asset`, if any
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+    /// Number of decimals in reported prices
+    fn decimals(env: Env) -> u32;
+}
+
+/// Where an asset's price comes from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceSource {
+    /// Price feed contract
+    pub feed: Address,
+    /// The feed's identifier for the asset
+    pub feed_asset: Asset,
+    /// Maximum accepted price age in seconds
+    pub max_age_seconds: u64,
+}
+
+/// Emitted when the admin sets an asset's price source
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PriceSourceUpdatedEvent {
+    /// Admin that made the change
+    pub admin: Address,
+    /// Priced asset
+    pub asset: Address,
+    /// The new source
+    pub source: PriceSource,
+}
+
+/// Storage keys for oracle data
+#[contracttype]
+#[derive(Clone)]
+pub enum OracleDataKey {
+    /// Explicit price source per asset
+    PriceSource(Address),
+}
+
+/// Set the price source for an asset (admin only)
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `asset` - The asset to price
+/// * `source` - Feed, feed asset identifier and maximum price age
+pub fn set_price_source(env: &Env, asset: Address, source: PriceSource) -> Result<(), OracleError> {
+    let admin = crate::cross_asset::check_admin(env).map_err(|_| OracleError::Unauthorized)?;
+    env.storage()
+        .persistent()
+        .set(&OracleDataKey::PriceSource(asset.clone()), &source);
+
+    PriceSourceUpdatedEvent {
+        admin,
+        asset,
+        source,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Get the price source for an asset
+///
+/// Falls back to the `price_feed` in the asset's params when no explicit
+/// source is set.
+pub fn get_price_source(env: &Env, asset: &Address) -> Option<PriceSource> {
+    env.storage()
+        .persistent()
+        .get(&OracleDataKey::PriceSource(asset.clone()))
+        .or_else(|| {
+            crate::cross_asset::get_asset_params(env, asset)
+                .ok()
+                .map(|params| PriceSource {
+                    feed: params.price_feed,
+                    feed_asset: Asset::Stellar(asset.clone()),
+                    max_age_seconds: DEFAULT_MAX_PRICE_AGE,
+                })
+        })
+}
+
+/// Get the price of an asset in the base currency
+///
+/// # Returns
+/// The price normalized to [`PRICE_DECIMALS`] decimals
+///
+/// # Errors
+/// * `PriceSourceNotSet` - The asset has no price source
+/// * `PriceUnavailable` - The feed has no price or failed
+/// * `PriceStale` - The price is older than the source allows
+/// * `InvalidPrice` - The price is not positive
+pub fn get_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
+    let source = get_price_source(env, asset).ok_or(OracleError::PriceSourceNotSet)?;
+    let feed = PriceFeedClient::new(env, &source.feed);
+
+    let data = match feed.try_lastprice(&source.feed_asset) {
+        Ok(Ok(Some(data))) => data,
+        _ => return Err(OracleError::PriceUnavailable),
+    };
+    let decimals = match feed.try_decimals() {
+        Ok(Ok(decimals)) => decimals,
+        _ => return Err(OracleError::PriceUnavailable),
+    };
+
+    let now = env.ledger().timestamp();
+    if now > data.timestamp && now - data.timestamp > source.max_age_seconds {
+        return Err(OracleError::PriceStale);
+    }
+    if data.price <= 0 {
+        return Err(OracleError::InvalidPrice);
+    }
+
+    let price = normalize(data.price, decimals)?;
+    if price == 0 {
+        return Err(OracleError::InvalidPrice);
+    }
+    Ok(price)
+}
+
+/// Rescale a price from `decimals` to [`PRICE_DECIMALS`]
+fn normalize(price: i128, decimals: u32) -> Result<i128, OracleError> {
+    if decimals >= PRICE_DECIMALS {
+        let divisor = 10i128
+            .checked_pow(decimals - PRICE_DECIMALS)
+            .ok_or(OracleError::Overflow)?;
+        Ok(price / divisor)
+    } else {
+        let factor = 10i128
+            .checked_pow(PRICE_DECIMALS - decimals)
+            .ok_or(OracleError::Overflow)?;
+        price.checked_mul(factor).ok_or(OracleError::Overflow)
+    }
+}
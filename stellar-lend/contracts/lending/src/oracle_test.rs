@@ -0,0 +1,228 @@
+use super::*;
+use crate::oracle::{Asset, PriceData};
+use crate::token_test::create_token;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short,
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+/// Reflector-style feed returning prices set by the test
+#[contract]
+pub struct MockPriceFeed;
+
+#[contracttype]
+#[derive(Clone)]
+enum MockFeedKey {
+    Price(Asset),
+    Decimals,
+}
+
+#[contractimpl]
+impl MockPriceFeed {
+    pub fn set_price(env: Env, asset: Asset, price: i128, timestamp: u64) {
+        env.storage()
+            .instance()
+            .set(&MockFeedKey::Price(asset), &PriceData { price, timestamp });
+    }
+
+    pub fn set_decimals(env: Env, decimals: u32) {
+        env.storage()
+            .instance()
+            .set(&MockFeedKey::Decimals, &decimals);
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&MockFeedKey::Price(asset))
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&MockFeedKey::Decimals)
+            .unwrap_or(14)
+    }
+}
+
+/// One unit of the base currency at the mock feed's default 14 decimals
+pub(crate) const ONE_DOLLAR: i128 = 100_000_000_000_000;
+
+/// Register a mock feed pricing `asset` at $1 and return its address
+pub(crate) fn price_feed(env: &Env, asset: &Address) -> Address {
+    let feed = env.register(MockPriceFeed, ());
+    MockPriceFeedClient::new(env, &feed).set_price(
+        &Asset::Stellar(asset.clone()),
+        &ONE_DOLLAR,
+        &env.ledger().timestamp(),
+    );
+    feed
+}
+
+fn setup(env: &Env) -> (LendingContractClient<'_>, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let contract_id = env.register(LendingContract, (Address::generate(env),));
+    let client = LendingContractClient::new(env, &contract_id);
+    let feed = env.register(MockPriceFeed, ());
+    (client, feed)
+}
+
+fn asset_params(feed: &Address, ltv: i128) -> AssetParams {
+    AssetParams {
+        ltv,
+        liquidation_threshold: ltv + 500,
+        price_feed: feed.clone(),
+        debt_ceiling: 1_000_000,
+        is_active: true,
+        supply_cap: 0,
+        borrow_cap: 0,
+    }
+}
+
+#[test]
+fn test_price_normalized_to_seven_decimals() {
+    let env = Env::default();
+    let (client, feed) = setup(&env);
+    let feed_client = MockPriceFeedClient::new(&env, &feed);
+    let asset = Address::generate(&env);
+    client.set_asset_params(&asset, &asset_params(&feed, 8000));
+
+    // $2.50 at 14 decimals
+    feed_client.set_price(
+        &Asset::Stellar(asset.clone()),
+        &250_000_000_000_000,
+        &10_000,
+    );
+    assert_eq!(client.get_price(&asset), 25_000_000);
+
+    // $2.50 at 5 decimals
+    feed_client.set_decimals(&5);
+    feed_client.set_price(&Asset::Stellar(asset.clone()), &250_000, &10_000);
+    assert_eq!(client.get_price(&asset), 25_000_000);
+}
+
+#[test]
+fn test_price_errors() {
+    let env = Env::default();
+    let (client, feed) = setup(&env);
+    let feed_client = MockPriceFeedClient::new(&env, &feed);
+    let asset = Address::generate(&env);
+
+    assert_eq!(client.get_price_source(&asset), None);
+    assert_eq!(
+        client.try_get_price(&asset),
+        Err(Ok(OracleError::PriceSourceNotSet))
+    );
+
+    client.set_asset_params(&asset, &asset_params(&feed, 8000));
+    assert_eq!(
+        client.try_get_price(&asset),
+        Err(Ok(OracleError::PriceUnavailable))
+    );
+
+    feed_client.set_price(&Asset::Stellar(asset.clone()), &ONE_DOLLAR, &5_000);
+    assert_eq!(
+        client.try_get_price(&asset),
+        Err(Ok(OracleError::PriceStale))
+    );
+
+    feed_client.set_price(&Asset::Stellar(asset.clone()), &0, &10_000);
+    assert_eq!(
+        client.try_get_price(&asset),
+        Err(Ok(OracleError::InvalidPrice))
+    );
+}
+
+#[test]
+fn test_explicit_price_source_overrides_params_feed() {
+    let env = Env::default();
+    let (client, feed) = setup(&env);
+    let asset = Address::generate(&env);
+    client.set_asset_params(&asset, &asset_params(&feed, 8000));
+
+    // A second feed that lists the asset by ticker
+    let ticker_feed = env.register(MockPriceFeed, ());
+    MockPriceFeedClient::new(&env, &ticker_feed).set_price(
+        &Asset::Other(symbol_short!("EURC")),
+        &(ONE_DOLLAR * 11 / 10),
+        &10_000,
+    );
+
+    let source = PriceSource {
+        feed: ticker_feed,
+        feed_asset: Asset::Other(symbol_short!("EURC")),
+        max_age_seconds: 600,
+    };
+    client.set_price_source(&asset, &source);
+
+    assert_eq!(client.get_price_source(&asset), Some(source));
+    assert_eq!(client.get_price(&asset), 11_000_000);
+}
+
+#[test]
+fn test_set_price_source_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
+    let client = LendingContractClient::new(&env, &contract_id);
+    let feed = env.register(MockPriceFeed, ());
+
+    // No authorization provided
+    env.set_auths(&[]);
+    let result = client.try_set_price_source(
+        &Address::generate(&env),
+        &PriceSource {
+            feed,
+            feed_asset: Asset::Other(symbol_short!("XLM")),
+            max_age_seconds: 600,
+        },
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_ltv_checks_use_common_base_currency() {
+    let env = Env::default();
+    let (client, feed) = setup(&env);
+    let feed_client = MockPriceFeedClient::new(&env, &feed);
+    let user = Address::generate(&env);
+
+    // Collateral worth $2.00 per unit, debt asset worth $0.50 per unit
+    let collateral = create_token(&env, &[&user]);
+    let debt = create_token(&env, &[&client.address]);
+    client.set_asset_params(&collateral, &asset_params(&feed, 8000));
+    client.set_asset_params(&debt, &asset_params(&feed, 8000));
+    feed_client.set_price(
+        &Asset::Stellar(collateral.clone()),
+        &(ONE_DOLLAR * 2),
+        &10_000,
+    );
+    feed_client.set_price(&Asset::Stellar(debt.clone()), &(ONE_DOLLAR / 2), &10_000);
+
+    // 1,000 units = $2,000 of collateral; 80% LTV allows $1,600 = 3,200 debt units
+    client.deposit_collateral_asset(&user, &collateral, &1_000);
+    assert_eq!(
+        client.try_borrow_asset(&user, &debt, &3_202),
+        Err(Ok(CrossAssetError::InsufficientCollateral))
+    );
+    client.borrow_asset(&user, &debt, &3_200);
+
+    let summary = client.get_cross_position_summary(&user);
+    assert_eq!(summary.total_collateral_usd, 2_000);
+    assert_eq!(summary.total_debt_usd, 1_600);
+    assert_eq!(summary.health_factor, 10_000);
+
+    // A collateral price drop blocks withdrawals
+    feed_client.set_price(&Asset::Stellar(collateral.clone()), &ONE_DOLLAR, &10_000);
+    assert_eq!(
+        client.try_withdraw_asset(&user, &collateral, &1),
+        Err(Ok(CrossAssetError::InsufficientCollateral))
+    );
+
+    // Stale prices block borrowing
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    assert_eq!(
+        client.try_borrow_asset(&user, &debt, &1),
+        Err(Ok(CrossAssetError::PriceStale))
+    );
+}
@@ -0,0 +1,21 @@
+//! # Token Transfers
+//!
+//! Moves funds between users and the contract through each asset's token
+//! contract. Native XLM is handled like any other asset through its Stellar
+//! Asset Contract address.
+//!
+//! Callers update positions before transferring; a failed transfer (for
+//! example, an insufficient balance) aborts the whole invocation and rolls
+//! the position change back.
+
+use soroban_sdk::{token, Address, Env};
+
+/// Pull `amount` of `asset` from `from` into the contract
+pub(crate) fn transfer_in(env: &Env, asset: &Address, from: &Address, amount: i128) {
+    token::Client::new(env, asset).transfer(from, env.current_contract_address(), &amount);
+}
+
+/// Send `amount` of `asset` from the contract to `to`
+pub(crate) fn transfer_out(env: &Env, asset: &Address, to: &Address, amount: i128) {
+    token::Client::new(env, asset).transfer(&env.current_contract_address(), to, &amount);
+}
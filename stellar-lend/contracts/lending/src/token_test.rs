@@ -0,0 +1,143 @@
+use super::*;
+use crate::oracle_test::price_feed;
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
+
+/// Balance minted to each holder by [`create_token`]
+pub(crate) const INITIAL_BALANCE: i128 = 1_000_000_000;
+
+/// Register a Stellar asset contract and mint [`INITIAL_BALANCE`] to each holder
+pub(crate) fn create_token(env: &Env, holders: &[&Address]) -> Address {
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let admin_client = StellarAssetClient::new(env, &token).mock_all_auths();
+    for holder in holders {
+        admin_client.mint(holder, &INITIAL_BALANCE);
+    }
+    token
+}
+
+fn balance(env: &Env, token: &Address, holder: &Address) -> i128 {
+    TokenClient::new(env, token).balance(holder)
+}
+
+fn setup(env: &Env) -> (LendingContractClient<'_>, Address) {
+    setup_with_admin(env, &Address::generate(env))
+}
+
+fn setup_with_admin<'a>(env: &Env, admin: &Address) -> (LendingContractClient<'a>, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(LendingContract, (admin,));
+    let client = LendingContractClient::new(env, &contract_id);
+    (client, Address::generate(env))
+}
+
+#[test]
+fn test_deposit_and_withdraw_move_tokens() {
+    let env = Env::default();
+    let (client, user) = setup(&env);
+    let asset = create_token(&env, &[&user]);
+
+    client.initialize_deposit_settings(&1_000_000_000, &100);
+    client.initialize_withdraw_settings(&100);
+
+    client.deposit(&user, &asset, &10_000);
+    assert_eq!(balance(&env, &asset, &user), INITIAL_BALANCE - 10_000);
+    assert_eq!(balance(&env, &asset, &client.address), 10_000);
+
+    client.withdraw(&user, &asset, &4_000);
+    assert_eq!(balance(&env, &asset, &user), INITIAL_BALANCE - 6_000);
+    assert_eq!(balance(&env, &asset, &client.address), 6_000);
+}
+
+#[test]
+fn test_deposit_without_balance_fails() {
+    let env = Env::default();
+    let (client, user) = setup(&env);
+    let asset = create_token(&env, &[]);
+
+    client.initialize_deposit_settings(&1_000_000_000, &100);
+
+    assert!(client.try_deposit(&user, &asset, &10_000).is_err());
+    assert_eq!(client.get_user_collateral_deposit(&user, &asset).amount, 0);
+}
+
+#[test]
+fn test_borrow_pulls_collateral_and_pays_out_loan() {
+    let env = Env::default();
+    let (client, user) = setup(&env);
+    let asset = create_token(&env, &[&client.address]);
+    let collateral_asset = create_token(&env, &[&user]);
+
+    client.initialize_borrow_settings(&1_000_000_000, &1000);
+    client.borrow(&user, &asset, &10_000, &collateral_asset, &20_000);
+
+    assert_eq!(balance(&env, &asset, &user), 10_000);
+    assert_eq!(
+        balance(&env, &asset, &client.address),
+        INITIAL_BALANCE - 10_000
+    );
+    assert_eq!(
+        balance(&env, &collateral_asset, &user),
+        INITIAL_BALANCE - 20_000
+    );
+    assert_eq!(balance(&env, &collateral_asset, &client.address), 20_000);
+}
+
+#[test]
+fn test_borrow_without_liquidity_fails() {
+    let env = Env::default();
+    let (client, user) = setup(&env);
+    let asset = create_token(&env, &[]);
+    let collateral_asset = create_token(&env, &[&user]);
+
+    client.initialize_borrow_settings(&1_000_000_000, &1000);
+
+    let result = client.try_borrow(&user, &asset, &10_000, &collateral_asset, &20_000);
+    assert!(result.is_err());
+    assert_eq!(client.get_user_debt(&user).borrowed_amount, 0);
+    assert_eq!(balance(&env, &collateral_asset, &user), INITIAL_BALANCE);
+}
+
+#[test]
+fn test_cross_asset_flows_move_tokens() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let (client, user) = setup_with_admin(&env, &admin);
+    let collateral = create_token(&env, &[&user]);
+    let debt = create_token(&env, &[&client.address]);
+
+    for asset in [&collateral, &debt] {
+        client.set_asset_params(
+            asset,
+            &AssetParams {
+                ltv: 8000,
+                liquidation_threshold: 8500,
+                price_feed: price_feed(&env, asset),
+                debt_ceiling: 1_000_000,
+                is_active: true,
+                supply_cap: 0,
+                borrow_cap: 0,
+            },
+        );
+    }
+
+    client.deposit_collateral_asset(&user, &collateral, &2000);
+    assert_eq!(balance(&env, &collateral, &client.address), 2000);
+
+    client.borrow_asset(&user, &debt, &1000);
+    assert_eq!(balance(&env, &debt, &user), 1000);
+
+    // Repayments above the debt only transfer what is owed
+    client.repay_asset(&user, &debt, &5000);
+    assert_eq!(balance(&env, &debt, &user), 0);
+    assert_eq!(balance(&env, &debt, &client.address), INITIAL_BALANCE);
+
+    client.withdraw_asset(&user, &collateral, &2000);
+    assert_eq!(balance(&env, &collateral, &user), INITIAL_BALANCE);
+    assert_eq!(balance(&env, &collateral, &client.address), 0);
+}
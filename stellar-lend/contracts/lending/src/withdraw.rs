@@ -1,6 +1,9 @@
-use soroban_sdk::{contracterror, contracttype, Address, Env, Symbol};
+use soroban_sdk::{contracterror, contractevent, contracttype, Address, Env, Symbol};
+use stellarlend_common::constants::MIN_COLLATERAL_RATIO_BPS;
+use stellarlend_common::pause::{self, PAUSE_WITHDRAW};
 
 use crate::deposit::{CollateralPosition, DepositDataKey};
+use crate::token::transfer_out;
 
 /// Errors that can occur during withdraw operations
 #[contracterror]
@@ -18,8 +21,10 @@ pub enum WithdrawError {
 #[contracttype]
 #[derive(Clone)]
 pub enum WithdrawDataKey {
-    Paused,
     MinWithdrawAmount,
+    /// Pause flag from before the shared pause map, read while the withdraw
+    /// switch is unset
+    Paused,
 }
 
 /// Withdraw event data
@@ -33,8 +38,13 @@ pub struct WithdrawEvent {
     pub timestamp: u64,
 }
 
-/// Minimum collateral ratio in basis points (150%)
-const MIN_COLLATERAL_RATIO_BPS: i128 = 15000;
+/// Emitted when the withdraw settings are set
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawSettingsUpdatedEvent {
+    /// Smallest accepted withdrawal
+    pub min_withdraw_amount: i128,
+}
 
 /// Withdraw collateral from the protocol
 ///
@@ -46,6 +56,8 @@ const MIN_COLLATERAL_RATIO_BPS: i128 = 15000;
 ///
 /// # Returns
 /// Returns the remaining collateral balance on success
+///
+/// The amount is transferred from the contract to the user.
 pub fn withdraw(
     env: &Env,
     user: Address,
@@ -92,6 +104,7 @@ pub fn withdraw(
     let new_total = total_deposits.checked_sub(amount).unwrap_or(0);
     set_total_deposits(env, new_total);
 
+    transfer_out(env, &asset, &user, amount);
     emit_withdraw_event(env, user, asset, amount, new_amount);
 
     Ok(new_amount)
@@ -140,20 +153,35 @@ pub fn initialize_withdraw_settings(
     env.storage()
         .persistent()
         .set(&WithdrawDataKey::MinWithdrawAmount, &min_withdraw_amount);
-    env.storage()
-        .persistent()
-        .set(&WithdrawDataKey::Paused, &false);
+    set_withdraw_paused(env, false)?;
+
+    WithdrawSettingsUpdatedEvent {
+        min_withdraw_amount,
+    }
+    .publish(env);
     Ok(())
 }
 
 /// Set withdraw pause state
 pub fn set_withdraw_paused(env: &Env, paused: bool) -> Result<(), WithdrawError> {
-    env.storage()
-        .persistent()
-        .set(&WithdrawDataKey::Paused, &paused);
+    pause::set_paused(env, PAUSE_WITHDRAW, paused);
+    env.storage().persistent().remove(&WithdrawDataKey::Paused);
     Ok(())
 }
 
+/// Whether withdrawals are paused
+///
+/// The pause switch wins once set; until then, the persistent flag kept by
+/// deployments predating the shared pause map is honored.
+fn is_paused(env: &Env) -> bool {
+    pause::get_paused(env, PAUSE_WITHDRAW).unwrap_or_else(|| {
+        env.storage()
+            .persistent()
+            .get(&WithdrawDataKey::Paused)
+            .unwrap_or(false)
+    })
+}
+
 fn get_collateral_position(env: &Env, user: &Address, asset: &Address) -> CollateralPosition {
     env.storage()
         .persistent()
@@ -191,13 +219,6 @@ fn get_min_withdraw_amount(env: &Env) -> i128 {
         .unwrap_or(0)
 }
 
-fn is_paused(env: &Env) -> bool {
-    env.storage()
-        .persistent()
-        .get(&WithdrawDataKey::Paused)
-        .unwrap_or(false)
-}
-
 fn emit_withdraw_event(
     env: &Env,
     user: Address,
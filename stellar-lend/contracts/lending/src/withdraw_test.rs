@@ -1,4 +1,5 @@
 use super::*;
+use crate::token_test::create_token;
 use soroban_sdk::{
     testutils::{Address as _, Events, Ledger},
     Address, Env, IntoVal, Symbol,
@@ -8,7 +9,7 @@ use soroban_sdk::{
 fn setup_env() -> (Env, LendingContractClient<'static>) {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(LendingContract, ());
+    let contract_id = env.register(LendingContract, (Address::generate(&env),));
     let client = LendingContractClient::new(&env, &contract_id);
     (env, client)
 }
@@ -32,7 +33,7 @@ fn setup_with_deposit(
 fn test_withdraw_success() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -47,7 +48,7 @@ fn test_withdraw_success() {
 fn test_withdraw_full_balance() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -62,7 +63,7 @@ fn test_withdraw_full_balance() {
 fn test_withdraw_multiple_times() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 100_000);
 
@@ -82,7 +83,7 @@ fn test_withdraw_multiple_times() {
 fn test_withdraw_invalid_amount_zero() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -94,7 +95,7 @@ fn test_withdraw_invalid_amount_zero() {
 fn test_withdraw_invalid_amount_negative() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -106,7 +107,7 @@ fn test_withdraw_invalid_amount_negative() {
 fn test_withdraw_below_minimum() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
     client.initialize_withdraw_settings(&5000);
@@ -122,7 +123,7 @@ fn test_withdraw_below_minimum() {
 fn test_withdraw_insufficient_collateral() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 10_000);
 
@@ -134,7 +135,7 @@ fn test_withdraw_insufficient_collateral() {
 fn test_withdraw_no_deposit() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
     client.initialize_withdraw_settings(&100);
@@ -149,7 +150,7 @@ fn test_withdraw_no_deposit() {
 fn test_withdraw_paused() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
     client.set_withdraw_paused(&true);
@@ -162,7 +163,7 @@ fn test_withdraw_paused() {
 fn test_withdraw_pause_unpause() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -181,9 +182,9 @@ fn test_withdraw_pause_unpause() {
 fn test_withdraw_ratio_violation_with_debt() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let borrow_asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+    let borrow_asset = create_token(&env, &[&client.address]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     // Deposit 100,000 collateral
     setup_with_deposit(&env, &client, &user, &asset, 100_000);
@@ -201,9 +202,9 @@ fn test_withdraw_ratio_violation_with_debt() {
 fn test_withdraw_ratio_valid_with_debt() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let borrow_asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+    let borrow_asset = create_token(&env, &[&client.address]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     // Deposit 100,000 collateral
     setup_with_deposit(&env, &client, &user, &asset, 100_000);
@@ -221,9 +222,9 @@ fn test_withdraw_ratio_valid_with_debt() {
 fn test_withdraw_ratio_boundary_exact_150_percent() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let borrow_asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+    let borrow_asset = create_token(&env, &[&client.address]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     // Deposit 100,000
     setup_with_deposit(&env, &client, &user, &asset, 100_000);
@@ -243,9 +244,9 @@ fn test_withdraw_ratio_boundary_exact_150_percent() {
 fn test_withdraw_ratio_boundary_just_below() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let borrow_asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+    let borrow_asset = create_token(&env, &[&client.address]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     // Deposit 30,000
     setup_with_deposit(&env, &client, &user, &asset, 30_000);
@@ -263,7 +264,7 @@ fn test_withdraw_ratio_boundary_just_below() {
 fn test_withdraw_no_debt_allows_full_withdrawal() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -278,9 +279,9 @@ fn test_withdraw_no_debt_allows_full_withdrawal() {
 fn test_withdraw_max_with_debt() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
-    let borrow_asset = Address::generate(&env);
-    let collateral_asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
+    let borrow_asset = create_token(&env, &[&client.address]);
+    let collateral_asset = create_token(&env, &[&user]);
 
     // Deposit 100,000
     setup_with_deposit(&env, &client, &user, &asset, 100_000);
@@ -305,7 +306,7 @@ fn test_withdraw_updates_total_deposits() {
     let (env, client) = setup_env();
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user1, &user2]);
 
     setup_with_deposit(&env, &client, &user1, &asset, 60_000);
     client.deposit(&user2, &asset, &40_000);
@@ -329,7 +330,7 @@ fn test_withdraw_separate_users() {
     let (env, client) = setup_env();
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user1, &user2]);
 
     setup_with_deposit(&env, &client, &user1, &asset, 50_000);
     client.deposit(&user2, &asset, &30_000);
@@ -353,7 +354,7 @@ fn test_withdraw_preserves_deposit_timestamp() {
     });
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -383,7 +384,7 @@ fn test_withdraw_emits_event() {
     });
 
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -403,7 +404,7 @@ fn test_withdraw_emits_event() {
 fn test_withdraw_minimum_amount_boundary() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     client.initialize_deposit_settings(&1_000_000_000, &100);
     client.initialize_withdraw_settings(&500);
@@ -422,7 +423,7 @@ fn test_withdraw_minimum_amount_boundary() {
 fn test_withdraw_after_multiple_deposits() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 20_000);
     client.deposit(&user, &asset, &30_000);
@@ -436,7 +437,7 @@ fn test_withdraw_after_multiple_deposits() {
 fn test_withdraw_deposit_withdraw_cycle() {
     let (env, client) = setup_env();
     let user = Address::generate(&env);
-    let asset = Address::generate(&env);
+    let asset = create_token(&env, &[&user]);
 
     setup_with_deposit(&env, &client, &user, &asset, 50_000);
 
@@ -0,0 +1,99 @@
+//! # StellarLend Component Registry Contract
+//!
+//! Lets integrators discover protocol components on-chain instead of
+//! hard-coding their addresses. Oracle adapters, markets, the safety module
+//! and governance are registered under symbol names; each update keeps the
+//! previous addresses as numbered versions.
+//!
+//! ## Features
+//! - Name to address resolution with `get_component`
+//! - Versioned history of every address a component has had
+//! - Admin-controlled registration, updates and removal
+
+#![no_std]
+use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, Vec};
+
+mod registry;
+use registry::{ComponentEntry, RegistryError};
+
+#[contract]
+pub struct RegistryContract;
+
+#[contractimpl]
+impl RegistryContract {
+    /// Initialize the registry
+    ///
+    /// # Arguments
+    /// * `admin` - Address allowed to register and update components
+    ///
+    /// # Errors
+    /// * `AlreadyInitialized` - The registry has already been initialized
+    pub fn initialize(env: Env, admin: Address) -> Result<(), RegistryError> {
+        registry::initialize(&env, admin)
+    }
+
+    /// Get the registry admin
+    pub fn get_admin(env: Env) -> Result<Address, RegistryError> {
+        registry::get_admin(&env)
+    }
+
+    /// Register a component or point it at a new address (admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The admin
+    /// * `name` - Component name, e.g. `oracle`
+    /// * `address` - The component's contract address
+    ///
+    /// # Returns
+    /// The new version number (1 for a first registration)
+    ///
+    /// # Events
+    /// Emits `component_updated`
+    pub fn set_component(
+        env: Env,
+        caller: Address,
+        name: Symbol,
+        address: Address,
+    ) -> Result<u32, RegistryError> {
+        registry::set_component(&env, caller, name, address)
+    }
+
+    /// Stop a component from resolving, keeping its history (admin only)
+    ///
+    /// # Events
+    /// Emits `component_removed`
+    pub fn remove_component(env: Env, caller: Address, name: Symbol) -> Result<(), RegistryError> {
+        registry::remove_component(&env, caller, name)
+    }
+
+    /// Resolve a component name to its current address
+    ///
+    /// # Errors
+    /// * `ComponentNotFound` - Nothing is registered under the name
+    pub fn get_component(env: Env, name: Symbol) -> Result<Address, RegistryError> {
+        registry::get_component(&env, name)
+    }
+
+    /// Get a component's current address, version and update time
+    pub fn get_component_entry(env: Env, name: Symbol) -> Option<ComponentEntry> {
+        registry::get_component_entry(&env, name)
+    }
+
+    /// Get a specific version of a component's address
+    pub fn get_component_version(env: Env, name: Symbol, version: u32) -> Option<ComponentEntry> {
+        registry::get_component_version(&env, name, version)
+    }
+
+    /// Get every address a component has had, oldest first
+    pub fn get_component_history(env: Env, name: Symbol) -> Vec<ComponentEntry> {
+        registry::get_component_history(&env, name)
+    }
+
+    /// Get every registered component name
+    pub fn get_components(env: Env) -> Vec<Symbol> {
+        registry::get_components(&env)
+    }
+}
+
+#[cfg(test)]
+mod test;
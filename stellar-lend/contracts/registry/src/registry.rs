@@ -0,0 +1,237 @@
+//! # Component Registry Implementation
+//!
+//! Stores the current address of each protocol component under a symbol
+//! name, such as `oracle`, `core`, `safety` or `governance`, together with
+//! every address it has had.
+//!
+//! ## Versions
+//! Each update of a component appends a [`ComponentEntry`] with the next
+//! version number, starting at 1. The latest entry is what
+//! [`get_component`] resolves; earlier entries stay readable with
+//! [`get_component_version`] so integrators can verify which address a name
+//! pointed to before an upgrade. Removing a component clears its current
+//! address but keeps its history, and a later update continues the version
+//! sequence.
+
+use soroban_sdk::{contracterror, contractevent, contracttype, Address, Env, Symbol, Vec};
+
+/// Maximum number of registered component names
+pub const MAX_COMPONENTS: u32 = 50;
+
+/// Errors that can occur during registry operations
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RegistryError {
+    /// Contract has already been initialized
+    AlreadyInitialized = 1,
+    /// Contract has not been initialized
+    NotInitialized = 2,
+    /// Caller is not the admin
+    Unauthorized = 3,
+    /// No current address is registered under the name
+    ComponentNotFound = 4,
+    /// The registry already holds [`MAX_COMPONENTS`] names
+    TooManyComponents = 5,
+    /// The component already points at this address
+    AddressUnchanged = 6,
+}
+
+/// Storage keys for registry data
+#[contracttype]
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum RegistryDataKey {
+    /// Admin address
+    Admin,
+    /// Registered component names, in order of first registration: Vec<Symbol>
+    Names,
+    /// Every address a component has had, oldest first: Vec<ComponentEntry>
+    History(Symbol),
+    /// Whether a component currently resolves: bool
+    Active(Symbol),
+}
+
+/// One version of a component's address
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComponentEntry {
+    /// Component contract address
+    pub address: Address,
+    /// Version number, starting at 1
+    pub version: u32,
+    /// Ledger timestamp the version was registered
+    pub updated_at: u64,
+}
+
+/// Emitted when a component is registered or its address changes
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ComponentUpdatedEvent {
+    pub name: Symbol,
+    pub address: Address,
+    pub version: u32,
+}
+
+/// Emitted when a component stops resolving
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ComponentRemovedEvent {
+    pub name: Symbol,
+    pub version: u32,
+}
+
+/// Set the registry admin
+///
+/// # Errors
+/// * `AlreadyInitialized` - The admin has already been set
+pub fn initialize(env: &Env, admin: Address) -> Result<(), RegistryError> {
+    if env.storage().instance().has(&RegistryDataKey::Admin) {
+        return Err(RegistryError::AlreadyInitialized);
+    }
+    env.storage()
+        .instance()
+        .set(&RegistryDataKey::Admin, &admin);
+    Ok(())
+}
+
+/// Get the registry admin
+pub fn get_admin(env: &Env) -> Result<Address, RegistryError> {
+    env.storage()
+        .instance()
+        .get(&RegistryDataKey::Admin)
+        .ok_or(RegistryError::NotInitialized)
+}
+
+fn require_admin(env: &Env, caller: &Address) -> Result<(), RegistryError> {
+    if get_admin(env)? != *caller {
+        return Err(RegistryError::Unauthorized);
+    }
+    caller.require_auth();
+    Ok(())
+}
+
+/// Point a component name at a new address (admin only)
+///
+/// # Returns
+/// The new version number.
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the admin
+/// * `TooManyComponents` - The name is new and the registry is full
+/// * `AddressUnchanged` - The component already resolves to `address`
+pub fn set_component(
+    env: &Env,
+    caller: Address,
+    name: Symbol,
+    address: Address,
+) -> Result<u32, RegistryError> {
+    require_admin(env, &caller)?;
+
+    let history = get_component_history(env, name.clone());
+    if history.is_empty() {
+        let mut names = get_components(env);
+        if names.len() >= MAX_COMPONENTS {
+            return Err(RegistryError::TooManyComponents);
+        }
+        names.push_back(name.clone());
+        env.storage()
+            .persistent()
+            .set(&RegistryDataKey::Names, &names);
+    }
+    if let Some(current) = get_component_entry(env, name.clone()) {
+        if current.address == address {
+            return Err(RegistryError::AddressUnchanged);
+        }
+    }
+
+    let version = history.len() + 1;
+    let mut history = history;
+    history.push_back(ComponentEntry {
+        address: address.clone(),
+        version,
+        updated_at: env.ledger().timestamp(),
+    });
+    env.storage()
+        .persistent()
+        .set(&RegistryDataKey::History(name.clone()), &history);
+    env.storage()
+        .persistent()
+        .set(&RegistryDataKey::Active(name.clone()), &true);
+
+    ComponentUpdatedEvent {
+        name,
+        address,
+        version,
+    }
+    .publish(env);
+    Ok(version)
+}
+
+/// Stop a component name from resolving, keeping its history (admin only)
+///
+/// # Errors
+/// * `Unauthorized` - Caller is not the admin
+/// * `ComponentNotFound` - The name does not currently resolve
+pub fn remove_component(env: &Env, caller: Address, name: Symbol) -> Result<(), RegistryError> {
+    require_admin(env, &caller)?;
+
+    let current = get_component_entry(env, name.clone()).ok_or(RegistryError::ComponentNotFound)?;
+    env.storage()
+        .persistent()
+        .set(&RegistryDataKey::Active(name.clone()), &false);
+
+    ComponentRemovedEvent {
+        name,
+        version: current.version,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Resolve a component name to its current address
+///
+/// # Errors
+/// * `ComponentNotFound` - The name does not currently resolve
+pub fn get_component(env: &Env, name: Symbol) -> Result<Address, RegistryError> {
+    get_component_entry(env, name)
+        .map(|entry| entry.address)
+        .ok_or(RegistryError::ComponentNotFound)
+}
+
+/// Get the current version of a component, if it resolves
+pub fn get_component_entry(env: &Env, name: Symbol) -> Option<ComponentEntry> {
+    let active: bool = env
+        .storage()
+        .persistent()
+        .get(&RegistryDataKey::Active(name.clone()))
+        .unwrap_or(false);
+    if !active {
+        return None;
+    }
+    get_component_history(env, name).last()
+}
+
+/// Get a specific version of a component
+pub fn get_component_version(env: &Env, name: Symbol, version: u32) -> Option<ComponentEntry> {
+    if version == 0 {
+        return None;
+    }
+    get_component_history(env, name).get(version - 1)
+}
+
+/// Get every address a component has had, oldest first
+pub fn get_component_history(env: &Env, name: Symbol) -> Vec<ComponentEntry> {
+    env.storage()
+        .persistent()
+        .get(&RegistryDataKey::History(name))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Get every registered component name, including removed ones
+pub fn get_components(env: &Env) -> Vec<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&RegistryDataKey::Names)
+        .unwrap_or(Vec::new(env))
+}
@@ -0,0 +1,127 @@
+extern crate std;
+
+use super::*;
+use crate::registry::MAX_COMPONENTS;
+use soroban_sdk::{testutils::Address as _, testutils::Ledger, Address, Env, Symbol};
+
+fn setup(env: &Env) -> (RegistryContractClient<'_>, Address) {
+    env.mock_all_auths();
+    let client = RegistryContractClient::new(env, &env.register(RegistryContract {}, ()));
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (client, admin)
+}
+
+#[test]
+fn test_initialize_once() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.get_admin(), admin);
+
+    let result = client.try_initialize(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(RegistryError::AlreadyInitialized)));
+}
+
+#[test]
+fn test_set_and_resolve_component() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let oracle = Symbol::new(&env, "oracle");
+    let address = Address::generate(&env);
+
+    assert_eq!(
+        client.try_get_component(&oracle),
+        Err(Ok(RegistryError::ComponentNotFound))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(client.set_component(&admin, &oracle, &address), 1);
+    assert_eq!(client.get_component(&oracle), address);
+    assert_eq!(
+        client.get_component_entry(&oracle),
+        Some(ComponentEntry {
+            address,
+            version: 1,
+            updated_at: 1_000,
+        })
+    );
+    assert_eq!(client.get_components().len(), 1);
+}
+
+#[test]
+fn test_update_keeps_previous_versions() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let core = Symbol::new(&env, "core");
+    let v1 = Address::generate(&env);
+    let v2 = Address::generate(&env);
+
+    client.set_component(&admin, &core, &v1);
+    assert_eq!(client.set_component(&admin, &core, &v2), 2);
+
+    assert_eq!(client.get_component(&core), v2);
+    assert_eq!(client.get_component_version(&core, &1).unwrap().address, v1);
+    assert_eq!(client.get_component_version(&core, &0), None);
+    assert_eq!(client.get_component_version(&core, &3), None);
+    assert_eq!(client.get_component_history(&core).len(), 2);
+    assert_eq!(client.get_components().len(), 1);
+
+    let result = client.try_set_component(&admin, &core, &v2);
+    assert_eq!(result, Err(Ok(RegistryError::AddressUnchanged)));
+}
+
+#[test]
+fn test_remove_component() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let safety = Symbol::new(&env, "safety");
+    let v1 = Address::generate(&env);
+
+    client.set_component(&admin, &safety, &v1);
+    client.remove_component(&admin, &safety);
+    assert_eq!(
+        client.try_get_component(&safety),
+        Err(Ok(RegistryError::ComponentNotFound))
+    );
+    assert_eq!(
+        client.try_remove_component(&admin, &safety),
+        Err(Ok(RegistryError::ComponentNotFound))
+    );
+    assert_eq!(client.get_component_history(&safety).len(), 1);
+
+    // Re-registering continues the version sequence, even at the old address
+    assert_eq!(client.set_component(&admin, &safety, &v1), 2);
+    assert_eq!(client.get_component(&safety), v1);
+}
+
+#[test]
+fn test_only_admin_updates() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let gov = Symbol::new(&env, "governance");
+    let outsider = Address::generate(&env);
+
+    let result = client.try_set_component(&outsider, &gov, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(RegistryError::Unauthorized)));
+
+    client.set_component(&admin, &gov, &Address::generate(&env));
+    let result = client.try_remove_component(&outsider, &gov);
+    assert_eq!(result, Err(Ok(RegistryError::Unauthorized)));
+}
+
+#[test]
+fn test_component_limit() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    for i in 0..MAX_COMPONENTS {
+        let name = Symbol::new(&env, &std::format!("market_{i}"));
+        client.set_component(&admin, &name, &Address::generate(&env));
+    }
+    let result = client.try_set_component(
+        &admin,
+        &Symbol::new(&env, "one_more"),
+        &Address::generate(&env),
+    );
+    assert_eq!(result, Err(Ok(RegistryError::TooManyComponents)));
+}